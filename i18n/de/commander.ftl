@@ -165,7 +165,6 @@ compressed = {$items} {$items ->
         [one] Element wurde
         *[other] Elemente wurden
     } von „{$from}“ nach „{$to}“ komprimiert
-copy_noun = Kopie
 creating = „{$name}“ in „{$parent}“ wird erstellt
 created = „{$name}“ in „{$parent}“ wurde erstellt
 copying = {$items} {$items ->
@@ -279,15 +278,17 @@ show-button-row = Zeige Knopfreihe
 show-embedded-terminal = Zeige eingebautes Terminal
 show-second-panel = Zeige zweiten Dateimanager
 queue-file-operations = Führe Dateioperationen nacheinander aus
-f2-rename = F2 Umbenennen
-f3-view = F3 Ansicht
-f4-edit = F4 Bearbeiten
+button-rename = Umbenennen
+button-view = Ansicht
+button-edit = Bearbeiten
+button-copy = Kopieren
+button-move = Bewegen
+button-mkdir = erst. Verz.
+button-delete = Löschen
+button-terminal = Terminal
+button-quit = Beenden
 f5-copy = F5 Kopieren
 f6-move = F6 Bewegen
-f7-mkdir = F7 erst. Verz.
-f8-delete = F8 Löschen
-f9-Term = F9 Terminal
-f10-quit = F10 Beenden
 copy-tab = Kopiere aktuelles Tab zur anderen Seite
 move-tab = Verschiebe aktuelles Tab zur anderen Seite
 grid-view = Rasteransicht
@@ -2,12 +2,23 @@ commander = Commmander
 empty-folder = Empty folder
 empty-folder-hidden = Empty folder (has hidden items)
 no-results = No results found
+flatten-title = {$name} with subfolders
+scan-permission-denied = Permission denied, this folder could not be read
+scan-not-found = No such folder, it may have been moved or deleted
 filesystem = Filesystem
 home = Home
 networks = Networks
 notification-in-progress = File operations are in progress.
+notification-complete = File operation complete
+notification-failed = File operation failed
 trash = Trash
 recents = Recents
+recent-files = Recent files
+reveal-in-panel = Reveal in panel
+clear-recent = Clear recent
+clear-recents = Clear recents
+remove-from-recents = Remove from recents
+no-recent-files = No recently opened files
 undo = Undo
 today = Today
 
@@ -20,11 +31,21 @@ trash-folder-icon = Trash folder icon
 icon-size-and-spacing = Icon size and spacing
 icon-size = Icon size
 grid-spacing = Grid spacing
+icon-placement = Icon placement
+grid-origin = Start from
+grid-origin-top-left = Top left
+grid-origin-top-right = Top right
+grid-origin-bottom-left = Bottom left
+grid-origin-bottom-right = Bottom right
+grid-flow = Fill
+grid-flow-columns = Columns first
+grid-flow-rows = Rows first
 
 # List view
 name = Name
 modified = Modified
 trashed-on = Trashed
+original-location = Original location
 size = Size
 
 # Progress footer
@@ -34,11 +55,82 @@ operations-running = {$running} operations running ({$percent}%)...
 operations-running-finished = {$running} operations running ({$percent}%), {$finished} finished...
 pause = Pause
 resume = Resume
+pause-all = Pause all
+resume-all = Resume all
+pause-resume-all = Pause or resume all operations
 
 # Dialogs
 
 ## Compress Dialog
 create-archive = Create archive
+archive-per-item = Separate archive per item
+archive-include-files = Also create archives for files, not only folders
+archive-named-after-item = Each archive is named after its item
+new-archive-name = Archive
+new-archive-description = Drop files onto the archive in the sidebar, or select the archive there to add the selected files. Create it from its context menu once everything is gathered.
+staged-archive = {$name} ({$items} {$items ->
+        [one] item
+        *[other] items
+    })
+finalize-archive = Create staged archive
+discard-archive = Discard archive
+
+## Copy As Dialog
+copy-as-title = Copy as
+copy-as-description = Copy "{$from}" into "{$to}" with the name
+copy-as-same = Choose a different name to copy the item into its own folder
+copy-as-replace = An item with this name already exists, you will be asked before replacing it
+
+## Copy/Move To Dialog
+copy-to-title = Copy {$items} {$items ->
+        [one] item
+        *[other] items
+    } to
+move-to-title = Move {$items} {$items ->
+        [one] item
+        *[other] items
+    } to
+copy-here = Copy here
+move-here = Move here
+create-folder = Create folder
+
+## Undo Dialog
+undo-permanent-delete = Permanently delete items?
+undo-permanent-delete-warning = Undoing this operation permanently deletes {$items} {$items ->
+        [one] item
+        *[other] items
+    } it created.
+undo-replaced-refused = The copy replaced {$items} existing {$items ->
+        [one] item
+        *[other] items
+    }, so it can not be undone.
+
+## Move to Trash Dialog
+move-to-trash-title = Move to trash?
+move-to-trash-warning = Move {$items} {$items ->
+        [one] item
+        *[other] items
+    } to the trash?
+
+## Trash Unavailable Dialog
+trash-unavailable-title = No trash on this drive
+trash-unavailable-warning = {$items} {$items ->
+        [one] item is
+        *[other] items are
+    } on a drive without a trash folder. Create one to move items there, or delete them without moving to the trash.
+create-trash-folder = Create trash folder
+
+## Permanently Delete Dialog
+permanently-delete-title = Permanently delete items?
+permanently-delete-warning = {$items} {$items ->
+        [one] item is
+        *[other] items are
+    } deleted without moving to the trash. This can not be undone.
+
+## Insufficient Space Dialog
+insufficient-space-title = Not enough free space
+insufficient-space-warning = The items need {$required}, but only {$free} is free at the destination.
+continue-anyway = Continue anyway
 
 ## Extract Dialog
 extract-password-required = Password required
@@ -46,12 +138,55 @@ extract-password-required = Password required
 ## Empty Trash Dialog
 empty-trash = Empty trash
 empty-trash-warning = Are you sure you want to permanently delete all the items in Trash?
+empty-trash-summary = {$items} {$items ->
+        [one] item
+        *[other] items
+    }, {$size}
+calculating-trash-size = Calculating size...
+
+## Restore To Dialog
+restore-to-title = Restore {$items} {$items ->
+        [one] item
+        *[other] items
+    } to
+restore-here = Restore here
+
+## Restore Missing Folder Dialog
+restore-missing-title = Original location missing
+restore-missing-warning = The {$folders ->
+        [one] folder
+        *[other] folders
+    } the items were trashed from no longer {$folders ->
+        [one] exists
+        *[other] exist
+    }. Recreate {$folders ->
+        [one] it
+        *[other] them
+    } or choose another destination.
+recreate-folders = Recreate
+
+## Open Files Dialog
+open-files-title = Open {$items} files?
+open-files-warning = An application will be launched for each of the {$items} selected files.
+
+## Open as Root Dialog
+open-as-root-title = Open as administrator?
+open-as-root-warning = "{$name}" will be opened with full access to the system. Changes made as administrator can damage your system.
+open-as-root-failed = Unable to open as administrator
+open-as-root-cancelled = Authentication was cancelled.
+open-as-root-denied = Authentication failed or you are not allowed to open items as administrator.
+open-as-root-no-app = No application was found to open this file.
+pkexec-missing = pkexec was not found. Install polkit to open items as administrator.
 
 ## Mount Error Dialog
 mount-error = Unable to access drive
+unmount-error = Unable to eject drive
+unmount-busy = "{$name}" is busy because files on it are still in use. Close them and try again.
 
 ## New File/Folder Dialog
 create-new-file = Create new file
+paste-as-file = Paste text as new file
+pasted-text-file-name = Pasted text.txt
 create-new-folder = Create new folder
 file-name = File name
 folder-name = Folder name
@@ -63,6 +198,7 @@ name-no-slashes = Name cannot contain slashes.
 
 ## Open/Save Dialog
 cancel = Cancel
+close = Close
 create = Create
 open = Open
 open-file = Open file
@@ -70,6 +206,7 @@ open-folder = Open folder
 open-in-new-tab = Open in new tab
 open-in-new-window = Open in new window
 open-item-location = Open item location
+open-link-target = Open link target location
 open-multiple-files = Open multiple files
 open-multiple-folders = Open multiple folders
 save = Save
@@ -77,6 +214,8 @@ save-file = Save file
 
 ## Open With Dialog
 open-with-title = How do you want to open "{$name}"?
+open-with-title-files = How do you want to open {$count} files?
+open-files-with = Open {$count} files with {$name}
 browse-store = Browse {$store}
 
 ## Rename Dialog
@@ -94,11 +233,44 @@ apply-to-all = Apply to all
 keep-both = Keep both
 skip = Skip
 
+## Select by Pattern Dialog
+select = Select
+deselect = Deselect
+pattern = Pattern (* and ? match any characters)
+pattern-invalid = The pattern is not valid.
+case-sensitive = Case sensitive
+
+## Select by Criteria Dialog
+larger-than = At least this size (like 10 MB)
+smaller-than = At most this size
+modified-after = Modified since
+modified-before = Modified before
+criteria-time-hint = Enter dates as YYYY-MM-DD, or a number of days ago. Only items meeting all filled in criteria are selected.
+criteria-invalid = A criterion is not valid.
+selected-by-criteria = {$items} {$items ->
+        [one] item
+        *[other] items
+    } selected
+
+## Set Modification Time Dialog
+set-modified-title = Set modification time of {$items} {$items ->
+        [one] item
+        *[other] items
+    }
+current-time = Use the current time
+time-invalid = The time is not valid, use the format 2024-12-31 23:59:59.
+recursive = Include the contents of folders
+
 ## Set as Executable and Launch Dialog
 set-executable-and-launch = Set as executable and launch
 set-executable-and-launch-description = Do you want to set "{$name}" as executable and launch it?
 set-and-launch = Set and launch
 
+## Open Script Dialog
+open-script-title = Run or open script?
+open-script-description = "{$name}" is a script. Run it in a terminal, or open it with an application?
+run-in-terminal = Run in terminal
+
 ## Metadata Dialog
 open-with = Open with
 owner = Owner
@@ -120,6 +292,19 @@ read-execute = Read and execute
 read-write = Read and write
 ### Mode 7
 read-write-execute = Read, write, and execute
+permission-read = R
+permission-write = W
+permission-execute = X
+permissions-mode = Mode
+permissions-not-owner = Only the owner of this item can change its permissions
+permissions-recursive = Apply to all contained items
+apply = Apply
+reset = Reset
+checksum = Checksum
+calculating-checksum = Calculating ({$percent}%)...
+checksum-verify = Paste a checksum to verify
+checksum-match = The checksums match.
+checksum-mismatch = The checksums do not match.
 
 # Context Pages
 
@@ -147,7 +332,13 @@ network-drive-schemes =
     WebDav,dav:// or davs://
 network-drive-error = Unable to access network drive
 password = Password
+recent-connections = Recent connections
 remember-password = Remember password
+sftp-unknown-host-key = The authenticity of {$host} can't be established
+sftp-host-key-fingerprint = The {$key_type} key fingerprint is SHA256:{$fingerprint}. Connecting adds it to the known hosts.
+sftp-host-key-changed = The host key of {$host} has changed. Refusing to connect, as someone could be intercepting the connection.
+sftp-key-passphrase = Passphrase for key {$key}
+remove = Remove
 try-again = Try again
 username = Username
 
@@ -162,6 +353,10 @@ progress-cancelled = {$percent}%, cancelled
 progress-paused = {$percent}%, paused
 failed = Failed
 complete = Complete
+operation-failed = Failed: {$operation}
+retry = Retry
+retry-all-failed = Retry all failed
+clear-history = Clear history
 compressing = Compressing {$items} {$items ->
         [one] item
         *[other] items
@@ -170,7 +365,6 @@ compressed = Compressed {$items} {$items ->
         [one] item
         *[other] items
     } from "{$from}" to "{$to}"
-copy_noun = Copy
 creating = Creating "{$name}" in "{$parent}"
 created = Created "{$name}" in "{$parent}"
 copying = Copying {$items} {$items ->
@@ -181,6 +375,18 @@ copied = Copied {$items} {$items ->
         [one] item
         *[other] items
     } from "{$from}" to "{$to}"
+changed-while-copying = {$items} {$items ->
+        [one] file
+        *[other] files
+    } changed while copying and may be incomplete: {$names}
+deleting = Deleting {$items} {$items ->
+        [one] item
+        *[other] items
+    } from "{$from}" ({$progress})...
+deleted = Deleted {$items} {$items ->
+        [one] item
+        *[other] items
+    } from "{$from}"
 emptying-trash = Emptying {trash} ({$progress})...
 emptied-trash = Emptied {trash}
 extracting = Extracting {$items} {$items ->
@@ -193,6 +399,19 @@ extracted = Extracted {$items} {$items ->
     } from "{$from}" to "{$to}"
 setting-executable-and-launching = Setting "{$name}" as executable and launching
 set-executable-and-launched = Set "{$name}" as executable and launched
+setting-permissions = Setting permissions of "{$name}" to {$mode} ({$progress})...
+set-permissions = Set permissions of "{$name}" to {$mode}
+setting-symlink-target = Pointing "{$name}" to "{$target}"...
+set-symlink-target = Pointed "{$name}" to "{$target}"
+setting-modified = Setting modification time of {$items} {$items ->
+        [one] item
+        *[other] items
+    } ({$progress})...
+set-modified = Set modification time of {$items} {$items ->
+        [one] item
+        *[other] items
+    }
+permission-denied = Permission denied for "{$name}", only its owner can change it
 moving = Moving {$items} {$items ->
         [one] item
         *[other] items
@@ -216,16 +435,42 @@ unknown-folder = unknown folder
 ## Open with
 menu-open-with = Open with...
 default-app = {$name} (default)
+current-default-app = Default for {$mime}: {$name}
+no-default-app = No default set for {$mime}
+set-default-app = Set as default
+clear-default-app = Reset to system default
+always-open-with = Always open {$mime} files with this app
 
 ## Show details
 show-details = Show details
 type = Type: {$mime}
+link-target = Link to: {$target}
+link-broken = The link target does not exist
+symlink-target = Link target
+extended-attributes = Extended attributes
+selinux-context = SELinux context
 items = Items: {$items}
 item-size = Size: {$size}
 item-created = Created: {$created}
 item-modified = Modified: {$modified}
 item-accessed = Accessed: {$accessed}
 calculating = Calculating...
+calculating-size = Calculating ({$size})...
+selected-items = {$items} {$items ->
+        [one] item
+        *[other] items
+    } selected
+type-count = {$mime}: {$count}
+common-parent = Location: {$parent}
+
+## Panel summary
+panel-summary = {$items} {$items ->
+        [one] item
+        *[other] items
+    }, total {$total}, {$selected} selected ({$selected_total})
+panel-free-space = {$free} free of {$total}
+panel-free-space-unavailable = —
+panel-size-unknown = —
 
 ## Settings
 settings = Settings
@@ -241,12 +486,19 @@ light = Light
 add-to-sidebar = Add to sidebar
 compress = Compress
 extract-here = Extract
+extract-to-other-panel = Extract to other panel
 new-file = New file...
 new-folder = New folder...
+new-archive = New archive...
 open-in-terminal = Open in terminal
 move-to-trash = Move to trash
+delete-permanently = Delete permanently
 restore-from-trash = Restore from trash
+restore-to = Restore to...
 remove-from-sidebar = Remove from sidebar
+hide-from-sidebar = Hide from sidebar
+move-up = Move up
+move-down = Move down
 sort-by-name = Sort by name
 sort-by-modified = Sort by modified
 sort-by-size = Sort by size
@@ -263,16 +515,42 @@ display-settings = Display settings...
 file = File
 new-tab = New tab
 new-window = New window
+duplicate-window = Duplicate window
+open-as-root = Open as administrator
 rename = Rename...
+change-extension = Change extension...
+change-extension-title = Change the extension of {$items} {$items ->
+        [one] file
+        *[other] files
+    }
+extension = Extension
+extension-conflicts = Names already in use: {$names}
+extension-empty = Enter an extension.
 close-tab = Close tab
+all-tabs = All tabs
 quit = Quit
 
 ## Edit
 edit = Edit
+redo = Redo
 cut = Cut
 copy = Copy
 paste = Paste
 select-all = Select all
+select-by-pattern = Select by pattern...
+deselect-by-pattern = Deselect by pattern...
+select-by-criteria = Select by size and date...
+mirror-selection = Select the same items in the other panel
+mirrored-selection = {$matched} of {$selected} selected {$selected ->
+        [one] item
+        *[other] items
+    } found in the other panel
+compare-files = Compare files
+compare-files-select = Select two files, or one file in each panel, to compare them
+compare-files-title = Comparing "{$left}" and "{$right}"
+compare-files-identical = The files have the same content
+diff-too-large = "{$name}" is too large to compare
+diff-binary = "{$name}" is not a text file and cannot be compared line by line
 
 ## View
 zoom-in = Zoom in
@@ -280,27 +558,133 @@ default-size = Default size
 zoom-out = Zoom out
 view = View
 features = Features
+sidebar = Sidebar
+sidebar-system-mounts = Show system drives
+sidebar-system-mounts-description = Internal disks and loop devices are listed with removable and network drives
 show-button-row = Show Button Row
 show-embedded-terminal = Show embedded Terminal
+open-terminal-embedded = Open terminals in the embedded terminal
+terminal-command = Command run by F9
+terminal-command-description = Runs in the embedded terminal instead of a shell, with %f, %F and %d replaced like in custom actions
+terminal-close-on-exit = Return to a shell when the command exits
+terminal-scrollback = Terminal scrollback
+terminal-scrollback-lines = {$lines} lines
+clear-scrollback = Clear scrollback
+reset-terminal = Reset terminal
+terminal-process-exited = [process exited with code {$code}]
+terminal-command-failed = {$command} failed with exit code {$code}
+terminal-theme-dark = Terminal color scheme (dark)
+terminal-theme-light = Terminal color scheme (light)
 show-second-panel = Show second Filemanager panel
+follow-symlinks = Open symlinked folders
+middle-click-open-files = Open files on middle click
+middle-click-open-files-description = Middle clicking a folder always opens it in a new tab
+respect-gitignore = Respect .gitignore
+respect-gitignore-description = Skip ignored files when searching and calculating folder sizes
+color-by-type = Color names by type
+color-by-type-description = Folders, executables, links, archives and images
+show-parent-entry = Show parent folder entry
+show-parent-entry-description = Add a ".." row above the items of the list view to go up a folder
+wrap-navigation = Wrap around with arrow keys
+wrap-navigation-description = Move from the last item to the first and from the first to the last
+slideshow-interval = Gallery slideshow interval
+seconds = {$seconds} {$seconds ->
+        [one] second
+        *[other] seconds
+    }
+folder-views = Remember view per folder
+folder-views-description = View, sort and hidden files are remembered for {$folders} {$folders ->
+        [one] folder
+        *[other] folders
+    }
+forget-folder-views = Forget all
 queue-file-operations = Execute File Operations one after the other
-f2-rename = F2 Rename
-f3-view = F3 View
-f4-edit = F4 Edit
+conflict-policy = When an item already exists
+conflict-ask = Ask every time
+conflict-overwrite = Overwrite
+conflict-skip = Skip
+conflict-rename = Rename with a number
+conflict-newer = Overwrite only if newer
+unique-name-template = Name of items kept alongside existing ones
+unique-name-template-description = {"{"}name{"}"}, {"{"}n{"}"} and {"{"}ext{"}"} are replaced by the name, a free number and the extension
+unique-name-template-invalid = The name must contain {"{"}n{"}"}, and no "/". The default is used instead.
+unique-name-base = First number tried
+archive-open-action = When opening an archive
+archive-open = Open it with the default application
+archive-open-extract-here = Extract it next to the archive
+archive-open-extract-other = Extract it to the other panel
+open-scripts-in-terminal = Offer to run scripts in a terminal
+open-scripts-in-terminal-description = Opening a script asks whether to run it or open it with an application
+auto-refresh-interval = Refresh the active tab every
+auto-refresh-interval-description = Picks up changes the file watcher misses, like on network folders
+auto-refresh-never = Never
+tab-titles = Tab titles
+tab-titles-folder-name = Folder name
+tab-titles-distinct = Folder name, with parent folders when names repeat
+tab-titles-full-path = Full path
+notify-operations = Notify when file operations finish in the background
+notify-completion = When complete or failed
+notify-failure = Only when failed
+notify-never = Never
+notify-sound = Play a sound when file operations complete
+confirm-trash = Ask before moving items to the trash
+trash-unavailable = When a drive has no trash
+trash-unavailable-ask = Ask every time
+trash-unavailable-create = Create a trash folder on the drive
+trash-unavailable-delete = Delete permanently
+confirm-permanent-delete = Ask before deleting items permanently
+single-instance = Open new invocations in the running window
+allow-open-as-root = Offer opening items as administrator
+allow-open-as-root-description = Adds "Open as administrator" to the File menu, which asks for your password with pkexec
+read-only-mode = Read-only mode
+read-only-mode-description = Files can be browsed, opened and copied to the clipboard, but not changed
+read-only-indicator = Read-only mode is on, click to turn it off
+read-only-refused = Nothing was changed, as read-only mode is on
+button-rename = Rename
+button-view = View
+button-edit = Edit
+button-copy = Copy
+button-move = Move
+button-mkdir = mkdir
+button-delete = Delete
+button-terminal = Terminal
+button-quit = Quit
 f5-copy = F5 Copy
 f6-move = F6 Move
-f7-mkdir = F7 mkdir
-f8-delete = F8 Delete
-f9-Term = F9 Terminal
-f10-quit = F10 Quit
+copy-to = Copy to...
+copy-as = Copy as...
+move-to = Move to...
+drop-copy = Drop to copy into {$name}
+drop-move = Drop to move into {$name}
+set-modified-time = Set modification time...
 copy-tab = Copy current tab to other side
 move-tab = Move current tab to other side
+move-tab-to-window = Move current tab to new window
 grid-view = Grid view
 list-view = List view
 show-hidden-files = Show hidden files
 list-directories-first = List directories first
+flatten-view = Show files of subfolders
+calculate-folder-sizes = Calculate folder sizes
+cancel-folder-sizes = Stop calculating folder sizes
+mirror-navigation = Mirror navigation in the other panel
+quick-preview = Quick preview
 gallery-preview = Gallery preview
+command-palette = Command palette
+type-a-command = Type a command
+no-matching-commands = No matching commands
+swap-panels = Swap panels
+focus-other-panel = Focus other panel
+reload-folder = Reload folder
 menu-settings = Settings...
+import-settings = Import settings...
+export-settings = Export settings...
+import = Import
+export = Export
+settings-file = Settings file
+import-settings-failed = Failed to import settings
+import-settings-warnings = Some settings were not imported
+export-settings-failed = Failed to export settings
 menu-about = About  Commander...
 
 ## Sort
@@ -311,3 +695,6 @@ sort-newest-first = Newest first
 sort-oldest-first = Oldest first
 sort-smallest-to-largest = Smallest to largest
 sort-largest-to-smallest = Largest to smallest
+sort-then-by-name = Then by name
+sort-then-by-modified = Then by date
+sort-then-by-size = Then by size
@@ -153,7 +153,6 @@ compressed = Komprimerade {$items} {$items ->
         [one] item
         *[other] items
     } from "{$from}" to "{$to}"
-copy_noun = Koperia
 creating = Skapar "{$name}" i "{$parent}"
 created = Skapade "{$name}" i "{$parent}"
 copying = Kopierar {$items} {$items ->
@@ -265,15 +264,17 @@ view = Visa
 show-button-row = Visa knapprad
 show-embedded-terminal = Visa inbäddad terminal
 show-second-panel = Visa den andra filhanterarens panel
-f2-rename = F2 Byt namn
-f3-view = F3 Visa
-f4-edit = F4 Redigera
+button-rename = Byt namn
+button-view = Visa
+button-edit = Redigera
+button-copy = Kopiera
+button-move = Flytta
+button-mkdir = mkdir
+button-delete = Radera
+button-terminal = Terminal
+button-quit = Avsluta
 f5-copy = F5 Kopiera
 f6-move = F6 Flytta
-f7-mkdir = F7 mkdir
-f8-delete = F8 Radera
-f9-Term = F9 Terminal
-f10-quit = F10 Avsluta
 copy-tab = Kopiera nuvarande flik till andra sidan
 move-tab = Flytta nuvarande flik till andra sidan
 grid-view = Rutnätsvy
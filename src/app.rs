@@ -16,7 +16,8 @@ use cosmic::iced::{
 use cosmic::iced_winit::commands::overlap_notify::overlap_notify;
 use cosmic::{
     app::{self, context_drawer, message, Core, Task},
-    cosmic_config, cosmic_theme, executor,
+    cosmic_config::{self, CosmicConfigEntry},
+    cosmic_theme, executor,
     iced::{
         clipboard::dnd::DndAction,
         event,
@@ -44,16 +45,19 @@ use notify_debouncer_full::{
     notify::{self, RecommendedWatcher, Watcher},
     DebouncedEvent, Debouncer, FileIdMap,
 };
+use serde::{Deserialize, Serialize};
 use slotmap::Key as SlotMapKey;
 use std::{
     any::TypeId,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
-    env, fmt, fs, io,
+    env,
+    ffi::OsString,
+    fmt, fs, io,
     num::NonZeroU16,
     path::{Path, PathBuf},
     process,
-    sync::{Arc, Mutex},
-    time::{self, Instant},
+    sync::{atomic, Arc, Mutex},
+    time::{self, Instant, SystemTime},
 };
 use tokio::sync::mpsc;
 use trash::TrashItem;
@@ -65,16 +69,26 @@ use alacritty_terminal::{event::Event as TermEvent, term, term::color::Colors as
 use crate::{
     clipboard::{ClipboardCopy, ClipboardKind, ClipboardPaste},
     config::{
-        self, AppTheme, ColorSchemeKind, Config, DesktopConfig, Favorite, IconSizes, TabConfig1,
-        TabConfig2,
+        self, AppTheme, ArchiveOpenAction, ButtonRowAction, ColorSchemeKind, Config,
+        ConflictPolicy, DesktopConfig, DesktopGridFlow, DesktopGridOrigin, Favorite, IconSizes,
+        NotifyOperations, ParsedCustomAction, SidebarBuiltin, TabConfig1, TabConfig2, TabTitles,
+        TrashUnavailable,
     },
-    fl, home_dir,
+    diff::{self, DiffKind, DiffRow},
+    dir_size, err_str, fl, home_dir, item_cache,
     key_bind::{key_binds, key_binds_terminal},
     localize::LANGUAGE_SORTER,
     menu, mime_app, mime_icon,
-    mounter::{MounterAuth, MounterItem, MounterItems, MounterKey, MounterMessage, MOUNTERS},
-    operation::{Controller, Operation, OperationSelection, ReplaceResult},
+    mounter::{
+        MounterAuth, MounterItem, MounterItems, MounterKey, MounterMessage, UnmountError, MOUNTERS,
+    },
+    operation::{
+        copy_unique_path, recursive::set_copy_options, set_unique_name_options, trash_dir,
+        unique_name_template_valid, Controller, Operation, OperationHistory, OperationSelection,
+        ReplaceResult, EXTRACT_MIME_TYPES, UNIQUE_NAME_TEMPLATE,
+    },
     pane_grid::{self, PaneGrid},
+    recents,
     spawn_detached::spawn_detached,
     tab1::{
         self, HeadingOptions as HeadingOptions1, ItemMetadata as ItemMetadata1,
@@ -88,6 +102,30 @@ use crate::{
 
 type TabModel = segmented_button::Model<segmented_button::SingleSelect>;
 
+const MAX_RECENT_NETWORK_DRIVES: usize = 10;
+// Opening more files than this at once asks for confirmation
+const OPEN_FILES_CONFIRM: usize = 10;
+// Number of files listed in the recent files menu
+const MAX_RECENT_FILES: usize = 20;
+// Choices for the seconds each image is shown in the gallery slideshow
+const SLIDESHOW_INTERVALS: &[u16] = &[2, 3, 5, 10, 30];
+// Choices for the lines of output the embedded terminal keeps
+const TERMINAL_SCROLLBACK_LINES: &[usize] = &[1_000, 10_000, 50_000, 100_000];
+// Choices for the seconds between rescans of the active tab, 0 disables rescanning
+const AUTO_REFRESH_INTERVALS: &[u16] = &[0, 5, 10, 30, 60, 300];
+// Choices for the first number tried in names that avoid overwriting an item
+const UNIQUE_NAME_BASES: &[u32] = &[0, 1, 2];
+// Number of completed operations kept in the edit history
+const MAX_COMPLETE_OPERATIONS: usize = 100;
+// Number of files changed while copying that are listed by name
+const CHANGED_NAMES_MAX: usize = 5;
+// Number of recently used commands listed first in the command palette
+const MAX_PALETTE_RECENT: usize = 8;
+// Characters of the path shown for the item an operation is processing
+const CURRENT_PATH_MAX: usize = 60;
+// Time a new window has to fail in before a tab moved to it is closed here
+const NEW_WINDOW_STARTUP: time::Duration = time::Duration::from_secs(2);
+
 #[derive(Clone, Debug)]
 pub enum Mode {
     App,
@@ -101,30 +139,110 @@ pub struct Flags {
     pub mode: Mode,
     pub locations1: Vec<Location1>,
     pub locations2: Vec<Location1>,
+    /// Path to show selected in its folder in the active panel
+    pub reveal_opt: Option<PathBuf>,
+    pub layout_overrides: LayoutOverrides,
+}
+
+impl Flags {
+    /// Command line arguments that open a new window with these locations and layout
+    pub fn args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        for (arg, locations) in [("--left", &self.locations1), ("--right", &self.locations2)] {
+            for location in locations {
+                match location {
+                    Location1::Path(path) => {
+                        args.push(arg.into());
+                        args.push(path.clone().into_os_string());
+                    }
+                    Location1::Trash if arg == "--left" => args.push("--trash".into()),
+                    _ => {}
+                }
+            }
+        }
+        let LayoutOverrides {
+            show_button_row,
+            show_embedded_terminal,
+            show_second_panel,
+        } = self.layout_overrides;
+        for (show_opt, show_arg, hide_arg) in [
+            (show_button_row, "--button-row", "--no-button-row"),
+            (show_embedded_terminal, "--terminal", "--no-terminal"),
+            (show_second_panel, "--second-panel", "--no-second-panel"),
+        ] {
+            if let Some(show) = show_opt {
+                args.push(if show { show_arg } else { hide_arg }.into());
+            }
+        }
+        args
+    }
+}
+
+/// Layout settings from the command line, which take precedence over the config without being
+/// saved to it
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LayoutOverrides {
+    pub show_button_row: Option<bool>,
+    pub show_embedded_terminal: Option<bool>,
+    pub show_second_panel: Option<bool>,
+}
+
+impl LayoutOverrides {
+    /// Take the settings given by `other`, keeping the others
+    fn merge(&mut self, other: Self) {
+        self.show_button_row = other.show_button_row.or(self.show_button_row);
+        self.show_embedded_terminal = other.show_embedded_terminal.or(self.show_embedded_terminal);
+        self.show_second_panel = other.show_second_panel.or(self.show_second_panel);
+    }
+
+    fn apply(&self, config: &mut Config) {
+        if let Some(show) = self.show_button_row {
+            config.show_button_row = show;
+        }
+        if let Some(show) = self.show_embedded_terminal {
+            config.show_embedded_terminal = show;
+        }
+        if let Some(show) = self.show_second_panel {
+            config.show_second_panel = show;
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Action {
     About,
     AddToSidebar,
+    CalculateFolderSizes,
+    CancelFolderSizes,
+    ChangeExtension,
+    ClearRecents,
     ClearScrollback,
+    CommandPalette,
     Compress,
     Copy,
+    CopyAs,
     CopyTerminal,
     CopyOrSigint,
     CopyPrimary,
     CopyTab,
+    CopyTo,
     Cut,
+    CustomAction(usize),
     CosmicSettingsAppearance,
     CosmicSettingsDisplays,
     CosmicSettingsWallpaper,
+    DeselectByPattern,
     DesktopViewOptions,
+    DiffSelected,
+    DuplicateWindow,
     EditHistory,
     EditLocation,
     EmptyTrash,
     #[cfg(feature = "desktop")]
     ExecEntryAction(usize),
+    ExportSettings,
     ExtractHere,
+    ExtractToOtherPanel,
     F2Rename,
     F3View,
     F4Edit,
@@ -134,36 +252,58 @@ pub enum Action {
     F8Delete,
     F9Terminal,
     F10Quit,
+    FinalizeArchive,
+    FocusOtherPanel,
     Gallery,
     HistoryNext,
     HistoryPrevious,
+    ImportSettings,
     ItemDown,
     ItemLeft,
     ItemRight,
     ItemUp,
     LocationUp,
+    MirrorSelection,
     MoveTab,
+    MoveTabToWindow,
+    MoveTo,
     MoveToTrash,
+    NewArchive,
     NewFile,
     NewFolder,
     Open,
+    OpenAsRoot,
     OpenInNewTab,
     OpenInNewWindow,
     OpenItemLocation,
+    OpenLinkTarget,
     OpenTerminal,
     OpenWith,
+    PageDown,
+    PageUp,
     Paste,
     PastePrimary,
     PasteTerminal,
     PastePrimaryTerminal,
+    PendingPauseToggle,
+    PermanentlyDelete,
     Preview,
+    QuickPreview,
+    Redo,
+    RemoveFromRecents,
     Rename,
+    ResetTerminal,
     RestoreFromTrash,
+    RestoreTo,
     SearchActivate,
+    SelectByCriteria,
+    SelectByPattern,
     SelectFirst,
     SelectLast,
     SelectAll,
+    SetModified,
     SetSort(HeadingOptions1, bool),
+    SetSortSecondary(HeadingOptions1),
     Settings,
     SwapPanels,
     TabClose,
@@ -173,16 +313,22 @@ pub enum Action {
     TabRescan,
     TabViewGrid,
     TabViewList,
+    ToggleFlattenView,
     ToggleFoldersFirst,
+    ToggleMirrorNavigation,
+    ToggleReadOnly,
+    ToggleRespectGitignore,
     ToggleShowHidden,
     ToggleSortLeft(HeadingOptions1),
     ToggleSortRight(HeadingOptions2),
+    Undo,
     WindowClose,
     WindowNew,
     ZoomDefault,
     ZoomIn,
     ZoomOut,
     Recents,
+    RecentFilesMenu,
 }
 
 impl Action {
@@ -190,22 +336,34 @@ impl Action {
         match self {
             Action::About => Message::ToggleContextPage(ContextPage::About),
             Action::AddToSidebar => Message::AddToSidebar(entity_opt),
+            Action::CalculateFolderSizes => Message::CalculateFolderSizes(entity_opt),
+            Action::CancelFolderSizes => Message::CancelFolderSizes(entity_opt),
+            Action::ChangeExtension => Message::ChangeExtension(entity_opt),
+            Action::ClearRecents => Message::RecentFilesClear,
             Action::ClearScrollback => Message::ClearScrollback(entity_opt),
+            Action::CommandPalette => Message::CommandPalette,
             Action::Compress => Message::Compress(entity_opt),
             Action::Copy => Message::Copy(entity_opt),
+            Action::CopyAs => Message::CopyAs(entity_opt),
             Action::CopyTerminal => Message::CopyTerminal(entity_opt),
             Action::CopyOrSigint => Message::CopyOrSigint(entity_opt),
             Action::CopyPrimary => Message::CopyPrimary(entity_opt),
             Action::CopyTab => Message::CopyTab(entity_opt),
+            Action::CopyTo => Message::CopyMoveTo(entity_opt, true),
             Action::Cut => Message::Cut(entity_opt),
+            Action::CustomAction(action) => Message::CustomAction(entity_opt, *action),
             Action::CosmicSettingsAppearance => Message::CosmicSettings("appearance"),
             Action::CosmicSettingsDisplays => Message::CosmicSettings("displays"),
             Action::CosmicSettingsWallpaper => Message::CosmicSettings("wallpaper"),
+            Action::DeselectByPattern => Message::SelectByPattern(false),
             Action::DesktopViewOptions => Message::DesktopViewOptions,
             Action::EditHistory => Message::ToggleContextPage(ContextPage::EditHistory),
             Action::EditLocation => Message::EditLocation(entity_opt),
             Action::EmptyTrash => Message::EmptyTrash(entity_opt),
+            Action::ExportSettings => Message::SettingsFile(true),
             Action::ExtractHere => Message::ExtractHere(entity_opt),
+            Action::ExtractToOtherPanel => Message::ExtractToOtherPanel(entity_opt),
+            Action::FinalizeArchive => Message::FinalizeArchive,
             #[cfg(feature = "desktop")]
             Action::ExecEntryAction(action) => Message::ExecEntryAction(entity_opt, *action),
             Action::F2Rename => Message::F2Rename,
@@ -217,36 +375,56 @@ impl Action {
             Action::F8Delete => Message::F8Delete,
             Action::F9Terminal => Message::F9Terminal,
             Action::F10Quit => Message::F10Quit,
+            Action::FocusOtherPanel => Message::FocusOtherPanel,
             Action::Gallery => Message::GalleryToggle(entity_opt),
             Action::HistoryNext => Message::HistoryNext(entity_opt),
             Action::HistoryPrevious => Message::HistoryPrevious(entity_opt),
+            Action::ImportSettings => Message::SettingsFile(false),
             Action::ItemDown => Message::ItemDown(entity_opt),
             Action::ItemLeft => Message::ItemLeft(entity_opt),
             Action::ItemRight => Message::ItemRight(entity_opt),
             Action::ItemUp => Message::ItemUp(entity_opt),
             Action::LocationUp => Message::LocationUp(entity_opt),
             Action::MoveTab => Message::MoveTab(entity_opt),
+            Action::MoveTabToWindow => Message::MoveTabToWindow(entity_opt),
+            Action::MoveTo => Message::CopyMoveTo(entity_opt, false),
             Action::MoveToTrash => Message::MoveToTrash(entity_opt),
+            Action::NewArchive => Message::NewArchive(entity_opt),
             Action::NewFile => Message::NewItem(entity_opt, false),
             Action::NewFolder => Message::NewItem(entity_opt, true),
             Action::Open => Message::Open(entity_opt),
+            Action::OpenAsRoot => Message::OpenAsRoot(entity_opt),
             Action::OpenInNewTab => Message::OpenInNewTab(entity_opt),
             Action::OpenInNewWindow => Message::OpenInNewWindow(entity_opt),
             Action::OpenItemLocation => Message::OpenItemLocation(entity_opt),
+            Action::OpenLinkTarget => Message::OpenLinkTarget(entity_opt),
             Action::OpenTerminal => Message::OpenTerminal(entity_opt),
             Action::OpenWith => Message::OpenWithDialog(entity_opt),
+            Action::PageDown => Message::PageDown(entity_opt),
+            Action::PageUp => Message::PageUp(entity_opt),
             Action::Paste => Message::Paste(entity_opt),
             Action::PastePrimary => Message::PastePrimary(entity_opt),
             Action::PasteTerminal => Message::PasteTerminal(entity_opt),
             Action::PastePrimaryTerminal => Message::PastePrimaryTerminal(entity_opt),
+            Action::PendingPauseToggle => Message::PendingPauseToggle,
+            Action::PermanentlyDelete => Message::PermanentlyDelete(entity_opt),
             Action::Preview => Message::Preview(entity_opt),
+            Action::QuickPreview => Message::QuickPreview(entity_opt),
+            Action::Redo => Message::Redo,
+            Action::RemoveFromRecents => Message::RemoveFromRecents(entity_opt),
             Action::Rename => Message::Rename(entity_opt),
+            Action::ResetTerminal => Message::ResetTerminal,
             Action::RestoreFromTrash => Message::RestoreFromTrash(entity_opt),
+            Action::RestoreTo => Message::RestoreTo(entity_opt),
             Action::SearchActivate => Message::SearchActivate,
             Action::SelectAll => Message::SelectAll(entity_opt),
+            Action::SelectByCriteria => Message::SelectByCriteria,
+            Action::SelectByPattern => Message::SelectByPattern(true),
             Action::SelectFirst => Message::SelectFirst(entity_opt),
             Action::SelectLast => Message::SelectLast(entity_opt),
+            Action::SetModified => Message::SetModified(entity_opt),
             Action::SetSort(sort, dir) => Message::SetSort(entity_opt, *sort, *dir),
+            Action::SetSortSecondary(sort) => Message::SetSortSecondary(*sort),
             Action::Settings => Message::ToggleContextPage(ContextPage::Settings),
             Action::SwapPanels => Message::SwapPanels,
             Action::TabClose => Message::TabClose(entity_opt),
@@ -256,18 +434,60 @@ impl Action {
             Action::TabRescan => Message::TabRescan,
             Action::TabViewGrid => Message::TabView(entity_opt, tab1::View::Grid),
             Action::TabViewList => Message::TabView(entity_opt, tab1::View::List),
+            Action::ToggleFlattenView => Message::ToggleFlattenView(entity_opt),
             Action::ToggleFoldersFirst => Message::ToggleFoldersFirst,
+            Action::ToggleMirrorNavigation => Message::ToggleMirrorNavigation,
+            Action::ToggleReadOnly => Message::ToggleReadOnly,
+            Action::MirrorSelection => Message::MirrorSelection,
+            Action::DiffSelected => Message::DiffSelected,
+            Action::DuplicateWindow => Message::DuplicateWindow,
+            Action::ToggleRespectGitignore => Message::ToggleRespectGitignore,
             Action::ToggleShowHidden => Message::ToggleShowHidden(entity_opt),
             Action::ToggleSortLeft(sort) => Message::ToggleSortLeft(entity_opt, *sort),
             Action::ToggleSortRight(sort) => Message::ToggleSortRight(entity_opt, *sort),
+            Action::Undo => Message::Undo,
             Action::WindowClose => Message::WindowClose,
             Action::WindowNew => Message::WindowNew,
             Action::ZoomDefault => Message::ZoomDefault(entity_opt),
             Action::ZoomIn => Message::ZoomIn(entity_opt),
             Action::ZoomOut => Message::ZoomOut(entity_opt),
             Action::Recents => Message::Recents,
+            Action::RecentFilesMenu => Message::RecentFilesMenu,
         }
     }
+
+    /// Whether the action changes files, so it is not offered in read-only mode
+    pub fn changes_files(&self) -> bool {
+        matches!(
+            self,
+            Action::ChangeExtension
+                | Action::Compress
+                | Action::CopyAs
+                | Action::CopyTo
+                | Action::EmptyTrash
+                | Action::ExtractHere
+                | Action::ExtractToOtherPanel
+                | Action::F2Rename
+                | Action::F5Copy
+                | Action::F6Move
+                | Action::F7Mkdir
+                | Action::F8Delete
+                | Action::FinalizeArchive
+                | Action::MoveTo
+                | Action::MoveToTrash
+                | Action::NewArchive
+                | Action::NewFile
+                | Action::NewFolder
+                | Action::Paste
+                | Action::PermanentlyDelete
+                | Action::Redo
+                | Action::Rename
+                | Action::RestoreFromTrash
+                | Action::RestoreTo
+                | Action::SetModified
+                | Action::Undo
+        )
+    }
 }
 
 impl MenuAction for Action {
@@ -309,7 +529,7 @@ pub enum PreviewKind {
     Selected,
 }
 
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Deserialize, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum PaneType {
     ButtonPane,
     TerminalPane,
@@ -317,6 +537,587 @@ pub enum PaneType {
     RightPane,
 }
 
+/// What happens to files dropped onto a folder
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DropAction {
+    Copy,
+    Move,
+    /// Holding Alt while dropping opens the compress dialog to create an archive in the folder
+    Compress,
+}
+
+impl DropAction {
+    fn new(action: DndAction, modifiers: Modifiers) -> Self {
+        if modifiers.alt() {
+            Self::Compress
+        } else if action == DndAction::Move {
+            Self::Move
+        } else {
+            Self::Copy
+        }
+    }
+
+    /// How the dropped files are pasted, unless they are compressed
+    fn paste_kind(self) -> Option<ClipboardKind> {
+        match self {
+            Self::Copy => Some(ClipboardKind::Copy),
+            Self::Move => Some(ClipboardKind::Cut),
+            Self::Compress => None,
+        }
+    }
+}
+
+/// Operation pasting files into the folder `to`, which is left out of the pasted files. Drops
+/// are pasted the same way, so they get the same conflict dialogs and progress.
+fn paste_operation(to: PathBuf, mut contents: ClipboardPaste) -> Option<Operation> {
+    contents.paths.retain(|p| p != &to);
+    if contents.paths.is_empty() {
+        return None;
+    }
+    Some(match contents.kind {
+        ClipboardKind::Copy => Operation::Copy {
+            paths: contents.paths,
+            to,
+        },
+        ClipboardKind::Cut => Operation::Move {
+            paths: contents.paths,
+            to,
+        },
+    })
+}
+
+/// Access to the tabs of one of the two file panels, so code that is the same for the left and
+/// right panel only has to be written once
+trait ActivePane {
+    type Tab: 'static;
+    type Location: Clone + 'static;
+    /// The panel on the other side
+    type Other: ActivePane;
+    /// The pane the panel is shown in
+    const PANE: PaneType;
+
+    fn tab_model(app: &App) -> &TabModel;
+
+    fn tab_model_mut(app: &mut App) -> &mut TabModel;
+
+    fn location(tab: &Self::Tab) -> &Self::Location;
+
+    /// The location as stored by the sidebar, which is shared by both panels
+    fn nav_location(location: &Self::Location) -> Location1;
+
+    /// A location stored by the sidebar as shown in this panel
+    fn from_nav_location(location: &Location1) -> Self::Location;
+
+    /// Message opening a location in the active tab, keeping its history
+    fn location_message(location: Self::Location) -> Message;
+
+    /// Message starting a scan of a tab, started with `Self::Tab::scan_begin`
+    fn rescan_message(entity: Entity, location: Self::Location, epoch: u64) -> Message;
+
+    fn title(tab: &Self::Tab) -> String;
+
+    fn scan_begin(tab: &Self::Tab) -> u64;
+
+    fn change_location(tab: &mut Self::Tab, location: &Self::Location);
+
+    fn close_edit_location(tab: &mut Self::Tab);
+
+    fn path_opt(location: &Self::Location) -> Option<&PathBuf>;
+
+    fn search_term(location: &Self::Location) -> Option<&str>;
+
+    /// Location of a tab searching for `term_opt`, or leaving the search if it is None, and
+    /// whether the search input needs focus
+    fn search_location(tab: &Self::Tab, term_opt: Option<String>)
+        -> Option<(Self::Location, bool)>;
+
+    fn is_trash(location: &Self::Location) -> bool;
+
+    fn is_recents(location: &Self::Location) -> bool;
+
+    fn selected_paths(tab: &Self::Tab) -> Vec<PathBuf>;
+
+    fn selected_trash_items(tab: &Self::Tab) -> Vec<TrashItem>;
+
+    fn update_tab(
+        app: &mut App,
+        entity: Entity,
+        location: Self::Location,
+        selection_paths: Option<Vec<PathBuf>>,
+    ) -> Task<Message>;
+
+    fn rescan_tab(
+        app: &mut App,
+        entity: Entity,
+        location: Self::Location,
+        selection_paths: Option<Vec<PathBuf>>,
+    ) -> Task<Message>;
+
+    fn update_watcher(app: &mut App) -> Task<Message>;
+}
+
+struct LeftPanel;
+
+impl ActivePane for LeftPanel {
+    type Tab = Tab1;
+    type Location = Location1;
+    type Other = RightPanel;
+    const PANE: PaneType = PaneType::LeftPane;
+
+    fn tab_model(app: &App) -> &TabModel {
+        &app.tab_model1
+    }
+
+    fn tab_model_mut(app: &mut App) -> &mut TabModel {
+        &mut app.tab_model1
+    }
+
+    fn location(tab: &Tab1) -> &Location1 {
+        &tab.location
+    }
+
+    fn nav_location(location: &Location1) -> Location1 {
+        location.clone()
+    }
+
+    fn from_nav_location(location: &Location1) -> Location1 {
+        location.clone()
+    }
+
+    fn location_message(location: Location1) -> Message {
+        Message::TabMessage(None, tab1::Message::Location(location))
+    }
+
+    fn rescan_message(entity: Entity, location: Location1, epoch: u64) -> Message {
+        Message::TabRescanLeft(entity, location, epoch, None, Some(Vec::new()), None, None)
+    }
+
+    fn title(tab: &Tab1) -> String {
+        tab.title()
+    }
+
+    fn scan_begin(tab: &Tab1) -> u64 {
+        tab.scan_begin()
+    }
+
+    fn change_location(tab: &mut Tab1, location: &Location1) {
+        tab.change_location(location, None);
+    }
+
+    fn close_edit_location(tab: &mut Tab1) {
+        tab.edit_location = None;
+    }
+
+    fn path_opt(location: &Location1) -> Option<&PathBuf> {
+        location.path_opt()
+    }
+
+    fn search_term(location: &Location1) -> Option<&str> {
+        match location {
+            Location1::Search(_, term, ..) => Some(term),
+            _ => None,
+        }
+    }
+
+    fn search_location(tab: &Tab1, term_opt: Option<String>) -> Option<(Location1, bool)> {
+        match term_opt {
+            Some(term) => match &tab.location {
+                Location1::Path(path)
+                | Location1::Search(path, ..)
+                | Location1::Flatten(path, ..) => Some((
+                    Location1::Search(
+                        path.to_path_buf(),
+                        term,
+                        tab.config.show_hidden,
+                        Instant::now(),
+                    ),
+                    true,
+                )),
+                _ => None,
+            },
+            None => match &tab.location {
+                Location1::Search(path, ..) | Location1::Flatten(path, ..) => {
+                    Some((Location1::Path(path.to_path_buf()), false))
+                }
+                _ => None,
+            },
+        }
+    }
+
+    fn is_trash(location: &Location1) -> bool {
+        matches!(location, Location1::Trash)
+    }
+
+    fn is_recents(location: &Location1) -> bool {
+        matches!(location, Location1::Recents)
+    }
+
+    fn selected_paths(tab: &Tab1) -> Vec<PathBuf> {
+        tab.selected_locations()
+            .iter()
+            .filter_map(|location| location.path_opt().cloned())
+            .collect()
+    }
+
+    fn selected_trash_items(tab: &Tab1) -> Vec<TrashItem> {
+        tab.items_opt()
+            .into_iter()
+            .flatten()
+            .filter(|item| item.selected)
+            .filter_map(|item| match &item.metadata {
+                ItemMetadata1::Trash { entry, .. } => Some(entry.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn update_tab(
+        app: &mut App,
+        entity: Entity,
+        location: Location1,
+        selection_paths: Option<Vec<PathBuf>>,
+    ) -> Task<Message> {
+        app.update_tab_left(entity, location, selection_paths)
+    }
+
+    fn rescan_tab(
+        app: &mut App,
+        entity: Entity,
+        location: Location1,
+        selection_paths: Option<Vec<PathBuf>>,
+    ) -> Task<Message> {
+        app.rescan_tab_left(entity, location, selection_paths)
+    }
+
+    fn update_watcher(app: &mut App) -> Task<Message> {
+        app.update_watcher_left()
+    }
+}
+
+struct RightPanel;
+
+impl ActivePane for RightPanel {
+    type Tab = Tab2;
+    type Location = Location2;
+    type Other = LeftPanel;
+    const PANE: PaneType = PaneType::RightPane;
+
+    fn tab_model(app: &App) -> &TabModel {
+        &app.tab_model2
+    }
+
+    fn tab_model_mut(app: &mut App) -> &mut TabModel {
+        &mut app.tab_model2
+    }
+
+    fn location(tab: &Tab2) -> &Location2 {
+        &tab.location
+    }
+
+    fn nav_location(location: &Location2) -> Location1 {
+        convert_location2_to_location1(location)
+    }
+
+    fn from_nav_location(location: &Location1) -> Location2 {
+        convert_location1_to_location2(location)
+    }
+
+    fn location_message(location: Location2) -> Message {
+        Message::TabMessageRight(None, tab2::Message::Location(location))
+    }
+
+    fn rescan_message(entity: Entity, location: Location2, epoch: u64) -> Message {
+        Message::TabRescanRight(entity, location, epoch, None, Some(Vec::new()), None, None)
+    }
+
+    fn title(tab: &Tab2) -> String {
+        tab.title()
+    }
+
+    fn scan_begin(tab: &Tab2) -> u64 {
+        tab.scan_begin()
+    }
+
+    fn change_location(tab: &mut Tab2, location: &Location2) {
+        tab.change_location(location, None);
+    }
+
+    fn close_edit_location(tab: &mut Tab2) {
+        tab.edit_location = None;
+    }
+
+    fn path_opt(location: &Location2) -> Option<&PathBuf> {
+        location.path_opt()
+    }
+
+    fn search_term(location: &Location2) -> Option<&str> {
+        match location {
+            Location2::Search(_, term, ..) => Some(term),
+            _ => None,
+        }
+    }
+
+    fn search_location(tab: &Tab2, term_opt: Option<String>) -> Option<(Location2, bool)> {
+        match term_opt {
+            Some(term) => match &tab.location {
+                Location2::Path(path)
+                | Location2::Search(path, ..)
+                | Location2::Flatten(path, ..) => Some((
+                    Location2::Search(
+                        path.to_path_buf(),
+                        term,
+                        tab.config.show_hidden,
+                        Instant::now(),
+                    ),
+                    true,
+                )),
+                _ => None,
+            },
+            None => match &tab.location {
+                Location2::Search(path, ..) | Location2::Flatten(path, ..) => {
+                    Some((Location2::Path(path.to_path_buf()), false))
+                }
+                _ => None,
+            },
+        }
+    }
+
+    fn is_trash(location: &Location2) -> bool {
+        matches!(location, Location2::Trash)
+    }
+
+    fn is_recents(location: &Location2) -> bool {
+        matches!(location, Location2::Recents)
+    }
+
+    fn selected_paths(tab: &Tab2) -> Vec<PathBuf> {
+        tab.selected_locations()
+            .iter()
+            .filter_map(|location| location.path_opt().cloned())
+            .collect()
+    }
+
+    fn selected_trash_items(tab: &Tab2) -> Vec<TrashItem> {
+        tab.items_opt()
+            .into_iter()
+            .flatten()
+            .filter(|item| item.selected)
+            .filter_map(|item| match &item.metadata {
+                ItemMetadata2::Trash { entry, .. } => Some(entry.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn update_tab(
+        app: &mut App,
+        entity: Entity,
+        location: Location2,
+        selection_paths: Option<Vec<PathBuf>>,
+    ) -> Task<Message> {
+        app.update_tab_right(entity, location, selection_paths)
+    }
+
+    fn rescan_tab(
+        app: &mut App,
+        entity: Entity,
+        location: Location2,
+        selection_paths: Option<Vec<PathBuf>>,
+    ) -> Task<Message> {
+        app.rescan_tab_right(entity, location, selection_paths)
+    }
+
+    fn update_watcher(app: &mut App) -> Task<Message> {
+        app.update_watcher_right()
+    }
+}
+
+/// Folder of the active tab of a panel
+fn active_tab_path<P: ActivePane>(tab_model: &TabModel) -> Option<PathBuf> {
+    tab_model
+        .data::<P::Tab>(tab_model.active())
+        .and_then(|tab| P::path_opt(P::location(tab)).cloned())
+}
+
+/// A tab of a panel, or its active tab
+fn tab_or_active<P: ActivePane>(
+    tab_model: &TabModel,
+    entity_opt: Option<Entity>,
+) -> Option<&P::Tab> {
+    tab_model.data::<P::Tab>(entity_opt.unwrap_or_else(|| tab_model.active()))
+}
+
+/// Selected paths of a tab of a panel, or of its active tab
+fn tab_selected_paths<P: ActivePane>(
+    tab_model: &TabModel,
+    entity_opt: Option<Entity>,
+) -> Vec<PathBuf> {
+    tab_or_active::<P>(tab_model, entity_opt)
+        .map(P::selected_paths)
+        .unwrap_or_default()
+}
+
+/// Renames giving files `extension` in place of their last extension, or adding it to files
+/// without one. Fails with the names that are already used by other items or would be used twice.
+fn extension_renames(
+    paths: &[PathBuf],
+    extension: &str,
+) -> Result<Vec<(PathBuf, PathBuf)>, Vec<String>> {
+    let extension = extension.trim_start_matches('.');
+    let mut renames = Vec::with_capacity(paths.len());
+    let mut conflicts = Vec::new();
+    let mut targets = HashSet::with_capacity(paths.len());
+    for from in paths {
+        let to = from.with_extension(extension);
+        if !targets.insert(to.clone()) || (&to != from && to.exists()) {
+            conflicts.push(to.file_name().map_or_else(
+                || to.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            ));
+        } else if &to != from {
+            renames.push((from.clone(), to));
+        }
+    }
+    if conflicts.is_empty() {
+        Ok(renames)
+    } else {
+        conflicts.sort();
+        conflicts.dedup();
+        Err(conflicts)
+    }
+}
+
+/// Renames of the change extension dialog, or the reason the extension can't be used
+fn change_extension_renames(
+    paths: &[PathBuf],
+    extension: &str,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    // An empty extension would strip the extension of every file
+    if extension.trim_start_matches('.').is_empty() {
+        return Err(fl!("extension-empty"));
+    }
+    if extension.contains('/') {
+        return Err(fl!("name-no-slashes"));
+    }
+    extension_renames(paths, extension)
+        .map_err(|conflicts| fl!("extension-conflicts", names = conflicts.join(", ")))
+}
+
+/// Files to compare, either two selected in the active panel or one selected in each panel. The
+/// file from the left panel is shown on the left.
+fn diff_pair(
+    active: &[PathBuf],
+    other: &[PathBuf],
+    active_is_left: bool,
+) -> Option<(PathBuf, PathBuf)> {
+    match (active, other) {
+        ([left, right], _) => Some((left.clone(), right.clone())),
+        ([active], [other]) if active_is_left => Some((active.clone(), other.clone())),
+        ([active], [other]) => Some((other.clone(), active.clone())),
+        _ => None,
+    }
+}
+
+/// Titles of tabs, given their own title and the folder they show. Folders can get parent
+/// folders added to tell apart tabs with the same title, or be shown by their full path.
+fn tab_titles(tabs: &[(String, Option<PathBuf>)], mode: TabTitles) -> Vec<String> {
+    // Names of the parent folders of a path, closest first
+    let parent_names = |path: &Path| -> Vec<OsString> {
+        path.parent()
+            .map(|parent| {
+                parent
+                    .components()
+                    .rev()
+                    .map(|component| component.as_os_str().to_os_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    tabs.iter()
+        .map(|(title, path_opt)| {
+            let Some(path) = path_opt else {
+                return title.clone();
+            };
+            match mode {
+                TabTitles::FolderName => title.clone(),
+                TabTitles::FullPath => path.display().to_string(),
+                TabTitles::Distinct => {
+                    let names = parent_names(path);
+                    // Enough parent folders to differ from every other folder with this title
+                    let depth = tabs
+                        .iter()
+                        .filter_map(|(other_title, other_path_opt)| {
+                            let other_path = other_path_opt.as_ref()?;
+                            if other_title != title || other_path == path {
+                                return None;
+                            }
+                            let same = names
+                                .iter()
+                                .zip(parent_names(other_path).iter())
+                                .take_while(|(a, b)| a == b)
+                                .count();
+                            Some(same + 1)
+                        })
+                        .max()
+                        .unwrap_or(0)
+                        .min(names.len());
+                    names[..depth]
+                        .iter()
+                        .rev()
+                        .collect::<PathBuf>()
+                        .join(title)
+                        .display()
+                        .to_string()
+                }
+            }
+        })
+        .collect()
+}
+
+// Names of files whose copies may be incomplete, only the first few are listed
+fn changed_while_copying(paths: &[PathBuf]) -> String {
+    let mut names: Vec<_> = paths
+        .iter()
+        .take(CHANGED_NAMES_MAX)
+        .map(|path| {
+            path.file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    if paths.len() > CHANGED_NAMES_MAX {
+        names.push("…".to_string());
+    }
+    fl!(
+        "changed-while-copying",
+        items = paths.len(),
+        names = names.join(", ")
+    )
+}
+
+/// Tabs of a panel that show the trash
+fn trash_tabs<P: ActivePane>(tab_model: &TabModel) -> Vec<(Entity, P::Location)> {
+    tab_model
+        .iter()
+        .filter_map(|entity| {
+            let location = P::location(tab_model.data::<P::Tab>(entity)?);
+            P::is_trash(location).then(|| (entity, location.clone()))
+        })
+        .collect()
+}
+
+/// Tabs of a panel showing Recents, with their location
+fn recents_tabs<P: ActivePane>(tab_model: &TabModel) -> Vec<(Entity, P::Location)> {
+    tab_model
+        .iter()
+        .filter_map(|entity| {
+            let location = P::location(tab_model.data::<P::Tab>(entity)?);
+            P::is_recents(location).then(|| (entity, location.clone()))
+        })
+        .collect()
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Pane {
     id: PaneType,
@@ -342,6 +1143,9 @@ fn convert_location1_to_location2(location: &Location1) -> Location2 {
         Location1::Search(path, s, b, i) => {
             loc = Location2::Search(path.to_owned(), s.clone(), b.to_owned(), i.to_owned())
         }
+        Location1::Flatten(path, b, i) => {
+            loc = Location2::Flatten(path.to_owned(), b.to_owned(), i.to_owned())
+        }
         Location1::Desktop(p, s, d) => {
             loc = Location2::Desktop(p.to_owned(), s.to_owned(), d.to_owned())
         }
@@ -349,24 +1153,170 @@ fn convert_location1_to_location2(location: &Location1) -> Location2 {
     loc
 }
 
-fn convert_location2_to_location1(location: &Location2) -> Location1 {
-    let loc;
-    match location {
-        Location2::Path(path) => loc = Location1::Path(path.to_owned()),
-        Location2::Trash => loc = Location1::Trash,
-        Location2::Network(s1, s2) => loc = Location1::Network(s1.clone(), s2.clone()),
-        Location2::Recents => loc = Location1::Recents,
-        Location2::Search(path, s, b, i) => {
-            loc = Location1::Search(path.to_owned(), s.clone(), b.to_owned(), i.to_owned())
-        }
-        Location2::Desktop(p, s, d) => {
-            loc = Location1::Desktop(p.to_owned(), s.to_owned(), d.to_owned())
-        }
+fn convert_sort1_to_sort2(sort: HeadingOptions1) -> HeadingOptions2 {
+    match sort {
+        HeadingOptions1::Modified => HeadingOptions2::Modified,
+        HeadingOptions1::Name => HeadingOptions2::Name,
+        HeadingOptions1::TrashedOn => HeadingOptions2::TrashedOn,
+        HeadingOptions1::Size => HeadingOptions2::Size,
     }
-    loc
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// View option changed for the tabs of one panel
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PanelOption {
+    FoldersFirst(bool),
+    MiddleClickOpenFiles(bool),
+    RespectGitignore(bool),
+    ShowHidden(bool),
+    SlideshowInterval(u16),
+    SortSecondary(HeadingOptions1),
+}
+
+impl PanelOption {
+    /// Config with the option set for the tabs of `panel`, leaving the other panel as it is
+    fn apply(self, config: &Config, panel: PaneType) -> Config {
+        let mut config = config.clone();
+        let left = panel == PaneType::LeftPane;
+        match self {
+            Self::FoldersFirst(folders_first) if left => {
+                config.tab_left.folders_first = folders_first
+            }
+            Self::FoldersFirst(folders_first) => config.tab_right.folders_first = folders_first,
+            Self::MiddleClickOpenFiles(open_files) if left => {
+                config.tab_left.middle_click_open_files = open_files
+            }
+            Self::MiddleClickOpenFiles(open_files) => {
+                config.tab_right.middle_click_open_files = open_files
+            }
+            Self::RespectGitignore(respect_gitignore) if left => {
+                config.tab_left.respect_gitignore = respect_gitignore
+            }
+            Self::RespectGitignore(respect_gitignore) => {
+                config.tab_right.respect_gitignore = respect_gitignore
+            }
+            Self::ShowHidden(show_hidden) if left => config.tab_left.show_hidden = show_hidden,
+            Self::ShowHidden(show_hidden) => config.tab_right.show_hidden = show_hidden,
+            Self::SlideshowInterval(interval) if left => {
+                config.tab_left.slideshow_interval = interval
+            }
+            Self::SlideshowInterval(interval) => config.tab_right.slideshow_interval = interval,
+            Self::SortSecondary(sort) if left => config.tab_left.sort_secondary = sort,
+            Self::SortSecondary(sort) => {
+                config.tab_right.sort_secondary = convert_sort1_to_sort2(sort)
+            }
+        }
+        config
+    }
+}
+
+/// Sort the active tab of the active panel, `left` and `right` are the active tabs of each panel
+fn set_sort_message(
+    active_panel: PaneType,
+    left: Entity,
+    right: Entity,
+    sort: HeadingOptions1,
+    dir: bool,
+) -> Message {
+    if active_panel == PaneType::LeftPane {
+        Message::TabMessage(Some(left), tab1::Message::SetSort(sort, dir))
+    } else {
+        Message::TabMessageRight(
+            Some(right),
+            tab2::Message::SetSort(convert_sort1_to_sort2(sort), dir),
+        )
+    }
+}
+
+/// Start another window of this program, `--new-window` keeps a single instance configuration
+/// from handing its locations back to this instance
+fn spawn_new_window<I: IntoIterator<Item = OsString>>(args: I) -> Option<process::Child> {
+    let exe = match env::current_exe() {
+        Ok(exe) => exe,
+        Err(err) => {
+            log::error!("failed to get current executable path: {}", err);
+            return None;
+        }
+    };
+    match process::Command::new(&exe)
+        .arg("--new-window")
+        .args(args)
+        .spawn()
+    {
+        Ok(child) => Some(child),
+        Err(err) => {
+            log::error!("failed to execute {:?}: {}", exe, err);
+            None
+        }
+    }
+}
+
+/// Whether a window started by [`spawn_new_window`] opened, one that fails to start exits with
+/// an error within [`NEW_WINDOW_STARTUP`]
+async fn new_window_opened(mut child: process::Child) -> bool {
+    tokio::time::sleep(NEW_WINDOW_STARTUP).await;
+    match child.try_wait() {
+        Ok(None) => true,
+        Ok(Some(status)) => status.success(),
+        Err(err) => {
+            log::warn!("failed to check new window process: {}", err);
+            false
+        }
+    }
+}
+
+/// Clipboard that text is pasted into the terminal from
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PasteSource {
+    Clipboard,
+    Primary,
+}
+
+/// Source of the messages that paste into the terminal, the regular clipboard is only used by
+/// the paste action, middle click and the primary paste actions use the primary selection
+fn terminal_paste_source(message: &Message) -> Option<PasteSource> {
+    match message {
+        Message::PasteTerminal(_) => Some(PasteSource::Clipboard),
+        Message::PastePrimary(_)
+        | Message::PastePrimaryTerminal(_)
+        | Message::TermMiddleClick(..) => Some(PasteSource::Primary),
+        _ => None,
+    }
+}
+
+/// Read the source and paste its text into the terminal
+fn paste_terminal(source: PasteSource) -> Task<Message> {
+    let read = match source {
+        PasteSource::Clipboard => clipboard::read(),
+        PasteSource::Primary => clipboard::read_primary(),
+    };
+    read.map(move |value_opt| match value_opt {
+        Some(value) => message::app(Message::PasteValueTerminal(value)),
+        None => message::none(),
+    })
+}
+
+fn convert_location2_to_location1(location: &Location2) -> Location1 {
+    let loc;
+    match location {
+        Location2::Path(path) => loc = Location1::Path(path.to_owned()),
+        Location2::Trash => loc = Location1::Trash,
+        Location2::Network(s1, s2) => loc = Location1::Network(s1.clone(), s2.clone()),
+        Location2::Recents => loc = Location1::Recents,
+        Location2::Search(path, s, b, i) => {
+            loc = Location1::Search(path.to_owned(), s.clone(), b.to_owned(), i.to_owned())
+        }
+        Location2::Flatten(path, b, i) => {
+            loc = Location1::Flatten(path.to_owned(), b.to_owned(), i.to_owned())
+        }
+        Location2::Desktop(p, s, d) => {
+            loc = Location1::Desktop(p.to_owned(), s.to_owned(), d.to_owned())
+        }
+    }
+    loc
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum NavMenuAction {
     Open(segmented_button::Entity),
     OpenWith(segmented_button::Entity),
@@ -374,7 +1324,12 @@ pub enum NavMenuAction {
     OpenInNewWindow(segmented_button::Entity),
     Preview(segmented_button::Entity),
     RemoveFromSidebar(segmented_button::Entity),
+    MoveFavoriteUp(segmented_button::Entity),
+    MoveFavoriteDown(segmented_button::Entity),
+    HideFromSidebar(segmented_button::Entity),
     EmptyTrash,
+    FinalizeArchive,
+    DiscardArchive,
 }
 
 impl MenuAction for NavMenuAction {
@@ -389,20 +1344,42 @@ impl MenuAction for NavMenuAction {
 #[derive(Clone, Debug)]
 pub enum Message {
     AddToSidebar(Option<Entity>),
+    AllowOpenAsRoot(bool),
     AppTheme(AppTheme),
+    ArchiveOpenAction(ArchiveOpenAction),
+    AutoRefresh,
+    AutoRefreshInterval(u16),
+    CalculateFolderSizes(Option<Entity>),
+    CancelFolderSizes(Option<Entity>),
+    ChangeExtension(Option<Entity>),
+    ChangeExtensionInput(String),
+    ClearFolderViews,
+    ClearHistory,
     ClearScrollback(Option<segmented_button::Entity>),
     CloseToast(widget::ToastId),
     CloseToastLeft(widget::ToastId),
     CloseToastRight(widget::ToastId),
+    ColorByType(bool),
+    CommandPalette,
+    CommandPaletteRun(Action),
     Compress(Option<Entity>),
     Config(Config),
+    ConflictPolicy(ConflictPolicy),
+    ConfirmPermanentDelete(bool),
+    ConfirmTrash(bool),
     Copy(Option<Entity>),
+    CopyAs(Option<Entity>),
     CopyTerminal(Option<Entity>),
     CopyOrSigint(Option<segmented_button::Entity>),
     CopyPrimary(Option<segmented_button::Entity>),
     CopyTab(Option<segmented_button::Entity>),
+    CopyMoveTo(Option<Entity>, bool),
+    CopyMoveToFolders(PathBuf, Option<Vec<String>>),
+    CopyMoveToNavigate(PathBuf),
+    CopyMoveToNewFolder,
     CosmicSettings(&'static str),
     Cut(Option<Entity>),
+    CustomAction(Option<Entity>, usize),
     DesktopConfig(DesktopConfig),
     DesktopViewOptions,
     DialogCancel,
@@ -410,10 +1387,17 @@ pub enum Message {
     DialogPush(DialogPage),
     DialogUpdate(DialogPage),
     DialogUpdateComplete(DialogPage),
+    DiffResult(PathBuf, PathBuf, Result<Vec<DiffRow>, String>),
+    DiffSelected,
+    DiskSpaceLeft(Entity, Location1, Option<(u64, u64)>),
+    DiskSpaceRight(Entity, Location2, Option<(u64, u64)>),
+    DuplicateWindow,
     EditLocation(Option<Entity>),
     EmptyTrash(Option<Entity>),
+    EmptyTrashSummary(usize, u64),
     ExecEntryAction(Option<Entity>, usize),
     ExtractHere(Option<Entity>),
+    ExtractToOtherPanel(Option<Entity>),
     F2Rename,
     F3View,
     F4Edit,
@@ -423,6 +1407,10 @@ pub enum Message {
     F8Delete,
     F9Terminal,
     F10Quit,
+    FavoriteReorder(usize, usize),
+    FinalizeArchive,
+    FocusOtherPanel,
+    FollowSymlinks(bool),
     GalleryToggle(Option<Entity>),
     HistoryNext(Option<Entity>),
     HistoryPrevious(Option<Entity>),
@@ -433,35 +1421,55 @@ pub enum Message {
     LocationUp(Option<Entity>),
     Key(Modifiers, Key),
     LaunchUrl(String),
+    LayoutOverrides(LayoutOverrides),
     MaybeExit,
+    MiddleClickOpenFiles(bool),
+    MirrorSelection,
     Modifiers(Modifiers),
     Move(Point),
     MoveTab(Option<segmented_button::Entity>),
+    MoveTabToWindow(Option<Entity>),
     MoveToTrash(Option<Entity>),
     MounterItems(MounterKey, MounterItems),
     MountResult(MounterKey, MounterItem, Result<bool, String>),
+    UnmountResult(MounterKey, MounterItem, Result<bool, UnmountError>),
     NavBarClose(Entity),
     NavBarContext(Entity),
     NavMenuAction(NavMenuAction),
     NetworkAuth(MounterKey, String, MounterAuth, mpsc::Sender<MounterAuth>),
     NetworkDriveInput(String),
+    NetworkDriveReconnect(String),
+    NetworkDriveRemoveRecent(String),
     NetworkDriveSubmit,
     NetworkResult(MounterKey, String, Result<bool, String>),
+    NewArchive(Option<Entity>),
     NewItem(Option<Entity>, bool),
     #[cfg(feature = "notify")]
     Notification(Arc<Mutex<notify_rust::NotificationHandle>>),
     NotifyEvents(Vec<DebouncedEvent>),
+    NotifyOperations(NotifyOperations),
+    NotifySound(bool),
     NotifyWatcher(WatcherWrapper),
     NotifyWatcherLeft(WatcherWrapper),
     NotifyWatcherRight(WatcherWrapper),
     Open(Option<Entity>),
+    OpenAsRoot(Option<Entity>),
+    OpenAsRootError(PathBuf, String),
     OpenTerminal(Option<Entity>),
+    OpenScriptsInTerminal(bool),
+    OpenTerminalEmbedded(bool),
     OpenInNewTab(Option<Entity>),
     OpenInNewWindow(Option<Entity>),
     OpenItemLocation(Option<Entity>),
+    OpenLinkTarget(Option<Entity>),
     OpenWithBrowse,
+    OpenWithClearDefault,
     OpenWithDialog(Option<Entity>),
+    OpenWithAlways(bool),
     OpenWithSelection(usize),
+    OpenWithSetDefault,
+    PageDown(Option<Entity>),
+    PageUp(Option<Entity>),
     #[cfg(all(feature = "desktop", feature = "wayland"))]
     Overlap(OverlapNotifyEvent, window::Id),
     PaneUpdate,
@@ -480,6 +1488,8 @@ pub enum Message {
     PastePrimary(Option<segmented_button::Entity>),
     PasteTerminal(Option<Entity>),
     PastePrimaryTerminal(Option<segmented_button::Entity>),
+    PasteText(PathBuf),
+    PasteTextFile(PathBuf, String),
     PasteValueTerminal(String),
     PasteContents(PathBuf, ClipboardPaste),
     PendingCancel(u64),
@@ -489,33 +1499,58 @@ pub enum Message {
     PendingError(u64, String),
     PendingPause(u64, bool),
     PendingPauseAll(bool),
+    PendingPauseToggle,
+    PermanentlyDelete(Option<Entity>),
     Preview(Option<Entity>),
     QueueFileOperations(bool),
+    ReadOnly(bool),
+    ReadOnlyRefused(u64),
+    QuickPreview(Option<Entity>),
+    Redo,
     RescanTrash,
+    RetryAllFailed,
+    RetryFailed(widget::ToastId, u64),
     Rename(Option<Entity>),
     ReplaceResult(ReplaceResult),
+    ResetTerminal,
+    Reveal(PathBuf),
+    RespectGitignore(bool),
     RestoreFromTrash(Option<Entity>),
+    RestoreMissingTo,
+    RestoreTo(Option<Entity>),
     SearchActivate,
     SearchClear,
     SearchInput(String),
     SelectAll(Option<Entity>),
+    SelectByCriteria,
+    SelectByPattern(bool),
     SelectFirst(Option<Entity>),
     SelectLast(Option<Entity>),
+    SetModified(Option<Entity>),
     SetSort(Option<Entity>, HeadingOptions1, bool),
     SetSortRight(Option<Entity>, HeadingOptions2, bool),
+    SetSortSecondary(HeadingOptions1),
     SetShowDetails(bool),
+    SettingsFile(bool),
     ShowButtonRow(bool),
     ShowEmbeddedTerminal(bool),
+    ShowParentEntry(bool),
     ShowSecondPanel(bool),
+    SidebarShow(SidebarBuiltin, bool),
+    SidebarSystemMounts(bool),
+    SingleInstance(bool),
+    SlideshowInterval(u16),
     SystemThemeModeChange(cosmic_theme::ThemeMode),
     Size(Size),
     StoreOpenPaths,
+    StorePaneLayout,
     SwapPanels,
     TabActivate(Entity),
     TabActivateLeft,
     TabActivateRight,
     TabActivateLeftEntity(Entity),
     TabActivateRightEntity(Entity),
+    TabList(Option<PaneType>),
     TabNext,
     TabPrev,
     TabRescan,
@@ -532,17 +1567,24 @@ pub enum Message {
     TabRescanLeft(
         Entity,
         Location1,
+        u64,
         Option<tab1::Item>,
-        Vec<tab1::Item>,
+        Option<Vec<tab1::Item>>,
+        Option<String>,
         Option<Vec<PathBuf>>,
     ),
+    TabRescanBatchLeft(Entity, Location1, u64, Vec<tab1::Item>),
     TabRescanRight(
         Entity,
         Location2,
+        u64,
         Option<tab2::Item>,
-        Vec<tab2::Item>,
+        Option<Vec<tab2::Item>>,
+        Option<String>,
         Option<Vec<PathBuf>>,
     ),
+    TabRescanBatchRight(Entity, Location2, u64, Vec<tab2::Item>),
+    TabTitles(TabTitles),
     TabView(Option<Entity>, tab1::View),
     TermContextAction(Action),
     TermContextMenu(pane_grid::Pane, Option<Point>),
@@ -551,18 +1593,32 @@ pub enum Message {
     TermMiddleClick(pane_grid::Pane, Option<segmented_button::Entity>),
     TermMouseEnter(pane_grid::Pane),
     TermNew,
+    TerminalCloseOnExit(bool),
+    TerminalScrollback(usize),
+    TerminalCommand(String),
+    TerminalThemeDark(usize),
+    TerminalThemeLight(usize),
     ToggleContextPage(ContextPage),
+    ToggleFlattenView(Option<Entity>),
     ToggleFoldersFirst,
+    ToggleMirrorNavigation,
+    ToggleReadOnly,
+    ToggleRespectGitignore,
     ToggleShowHidden(Option<Entity>),
     ToggleSortLeft(Option<Entity>, HeadingOptions1),
     ToggleSortRight(Option<Entity>, HeadingOptions2),
-    Undo(usize),
+    TrashUnavailable(TrashUnavailable),
+    Undo,
     UndoTrash(widget::ToastId, Arc<[PathBuf]>),
     UndoTrashStart(Vec<TrashItem>),
+    UniqueNameBase(u32),
+    UniqueNameTemplate(String),
     WindowClose,
     WindowCloseRequested(window::Id),
+    WindowFocus,
     WindowNew,
     WindowUnfocus,
+    WrapNavigation(bool),
     ZoomDefault(Option<Entity>),
     ZoomIn(Option<Entity>),
     ZoomOut(Option<Entity>),
@@ -593,6 +1649,11 @@ pub enum Message {
     DndDropTabRight(Entity, Option<ClipboardPaste>, DndAction),
     DndDropNav(Entity, Option<ClipboardPaste>, DndAction),
     Recents,
+    RecentFileOpen(PathBuf),
+    RecentFileReveal(PathBuf),
+    RecentFilesClear,
+    RecentFilesMenu,
+    RemoveFromRecents(Option<Entity>),
     #[cfg(feature = "wayland")]
     OutputEvent(OutputEvent, WlOutput),
     Cosmic(app::cosmic::Message),
@@ -634,26 +1695,181 @@ impl AsRef<str> for ArchiveType {
     }
 }
 
+/// Archive created in `to` for each item compressed separately, named after the item. Files are
+/// skipped unless `include_files` is set.
+fn separate_archives(
+    paths: &[PathBuf],
+    to: &Path,
+    archive_type: ArchiveType,
+    include_files: bool,
+) -> Vec<(PathBuf, PathBuf)> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let is_dir = path.is_dir();
+            if !is_dir && !include_files {
+                return None;
+            }
+            // Folder names are kept whole, as dots in them rarely start an extension
+            let name = if is_dir {
+                path.file_name()
+            } else {
+                path.file_stem()
+            }?;
+            let name = format!("{}{}", name.to_string_lossy(), archive_type.extension());
+            Some((path.clone(), to.join(name)))
+        })
+        .collect()
+}
+
+/// Archive created once files are gathered for it, by dropping them onto its sidebar item or
+/// selecting the item to add the selected files
+#[derive(Clone, Debug)]
+pub struct StagedArchive {
+    to: PathBuf,
+    archive_type: ArchiveType,
+    password: Option<String>,
+    paths: Vec<PathBuf>,
+}
+
+impl StagedArchive {
+    /// Add the paths that are not staged yet, except for the archive itself
+    fn add(&mut self, paths: Vec<PathBuf>) {
+        for path in paths {
+            if path != self.to && !self.paths.contains(&path) {
+                self.paths.push(path);
+            }
+        }
+    }
+}
+
+/// Marks the sidebar item of the staged archive
+#[derive(Clone, Copy, Debug)]
+pub struct StagedArchiveItem;
+
+/// Shortest of the shortcuts bound to an action
+fn action_shortcut(key_binds: &HashMap<KeyBind, Action>, action: &Action) -> Option<String> {
+    key_binds
+        .iter()
+        .filter(|(_, bound)| *bound == action)
+        .map(|(key_bind, _)| key_bind.to_string())
+        .min_by_key(|shortcut| shortcut.len())
+}
+
+/// Actions of the buttons of the button row with their labels, which start with the shortcut
+/// bound to the action
+fn button_row(
+    buttons: &[ButtonRowAction],
+    key_binds: &HashMap<KeyBind, Action>,
+) -> Vec<(Action, String)> {
+    let defaults;
+    let buttons = if buttons.is_empty() {
+        defaults = ButtonRowAction::defaults();
+        &defaults
+    } else {
+        buttons
+    };
+    buttons
+        .iter()
+        .map(|button| {
+            let (action, name) = match button {
+                ButtonRowAction::Rename => (Action::F2Rename, fl!("button-rename")),
+                ButtonRowAction::View => (Action::F3View, fl!("button-view")),
+                ButtonRowAction::Edit => (Action::F4Edit, fl!("button-edit")),
+                ButtonRowAction::Copy => (Action::F5Copy, fl!("button-copy")),
+                ButtonRowAction::Move => (Action::F6Move, fl!("button-move")),
+                ButtonRowAction::NewFolder => (Action::F7Mkdir, fl!("button-mkdir")),
+                ButtonRowAction::Delete => (Action::F8Delete, fl!("button-delete")),
+                ButtonRowAction::Terminal => (Action::F9Terminal, fl!("button-terminal")),
+                ButtonRowAction::Quit => (Action::F10Quit, fl!("button-quit")),
+                ButtonRowAction::CompareFiles => (Action::DiffSelected, fl!("compare-files")),
+                ButtonRowAction::Compress => (Action::Compress, fl!("compress")),
+                ButtonRowAction::CopyTo => (Action::CopyTo, fl!("copy-to")),
+                ButtonRowAction::MoveTo => (Action::MoveTo, fl!("move-to")),
+                ButtonRowAction::NewFile => (Action::NewFile, fl!("new-file")),
+                ButtonRowAction::SelectAll => (Action::SelectAll, fl!("select-all")),
+                ButtonRowAction::ShowHidden => (Action::ToggleShowHidden, fl!("show-hidden-files")),
+                ButtonRowAction::SwapPanels => (Action::SwapPanels, fl!("swap-panels")),
+            };
+            let label = match action_shortcut(key_binds, &action) {
+                Some(shortcut) => format!("{} {}", shortcut, name),
+                None => name,
+            };
+            (action, label)
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug)]
 pub enum DialogPage {
+    ChangeExtension {
+        paths: Vec<PathBuf>,
+        extension: String,
+        /// Renames for the extension, or why it can't be used
+        renames: Result<Vec<(PathBuf, PathBuf)>, String>,
+    },
+    CommandPalette {
+        query: String,
+    },
     Compress {
         paths: Vec<PathBuf>,
         to: PathBuf,
         name: String,
         archive_type: ArchiveType,
         password: Option<String>,
+        /// Create an archive for each item instead of one for all
+        separate: bool,
+        /// Also create archives for files when creating one for each item
+        include_files: bool,
+    },
+    CopyAs {
+        from: PathBuf,
+        parent: PathBuf,
+        name: String,
+        dir: bool,
+    },
+    CopyMoveTo {
+        paths: Vec<PathBuf>,
+        to: PathBuf,
+        folders_opt: Option<Vec<String>>,
+        new_folder: String,
+        error_opt: Option<String>,
+        copy: bool,
+    },
+    Diff {
+        left: PathBuf,
+        right: PathBuf,
+        rows_res: Result<Vec<DiffRow>, String>,
+    },
+    EmptyTrash {
+        /// Number of items and their size, once calculated
+        summary_opt: Option<(usize, u64)>,
     },
-    EmptyTrash,
     FailedOperation(u64),
     ExtractPassword {
         id: u64,
         password: String,
     },
+    InsufficientSpace {
+        /// Bytes that have to be copied
+        required: u64,
+        /// Bytes available at the destination
+        free: u64,
+        tx: mpsc::Sender<bool>,
+    },
     MountError {
         mounter_key: MounterKey,
         item: MounterItem,
         error: String,
     },
+    UnmountError {
+        mounter_key: MounterKey,
+        item: MounterItem,
+        error: UnmountError,
+    },
+    MoveToTrash {
+        paths: Vec<PathBuf>,
+    },
     NetworkAuth {
         mounter_key: MounterKey,
         uri: String,
@@ -669,13 +1885,40 @@ pub enum DialogPage {
         parent: PathBuf,
         name: String,
         dir: bool,
+        /// Text written to the new file, when pasting text as a file
+        text_opt: Option<String>,
     },
-    OpenWith {
+    OpenFiles {
+        paths: Vec<PathBuf>,
+    },
+    OpenScript {
+        path: PathBuf,
+        run: bool,
+    },
+    OpenAsRoot {
         path: PathBuf,
+        /// Why the last attempt failed, asking to try again
+        error_opt: Option<String>,
+    },
+    OpenWith {
+        /// Files opened together, with the apps of the first one's mime type
+        paths: Vec<PathBuf>,
         mime: mime_guess::Mime,
         selected: usize,
+        /// Make the selected app the default for the mime type when opening
+        always: bool,
         store_opt: Option<mime_app::MimeApp>,
     },
+    PermanentlyDelete {
+        paths: Vec<PathBuf>,
+    },
+    QuickPreview {
+        pane: PaneType,
+        entity: Entity,
+    },
+    RecentFiles {
+        paths: Vec<PathBuf>,
+    },
     RenameItem {
         from: PathBuf,
         parent: PathBuf,
@@ -696,9 +1939,50 @@ pub enum DialogPage {
         apply_to_all: bool,
         tx: mpsc::Sender<ReplaceResult>,
     },
+    RestoreMissing {
+        items: Vec<TrashItem>,
+        missing: Vec<PathBuf>,
+    },
+    RestoreTo {
+        items: Vec<TrashItem>,
+        to: PathBuf,
+        folders_opt: Option<Vec<String>>,
+        new_folder: String,
+        error_opt: Option<String>,
+    },
+    SelectByCriteria(SelectCriteriaInput),
+    SelectPattern {
+        pattern: String,
+        select: bool,
+        case_sensitive: bool,
+    },
     SetExecutableAndLaunch {
         path: PathBuf,
     },
+    SetModified {
+        paths: Vec<PathBuf>,
+        now: bool,
+        time: String,
+        recursive: bool,
+    },
+    SettingsFile {
+        export: bool,
+        path: String,
+    },
+    SettingsReport {
+        title: String,
+        lines: Vec<String>,
+    },
+    TrashUnavailable {
+        paths: Vec<PathBuf>,
+        /// Items on filesystems without a trash folder
+        no_trash: Vec<PathBuf>,
+        choice: TrashUnavailable,
+    },
+    UndoPermanentDelete {
+        /// Entry of the undo history that was confirmed
+        history: OperationHistory,
+    },
 }
 
 pub struct FavoriteIndex(usize);
@@ -735,6 +2019,482 @@ impl PartialEq for WatcherWrapper {
     }
 }
 
+/// Sorted names of the visible folders in a directory, or None if it can't be read
+fn folder_names(path: &Path) -> Option<Vec<String>> {
+    let mut names: Vec<String> = fs::read_dir(path)
+        .ok()?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().into_string().ok()?;
+            (!name.starts_with('.') && entry.path().is_dir()).then_some(name)
+        })
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    Some(names)
+}
+
+/// List the folders of a destination dialog location without blocking the UI
+fn dialog_folders(path: PathBuf) -> Task<Message> {
+    Task::perform(
+        async move {
+            let path2 = path.clone();
+            match tokio::task::spawn_blocking(move || folder_names(&path2)).await {
+                Ok(folders_opt) => message::app(Message::CopyMoveToFolders(path, folders_opt)),
+                Err(err) => {
+                    log::warn!("failed to list folders of {:?}: {}", path, err);
+                    message::none()
+                }
+            }
+        },
+        |x| x,
+    )
+}
+
+/// Pick a folder to restore trash items to, starting at the closest existing folder to where the
+/// first item was trashed from, with the task listing its folders
+fn restore_to_dialog(items: Vec<TrashItem>) -> (DialogPage, Task<Message>) {
+    let to = items
+        .first()
+        .and_then(|item| {
+            item.original_parent
+                .ancestors()
+                .find(|path| path.is_dir())
+                .map(Path::to_path_buf)
+        })
+        .unwrap_or_else(home_dir);
+    let task = dialog_folders(to.clone());
+    let page = DialogPage::RestoreTo {
+        items,
+        to,
+        folders_opt: None,
+        new_folder: String::new(),
+        error_opt: None,
+    };
+    (page, task)
+}
+
+/// Apply the step from `from` to `to` to `other`, if the result is an existing folder other
+/// than `other` itself
+fn mirror_path(from: &Path, to: &Path, other: &Path) -> Option<PathBuf> {
+    let mirrored = if let Ok(relative) = to.strip_prefix(from) {
+        other.join(relative)
+    } else if let Ok(relative) = from.strip_prefix(to) {
+        let mut mirrored = other.to_path_buf();
+        for _ in relative.components() {
+            if !mirrored.pop() {
+                return None;
+            }
+        }
+        mirrored
+    } else {
+        return None;
+    };
+    (mirrored != other && mirrored.is_dir()).then_some(mirrored)
+}
+
+/// Scripts start with `#!`, or are executable text files
+fn script_file(path: &Path, mime: &mime_guess::Mime) -> bool {
+    use std::io::Read;
+
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if mime.type_() == "text" && metadata.permissions().mode() & 0o111 != 0 {
+            return true;
+        }
+    }
+    let mut magic = [0; 2];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok()
+        && magic == *b"#!"
+}
+
+/// Folders to open terminals in, selected files open in the folder containing them and each
+/// folder is only opened once. Without a selection the current folder is used.
+fn terminal_dirs(selected: Vec<PathBuf>, location: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for path in selected {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            match path.parent() {
+                Some(parent) => parent.to_path_buf(),
+                None => continue,
+            }
+        };
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    if dirs.is_empty() {
+        dirs.push(location.to_path_buf());
+    }
+    dirs
+}
+
+/// Shorten a path to at most `max` characters, keeping its start and the file name at the end
+fn elide_path(path: &Path, max: usize) -> String {
+    let text = path.display().to_string();
+    let count = text.chars().count();
+    if count <= max || max < 2 {
+        return text;
+    }
+    let end = (max - 1) * 2 / 3;
+    let start = max - 1 - end;
+    let mut elided: String = text.chars().take(start).collect();
+    elided.push('…');
+    elided.extend(text.chars().skip(count - end));
+    elided
+}
+
+/// Terminal options that run `args` in `dir` instead of a shell, and a description of the command
+fn terminal_command_options(
+    args: &[OsString],
+    dir: PathBuf,
+) -> Option<(alacritty_terminal::tty::Options, String)> {
+    let mut args = args.iter().map(|arg| arg.to_string_lossy().into_owned());
+    let program = args.next()?;
+    let args: Vec<String> = args.collect();
+    let description =
+        shlex::try_join(std::iter::once(program.as_str()).chain(args.iter().map(String::as_str)))
+            .unwrap_or_else(|_| program.clone());
+    let options = alacritty_terminal::tty::Options {
+        shell: Some(alacritty_terminal::tty::Shell::new(program, args)),
+        working_directory: Some(dir),
+        // The output stays visible after the command exits
+        hold: true,
+        ..Default::default()
+    };
+    Some((options, description))
+}
+
+// Variables of the session that pkexec clears, which graphical programs need to show windows
+const PKEXEC_ENV_VARS: [&str; 4] = [
+    "DISPLAY",
+    "WAYLAND_DISPLAY",
+    "XAUTHORITY",
+    "XDG_RUNTIME_DIR",
+];
+
+/// Arguments of pkexec to run a program as root, passing on the variables of the session
+fn pkexec_args(program: &[OsString], vars: &[(&str, OsString)]) -> Vec<OsString> {
+    let mut args = vec![OsString::from("env")];
+    for (name, value) in vars {
+        let mut arg = OsString::from(format!("{}=", name));
+        arg.push(value);
+        args.push(arg);
+    }
+    args.extend(program.iter().cloned());
+    args
+}
+
+/// Why running a program with pkexec failed, or `None` if it ran
+fn pkexec_error(res: io::Result<process::ExitStatus>) -> Option<String> {
+    match res {
+        Ok(status) => match status.code() {
+            // The authentication dialog was dismissed
+            Some(126) => Some(fl!("open-as-root-cancelled")),
+            // The user is not authorized, or the program could not be found
+            Some(127) => Some(fl!("open-as-root-denied")),
+            _ => None,
+        },
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Some(fl!("pkexec-missing")),
+        Err(err) => Some(err.to_string()),
+    }
+}
+
+/// Recently opened files that still exist, most recently used first
+fn recent_files() -> Vec<PathBuf> {
+    let recent_files = match recently_used_xbel::parse_file() {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!("failed to read recent files: {:?}", err);
+            return Vec::new();
+        }
+    };
+    let mut files: Vec<(chrono::DateTime<chrono::Utc>, PathBuf)> = recent_files
+        .bookmarks
+        .into_iter()
+        .filter_map(|bookmark| {
+            let path = url::Url::parse(&bookmark.href).ok()?.to_file_path().ok()?;
+            let used = [&bookmark.modified, &bookmark.visited]
+                .into_iter()
+                .filter_map(|time| time.parse().ok())
+                .max()?;
+            path.is_file().then_some((used, path))
+        })
+        .collect();
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files
+        .into_iter()
+        .map(|(_, path)| path)
+        .take(MAX_RECENT_FILES)
+        .collect()
+}
+
+/// Remove all entries from the recently used history, keeping it a valid document
+fn clear_recent_files() -> io::Result<()> {
+    let Some(path) = recents::path() else {
+        return Ok(());
+    };
+    fs::write(
+        path,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <xbel version=\"1.0\"\n      \
+         xmlns:bookmark=\"http://www.freedesktop.org/standards/desktop-bookmarks\"\n      \
+         xmlns:mime=\"http://www.freedesktop.org/standards/shared-mime-info\"\n>\n\
+         </xbel>\n",
+    )
+}
+
+/// Actions listed in the command palette, with their labels
+fn palette_actions() -> Vec<(Action, String)> {
+    vec![
+        (Action::About, fl!("menu-about")),
+        (Action::AddToSidebar, fl!("add-to-sidebar")),
+        (Action::CalculateFolderSizes, fl!("calculate-folder-sizes")),
+        (Action::CancelFolderSizes, fl!("cancel-folder-sizes")),
+        (Action::ChangeExtension, fl!("change-extension")),
+        (Action::ClearRecents, fl!("clear-recents")),
+        (Action::ClearScrollback, fl!("clear-scrollback")),
+        (Action::Compress, fl!("compress")),
+        (Action::Copy, fl!("copy")),
+        (Action::CopyAs, fl!("copy-as")),
+        (Action::CopyTab, fl!("copy-tab")),
+        (Action::CopyTo, fl!("copy-to")),
+        (Action::Cut, fl!("cut")),
+        (Action::DeselectByPattern, fl!("deselect-by-pattern")),
+        (Action::DiffSelected, fl!("compare-files")),
+        (Action::DuplicateWindow, fl!("duplicate-window")),
+        (Action::EditHistory, fl!("history")),
+        (Action::EmptyTrash, fl!("empty-trash")),
+        (Action::ExportSettings, fl!("export-settings")),
+        (Action::ExtractHere, fl!("extract-here")),
+        (Action::ExtractToOtherPanel, fl!("extract-to-other-panel")),
+        (Action::FinalizeArchive, fl!("finalize-archive")),
+        (Action::FocusOtherPanel, fl!("focus-other-panel")),
+        (Action::Gallery, fl!("gallery-preview")),
+        (Action::ImportSettings, fl!("import-settings")),
+        (Action::MoveTab, fl!("move-tab")),
+        (Action::MoveTabToWindow, fl!("move-tab-to-window")),
+        (Action::MoveTo, fl!("move-to")),
+        (Action::MoveToTrash, fl!("move-to-trash")),
+        (Action::NewArchive, fl!("new-archive")),
+        (Action::NewFile, fl!("new-file")),
+        (Action::NewFolder, fl!("new-folder")),
+        (Action::Open, fl!("open")),
+        (Action::OpenInNewTab, fl!("open-in-new-tab")),
+        (Action::OpenInNewWindow, fl!("open-in-new-window")),
+        (Action::OpenItemLocation, fl!("open-item-location")),
+        (Action::OpenLinkTarget, fl!("open-link-target")),
+        (Action::OpenTerminal, fl!("open-in-terminal")),
+        (Action::OpenWith, fl!("menu-open-with")),
+        (Action::MirrorSelection, fl!("mirror-selection")),
+        (Action::Paste, fl!("paste")),
+        (Action::PendingPauseToggle, fl!("pause-resume-all")),
+        (Action::PermanentlyDelete, fl!("delete-permanently")),
+        (Action::Preview, fl!("show-details")),
+        (Action::QuickPreview, fl!("quick-preview")),
+        (Action::RecentFilesMenu, fl!("recent-files")),
+        (Action::Recents, fl!("recents")),
+        (Action::Redo, fl!("redo")),
+        (Action::RemoveFromRecents, fl!("remove-from-recents")),
+        (Action::Rename, fl!("rename")),
+        (Action::ResetTerminal, fl!("reset-terminal")),
+        (Action::RestoreFromTrash, fl!("restore-from-trash")),
+        (Action::RestoreTo, fl!("restore-to")),
+        (Action::SelectAll, fl!("select-all")),
+        (Action::SelectByCriteria, fl!("select-by-criteria")),
+        (Action::SelectByPattern, fl!("select-by-pattern")),
+        (Action::SetModified, fl!("set-modified-time")),
+        (Action::Settings, fl!("menu-settings")),
+        (Action::SwapPanels, fl!("swap-panels")),
+        (Action::TabClose, fl!("close-tab")),
+        (Action::TabNew, fl!("new-tab")),
+        (Action::TabRescan, fl!("reload-folder")),
+        (Action::TabViewGrid, fl!("grid-view")),
+        (Action::TabViewList, fl!("list-view")),
+        (Action::ToggleFlattenView, fl!("flatten-view")),
+        (Action::ToggleFoldersFirst, fl!("list-directories-first")),
+        (Action::ToggleMirrorNavigation, fl!("mirror-navigation")),
+        (Action::ToggleReadOnly, fl!("read-only-mode")),
+        (Action::ToggleRespectGitignore, fl!("respect-gitignore")),
+        (Action::ToggleShowHidden, fl!("show-hidden-files")),
+        (Action::Undo, fl!("undo")),
+        (Action::WindowClose, fl!("quit")),
+        (Action::WindowNew, fl!("new-window")),
+        (Action::ZoomDefault, fl!("default-size")),
+        (Action::ZoomIn, fl!("zoom-in")),
+        (Action::ZoomOut, fl!("zoom-out")),
+    ]
+}
+
+/// How well a query matches a label as a case insensitive subsequence, lower is better and
+/// `None` if it does not match
+fn fuzzy_score(query: &str, label: &str) -> Option<usize> {
+    let mut label_chars = label.chars().flat_map(char::to_lowercase).enumerate();
+    let mut score = 0;
+    let mut next = 0;
+    for query_char in query.chars().flat_map(char::to_lowercase) {
+        let (i, _) = label_chars.find(|(_, label_char)| *label_char == query_char)?;
+        // Skipped characters make the match worse
+        score += i - next;
+        next = i + 1;
+    }
+    Some(score)
+}
+
+/// Commands matching the query, best matches first and recently used ones first among equal
+/// matches
+fn palette_matches<'a>(
+    actions: &'a [(Action, String)],
+    recent: &[Action],
+    query: &str,
+) -> Vec<&'a (Action, String)> {
+    let mut matches: Vec<_> = actions
+        .iter()
+        .filter_map(|entry| Some((fuzzy_score(query, &entry.1)?, entry)))
+        .collect();
+    matches.sort_by_key(|(score, (action, _))| {
+        let recent_i = recent.iter().position(|recent| recent == action);
+        (*score, recent_i.unwrap_or(usize::MAX))
+    });
+    matches.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Format of the timestamps entered when setting modification times
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Parse a local timestamp in [`TIME_FORMAT`], the seconds may be left out
+fn parse_local_time(text: &str) -> Option<SystemTime> {
+    let text = text.trim();
+    let naive = [TIME_FORMAT, "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(text, format).ok())?;
+    let local = naive.and_local_timezone(chrono::Local).earliest()?;
+    Some(local.into())
+}
+
+/// Parse a size like `500`, `1.5 MB` or `2 GiB`, units are decimal like the shown sizes unless
+/// they are binary
+fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let factor: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1000,
+        "kib" => 1 << 10,
+        "m" | "mb" => 1000 * 1000,
+        "mib" => 1 << 20,
+        "g" | "gb" => 1000 * 1000 * 1000,
+        "gib" => 1 << 30,
+        "t" | "tb" => 1000 * 1000 * 1000 * 1000,
+        "tib" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * factor as f64) as u64)
+}
+
+/// Parse a time bound of the criteria dialog, either a number of days before `now` or a local
+/// date, optionally with a time in [`TIME_FORMAT`]
+fn parse_time_bound(text: &str, now: SystemTime) -> Option<SystemTime> {
+    let text = text.trim();
+    if let Ok(days) = text.parse::<u64>() {
+        return now.checked_sub(time::Duration::from_secs(days * 24 * 60 * 60));
+    }
+    parse_local_time(text).or_else(|| {
+        let date = chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+        let local = date
+            .and_hms_opt(0, 0, 0)?
+            .and_local_timezone(chrono::Local)
+            .earliest()?;
+        Some(local.into())
+    })
+}
+
+/// Text entered in the fields of the select by criteria dialog
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SelectCriteriaInput {
+    pub pattern: String,
+    pub min_size: String,
+    pub max_size: String,
+    pub modified_after: String,
+    pub modified_before: String,
+}
+
+impl SelectCriteriaInput {
+    /// Criteria of the fields that are filled in, or `None` if one of them is not valid or none
+    /// are filled in
+    fn parse(&self, now: SystemTime) -> Option<SelectCriteria> {
+        fn field<T>(text: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Option<T>> {
+            if text.trim().is_empty() {
+                Some(None)
+            } else {
+                parse(text).map(Some)
+            }
+        }
+        let criteria = SelectCriteria {
+            pattern_opt: field(&self.pattern, |text| glob::Pattern::new(text.trim()).ok())?,
+            min_size_opt: field(&self.min_size, parse_size)?,
+            max_size_opt: field(&self.max_size, parse_size)?,
+            modified_after_opt: field(&self.modified_after, |text| parse_time_bound(text, now))?,
+            modified_before_opt: field(&self.modified_before, |text| parse_time_bound(text, now))?,
+        };
+        if criteria == SelectCriteria::default() {
+            None
+        } else {
+            Some(criteria)
+        }
+    }
+}
+
+/// Conditions of the select by criteria dialog, items have to meet all that are set
+#[derive(Debug, Default, PartialEq)]
+struct SelectCriteria {
+    /// Glob pattern the name matches, ignoring case
+    pattern_opt: Option<glob::Pattern>,
+    min_size_opt: Option<u64>,
+    max_size_opt: Option<u64>,
+    modified_after_opt: Option<SystemTime>,
+    modified_before_opt: Option<SystemTime>,
+}
+
+impl SelectCriteria {
+    /// Items without a known size or modification time do not match criteria using them
+    fn matches(&self, name: &str, size_opt: Option<u64>, modified_opt: Option<SystemTime>) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: false,
+            ..glob::MatchOptions::new()
+        };
+        self.pattern_opt
+            .as_ref()
+            .map_or(true, |pattern| pattern.matches_with(name, options))
+            && self
+                .min_size_opt
+                .map_or(true, |min| size_opt.is_some_and(|size| size >= min))
+            && self
+                .max_size_opt
+                .map_or(true, |max| size_opt.is_some_and(|size| size <= max))
+            && self
+                .modified_after_opt
+                .map_or(true, |after| modified_opt.is_some_and(|time| time >= after))
+            && self.modified_before_opt.map_or(true, |before| {
+                modified_opt.is_some_and(|time| time < before)
+            })
+    }
+}
+
 fn osstr_to_string(osstr: std::ffi::OsString) -> String {
     match osstr.to_str() {
         Some(str) => return str.to_string(),
@@ -754,15 +2514,35 @@ pub struct App {
     term_event_tx_opt:
         Option<mpsc::UnboundedSender<(pane_grid::Pane, Entity, alacritty_terminal::event::Event)>>,
     terminal: Option<Mutex<crate::terminal::Terminal>>,
+    /// Command the embedded terminal runs instead of a shell, with its exit code once it exited
+    terminal_command_opt: Option<(String, Option<i32>)>,
     active_panel: PaneType,
     //terminal: Terminal,
     show_button_row: bool,
     show_embedded_terminal: bool,
     show_second_panel: bool,
+    /// Navigating in the active panel steps the other panel into the same relative folder
+    mirror_navigation: bool,
     config_handler: Option<cosmic_config::Config>,
     config: Config,
+    layout_overrides: LayoutOverrides,
     mode: Mode,
     app_themes: Vec<String>,
+    archive_open_actions: Vec<String>,
+    auto_refresh_intervals: Vec<String>,
+    conflict_policies: Vec<String>,
+    /// Custom actions of the config that could be parsed
+    custom_actions: Vec<ParsedCustomAction>,
+    desktop_grid_flows: Vec<String>,
+    desktop_grid_origins: Vec<String>,
+    notify_operations: Vec<String>,
+    slideshow_intervals: Vec<String>,
+    tab_titles: Vec<String>,
+    terminal_scrollback_choices: Vec<String>,
+    trash_unavailable_choices: Vec<String>,
+    unique_name_bases: Vec<String>,
+    /// Commands run from the command palette, most recent first
+    palette_recent: Vec<Action>,
     themes: HashMap<(String, ColorSchemeKind), TermColors>,
     theme_names_dark: Vec<String>,
     theme_names_light: Vec<String>,
@@ -775,6 +2555,8 @@ pub struct App {
     mime_app_cache: mime_app::MimeAppCache,
     modifiers: Modifiers,
     mounter_items: HashMap<MounterKey, MounterItems>,
+    /// Names of items that are being mounted or unmounted
+    mounter_pending: HashSet<(MounterKey, String)>,
     network_drive_connecting: Option<(MounterKey, String)>,
     network_drive_input: String,
     #[cfg(feature = "notify")]
@@ -784,10 +2566,21 @@ pub struct App {
     pending_operations: BTreeMap<u64, (Operation, Controller)>,
     _fileops: BTreeMap<u64, (Operation, Controller)>,
     progress_operations: BTreeSet<u64>,
-    complete_operations: BTreeMap<u64, Operation>,
+    /// Completed operations with the sources that changed while they were copied
+    complete_operations: BTreeMap<u64, (Operation, Vec<PathBuf>)>,
     failed_operations: BTreeMap<u64, (Operation, Controller, String)>,
+    undo_history: Vec<OperationHistory>,
+    redo_history: Vec<OperationHistory>,
+    undo_operation_ids: BTreeSet<u64>,
+    redo_operation_ids: BTreeSet<u64>,
+    retry_operation_ids: BTreeMap<u64, u64>,
+    /// Folders created from the destination dialog, which it opens once they exist
+    dialog_new_folder_ids: BTreeMap<u64, PathBuf>,
+    /// Operations refused in read-only mode, which are yet to be reported
+    read_only_refused: BTreeSet<u64>,
     search_id: widget::Id,
     size: Option<Size>,
+    staged_archive_opt: Option<StagedArchive>,
     #[cfg(feature = "wayland")]
     surface_ids: HashMap<WlOutput, WindowId>,
     #[cfg(feature = "wayland")]
@@ -806,6 +2599,8 @@ pub struct App {
     tab_dnd_hover_right: Option<(Entity, Instant)>,
     panegrid_drag_id: DragId,
     term_drag_id: DragId,
+    /// Panel whose list of all open tabs is shown
+    tab_list_opt: Option<PaneType>,
     nav_drag_id: DragId,
     tab_drag_id_left: DragId,
     tab_drag_id_right: DragId,
@@ -816,8 +2611,40 @@ pub struct App {
 }
 
 impl App {
+    fn open_files(&mut self, paths: Vec<PathBuf>) {
+        if paths.len() > OPEN_FILES_CONFIRM {
+            self.dialog_pages.push_back(DialogPage::OpenFiles { paths });
+        } else {
+            for path in paths.iter() {
+                self.open_file(path);
+            }
+        }
+    }
+
     fn open_file(&mut self, path: &PathBuf) {
         let mime = mime_icon::mime_for_path(path);
+        // Archives can be extracted instead of opened with an application
+        if EXTRACT_MIME_TYPES.contains(&mime.essence_str()) {
+            match self.config.archive_open_action {
+                ArchiveOpenAction::Open => {}
+                ArchiveOpenAction::ExtractHere => {
+                    self.extract(vec![path.clone()], false);
+                    return;
+                }
+                ArchiveOpenAction::ExtractToOtherPanel => {
+                    self.extract(vec![path.clone()], true);
+                    return;
+                }
+            }
+        }
+        // Scripts could be meant to be run or edited, so let the user choose
+        if self.config.open_scripts_in_terminal && script_file(path, &mime) {
+            self.dialog_pages.push_back(DialogPage::OpenScript {
+                path: path.clone(),
+                run: true,
+            });
+            return;
+        }
         if mime == "application/x-desktop" {
             // Try opening desktop application
             match freedesktop_entry_parser::parse_entry(path) {
@@ -864,8 +2691,13 @@ impl App {
             return;
         }
 
+        self.open_file_with_apps(path, &mime);
+    }
+
+    /// Open a file with the applications for its mime type, falling back to the system default
+    fn open_file_with_apps(&mut self, path: &PathBuf, mime: &mime_guess::Mime) {
         // Try mime apps, which should be faster than xdg-open
-        for app in self.mime_app_cache.get(&mime) {
+        for app in self.mime_app_cache.get(mime) {
             let Some(mut command) = app.command(Some(path.clone().into())) else {
                 continue;
             };
@@ -901,36 +2733,128 @@ impl App {
         }
     }
 
-    #[cfg(feature = "desktop")]
-    fn exec_entry_action(entry: cosmic::desktop::DesktopEntryData, action: usize) {
-        if let Some(action) = entry.desktop_actions.get(action) {
-            // Largely copied from COSMIC app library
-            let mut exec = shlex::Shlex::new(&action.exec);
-            match exec.next() {
-                Some(cmd) if !cmd.contains('=') => {
-                    let mut proc = tokio::process::Command::new(cmd);
-                    for arg in exec {
-                        if !arg.starts_with('%') {
-                            proc.arg(arg);
-                        }
-                    }
-                    let _ = proc.spawn();
-                }
-                _ => (),
+    /// Extract archives into new folders next to them, or in the folder of the other panel
+    fn extract(&mut self, paths: Vec<PathBuf>, to_other_panel: bool) {
+        let to_opt = if to_other_panel {
+            match self.active_panel {
+                PaneType::LeftPane => active_tab_path::<RightPanel>(&self.tab_model2),
+                _ => active_tab_path::<LeftPanel>(&self.tab_model1),
             }
         } else {
-            log::warn!(
-                "Invalid actions index `{action}` for desktop entry {}",
-                entry.name
-            );
+            paths
+                .first()
+                .and_then(|first| first.parent())
+                .map(|parent| parent.to_path_buf())
+        };
+        if let Some(to) = to_opt {
+            self.operation(Operation::Extract {
+                paths,
+                to,
+                password: None,
+            });
         }
     }
 
-    fn handle_overlap(&mut self) {
-        let Some((bl, br, tl, tr, mut size)) = self.size.as_ref().map(|s| {
-            (
-                Rectangle::new(
-                    Point::new(0., s.height / 2.),
+    /// Command line opening an item as root: the editor of a file, or a new window for a folder
+    fn open_as_root_program(&self, path: &Path) -> Option<Vec<OsString>> {
+        if path.is_dir() {
+            return match env::current_exe() {
+                Ok(exe) => Some(vec![
+                    exe.into_os_string(),
+                    "--new-window".into(),
+                    path.as_os_str().to_os_string(),
+                ]),
+                Err(err) => {
+                    log::error!("failed to get current executable path: {}", err);
+                    None
+                }
+            };
+        }
+        let mime = mime_icon::mime_for_path(path);
+        self.mime_app_cache.get(&mime).iter().find_map(|app| {
+            let command = app.command(Some(path.as_os_str().to_os_string()))?;
+            Some(
+                std::iter::once(command.get_program().to_os_string())
+                    .chain(command.get_args().map(|arg| arg.to_os_string()))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Run the program opening an item with pkexec, showing why if it fails
+    fn open_as_root(&mut self, path: PathBuf) -> Task<Message> {
+        let Some(program) = self.open_as_root_program(&path) else {
+            self.dialog_pages.push_back(DialogPage::OpenAsRoot {
+                path,
+                error_opt: Some(fl!("open-as-root-no-app")),
+            });
+            return Task::none();
+        };
+        let vars: Vec<(&str, OsString)> = PKEXEC_ENV_VARS
+            .iter()
+            .filter_map(|name| Some((*name, env::var_os(name)?)))
+            .collect();
+        let args = pkexec_args(&program, &vars);
+        Task::perform(
+            async move {
+                // pkexec waits for the program, so its exit status tells if authentication failed
+                let res = tokio::task::spawn_blocking(move || {
+                    process::Command::new("pkexec")
+                        .args(args)
+                        .stdin(process::Stdio::null())
+                        .stdout(process::Stdio::null())
+                        .stderr(process::Stdio::null())
+                        .status()
+                })
+                .await;
+                match res {
+                    Ok(status_res) => match pkexec_error(status_res) {
+                        Some(error) => message::app(Message::OpenAsRootError(path, error)),
+                        None => message::none(),
+                    },
+                    Err(err) => {
+                        log::warn!("failed to run pkexec: {}", err);
+                        message::none()
+                    }
+                }
+            },
+            |x| x,
+        )
+    }
+
+    #[cfg(feature = "desktop")]
+    fn exec_entry_action(entry: cosmic::desktop::DesktopEntryData, action: usize) {
+        if let Some(action) = entry.desktop_actions.get(action) {
+            // Largely copied from COSMIC app library
+            let mut exec = shlex::Shlex::new(&action.exec);
+            match exec.next() {
+                Some(cmd) if !cmd.contains('=') => {
+                    let mut proc = tokio::process::Command::new(cmd);
+                    for arg in exec {
+                        if !arg.starts_with('%') {
+                            proc.arg(arg);
+                        }
+                    }
+                    let _ = proc.spawn();
+                }
+                _ => (),
+            }
+        } else {
+            log::warn!(
+                "Invalid actions index `{action}` for desktop entry {}",
+                entry.name
+            );
+        }
+    }
+
+    /// Margins keeping docks and panels from covering desktop windows. The margins only apply
+    /// to desktop windows, which show a single tab filling the window without the pane grid, so
+    /// the quadrants of the window are the quadrants of that tab.
+    fn handle_overlap(&mut self) {
+        let Some((bl, br, tl, tr, mut size)) = self.size.as_ref().map(|s| {
+            (
+                Rectangle::new(
+                    Point::new(0., s.height / 2.),
                     Size::new(s.width / 2., s.height / 2.),
                 ),
                 Rectangle::new(
@@ -1032,6 +2956,9 @@ impl App {
                 tab1::Mode::Desktop
             }
         };
+        tab.folder_views = Arc::new(self.config.folder_views_left.clone());
+        tab.operations = self.tab_operations();
+        tab.apply_folder_view();
         let entity;
         entity = self
             .tab_model1
@@ -1072,6 +2999,9 @@ impl App {
                 tab2::Mode::Desktop
             }
         };
+        tab.folder_views = Arc::new(self.config.folder_views_right.clone());
+        tab.operations = self.tab_operations();
+        tab.apply_folder_view();
         let entity;
         entity = self
             .tab_model2
@@ -1117,6 +3047,38 @@ impl App {
             .1
     }
 
+    /// Open the location of the active tab in a new tab of the other panel without moving
+    /// the focus, returns the source tab
+    fn copy_tab_to_other_panel(&mut self) -> (Option<Entity>, Task<Message>) {
+        if self.active_panel == PaneType::LeftPane {
+            let entity = self.tab_model1.active();
+            let Some(location) = self
+                .tab_model1
+                .data::<Tab1>(entity)
+                .map(|tab| convert_location1_to_location2(&tab.location))
+            else {
+                return (None, Task::none());
+            };
+            let _ = self.update(Message::StoreOpenPaths);
+            // rescans the new tab of the right panel, not the source tab
+            let (_new_entity, task) = self.open_tab_entity_right(location, true, None);
+            (Some(entity), task)
+        } else {
+            let entity = self.tab_model2.active();
+            let Some(location) = self
+                .tab_model2
+                .data::<Tab2>(entity)
+                .map(|tab| convert_location2_to_location1(&tab.location))
+            else {
+                return (None, Task::none());
+            };
+            let _ = self.update(Message::StoreOpenPaths);
+            // rescans the new tab of the left panel, not the source tab
+            let (_new_entity, task) = self.open_tab_entity_left(location, true, None);
+            (Some(entity), task)
+        }
+    }
+
     fn activate_left_pane(&mut self) {
         self.active_panel = PaneType::LeftPane;
     }
@@ -1126,6 +3088,11 @@ impl App {
     }
 
     fn operation(&mut self, operation: Operation) {
+        if self.config.read_only {
+            log::info!("read-only mode refused {:?}", operation);
+            self.refuse_read_only();
+            return;
+        }
         let id = self.pending_operation_id;
         self.pending_operation_id += 1;
         if operation.show_progress_notification() {
@@ -1148,6 +3115,84 @@ impl App {
         self.pending_operations
             .insert(id, (operation, Controller::default()));
         //}
+        self.update_tab_operations();
+    }
+
+    /// Report an operation refused in read-only mode
+    fn refuse_read_only(&mut self) {
+        let id = self.pending_operation_id;
+        self.pending_operation_id += 1;
+        self.undo_operation_ids.remove(&id);
+        self.redo_operation_ids.remove(&id);
+        self.retry_operation_ids.remove(&id);
+        self.dialog_new_folder_ids.remove(&id);
+        self.read_only_refused.insert(id);
+    }
+
+    fn undo(&mut self) {
+        match self.undo_history.len().checked_sub(1) {
+            Some(index) => self.undo_entry(index),
+            None if self.config.read_only => self.refuse_read_only(),
+            None => {}
+        }
+    }
+
+    /// Revert the entry at `index` of the undo history, which can be redone afterwards
+    fn undo_entry(&mut self, index: usize) {
+        // The history is kept, as its operations would be refused
+        if self.config.read_only {
+            self.refuse_read_only();
+            return;
+        }
+        let history = self.undo_history.remove(index);
+        for op in history.undo.iter().cloned() {
+            // Reverting operations are not recorded in the history
+            self.undo_operation_ids.insert(self.pending_operation_id);
+            self.operation(op);
+        }
+        self.redo_history.push(history);
+    }
+
+    fn redo(&mut self) {
+        if self.config.read_only {
+            self.refuse_read_only();
+            return;
+        }
+        let Some(history) = self.redo_history.pop() else {
+            return;
+        };
+        // Redone operations are recorded again without clearing the redo stack
+        self.redo_operation_ids.insert(self.pending_operation_id);
+        self.operation(history.redo);
+    }
+
+    fn retry_operation(&mut self, failed_id: u64) {
+        // A failure is only retried once at a time
+        if self.retry_operation_ids.values().any(|id| *id == failed_id) {
+            return;
+        }
+        let Some((op, _, _)) = self.failed_operations.get(&failed_id) else {
+            return;
+        };
+        // The failed entry is cleared once the retry completes
+        let op = op.clone();
+        self.retry_operation_ids
+            .insert(self.pending_operation_id, failed_id);
+        self.remove_failed_dialog(failed_id);
+        self.operation(op);
+    }
+
+    /// Forget a failed operation together with its queued dialog
+    fn remove_failed_operation(&mut self, failed_id: u64) {
+        self.failed_operations.remove(&failed_id);
+        self.remove_failed_dialog(failed_id);
+    }
+
+    /// Drop the queued dialog of a failed operation, which would otherwise stay queued without
+    /// anything to show and keep later dialogs from opening
+    fn remove_failed_dialog(&mut self, failed_id: u64) {
+        self.dialog_pages
+            .retain(|page| !matches!(page, DialogPage::FailedOperation(id) if *id == failed_id));
     }
 
     fn remove_window(&mut self, id: &window::Id) {
@@ -1161,7 +3206,13 @@ impl App {
         }
     }
 
-    fn rescan_operation_selection(&mut self, op_sel: OperationSelection) -> Task<Message> {
+    /// Rescan the active tab and select the items of a completed operation, unless other items
+    /// were selected in the meantime or `replace_selection` is set
+    fn rescan_operation_selection(
+        &mut self,
+        op_sel: OperationSelection,
+        replace_selection: bool,
+    ) -> Task<Message> {
         log::info!("rescan_operation_selection {:?}", op_sel);
         if self.active_panel == PaneType::LeftPane {
             let entity = self.tab_model1.active();
@@ -1170,7 +3221,7 @@ impl App {
                     return Task::none();
                 };
                 for item in items.iter() {
-                    if item.selected {
+                    if item.selected && !replace_selection {
                         if let Some(path) = item.path_opt() {
                             if op_sel.selected.contains(path) || op_sel.ignored.contains(path) {
                                 // Ignore if path in selected or ignored paths
@@ -1193,7 +3244,7 @@ impl App {
                     return Task::none();
                 };
                 for item in items.iter() {
-                    if item.selected {
+                    if item.selected && !replace_selection {
                         if let Some(path) = item.path_opt() {
                             if op_sel.selected.contains(path) || op_sel.ignored.contains(path) {
                                 // Ignore if path in selected or ignored paths
@@ -1212,6 +3263,51 @@ impl App {
         }
     }
 
+    /// Navigate the active tab of the active panel to the folder of a path and select it
+    pub fn reveal(&mut self, path: PathBuf) -> Task<Message> {
+        let Some(parent) = path.parent().map(Path::to_path_buf) else {
+            return Task::none();
+        };
+        let selection_paths = Some(vec![path]);
+        if self.active_panel == PaneType::LeftPane {
+            let entity = self.tab_model1.active();
+            let location = Location1::Path(parent);
+            let title_opt = self.tab_model1.data_mut::<Tab1>(entity).map(|tab| {
+                tab.change_location(&location, None);
+                tab.title()
+            });
+            match title_opt {
+                Some(title) => {
+                    self.tab_model1.text_set(entity, title);
+                    Task::batch([
+                        self.update_title(),
+                        self.update_watcher_left(),
+                        self.update_tab_left(entity, location, selection_paths),
+                    ])
+                }
+                None => self.open_tab(location, true, selection_paths),
+            }
+        } else {
+            let entity = self.tab_model2.active();
+            let location = Location2::Path(parent);
+            let title_opt = self.tab_model2.data_mut::<Tab2>(entity).map(|tab| {
+                tab.change_location(&location, None);
+                tab.title()
+            });
+            match title_opt {
+                Some(title) => {
+                    self.tab_model2.text_set(entity, title);
+                    Task::batch([
+                        self.update_title(),
+                        self.update_watcher_right(),
+                        self.update_tab_right(entity, location, selection_paths),
+                    ])
+                }
+                None => self.open_tab_right(location, true, selection_paths),
+            }
+        }
+    }
+
     fn update_tab_left(
         &mut self,
         entity: Entity,
@@ -1219,7 +3315,7 @@ impl App {
         selection_paths: Option<Vec<PathBuf>>,
     ) -> Task<Message> {
         if let Location1::Search(_, term, ..) = location {
-            self.search_set(entity, Some(term), selection_paths)
+            self.search_set_in::<LeftPanel>(entity, Some(term), selection_paths)
         } else {
             self.rescan_tab_left(entity, location, selection_paths)
         }
@@ -1232,7 +3328,7 @@ impl App {
         selection_paths: Option<Vec<PathBuf>>,
     ) -> Task<Message> {
         if let Location2::Search(_, term, ..) = location {
-            self.search_set(entity, Some(term), selection_paths)
+            self.search_set_in::<RightPanel>(entity, Some(term), selection_paths)
         } else {
             self.rescan_tab_right(entity, location, selection_paths)
         }
@@ -1247,17 +3343,89 @@ impl App {
         log::info!("rescan_tab {entity:?} {location:?} {selection_paths:?}");
         let icon_sizes;
         icon_sizes = self.config.tab_left.icon_sizes;
+        let recents_excluded = self.config.recents_excluded.clone();
+        let Some(tab) = self.tab_model1.data::<Tab1>(entity) else {
+            return Task::none();
+        };
+        // Results of scans still running for the tab are dropped once this one starts
+        let epoch = tab.scan_begin();
+        // Folders that are opened are shown in batches while scanning, so large ones stay
+        // responsive
+        if let Location1::Path(path) = &location {
+            if tab.items_opt().is_none() {
+                let path = path.clone();
+                let scan_epoch = tab.scan_epoch();
+                return Task::run(
+                    stream::channel(2, move |mut output| async move {
+                        let location2 = location.clone();
+                        let res = tokio::task::spawn_blocking(move || {
+                            let scan_res = tab1::scan_path_batched(&path, icon_sizes, |items| {
+                                // Stop scanning if the tab has moved on
+                                if scan_epoch.load(atomic::Ordering::SeqCst) != epoch {
+                                    return false;
+                                }
+                                futures::executor::block_on(output.send(
+                                    Message::TabRescanBatchLeft(
+                                        entity,
+                                        location2.clone(),
+                                        epoch,
+                                        items,
+                                    ),
+                                ))
+                                .is_ok()
+                            });
+                            (output, location2.parent_item(icon_sizes), scan_res.err())
+                        })
+                        .await;
+                        match res {
+                            Ok((mut output, parent_item_opt, scan_error_opt)) => {
+                                let _ = output
+                                    .send(Message::TabRescanLeft(
+                                        entity,
+                                        location,
+                                        epoch,
+                                        parent_item_opt,
+                                        None,
+                                        scan_error_opt,
+                                        selection_paths,
+                                    ))
+                                    .await;
+                            }
+                            Err(err) => {
+                                log::warn!("failed to rescan: {}", err);
+                            }
+                        }
+                    }),
+                    message::app,
+                );
+            }
+        }
         Task::perform(
             async move {
                 let location2 = location.clone();
-                match tokio::task::spawn_blocking(move || location2.scan(icon_sizes)).await {
-                    Ok((parent_item_opt, items)) => message::app(Message::TabRescanLeft(
-                        entity,
-                        location,
-                        parent_item_opt,
-                        items,
-                        selection_paths,
-                    )),
+                let scan = move || match &location2 {
+                    Location1::Recents => (
+                        location2.parent_item(icon_sizes),
+                        Ok(tab1::scan_recents(icon_sizes, &recents_excluded)),
+                    ),
+                    _ => location2.scan_checked(icon_sizes),
+                };
+                match tokio::task::spawn_blocking(scan).await {
+                    Ok((parent_item_opt, items_res)) => {
+                        let (items, scan_error_opt) = match items_res {
+                            Ok(items) => (items, None),
+                            Err(err) => (Vec::new(), Some(err)),
+                        };
+                        message::app(Message::TabRescanLeft(
+                            entity,
+                            location,
+                            epoch,
+                            parent_item_opt,
+                            Some(items),
+                            scan_error_opt,
+                            selection_paths,
+                        ))
+                    }
                     Err(err) => {
                         log::warn!("failed to rescan: {}", err);
                         message::none()
@@ -1277,17 +3445,89 @@ impl App {
         log::info!("rescan_tab {entity:?} {location:?} {selection_paths:?}");
         let icon_sizes;
         icon_sizes = self.config.tab_right.icon_sizes;
+        let recents_excluded = self.config.recents_excluded.clone();
+        let Some(tab) = self.tab_model2.data::<Tab2>(entity) else {
+            return Task::none();
+        };
+        // Results of scans still running for the tab are dropped once this one starts
+        let epoch = tab.scan_begin();
+        // Folders that are opened are shown in batches while scanning, so large ones stay
+        // responsive
+        if let Location2::Path(path) = &location {
+            if tab.items_opt().is_none() {
+                let path = path.clone();
+                let scan_epoch = tab.scan_epoch();
+                return Task::run(
+                    stream::channel(2, move |mut output| async move {
+                        let location2 = location.clone();
+                        let res = tokio::task::spawn_blocking(move || {
+                            let scan_res = tab2::scan_path_batched(&path, icon_sizes, |items| {
+                                // Stop scanning if the tab has moved on
+                                if scan_epoch.load(atomic::Ordering::SeqCst) != epoch {
+                                    return false;
+                                }
+                                futures::executor::block_on(output.send(
+                                    Message::TabRescanBatchRight(
+                                        entity,
+                                        location2.clone(),
+                                        epoch,
+                                        items,
+                                    ),
+                                ))
+                                .is_ok()
+                            });
+                            (output, location2.parent_item(icon_sizes), scan_res.err())
+                        })
+                        .await;
+                        match res {
+                            Ok((mut output, parent_item_opt, scan_error_opt)) => {
+                                let _ = output
+                                    .send(Message::TabRescanRight(
+                                        entity,
+                                        location,
+                                        epoch,
+                                        parent_item_opt,
+                                        None,
+                                        scan_error_opt,
+                                        selection_paths,
+                                    ))
+                                    .await;
+                            }
+                            Err(err) => {
+                                log::warn!("failed to rescan: {}", err);
+                            }
+                        }
+                    }),
+                    message::app,
+                );
+            }
+        }
         Task::perform(
             async move {
                 let location2 = location.clone();
-                match tokio::task::spawn_blocking(move || location2.scan(icon_sizes)).await {
-                    Ok((parent_item_opt, items)) => message::app(Message::TabRescanRight(
-                        entity,
-                        location,
-                        parent_item_opt,
-                        items,
-                        selection_paths,
-                    )),
+                let scan = move || match &location2 {
+                    Location2::Recents => (
+                        location2.parent_item(icon_sizes),
+                        Ok(tab2::scan_recents(icon_sizes, &recents_excluded)),
+                    ),
+                    _ => location2.scan_checked(icon_sizes),
+                };
+                match tokio::task::spawn_blocking(scan).await {
+                    Ok((parent_item_opt, items_res)) => {
+                        let (items, scan_error_opt) = match items_res {
+                            Ok(items) => (items, None),
+                            Err(err) => (Vec::new(), Some(err)),
+                        };
+                        message::app(Message::TabRescanRight(
+                            entity,
+                            location,
+                            epoch,
+                            parent_item_opt,
+                            Some(items),
+                            scan_error_opt,
+                            selection_paths,
+                        ))
+                    }
                     Err(err) => {
                         log::warn!("failed to rescan: {}", err);
                         message::none()
@@ -1298,201 +3538,329 @@ impl App {
         )
     }
 
-    fn rescan_trash(&mut self) -> Task<Message> {
-        if self.active_panel == PaneType::LeftPane {
-            let mut needs_reload = Vec::new();
-            let entities: Vec<_> = self.tab_model1.iter().collect();
-            for entity in entities {
-                if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                    {
-                        if let Location1::Trash = &tab.location {
-                            needs_reload.push((entity, Location1::Trash));
-                        }
+    /// Count the items in the trash and their size for the empty trash dialog
+    fn empty_trash_summary(&self) -> Task<Message> {
+        Task::perform(
+            async move {
+                match tokio::task::spawn_blocking(crate::operation::trash_summary).await {
+                    Ok(Ok((items, size))) => message::app(Message::EmptyTrashSummary(items, size)),
+                    Ok(Err(err)) => {
+                        log::warn!("failed to summarize trash: {}", err);
+                        message::none()
                     }
-                }
-            }
-            let mut commands = Vec::with_capacity(needs_reload.len());
-            for (entity, location) in needs_reload {
-                commands.push(self.update_tab_left(entity, location, None));
-            }
-            Task::batch(commands)
-        } else {
-            let mut needs_reload = Vec::new();
-            let entities: Vec<_> = self.tab_model2.iter().collect();
-            for entity in entities {
-                if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                    {
-                        if let Location2::Trash = &tab.location {
-                            needs_reload.push((entity, Location2::Trash));
-                        }
+                    Err(err) => {
+                        log::warn!("failed to summarize trash: {}", err);
+                        message::none()
                     }
                 }
-            }
-            let mut commands = Vec::with_capacity(needs_reload.len());
-            for (entity, location) in needs_reload {
-                commands.push(self.update_tab_right(entity, location, None));
-            }
-            Task::batch(commands)
-        }
+            },
+            |x| x,
+        )
     }
 
-    fn search_get(&self) -> Option<&str> {
-        if self.active_panel == PaneType::LeftPane {
-            let entity = self.tab_model1.active();
-            if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
-                match &tab.location {
-                    Location1::Search(_, term, ..) => Some(term),
-                    _ => None,
+    /// Look up the free space of the filesystem of a tab in the left panel
+    fn update_disk_space_left(&mut self, entity: Entity) -> Task<Message> {
+        let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) else {
+            return Task::none();
+        };
+        let location = tab.location.clone();
+        let Some(path) = location.path_opt().cloned() else {
+            // Network and virtual locations have no filesystem to ask
+            tab.disk_space_opt = None;
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                match tokio::task::spawn_blocking(move || crate::operation::disk_space(&path)).await
+                {
+                    Ok(disk_space_opt) => {
+                        message::app(Message::DiskSpaceLeft(entity, location, disk_space_opt))
+                    }
+                    Err(err) => {
+                        log::warn!("failed to get free space: {}", err);
+                        message::none()
+                    }
                 }
-            } else {
-                None
-            }
-        } else {
-            let entity = self.tab_model2.active();
-            if let Some(tab) = self.tab_model2.data::<Tab2>(entity) {
-                match &tab.location {
-                    Location2::Search(_, term, ..) => Some(term),
-                    _ => None,
+            },
+            |x| x,
+        )
+    }
+
+    /// Look up the free space of the filesystem of a tab in the right panel
+    fn update_disk_space_right(&mut self, entity: Entity) -> Task<Message> {
+        let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) else {
+            return Task::none();
+        };
+        let location = tab.location.clone();
+        let Some(path) = location.path_opt().cloned() else {
+            // Network and virtual locations have no filesystem to ask
+            tab.disk_space_opt = None;
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                match tokio::task::spawn_blocking(move || crate::operation::disk_space(&path)).await
+                {
+                    Ok(disk_space_opt) => {
+                        message::app(Message::DiskSpaceRight(entity, location, disk_space_opt))
+                    }
+                    Err(err) => {
+                        log::warn!("failed to get free space: {}", err);
+                        message::none()
+                    }
                 }
-            } else {
-                None
-            }
+            },
+            |x| x,
+        )
+    }
+
+    fn rescan_trash(&mut self) -> Task<Message> {
+        match self.active_panel {
+            PaneType::LeftPane => self.rescan_trash_in::<LeftPanel>(),
+            _ => self.rescan_trash_in::<RightPanel>(),
         }
     }
 
-    fn search_set_active(&mut self, term_opt: Option<String>) -> Task<Message> {
-        let entity;
-        if self.active_panel == PaneType::LeftPane {
-            entity = self.tab_model1.active();
-        } else {
-            entity = self.tab_model2.active();
+    /// Rescan the Recents tabs of both panels, as the history is shared
+    fn rescan_recents(&mut self) -> Task<Message> {
+        let left = recents_tabs::<LeftPanel>(&self.tab_model1);
+        let right = recents_tabs::<RightPanel>(&self.tab_model2);
+        Task::batch([
+            self.rescan_tabs::<LeftPanel>(left),
+            self.rescan_tabs::<RightPanel>(right),
+        ])
+    }
+
+    fn rescan_trash_in<P: ActivePane>(&mut self) -> Task<Message> {
+        let needs_reload = trash_tabs::<P>(P::tab_model(self));
+        self.rescan_tabs::<P>(needs_reload)
+    }
+
+    fn rescan_tabs<P: ActivePane>(&mut self, tabs: Vec<(Entity, P::Location)>) -> Task<Message> {
+        let mut commands = Vec::with_capacity(tabs.len());
+        for (entity, location) in tabs {
+            commands.push(P::update_tab(self, entity, location, None));
         }
-        self.search_set(entity, term_opt, None)
+        Task::batch(commands)
     }
 
-    fn search_set(
-        &mut self,
-        entity: Entity,
-        term_opt: Option<String>,
-        selection_paths: Option<Vec<PathBuf>>,
-    ) -> Task<Message> {
-        if self.active_panel == PaneType::LeftPane {
-            let mut title_location_opt = None;
-            if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                let location_opt = match term_opt {
-                    Some(term) => match &tab.location {
-                        Location1::Path(path) | Location1::Search(path, ..) => Some((
-                            Location1::Search(
-                                path.to_path_buf(),
-                                term,
-                                tab.config.show_hidden,
-                                Instant::now(),
-                            ),
-                            true,
-                        )),
-                        _ => None,
-                    },
-                    None => match &tab.location {
-                        Location1::Search(path, ..) => {
-                            Some((Location1::Path(path.to_path_buf()), false))
-                        }
-                        _ => None,
-                    },
-                };
-                if let Some((location, focus_search)) = location_opt {
-                    tab.change_location(&location, None);
-                    title_location_opt = Some((tab.title(), tab.location.clone(), focus_search));
-                }
-            }
-            if let Some((title, location, focus_search)) = title_location_opt {
-                self.tab_model1.text_set(entity, title);
-                return Task::batch([
-                    self.update_title(),
-                    self.update_watcher_left(),
-                    self.rescan_tab_left(entity, location, selection_paths),
-                    if focus_search {
-                        widget::text_input::focus(self.search_id.clone())
-                    } else {
-                        Task::none()
-                    },
-                ]);
-            }
-        } else {
-            let mut title_location_opt = None;
-            if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                let location_opt = match term_opt {
-                    Some(term) => match &tab.location {
-                        Location2::Path(path) | Location2::Search(path, ..) => Some((
-                            Location2::Search(
-                                path.to_path_buf(),
-                                term,
-                                tab.config.show_hidden,
-                                Instant::now(),
-                            ),
-                            true,
-                        )),
-                        _ => None,
-                    },
-                    None => match &tab.location {
-                        Location2::Search(path, ..) => {
-                            Some((Location2::Path(path.to_path_buf()), false))
-                        }
-                        _ => None,
-                    },
-                };
-                if let Some((location, focus_search)) = location_opt {
-                    tab.change_location(&location, None);
-                    title_location_opt = Some((tab.title(), tab.location.clone(), focus_search));
-                }
+    fn search_get(&self) -> Option<&str> {
+        match self.active_panel {
+            PaneType::LeftPane => self.search_get_in::<LeftPanel>(),
+            _ => self.search_get_in::<RightPanel>(),
+        }
+    }
+
+    fn search_get_in<P: ActivePane>(&self) -> Option<&str> {
+        tab_or_active::<P>(P::tab_model(self), None)
+            .and_then(|tab| P::search_term(P::location(tab)))
+    }
+
+    fn search_set_active(&mut self, term_opt: Option<String>) -> Task<Message> {
+        match self.active_panel {
+            PaneType::LeftPane => {
+                let entity = self.tab_model1.active();
+                self.search_set_in::<LeftPanel>(entity, term_opt, None)
             }
-            if let Some((title, location, focus_search)) = title_location_opt {
-                self.tab_model2.text_set(entity, title);
-                return Task::batch([
-                    self.update_title(),
-                    self.update_watcher_right(),
-                    self.rescan_tab_right(entity, location, selection_paths),
-                    if focus_search {
-                        widget::text_input::focus(self.search_id.clone())
-                    } else {
-                        Task::none()
-                    },
-                ]);
+            _ => {
+                let entity = self.tab_model2.active();
+                self.search_set_in::<RightPanel>(entity, term_opt, None)
             }
         }
+    }
 
-        Task::none()
+    fn search_set_in<P: ActivePane>(
+        &mut self,
+        entity: Entity,
+        term_opt: Option<String>,
+        selection_paths: Option<Vec<PathBuf>>,
+    ) -> Task<Message> {
+        let Some(tab) = P::tab_model_mut(self).data_mut::<P::Tab>(entity) else {
+            return Task::none();
+        };
+        let Some((location, focus_search)) = P::search_location(tab, term_opt) else {
+            return Task::none();
+        };
+        P::change_location(tab, &location);
+        let title = P::title(tab);
+        let location = P::location(tab).clone();
+        P::tab_model_mut(self).text_set(entity, title);
+        Task::batch([
+            self.update_title(),
+            P::update_watcher(self),
+            P::rescan_tab(self, entity, location, selection_paths),
+            if focus_search {
+                widget::text_input::focus(self.search_id.clone())
+            } else {
+                Task::none()
+            },
+        ])
+    }
+
+    fn selected_trash_items(&self, entity_opt: Option<Entity>) -> Vec<TrashItem> {
+        match self.active_panel {
+            PaneType::LeftPane => tab_or_active::<LeftPanel>(&self.tab_model1, entity_opt)
+                .map(LeftPanel::selected_trash_items)
+                .unwrap_or_default(),
+            _ => tab_or_active::<RightPanel>(&self.tab_model2, entity_opt)
+                .map(RightPanel::selected_trash_items)
+                .unwrap_or_default(),
+        }
     }
 
     fn selected_paths(&self, entity_opt: Option<Entity>) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
-        let entity = match entity_opt {
-            Some(entity) => entity,
-            None => {
-                if self.active_panel == PaneType::LeftPane {
-                    self.tab_model1.active()
-                } else {
-                    self.tab_model2.active()
-                }
+        match self.active_panel {
+            PaneType::LeftPane => tab_selected_paths::<LeftPanel>(&self.tab_model1, entity_opt),
+            _ => tab_selected_paths::<RightPanel>(&self.tab_model2, entity_opt),
+        }
+    }
+
+    /// Flags for a new window with the tabs of both panels and the current layout
+    fn duplicate_flags(&self) -> Flags {
+        let locations1 = self
+            .tab_model1
+            .iter()
+            .filter_map(|entity| self.tab_model1.data::<Tab1>(entity))
+            .map(|tab| tab.location.clone())
+            .collect();
+        let locations2 = self
+            .tab_model2
+            .iter()
+            .filter_map(|entity| self.tab_model2.data::<Tab2>(entity))
+            .filter_map(|tab| match &tab.location {
+                Location2::Path(path) => Some(Location1::Path(path.clone())),
+                _ => None,
+            })
+            .collect();
+        Flags {
+            config_handler: None,
+            config: self.config.clone(),
+            mode: self.mode.clone(),
+            locations1,
+            locations2,
+            reveal_opt: None,
+            layout_overrides: LayoutOverrides {
+                show_button_row: Some(self.show_button_row),
+                show_embedded_terminal: Some(self.show_embedded_terminal),
+                show_second_panel: Some(self.show_second_panel),
+            },
+        }
+    }
+
+    /// Move items to the trash, asking first if configured
+    fn move_to_trash(&mut self, paths: Vec<PathBuf>) -> Task<Message> {
+        if paths.is_empty() {
+            return Task::none();
+        }
+        let (no_trash, paths): (Vec<_>, Vec<_>) = paths
+            .into_iter()
+            .partition(|path| trash_dir::missing(path).is_some());
+        if !no_trash.is_empty() {
+            self.trash_unavailable(paths, no_trash, self.config.trash_unavailable);
+        } else if self.config.confirm_trash {
+            self.dialog_pages
+                .push_back(DialogPage::MoveToTrash { paths });
+        } else {
+            self.operation(Operation::Delete { paths });
+        }
+        Task::none()
+    }
+
+    /// Move items to the trash when some of them are on filesystems without a trash folder
+    fn trash_unavailable(
+        &mut self,
+        mut paths: Vec<PathBuf>,
+        no_trash: Vec<PathBuf>,
+        choice: TrashUnavailable,
+    ) {
+        match choice {
+            TrashUnavailable::Ask => {
+                self.dialog_pages.push_back(DialogPage::TrashUnavailable {
+                    paths,
+                    no_trash,
+                    choice,
+                });
             }
-        };
-        if self.active_panel == PaneType::LeftPane {
-            if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
-                for location in tab.selected_locations() {
-                    if let Some(path) = location.path_opt() {
-                        paths.push(path.to_path_buf());
+            TrashUnavailable::CreateTrash => {
+                let topdirs: BTreeSet<PathBuf> = no_trash
+                    .iter()
+                    .filter_map(|path| trash_dir::missing(path))
+                    .collect();
+                for topdir in topdirs {
+                    if let Err(err) = trash_dir::create(&topdir) {
+                        log::warn!("failed to create trash folder in {:?}: {}", topdir, err);
                     }
                 }
+                paths.extend(no_trash);
+                self.operation(Operation::Delete { paths });
             }
-        } else {
-            if let Some(tab) = self.tab_model2.data::<Tab2>(entity) {
-                for location in tab.selected_locations() {
-                    if let Some(path) = location.path_opt() {
-                        paths.push(path.to_path_buf());
-                    }
+            TrashUnavailable::Delete => {
+                if !paths.is_empty() {
+                    self.operation(Operation::Delete { paths });
+                }
+                if self.config.confirm_permanent_delete {
+                    self.dialog_pages
+                        .push_back(DialogPage::PermanentlyDelete { paths: no_trash });
+                } else {
+                    self.operation(Operation::PermanentDelete { paths: no_trash });
                 }
             }
         }
-        paths
+    }
+
+    /// Ask for the name and type of an archive of `paths` to create in `to`
+    fn compress_dialog(&mut self, paths: Vec<PathBuf>, to: PathBuf) -> Task<Message> {
+        let Some(name) = paths
+            .first()
+            .and_then(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+        else {
+            return Task::none();
+        };
+        self.dialog_pages.push_back(DialogPage::Compress {
+            paths,
+            to,
+            name,
+            archive_type: ArchiveType::default(),
+            password: None,
+            separate: false,
+            include_files: false,
+        });
+        widget::text_input::focus(self.dialog_text_input.clone())
+    }
+
+    /// Paste or compress files dropped onto the folder `to`
+    fn drop_paths(
+        &mut self,
+        to: PathBuf,
+        paste: ClipboardPaste,
+        action: DndAction,
+    ) -> Task<Message> {
+        match DropAction::new(action, self.modifiers).paste_kind() {
+            Some(kind) => self.update(Message::PasteContents(
+                to,
+                ClipboardPaste {
+                    kind,
+                    paths: paste.paths,
+                },
+            )),
+            None => self.compress_dialog(paste.paths, to),
+        }
+    }
+
+    /// Copy or move the selection of the active tab of a panel into the folder of the active tab
+    /// of the other panel
+    fn copy_move_to_other_panel<P: ActivePane>(&mut self, copy: bool) {
+        let paths = tab_selected_paths::<P>(P::tab_model(self), None);
+        let Some(to) = active_tab_path::<P::Other>(<P::Other as ActivePane>::tab_model(self))
+        else {
+            return;
+        };
+        if copy {
+            self.operation(Operation::Copy { paths, to });
+        } else {
+            self.operation(Operation::Move { paths, to });
+        }
     }
 
     fn pane_setup(
@@ -1625,10 +3993,110 @@ impl App {
         } else {
             //
         }
+
+        // Restore the split ratios that were dragged in a previous session
+        for (split, pane_type) in self.pane_model.type_by_split.iter() {
+            if let Some((_, ratio)) = self
+                .config
+                .pane_ratios
+                .iter()
+                .find(|(ratio_type, _)| ratio_type == pane_type)
+            {
+                self.pane_model
+                    .panestates
+                    .resize(*split, f32::from(*ratio) / 1000.0);
+            }
+        }
+    }
+
+    /// Running operations shared with tabs, which show how far they got with each item
+    fn tab_operations(&self) -> Arc<Vec<(Operation, Controller)>> {
+        Arc::new(
+            self.pending_operations
+                .values()
+                .map(|(op, controller)| (op.clone(), controller.clone()))
+                .collect(),
+        )
+    }
+
+    fn update_tab_operations(&mut self) {
+        let operations = self.tab_operations();
+        for entity in self.tab_model1.iter().collect::<Vec<_>>() {
+            if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
+                tab.operations = operations.clone();
+            }
+        }
+        for entity in self.tab_model2.iter().collect::<Vec<_>>() {
+            if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
+                tab.operations = operations.clone();
+            }
+        }
+    }
+
+    /// Share the remembered folder views with all tabs, they are applied on the next location change
+    fn update_folder_views(&mut self) {
+        let folder_views_left = Arc::new(self.config.folder_views_left.clone());
+        for entity in self.tab_model1.iter().collect::<Vec<_>>() {
+            if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
+                tab.folder_views = folder_views_left.clone();
+            }
+        }
+        let folder_views_right = Arc::new(self.config.folder_views_right.clone());
+        for entity in self.tab_model2.iter().collect::<Vec<_>>() {
+            if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
+                tab.folder_views = folder_views_right.clone();
+            }
+        }
+    }
+
+    /// Zoom the gallery image instead of the icons when the active tab shows the gallery
+    fn gallery_zoom(
+        &mut self,
+        message1: tab1::Message,
+        message2: tab2::Message,
+    ) -> Option<Task<Message>> {
+        if self.active_panel == PaneType::LeftPane {
+            let entity = self.tab_model1.active();
+            let gallery = self
+                .tab_model1
+                .data::<Tab1>(entity)
+                .is_some_and(|tab| tab.gallery);
+            gallery.then(|| self.update(Message::TabMessage(Some(entity), message1)))
+        } else {
+            let entity = self.tab_model2.active();
+            let gallery = self
+                .tab_model2
+                .data::<Tab2>(entity)
+                .is_some_and(|tab| tab.gallery);
+            gallery.then(|| self.update(Message::TabMessageRight(Some(entity), message2)))
+        }
     }
 
     fn update_config(&mut self) -> Task<Message> {
+        item_cache::set_capacity(self.config.item_cache_size);
+        set_copy_options(
+            self.config.copy_buffer_size,
+            self.config.copy_concurrency,
+            self.config.copy_strict,
+        );
+        set_unique_name_options(
+            &self.config.unique_name_template,
+            self.config.unique_name_base,
+        );
+        self.custom_actions = self
+            .config
+            .custom_actions
+            .iter()
+            .filter_map(|custom_action| match custom_action.parse() {
+                Ok(parsed) => Some(parsed),
+                Err(err) => {
+                    log::warn!("skipping custom action {:?}: {}", custom_action.label, err);
+                    None
+                }
+            })
+            .collect();
         self.update_color_schemes();
+        self.update_folder_views();
         let commands: Vec<_>;
         if self.show_button_row != self.config.show_button_row
             || self.show_embedded_terminal != self.config.show_embedded_terminal
@@ -1647,7 +4115,7 @@ impl App {
             }
         }
         if self.active_panel == PaneType::LeftPane {
-            self.update_nav_model_left();
+            self.update_nav_model_in::<LeftPanel>();
             // Tabs are collected first to placate the borrowck
             let tabs: Vec<_> = self.tab_model1.iter().collect();
             // Update main conf and each tab with the new config
@@ -1662,7 +4130,7 @@ impl App {
             }))
             .collect();
         } else {
-            self.update_nav_model_right();
+            self.update_nav_model_in::<RightPanel>();
             // Tabs are collected first to placate the borrowck
             let tabs: Vec<_> = self.tab_model2.iter().collect();
             // Update main conf and each tab with the new config
@@ -1731,7 +4199,7 @@ impl App {
         Task::none()
     }
 
-    fn activate_nav_model_location_left(&mut self, location: &Location1) {
+    fn activate_nav_model_location(&mut self, location: &Location1) {
         let nav_bar_id = self.nav_model.iter().find(|&id| {
             self.nav_model
                 .data::<Location1>(id)
@@ -1747,38 +4215,55 @@ impl App {
         }
     }
 
-    fn activate_nav_model_location_right(&mut self, location: &Location2) {
-        let loc = convert_location2_to_location1(location);
-        let nav_bar_id = self.nav_model.iter().find(|&id| {
-            self.nav_model
-                .data::<Location1>(id)
-                .map(|l| *l == loc)
-                .unwrap_or_default()
-        });
-
-        if let Some(id) = nav_bar_id {
-            self.nav_model.activate(id);
-        } else {
-            let active = self.nav_model.active();
-            segmented_button::Selectable::deactivate(&mut self.nav_model, active);
+    /// Activate the sidebar item of the location of a tab of a panel
+    fn activate_nav_model_tab<P: ActivePane>(&mut self, entity: Entity) {
+        if let Some(tab) = P::tab_model(self).data::<P::Tab>(entity) {
+            let location = P::nav_location(P::location(tab));
+            self.activate_nav_model_location(&location);
         }
     }
 
     fn update_nav_model(&mut self) {
         let mut nav_model = segmented_button::ModelBuilder::default();
+        let hidden = &self.config.sidebar_hidden;
+
+        if let Some(staged) = &self.staged_archive_opt {
+            let text = fl!(
+                "staged-archive",
+                name = staged
+                    .to
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                items = staged.paths.len()
+            );
+            nav_model = nav_model.insert(move |b| {
+                b.text(text.clone())
+                    .icon(widget::icon::icon(
+                        widget::icon::from_name("package-x-generic-symbolic")
+                            .size(16)
+                            .handle(),
+                    ))
+                    .data(StagedArchiveItem)
+            });
+        }
 
-        nav_model = nav_model.insert(|b| {
-            b.text(fl!("recents"))
-                .icon(widget::icon::from_name("document-open-recent-symbolic"))
-                .data(Location1::Recents)
-        });
+        if !hidden.contains(&SidebarBuiltin::Recents) {
+            nav_model = nav_model.insert(|b| {
+                b.text(fl!("recents"))
+                    .icon(widget::icon::from_name("document-open-recent-symbolic"))
+                    .data(Location1::Recents)
+                    .data(SidebarBuiltin::Recents)
+            });
+        }
 
         for (favorite_i, favorite) in self.config.favorites.iter().enumerate() {
             if let Some(path) = favorite.path_opt() {
                 let name = if matches!(favorite, Favorite::Home) {
                     fl!("home")
-                } else if let Some(file_name) = path.file_name().and_then(|x| x.to_str()) {
-                    file_name.to_string()
+                } else if let Some(file_name) = path.file_name() {
+                    // Localized user folders may not be valid UTF-8
+                    file_name.to_string_lossy().into_owned()
                 } else {
                     fl!("filesystem")
                 };
@@ -1800,14 +4285,17 @@ impl App {
             }
         }
 
-        nav_model = nav_model.insert(|b| {
-            b.text(fl!("trash"))
-                .icon(widget::icon::icon(tab1::trash_icon_symbolic(16)))
-                .data(Location1::Trash)
-                .divider_above()
-        });
+        if !hidden.contains(&SidebarBuiltin::Trash) {
+            nav_model = nav_model.insert(|b| {
+                b.text(fl!("trash"))
+                    .icon(widget::icon::icon(tab1::trash_icon_symbolic(16)))
+                    .data(Location1::Trash)
+                    .data(SidebarBuiltin::Trash)
+                    .divider_above()
+            });
+        }
 
-        if !MOUNTERS.is_empty() {
+        if !MOUNTERS.is_empty() && !hidden.contains(&SidebarBuiltin::Networks) {
             nav_model = nav_model.insert(|b| {
                 b.text(fl!("networks"))
                     .icon(widget::icon::icon(
@@ -1819,6 +4307,7 @@ impl App {
                         "network:///".to_string(),
                         fl!("networks"),
                     ))
+                    .data(SidebarBuiltin::Networks)
                     .divider_above()
             });
         }
@@ -1827,6 +4316,9 @@ impl App {
         let mut nav_items = Vec::new();
         for (key, items) in self.mounter_items.iter() {
             for item in items.iter() {
+                if item.is_system() && !self.config.sidebar_system_mounts {
+                    continue;
+                }
                 nav_items.push((*key, item));
             }
         }
@@ -1834,15 +4326,22 @@ impl App {
         nav_items.sort_by(|a, b| LANGUAGE_SORTER.compare(&a.1.name(), &b.1.name()));
         // Add items to nav model
         for (i, (key, item)) in nav_items.into_iter().enumerate() {
+            let pending = self.mounter_pending.contains(&(key, item.name()));
             nav_model = nav_model.insert(|mut b| {
                 b = b.text(item.name()).data(MounterData(key, item.clone()));
                 if let Some(path) = item.path() {
                     b = b.data(Location1::Path(path.clone()));
                 }
-                if let Some(icon) = item.icon(true) {
+                if pending {
+                    b = b.icon(
+                        widget::icon::from_name("process-working-symbolic")
+                            .size(16)
+                            .icon(),
+                    );
+                } else if let Some(icon) = item.icon(true) {
                     b = b.icon(widget::icon::icon(icon).size(16));
                 }
-                if item.is_mounted() {
+                if item.is_mounted() && !pending {
                     b = b.closable();
                 }
                 if i == 0 {
@@ -1854,81 +4353,321 @@ impl App {
         self.nav_model = nav_model.build();
     }
 
-    fn update_nav_model_left(&mut self) {
+    fn update_nav_model_in<P: ActivePane>(&mut self) {
         self.update_nav_model();
 
-        let tab_entity = self.tab_model1.active();
-        if let Some(tab) = self.tab_model1.data::<Tab1>(tab_entity) {
-            self.activate_nav_model_location_left(&tab.location.clone());
-        }
+        let tab_entity = P::tab_model(self).active();
+        self.activate_nav_model_tab::<P>(tab_entity);
     }
 
-    fn update_nav_model_right(&mut self) {
-        self.update_nav_model();
+    /// Close a tab of a panel and activate the one next to it. Returns closing the window when
+    /// that was the last tab.
+    fn close_tab<P: ActivePane>(&mut self, entity: Entity) -> Option<Task<Message>> {
+        let tab_model = P::tab_model_mut(self);
+        if let Some(position) = tab_model.position(entity) {
+            let new_position = if position > 0 {
+                position - 1
+            } else {
+                position + 1
+            };
+
+            if tab_model.activate_position(new_position) {
+                if let Some(new_entity) = tab_model.entity_at(new_position) {
+                    self.activate_nav_model_tab::<P>(new_entity);
+                }
+            }
+        }
+
+        // Remove item
+        let tab_model = P::tab_model_mut(self);
+        tab_model.remove(entity);
 
-        let tab_entity = self.tab_model2.active();
-        if let Some(tab) = self.tab_model2.data::<Tab2>(tab_entity) {
-            self.activate_nav_model_location_right(&tab.location.clone());
+        // If that was the last tab, close window
+        if tab_model.iter().next().is_none() {
+            return self.window_id_opt.map(window::close);
         }
+        None
     }
 
-    fn update_notification(&mut self) -> Task<Message> {
-        // Handle closing notification if there are no operations
-        if self.pending_operations.is_empty() {
-            #[cfg(feature = "notify")]
-            if let Some(notification_arc) = self.notification_opt.take() {
-                return Task::perform(
-                    async move {
-                        tokio::task::spawn_blocking(move || {
-                            //TODO: this is nasty
-                            let notification_mutex = Arc::try_unwrap(notification_arc).unwrap();
-                            let notification = notification_mutex.into_inner().unwrap();
-                            notification.close();
-                        })
-                        .await
-                        .unwrap();
-                        message::app(Message::MaybeExit)
-                    },
-                    |x| x,
-                );
-            }
+    /// Close a tab of a panel, or its active tab, and make it the active panel
+    fn close_tab_in<P: ActivePane>(&mut self, entity_opt: Option<Entity>) -> Task<Message> {
+        self.active_panel = P::PANE;
+        let entity = entity_opt.unwrap_or_else(|| P::tab_model(self).active());
+        if let Some(task) = self.close_tab::<P>(entity) {
+            return task;
         }
+        let _ = self.update(Message::StoreOpenPaths);
+        Task::batch([self.update_title(), P::update_watcher(self)])
+    }
 
-        Task::none()
+    /// Activate a tab of a panel and make it the active panel
+    fn activate_tab<P: ActivePane>(&mut self, entity: Entity) -> Task<Message> {
+        P::tab_model_mut(self).activate(entity);
+        self.active_panel = P::PANE;
+        self.activate_nav_model_tab::<P>(entity);
+        self.update_title()
     }
 
-    fn update_title(&mut self) -> Task<Message> {
-        let window_title;
-        if self.active_panel == PaneType::LeftPane {
-            window_title = match self.tab_model1.text(self.tab_model1.active()) {
-                Some(tab_title) => format!("{tab_title} — {}", fl!("commander")),
-                None => fl!("commander"),
-            };
-        } else {
-            window_title = match self.tab_model2.text(self.tab_model2.active()) {
-                Some(tab_title) => format!("{tab_title} — {}", fl!("commander")),
-                None => fl!("commander"),
-            };
+    /// Activate the next or previous tab of a panel, wrapping around at either end
+    fn activate_tab_next<P: ActivePane>(&mut self, next: bool) -> Task<Message> {
+        let tab_model = P::tab_model(self);
+        let len = tab_model.iter().count();
+        let position = tab_model.position(tab_model.active()).map(|i| i as usize);
+        let position = match position {
+            Some(i) if next => (i + 1) % len,
+            Some(i) if i > 0 => i - 1,
+            _ => len.saturating_sub(1),
+        };
+        let entity_opt = tab_model.iter().nth(position);
+        match entity_opt {
+            Some(entity) => self.activate_tab::<P>(entity),
+            None => Task::none(),
         }
-        if let Some(window_id) = &self.window_id_opt {
-            self.set_window_title(window_title, *window_id)
+    }
+
+    /// Scan the active tab of a panel again
+    fn rescan_active_tab<P: ActivePane>(&mut self) -> Task<Message> {
+        let tab_model = P::tab_model(self);
+        let entity = tab_model.active();
+        let Some(tab) = tab_model.data::<P::Tab>(entity) else {
+            return Task::none();
+        };
+        let location = P::location(tab).clone();
+        let epoch = P::scan_begin(tab);
+        self.update(P::rescan_message(entity, location, epoch))
+    }
+
+    /// Show a location in the active tab of a panel
+    fn change_active_location<P: ActivePane>(&mut self, location: P::Location) -> Task<Message> {
+        let entity = P::tab_model(self).active();
+        let Some(tab) = P::tab_model_mut(self).data_mut::<P::Tab>(entity) else {
+            return Task::none();
+        };
+        P::change_location(tab, &location);
+        let title = P::title(tab);
+        P::tab_model_mut(self).text_set(entity, title);
+        Task::batch([
+            self.update_title(),
+            P::update_watcher(self),
+            P::update_tab(self, entity, location, None),
+        ])
+    }
+
+    /// Open a sidebar location in the active tab of a panel
+    fn open_nav_location<P: ActivePane>(&mut self, location: &Location1) -> Task<Message> {
+        self.update(P::location_message(P::from_nav_location(location)))
+    }
+
+    /// Stop editing the location of the active tab of a panel
+    fn close_edit_location<P: ActivePane>(&mut self) {
+        let entity = P::tab_model(self).active();
+        if let Some(tab) = P::tab_model_mut(self).data_mut::<P::Tab>(entity) {
+            P::close_edit_location(tab);
+        }
+    }
+
+    /// Whether the active panel hides files ignored by git
+    fn respect_gitignore(&self) -> bool {
+        if self.active_panel == PaneType::LeftPane {
+            self.config.tab_left.respect_gitignore
         } else {
-            Task::none()
+            self.config.tab_right.respect_gitignore
         }
     }
 
-    fn update_watcher_left(&mut self) -> Task<Message> {
-        if let Some((mut watcher, old_paths)) = self.watcher_opt_left.take() {
-            let mut new_paths = HashSet::new();
-            for entity in self.tab_model1.iter() {
-                if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
-                    if let Location1::Path(path) = &tab.location {
-                        new_paths.insert(path.clone());
-                    }
-                }
-            }
+    /// Save the tab configs of both panels, only a changed one updates its tabs
+    fn update_tab_configs(&mut self, config: Config) -> Task<Message> {
+        Task::batch([
+            self.update(Message::TabConfigLeft(config.tab_left)),
+            self.update(Message::TabConfigRight(config.tab_right)),
+        ])
+    }
 
-            // Unwatch paths no longer used
+    /// Add paths to the staged archive, updating its sidebar item
+    fn stage_archive_paths(&mut self, paths: Vec<PathBuf>) {
+        if let Some(staged) = &mut self.staged_archive_opt {
+            staged.add(paths);
+        }
+        self.update_nav_model_active();
+    }
+
+    fn update_nav_model_active(&mut self) {
+        match self.active_panel {
+            PaneType::LeftPane => self.update_nav_model_in::<LeftPanel>(),
+            _ => self.update_nav_model_in::<RightPanel>(),
+        }
+    }
+
+    /// Mount or unmount an item, showing it as busy in the sidebar until the result arrives
+    fn mount_item(
+        &mut self,
+        mounter_key: MounterKey,
+        item: MounterItem,
+        mount: bool,
+    ) -> Task<Message> {
+        let Some(mounter) = MOUNTERS.get(&mounter_key) else {
+            return Task::none();
+        };
+        self.mounter_pending.insert((mounter_key, item.name()));
+        self.update_nav_model_active();
+        let task = if mount {
+            mounter.mount(item)
+        } else {
+            mounter.unmount(item)
+        };
+        task.map(|_| message::none())
+    }
+
+    /// Stop showing an item as busy once mounting or unmounting it finished
+    fn mount_item_done(&mut self, mounter_key: MounterKey, item: &MounterItem) {
+        if self.mounter_pending.remove(&(mounter_key, item.name())) {
+            self.update_nav_model_active();
+        }
+    }
+
+    /// Close the progress notification when no operations are left, and notify about the
+    /// operation that just finished, given by its text and error if it failed
+    #[cfg_attr(not(feature = "notify"), allow(unused_variables))]
+    fn update_notification(
+        &mut self,
+        finished_opt: Option<(String, Option<String>)>,
+    ) -> Task<Message> {
+        #[cfg(feature = "notify")]
+        {
+            // Finished operations are only notified while the window is closed, the edit history
+            // lists them otherwise
+            let finished_opt = finished_opt.filter(|(_, error_opt)| {
+                self.window_id_opt.is_none()
+                    && self.config.notify_operations.notify(error_opt.is_some())
+            });
+            let notification_opt = if self.pending_operations.is_empty() {
+                self.notification_opt.take()
+            } else {
+                None
+            };
+            if finished_opt.is_some() || self.pending_operations.is_empty() {
+                let sound = self.config.notify_sound;
+                return Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            // Handle closing notification if there are no operations
+                            if let Some(notification_arc) = notification_opt {
+                                //TODO: this is nasty
+                                let notification_mutex = Arc::try_unwrap(notification_arc).unwrap();
+                                let notification = notification_mutex.into_inner().unwrap();
+                                notification.close();
+                            }
+                            if let Some((text, error_opt)) = finished_opt {
+                                let mut notification = notify_rust::Notification::new();
+                                match error_opt {
+                                    Some(err) => {
+                                        notification
+                                            .summary(&fl!("notification-failed"))
+                                            .body(&format!("{}: {}", text, err));
+                                    }
+                                    None => {
+                                        notification
+                                            .summary(&fl!("notification-complete"))
+                                            .body(&text);
+                                        if sound {
+                                            notification.sound_name("complete");
+                                        }
+                                    }
+                                }
+                                if let Err(err) = notification.show() {
+                                    log::warn!("failed to show notification: {}", err);
+                                }
+                            }
+                        })
+                        .await
+                        .unwrap();
+                        message::app(Message::MaybeExit)
+                    },
+                    |x| x,
+                );
+            }
+        }
+
+        Task::none()
+    }
+
+    /// Set the titles of the tabs of both panels, as they depend on the other tabs of the panel
+    fn update_tab_titles(&mut self) {
+        let tabs: Vec<_> = self
+            .tab_model1
+            .iter()
+            .filter_map(|entity| {
+                let tab = self.tab_model1.data::<Tab1>(entity)?;
+                let path_opt = match &tab.location {
+                    Location1::Path(path) => Some(path.clone()),
+                    _ => None,
+                };
+                Some((entity, (tab.title(), path_opt)))
+            })
+            .collect();
+        let (entities, titles): (Vec<_>, Vec<_>) = tabs.into_iter().unzip();
+        for (entity, title) in entities
+            .into_iter()
+            .zip(tab_titles(&titles, self.config.tab_titles))
+        {
+            self.tab_model1.text_set(entity, title);
+        }
+
+        let tabs: Vec<_> = self
+            .tab_model2
+            .iter()
+            .filter_map(|entity| {
+                let tab = self.tab_model2.data::<Tab2>(entity)?;
+                let path_opt = match &tab.location {
+                    Location2::Path(path) => Some(path.clone()),
+                    _ => None,
+                };
+                Some((entity, (tab.title(), path_opt)))
+            })
+            .collect();
+        let (entities, titles): (Vec<_>, Vec<_>) = tabs.into_iter().unzip();
+        for (entity, title) in entities
+            .into_iter()
+            .zip(tab_titles(&titles, self.config.tab_titles))
+        {
+            self.tab_model2.text_set(entity, title);
+        }
+    }
+
+    fn update_title(&mut self) -> Task<Message> {
+        // Tabs were opened, closed or changed their location
+        self.update_tab_titles();
+        let window_title;
+        if self.active_panel == PaneType::LeftPane {
+            window_title = match self.tab_model1.text(self.tab_model1.active()) {
+                Some(tab_title) => format!("{tab_title} — {}", fl!("commander")),
+                None => fl!("commander"),
+            };
+        } else {
+            window_title = match self.tab_model2.text(self.tab_model2.active()) {
+                Some(tab_title) => format!("{tab_title} — {}", fl!("commander")),
+                None => fl!("commander"),
+            };
+        }
+        if let Some(window_id) = &self.window_id_opt {
+            self.set_window_title(window_title, *window_id)
+        } else {
+            Task::none()
+        }
+    }
+
+    fn update_watcher_left(&mut self) -> Task<Message> {
+        if let Some((mut watcher, old_paths)) = self.watcher_opt_left.take() {
+            let mut new_paths = HashSet::new();
+            for entity in self.tab_model1.iter() {
+                if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
+                    if let Location1::Path(path) = &tab.location {
+                        new_paths.insert(path.clone());
+                    }
+                }
+            }
+
+            // Unwatch paths no longer used
             for path in old_paths.iter() {
                 if !new_paths.contains(path) {
                     match watcher.watcher().unwatch(path) {
@@ -2074,12 +4813,40 @@ impl App {
                 table = table.push(widget::divider::horizontal::light());
             }
         }
-        widget::column::with_children(vec![
+        let mut children = vec![
             widget::text::body(fl!("network-drive-description")).into(),
             table.into(),
-        ])
-        .spacing(space_m)
-        .into()
+        ];
+        if !self.config.recent_network_drives.is_empty() {
+            let mut section = widget::settings::section().title(fl!("recent-connections"));
+            for uri in self.config.recent_network_drives.iter() {
+                section = section.add(
+                    widget::row::with_children(vec![
+                        widget::button::link(uri.clone())
+                            .on_press_maybe(
+                                self.network_drive_connecting
+                                    .is_none()
+                                    .then(|| Message::NetworkDriveReconnect(uri.clone())),
+                            )
+                            .into(),
+                        widget::horizontal_space().into(),
+                        widget::tooltip(
+                            widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                                .on_press(Message::NetworkDriveRemoveRecent(uri.clone()))
+                                .padding(8),
+                            widget::text::body(fl!("remove")),
+                            widget::tooltip::Position::Top,
+                        )
+                        .into(),
+                    ])
+                    .align_y(Alignment::Center),
+                );
+            }
+            children.push(section.into());
+        }
+        widget::column::with_children(children)
+            .spacing(space_m)
+            .into()
     }
 
     fn desktop_view_options(&self) -> Element<Message> {
@@ -2158,12 +4925,62 @@ impl App {
         );
         children.push(section.into());
 
+        let mut section = widget::settings::section().title(fl!("icon-placement"));
+        let origins = DesktopGridOrigin::all();
+        let selected = origins
+            .iter()
+            .position(|origin| *origin == config.grid_origin);
+        section = section.add(widget::settings::item::builder(fl!("grid-origin")).control(
+            widget::dropdown(&self.desktop_grid_origins, selected, move |index| {
+                Message::DesktopConfig(DesktopConfig {
+                    grid_origin: origins[index],
+                    ..config
+                })
+            }),
+        ));
+        let flows = DesktopGridFlow::all();
+        let selected = flows.iter().position(|flow| *flow == config.grid_flow);
+        section = section.add(widget::settings::item::builder(fl!("grid-flow")).control(
+            widget::dropdown(&self.desktop_grid_flows, selected, move |index| {
+                Message::DesktopConfig(DesktopConfig {
+                    grid_flow: flows[index],
+                    ..config
+                })
+            }),
+        ));
+        children.push(section.into());
+
         widget::column::with_children(children)
             .padding([0, space_l, space_l, space_l])
             .spacing(space_m)
             .into()
     }
 
+    /// Whether every pending operation is paused
+    fn pending_all_paused(&self) -> bool {
+        self.pending_operations
+            .values()
+            .all(|(_, controller)| controller.is_paused())
+    }
+
+    /// Pause or resume all pending operations, shown above the edit history
+    fn edit_history_header(&self) -> Element<Message> {
+        let all_paused = self.pending_all_paused();
+        let (icon, label) = if all_paused {
+            ("media-playback-start-symbolic", fl!("resume-all"))
+        } else {
+            ("media-playback-pause-symbolic", fl!("pause-all"))
+        };
+        widget::row::with_children(vec![
+            widget::horizontal_space().into(),
+            widget::button::icon(widget::icon::from_name(icon))
+                .label(label)
+                .on_press(Message::PendingPauseAll(!all_paused))
+                .into(),
+        ])
+        .into()
+    }
+
     fn edit_history(&self) -> Element<Message> {
         let cosmic_theme::Spacing { space_m, .. } = theme::active().cosmic().spacing;
 
@@ -2176,7 +4993,7 @@ impl App {
             let mut section = widget::settings::section().title(fl!("pending"));
             for (id, (op, controller)) in self.pending_operations.iter().rev() {
                 let progress = controller.progress();
-                section = section.add(widget::column::with_children(vec![
+                let mut column = widget::column::with_children(vec![
                     widget::row::with_children(vec![
                         widget::progress_bar(0.0..=1.0, progress)
                             .height(progress_bar_height)
@@ -2216,12 +5033,23 @@ impl App {
                     .align_y(Alignment::Center)
                     .into(),
                     widget::text::body(op.pending_text(progress, controller.state())).into(),
-                ]));
+                ]);
+                // Shows whether an operation is stuck on one large file or going through many
+                if let Some(path) = controller.current() {
+                    column =
+                        column.push(widget::text::caption(elide_path(&path, CURRENT_PATH_MAX)));
+                }
+                section = section.add(column);
             }
             children.push(section.into());
         }
 
         if !self.failed_operations.is_empty() {
+            children.push(
+                widget::button::standard(fl!("retry-all-failed"))
+                    .on_press(Message::RetryAllFailed)
+                    .into(),
+            );
             let mut section = widget::settings::section().title(fl!("failed"));
             for (_id, (op, controller, error)) in self.failed_operations.iter().rev() {
                 let progress = controller.progress();
@@ -2234,9 +5062,19 @@ impl App {
         }
 
         if !self.complete_operations.is_empty() {
+            children.push(
+                widget::button::standard(fl!("clear-history"))
+                    .on_press(Message::ClearHistory)
+                    .into(),
+            );
             let mut section = widget::settings::section().title(fl!("complete"));
-            for (_id, op) in self.complete_operations.iter().rev() {
-                section = section.add(widget::text::body(op.completed_text()));
+            for (_id, (op, changed)) in self.complete_operations.iter().rev() {
+                let mut column =
+                    widget::column::with_capacity(2).push(widget::text::body(op.completed_text()));
+                if !changed.is_empty() {
+                    column = column.push(widget::text::caption(changed_while_copying(changed)));
+                }
+                section = section.add(column);
             }
             children.push(section.into());
         }
@@ -2292,7 +5130,9 @@ impl App {
             }
             PreviewKind::Selected => {
                 if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
-                    if let Some(items) = tab.items_opt() {
+                    if let Some(view) = tab.selection_preview_view() {
+                        children.push(view);
+                    } else if let Some(items) = tab.items_opt() {
                         for item in items.iter() {
                             if item.selected {
                                 children.push(item.preview_view(
@@ -2368,7 +5208,9 @@ impl App {
             }
             PreviewKind::Selected => {
                 if let Some(tab) = self.tab_model2.data::<Tab2>(entity) {
-                    if let Some(items) = tab.items_opt() {
+                    if let Some(view) = tab.selection_preview_view() {
+                        children.push(view);
+                    } else if let Some(items) = tab.items_opt() {
                         for item in items.iter() {
                             if item.selected {
                                 children.push(item.preview_view(
@@ -2403,6 +5245,19 @@ impl App {
     }
 
     fn settings(&self) -> Element<Message> {
+        // Options kept per panel show the state of the active panel
+        let (middle_click_open_files, slideshow_interval) =
+            if self.active_panel == PaneType::LeftPane {
+                (
+                    self.config.tab_left.middle_click_open_files,
+                    self.config.tab_left.slideshow_interval,
+                )
+            } else {
+                (
+                    self.config.tab_right.middle_click_open_files,
+                    self.config.tab_right.slideshow_interval,
+                )
+            };
         // TODO: Should dialog be updated here too?
         widget::column::with_children(vec![
             widget::settings::section()
@@ -2438,10 +5293,150 @@ impl App {
                         Message::ShowEmbeddedTerminal,
                     ),
                 )
+                .add(
+                    widget::settings::item::builder(fl!("open-terminal-embedded")).toggler(
+                        self.config.open_terminal_embedded,
+                        Message::OpenTerminalEmbedded,
+                    ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("terminal-command"))
+                        .description(fl!("terminal-command-description"))
+                        .control(
+                            widget::text_input("", self.config.terminal_command.as_str())
+                                .on_input(Message::TerminalCommand),
+                        ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("terminal-close-on-exit")).toggler(
+                        self.config.terminal_close_on_exit,
+                        Message::TerminalCloseOnExit,
+                    ),
+                )
+                .add({
+                    let selected = TERMINAL_SCROLLBACK_LINES
+                        .iter()
+                        .position(|lines| *lines == self.config.terminal_scrollback);
+                    widget::settings::item::builder(fl!("terminal-scrollback")).control(
+                        widget::dropdown(&self.terminal_scrollback_choices, selected, |index| {
+                            Message::TerminalScrollback(TERMINAL_SCROLLBACK_LINES[index])
+                        }),
+                    )
+                })
+                .add({
+                    let selected = self
+                        .theme_names_dark
+                        .iter()
+                        .position(|name| *name == self.config.terminal_theme_dark);
+                    widget::settings::item::builder(fl!("terminal-theme-dark")).control(
+                        widget::dropdown(&self.theme_names_dark, selected, |index| {
+                            Message::TerminalThemeDark(index)
+                        }),
+                    )
+                })
+                .add({
+                    let selected = self
+                        .theme_names_light
+                        .iter()
+                        .position(|name| *name == self.config.terminal_theme_light);
+                    widget::settings::item::builder(fl!("terminal-theme-light")).control(
+                        widget::dropdown(&self.theme_names_light, selected, |index| {
+                            Message::TerminalThemeLight(index)
+                        }),
+                    )
+                })
                 .add(
                     widget::settings::item::builder(fl!("show-second-panel"))
                         .toggler(self.config.show_second_panel, Message::ShowSecondPanel),
                 )
+                .add(
+                    widget::settings::item::builder(fl!("follow-symlinks")).toggler(
+                        self.config.tab_left.follow_symlinks,
+                        Message::FollowSymlinks,
+                    ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("middle-click-open-files"))
+                        .description(fl!("middle-click-open-files-description"))
+                        .toggler(middle_click_open_files, Message::MiddleClickOpenFiles),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("respect-gitignore"))
+                        .description(fl!("respect-gitignore-description"))
+                        .toggler(self.respect_gitignore(), Message::RespectGitignore),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("color-by-type"))
+                        .description(fl!("color-by-type-description"))
+                        .toggler(self.config.tab_left.color_by_type, Message::ColorByType),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("show-parent-entry"))
+                        .description(fl!("show-parent-entry-description"))
+                        .toggler(
+                            self.config.tab_left.show_parent_entry,
+                            Message::ShowParentEntry,
+                        ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("wrap-navigation"))
+                        .description(fl!("wrap-navigation-description"))
+                        .toggler(
+                            self.config.tab_left.wrap_navigation,
+                            Message::WrapNavigation,
+                        ),
+                )
+                .add({
+                    let selected = SLIDESHOW_INTERVALS
+                        .iter()
+                        .position(|seconds| *seconds == slideshow_interval);
+                    widget::settings::item::builder(fl!("slideshow-interval")).control(
+                        widget::dropdown(&self.slideshow_intervals, selected, |index| {
+                            Message::SlideshowInterval(SLIDESHOW_INTERVALS[index])
+                        }),
+                    )
+                })
+                .add(
+                    widget::settings::item::builder(fl!("folder-views"))
+                        .description(fl!(
+                            "folder-views-description",
+                            folders = self.config.folder_views_left.len()
+                                + self.config.folder_views_right.len()
+                        ))
+                        .control(
+                            widget::button::standard(fl!("forget-folder-views")).on_press_maybe(
+                                (!self.config.folder_views_left.is_empty()
+                                    || !self.config.folder_views_right.is_empty())
+                                .then_some(Message::ClearFolderViews),
+                            ),
+                        ),
+                )
+                .into(),
+            SidebarBuiltin::all()
+                .iter()
+                .fold(
+                    widget::settings::section().title(fl!("sidebar")),
+                    |section, builtin| {
+                        let builtin = *builtin;
+                        let name = match builtin {
+                            SidebarBuiltin::Recents => fl!("recents"),
+                            SidebarBuiltin::Trash => fl!("trash"),
+                            SidebarBuiltin::Networks => fl!("networks"),
+                        };
+                        section.add(widget::settings::item::builder(name).toggler(
+                            !self.config.sidebar_hidden.contains(&builtin),
+                            move |show| Message::SidebarShow(builtin, show),
+                        ))
+                    },
+                )
+                .add(
+                    widget::settings::item::builder(fl!("sidebar-system-mounts"))
+                        .description(fl!("sidebar-system-mounts-description"))
+                        .toggler(
+                            self.config.sidebar_system_mounts,
+                            Message::SidebarSystemMounts,
+                        ),
+                )
                 .into(),
             widget::settings::section()
                 .title(fl!("features"))
@@ -2451,11 +5446,216 @@ impl App {
                         Message::QueueFileOperations,
                     ),
                 )
+                .add({
+                    let policies = ConflictPolicy::all();
+                    let selected = policies
+                        .iter()
+                        .position(|policy| *policy == self.config.conflict_policy);
+                    widget::settings::item::builder(fl!("conflict-policy")).control(
+                        widget::dropdown(&self.conflict_policies, selected, move |index| {
+                            Message::ConflictPolicy(policies[index])
+                        }),
+                    )
+                })
+                .add(
+                    widget::settings::item::builder(fl!("unique-name-template"))
+                        .description(
+                            if unique_name_template_valid(&self.config.unique_name_template) {
+                                fl!("unique-name-template-description")
+                            } else {
+                                fl!("unique-name-template-invalid")
+                            },
+                        )
+                        .control(
+                            widget::text_input(
+                                UNIQUE_NAME_TEMPLATE,
+                                self.config.unique_name_template.as_str(),
+                            )
+                            .on_input(Message::UniqueNameTemplate),
+                        ),
+                )
+                .add({
+                    let selected = UNIQUE_NAME_BASES
+                        .iter()
+                        .position(|base| *base == self.config.unique_name_base);
+                    widget::settings::item::builder(fl!("unique-name-base")).control(
+                        widget::dropdown(&self.unique_name_bases, selected, |index| {
+                            Message::UniqueNameBase(UNIQUE_NAME_BASES[index])
+                        }),
+                    )
+                })
+                .add({
+                    let actions = ArchiveOpenAction::all();
+                    let selected = actions
+                        .iter()
+                        .position(|action| *action == self.config.archive_open_action);
+                    widget::settings::item::builder(fl!("archive-open-action")).control(
+                        widget::dropdown(&self.archive_open_actions, selected, move |index| {
+                            Message::ArchiveOpenAction(actions[index])
+                        }),
+                    )
+                })
+                .add(
+                    widget::settings::item::builder(fl!("open-scripts-in-terminal"))
+                        .description(fl!("open-scripts-in-terminal-description"))
+                        .toggler(
+                            self.config.open_scripts_in_terminal,
+                            Message::OpenScriptsInTerminal,
+                        ),
+                )
+                .add({
+                    let selected = AUTO_REFRESH_INTERVALS
+                        .iter()
+                        .position(|seconds| *seconds == self.config.auto_refresh_interval);
+                    widget::settings::item::builder(fl!("auto-refresh-interval"))
+                        .description(fl!("auto-refresh-interval-description"))
+                        .control(widget::dropdown(
+                            &self.auto_refresh_intervals,
+                            selected,
+                            |index| Message::AutoRefreshInterval(AUTO_REFRESH_INTERVALS[index]),
+                        ))
+                })
+                .add({
+                    let choices = TabTitles::all();
+                    let selected = choices
+                        .iter()
+                        .position(|tab_titles| *tab_titles == self.config.tab_titles);
+                    widget::settings::item::builder(fl!("tab-titles")).control(widget::dropdown(
+                        &self.tab_titles,
+                        selected,
+                        move |index| Message::TabTitles(choices[index]),
+                    ))
+                })
+                .add({
+                    let notify_operations = NotifyOperations::all();
+                    let selected = notify_operations
+                        .iter()
+                        .position(|notify| *notify == self.config.notify_operations);
+                    widget::settings::item::builder(fl!("notify-operations")).control(
+                        widget::dropdown(&self.notify_operations, selected, move |index| {
+                            Message::NotifyOperations(notify_operations[index])
+                        }),
+                    )
+                })
+                .add(
+                    widget::settings::item::builder(fl!("notify-sound"))
+                        .toggler(self.config.notify_sound, Message::NotifySound),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("confirm-trash"))
+                        .toggler(self.config.confirm_trash, Message::ConfirmTrash),
+                )
+                .add({
+                    let choices = TrashUnavailable::all();
+                    let selected = choices
+                        .iter()
+                        .position(|choice| *choice == self.config.trash_unavailable);
+                    widget::settings::item::builder(fl!("trash-unavailable")).control(
+                        widget::dropdown(&self.trash_unavailable_choices, selected, move |index| {
+                            Message::TrashUnavailable(choices[index])
+                        }),
+                    )
+                })
+                .add(
+                    widget::settings::item::builder(fl!("confirm-permanent-delete")).toggler(
+                        self.config.confirm_permanent_delete,
+                        Message::ConfirmPermanentDelete,
+                    ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("single-instance"))
+                        .toggler(self.config.single_instance, Message::SingleInstance),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("allow-open-as-root"))
+                        .description(fl!("allow-open-as-root-description"))
+                        .toggler(self.config.allow_open_as_root, Message::AllowOpenAsRoot),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("read-only-mode"))
+                        .description(fl!("read-only-mode-description"))
+                        .toggler(self.config.read_only, Message::ReadOnly),
+                )
                 .into(),
         ])
         .into()
     }
 
+    /// Button showing all open tabs of a panel, for when they do not fit into the tab bar
+    fn tab_list(&self, pane_type: PaneType) -> Element<Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        // Message activating each tab, with its title, folder and whether it is active
+        let tabs: Vec<(Message, String, Option<PathBuf>, bool)> = if pane_type == PaneType::LeftPane
+        {
+            let active = self.tab_model1.active();
+            self.tab_model1
+                .iter()
+                .map(|entity| {
+                    (
+                        Message::TabActivateLeftEntity(entity),
+                        self.tab_model1.text(entity).unwrap_or_default().to_string(),
+                        self.tab_model1
+                            .data::<Tab1>(entity)
+                            .and_then(|tab| tab.location.path_opt().cloned()),
+                        entity == active,
+                    )
+                })
+                .collect()
+        } else {
+            let active = self.tab_model2.active();
+            self.tab_model2
+                .iter()
+                .map(|entity| {
+                    (
+                        Message::TabActivateRightEntity(entity),
+                        self.tab_model2.text(entity).unwrap_or_default().to_string(),
+                        self.tab_model2
+                            .data::<Tab2>(entity)
+                            .and_then(|tab| tab.location.path_opt().cloned()),
+                        entity == active,
+                    )
+                })
+                .collect()
+        };
+
+        let open = self.tab_list_opt == Some(pane_type);
+        let button = widget::tooltip(
+            widget::button::icon(widget::icon::from_name("view-list-symbolic"))
+                .on_press(Message::TabList(if open { None } else { Some(pane_type) }))
+                .padding(space_xxs),
+            widget::text::body(fl!("all-tabs")),
+            widget::tooltip::Position::Bottom,
+        );
+        let mut popover = widget::popover(button).position(widget::popover::Position::Bottom);
+        if open {
+            let mut column = widget::column::with_capacity(tabs.len()).padding(space_xxs);
+            for (message, title, path_opt, active) in tabs {
+                let mut text = widget::column::with_capacity(2).push(widget::text::body(title));
+                if let Some(path) = path_opt {
+                    text = text.push(widget::text::caption(path.display().to_string()));
+                }
+                column = column.push(
+                    widget::button::custom(text)
+                        .class(if active {
+                            theme::Button::Standard
+                        } else {
+                            theme::Button::HeaderBar
+                        })
+                        .on_press(message)
+                        .padding(space_xxs)
+                        .width(Length::Fill),
+                );
+            }
+            popover = popover.popup(
+                widget::container(widget::scrollable(column))
+                    .class(theme::Container::Dropdown)
+                    .max_width(400.0)
+                    .max_height(480.0),
+            );
+        }
+        popover.into()
+    }
+
     fn view_pane_content(
         &self,
         pane: pane_grid::Pane,
@@ -2463,7 +5663,10 @@ impl App {
         _size: Size,
     ) -> Element<Message> {
         let cosmic_theme::Spacing {
-            space_xxs, space_s, ..
+            space_xxs,
+            space_xs,
+            space_s,
+            ..
         } = theme::active().cosmic().spacing;
         let pane_type = self.pane_model.type_by_pane[&pane];
         if pane_type == PaneType::LeftPane || pane_type == PaneType::RightPane {
@@ -2486,17 +5689,25 @@ impl App {
             if pane_type == PaneType::LeftPane {
                 tab_column = tab_column.push(
                     widget::container(
-                        widget::tab_bar::horizontal(&self.tab_model1)
-                            .button_height(32)
-                            .button_spacing(space_xxs)
-                            .on_activate(|entity| Message::TabActivateLeftEntity(entity))
-                            .on_close(|entity| Message::TabCloseLeft(Some(entity)))
-                            .drag_id(self.tab_drag_id_left)
-                            .on_dnd_enter(|entity, _| Message::DndEnterTabLeft(entity))
-                            .on_dnd_leave(|_| Message::DndExitTabLeft)
-                            .on_dnd_drop(|entity, data, action| {
-                                Message::DndDropTabLeft(entity, data, action)
-                            }),
+                        widget::row::with_children(vec![
+                            widget::container(
+                                widget::tab_bar::horizontal(&self.tab_model1)
+                                    .button_height(32)
+                                    .button_spacing(space_xxs)
+                                    .on_activate(|entity| Message::TabActivateLeftEntity(entity))
+                                    .on_close(|entity| Message::TabCloseLeft(Some(entity)))
+                                    .drag_id(self.tab_drag_id_left)
+                                    .on_dnd_enter(|entity, _| Message::DndEnterTabLeft(entity))
+                                    .on_dnd_leave(|_| Message::DndExitTabLeft)
+                                    .on_dnd_drop(|entity, data, action| {
+                                        Message::DndDropTabLeft(entity, data, action)
+                                    }),
+                            )
+                            .width(Length::Fill)
+                            .into(),
+                            self.tab_list(PaneType::LeftPane),
+                        ])
+                        .align_y(Alignment::Center),
                     )
                     .class(style::Container::Background)
                     .width(Length::Fill)
@@ -2505,9 +5716,18 @@ impl App {
                 let entity_left = self.tab_model1.active();
                 if let Some(tab) = self.tab_model1.data::<Tab1>(entity_left) {
                     let tab_view_left = tab
-                        .view(&self.key_binds)
+                        .view(&self.key_binds, &self.custom_actions)
                         .map(move |message| Message::TabMessage(Some(entity_left), message));
-                    tab_column = tab_column.push(tab_view_left)
+                    tab_column = tab_column.push(tab_view_left);
+                    tab_column = tab_column.push(
+                        widget::container(widget::row::with_children(vec![
+                            widget::text::caption(tab.summary()).into(),
+                            widget::horizontal_space().into(),
+                            widget::text::caption(tab.disk_space()).into(),
+                        ]))
+                        .width(Length::Fill)
+                        .padding([space_xxs, space_xs]),
+                    );
                 }
                 // The toaster is added on top of an empty element to ensure that it does not override context menus
                 tab_column = tab_column.push(widget::toaster(
@@ -2517,17 +5737,25 @@ impl App {
             } else if pane_type == PaneType::RightPane {
                 tab_column = tab_column.push(
                     widget::container(
-                        widget::tab_bar::horizontal(&self.tab_model2)
-                            .button_height(32)
-                            .button_spacing(space_xxs)
-                            .on_activate(|entity| Message::TabActivateRightEntity(entity))
-                            .on_close(|entity| Message::TabCloseRight(Some(entity)))
-                            .drag_id(self.tab_drag_id_right)
-                            .on_dnd_enter(|entity, _| Message::DndEnterTabRight(entity))
-                            .on_dnd_leave(|_| Message::DndExitTabRight)
-                            .on_dnd_drop(|entity, data, action| {
-                                Message::DndDropTabRight(entity, data, action)
-                            }),
+                        widget::row::with_children(vec![
+                            widget::container(
+                                widget::tab_bar::horizontal(&self.tab_model2)
+                                    .button_height(32)
+                                    .button_spacing(space_xxs)
+                                    .on_activate(|entity| Message::TabActivateRightEntity(entity))
+                                    .on_close(|entity| Message::TabCloseRight(Some(entity)))
+                                    .drag_id(self.tab_drag_id_right)
+                                    .on_dnd_enter(|entity, _| Message::DndEnterTabRight(entity))
+                                    .on_dnd_leave(|_| Message::DndExitTabRight)
+                                    .on_dnd_drop(|entity, data, action| {
+                                        Message::DndDropTabRight(entity, data, action)
+                                    }),
+                            )
+                            .width(Length::Fill)
+                            .into(),
+                            self.tab_list(PaneType::RightPane),
+                        ])
+                        .align_y(Alignment::Center),
                     )
                     .class(style::Container::Background)
                     .padding([0, space_s]),
@@ -2535,9 +5763,18 @@ impl App {
                 let entity_right = self.tab_model2.active();
                 if let Some(tab) = self.tab_model2.data::<Tab2>(entity_right) {
                     let tab_view_right = tab
-                        .view(&self.key_binds)
+                        .view(&self.key_binds, &self.custom_actions)
                         .map(move |message| Message::TabMessageRight(Some(entity_right), message));
-                    tab_column = tab_column.push(tab_view_right)
+                    tab_column = tab_column.push(tab_view_right);
+                    tab_column = tab_column.push(
+                        widget::container(widget::row::with_children(vec![
+                            widget::text::caption(tab.summary()).into(),
+                            widget::horizontal_space().into(),
+                            widget::text::caption(tab.disk_space()).into(),
+                        ]))
+                        .width(Length::Fill)
+                        .padding([space_xxs, space_xs]),
+                    );
                 }
                 // The toaster is added on top of an empty element to ensure that it does not override context menus
                 tab_column = tab_column.push(widget::toaster(
@@ -2563,53 +5800,20 @@ impl App {
             })
             .into()
         } else if pane_type == PaneType::ButtonPane {
-            let tab_column = widget::row::with_children(vec![
-                widget::button::text(fl!("f2-rename"))
-                    .on_press(Message::F2Rename)
-                    .width(cosmic::iced::Length::Shrink)
-                    .into(),
-                widget::horizontal_space().into(),
-                widget::button::text(fl!("f3-view"))
-                    .on_press(Message::F3View)
-                    .width(cosmic::iced::Length::Shrink)
-                    .into(),
-                widget::horizontal_space().into(),
-                widget::button::text(fl!("f4-edit"))
-                    .on_press(Message::F4Edit)
-                    .width(cosmic::iced::Length::Shrink)
-                    .into(),
-                widget::horizontal_space().into(),
-                widget::button::text(fl!("f5-copy"))
-                    .on_press(Message::F5Copy)
-                    .width(cosmic::iced::Length::Shrink)
-                    .into(),
-                widget::horizontal_space().into(),
-                widget::button::text(fl!("f6-move"))
-                    .on_press(Message::F6Move)
-                    .width(cosmic::iced::Length::Shrink)
-                    .into(),
-                widget::horizontal_space().into(),
-                widget::button::text(fl!("f7-mkdir"))
-                    .on_press(Message::F7Mkdir)
-                    .width(cosmic::iced::Length::Shrink)
-                    .into(),
-                widget::horizontal_space().into(),
-                widget::button::text(fl!("f8-delete"))
-                    .on_press(Message::F8Delete)
-                    .width(cosmic::iced::Length::Shrink)
-                    .into(),
-                widget::horizontal_space().into(),
-                widget::button::text(fl!("f9-Term"))
-                    .on_press(Message::F9Terminal)
-                    .width(cosmic::iced::Length::Shrink)
-                    .into(),
-                widget::horizontal_space().into(),
-                widget::button::text(fl!("f10-quit"))
-                    .on_press(Message::F10Quit)
-                    .width(cosmic::iced::Length::Shrink)
-                    .into(),
-            ])
-            .width(Length::Fill);
+            let mut children = Vec::new();
+            for (action, label) in button_row(&self.config.button_row, &self.key_binds) {
+                if !children.is_empty() {
+                    children.push(widget::horizontal_space().into());
+                }
+                let enabled = !(self.config.read_only && action.changes_files());
+                children.push(
+                    widget::button::text(label)
+                        .on_press_maybe(enabled.then(|| action.message(None)))
+                        .width(cosmic::iced::Length::Shrink)
+                        .into(),
+                );
+            }
+            let tab_column = widget::row::with_children(children).width(Length::Fill);
             return tab_column.into();
         } else {
             // Terminal
@@ -2671,127 +5875,71 @@ impl App {
     }
 
     fn pane_by_type(&self, panetype: PaneType) -> pane_grid::Pane {
-        if self.config.show_button_row
-            && self.config.show_embedded_terminal
-            && self.config.show_second_panel
-        {
-            // full window
-            match panetype {
-                PaneType::LeftPane => return self.pane_model.panes[3],
-                PaneType::RightPane => return self.pane_model.panes[2],
-                PaneType::TerminalPane => return self.pane_model.panes[0],
-                PaneType::ButtonPane => return self.pane_model.panes[3],
-            }
-        } else if self.config.show_button_row
-            && self.config.show_embedded_terminal
-            && !self.config.show_second_panel
-        {
-            // full window
-            match panetype {
-                PaneType::LeftPane => return self.pane_model.panes[2],
-                PaneType::RightPane => return self.pane_model.panes[2],
-                PaneType::TerminalPane => return self.pane_model.panes[0],
-                PaneType::ButtonPane => return self.pane_model.panes[2],
-            }
-        } else if !self.config.show_button_row
-            && self.config.show_embedded_terminal
-            && self.config.show_second_panel
-        {
-            match panetype {
-                PaneType::LeftPane => return self.pane_model.panes[2],
-                PaneType::RightPane => return self.pane_model.panes[1],
-                PaneType::TerminalPane => return self.pane_model.panes[0],
-                PaneType::ButtonPane => return self.pane_model.panes[2],
-            }
-        } else if self.config.show_button_row
-            && !self.config.show_embedded_terminal
-            && self.config.show_second_panel
-        {
-            match panetype {
-                PaneType::LeftPane => return self.pane_model.panes[0],
-                PaneType::RightPane => return self.pane_model.panes[2],
-                PaneType::TerminalPane => return self.pane_model.panes[1],
-                PaneType::ButtonPane => return self.pane_model.panes[0],
-            }
-        } else if !self.config.show_button_row
-            && self.config.show_embedded_terminal
-            && !self.config.show_second_panel
-        {
-            match panetype {
-                PaneType::LeftPane => return self.pane_model.panes[1],
-                PaneType::RightPane => return self.pane_model.panes[1],
-                PaneType::TerminalPane => return self.pane_model.panes[0],
-                PaneType::ButtonPane => return self.pane_model.panes[1],
-            }
-        } else if self.config.show_button_row
-            && !self.config.show_embedded_terminal
-            && !self.config.show_second_panel
-        {
-            match panetype {
-                PaneType::LeftPane => return self.pane_model.panes[0],
-                PaneType::RightPane => return self.pane_model.panes[0],
-                PaneType::TerminalPane => return self.pane_model.panes[0],
-                PaneType::ButtonPane => return self.pane_model.panes[0],
-            }
-        } else if !self.config.show_button_row
-            && !self.config.show_embedded_terminal
-            && self.config.show_second_panel
-        {
-            match panetype {
-                PaneType::LeftPane => return self.pane_model.panes[0],
-                PaneType::RightPane => return self.pane_model.panes[1],
-                PaneType::TerminalPane => return self.pane_model.panes[0],
-                PaneType::ButtonPane => return self.pane_model.panes[0],
-            }
-        } else {
-            match panetype {
-                PaneType::LeftPane => return self.pane_model.panes[0],
-                PaneType::RightPane => return self.pane_model.panes[0],
-                PaneType::TerminalPane => return self.pane_model.panes[0],
-                PaneType::ButtonPane => return self.pane_model.panes[0],
-            }
+        // Panes that are not shown resolve to the left panel
+        self.pane_model
+            .pane_by_type
+            .get(&panetype)
+            .or_else(|| self.pane_model.pane_by_type.get(&PaneType::LeftPane))
+            .copied()
+            .unwrap_or(self.pane_model.first_pane)
+    }
+
+    fn terminal_config(&self) -> term::Config {
+        term::Config {
+            scrolling_history: self.config.terminal_scrollback,
+            ..Default::default()
         }
     }
 
+    /// Open a shell in the embedded terminal, in `shell_dir_opt` or the folder it was started in,
+    /// or run the arguments of `command_opt` in its folder
     fn create_and_focus_new_terminal(
         &mut self,
         pane: pane_grid::Pane,
         //profile_id_opt: Option<ProfileId>,
+        command_opt: Option<(Vec<OsString>, PathBuf)>,
+        shell_dir_opt: Option<PathBuf>,
     ) -> Task<Message> {
         self.pane_model.focus = pane;
         match &self.term_event_tx_opt {
             Some(term_event_tx) => {
-                let colors = match self.config.color_scheme_kind() {
-                    ColorSchemeKind::Dark => self
-                        .themes
-                        .get(&(config::COSMIC_THEME_DARK.to_string(), ColorSchemeKind::Dark)),
-                    ColorSchemeKind::Light => self.themes.get(&(
-                        config::COSMIC_THEME_LIGHT.to_string(),
-                        ColorSchemeKind::Light,
-                    )),
-                };
-                match colors {
+                match self.terminal_colors() {
                     Some(colors) => {
                         let current_pane = pane;
                         // Use the startup options, profile options, or defaults
-                        let (options, tab_title_override) =
-                            (alacritty_terminal::tty::Options::default(), None);
+                        let (mut options, tab_title_override) = (
+                            alacritty_terminal::tty::Options {
+                                working_directory: shell_dir_opt,
+                                ..Default::default()
+                            },
+                            None,
+                        );
+                        let mut description_opt = None;
+                        if let Some((args, dir)) = command_opt {
+                            let Some((command_options, description)) =
+                                terminal_command_options(&args, dir)
+                            else {
+                                return Task::none();
+                            };
+                            options = command_options;
+                            description_opt = Some(description);
+                        }
                         match crate::terminal::Terminal::new(
                             current_pane,
                             Entity::default(),
                             term_event_tx.clone(),
-                            term::Config {
-                                ..Default::default()
-                            },
+                            self.terminal_config(),
                             options,
                             //&self.config,
-                            *colors,
+                            colors,
                             //profile_id_opt,
                             tab_title_override,
                         ) {
                             Ok(terminal) => {
                                 //terminal.set_config(&self.config, &self.themes);
                                 self.terminal = Some(Mutex::new(terminal));
+                                self.terminal_command_opt =
+                                    description_opt.map(|description| (description, None));
                                 return Task::none();
                             }
                             Err(err) => {
@@ -2814,6 +5962,146 @@ impl App {
         }
     }
 
+    /// Run the configured command for the selection in the embedded terminal, instead of a shell
+    fn run_terminal_command(&mut self, entity_opt: Option<Entity>) -> Task<Message> {
+        let custom_action = config::CustomAction {
+            label: fl!("terminal-command"),
+            pattern: String::new(),
+            command: self.config.terminal_command.clone(),
+            terminal: true,
+        };
+        let parsed = match custom_action.parse() {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!("invalid terminal command: {}", err);
+                return Task::none();
+            }
+        };
+        let dir_opt = match self.active_panel {
+            PaneType::LeftPane => active_tab_path::<LeftPanel>(&self.tab_model1),
+            _ => active_tab_path::<RightPanel>(&self.tab_model2),
+        };
+        let Some(dir) = dir_opt else {
+            return Task::none();
+        };
+        let args = parsed.args(&self.selected_paths(entity_opt), &dir);
+        let pane = self.pane_model.pane_by_type[&PaneType::TerminalPane];
+        self.create_and_focus_new_terminal(pane, Some((args, dir)), None)
+    }
+
+    /// Replace the embedded terminal with a shell if it holds the output of a command that exited
+    fn terminal_shell(&mut self) {
+        if matches!(self.terminal_command_opt, Some((_, Some(_)))) {
+            let pane = self.pane_model.pane_by_type[&PaneType::TerminalPane];
+            let _ = self.create_and_focus_new_terminal(pane, None, None);
+        }
+    }
+
+    /// Run a command in a terminal so its output can be seen, preferring the embedded terminal
+    fn run_in_terminal(&mut self, args: &[OsString], dir: &Path) -> Task<Message> {
+        if self.show_embedded_terminal {
+            self.terminal_shell();
+            if let Some(terminalmutex) = &self.terminal {
+                let args_opt: Option<Vec<&str>> = args.iter().map(|arg| arg.to_str()).collect();
+                let line_opt = dir.to_str().zip(args_opt).and_then(|(dir, args)| {
+                    let dir = shlex::try_quote(dir).ok()?;
+                    let command = shlex::try_join(args).ok()?;
+                    Some(format!("cd {} && {}\r", dir, command))
+                });
+                match line_opt {
+                    Some(line) => {
+                        if let Ok(terminal) = terminalmutex.lock() {
+                            terminal.input_scroll(line.into_bytes());
+                        }
+                        self.pane_model.focus =
+                            self.pane_model.pane_by_type[&PaneType::TerminalPane];
+                    }
+                    None => log::warn!("failed to quote {:?} for the terminal", args),
+                }
+                return Task::none();
+            }
+        }
+
+        match self
+            .mime_app_cache
+            .terminal()
+            .and_then(|terminal| terminal.command(None))
+        {
+            Some(mut command) => {
+                //TODO: not all terminals take the command to run after -e
+                command.arg("-e").args(args).current_dir(dir);
+                if let Err(err) = spawn_detached(&mut command) {
+                    log::warn!("failed to run {:?} in terminal: {}", args, err);
+                }
+            }
+            None => log::warn!("failed to find terminal to run {:?}", args),
+        }
+        Task::none()
+    }
+
+    /// Write the config to a settings file, or apply one on top of it
+    fn settings_file(&mut self, export: bool, path: PathBuf) -> Task<Message> {
+        if export {
+            if let Err(err) = self
+                .config
+                .export()
+                .and_then(|text| fs::write(&path, text).map_err(err_str))
+            {
+                self.dialog_pages.push_back(DialogPage::SettingsReport {
+                    title: fl!("export-settings-failed"),
+                    lines: vec![format!("{}: {}", path.display(), err)],
+                });
+            }
+            return Task::none();
+        }
+
+        let (config, warnings) = match fs::read_to_string(&path)
+            .map_err(err_str)
+            .and_then(|text| self.config.import(&text))
+        {
+            Ok(ok) => ok,
+            Err(err) => {
+                self.dialog_pages.push_back(DialogPage::SettingsReport {
+                    title: fl!("import-settings-failed"),
+                    lines: vec![format!("{}: {}", path.display(), err)],
+                });
+                return Task::none();
+            }
+        };
+        for warning in warnings.iter() {
+            log::warn!("importing settings from {:?}: {}", path, warning);
+        }
+        if !warnings.is_empty() {
+            self.dialog_pages.push_back(DialogPage::SettingsReport {
+                title: fl!("import-settings-warnings"),
+                lines: warnings,
+            });
+        }
+        if let Some(config_handler) = &self.config_handler {
+            if let Err(err) = config.write_entry(config_handler) {
+                log::warn!("failed to save imported settings: {}", err);
+            }
+        }
+        self.config = config;
+        self.update_config()
+    }
+
+    /// Colors of the chosen terminal color scheme, the COSMIC one is used if it is missing
+    fn terminal_colors(&self) -> Option<TermColors> {
+        let kind = self.config.color_scheme_kind();
+        let (name, fallback) = match kind {
+            ColorSchemeKind::Dark => (&self.config.terminal_theme_dark, config::COSMIC_THEME_DARK),
+            ColorSchemeKind::Light => (
+                &self.config.terminal_theme_light,
+                config::COSMIC_THEME_LIGHT,
+            ),
+        };
+        self.themes
+            .get(&(name.clone(), kind))
+            .or_else(|| self.themes.get(&(fallback.to_string(), kind)))
+            .copied()
+    }
+
     fn update_color_schemes(&mut self) {
         self.themes = crate::terminal_theme::terminal_themes();
         for &color_scheme_kind in &[ColorSchemeKind::Dark, ColorSchemeKind::Light] {
@@ -2859,6 +6147,13 @@ impl App {
             .sort_by(|a, b| LANGUAGE_SORTER.compare(a, b));
         self.theme_names_light
             .sort_by(|a, b| LANGUAGE_SORTER.compare(a, b));
+
+        // The embedded terminal follows changes of the chosen scheme and of the app theme
+        if let Some(colors) = self.terminal_colors() {
+            if let Some(terminal) = &self.terminal {
+                terminal.lock().unwrap().set_colors(colors);
+            }
+        }
     }
 }
 
@@ -2885,7 +6180,8 @@ impl Application for App {
     }
 
     /// Creates the application, and optionally emits command on initialize.
-    fn init(mut core: Core, flags: Self::Flags) -> (Self, Task<Self::Message>) {
+    fn init(mut core: Core, mut flags: Self::Flags) -> (Self, Task<Self::Message>) {
+        flags.layout_overrides.apply(&mut flags.config);
         core.window.context_is_overlay = false;
         match flags.mode {
             Mode::App => {
@@ -2903,6 +6199,83 @@ impl Application for App {
         }
 
         let app_themes = vec![fl!("match-desktop"), fl!("dark"), fl!("light")];
+        let conflict_policies = ConflictPolicy::all()
+            .iter()
+            .map(|policy| match policy {
+                ConflictPolicy::Ask => fl!("conflict-ask"),
+                ConflictPolicy::Overwrite => fl!("conflict-overwrite"),
+                ConflictPolicy::Skip => fl!("conflict-skip"),
+                ConflictPolicy::Rename => fl!("conflict-rename"),
+                ConflictPolicy::Newer => fl!("conflict-newer"),
+            })
+            .collect();
+        let desktop_grid_flows = DesktopGridFlow::all()
+            .iter()
+            .map(|flow| match flow {
+                DesktopGridFlow::Columns => fl!("grid-flow-columns"),
+                DesktopGridFlow::Rows => fl!("grid-flow-rows"),
+            })
+            .collect();
+        let desktop_grid_origins = DesktopGridOrigin::all()
+            .iter()
+            .map(|origin| match origin {
+                DesktopGridOrigin::TopLeft => fl!("grid-origin-top-left"),
+                DesktopGridOrigin::TopRight => fl!("grid-origin-top-right"),
+                DesktopGridOrigin::BottomLeft => fl!("grid-origin-bottom-left"),
+                DesktopGridOrigin::BottomRight => fl!("grid-origin-bottom-right"),
+            })
+            .collect();
+        let notify_operations = NotifyOperations::all()
+            .iter()
+            .map(|notify| match notify {
+                NotifyOperations::Completion => fl!("notify-completion"),
+                NotifyOperations::Failure => fl!("notify-failure"),
+                NotifyOperations::Never => fl!("notify-never"),
+            })
+            .collect();
+        let slideshow_intervals = SLIDESHOW_INTERVALS
+            .iter()
+            .map(|seconds| fl!("seconds", seconds = *seconds))
+            .collect();
+        let auto_refresh_intervals = AUTO_REFRESH_INTERVALS
+            .iter()
+            .map(|seconds| match seconds {
+                0 => fl!("auto-refresh-never"),
+                _ => fl!("seconds", seconds = *seconds),
+            })
+            .collect();
+        let archive_open_actions = ArchiveOpenAction::all()
+            .iter()
+            .map(|action| match action {
+                ArchiveOpenAction::Open => fl!("archive-open"),
+                ArchiveOpenAction::ExtractHere => fl!("archive-open-extract-here"),
+                ArchiveOpenAction::ExtractToOtherPanel => fl!("archive-open-extract-other"),
+            })
+            .collect();
+        let tab_titles = TabTitles::all()
+            .iter()
+            .map(|tab_titles| match tab_titles {
+                TabTitles::FolderName => fl!("tab-titles-folder-name"),
+                TabTitles::Distinct => fl!("tab-titles-distinct"),
+                TabTitles::FullPath => fl!("tab-titles-full-path"),
+            })
+            .collect();
+        let terminal_scrollback_choices = TERMINAL_SCROLLBACK_LINES
+            .iter()
+            .map(|lines| fl!("terminal-scrollback-lines", lines = *lines))
+            .collect();
+        let trash_unavailable_choices = TrashUnavailable::all()
+            .iter()
+            .map(|choice| match choice {
+                TrashUnavailable::Ask => fl!("trash-unavailable-ask"),
+                TrashUnavailable::CreateTrash => fl!("trash-unavailable-create"),
+                TrashUnavailable::Delete => fl!("trash-unavailable-delete"),
+            })
+            .collect();
+        let unique_name_bases = UNIQUE_NAME_BASES
+            .iter()
+            .map(|base| base.to_string())
+            .collect();
 
         let key_binds = key_binds(&tab1::Mode::App);
         let key_binds_terminal = key_binds_terminal();
@@ -2927,14 +6300,30 @@ impl Application for App {
             pane_model,
             term_event_tx_opt,
             terminal,
+            terminal_command_opt: None,
             active_panel: PaneType::LeftPane,
             show_button_row: flags.config.show_button_row,
             show_embedded_terminal: flags.config.show_embedded_terminal,
             show_second_panel: flags.config.show_second_panel,
+            mirror_navigation: false,
             config_handler: flags.config_handler,
             config: flags.config.clone(),
+            layout_overrides: flags.layout_overrides,
             mode: flags.mode,
             app_themes,
+            archive_open_actions,
+            auto_refresh_intervals,
+            conflict_policies,
+            custom_actions: Vec::new(),
+            desktop_grid_flows,
+            desktop_grid_origins,
+            notify_operations,
+            slideshow_intervals,
+            tab_titles,
+            terminal_scrollback_choices,
+            trash_unavailable_choices,
+            unique_name_bases,
+            palette_recent: Vec::new(),
             themes: HashMap::new(),
             theme_names_dark: Vec::new(),
             theme_names_light: Vec::new(),
@@ -2947,6 +6336,7 @@ impl Application for App {
             mime_app_cache: mime_app::MimeAppCache::new(),
             modifiers: Modifiers::empty(),
             mounter_items: HashMap::new(),
+            mounter_pending: HashSet::new(),
             network_drive_connecting: None,
             network_drive_input: String::new(),
             #[cfg(feature = "notify")]
@@ -2958,8 +6348,16 @@ impl Application for App {
             progress_operations: BTreeSet::new(),
             complete_operations: BTreeMap::new(),
             failed_operations: BTreeMap::new(),
+            undo_history: Vec::new(),
+            redo_history: Vec::new(),
+            undo_operation_ids: BTreeSet::new(),
+            redo_operation_ids: BTreeSet::new(),
+            retry_operation_ids: BTreeMap::new(),
+            dialog_new_folder_ids: BTreeMap::new(),
+            read_only_refused: BTreeSet::new(),
             search_id: widget::Id::unique(),
             size: None,
+            staged_archive_opt: None,
             #[cfg(feature = "wayland")]
             surface_ids: HashMap::new(),
             #[cfg(feature = "wayland")]
@@ -2978,6 +6376,7 @@ impl Application for App {
             tab_dnd_hover_right: None,
             panegrid_drag_id: DragId::new(),
             term_drag_id: DragId::new(),
+            tab_list_opt: None,
             nav_drag_id: DragId::new(),
             tab_drag_id_left: DragId::new(),
             tab_drag_id_right: DragId::new(),
@@ -3023,7 +6422,11 @@ impl Application for App {
                     }
                 }
             }
-            commands.push(app.open_tab(location, true, None));
+            commands.push(app.open_tab_right(
+                convert_location1_to_location2(&location),
+                true,
+                None,
+            ));
         }
         // restore previously opened tabs
         for i in 0..app.config.paths_left.len() {
@@ -3054,6 +6457,19 @@ impl Application for App {
                 commands.push(app.open_tab_right(Location2::Path(home_dir()), true, None));
             }
         }
+        // Restore the focused pane if it is still shown
+        if let Some(pane) = app.pane_model.pane_by_type.get(&app.config.pane_focus) {
+            app.pane_model.focus = *pane;
+            if matches!(
+                app.config.pane_focus,
+                PaneType::LeftPane | PaneType::RightPane
+            ) {
+                app.active_panel = app.config.pane_focus;
+            }
+        }
+        if let Some(path) = flags.reveal_opt {
+            commands.push(app.reveal(path));
+        }
         app.core.nav_bar_set_toggled(false);
         (app, Task::batch(commands))
     }
@@ -3101,7 +6517,23 @@ impl Application for App {
         &self,
         entity: widget::nav_bar::Id,
     ) -> Option<Vec<widget::menu::Tree<cosmic::app::Message<Self::Message>>>> {
+        if self.nav_model.data::<StagedArchiveItem>(entity).is_some() {
+            let items = vec![
+                cosmic::widget::menu::Item::Button(
+                    fl!("create-archive"),
+                    None,
+                    NavMenuAction::FinalizeArchive,
+                ),
+                cosmic::widget::menu::Item::Button(
+                    fl!("discard-archive"),
+                    None,
+                    NavMenuAction::DiscardArchive,
+                ),
+            ];
+            return Some(cosmic::widget::menu::items(&HashMap::new(), items));
+        }
         let favorite_index_opt = self.nav_model.data::<FavoriteIndex>(entity);
+        let builtin_opt = self.nav_model.data::<SidebarBuiltin>(entity);
         let location_opt = self.nav_model.data::<Location1>(entity);
         if self.active_panel == PaneType::RightPane && location_opt.is_some() {
             let location_opt2;
@@ -3146,13 +6578,34 @@ impl Application for App {
                 NavMenuAction::Preview(entity),
             ));
             items.push(cosmic::widget::menu::Item::Divider);
-            if favorite_index_opt.is_some() {
+            if let Some(FavoriteIndex(favorite_i)) = favorite_index_opt {
+                if *favorite_i > 0 {
+                    items.push(cosmic::widget::menu::Item::Button(
+                        fl!("move-up"),
+                        None,
+                        NavMenuAction::MoveFavoriteUp(entity),
+                    ));
+                }
+                if favorite_i + 1 < self.config.favorites.len() {
+                    items.push(cosmic::widget::menu::Item::Button(
+                        fl!("move-down"),
+                        None,
+                        NavMenuAction::MoveFavoriteDown(entity),
+                    ));
+                }
                 items.push(cosmic::widget::menu::Item::Button(
                     fl!("remove-from-sidebar"),
                     None,
                     NavMenuAction::RemoveFromSidebar(entity),
                 ));
             }
+            if builtin_opt.is_some() {
+                items.push(cosmic::widget::menu::Item::Button(
+                    fl!("hide-from-sidebar"),
+                    None,
+                    NavMenuAction::HideFromSidebar(entity),
+                ));
+            }
             if matches!(location_opt, Some(Location1::Trash)) {
                 items.push(cosmic::widget::menu::Item::Button(
                     fl!("empty-trash"),
@@ -3198,13 +6651,34 @@ impl Application for App {
                 NavMenuAction::Preview(entity),
             ));
             items.push(cosmic::widget::menu::Item::Divider);
-            if favorite_index_opt.is_some() {
+            if let Some(FavoriteIndex(favorite_i)) = favorite_index_opt {
+                if *favorite_i > 0 {
+                    items.push(cosmic::widget::menu::Item::Button(
+                        fl!("move-up"),
+                        None,
+                        NavMenuAction::MoveFavoriteUp(entity),
+                    ));
+                }
+                if favorite_i + 1 < self.config.favorites.len() {
+                    items.push(cosmic::widget::menu::Item::Button(
+                        fl!("move-down"),
+                        None,
+                        NavMenuAction::MoveFavoriteDown(entity),
+                    ));
+                }
                 items.push(cosmic::widget::menu::Item::Button(
                     fl!("remove-from-sidebar"),
                     None,
                     NavMenuAction::RemoveFromSidebar(entity),
                 ));
             }
+            if builtin_opt.is_some() {
+                items.push(cosmic::widget::menu::Item::Button(
+                    fl!("hide-from-sidebar"),
+                    None,
+                    NavMenuAction::HideFromSidebar(entity),
+                ));
+            }
             if matches!(location_opt, Some(Location1::Trash)) {
                 items.push(cosmic::widget::menu::Item::Button(
                     fl!("empty-trash"),
@@ -3225,26 +6699,23 @@ impl Application for App {
     }
 
     fn on_nav_select(&mut self, entity: Entity) -> Task<Self::Message> {
+        if self.nav_model.data::<StagedArchiveItem>(entity).is_some() {
+            let paths = self.selected_paths(None);
+            self.stage_archive_paths(paths);
+            return Task::none();
+        }
+
         self.nav_model.activate(entity);
-        if let Some(location) = self.nav_model.data::<Location1>(entity) {
-            if self.active_panel == PaneType::LeftPane {
-                let message = Message::TabMessage(None, tab1::Message::Location(location.clone()));
-                return self.update(message);
-            } else {
-                let location2;
-                if let Some(path) = location.path_opt() {
-                    location2 = Location2::Path(path.to_owned());
-                    let message =
-                        Message::TabMessageRight(None, tab2::Message::Location(location2.clone()));
-                    return self.update(message);
-                }
-            }
+        if let Some(location) = self.nav_model.data::<Location1>(entity).cloned() {
+            return match self.active_panel {
+                PaneType::LeftPane => self.open_nav_location::<LeftPanel>(&location),
+                _ => self.open_nav_location::<RightPanel>(&location),
+            };
         }
 
-        if let Some(data) = self.nav_model.data::<MounterData>(entity) {
-            if let Some(mounter) = MOUNTERS.get(&data.0) {
-                return mounter.mount(data.1.clone()).map(|_| message::none());
-            }
+        if let Some(MounterData(mounter_key, item)) = self.nav_model.data::<MounterData>(entity) {
+            let (mounter_key, item) = (*mounter_key, item.clone());
+            return self.mount_item(mounter_key, item, true);
         }
         Task::none()
     }
@@ -3268,10 +6739,35 @@ impl Application for App {
     }
 
     fn on_escape(&mut self) -> Task<Self::Message> {
+        // Leave the gallery first, as it is shown over dialogs and context panes
+        if self.active_panel == PaneType::LeftPane {
+            let entity = self.tab_model1.active();
+            if let Some(tab) = self
+                .tab_model1
+                .data_mut::<Tab1>(entity)
+                .filter(|tab| tab.gallery)
+            {
+                tab.set_gallery(false);
+                return Task::none();
+            }
+        } else {
+            let entity = self.tab_model2.active();
+            if let Some(tab) = self
+                .tab_model2
+                .data_mut::<Tab2>(entity)
+                .filter(|tab| tab.gallery)
+            {
+                tab.set_gallery(false);
+                return Task::none();
+            }
+        }
         // Close dialog if open
         if self.dialog_pages.pop_front().is_some() {
             return Task::none();
         }
+        if self.tab_list_opt.take().is_some() {
+            return Task::none();
+        }
         if self.search_get().is_some() {
             // Close search if open
             return self.search_set_active(None);
@@ -3292,15 +6788,16 @@ impl Application for App {
         if self.active_panel == PaneType::LeftPane {
             let entity = self.tab_model1.active();
             if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                if tab.gallery {
-                    tab.gallery = false;
-                    return Task::none();
-                }
                 if tab.context_menu.is_some() {
                     tab.context_menu = None;
                     return Task::none();
                 }
 
+                if tab.rename_opt.is_some() {
+                    tab.rename_opt = None;
+                    return Task::none();
+                }
+
                 if tab.edit_location.is_some() {
                     tab.edit_location = None;
                     return Task::none();
@@ -3318,15 +6815,16 @@ impl Application for App {
         } else {
             let entity = self.tab_model2.active();
             if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                if tab.gallery {
-                    tab.gallery = false;
-                    return Task::none();
-                }
                 if tab.context_menu.is_some() {
                     tab.context_menu = None;
                     return Task::none();
                 }
 
+                if tab.rename_opt.is_some() {
+                    tab.rename_opt = None;
+                    return Task::none();
+                }
+
                 if tab.edit_location.is_some() {
                     tab.edit_location = None;
                     return Task::none();
@@ -3391,6 +6889,16 @@ impl Application for App {
                 config_set!(app_theme, app_theme);
                 return self.update_config();
             }
+            Message::ClearFolderViews => {
+                config_set!(folder_views_left, Vec::new());
+                config_set!(folder_views_right, Vec::new());
+                self.update_folder_views();
+            }
+            Message::ClearHistory => {
+                self.complete_operations.clear();
+                self.progress_operations
+                    .retain(|id| self.pending_operations.contains_key(id));
+            }
             Message::ClearScrollback(_entity_opt) => {
                 if let Some(terminalmutex) = &self.terminal.as_mut() {
                     if let Ok(terminal) = terminalmutex.lock() {
@@ -3401,47 +6909,128 @@ impl Application for App {
             }
             Message::Compress(entity_opt) => {
                 let paths = self.selected_paths(entity_opt);
-                if let Some(current_path) = paths.first() {
-                    if let Some(destination) = current_path.parent().zip(current_path.file_stem()) {
-                        let to = destination.0.to_path_buf();
-                        let name = destination.1.to_str().unwrap_or_default().to_string();
-                        let archive_type = ArchiveType::default();
-                        self.dialog_pages.push_back(DialogPage::Compress {
-                            paths,
-                            to,
-                            name,
-                            archive_type,
-                            password: None,
-                        });
-                        return widget::text_input::focus(self.dialog_text_input.clone());
-                    }
-                }
+                if let Some(to) = paths.first().and_then(|path| path.parent()) {
+                    let to = to.to_path_buf();
+                    return self.compress_dialog(paths, to);
+                }
+            }
+            Message::ColorByType(color_by_type) => {
+                let mut tab_left = self.config.tab_left;
+                tab_left.color_by_type = color_by_type;
+                config_set!(tab_left, tab_left);
+                let mut tab_right = self.config.tab_right;
+                tab_right.color_by_type = color_by_type;
+                config_set!(tab_right, tab_right);
+                return self.update_config();
             }
-            Message::Config(config) => {
-                if config != self.config {
-                    log::info!("update config");
-                    // Show details is preserved for existing instances
-                    let show_details = self.config.show_details;
-                    self.config = config;
-                    self.config.show_details = show_details;
-                    return self.update_config();
-                }
+            Message::ShowParentEntry(show_parent_entry) => {
+                let mut tab_left = self.config.tab_left;
+                tab_left.show_parent_entry = show_parent_entry;
+                config_set!(tab_left, tab_left);
+                let mut tab_right = self.config.tab_right;
+                tab_right.show_parent_entry = show_parent_entry;
+                config_set!(tab_right, tab_right);
+                return self.update_config();
             }
-            Message::Copy(entity_opt) => {
-                let paths = self.selected_paths(entity_opt);
-                let contents = ClipboardCopy::new(ClipboardKind::Copy, &paths);
-                return clipboard::write_data(contents);
+            Message::WrapNavigation(wrap_navigation) => {
+                let mut tab_left = self.config.tab_left;
+                tab_left.wrap_navigation = wrap_navigation;
+                config_set!(tab_left, tab_left);
+                let mut tab_right = self.config.tab_right;
+                tab_right.wrap_navigation = wrap_navigation;
+                config_set!(tab_right, tab_right);
+                return self.update_config();
             }
-            Message::CopyTerminal(_entity_opt) => {
-                if let Some(terminal) = self.terminal.as_mut() {
-                    let terminal = terminal.lock().unwrap();
-                    let term = terminal.term.lock();
-                    if let Some(text) = term.selection_to_string() {
-                        return Task::batch([clipboard::write(text)]);
-                    }
-                } else {
-                    log::warn!("Failed to get terminal");
-                }
+            Message::NotifyOperations(notify_operations) => {
+                config_set!(notify_operations, notify_operations);
+                return self.update_config();
+            }
+            Message::NotifySound(notify_sound) => {
+                config_set!(notify_sound, notify_sound);
+                return self.update_config();
+            }
+            Message::ConflictPolicy(conflict_policy) => {
+                self.config.conflict_policy = conflict_policy;
+                config_set!(conflict_policy, self.config.conflict_policy);
+                return self.update_config();
+            }
+            Message::TrashUnavailable(trash_unavailable) => {
+                config_set!(trash_unavailable, trash_unavailable);
+                return self.update_config();
+            }
+            Message::ArchiveOpenAction(archive_open_action) => {
+                config_set!(archive_open_action, archive_open_action);
+                return self.update_config();
+            }
+            Message::AutoRefresh => {
+                // Unlike reloading, the items are replaced once scanned and stay selected
+                if self.active_panel == PaneType::LeftPane {
+                    let entity = self.tab_model1.active();
+                    if let Some(location @ Location1::Path(_)) = self
+                        .tab_model1
+                        .data::<Tab1>(entity)
+                        .map(|tab| tab.location.clone())
+                    {
+                        return self.rescan_tab_left(entity, location, None);
+                    }
+                } else {
+                    let entity = self.tab_model2.active();
+                    if let Some(location @ Location2::Path(_)) = self
+                        .tab_model2
+                        .data::<Tab2>(entity)
+                        .map(|tab| tab.location.clone())
+                    {
+                        return self.rescan_tab_right(entity, location, None);
+                    }
+                }
+            }
+            Message::TabTitles(tab_titles) => {
+                config_set!(tab_titles, tab_titles);
+                return Task::batch([self.update_title(), self.update_config()]);
+            }
+            Message::AutoRefreshInterval(auto_refresh_interval) => {
+                config_set!(auto_refresh_interval, auto_refresh_interval);
+                return self.update_config();
+            }
+            Message::ConfirmPermanentDelete(confirm_permanent_delete) => {
+                self.config.confirm_permanent_delete = confirm_permanent_delete;
+                config_set!(
+                    confirm_permanent_delete,
+                    self.config.confirm_permanent_delete
+                );
+                return self.update_config();
+            }
+            Message::ConfirmTrash(confirm_trash) => {
+                self.config.confirm_trash = confirm_trash;
+                config_set!(confirm_trash, self.config.confirm_trash);
+                return self.update_config();
+            }
+            Message::Config(mut config) => {
+                self.layout_overrides.apply(&mut config);
+                if config != self.config {
+                    log::info!("update config");
+                    // Show details is preserved for existing instances
+                    let show_details = self.config.show_details;
+                    self.config = config;
+                    self.config.show_details = show_details;
+                    return self.update_config();
+                }
+            }
+            Message::Copy(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                let contents = ClipboardCopy::new(ClipboardKind::Copy, &paths);
+                return clipboard::write_data(contents);
+            }
+            Message::CopyTerminal(_entity_opt) => {
+                if let Some(terminal) = self.terminal.as_mut() {
+                    let terminal = terminal.lock().unwrap();
+                    let term = terminal.term.lock();
+                    if let Some(text) = term.selection_to_string() {
+                        return Task::batch([clipboard::write(text)]);
+                    }
+                } else {
+                    log::warn!("Failed to get terminal");
+                }
             }
             Message::CopyOrSigint(_entity_opt) => {
                 if let Some(terminalmutex) = self.terminal.as_mut() {
@@ -3471,40 +7060,138 @@ impl Application for App {
                 }
             }
             Message::CopyTab(_entity_opt) => {
-                let entity;
-                // get the selected paths of the active panel
-                let tempactive;
-                let saveactive;
-                if self.active_panel == PaneType::LeftPane {
-                    entity = self.tab_model1.active();
-                    tempactive = PaneType::RightPane;
-                    saveactive = PaneType::LeftPane;
-                    if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                        let location = tab.location.clone();
-                        let newlocation = convert_location1_to_location2(&location);
-                        // create a new tab in the other panel
-                        self.active_panel = tempactive;
-                        let _ = self.update(Message::TabCreateRight(Some(newlocation.clone())));
-                        let _ = self.update_title();
-                        let _ = self.update_watcher_right();
-                        let _ = self.update_tab_right(entity, newlocation, None);
-                        self.active_panel = saveactive;
-                    }
+                // open the location of the active tab in a new tab of the other panel
+                return self.copy_tab_to_other_panel().1;
+            }
+            Message::CopyAs(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                let from = match paths.as_slice() {
+                    [] => return Task::none(),
+                    [from] => from.clone(),
+                    // Several items keep their names
+                    _ => return self.update(Message::F5Copy),
+                };
+                // Copy into the folder of the other panel, like F5 Copy
+                let parent = match self.active_panel {
+                    PaneType::LeftPane => active_tab_path::<RightPanel>(&self.tab_model2),
+                    _ => active_tab_path::<LeftPanel>(&self.tab_model1),
+                }
+                .or_else(|| from.parent().map(Path::to_path_buf));
+                let name = from.file_name().and_then(|x| x.to_str());
+                if let Some((parent, name)) = parent.zip(name) {
+                    // Suggest the first name that is free in the destination
+                    let name = copy_unique_path(&from, &parent)
+                        .file_name()
+                        .and_then(|x| x.to_str())
+                        .unwrap_or(name)
+                        .to_string();
+                    let dir = from.is_dir();
+                    self.dialog_pages.push_back(DialogPage::CopyAs {
+                        from,
+                        parent,
+                        name,
+                        dir,
+                    });
+                    return widget::text_input::focus(self.dialog_text_input.clone());
+                }
+            }
+            Message::CopyMoveTo(entity_opt, copy) => {
+                let paths = self.selected_paths(entity_opt);
+                if paths.is_empty() {
+                    return Task::none();
+                }
+                // Start in the folder of the other panel, where F5 Copy and F6 Move would go
+                let to = if self.active_panel == PaneType::LeftPane {
+                    self.tab_model2
+                        .data::<Tab2>(self.tab_model2.active())
+                        .and_then(|tab| tab.location.path_opt().cloned())
                 } else {
-                    entity = self.tab_model2.active();
-                    tempactive = PaneType::LeftPane;
-                    saveactive = PaneType::RightPane;
-                    if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                        let location = tab.location.clone();
-                        // create a new tab in the other panel
-                        self.active_panel = tempactive;
-                        let newlocation = convert_location2_to_location1(&location);
-                        let _ = self.update(Message::TabCreateLeft(Some(newlocation.clone())));
-                        let _ = self.update_title();
-                        let _ = self.update_watcher_left();
-                        let _ = self.update_tab_left(entity, newlocation, None);
-                        self.active_panel = saveactive;
+                    self.tab_model1
+                        .data::<Tab1>(self.tab_model1.active())
+                        .and_then(|tab| tab.location.path_opt().cloned())
+                }
+                .unwrap_or_else(home_dir);
+                let task = dialog_folders(to.clone());
+                self.dialog_pages.push_back(DialogPage::CopyMoveTo {
+                    paths,
+                    to,
+                    folders_opt: None,
+                    new_folder: String::new(),
+                    error_opt: None,
+                    copy,
+                });
+                return Task::batch([
+                    task,
+                    widget::text_input::focus(self.dialog_text_input.clone()),
+                ]);
+            }
+            Message::CopyMoveToFolders(path, folders) => {
+                // Listings of a location the dialog has left are ignored
+                for dialog_page in self.dialog_pages.iter_mut() {
+                    if let DialogPage::CopyMoveTo {
+                        to, folders_opt, ..
+                    }
+                    | DialogPage::RestoreTo {
+                        to, folders_opt, ..
+                    } = dialog_page
+                    {
+                        if *to == path {
+                            *folders_opt = folders.clone();
+                        }
+                    }
+                }
+            }
+            Message::CopyMoveToNavigate(path) => {
+                if let Some(
+                    DialogPage::CopyMoveTo {
+                        to,
+                        folders_opt,
+                        error_opt,
+                        ..
                     }
+                    | DialogPage::RestoreTo {
+                        to,
+                        folders_opt,
+                        error_opt,
+                        ..
+                    },
+                ) = self.dialog_pages.front_mut()
+                {
+                    // Folders are listed in the background so that typing a path does not block
+                    *folders_opt = None;
+                    *to = path.clone();
+                    *error_opt = None;
+                    return dialog_folders(path);
+                }
+            }
+            Message::CopyMoveToNewFolder => {
+                let path_opt = match self.dialog_pages.front_mut() {
+                    Some(
+                        DialogPage::CopyMoveTo {
+                            to,
+                            new_folder,
+                            error_opt,
+                            ..
+                        }
+                        | DialogPage::RestoreTo {
+                            to,
+                            new_folder,
+                            error_opt,
+                            ..
+                        },
+                    ) => {
+                        let path = to.join(&*new_folder);
+                        new_folder.clear();
+                        *error_opt = None;
+                        Some(path)
+                    }
+                    _ => None,
+                };
+                if let Some(path) = path_opt {
+                    // The dialog opens the folder once the operation created it
+                    self.dialog_new_folder_ids
+                        .insert(self.pending_operation_id, path.clone());
+                    self.operation(Operation::NewFolder { path });
                 }
             }
             Message::Cut(entity_opt) => {
@@ -3521,6 +7208,26 @@ impl Application for App {
             Message::CloseToastRight(id) => {
                 self.toasts_right.remove(id);
             }
+            Message::CommandPalette => {
+                self.dialog_pages.push_back(DialogPage::CommandPalette {
+                    query: String::new(),
+                });
+                return widget::text_input::focus(self.dialog_text_input.clone());
+            }
+            Message::CommandPaletteRun(action) => {
+                if let Some(DialogPage::CommandPalette { .. }) = self.dialog_pages.front() {
+                    self.dialog_pages.pop_front();
+                }
+                self.palette_recent.retain(|recent| *recent != action);
+                self.palette_recent.insert(0, action);
+                self.palette_recent.truncate(MAX_PALETTE_RECENT);
+                let entity = if self.active_panel == PaneType::LeftPane {
+                    self.tab_model1.active()
+                } else {
+                    self.tab_model2.active()
+                };
+                return self.update(action.message(Some(entity)));
+            }
             Message::CosmicSettings(arg) => {
                 //TODO: use special settings URL scheme instead?
                 let mut command = process::Command::new("cosmic-settings");
@@ -3532,6 +7239,34 @@ impl Application for App {
                     }
                 }
             }
+            Message::CustomAction(entity_opt, index) => {
+                let Some(custom_action) = self.custom_actions.get(index) else {
+                    return Task::none();
+                };
+                let dir_opt = match self.active_panel {
+                    PaneType::LeftPane => active_tab_path::<LeftPanel>(&self.tab_model1),
+                    _ => active_tab_path::<RightPanel>(&self.tab_model2),
+                };
+                let Some(dir) = dir_opt else {
+                    return Task::none();
+                };
+                let args = custom_action.args(&self.selected_paths(entity_opt), &dir);
+                if custom_action.terminal {
+                    return self.run_in_terminal(&args, &dir);
+                }
+                let Some((program, program_args)) = args.split_first() else {
+                    return Task::none();
+                };
+                let mut command = process::Command::new(program);
+                command.args(program_args).current_dir(&dir);
+                if let Err(err) = spawn_detached(&mut command) {
+                    log::warn!(
+                        "failed to run custom action {:?}: {}",
+                        custom_action.label,
+                        err
+                    );
+                }
+            }
             Message::DesktopConfig(config) => {
                 if config != self.config.desktop {
                     config_set!(desktop, config);
@@ -3565,28 +7300,75 @@ impl Application for App {
             Message::DialogComplete => {
                 if let Some(dialog_page) = self.dialog_pages.pop_front() {
                     match dialog_page {
+                        DialogPage::Compress {
+                            paths,
+                            to,
+                            archive_type,
+                            password,
+                            separate: true,
+                            include_files,
+                            ..
+                        } => {
+                            // Each archive is its own operation, so progress shows for each
+                            for (path, to) in
+                                separate_archives(&paths, &to, archive_type, include_files)
+                            {
+                                self.operation(Operation::Compress {
+                                    paths: vec![path],
+                                    to,
+                                    archive_type,
+                                    password: password.clone(),
+                                });
+                            }
+                        }
                         DialogPage::Compress {
                             paths,
                             to,
                             name,
                             archive_type,
                             password,
+                            ..
                         } => {
                             let extension = archive_type.extension();
                             let name = format!("{}{}", name, extension);
                             let to = to.join(name);
-                            self.operation(Operation::Compress {
-                                paths,
-                                to,
-                                archive_type,
-                                password,
-                            })
+                            if paths.is_empty() {
+                                self.staged_archive_opt = Some(StagedArchive {
+                                    to,
+                                    archive_type,
+                                    password,
+                                    paths,
+                                });
+                                self.update_nav_model_active();
+                            } else {
+                                self.operation(Operation::Compress {
+                                    paths,
+                                    to,
+                                    archive_type,
+                                    password,
+                                })
+                            }
+                        }
+                        DialogPage::CopyAs {
+                            from, parent, name, ..
+                        } => {
+                            let to = parent.join(name);
+                            self.operation(Operation::CopyAs { from, to });
+                        }
+                        DialogPage::CopyMoveTo {
+                            paths, to, copy, ..
+                        } => {
+                            self.operation(if copy {
+                                Operation::Copy { paths, to }
+                            } else {
+                                Operation::Move { paths, to }
+                            });
                         }
-                        DialogPage::EmptyTrash => {
+                        DialogPage::EmptyTrash { .. } => {
                             self.operation(Operation::EmptyTrash);
                         }
                         DialogPage::FailedOperation(id) => {
-                            log::warn!("TODO: retry operation {}", id);
+                            self.retry_operation(id);
                         }
                         DialogPage::ExtractPassword { id, password } => {
                             let (operation, _, _err) = self.failed_operations.get(&id).unwrap();
@@ -3605,9 +7387,14 @@ impl Application for App {
                             item,
                             error: _,
                         } => {
-                            if let Some(mounter) = MOUNTERS.get(&mounter_key) {
-                                return mounter.mount(item).map(|_| message::none());
-                            }
+                            return self.mount_item(mounter_key, item, true);
+                        }
+                        DialogPage::UnmountError {
+                            mounter_key,
+                            item,
+                            error: _,
+                        } => {
+                            return self.mount_item(mounter_key, item, false);
                         }
                         DialogPage::NetworkAuth {
                             mounter_key: _,
@@ -3634,44 +7421,90 @@ impl Application for App {
                                 self.update(Message::NetworkDriveSubmit),
                             ]);
                         }
-                        DialogPage::NewItem { parent, name, dir } => {
+                        DialogPage::NewItem {
+                            parent,
+                            name,
+                            dir,
+                            text_opt,
+                        } => {
                             let path = parent.join(name);
                             self.operation(if dir {
                                 Operation::NewFolder { path }
                             } else {
-                                Operation::NewFile { path }
+                                Operation::NewFile {
+                                    path,
+                                    contents: text_opt.unwrap_or_default(),
+                                }
                             });
                         }
+                        DialogPage::OpenFiles { paths } => {
+                            for path in paths.iter() {
+                                self.open_file(path);
+                            }
+                        }
+                        DialogPage::OpenScript { path, run } => {
+                            if !run {
+                                let mime = mime_icon::mime_for_path(&path);
+                                self.open_file_with_apps(&path, &mime);
+                            } else if let Some(dir) = path.parent() {
+                                return self.run_in_terminal(&[path.clone().into_os_string()], dir);
+                            }
+                        }
+                        DialogPage::OpenAsRoot { path, .. } => {
+                            return self.open_as_root(path);
+                        }
                         DialogPage::OpenWith {
-                            path,
+                            paths,
                             mime,
-                            selected,
+                            mut selected,
+                            always,
                             ..
                         } => {
+                            if always {
+                                if let Some(app) = self.mime_app_cache.get(&mime).get(selected) {
+                                    if !app.is_default {
+                                        // Reloads the cache, so later opens use the new default
+                                        let id = app.id.clone();
+                                        self.mime_app_cache.set_default(mime.clone(), id.clone());
+                                        selected = self
+                                            .mime_app_cache
+                                            .get(&mime)
+                                            .iter()
+                                            .position(|app| app.id == id)
+                                            .unwrap_or(selected);
+                                    }
+                                }
+                            }
                             if let Some(app) = self.mime_app_cache.get(&mime).get(selected) {
-                                if let Some(mut command) = app.command(Some(path.clone().into())) {
-                                    match spawn_detached(&mut command) {
-                                        Ok(()) => {
+                                let args: Vec<OsString> =
+                                    paths.iter().map(|path| path.clone().into()).collect();
+                                if let Some(commands) = app.commands(&args) {
+                                    let mut opened = true;
+                                    for mut command in commands {
+                                        if let Err(err) = spawn_detached(&mut command) {
+                                            log::warn!(
+                                                "failed to open {:?} with {:?}: {}",
+                                                paths,
+                                                app.id,
+                                                err
+                                            );
+                                            opened = false;
+                                        }
+                                    }
+                                    if opened {
+                                        for path in paths.iter() {
                                             let _ = recently_used_xbel::update_recently_used(
-                                                &path,
+                                                path,
                                                 App::APP_ID.to_string(),
                                                 "commander".to_string(),
                                                 None,
                                             );
                                         }
-                                        Err(err) => {
-                                            log::warn!(
-                                                "failed to open {:?} with {:?}: {}",
-                                                path,
-                                                app.id,
-                                                err
-                                            )
-                                        }
                                     }
                                 } else {
                                     log::warn!(
                                         "failed to open {:?} with {:?}: failed to get command",
-                                        path,
+                                        paths,
                                         app.id
                                     );
                                 }
@@ -3683,19 +7516,181 @@ impl Application for App {
                             let to = parent.join(name);
                             self.operation(Operation::Rename { from, to });
                         }
+                        DialogPage::ChangeExtension {
+                            paths, extension, ..
+                        } => {
+                            // Items may have been added since the renames were shown
+                            if let Ok(renames) = change_extension_renames(&paths, &extension) {
+                                for (from, to) in renames {
+                                    self.operation(Operation::Rename { from, to });
+                                }
+                            }
+                        }
+                        DialogPage::InsufficientSpace { tx, .. } => {
+                            return Task::perform(
+                                async move {
+                                    let _ = tx.send(true).await;
+                                    message::none()
+                                },
+                                |x| x,
+                            );
+                        }
                         DialogPage::Replace1 { .. } => {
                             log::warn!("replace dialog should be completed with replace result");
                         }
                         DialogPage::Replace2 { .. } => {
                             log::warn!("replace dialog should be completed with replace result");
                         }
+                        DialogPage::RestoreMissing { items, missing } => {
+                            // Restoring is refused as well, so the folders are not created
+                            if self.config.read_only {
+                                self.refuse_read_only();
+                            } else {
+                                for path in missing {
+                                    if let Err(err) = fs::create_dir_all(&path) {
+                                        log::warn!("failed to create folder {:?}: {}", path, err);
+                                    }
+                                }
+                                self.operation(Operation::Restore { items });
+                            }
+                        }
+                        DialogPage::RestoreTo { mut items, to, .. } => {
+                            // Items are restored to where they were trashed from
+                            for item in items.iter_mut() {
+                                item.original_parent = to.clone();
+                            }
+                            self.operation(Operation::Restore { items });
+                        }
+                        DialogPage::SelectByCriteria(input) => {
+                            let Some(criteria) = input.parse(SystemTime::now()) else {
+                                return Task::none();
+                            };
+                            let count = if self.active_panel == PaneType::LeftPane {
+                                let entity = self.tab_model1.active();
+                                self.tab_model1.data_mut::<Tab1>(entity).map_or(0, |tab| {
+                                    tab.select_matching(|item| {
+                                        criteria.matches(
+                                            &item.name,
+                                            item.size_opt(),
+                                            item.metadata.modified(),
+                                        )
+                                    })
+                                })
+                            } else {
+                                let entity = self.tab_model2.active();
+                                self.tab_model2.data_mut::<Tab2>(entity).map_or(0, |tab| {
+                                    tab.select_matching(|item| {
+                                        criteria.matches(
+                                            &item.name,
+                                            item.size_opt(),
+                                            item.metadata.modified(),
+                                        )
+                                    })
+                                })
+                            };
+                            return self
+                                .toasts
+                                .push(widget::toaster::Toast::new(fl!(
+                                    "selected-by-criteria",
+                                    items = count
+                                )))
+                                .map(cosmic::app::Message::App);
+                        }
+                        DialogPage::SelectPattern {
+                            pattern,
+                            select,
+                            case_sensitive,
+                        } => match glob::Pattern::new(&pattern) {
+                            Ok(pattern) => {
+                                if self.active_panel == PaneType::LeftPane {
+                                    let entity = self.tab_model1.active();
+                                    if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
+                                        tab.select_pattern(&pattern, case_sensitive, select);
+                                    }
+                                } else {
+                                    let entity = self.tab_model2.active();
+                                    if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
+                                        tab.select_pattern(&pattern, case_sensitive, select);
+                                    }
+                                }
+                            }
+                            Err(err) => {
+                                log::warn!("failed to parse glob {:?}: {}", pattern, err);
+                            }
+                        },
                         DialogPage::SetExecutableAndLaunch { path } => {
                             self.operation(Operation::SetExecutableAndLaunch { path });
                         }
+                        DialogPage::SetModified {
+                            paths,
+                            now,
+                            time,
+                            recursive,
+                        } => {
+                            let time_opt = if now {
+                                Some(SystemTime::now())
+                            } else {
+                                parse_local_time(&time)
+                            };
+                            match time_opt {
+                                Some(time) => self.operation(Operation::SetModified {
+                                    paths,
+                                    time,
+                                    recursive,
+                                }),
+                                None => log::warn!("failed to parse time {:?}", time),
+                            }
+                        }
+                        DialogPage::UndoPermanentDelete { history } => {
+                            // Operations completed while asking are not undone instead
+                            let index_opt = self
+                                .undo_history
+                                .iter()
+                                .rposition(|entry| *entry == history);
+                            if let Some(index) = index_opt {
+                                self.undo_entry(index);
+                            }
+                        }
+                        DialogPage::MoveToTrash { paths } => {
+                            self.operation(Operation::Delete { paths });
+                        }
+                        DialogPage::PermanentlyDelete { paths } => {
+                            self.operation(Operation::PermanentDelete { paths });
+                        }
+                        DialogPage::QuickPreview { .. } => {}
+                        DialogPage::RecentFiles { .. } => {}
+                        DialogPage::SettingsFile { export, path } => {
+                            return self.settings_file(export, PathBuf::from(path));
+                        }
+                        DialogPage::SettingsReport { .. } => {}
+                        DialogPage::TrashUnavailable {
+                            paths,
+                            no_trash,
+                            choice,
+                        } => {
+                            self.trash_unavailable(paths, no_trash, choice);
+                        }
+                        DialogPage::Diff { .. } => {}
+                        DialogPage::CommandPalette { .. } => {}
                     }
                 }
             }
             Message::DialogPush(dialog_page) => {
+                // Conflicts are answered without asking when a policy is set
+                if let DialogPage::Replace1 { tx, .. } | DialogPage::Replace2 { tx, .. } =
+                    &dialog_page
+                {
+                    if let Some(replace_result) = self.config.conflict_policy.replace_result() {
+                        let tx = tx.clone();
+                        return Task::perform(
+                            async move {
+                                let _ = tx.send(replace_result).await;
+                                message::none()
+                            },
+                            |x| x,
+                        );
+                    }
+                }
                 self.dialog_pages.push_back(dialog_page);
             }
             Message::DialogUpdate(dialog_page) => {
@@ -3709,6 +7704,20 @@ impl Application for App {
                     self.update(Message::DialogComplete),
                 ]);
             }
+            Message::DiskSpaceLeft(entity, location, disk_space_opt) => {
+                if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
+                    if location == tab.location {
+                        tab.disk_space_opt = disk_space_opt;
+                    }
+                }
+            }
+            Message::DiskSpaceRight(entity, location, disk_space_opt) => {
+                if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
+                    if location == tab.location {
+                        tab.disk_space_opt = disk_space_opt;
+                    }
+                }
+            }
             Message::EditLocation(entity_opt) => {
                 if self.active_panel == PaneType::LeftPane {
                     return self.update(Message::TabMessage(
@@ -3732,6 +7741,13 @@ impl Application for App {
                     ));
                 }
             }
+            Message::EmptyTrashSummary(items, size) => {
+                for dialog_page in self.dialog_pages.iter_mut() {
+                    if let DialogPage::EmptyTrash { summary_opt } = dialog_page {
+                        *summary_opt = Some((items, size));
+                    }
+                }
+            }
             Message::ExecEntryAction(entity_opt, action) => {
                 if self.active_panel == PaneType::LeftPane {
                     return self.update(Message::TabMessage(
@@ -3745,19 +7761,34 @@ impl Application for App {
                     ));
                 }
             }
+            Message::SettingsFile(export) => {
+                let path = home_dir().join("commander-settings.json");
+                self.dialog_pages.push_back(DialogPage::SettingsFile {
+                    export,
+                    path: path.to_string_lossy().into_owned(),
+                });
+                return widget::text_input::focus(self.dialog_text_input.clone());
+            }
             Message::ExtractHere(entity_opt) => {
                 let paths = self.selected_paths(entity_opt);
-                if let Some(destination) = paths
-                    .first()
-                    .and_then(|first| first.parent())
-                    .map(|parent| parent.to_path_buf())
-                {
-                    self.operation(Operation::Extract {
-                        paths,
-                        to: destination,
-                        password: None,
+                self.extract(paths, false);
+            }
+            Message::FinalizeArchive => match self.staged_archive_opt.take() {
+                Some(staged) if !staged.paths.is_empty() => {
+                    self.operation(Operation::Compress {
+                        paths: staged.paths,
+                        to: staged.to,
+                        archive_type: staged.archive_type,
+                        password: staged.password,
                     });
+                    self.update_nav_model_active();
                 }
+                // Nothing is created until files are added
+                staged_opt => self.staged_archive_opt = staged_opt,
+            },
+            Message::ExtractToOtherPanel(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                self.extract(paths, true);
             }
             Message::F2Rename => {
                 let entity;
@@ -3786,72 +7817,16 @@ impl Application for App {
                 }
                 return self.update(Message::OpenWithDialog(Some(entity)));
             }
-            Message::F5Copy => {
-                let to;
-                if self.active_panel == PaneType::LeftPane {
-                    let entity = self.tab_model1.active();
-                    // get the selected paths of the active panel
-                    let paths = self.selected_paths(Some(entity));
-                    if let Some(tab) = self.tab_model2.data_mut::<Tab2>(self.tab_model2.active()) {
-                        if let Some(path) = tab.location.path_opt() {
-                            to = path.to_owned();
-                        } else {
-                            return Task::none();
-                        }
-                    } else {
-                        return Task::none();
-                    }
-                    self.operation(Operation::Copy { paths, to });
-                } else {
-                    let entity = self.tab_model2.active();
-                    // get the selected paths of the active panel
-                    let paths = self.selected_paths(Some(entity));
-                    if let Some(tab) = self.tab_model1.data_mut::<Tab1>(self.tab_model1.active()) {
-                        if let Some(path) = tab.location.path_opt() {
-                            to = path.to_owned();
-                        } else {
-                            return Task::none();
-                        }
-                    } else {
-                        return Task::none();
-                    }
-                    self.operation(Operation::Copy { paths, to });
-                }
-            }
-            Message::F6Move => {
-                let to;
-                if self.active_panel == PaneType::LeftPane {
-                    let entity = self.tab_model1.active();
-                    // get the selected paths of the active panel
-                    let paths = self.selected_paths(Some(entity));
-                    if let Some(tab) = self.tab_model2.data_mut::<Tab2>(self.tab_model2.active()) {
-                        if let Some(path) = tab.location.path_opt() {
-                            to = path.to_owned();
-                        } else {
-                            return Task::none();
-                        }
-                    } else {
-                        return Task::none();
-                    }
-                    self.operation(Operation::Move { paths, to });
-                } else {
-                    let entity = self.tab_model2.active();
-                    // get the selected paths of the active panel
-                    let paths = self.selected_paths(Some(entity));
-                    if let Some(tab) = self.tab_model1.data_mut::<Tab1>(self.tab_model1.active()) {
-                        if let Some(path) = tab.location.path_opt() {
-                            to = path.to_owned();
-                        } else {
-                            return Task::none();
-                        }
-                    } else {
-                        return Task::none();
-                    }
-                    self.operation(Operation::Move { paths, to });
-                }
-            }
-            Message::F7Mkdir => {
-                let entity;
+            Message::F5Copy => match self.active_panel {
+                PaneType::LeftPane => self.copy_move_to_other_panel::<LeftPanel>(true),
+                _ => self.copy_move_to_other_panel::<RightPanel>(true),
+            },
+            Message::F6Move => match self.active_panel {
+                PaneType::LeftPane => self.copy_move_to_other_panel::<LeftPanel>(false),
+                _ => self.copy_move_to_other_panel::<RightPanel>(false),
+            },
+            Message::F7Mkdir => {
+                let entity;
                 if self.active_panel == PaneType::LeftPane {
                     entity = self.tab_model1.active();
                 } else {
@@ -3860,23 +7835,9 @@ impl Application for App {
                 return self.update(Message::NewItem(Some(entity), true));
             }
             Message::F8Delete => {
-                if self.active_panel == PaneType::LeftPane {
-                    let entity = self.tab_model1.active();
-                    // get the selected paths of the active panel
-                    let paths = self.selected_paths(Some(entity));
-                    if paths.len() == 0 {
-                        return Task::none();
-                    }
-                    self.operation(Operation::Delete { paths });
-                } else {
-                    let entity = self.tab_model2.active();
-                    // get the selected paths of the active panel
-                    let paths = self.selected_paths(Some(entity));
-                    if paths.len() == 0 {
-                        return Task::none();
-                    }
-                    self.operation(Operation::Delete { paths });
-                }
+                // get the selected paths of the active tab of the active panel
+                let paths = self.selected_paths(None);
+                return self.move_to_trash(paths);
             }
             Message::F9Terminal => {
                 let entity;
@@ -3885,11 +7846,23 @@ impl Application for App {
                 } else {
                     entity = self.tab_model2.active();
                 }
+                if self.show_embedded_terminal && !self.config.terminal_command.is_empty() {
+                    return self.run_terminal_command(Some(entity));
+                }
                 return self.update(Message::OpenTerminal(Some(entity)));
             }
             Message::F10Quit => {
                 return self.update(Message::WindowClose);
             }
+            Message::FollowSymlinks(follow_symlinks) => {
+                let mut tab_left = self.config.tab_left;
+                tab_left.follow_symlinks = follow_symlinks;
+                config_set!(tab_left, tab_left);
+                let mut tab_right = self.config.tab_right;
+                tab_right.follow_symlinks = follow_symlinks;
+                config_set!(tab_right, tab_right);
+                return self.update_config();
+            }
             Message::GalleryToggle(entity_opt) => {
                 if self.active_panel == PaneType::LeftPane {
                     return self.update(Message::TabMessage(
@@ -3999,61 +7972,78 @@ impl Application for App {
                     process::exit(0);
                 }
             }
+            Message::LayoutOverrides(layout_overrides) => {
+                // Like the command line of a new window, without saving to the config
+                self.layout_overrides.merge(layout_overrides);
+                let mut config = self.config.clone();
+                layout_overrides.apply(&mut config);
+                if config != self.config {
+                    self.config = config;
+                    return self.update_config();
+                }
+            }
             Message::LaunchUrl(url) => match open::that_detached(&url) {
                 Ok(()) => {}
                 Err(err) => {
                     log::warn!("failed to open {:?}: {}", url, err);
                 }
             },
+            Message::MiddleClickOpenFiles(middle_click_open_files) => {
+                let config = PanelOption::MiddleClickOpenFiles(middle_click_open_files)
+                    .apply(&self.config, self.active_panel);
+                return self.update_tab_configs(config);
+            }
             Message::Modifiers(modifiers) => {
                 self.modifiers = modifiers;
             }
-            Message::MoveTab(entity_opt) => {
-                let entity;
-                // get the selected paths of the active panel
-                let tempactive;
-                let saveactive;
-                if self.active_panel == PaneType::LeftPane {
-                    entity = self.tab_model1.active();
-                    tempactive = PaneType::LeftPane;
-                    saveactive = PaneType::RightPane;
-                    if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                        let location = tab.location.clone();
-                        let newlocation = convert_location1_to_location2(&location);
-                        // create a new tab in the other panel
-                        self.active_panel = tempactive;
-                        let _ = self.update(Message::TabCreateRight(Some(newlocation.clone())));
-                        let _ = self.update_title();
-                        let _ = self.update_watcher_right();
-                        let _ = self.update_tab_right(entity, newlocation, None);
-                        self.active_panel = saveactive;
-                        let _ = self.update(Message::TabClose(entity_opt));
-                    }
+            Message::MoveTab(_entity_opt) => {
+                let (entity_opt, task) = self.copy_tab_to_other_panel();
+                // close the source tab, which is still in the active panel
+                if entity_opt.is_some() {
+                    return Task::batch([task, self.update(Message::TabClose(entity_opt))]);
+                }
+                return task;
+            }
+            Message::MoveTabToWindow(entity_opt) => {
+                let (path_opt, close) = if self.active_panel == PaneType::LeftPane {
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model1.active());
+                    let path_opt = self
+                        .tab_model1
+                        .data::<Tab1>(entity)
+                        .and_then(|tab| tab.location.path_opt().cloned());
+                    (path_opt, Message::TabCloseLeft(Some(entity)))
                 } else {
-                    entity = self.tab_model2.active();
-                    tempactive = PaneType::LeftPane;
-                    saveactive = PaneType::RightPane;
-                    if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                        let location = tab.location.clone();
-                        // create a new tab in the other panel
-                        self.active_panel = tempactive;
-                        let newlocation = convert_location2_to_location1(&location);
-                        let _ = self.update(Message::TabCreateLeft(Some(newlocation.clone())));
-                        let _ = self.update_title();
-                        let _ = self.update_watcher_left();
-                        let _ = self.update_tab_left(entity, newlocation, None);
-                        self.active_panel = saveactive;
-                        let _ = self.update(Message::TabClose(entity_opt));
-                    }
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model2.active());
+                    let path_opt = self
+                        .tab_model2
+                        .data::<Tab2>(entity)
+                        .and_then(|tab| tab.location.path_opt().cloned());
+                    (path_opt, Message::TabCloseRight(Some(entity)))
+                };
+                // Only folders can be opened by a new window
+                let Some(path) = path_opt else {
+                    return Task::none();
+                };
+                // The tab is kept if the new window fails to open
+                if let Some(child) = spawn_new_window([path.into_os_string()]) {
+                    return Task::perform(new_window_opened(child), move |opened| {
+                        if opened {
+                            message::app(close.clone())
+                        } else {
+                            message::none()
+                        }
+                    });
                 }
             }
             Message::MoveToTrash(entity_opt) => {
                 let paths = self.selected_paths(entity_opt);
-                if !paths.is_empty() {
-                    self.operation(Operation::Delete { paths });
-                }
+                return self.move_to_trash(paths);
             }
             Message::MounterItems(mounter_key, mounter_items) => {
+                // Items that disappeared will not report a result
+                self.mounter_pending.retain(|(key, name)| {
+                    *key != mounter_key || mounter_items.iter().any(|item| item.name() == *name)
+                });
                 // Go back to home in any tabs that were unmounted
                 let mut commands = Vec::new();
                 {
@@ -4112,7 +8102,7 @@ impl Application for App {
 
                         // Update nav bar
                         //TODO: this could change favorites IDs while they are in use
-                        self.update_nav_model_left();
+                        self.update_nav_model_in::<LeftPanel>();
 
                         // Update desktop tabs
                         commands.push(self.update_desktop());
@@ -4171,7 +8161,7 @@ impl Application for App {
 
                         // Update nav bar
                         //TODO: this could change favorites IDs while they are in use
-                        self.update_nav_model_right();
+                        self.update_nav_model_in::<RightPanel>();
 
                         // Update desktop tabs
                         commands.push(self.update_desktop());
@@ -4180,22 +8170,44 @@ impl Application for App {
 
                 return Task::batch(commands);
             }
-            Message::MountResult(mounter_key, item, res) => match res {
-                Ok(true) => {
-                    log::info!("connected to {:?}", item);
-                }
-                Ok(false) => {
-                    log::info!("cancelled connection to {:?}", item);
+            Message::MountResult(mounter_key, item, res) => {
+                self.mount_item_done(mounter_key, &item);
+                match res {
+                    Ok(true) => {
+                        log::info!("connected to {:?}", item);
+                    }
+                    Ok(false) => {
+                        log::info!("cancelled connection to {:?}", item);
+                    }
+                    Err(error) => {
+                        log::warn!("failed to connect to {:?}: {}", item, error);
+                        self.dialog_pages.push_back(DialogPage::MountError {
+                            mounter_key,
+                            item,
+                            error,
+                        });
+                    }
                 }
-                Err(error) => {
-                    log::warn!("failed to connect to {:?}: {}", item, error);
-                    self.dialog_pages.push_back(DialogPage::MountError {
-                        mounter_key,
-                        item,
-                        error,
-                    });
+            }
+            Message::UnmountResult(mounter_key, item, res) => {
+                self.mount_item_done(mounter_key, &item);
+                match res {
+                    Ok(true) => {
+                        log::info!("unmounted {:?}", item);
+                    }
+                    Ok(false) => {
+                        log::info!("cancelled unmounting {:?}", item);
+                    }
+                    Err(error) => {
+                        log::warn!("failed to unmount {:?}: {:?}", item, error);
+                        self.dialog_pages.push_back(DialogPage::UnmountError {
+                            mounter_key,
+                            item,
+                            error,
+                        });
+                    }
                 }
-            },
+            }
             Message::NetworkAuth(mounter_key, uri, auth, auth_tx) => {
                 self.dialog_pages.push_back(DialogPage::NetworkAuth {
                     mounter_key,
@@ -4208,9 +8220,29 @@ impl Application for App {
             Message::NetworkDriveInput(input) => {
                 self.network_drive_input = input;
             }
+            Message::NetworkDriveReconnect(uri) => {
+                return Task::batch([
+                    self.update(Message::NetworkDriveInput(uri)),
+                    self.update(Message::NetworkDriveSubmit),
+                ]);
+            }
+            Message::NetworkDriveRemoveRecent(uri) => {
+                let mut recent_network_drives = self.config.recent_network_drives.clone();
+                recent_network_drives.retain(|recent| recent != &uri);
+                config_set!(recent_network_drives, recent_network_drives);
+                return self.update_config();
+            }
             Message::NetworkDriveSubmit => {
-                //TODO: know which mounter to use for network drives
-                for (mounter_key, mounter) in MOUNTERS.iter() {
+                // Prefer a mounter dedicated to the scheme, falling back to the first one
+                let scheme = self
+                    .network_drive_input
+                    .split_once("://")
+                    .map_or("", |(scheme, _)| scheme);
+                let mounter_opt = MOUNTERS
+                    .iter()
+                    .find(|(_, mounter)| mounter.network_schemes().contains(&scheme))
+                    .or_else(|| MOUNTERS.iter().next());
+                if let Some((mounter_key, mounter)) = mounter_opt {
                     self.network_drive_connecting =
                         Some((*mounter_key, self.network_drive_input.clone()));
                     return mounter
@@ -4232,6 +8264,22 @@ impl Application for App {
                         if matches!(self.context_page, ContextPage::NetworkDrive) {
                             self.set_show_context(false);
                         }
+                        // Remember the location without any credentials
+                        let uri = match url::Url::parse(&uri) {
+                            Ok(mut url) => {
+                                let _ = url.set_password(None);
+                                url.to_string()
+                            }
+                            Err(_) => uri,
+                        };
+                        let mut recent_network_drives = self.config.recent_network_drives.clone();
+                        recent_network_drives.retain(|recent| recent != &uri);
+                        recent_network_drives.insert(0, uri);
+                        recent_network_drives.truncate(MAX_RECENT_NETWORK_DRIVES);
+                        if recent_network_drives != self.config.recent_network_drives {
+                            config_set!(recent_network_drives, recent_network_drives);
+                            return self.update_config();
+                        }
                     }
                     Ok(false) => {
                         log::info!("cancelled connection to {:?}", uri);
@@ -4246,6 +8294,32 @@ impl Application for App {
                     }
                 }
             }
+            Message::NewArchive(entity_opt) => {
+                let to_opt = if self.active_panel == PaneType::LeftPane {
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model1.active());
+                    self.tab_model1
+                        .data::<Tab1>(entity)
+                        .and_then(|tab| tab.location.path_opt().cloned())
+                } else {
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model2.active());
+                    self.tab_model2
+                        .data::<Tab2>(entity)
+                        .and_then(|tab| tab.location.path_opt().cloned())
+                };
+                if let Some(to) = to_opt {
+                    // Without paths, completing the dialog stages the archive
+                    self.dialog_pages.push_back(DialogPage::Compress {
+                        paths: Vec::new(),
+                        to,
+                        name: fl!("new-archive-name"),
+                        archive_type: ArchiveType::default(),
+                        password: None,
+                        separate: false,
+                        include_files: false,
+                    });
+                    return widget::text_input::focus(self.dialog_text_input.clone());
+                }
+            }
             Message::NewItem(entity_opt, dir) => {
                 let entity = match entity_opt {
                     Some(entity) => entity,
@@ -4264,6 +8338,7 @@ impl Application for App {
                                 parent: path.to_path_buf(),
                                 name: String::new(),
                                 dir,
+                                text_opt: None,
                             });
                             return widget::text_input::focus(self.dialog_text_input.clone());
                         }
@@ -4275,6 +8350,7 @@ impl Application for App {
                                 parent: path.to_path_buf(),
                                 name: String::new(),
                                 dir,
+                                text_opt: None,
                             });
                             return widget::text_input::focus(self.dialog_text_input.clone());
                         }
@@ -4477,88 +8553,76 @@ impl Application for App {
                     }
                 }
             }
+            Message::OpenAsRoot(entity_opt) => {
+                if !self.config.allow_open_as_root {
+                    return Task::none();
+                }
+                let mut paths = self.selected_paths(entity_opt);
+                if paths.len() == 1 {
+                    self.dialog_pages.push_back(DialogPage::OpenAsRoot {
+                        path: paths.remove(0),
+                        error_opt: None,
+                    });
+                }
+            }
+            Message::OpenAsRootError(path, error) => {
+                self.dialog_pages.push_back(DialogPage::OpenAsRoot {
+                    path,
+                    error_opt: Some(error),
+                });
+            }
             Message::OpenTerminal(entity_opt) => {
-                if let Some(terminal) = self.mime_app_cache.terminal() {
-                    let mut paths = Vec::new();
-                    let entity = match entity_opt {
-                        Some(entity) => entity,
-                        None => {
-                            if self.active_panel == PaneType::LeftPane {
-                                self.tab_model1.active()
-                            } else {
-                                self.tab_model2.active()
-                            }
-                        }
-                    };
-                    if self.active_panel == PaneType::LeftPane {
-                        if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                            if let Some(path) = &tab.location.path_opt() {
-                                if let Some(items) = tab.items_opt() {
-                                    for item in items.iter() {
-                                        if item.selected {
-                                            if let Some(path) = item.path_opt() {
-                                                paths.push(path.to_path_buf());
-                                            }
-                                        }
-                                    }
-                                }
-                                if paths.is_empty() {
-                                    paths.push(path.to_path_buf());
-                                }
-                            }
-                        }
-                        for path in paths {
-                            if let Some(mut command) = terminal.command(None) {
-                                command.current_dir(&path);
-                                match spawn_detached(&mut command) {
-                                    Ok(()) => {}
-                                    Err(err) => {
-                                        log::warn!(
-                                            "failed to open {:?} with terminal {:?}: {}",
-                                            path,
-                                            terminal.id,
-                                            err
-                                        )
-                                    }
-                                }
-                            } else {
-                                log::warn!("failed to get command for {:?}", terminal.id);
-                            }
-                        }
-                    } else {
-                        if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                            if let Some(path) = &tab.location.path_opt() {
-                                if let Some(items) = tab.items_opt() {
-                                    for item in items.iter() {
-                                        if item.selected {
-                                            if let Some(path) = item.path_opt() {
-                                                paths.push(path.to_path_buf());
-                                            }
-                                        }
-                                    }
-                                }
-                                if paths.is_empty() {
-                                    paths.push(path.to_path_buf());
+                let location_path_opt = if self.active_panel == PaneType::LeftPane {
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model1.active());
+                    self.tab_model1
+                        .data::<Tab1>(entity)
+                        .and_then(|tab| tab.location.path_opt().cloned())
+                } else {
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model2.active());
+                    self.tab_model2
+                        .data::<Tab2>(entity)
+                        .and_then(|tab| tab.location.path_opt().cloned())
+                };
+                let Some(location_path) = location_path_opt else {
+                    return Task::none();
+                };
+                let dirs = terminal_dirs(self.selected_paths(entity_opt), &location_path);
+
+                if self.config.open_terminal_embedded && self.show_embedded_terminal {
+                    self.terminal_shell();
+                    // There is only one embedded terminal, so it changes to the first folder
+                    if let (Some(terminalmutex), Some(dir)) = (&self.terminal, dirs.first()) {
+                        match dir.to_str().map(shlex::try_quote) {
+                            Some(Ok(quoted)) => {
+                                if let Ok(terminal) = terminalmutex.lock() {
+                                    terminal.input_scroll(format!("cd {}\r", quoted).into_bytes());
                                 }
+                                self.pane_model.focus =
+                                    self.pane_model.pane_by_type[&PaneType::TerminalPane];
                             }
+                            _ => log::warn!("failed to quote {:?} for the terminal", dir),
                         }
-                        for path in paths {
-                            if let Some(mut command) = terminal.command(None) {
-                                command.current_dir(&path);
-                                match spawn_detached(&mut command) {
-                                    Ok(()) => {}
-                                    Err(err) => {
-                                        log::warn!(
-                                            "failed to open {:?} with terminal {:?}: {}",
-                                            path,
-                                            terminal.id,
-                                            err
-                                        )
-                                    }
+                        return Task::none();
+                    }
+                }
+
+                if let Some(terminal) = self.mime_app_cache.terminal() {
+                    for dir in dirs {
+                        if let Some(mut command) = terminal.command(None) {
+                            command.current_dir(&dir);
+                            match spawn_detached(&mut command) {
+                                Ok(()) => {}
+                                Err(err) => {
+                                    log::warn!(
+                                        "failed to open {:?} with terminal {:?}: {}",
+                                        dir,
+                                        terminal.id,
+                                        err
+                                    )
                                 }
-                            } else {
-                                log::warn!("failed to get command for {:?}", terminal.id);
                             }
+                        } else {
+                            log::warn!("failed to get command for {:?}", terminal.id);
                         }
                     }
                 }
@@ -4598,21 +8662,13 @@ impl Application for App {
                     return commands;
                 }
             }
-            Message::OpenInNewWindow(entity_opt) => match env::current_exe() {
-                Ok(exe) => self
-                    .selected_paths(entity_opt)
-                    .into_iter()
-                    .filter(|p| p.is_dir())
-                    .for_each(|path| match process::Command::new(&exe).arg(path).spawn() {
-                        Ok(_child) => {}
-                        Err(err) => {
-                            log::error!("failed to execute {:?}: {}", exe, err);
-                        }
-                    }),
-                Err(err) => {
-                    log::error!("failed to get current executable path: {}", err);
+            Message::OpenInNewWindow(entity_opt) => {
+                for path in self.selected_paths(entity_opt) {
+                    if path.is_dir() {
+                        spawn_new_window([path.into_os_string()]);
+                    }
                 }
-            },
+            }
             Message::OpenItemLocation(entity_opt) => {
                 return Task::batch(self.selected_paths(entity_opt).into_iter().filter_map(
                     |path| {
@@ -4626,6 +8682,19 @@ impl Application for App {
                     },
                 ))
             }
+            Message::OpenLinkTarget(entity_opt) => {
+                if self.active_panel == PaneType::LeftPane {
+                    return self.update(Message::TabMessage(
+                        entity_opt,
+                        tab1::Message::OpenLinkTarget,
+                    ));
+                } else {
+                    return self.update(Message::TabMessageRight(
+                        entity_opt,
+                        tab2::Message::OpenLinkTarget,
+                    ));
+                }
+            }
             Message::OpenWithBrowse => match self.dialog_pages.pop_front() {
                 Some(DialogPage::OpenWith {
                     mime,
@@ -4667,17 +8736,19 @@ impl Application for App {
                 if self.active_panel == PaneType::LeftPane {
                     if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
                         if let Some(items) = tab.items_opt() {
-                            for item in items {
-                                if !item.selected {
-                                    continue;
-                                }
-                                let Some(path) = item.path_opt() else {
-                                    continue;
-                                };
+                            let mut selected = items
+                                .iter()
+                                .filter(|item| item.selected && item.path_opt().is_some());
+                            if let Some(item) = selected.next() {
+                                let paths = std::iter::once(item)
+                                    .chain(selected)
+                                    .filter_map(|item| item.path_opt().cloned())
+                                    .collect();
                                 return self.update(Message::DialogPush(DialogPage::OpenWith {
-                                    path: path.to_path_buf(),
+                                    paths,
                                     mime: item.mime.clone(),
                                     selected: 0,
+                                    always: false,
                                     store_opt: "x-scheme-handler/mime"
                                         .parse::<mime_guess::Mime>()
                                         .ok()
@@ -4691,17 +8762,19 @@ impl Application for App {
                 } else {
                     if let Some(tab) = self.tab_model2.data::<Tab2>(entity) {
                         if let Some(items) = tab.items_opt() {
-                            for item in items {
-                                if !item.selected {
-                                    continue;
-                                }
-                                let Some(path) = item.path_opt() else {
-                                    continue;
-                                };
+                            let mut selected = items
+                                .iter()
+                                .filter(|item| item.selected && item.path_opt().is_some());
+                            if let Some(item) = selected.next() {
+                                let paths = std::iter::once(item)
+                                    .chain(selected)
+                                    .filter_map(|item| item.path_opt().cloned())
+                                    .collect();
                                 return self.update(Message::DialogPush(DialogPage::OpenWith {
-                                    path: path.to_path_buf(),
+                                    paths,
                                     mime: item.mime.clone(),
                                     selected: 0,
+                                    always: false,
                                     store_opt: "x-scheme-handler/mime"
                                         .parse::<mime_guess::Mime>()
                                         .ok()
@@ -4714,11 +8787,73 @@ impl Application for App {
                     }
                 }
             }
+            Message::OpenWithAlways(value) => {
+                if let Some(DialogPage::OpenWith { always, .. }) = self.dialog_pages.front_mut() {
+                    *always = value;
+                }
+            }
             Message::OpenWithSelection(index) => {
                 if let Some(DialogPage::OpenWith { selected, .. }) = self.dialog_pages.front_mut() {
                     *selected = index;
                 }
             }
+            Message::OpenWithSetDefault => {
+                if let Some(DialogPage::OpenWith { mime, selected, .. }) =
+                    self.dialog_pages.front_mut()
+                {
+                    if let Some(app) = self.mime_app_cache.get(mime).get(*selected).cloned() {
+                        self.mime_app_cache
+                            .set_default(mime.clone(), app.id.clone());
+                        // The default app is sorted first, so keep it selected
+                        if let Some(index) = self
+                            .mime_app_cache
+                            .get(mime)
+                            .iter()
+                            .position(|x| x.id == app.id)
+                        {
+                            *selected = index;
+                        }
+                    }
+                }
+            }
+            Message::OpenWithClearDefault => {
+                if let Some(DialogPage::OpenWith { mime, selected, .. }) =
+                    self.dialog_pages.front_mut()
+                {
+                    let id_opt = self
+                        .mime_app_cache
+                        .get(mime)
+                        .get(*selected)
+                        .map(|app| app.id.clone());
+                    self.mime_app_cache.clear_default(mime.clone());
+                    *selected = id_opt
+                        .and_then(|id| {
+                            self.mime_app_cache
+                                .get(mime)
+                                .iter()
+                                .position(|x| x.id == id)
+                        })
+                        .unwrap_or(0);
+                }
+            }
+            Message::PageDown(entity_opt) => {
+                if self.active_panel == PaneType::LeftPane {
+                    return self.update(Message::TabMessage(entity_opt, tab1::Message::PageDown));
+                } else {
+                    return self.update(Message::TabMessageRight(
+                        entity_opt,
+                        tab2::Message::PageDown,
+                    ));
+                }
+            }
+            Message::PageUp(entity_opt) => {
+                if self.active_panel == PaneType::LeftPane {
+                    return self.update(Message::TabMessage(entity_opt, tab1::Message::PageUp));
+                } else {
+                    return self
+                        .update(Message::TabMessageRight(entity_opt, tab2::Message::PageUp));
+                }
+            }
             Message::PaneUpdate => {
                 self.pane_setup(
                     self.show_button_row,
@@ -4754,6 +8889,9 @@ impl Application for App {
             }
             Message::PaneResized(pane_grid::ResizeEvent { split, ratio }) => {
                 self.pane_model.panestates.resize(split, ratio);
+                if let Some(pane_type) = self.pane_model.type_by_split.get(&split) {
+                    self.pane_model.ratio_by_type.insert(*pane_type, ratio);
+                }
             }
             Message::PaneDragged(pane_grid::DragEvent::Dropped { pane, target }) => {
                 self.pane_model.panestates.drop(pane, target);
@@ -4801,7 +8939,8 @@ impl Application for App {
                                     Some(contents) => {
                                         message::app(Message::PasteContents(to.clone(), contents))
                                     }
-                                    None => message::none(),
+                                    // Text is pasted as a new file
+                                    None => message::app(Message::PasteText(to.clone())),
                                 },
                             );
                         }
@@ -4815,30 +8954,38 @@ impl Application for App {
                                     Some(contents) => {
                                         message::app(Message::PasteContents(to.clone(), contents))
                                     }
-                                    None => message::none(),
+                                    // Text is pasted as a new file
+                                    None => message::app(Message::PasteText(to.clone())),
                                 },
                             );
                         }
                     }
                 }
             }
-            Message::PastePrimary(_entity_opt) => {
-                return clipboard::read_primary().map(move |value_opt| match value_opt {
-                    Some(value) => message::app(Message::PasteValueTerminal(value)),
-                    None => message::none(),
-                });
+            Message::PastePrimary(_)
+            | Message::PasteTerminal(_)
+            | Message::PastePrimaryTerminal(_)
+            | Message::TermMiddleClick(..) => {
+                if let Some(source) = terminal_paste_source(&message) {
+                    return paste_terminal(source);
+                }
             }
-            Message::PasteTerminal(_entity_opt) => {
-                return clipboard::read_primary().map(move |value_opt| match value_opt {
-                    Some(value) => message::app(Message::PasteValueTerminal(value)),
-                    None => message::none(),
+            Message::PasteText(to) => {
+                return clipboard::read().map(move |text_opt| match text_opt {
+                    Some(text) if !text.is_empty() => {
+                        message::app(Message::PasteTextFile(to.clone(), text))
+                    }
+                    _ => message::none(),
                 });
             }
-            Message::PastePrimaryTerminal(_entity_opt) => {
-                return clipboard::read_primary().map(move |value_opt| match value_opt {
-                    Some(value) => message::app(Message::PasteValueTerminal(value)),
-                    None => message::none(),
+            Message::PasteTextFile(parent, text) => {
+                self.dialog_pages.push_back(DialogPage::NewItem {
+                    parent,
+                    name: fl!("pasted-text-file-name"),
+                    dir: false,
+                    text_opt: Some(text),
                 });
+                return widget::text_input::focus(self.dialog_text_input.clone());
             }
             Message::PasteValueTerminal(value) => {
                 if let Some(terminalmutex) = &self.terminal.as_mut() {
@@ -4847,23 +8994,9 @@ impl Application for App {
                     }
                 }
             }
-            Message::PasteContents(to, mut contents) => {
-                contents.paths.retain(|p| p != &to);
-                if !contents.paths.is_empty() {
-                    match contents.kind {
-                        ClipboardKind::Copy => {
-                            self.operation(Operation::Copy {
-                                paths: contents.paths,
-                                to,
-                            });
-                        }
-                        ClipboardKind::Cut => {
-                            self.operation(Operation::Move {
-                                paths: contents.paths,
-                                to,
-                            });
-                        }
-                    }
+            Message::PasteContents(to, contents) => {
+                if let Some(op) = paste_operation(to, contents) {
+                    self.operation(op);
                 }
             }
             Message::PendingCancel(id) => {
@@ -4880,8 +9013,14 @@ impl Application for App {
             }
             Message::PendingComplete(id, op_sel) => {
                 let mut commands = Vec::with_capacity(4);
+                let mut finished_opt = None;
+                let mut created = false;
+                let removed_opt = self.pending_operations.remove(&id);
+                self.update_tab_operations();
                 // Show toast for some operations
-                if let Some((op, _)) = self.pending_operations.remove(&id) {
+                if let Some((op, _)) = removed_opt {
+                    created = matches!(op, Operation::NewFile { .. } | Operation::NewFolder { .. });
+                    dir_size::invalidate(&op.changed_paths());
                     if let Some(description) = op.toast() {
                         if let Operation::Delete { ref paths } = op {
                             let paths: Arc<[PathBuf]> = Arc::from(paths.as_slice());
@@ -4897,7 +9036,37 @@ impl Application for App {
                             );
                         }
                     }
-                    self.complete_operations.insert(id, op);
+                    let undoing = self.undo_operation_ids.remove(&id);
+                    let redoing = self.redo_operation_ids.remove(&id);
+                    if !undoing {
+                        if let Some(history) = op.history(&op_sel) {
+                            if !redoing {
+                                self.redo_history.clear();
+                            }
+                            self.undo_history.push(history);
+                        }
+                    }
+                    if let Some(failed_id) = self.retry_operation_ids.remove(&id) {
+                        self.remove_failed_operation(failed_id);
+                    }
+                    if let Some(path) = self.dialog_new_folder_ids.remove(&id) {
+                        commands.push(self.update(Message::CopyMoveToNavigate(path)));
+                    }
+                    finished_opt = Some((op.completed_text(), None));
+                    if !op_sel.changed.is_empty() {
+                        commands.push(
+                            self.toasts
+                                .push(widget::toaster::Toast::new(changed_while_copying(
+                                    &op_sel.changed,
+                                )))
+                                .map(cosmic::app::Message::App),
+                        );
+                    }
+                    self.complete_operations
+                        .insert(id, (op, op_sel.changed.clone()));
+                    while self.complete_operations.len() > MAX_COMPLETE_OPERATIONS {
+                        self.complete_operations.pop_first();
+                    }
                 }
                 // Close progress notification if all relavent operations are finished
                 if !self
@@ -4908,21 +9077,66 @@ impl Application for App {
                     self.progress_operations.clear();
                 }
                 // Potentially show a notification
-                commands.push(self.update_notification());
-                // Rescan and select based on operation
-                commands.push(self.rescan_operation_selection(op_sel));
+                commands.push(self.update_notification(finished_opt));
+                // Rescan and select based on operation, new items are always selected
+                commands.push(self.rescan_operation_selection(op_sel, created));
                 // Manually rescan any trash tabs after any operation is completed
                 commands.push(self.rescan_trash());
+                // Operations may have changed the free space of either panel
+                let (entity_left, entity_right) =
+                    (self.tab_model1.active(), self.tab_model2.active());
+                commands.push(self.update_disk_space_left(entity_left));
+                commands.push(self.update_disk_space_right(entity_right));
                 return Task::batch(commands);
             }
             Message::PendingDismiss => {
                 self.progress_operations.clear();
             }
             Message::PendingError(id, err) => {
-                if let Some((op, controller)) = self.pending_operations.remove(&id) {
+                self.undo_operation_ids.remove(&id);
+                self.redo_operation_ids.remove(&id);
+                // A failed retry replaces the previous failure
+                if let Some(failed_id) = self.retry_operation_ids.remove(&id) {
+                    self.remove_failed_operation(failed_id);
+                }
+                // The destination dialog shows why its new folder was not created
+                if self.dialog_new_folder_ids.remove(&id).is_some() {
+                    if let Some(
+                        DialogPage::CopyMoveTo { error_opt, .. }
+                        | DialogPage::RestoreTo { error_opt, .. },
+                    ) = self.dialog_pages.front_mut()
+                    {
+                        *error_opt = Some(err.clone());
+                    }
+                }
+                let mut commands = Vec::with_capacity(2);
+                let mut finished_opt = None;
+                let removed_opt = self.pending_operations.remove(&id);
+                self.update_tab_operations();
+                if let Some((op, controller)) = removed_opt {
+                    // Failed and cancelled operations may still have changed some items
+                    dir_size::invalidate(&op.changed_paths());
                     // Only show dialog if not cancelled
                     if !controller.is_cancelled() {
+                        finished_opt = Some((
+                            op.pending_text(controller.progress(), controller.state()),
+                            Some(err.clone()),
+                        ));
                         self.dialog_pages.push_back(DialogPage::FailedOperation(id));
+                        commands.push(
+                            self.toasts
+                                .push(
+                                    widget::toaster::Toast::new(fl!(
+                                        "operation-failed",
+                                        operation = op.pending_text(
+                                            controller.progress(),
+                                            controller.state()
+                                        )
+                                    ))
+                                    .action(fl!("retry"), move |tid| Message::RetryFailed(tid, id)),
+                                )
+                                .map(cosmic::app::Message::App),
+                        );
                     }
                     // Remove from progress
                     self.progress_operations.remove(&id);
@@ -4936,8 +9150,15 @@ impl Application for App {
                 {
                     self.progress_operations.clear();
                 }
+                commands.push(self.update_notification(finished_opt));
                 // Manually rescan any trash tabs after any operation is completed
-                return self.rescan_trash();
+                commands.push(self.rescan_trash());
+                // Operations may have changed the free space of either panel
+                let (entity_left, entity_right) =
+                    (self.tab_model1.active(), self.tab_model2.active());
+                commands.push(self.update_disk_space_left(entity_left));
+                commands.push(self.update_disk_space_right(entity_right));
+                return Task::batch(commands);
             }
             Message::PendingPause(id, pause) => {
                 if let Some((_, controller)) = self.pending_operations.get(&id) {
@@ -4957,6 +9178,11 @@ impl Application for App {
                     }
                 }
             }
+            Message::PendingPauseToggle => {
+                if !self.pending_operations.is_empty() {
+                    return self.update(Message::PendingPauseAll(!self.pending_all_paused()));
+                }
+            }
             Message::Preview(entity_opt) => {
                 match self.mode {
                     Mode::App => {
@@ -5009,11 +9235,58 @@ impl Application for App {
                     }
                 }
             }
+            Message::QuickPreview(entity_opt) => {
+                let pane = self.active_panel;
+                let entity = entity_opt.unwrap_or_else(|| {
+                    if pane == PaneType::LeftPane {
+                        self.tab_model1.active()
+                    } else {
+                        self.tab_model2.active()
+                    }
+                });
+                let gallery = if pane == PaneType::LeftPane {
+                    self.tab_model1
+                        .data::<Tab1>(entity)
+                        .is_some_and(|tab| tab.gallery)
+                } else {
+                    self.tab_model2
+                        .data::<Tab2>(entity)
+                        .is_some_and(|tab| tab.gallery)
+                };
+                if gallery {
+                    // Space still closes the gallery
+                    return self.update(Message::GalleryToggle(Some(entity)));
+                }
+                match self.dialog_pages.front() {
+                    Some(DialogPage::QuickPreview { .. }) => {
+                        self.dialog_pages.pop_front();
+                    }
+                    Some(_) => {}
+                    None => {
+                        self.dialog_pages
+                            .push_back(DialogPage::QuickPreview { pane, entity });
+                    }
+                }
+            }
             Message::QueueFileOperations(show) => {
                 self.config.queue_file_operations = show;
                 config_set!(queue_file_operations, self.config.queue_file_operations);
                 return self.update_config();
             }
+            Message::Redo => {
+                self.redo();
+            }
+            Message::RetryAllFailed => {
+                // Failures that are already being retried are skipped
+                let failed_ids: Vec<u64> = self.failed_operations.keys().copied().collect();
+                for failed_id in failed_ids {
+                    self.retry_operation(failed_id);
+                }
+            }
+            Message::RetryFailed(tid, failed_id) => {
+                self.toasts.remove(tid);
+                self.retry_operation(failed_id);
+            }
             Message::RescanTrash => {
                 // Update trash icon if empty/full
                 let maybe_entity = self.nav_model.iter().find(|&entity| {
@@ -5052,6 +9325,13 @@ impl Application for App {
                                     }
                                 }
                             }
+                            // A single item is renamed in place, the dialog renames several
+                            if selected.len() == 1 {
+                                return self.update(Message::TabMessage(
+                                    Some(entity),
+                                    tab1::Message::RenameStart,
+                                ));
+                            }
                             if !selected.is_empty() {
                                 //TODO: batch rename
                                 for path in selected {
@@ -5086,6 +9366,13 @@ impl Application for App {
                                     }
                                 }
                             }
+                            // A single item is renamed in place, the dialog renames several
+                            if selected.len() == 1 {
+                                return self.update(Message::TabMessageRight(
+                                    Some(entity),
+                                    tab2::Message::RenameStart,
+                                ));
+                            }
                             if !selected.is_empty() {
                                 //TODO: batch rename
                                 for path in selected {
@@ -5111,6 +9398,17 @@ impl Application for App {
                     }
                 }
             }
+            Message::ResetTerminal => {
+                // The new shell starts in the folder of the active tab
+                let dir_opt = match self.active_panel {
+                    PaneType::LeftPane => active_tab_path::<LeftPanel>(&self.tab_model1),
+                    _ => active_tab_path::<RightPanel>(&self.tab_model2),
+                };
+                if self.terminal.take().is_some() {
+                    let pane = self.pane_model.pane_by_type[&PaneType::TerminalPane];
+                    return self.create_and_focus_new_terminal(pane, None, dir_opt);
+                }
+            }
             Message::ReplaceResult(replace_result) => {
                 if let Some(dialog_page) = self.dialog_pages.pop_front() {
                     match dialog_page {
@@ -5140,57 +9438,45 @@ impl Application for App {
                 }
             }
             Message::RestoreFromTrash(entity_opt) => {
-                let mut trash_items = Vec::new();
-                let entity = match entity_opt {
-                    Some(entity) => entity,
-                    None => {
-                        if self.active_panel == PaneType::LeftPane {
-                            self.tab_model1.active()
-                        } else {
-                            self.tab_model2.active()
-                        }
-                    }
-                };
-                if self.active_panel == PaneType::LeftPane {
-                    if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                        if let Some(items) = tab.items_opt() {
-                            for item in items.iter() {
-                                if item.selected {
-                                    match &item.metadata {
-                                        ItemMetadata1::Trash { entry, .. } => {
-                                            trash_items.push(entry.clone());
-                                        }
-                                        _ => {
-                                            //TODO: error on trying to restore non-trash file?
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    if !trash_items.is_empty() {
-                        self.operation(Operation::Restore { items: trash_items });
+                let trash_items = self.selected_trash_items(entity_opt);
+                if trash_items.is_empty() {
+                    return Task::none();
+                }
+                // Ask before restoring into folders that no longer exist
+                let mut missing: Vec<PathBuf> = Vec::new();
+                for item in trash_items.iter() {
+                    if !item.original_parent.is_dir() && !missing.contains(&item.original_parent) {
+                        missing.push(item.original_parent.clone());
                     }
+                }
+                if missing.is_empty() {
+                    self.operation(Operation::Restore { items: trash_items });
                 } else {
-                    if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                        if let Some(items) = tab.items_opt() {
-                            for item in items.iter() {
-                                if item.selected {
-                                    match &item.metadata {
-                                        ItemMetadata2::Trash { entry, .. } => {
-                                            trash_items.push(entry.clone());
-                                        }
-                                        _ => {
-                                            //TODO: error on trying to restore non-trash file?
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    if !trash_items.is_empty() {
-                        self.operation(Operation::Restore { items: trash_items });
-                    }
+                    self.dialog_pages.push_back(DialogPage::RestoreMissing {
+                        items: trash_items,
+                        missing,
+                    });
+                }
+            }
+            Message::RestoreTo(entity_opt) => {
+                let trash_items = self.selected_trash_items(entity_opt);
+                if !trash_items.is_empty() {
+                    let (dialog_page, task) = restore_to_dialog(trash_items);
+                    self.dialog_pages.push_back(dialog_page);
+                    return Task::batch([
+                        task,
+                        widget::text_input::focus(self.dialog_text_input.clone()),
+                    ]);
+                }
+            }
+            Message::RestoreMissingTo => {
+                if let Some(DialogPage::RestoreMissing { items, .. }) = self.dialog_pages.front() {
+                    let (dialog_page, task) = restore_to_dialog(items.clone());
+                    self.dialog_pages[0] = dialog_page;
+                    return Task::batch([
+                        task,
+                        widget::text_input::focus(self.dialog_text_input.clone()),
+                    ]);
                 }
             }
             Message::SearchActivate => {
@@ -5206,6 +9492,32 @@ impl Application for App {
             Message::SearchInput(input) => {
                 return self.search_set_active(Some(input));
             }
+            Message::CalculateFolderSizes(entity_opt) => {
+                if self.active_panel == PaneType::LeftPane {
+                    return self.update(Message::TabMessage(
+                        entity_opt,
+                        tab1::Message::CalculateFolderSizes,
+                    ));
+                } else {
+                    return self.update(Message::TabMessageRight(
+                        entity_opt,
+                        tab2::Message::CalculateFolderSizes,
+                    ));
+                }
+            }
+            Message::CancelFolderSizes(entity_opt) => {
+                if self.active_panel == PaneType::LeftPane {
+                    return self.update(Message::TabMessage(
+                        entity_opt,
+                        tab1::Message::CancelFolderSizes,
+                    ));
+                } else {
+                    return self.update(Message::TabMessageRight(
+                        entity_opt,
+                        tab2::Message::CancelFolderSizes,
+                    ));
+                }
+            }
             Message::SelectAll(entity_opt) => {
                 if self.active_panel == PaneType::LeftPane {
                     return self.update(Message::TabMessage(entity_opt, tab1::Message::SelectAll));
@@ -5216,6 +9528,19 @@ impl Application for App {
                     ));
                 }
             }
+            Message::SelectByCriteria => {
+                self.dialog_pages
+                    .push_back(DialogPage::SelectByCriteria(SelectCriteriaInput::default()));
+                return widget::text_input::focus(self.dialog_text_input.clone());
+            }
+            Message::SelectByPattern(select) => {
+                self.dialog_pages.push_back(DialogPage::SelectPattern {
+                    pattern: String::new(),
+                    select,
+                    case_sensitive: false,
+                });
+                return widget::text_input::focus(self.dialog_text_input.clone());
+            }
             Message::SelectFirst(entity_opt) => {
                 if self.active_panel == PaneType::LeftPane {
                     return self
@@ -5227,6 +9552,45 @@ impl Application for App {
                     ));
                 }
             }
+            Message::ChangeExtension(entity_opt) => {
+                let mut paths = self.selected_paths(entity_opt);
+                paths.retain(|path| !path.is_dir());
+                if let Some(first) = paths.first() {
+                    let extension = first
+                        .extension()
+                        .map(|extension| extension.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let renames = change_extension_renames(&paths, &extension);
+                    self.dialog_pages.push_back(DialogPage::ChangeExtension {
+                        paths,
+                        extension,
+                        renames,
+                    });
+                    return widget::text_input::focus(self.dialog_text_input.clone());
+                }
+            }
+            Message::ChangeExtensionInput(input) => {
+                if let Some(DialogPage::ChangeExtension {
+                    paths,
+                    extension,
+                    renames,
+                }) = self.dialog_pages.front_mut()
+                {
+                    *renames = change_extension_renames(paths, &input);
+                    *extension = input;
+                }
+            }
+            Message::SetModified(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                if !paths.is_empty() {
+                    self.dialog_pages.push_back(DialogPage::SetModified {
+                        paths,
+                        now: true,
+                        time: chrono::Local::now().format(TIME_FORMAT).to_string(),
+                        recursive: false,
+                    });
+                }
+            }
             Message::SelectLast(entity_opt) => {
                 if self.active_panel == PaneType::LeftPane {
                     return self.update(Message::TabMessage(entity_opt, tab1::Message::SelectLast));
@@ -5238,25 +9602,13 @@ impl Application for App {
                 }
             }
             Message::SetSort(_entity_opt, sort, dir) => {
-                if self.active_panel == PaneType::LeftPane {
-                    let entity = self.tab_model1.active();
-                    return self.update(Message::TabMessage(
-                        Some(entity),
-                        tab1::Message::SetSort(sort, dir),
-                    ));
-                } else {
-                    let entity = self.tab_model1.active();
-                    let newsort = match sort {
-                        tab1::HeadingOptions::Modified => tab2::HeadingOptions::Modified,
-                        tab1::HeadingOptions::Name => tab2::HeadingOptions::Name,
-                        tab1::HeadingOptions::TrashedOn => tab2::HeadingOptions::TrashedOn,
-                        tab1::HeadingOptions::Size => tab2::HeadingOptions::Size,
-                    };
-                    return self.update(Message::TabMessageRight(
-                        Some(entity),
-                        tab2::Message::SetSort(newsort, dir),
-                    ));
-                }
+                return self.update(set_sort_message(
+                    self.active_panel,
+                    self.tab_model1.active(),
+                    self.tab_model2.active(),
+                    sort,
+                    dir,
+                ));
             }
             Message::SetSortRight(entity_opt, sort, dir) => {
                 return self.update(Message::TabMessageRight(
@@ -5264,25 +9616,133 @@ impl Application for App {
                     tab2::Message::SetSort(sort, dir),
                 ));
             }
+            Message::SetSortSecondary(sort) => {
+                let config =
+                    PanelOption::SortSecondary(sort).apply(&self.config, self.active_panel);
+                return self.update_tab_configs(config);
+            }
             Message::SetShowDetails(show_details) => {
                 config_set!(show_details, show_details);
                 return self.update_config();
             }
             Message::ShowButtonRow(show) => {
+                self.layout_overrides.show_button_row = None;
                 self.config.show_button_row = show;
                 config_set!(show_button_row, self.config.show_button_row);
                 return self.update_config();
             }
             Message::ShowEmbeddedTerminal(show) => {
+                self.layout_overrides.show_embedded_terminal = None;
                 self.config.show_embedded_terminal = show;
                 config_set!(show_embedded_terminal, self.config.show_embedded_terminal);
                 return self.update_config();
             }
+            Message::SidebarShow(builtin, show) => {
+                let mut sidebar_hidden = self.config.sidebar_hidden.clone();
+                sidebar_hidden.retain(|hidden| *hidden != builtin);
+                if !show {
+                    sidebar_hidden.push(builtin);
+                }
+                config_set!(sidebar_hidden, sidebar_hidden);
+                return self.update_config();
+            }
+            Message::SidebarSystemMounts(sidebar_system_mounts) => {
+                config_set!(sidebar_system_mounts, sidebar_system_mounts);
+                return self.update_config();
+            }
+            Message::FavoriteReorder(from, to) => {
+                let mut favorites = self.config.favorites.clone();
+                if Favorite::reorder(&mut favorites, from, to) {
+                    config_set!(favorites, favorites);
+                    return self.update_config();
+                }
+            }
             Message::ShowSecondPanel(show) => {
+                self.layout_overrides.show_second_panel = None;
                 self.config.show_second_panel = show;
                 config_set!(show_second_panel, self.config.show_second_panel);
                 return self.update_config();
             }
+            Message::PermanentlyDelete(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                if paths.is_empty() {
+                    return Task::none();
+                }
+                if self.config.confirm_permanent_delete {
+                    self.dialog_pages
+                        .push_back(DialogPage::PermanentlyDelete { paths });
+                } else {
+                    self.operation(Operation::PermanentDelete { paths });
+                }
+            }
+            Message::TerminalCommand(terminal_command) => {
+                config_set!(terminal_command, terminal_command);
+                return self.update_config();
+            }
+            Message::UniqueNameBase(unique_name_base) => {
+                config_set!(unique_name_base, unique_name_base);
+                return self.update_config();
+            }
+            Message::UniqueNameTemplate(unique_name_template) => {
+                config_set!(unique_name_template, unique_name_template);
+                return self.update_config();
+            }
+            Message::TerminalScrollback(terminal_scrollback) => {
+                config_set!(terminal_scrollback, terminal_scrollback);
+                // The history of the running terminal grows or shrinks right away
+                if let Some(terminal) = &self.terminal {
+                    if let Ok(terminal) = terminal.lock() {
+                        terminal.term.lock().set_options(self.terminal_config());
+                    }
+                }
+                return self.update_config();
+            }
+            Message::TerminalCloseOnExit(terminal_close_on_exit) => {
+                config_set!(terminal_close_on_exit, terminal_close_on_exit);
+                return self.update_config();
+            }
+            Message::OpenScriptsInTerminal(open_scripts_in_terminal) => {
+                config_set!(open_scripts_in_terminal, open_scripts_in_terminal);
+                return self.update_config();
+            }
+            Message::OpenTerminalEmbedded(open_terminal_embedded) => {
+                self.config.open_terminal_embedded = open_terminal_embedded;
+                config_set!(open_terminal_embedded, self.config.open_terminal_embedded);
+                return self.update_config();
+            }
+            Message::SingleInstance(single_instance) => {
+                self.config.single_instance = single_instance;
+                config_set!(single_instance, self.config.single_instance);
+                return self.update_config();
+            }
+            Message::AllowOpenAsRoot(allow_open_as_root) => {
+                config_set!(allow_open_as_root, allow_open_as_root);
+                return self.update_config();
+            }
+            Message::ReadOnly(read_only) => {
+                config_set!(read_only, read_only);
+                return self.update_config();
+            }
+            Message::ReadOnlyRefused(id) => {
+                if self.read_only_refused.remove(&id) {
+                    return self
+                        .toasts
+                        .push(widget::toaster::Toast::new(fl!("read-only-refused")))
+                        .map(cosmic::app::Message::App);
+                }
+            }
+            Message::RespectGitignore(respect_gitignore) => {
+                let config = PanelOption::RespectGitignore(respect_gitignore)
+                    .apply(&self.config, self.active_panel);
+                // Cached sizes may include ignored files or not
+                dir_size::clear_cache();
+                return self.update_tab_configs(config);
+            }
+            Message::SlideshowInterval(slideshow_interval) => {
+                let config = PanelOption::SlideshowInterval(slideshow_interval)
+                    .apply(&self.config, self.active_panel);
+                return self.update_tab_configs(config);
+            }
             Message::StoreOpenPaths => {
                 let mut left = Vec::new();
                 let mut right = Vec::new();
@@ -5304,9 +9764,45 @@ impl Application for App {
                 config_set!(paths_right, right);
                 return self.update_config();
             }
+            Message::StorePaneLayout => {
+                let mut pane_ratios = self.config.pane_ratios.clone();
+                for (pane_type, ratio) in self.pane_model.ratio_by_type.iter() {
+                    let ratio = (ratio * 1000.0).round() as u16;
+                    match pane_ratios
+                        .iter_mut()
+                        .find(|(ratio_type, _)| ratio_type == pane_type)
+                    {
+                        Some((_, stored)) => *stored = ratio,
+                        None => pane_ratios.push((*pane_type, ratio)),
+                    }
+                }
+                if pane_ratios != self.config.pane_ratios {
+                    config_set!(pane_ratios, pane_ratios);
+                }
+                let pane_focus = match self.pane_model.type_by_pane.get(&self.pane_model.focus) {
+                    Some(PaneType::TerminalPane) => PaneType::TerminalPane,
+                    _ => self.active_panel,
+                };
+                if pane_focus != self.config.pane_focus {
+                    config_set!(pane_focus, pane_focus);
+                }
+            }
             Message::SystemThemeModeChange(_theme_mode) => {
                 return self.update_config();
             }
+            Message::FocusOtherPanel => {
+                if !self.show_second_panel {
+                    return Task::none();
+                }
+                // Only the file panels take turns, even if a terminal or button pane has focus
+                let (pane_type, message) = if self.active_panel == PaneType::LeftPane {
+                    (PaneType::RightPane, Message::TabActivateRight)
+                } else {
+                    (PaneType::LeftPane, Message::TabActivateLeft)
+                };
+                self.pane_model.focus = self.pane_by_type(pane_type);
+                return self.update(message);
+            }
             Message::SwapPanels => {
                 if !self.show_second_panel {
                     return Task::none();
@@ -5326,276 +9822,59 @@ impl Application for App {
                 }
             }
             Message::TabActivate(entity) => {
-                if self.active_panel == PaneType::LeftPane {
-                    self.tab_model1.activate(entity);
-                    self.active_panel = PaneType::LeftPane;
-                    if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
-                        self.activate_nav_model_location_left(&tab.location.clone());
-                    }
-                } else {
-                    self.tab_model2.activate(entity);
-                    self.active_panel = PaneType::RightPane;
-                    if let Some(tab) = self.tab_model2.data::<Tab2>(entity) {
-                        self.activate_nav_model_location_right(&tab.location.clone());
-                    }
-                }
-                return self.update_title();
+                return match self.active_panel {
+                    PaneType::LeftPane => self.activate_tab::<LeftPanel>(entity),
+                    _ => self.activate_tab::<RightPanel>(entity),
+                };
             }
             Message::TabActivateLeft => {
-                self.active_panel = PaneType::LeftPane;
                 let entity = self.tab_model1.active();
-                self.active_panel = PaneType::LeftPane;
-                return self.update(Message::TabActivate(entity));
+                return self.activate_tab::<LeftPanel>(entity);
             }
             Message::TabActivateRight => {
-                self.active_panel = PaneType::RightPane;
                 let entity = self.tab_model2.active();
-                self.active_panel = PaneType::RightPane;
-                return self.update(Message::TabActivate(entity));
+                return self.activate_tab::<RightPanel>(entity);
             }
             Message::TabActivateLeftEntity(entity) => {
-                self.active_panel = PaneType::LeftPane;
-                self.active_panel = PaneType::LeftPane;
-                return self.update(Message::TabActivate(entity));
+                self.tab_list_opt = None;
+                return self.activate_tab::<LeftPanel>(entity);
             }
             Message::TabActivateRightEntity(entity) => {
-                self.active_panel = PaneType::RightPane;
-                self.active_panel = PaneType::RightPane;
-                return self.update(Message::TabActivate(entity));
+                self.tab_list_opt = None;
+                return self.activate_tab::<RightPanel>(entity);
+            }
+            Message::TabList(pane_type_opt) => {
+                self.tab_list_opt = pane_type_opt;
             }
             Message::TabNext => {
-                if self.active_panel == PaneType::LeftPane {
-                    let len = self.tab_model1.iter().count();
-                    let pos = self
-                        .tab_model1
-                        .position(self.tab_model1.active())
-                        // Wraparound to 0 if i + 1 > num of tabs
-                        .map(|i| (i as usize + 1) % len)
-                        .expect("should always be at least one tab open");
-
-                    let entity = self.tab_model1.iter().nth(pos);
-                    if let Some(entity) = entity {
-                        return self.update(Message::TabActivate(entity));
-                    }
-                } else {
-                    let len = self.tab_model2.iter().count();
-                    let pos = self
-                        .tab_model2
-                        .position(self.tab_model2.active())
-                        // Wraparound to 0 if i + 1 > num of tabs
-                        .map(|i| (i as usize + 1) % len)
-                        .expect("should always be at least one tab open");
-
-                    let entity = self.tab_model2.iter().nth(pos);
-                    if let Some(entity) = entity {
-                        return self.update(Message::TabActivate(entity));
-                    }
-                }
+                return match self.active_panel {
+                    PaneType::LeftPane => self.activate_tab_next::<LeftPanel>(true),
+                    _ => self.activate_tab_next::<RightPanel>(true),
+                };
             }
             Message::TabPrev => {
-                if self.active_panel == PaneType::LeftPane {
-                    let pos = self
-                        .tab_model1
-                        .position(self.tab_model1.active())
-                        .and_then(|i| (i as usize).checked_sub(1))
-                        // Subtraction underflow => last tab; i.e. it wraps around
-                        .unwrap_or_else(|| {
-                            self.tab_model1
-                                .iter()
-                                .count()
-                                .checked_sub(1)
-                                .unwrap_or_default()
-                        });
-
-                    let entity = self.tab_model1.iter().nth(pos);
-                    if let Some(entity) = entity {
-                        return self.update(Message::TabActivate(entity));
-                    }
-                } else {
-                    let pos = self
-                        .tab_model2
-                        .position(self.tab_model2.active())
-                        .and_then(|i| (i as usize).checked_sub(1))
-                        // Subtraction underflow => last tab; i.e. it wraps around
-                        .unwrap_or_else(|| {
-                            self.tab_model2
-                                .iter()
-                                .count()
-                                .checked_sub(1)
-                                .unwrap_or_default()
-                        });
-
-                    let entity = self.tab_model2.iter().nth(pos);
-                    if let Some(entity) = entity {
-                        return self.update(Message::TabActivate(entity));
-                    }
-                }
+                return match self.active_panel {
+                    PaneType::LeftPane => self.activate_tab_next::<LeftPanel>(false),
+                    _ => self.activate_tab_next::<RightPanel>(false),
+                };
             }
             Message::TabRescan => {
-                if self.active_panel == PaneType::LeftPane {
-                    let entity = self.tab_model1.active();
-                    if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                        let location = tab.location.clone();
-
-                        return self.update(Message::TabRescanLeft(
-                            entity,
-                            location,
-                            None,
-                            Vec::new(),
-                            None,
-                        ));
-                    }
-                } else {
-                    let entity = self.tab_model2.active();
-                    if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                        let location = tab.location.clone();
-
-                        return self.update(Message::TabRescanRight(
-                            entity,
-                            location,
-                            None,
-                            Vec::new(),
-                            None,
-                        ));
-                    }
-                }
+                return match self.active_panel {
+                    PaneType::LeftPane => self.rescan_active_tab::<LeftPanel>(),
+                    _ => self.rescan_active_tab::<RightPanel>(),
+                };
             }
             Message::TabClose(entity_opt) => {
-                let entity = match entity_opt {
-                    Some(entity) => entity,
-                    None => {
-                        if self.active_panel == PaneType::LeftPane {
-                            self.tab_model1.active()
-                        } else {
-                            self.tab_model2.active()
-                        }
-                    }
+                return match self.active_panel {
+                    PaneType::LeftPane => self.close_tab_in::<LeftPanel>(entity_opt),
+                    _ => self.close_tab_in::<RightPanel>(entity_opt),
                 };
-                if self.active_panel == PaneType::LeftPane {
-                    if let Some(position) = self.tab_model1.position(entity) {
-                        let new_position = if position > 0 {
-                            position - 1
-                        } else {
-                            position + 1
-                        };
-
-                        if self.tab_model1.activate_position(new_position) {
-                            if let Some(new_entity) = self.tab_model1.entity_at(new_position) {
-                                if let Some(tab) = self.tab_model1.data::<Tab1>(new_entity) {
-                                    self.activate_nav_model_location_left(&tab.location.clone());
-                                }
-                            }
-                        }
-                    }
-
-                    // Remove item
-                    self.tab_model1.remove(entity);
-
-                    // If that was the last tab, close window
-                    if self.tab_model1.iter().next().is_none() {
-                        if let Some(window_id) = &self.window_id_opt {
-                            return window::close(*window_id);
-                        }
-                    }
-                    // Activate closest item
-                    let _ = self.update(Message::StoreOpenPaths);
-                    return Task::batch([self.update_title(), self.update_watcher_left()]);
-                } else {
-                    if let Some(position) = self.tab_model2.position(entity) {
-                        let new_position = if position > 0 {
-                            position - 1
-                        } else {
-                            position + 1
-                        };
-
-                        if self.tab_model2.activate_position(new_position) {
-                            if let Some(new_entity) = self.tab_model2.entity_at(new_position) {
-                                if let Some(tab) = self.tab_model2.data::<Tab2>(new_entity) {
-                                    self.activate_nav_model_location_right(&tab.location.clone());
-                                }
-                            }
-                        }
-                    }
-
-                    // Remove item
-                    self.tab_model2.remove(entity);
-
-                    // If that was the last tab, close window
-                    if self.tab_model2.iter().next().is_none() {
-                        if let Some(window_id) = &self.window_id_opt {
-                            return window::close(*window_id);
-                        }
-                    }
-                    // Activate closest item
-                    let _ = self.update(Message::StoreOpenPaths);
-                    return Task::batch([self.update_title(), self.update_watcher_right()]);
-                }
             }
             Message::TabCloseLeft(entity_opt) => {
-                self.active_panel = PaneType::LeftPane;
-                let entity = match entity_opt {
-                    Some(entity) => entity,
-                    None => self.tab_model1.active(),
-                };
-                if let Some(position) = self.tab_model1.position(entity) {
-                    let new_position = if position > 0 {
-                        position - 1
-                    } else {
-                        position + 1
-                    };
-
-                    if self.tab_model1.activate_position(new_position) {
-                        if let Some(new_entity) = self.tab_model1.entity_at(new_position) {
-                            if let Some(tab) = self.tab_model1.data::<Tab1>(new_entity) {
-                                self.activate_nav_model_location_left(&tab.location.clone());
-                            }
-                        }
-                    }
-                }
-
-                // Remove item
-                self.tab_model1.remove(entity);
-
-                // If that was the last tab, close window
-                if self.tab_model1.iter().next().is_none() {
-                    if let Some(window_id) = &self.window_id_opt {
-                        return window::close(*window_id);
-                    }
-                }
-                let _ = self.update(Message::StoreOpenPaths);
+                return self.close_tab_in::<LeftPanel>(entity_opt);
             }
             Message::TabCloseRight(entity_opt) => {
-                self.active_panel = PaneType::RightPane;
-                let entity = match entity_opt {
-                    Some(entity) => entity,
-                    None => self.tab_model2.active(),
-                };
-                if let Some(position) = self.tab_model2.position(entity) {
-                    let new_position = if position > 0 {
-                        position - 1
-                    } else {
-                        position + 1
-                    };
-
-                    if self.tab_model2.activate_position(new_position) {
-                        if let Some(new_entity) = self.tab_model2.entity_at(new_position) {
-                            if let Some(tab) = self.tab_model2.data::<Tab2>(new_entity) {
-                                self.activate_nav_model_location_right(&tab.location.clone());
-                            }
-                        }
-                    }
-                }
-
-                // Remove item
-                self.tab_model2.remove(entity);
-
-                // If that was the last tab, close window
-                if self.tab_model2.iter().next().is_none() {
-                    if let Some(window_id) = &self.window_id_opt {
-                        return window::close(*window_id);
-                    }
-                }
-                let _ = self.update(Message::StoreOpenPaths);
+                return self.close_tab_in::<RightPanel>(entity_opt);
             }
             Message::TabConfigLeft(config) => {
                 if config != self.config.tab_left {
@@ -5637,29 +9916,158 @@ impl Application for App {
                     return self.open_tab_right(location, true, None);
                 }
             }
-            Message::ToggleFoldersFirst => {
+            Message::ToggleFlattenView(entity_opt) => {
                 if self.active_panel == PaneType::LeftPane {
-                    let mut config = self.config.tab_left;
-                    config.folders_first = !config.folders_first;
-                    return self.update(Message::TabConfigLeft(config));
+                    return self.update(Message::TabMessage(
+                        entity_opt,
+                        tab1::Message::ToggleFlatten,
+                    ));
                 } else {
-                    let mut config = self.config.tab_right;
-                    config.folders_first = !config.folders_first;
-                    return self.update(Message::TabConfigRight(config));
+                    return self.update(Message::TabMessageRight(
+                        entity_opt,
+                        tab2::Message::ToggleFlatten,
+                    ));
+                }
+            }
+            Message::ToggleFoldersFirst => {
+                let folders_first = if self.active_panel == PaneType::LeftPane {
+                    self.config.tab_left.folders_first
+                } else {
+                    self.config.tab_right.folders_first
+                };
+                let config = PanelOption::FoldersFirst(!folders_first)
+                    .apply(&self.config, self.active_panel);
+                return self.update_tab_configs(config);
+            }
+            Message::DiffSelected => {
+                let (active, other, active_is_left) = if self.active_panel == PaneType::LeftPane {
+                    (
+                        tab_selected_paths::<LeftPanel>(&self.tab_model1, None),
+                        tab_selected_paths::<RightPanel>(&self.tab_model2, None),
+                        true,
+                    )
+                } else {
+                    (
+                        tab_selected_paths::<RightPanel>(&self.tab_model2, None),
+                        tab_selected_paths::<LeftPanel>(&self.tab_model1, None),
+                        false,
+                    )
+                };
+                // Only the selection of the active panel counts if the other panel is hidden
+                let other = if self.show_second_panel {
+                    other
+                } else {
+                    Vec::new()
+                };
+                let Some((left, right)) = diff_pair(&active, &other, active_is_left) else {
+                    return self
+                        .toasts
+                        .push(widget::toaster::Toast::new(fl!("compare-files-select")))
+                        .map(cosmic::app::Message::App);
+                };
+                return Task::perform(
+                    async move {
+                        let (left2, right2) = (left.clone(), right.clone());
+                        match tokio::task::spawn_blocking(move || diff::diff_files(&left2, &right2))
+                            .await
+                        {
+                            Ok(rows_res) => {
+                                message::app(Message::DiffResult(left, right, rows_res))
+                            }
+                            Err(err) => {
+                                log::warn!("failed to compare files: {}", err);
+                                message::none()
+                            }
+                        }
+                    },
+                    |x| x,
+                );
+            }
+            Message::DiffResult(left, right, rows_res) => {
+                self.dialog_pages.push_back(DialogPage::Diff {
+                    left,
+                    right,
+                    rows_res,
+                });
+            }
+            Message::MirrorSelection => {
+                if !self.show_second_panel {
+                    return Task::none();
                 }
+                let (entity_left, entity_right) =
+                    (self.tab_model1.active(), self.tab_model2.active());
+                let (names, matched) = if self.active_panel == PaneType::LeftPane {
+                    let names = self
+                        .tab_model1
+                        .data::<Tab1>(entity_left)
+                        .map(|tab| tab.selected_names())
+                        .unwrap_or_default();
+                    let matched = self
+                        .tab_model2
+                        .data_mut::<Tab2>(entity_right)
+                        .map_or(0, |tab| tab.select_names(&names));
+                    (names, matched)
+                } else {
+                    let names = self
+                        .tab_model2
+                        .data::<Tab2>(entity_right)
+                        .map(|tab| tab.selected_names())
+                        .unwrap_or_default();
+                    let matched = self
+                        .tab_model1
+                        .data_mut::<Tab1>(entity_left)
+                        .map_or(0, |tab| tab.select_names(&names));
+                    (names, matched)
+                };
+                return self
+                    .toasts
+                    .push(widget::toaster::Toast::new(fl!(
+                        "mirrored-selection",
+                        matched = matched,
+                        selected = names.len()
+                    )))
+                    .map(cosmic::app::Message::App);
+            }
+            Message::ToggleMirrorNavigation => {
+                self.mirror_navigation = !self.mirror_navigation;
+            }
+            Message::ToggleReadOnly => {
+                return self.update(Message::ReadOnly(!self.config.read_only));
+            }
+            Message::ToggleRespectGitignore => {
+                return self.update(Message::RespectGitignore(!self.respect_gitignore()));
             }
             Message::ToggleShowHidden(entity_opt) => {
-                if self.active_panel == PaneType::LeftPane {
-                    return self.update(Message::TabMessage(
+                let (task, show_hidden_opt) = if self.active_panel == PaneType::LeftPane {
+                    let task = self.update(Message::TabMessage(
                         entity_opt,
                         tab1::Message::ToggleShowHidden,
                     ));
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model1.active());
+                    let show_hidden_opt = self
+                        .tab_model1
+                        .data::<Tab1>(entity)
+                        .map(|tab| tab.config.show_hidden);
+                    (task, show_hidden_opt)
                 } else {
-                    return self.update(Message::TabMessageRight(
+                    let task = self.update(Message::TabMessageRight(
                         entity_opt,
                         tab2::Message::ToggleShowHidden,
                     ));
-                }
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model2.active());
+                    let show_hidden_opt = self
+                        .tab_model2
+                        .data::<Tab2>(entity)
+                        .map(|tab| tab.config.show_hidden);
+                    (task, show_hidden_opt)
+                };
+                // Hidden files are shown by default in new tabs of the same panel only
+                let Some(show_hidden) = show_hidden_opt else {
+                    return task;
+                };
+                let config =
+                    PanelOption::ShowHidden(show_hidden).apply(&self.config, self.active_panel);
+                return Task::batch([task, self.update_tab_configs(config)]);
             }
             Message::ToggleSortLeft(entity_opt, sort) => {
                 return self.update(Message::TabMessage(
@@ -5685,6 +10093,14 @@ impl Application for App {
                     self.set_show_context(false);
                 }
 
+                // Location before the update, for mirroring navigation to the other panel
+                let from_opt = match self.tab_model1.data::<Tab1>(entity) {
+                    Some(Tab1 {
+                        location: Location1::Path(path),
+                        ..
+                    }) => Some(path.clone()),
+                    _ => None,
+                };
                 let tab_commands = match { self.tab_model1.data_mut::<Tab1>(entity) } {
                     Some(tab) => tab.update(tab_message, self.modifiers),
                     _ => Vec::new(),
@@ -5712,19 +10128,49 @@ impl Application for App {
                             commands.push(self.update_config());
                         }
                         tab1::Command::ChangeLocation(tab_title, tab_path, selection_paths) => {
-                            self.activate_nav_model_location_left(&tab_path);
+                            let mirror_opt = match (&from_opt, &tab_path) {
+                                (Some(from), Location1::Path(to))
+                                    if self.mirror_navigation
+                                        && self.show_second_panel
+                                        && active_panel == PaneType::LeftPane
+                                        && entity == self.tab_model1.active() =>
+                                {
+                                    match self.tab_model2.active_data::<Tab2>() {
+                                        Some(Tab2 {
+                                            location: Location2::Path(other),
+                                            ..
+                                        }) => mirror_path(from, to, other),
+                                        _ => None,
+                                    }
+                                }
+                                _ => None,
+                            };
+                            self.activate_nav_model_location(&tab_path);
                             self.tab_model1.text_set(entity, tab_title);
                             commands.push(Task::batch([
                                 self.update_title(),
                                 self.update_watcher_left(),
                                 self.update_tab_left(entity, tab_path, selection_paths),
                             ]));
+                            // The other panel stays put if it has no matching folder
+                            if let Some(path) = mirror_opt {
+                                commands.push(self.update(Message::TabMessageRight(
+                                    None,
+                                    tab2::Message::Location(Location2::Path(path)),
+                                )));
+                            }
                         }
                         tab1::Command::DropFiles(to, from) => {
-                            commands.push(self.update(Message::PasteContents(to, from)));
+                            if self.modifiers.alt() {
+                                commands.push(self.compress_dialog(from.paths, to));
+                            } else {
+                                commands.push(self.update(Message::PasteContents(to, from)));
+                            }
                         }
                         tab1::Command::EmptyTrash => {
-                            self.dialog_pages.push_back(DialogPage::EmptyTrash);
+                            self.dialog_pages
+                                .push_back(DialogPage::EmptyTrash { summary_opt: None });
+                            commands.push(self.empty_trash_summary());
                         }
                         #[cfg(feature = "desktop")]
                         tab1::Command::ExecEntryAction(entry, action) => {
@@ -5741,6 +10187,7 @@ impl Application for App {
                             self.operation(Operation::Delete { paths });
                         }
                         tab1::Command::OpenFile(path) => self.open_file(&path),
+                        tab1::Command::OpenFiles(paths) => self.open_files(paths),
                         tab1::Command::OpenInNewTab(path) => {
                             commands.push(self.open_tab(
                                 Location1::Path(path.clone()),
@@ -5748,17 +10195,9 @@ impl Application for App {
                                 None,
                             ));
                         }
-                        tab1::Command::OpenInNewWindow(path) => match env::current_exe() {
-                            Ok(exe) => match process::Command::new(&exe).arg(path).spawn() {
-                                Ok(_child) => {}
-                                Err(err) => {
-                                    log::error!("failed to execute {:?}: {}", exe, err);
-                                }
-                            },
-                            Err(err) => {
-                                log::error!("failed to get current executable path: {}", err);
-                            }
-                        },
+                        tab1::Command::OpenInNewWindow(path) => {
+                            spawn_new_window([path.into_os_string()]);
+                        }
                         tab1::Command::OpenTrash => {
                             //TODO: use handler for x-scheme-handler/trash and open trash:///
                             let mut command = process::Command::new("commander");
@@ -5774,10 +10213,35 @@ impl Application for App {
                             self.context_page = ContextPage::Preview(Some(entity), kind);
                             self.set_show_context(true);
                         }
+                        tab1::Command::Rename(from, to) => {
+                            self.operation(Operation::Rename { from, to });
+                        }
+                        tab1::Command::Rescan => {
+                            if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
+                                let location = tab.location.clone();
+                                commands.push(self.update_tab_left(entity, location, None));
+                            }
+                        }
                         tab1::Command::SetOpenWith(mime, id) => {
                             //TODO: this will block for a few ms, run in background?
                             self.mime_app_cache.set_default(mime, id);
                         }
+                        tab1::Command::FolderView(path, folder_view) => {
+                            let mut folder_views = self.config.folder_views_left.clone();
+                            config::remember_folder_view(&mut folder_views, path, folder_view);
+                            config_set!(folder_views_left, folder_views);
+                            self.update_folder_views();
+                        }
+                        tab1::Command::SetPermissions(path, mode, recursive) => {
+                            self.operation(Operation::SetPermissions {
+                                path,
+                                mode,
+                                recursive,
+                            });
+                        }
+                        tab1::Command::SetSymlinkTarget(path, target) => {
+                            self.operation(Operation::SetSymlinkTarget { path, target });
+                        }
                         tab1::Command::WindowDrag => {
                             if let Some(window_id) = &self.window_id_opt {
                                 commands.push(window::drag(*window_id));
@@ -5805,6 +10269,14 @@ impl Application for App {
                     self.set_show_context(false);
                 }
 
+                // Location before the update, for mirroring navigation to the other panel
+                let from_opt = match self.tab_model2.data::<Tab2>(entity) {
+                    Some(Tab2 {
+                        location: Location2::Path(path),
+                        ..
+                    }) => Some(path.clone()),
+                    _ => None,
+                };
                 let tab_commands = match { self.tab_model2.data_mut::<Tab2>(entity) } {
                     Some(tab) => tab.update(tab_message, self.modifiers),
                     _ => Vec::new(),
@@ -5831,19 +10303,49 @@ impl Application for App {
                             commands.push(self.update_config());
                         }
                         tab2::Command::ChangeLocation(tab_title, tab_path, selection_paths) => {
-                            self.activate_nav_model_location_right(&tab_path);
+                            let mirror_opt = match (&from_opt, &tab_path) {
+                                (Some(from), Location2::Path(to))
+                                    if self.mirror_navigation
+                                        && self.show_second_panel
+                                        && active_panel == PaneType::RightPane
+                                        && entity == self.tab_model2.active() =>
+                                {
+                                    match self.tab_model1.active_data::<Tab1>() {
+                                        Some(Tab1 {
+                                            location: Location1::Path(other),
+                                            ..
+                                        }) => mirror_path(from, to, other),
+                                        _ => None,
+                                    }
+                                }
+                                _ => None,
+                            };
+                            self.activate_nav_model_location(&RightPanel::nav_location(&tab_path));
                             self.tab_model2.text_set(entity, tab_title);
                             commands.push(Task::batch([
                                 self.update_title(),
                                 self.update_watcher_right(),
                                 self.update_tab_right(entity, tab_path, selection_paths),
                             ]));
+                            // The other panel stays put if it has no matching folder
+                            if let Some(path) = mirror_opt {
+                                commands.push(self.update(Message::TabMessage(
+                                    None,
+                                    tab1::Message::Location(Location1::Path(path)),
+                                )));
+                            }
                         }
                         tab2::Command::DropFiles(to, from) => {
-                            commands.push(self.update(Message::PasteContents(to, from)));
+                            if self.modifiers.alt() {
+                                commands.push(self.compress_dialog(from.paths, to));
+                            } else {
+                                commands.push(self.update(Message::PasteContents(to, from)));
+                            }
                         }
                         tab2::Command::EmptyTrash => {
-                            self.dialog_pages.push_back(DialogPage::EmptyTrash);
+                            self.dialog_pages
+                                .push_back(DialogPage::EmptyTrash { summary_opt: None });
+                            commands.push(self.empty_trash_summary());
                         }
                         #[cfg(feature = "desktop")]
                         tab2::Command::ExecEntryAction(entry, action) => {
@@ -5858,6 +10360,7 @@ impl Application for App {
                             self.operation(Operation::Delete { paths });
                         }
                         tab2::Command::OpenFile(path) => self.open_file(&path),
+                        tab2::Command::OpenFiles(paths) => self.open_files(paths),
                         tab2::Command::OpenInNewTab(path) => {
                             commands.push(self.open_tab_right(
                                 Location2::Path(path.clone()),
@@ -5865,17 +10368,9 @@ impl Application for App {
                                 None,
                             ));
                         }
-                        tab2::Command::OpenInNewWindow(path) => match env::current_exe() {
-                            Ok(exe) => match process::Command::new(&exe).arg(path).spawn() {
-                                Ok(_child) => {}
-                                Err(err) => {
-                                    log::error!("failed to execute {:?}: {}", exe, err);
-                                }
-                            },
-                            Err(err) => {
-                                log::error!("failed to get current executable path: {}", err);
-                            }
-                        },
+                        tab2::Command::OpenInNewWindow(path) => {
+                            spawn_new_window([path.into_os_string()]);
+                        }
                         tab2::Command::OpenTrash => {
                             //TODO: use handler for x-scheme-handler/trash and open trash:///
                             let mut command = process::Command::new("commander");
@@ -5891,10 +10386,35 @@ impl Application for App {
                             self.context_page = ContextPage::Preview(Some(entity), kind);
                             self.set_show_context(true);
                         }
+                        tab2::Command::Rename(from, to) => {
+                            self.operation(Operation::Rename { from, to });
+                        }
+                        tab2::Command::Rescan => {
+                            if let Some(tab) = self.tab_model2.data::<Tab2>(entity) {
+                                let location = tab.location.clone();
+                                commands.push(self.update_tab_right(entity, location, None));
+                            }
+                        }
                         tab2::Command::SetOpenWith(mime, id) => {
                             //TODO: this will block for a few ms, run in background?
                             self.mime_app_cache.set_default(mime, id);
                         }
+                        tab2::Command::FolderView(path, folder_view) => {
+                            let mut folder_views = self.config.folder_views_right.clone();
+                            config::remember_folder_view(&mut folder_views, path, folder_view);
+                            config_set!(folder_views_right, folder_views);
+                            self.update_folder_views();
+                        }
+                        tab2::Command::SetPermissions(path, mode, recursive) => {
+                            self.operation(Operation::SetPermissions {
+                                path,
+                                mode,
+                                recursive,
+                            });
+                        }
+                        tab2::Command::SetSymlinkTarget(path, target) => {
+                            self.operation(Operation::SetSymlinkTarget { path, target });
+                        }
                         tab2::Command::WindowDrag => {
                             if let Some(window_id) = &self.window_id_opt {
                                 commands.push(window::drag(*window_id));
@@ -5929,49 +10449,120 @@ impl Application for App {
                     return self.open_tab_right(location, true, None);
                 }
             }
-            Message::TabRescanLeft(entity, location, parent_item_opt, items, selection_paths) => {
+            Message::TabRescanLeft(
+                entity,
+                location,
+                epoch,
+                parent_item_opt,
+                items_opt,
+                scan_error_opt,
+                selection_paths,
+            ) => {
                 if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                    if location == tab.location {
+                    if location == tab.location && tab.scan_current(epoch) {
                         tab.parent_item_opt = parent_item_opt;
-                        tab.set_items(items);
+                        tab.scan_error_opt = scan_error_opt;
+                        match items_opt {
+                            Some(items) => tab.set_items(items),
+                            // Items were added in batches while scanning
+                            None => tab.append_items(Vec::new()),
+                        }
+                        let scroll = selection_paths.is_some();
                         if let Some(selection_paths) = selection_paths {
                             tab.select_paths(selection_paths);
                         }
+                        let disk_space = self.update_disk_space_left(entity);
+                        if !scroll {
+                            return disk_space;
+                        }
+                        // Items only have a position once the new items were laid out
+                        return Task::batch([
+                            disk_space,
+                            Task::perform(async {}, move |()| {
+                                message::app(Message::TabMessage(
+                                    Some(entity),
+                                    tab1::Message::ScrollToFocus,
+                                ))
+                            }),
+                        ]);
+                    }
+                }
+            }
+            Message::TabRescanBatchLeft(entity, location, epoch, items) => {
+                if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
+                    if location == tab.location && tab.scan_current(epoch) {
+                        tab.append_items(items);
                     }
                 }
             }
-            Message::TabRescanRight(entity, location, parent_item_opt, items, selection_paths) => {
+            Message::TabRescanRight(
+                entity,
+                location,
+                epoch,
+                parent_item_opt,
+                items_opt,
+                scan_error_opt,
+                selection_paths,
+            ) => {
                 if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                    if location == tab.location {
+                    if location == tab.location && tab.scan_current(epoch) {
                         tab.parent_item_opt = parent_item_opt;
-                        tab.set_items(items);
+                        tab.scan_error_opt = scan_error_opt;
+                        match items_opt {
+                            Some(items) => tab.set_items(items),
+                            // Items were added in batches while scanning
+                            None => tab.append_items(Vec::new()),
+                        }
+                        let scroll = selection_paths.is_some();
                         if let Some(selection_paths) = selection_paths {
                             tab.select_paths(selection_paths);
                         }
+                        let disk_space = self.update_disk_space_right(entity);
+                        if !scroll {
+                            return disk_space;
+                        }
+                        // Items only have a position once the new items were laid out
+                        return Task::batch([
+                            disk_space,
+                            Task::perform(async {}, move |()| {
+                                message::app(Message::TabMessageRight(
+                                    Some(entity),
+                                    tab2::Message::ScrollToFocus,
+                                ))
+                            }),
+                        ]);
                     }
                 }
             }
-            Message::TabView(_entity_opt, view) => {
-                if self.active_panel == PaneType::LeftPane {
-                    let entity = self.tab_model1.active();
-                    if let Some(tab) = self.tab_model1.data_mut::<Tab1>(entity) {
-                        tab.config.view = view;
-                        let mut config = self.config.tab_left;
-                        config.view = view;
-                        return self.update(Message::TabConfigLeft(config));
+            Message::TabRescanBatchRight(entity, location, epoch, items) => {
+                if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
+                    if location == tab.location && tab.scan_current(epoch) {
+                        tab.append_items(items);
                     }
+                }
+            }
+            Message::TabView(entity_opt, view) => {
+                // The view is remembered for the current folder and becomes the default
+                if self.active_panel == PaneType::LeftPane {
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model1.active());
+                    let task =
+                        self.update(Message::TabMessage(Some(entity), tab1::Message::View(view)));
+                    let mut config = self.config.tab_left;
+                    config.view = view;
+                    return Task::batch([task, self.update(Message::TabConfigLeft(config))]);
                 } else {
                     let newview = match view {
                         tab1::View::Grid => tab2::View::Grid,
                         tab1::View::List => tab2::View::List,
                     };
-                    let entity = self.tab_model2.active();
-                    if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
-                        tab.config.view = newview;
-                        let mut config = self.config.tab_right;
-                        config.view = newview;
-                        return self.update(Message::TabConfigRight(config));
-                    }
+                    let entity = entity_opt.unwrap_or_else(|| self.tab_model2.active());
+                    let task = self.update(Message::TabMessageRight(
+                        Some(entity),
+                        tab2::Message::View(newview),
+                    ));
+                    let mut config = self.config.tab_right;
+                    config.view = newview;
+                    return Task::batch([task, self.update(Message::TabConfigRight(config))]);
                 }
             }
             Message::TermContextAction(action) => {
@@ -6054,8 +10645,41 @@ impl Application for App {
                             terminal.needs_update = true;
                         }
                     }
-                    TermEvent::ChildExit(_error_code) => {
-                        //Ignore this for now
+                    TermEvent::ChildExit(exit_code) => {
+                        // Only commands are reported, the shell exiting is ignored for now
+                        let Some((command, exit_code_opt)) = &mut self.terminal_command_opt else {
+                            return Task::none();
+                        };
+                        *exit_code_opt = Some(exit_code);
+                        let failed = exit_code != 0;
+                        if failed {
+                            log::warn!("{} exited with code {}", command, exit_code);
+                        } else {
+                            log::info!("{} exited", command);
+                        }
+                        let toast = fl!(
+                            "terminal-command-failed",
+                            command = command.as_str(),
+                            code = exit_code
+                        );
+                        if self.config.terminal_close_on_exit {
+                            // Focus stays where it was when the command exits
+                            let focus = self.pane_model.focus;
+                            let pane = self.pane_model.pane_by_type[&PaneType::TerminalPane];
+                            let _ = self.create_and_focus_new_terminal(pane, None, None);
+                            self.pane_model.focus = focus;
+                        } else if let Some(terminal) = &self.terminal {
+                            terminal
+                                .lock()
+                                .unwrap()
+                                .notice(&fl!("terminal-process-exited", code = exit_code));
+                        }
+                        if failed {
+                            return self
+                                .toasts
+                                .push(widget::toaster::Toast::new(toast))
+                                .map(cosmic::app::Message::App);
+                        }
                     }
                 }
             }
@@ -6072,20 +10696,24 @@ impl Application for App {
                 // Spawn first tab
                 return self.update(Message::TermNew);
             }
-            Message::TermMiddleClick(_pane, _entity_opt) => {
-                return Task::batch([clipboard::read_primary().map(
-                    move |value_opt| match value_opt {
-                        Some(value) => message::app(Message::PasteValueTerminal(value)),
-                        None => message::none(),
-                    },
-                )]);
+            Message::TerminalThemeDark(index) => {
+                if let Some(name) = self.theme_names_dark.get(index) {
+                    config_set!(terminal_theme_dark, name.clone());
+                    return self.update_config();
+                }
+            }
+            Message::TerminalThemeLight(index) => {
+                if let Some(name) = self.theme_names_light.get(index) {
+                    config_set!(terminal_theme_light, name.clone());
+                    return self.update_config();
+                }
             }
             Message::TermMouseEnter(pane) => {
                 self.pane_model.focus = pane;
             }
             Message::TermNew => {
                 let pane = self.pane_model.pane_by_type[&PaneType::TerminalPane];
-                return self.create_and_focus_new_terminal(pane);
+                return self.create_and_focus_new_terminal(pane, None, None);
             }
             Message::ToggleContextPage(context_page) => {
                 //TODO: ensure context menus are closed
@@ -6102,10 +10730,35 @@ impl Application for App {
                     )));
                 }
             }
-            Message::Undo(_id) => {
-                // TODO: undo
-            }
+            Message::Undo => match self.undo_history.last() {
+                // Deleting copies that replaced items would lose both, so they are not undone
+                Some(history) if !history.replaced.is_empty() => {
+                    let items = history.replaced.len();
+                    self.undo_history.pop();
+                    return self
+                        .toasts
+                        .push(widget::toaster::Toast::new(fl!(
+                            "undo-replaced-refused",
+                            items = items
+                        )))
+                        .map(cosmic::app::Message::App);
+                }
+                // Deleting copies cannot be reverted, so ask first
+                Some(history) if !history.destructive_paths().is_empty() => {
+                    self.dialog_pages
+                        .push_back(DialogPage::UndoPermanentDelete {
+                            history: history.clone(),
+                        });
+                }
+                _ => self.undo(),
+            },
             Message::UndoTrash(id, recently_trashed) => {
+                // The toast restores the items, so drop them from the history
+                self.undo_history.retain(|history| match &history.redo {
+                    Operation::Delete { paths } => paths[..] != recently_trashed[..],
+                    _ => true,
+                });
+
                 if self.active_panel == PaneType::LeftPane {
                     self.toasts_left.remove(id);
                 } else {
@@ -6148,6 +10801,7 @@ impl Application for App {
                 self.operation(Operation::Restore { items });
             }
             Message::WindowClose => {
+                let _ = self.update(Message::StorePaneLayout);
                 if let Some(window_id) = self.window_id_opt.take() {
                     return Task::batch([
                         window::close(window_id),
@@ -6169,20 +10823,30 @@ impl Application for App {
                 }
             }
             Message::WindowCloseRequested(id) => {
+                let _ = self.update(Message::StorePaneLayout);
                 self.remove_window(&id);
             }
-            Message::WindowNew => match env::current_exe() {
-                Ok(exe) => match process::Command::new(&exe).spawn() {
-                    Ok(_child) => {}
-                    Err(err) => {
-                        log::error!("failed to execute {:?}: {}", exe, err);
-                    }
-                },
-                Err(err) => {
-                    log::error!("failed to get current executable path: {}", err);
+            Message::Reveal(path) => {
+                return self.reveal(path);
+            }
+            Message::WindowFocus => {
+                if let Some(window_id) = self.window_id_opt {
+                    return window::gain_focus(window_id);
                 }
-            },
+            }
+            Message::DuplicateWindow => {
+                spawn_new_window(self.duplicate_flags().args());
+            }
+            Message::WindowNew => {
+                spawn_new_window([]);
+            }
             Message::ZoomDefault(_entity_opt) => {
+                if let Some(task) = self.gallery_zoom(
+                    tab1::Message::GalleryZoomDefault,
+                    tab2::Message::GalleryZoomDefault,
+                ) {
+                    return task;
+                }
                 if self.show_embedded_terminal
                     && self.pane_model.focus
                         == self.pane_model.pane_by_type[&PaneType::TerminalPane]
@@ -6209,7 +10873,7 @@ impl Application for App {
                         }
                     } else {
                         entity = self.tab_model2.active();
-                        let mut config = self.config.tab_left;
+                        let mut config = self.config.tab_right;
                         if let Some(tab) = self.tab_model2.data_mut::<Tab2>(entity) {
                             match tab.config.view {
                                 tab2::View::List => {
@@ -6225,6 +10889,11 @@ impl Application for App {
                 }
             }
             Message::ZoomIn(_entity_opt) => {
+                if let Some(task) =
+                    self.gallery_zoom(tab1::Message::GalleryZoomIn, tab2::Message::GalleryZoomIn)
+                {
+                    return task;
+                }
                 let zoom_in = |size: &mut NonZeroU16, min: u16, max: u16| {
                     let mut step = min;
                     while step <= max {
@@ -6261,6 +10930,11 @@ impl Application for App {
                 return self.update(Message::TabActivate(entity));
             }
             Message::ZoomOut(_entity_opt) => {
+                if let Some(task) =
+                    self.gallery_zoom(tab1::Message::GalleryZoomOut, tab2::Message::GalleryZoomOut)
+                {
+                    return task;
+                }
                 let zoom_out = |size: &mut NonZeroU16, min: u16, max: u16| {
                     let mut step = max;
                     while step >= min {
@@ -6322,6 +10996,27 @@ impl Application for App {
             }
             Message::DndDropNav(entity, data, action) => {
                 self.nav_dnd_hover = None;
+                if self.nav_model.data::<StagedArchiveItem>(entity).is_some() {
+                    if let Some(data) = data {
+                        self.stage_archive_paths(data.paths);
+                    }
+                    return Task::none();
+                }
+                // A favorite dropped onto another one is moved to its position instead of being
+                // copied into it, so favorites can be reordered by dragging them in the sidebar
+                if let (Some(&FavoriteIndex(to)), Some([path])) = (
+                    self.nav_model.data::<FavoriteIndex>(entity),
+                    data.as_ref().map(|data| data.paths.as_slice()),
+                ) {
+                    if let Some(from) = self
+                        .config
+                        .favorites
+                        .iter()
+                        .position(|favorite| favorite.path_opt().as_ref() == Some(path))
+                    {
+                        return self.update(Message::FavoriteReorder(from, to));
+                    }
+                }
                 if let Some((location, data)) = self.nav_model.data::<Location1>(entity).zip(data) {
                     let kind = match action {
                         DndAction::Move => ClipboardKind::Cut,
@@ -6354,22 +11049,7 @@ impl Application for App {
                     .is_some_and(|(loc, i)| *loc == location && i.elapsed() >= HOVER_DURATION1)
                 {
                     self.nav_dnd_hover = None;
-                    let entity = self.tab_model1.active();
-                    let title_opt = match self.tab_model1.data_mut::<Tab1>(entity) {
-                        Some(tab) => {
-                            tab.change_location(&location, None);
-                            Some(tab.title())
-                        }
-                        None => None,
-                    };
-                    if let Some(title) = title_opt {
-                        self.tab_model1.text_set(entity, title);
-                        return Task::batch([
-                            self.update_title(),
-                            self.update_watcher_left(),
-                            self.update_tab_left(entity, location, None),
-                        ]);
-                    }
+                    return self.change_active_location::<LeftPanel>(location);
                 }
             }
             Message::DndHoverLocTimeoutRight(location) => {
@@ -6379,22 +11059,7 @@ impl Application for App {
                     .is_some_and(|(loc, i)| *loc == location && i.elapsed() >= HOVER_DURATION2)
                 {
                     self.nav_dnd_hover_right = None;
-                    let entity = self.tab_model1.active();
-                    let title_opt = match self.tab_model2.data_mut::<Tab2>(entity) {
-                        Some(tab) => {
-                            tab.change_location(&location, None);
-                            Some(tab.title())
-                        }
-                        None => None,
-                    };
-                    if let Some(title) = title_opt {
-                        self.tab_model2.text_set(entity, title);
-                        return Task::batch([
-                            self.update_title(),
-                            self.update_watcher_right(),
-                            self.update_tab_right(entity, location, None),
-                        ]);
-                    }
+                    return self.change_active_location::<RightPanel>(location);
                 }
             }
             Message::DndEnterTab(entity) => {
@@ -6612,15 +11277,15 @@ impl Application for App {
                 let drag_id = drag_opt.unwrap();
                 if drag_id == self.tab_drag_id_left {
                     let entity = self.tab_model1.active();
-                    _ = self.update(Message::DndDropTabLeft(entity, data, action));
+                    return self.update(Message::DndDropTabLeft(entity, data, action));
                 } else if self.config.show_second_panel && drag_id == self.tab_drag_id_right {
                     let entity = self.tab_model2.active();
-                    _ = self.update(Message::DndDropTabRight(entity, data, action));
+                    return self.update(Message::DndDropTabRight(entity, data, action));
                 } else {
                     if let Some(drop) = data {
                         if drop.paths.len() > 0 {
                             let s = osstr_to_string(drop.paths[0].clone().into_os_string());
-                            let _ = self.update(Message::PasteValueTerminal(s));
+                            return self.update(Message::PasteValueTerminal(s));
                         }
                     }
                 }
@@ -6634,8 +11299,11 @@ impl Application for App {
                             kind: ClipboardKind::Copy,
                             paths: drop.paths,
                         };
-                        let _ =
-                            self.update(Message::DndDropTabLeft(entity, Some(c), DndAction::Copy));
+                        return self.update(Message::DndDropTabLeft(
+                            entity,
+                            Some(c),
+                            DndAction::Copy,
+                        ));
                     }
                     PaneType::RightPane => {
                         let entity = self.tab_model2.active();
@@ -6643,19 +11311,22 @@ impl Application for App {
                             kind: ClipboardKind::Copy,
                             paths: drop.paths,
                         };
-                        let _ =
-                            self.update(Message::DndDropTabRight(entity, Some(c), DndAction::Copy));
+                        return self.update(Message::DndDropTabRight(
+                            entity,
+                            Some(c),
+                            DndAction::Copy,
+                        ));
                     }
                     PaneType::TerminalPane => {
                         if drop.paths.len() > 0 {
                             let s = osstr_to_string(drop.paths[0].clone().into_os_string());
-                            let _ = self.update(Message::PasteValueTerminal(s));
+                            return self.update(Message::PasteValueTerminal(s));
                         }
                     }
                     PaneType::ButtonPane => {
                         if drop.paths.len() > 0 {
                             let s = osstr_to_string(drop.paths[0].clone().into_os_string());
-                            let _ = self.update(Message::PasteValueTerminal(s));
+                            return self.update(Message::PasteValueTerminal(s));
                         }
                     }
                 },
@@ -6667,7 +11338,7 @@ impl Application for App {
                 {
                     // Terminal is active
                     let s = osstr_to_string(path.clone().into_os_string());
-                    let _ = self.update(Message::PasteValueTerminal(s));
+                    return self.update(Message::PasteValueTerminal(s));
                 } else if self.active_panel == PaneType::LeftPane {
                     let entity = self.tab_model1.active();
                     let v = vec![path];
@@ -6675,32 +11346,22 @@ impl Application for App {
                         kind: ClipboardKind::Copy,
                         paths: v,
                     };
-                    let _ = self.update(Message::DndDropTabLeft(entity, Some(c), DndAction::Copy));
+                    return self.update(Message::DndDropTabLeft(entity, Some(c), DndAction::Copy));
                 } else {
-                    let entity = self.tab_model1.active();
+                    let entity = self.tab_model2.active();
                     let v = vec![path];
                     let c = ClipboardPaste {
                         kind: ClipboardKind::Copy,
                         paths: v,
                     };
-                    let _ = self.update(Message::DndDropTabRight(entity, Some(c), DndAction::Copy));
+                    return self.update(Message::DndDropTabRight(entity, Some(c), DndAction::Copy));
                 }
             }
             Message::DndDropTabLeft(entity, data, action) => {
                 self.tab_dnd_hover_left = None;
                 if let Some((tab, data)) = self.tab_model1.data::<Tab1>(entity).zip(data) {
-                    let kind = match action {
-                        DndAction::Move => ClipboardKind::Cut,
-                        _ => ClipboardKind::Copy,
-                    };
                     let ret = match &tab.location {
-                        Location1::Path(p) => self.update(Message::PasteContents(
-                            p.clone(),
-                            ClipboardPaste {
-                                kind,
-                                paths: data.paths,
-                            },
-                        )),
+                        Location1::Path(p) => self.drop_paths(p.clone(), data, action),
                         Location1::Trash if matches!(action, DndAction::Move) => {
                             self.operation(Operation::Delete { paths: data.paths });
                             Task::none()
@@ -6716,18 +11377,8 @@ impl Application for App {
             Message::DndDropTabRight(entity, data, action) => {
                 self.tab_dnd_hover_right = None;
                 if let Some((tab, data)) = self.tab_model2.data::<Tab2>(entity).zip(data) {
-                    let kind = match action {
-                        DndAction::Move => ClipboardKind::Cut,
-                        _ => ClipboardKind::Copy,
-                    };
                     let ret = match &tab.location {
-                        Location2::Path(p) => self.update(Message::PasteContents(
-                            p.clone(),
-                            ClipboardPaste {
-                                kind,
-                                paths: data.paths,
-                            },
-                        )),
+                        Location2::Path(p) => self.drop_paths(p.clone(), data, action),
                         Location2::Trash if matches!(action, DndAction::Move) => {
                             self.operation(Operation::Delete { paths: data.paths });
                             Task::none()
@@ -6762,26 +11413,20 @@ impl Application for App {
             }
 
             Message::NavBarClose(entity) => {
-                if let Some(data) = self.nav_model.data::<MounterData>(entity) {
-                    if let Some(mounter) = MOUNTERS.get(&data.0) {
-                        return mounter.unmount(data.1.clone()).map(|_| message::none());
-                    }
+                if let Some(MounterData(mounter_key, item)) =
+                    self.nav_model.data::<MounterData>(entity)
+                {
+                    let (mounter_key, item) = (*mounter_key, item.clone());
+                    return self.mount_item(mounter_key, item, false);
                 }
             }
 
             // Tracks which nav bar item to show a context menu for.
             Message::NavBarContext(entity) => {
                 // Close location editing if enabled
-                if self.active_panel == PaneType::LeftPane {
-                    let tab_entity = self.tab_model1.active();
-                    if let Some(tab) = self.tab_model1.data_mut::<Tab1>(tab_entity) {
-                        tab.edit_location = None;
-                    }
-                } else {
-                    let tab_entity = self.tab_model2.active();
-                    if let Some(tab) = self.tab_model2.data_mut::<Tab2>(tab_entity) {
-                        tab.edit_location = None;
-                    }
+                match self.active_panel {
+                    PaneType::LeftPane => self.close_edit_location::<LeftPanel>(),
+                    _ => self.close_edit_location::<RightPanel>(),
                 }
                 self.nav_bar_context_id = entity;
             }
@@ -6807,9 +11452,10 @@ impl Application for App {
                         match tab1::item_from_path(&path, IconSizes::default()) {
                             Ok(item) => {
                                 return self.update(Message::DialogPush(DialogPage::OpenWith {
-                                    path: path.to_path_buf(),
+                                    paths: vec![path.to_path_buf()],
                                     mime: item.mime.clone(),
                                     selected: 0,
+                                    always: false,
                                     store_opt: "x-scheme-handler/mime"
                                         .parse::<mime_guess::Mime>()
                                         .ok()
@@ -6850,17 +11496,7 @@ impl Application for App {
                 // Open the selected path in a new commander window.
                 NavMenuAction::OpenInNewWindow(entity) => {
                     if let Some(Location1::Path(path)) = self.nav_model.data::<Location1>(entity) {
-                        match env::current_exe() {
-                            Ok(exe) => match process::Command::new(&exe).arg(path).spawn() {
-                                Ok(_child) => {}
-                                Err(err) => {
-                                    log::error!("failed to execute {:?}: {}", exe, err);
-                                }
-                            },
-                            Err(err) => {
-                                log::error!("failed to get current executable path: {}", err);
-                            }
-                        }
+                        spawn_new_window([path.clone().into_os_string()]);
                     }
                 }
 
@@ -6896,10 +11532,81 @@ impl Application for App {
                     }
                 }
 
+                NavMenuAction::MoveFavoriteUp(entity) | NavMenuAction::MoveFavoriteDown(entity) => {
+                    if let Some(&FavoriteIndex(favorite_i)) =
+                        self.nav_model.data::<FavoriteIndex>(entity)
+                    {
+                        let to = if matches!(action, NavMenuAction::MoveFavoriteUp(_)) {
+                            favorite_i.checked_sub(1)
+                        } else {
+                            Some(favorite_i + 1)
+                        };
+                        if let Some(to) = to {
+                            return self.update(Message::FavoriteReorder(favorite_i, to));
+                        }
+                    }
+                }
+
+                NavMenuAction::HideFromSidebar(entity) => {
+                    if let Some(builtin) = self.nav_model.data::<SidebarBuiltin>(entity) {
+                        return self.update(Message::SidebarShow(*builtin, false));
+                    }
+                }
+
                 NavMenuAction::EmptyTrash => {
-                    self.dialog_pages.push_front(DialogPage::EmptyTrash);
+                    self.dialog_pages
+                        .push_front(DialogPage::EmptyTrash { summary_opt: None });
+                    return self.empty_trash_summary();
+                }
+                NavMenuAction::FinalizeArchive => {
+                    return self.update(Message::FinalizeArchive);
+                }
+                NavMenuAction::DiscardArchive => {
+                    self.staged_archive_opt = None;
+                    self.update_nav_model_active();
                 }
             },
+            Message::RecentFileOpen(path) => {
+                self.dialog_pages.pop_front();
+                self.open_file(&path);
+            }
+            Message::RecentFileReveal(path) => {
+                self.dialog_pages.pop_front();
+                if let Some(parent) = path.parent().map(Path::to_path_buf) {
+                    if self.active_panel == PaneType::LeftPane {
+                        return self.open_tab(Location1::Path(parent), true, Some(vec![path]));
+                    } else {
+                        return self.open_tab_right(
+                            Location2::Path(parent),
+                            true,
+                            Some(vec![path]),
+                        );
+                    }
+                }
+            }
+            Message::RecentFilesClear => {
+                if let Err(err) = clear_recent_files() {
+                    log::warn!("failed to clear recent files: {}", err);
+                }
+                if let Some(DialogPage::RecentFiles { paths }) = self.dialog_pages.front_mut() {
+                    *paths = recent_files();
+                }
+                return self.rescan_recents();
+            }
+            Message::RecentFilesMenu => {
+                self.dialog_pages.push_back(DialogPage::RecentFiles {
+                    paths: recent_files(),
+                });
+            }
+            Message::RemoveFromRecents(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                if !paths.is_empty() {
+                    if let Err(err) = recents::retain(|path| !paths.iter().any(|x| x == path)) {
+                        log::warn!("failed to remove {:?} from recent files: {}", paths, err);
+                    }
+                    return self.rescan_recents();
+                }
+            }
             Message::Recents => {
                 if self.active_panel == PaneType::LeftPane {
                     return self.open_tab(Location1::Recents, false, None);
@@ -7045,11 +11752,18 @@ impl Application for App {
                 self.about(),
                 Message::ToggleContextPage(ContextPage::About),
             ),
-            ContextPage::EditHistory => context_drawer::context_drawer(
-                self.edit_history(),
-                Message::ToggleContextPage(ContextPage::EditHistory),
-            )
-            .title(fl!("edit-history")),
+            ContextPage::EditHistory => {
+                let context_drawer = context_drawer::context_drawer(
+                    self.edit_history(),
+                    Message::ToggleContextPage(ContextPage::EditHistory),
+                )
+                .title(fl!("edit-history"));
+                if self.pending_operations.is_empty() {
+                    context_drawer
+                } else {
+                    context_drawer.header(self.edit_history_header())
+                }
+            }
             ContextPage::NetworkDrive => {
                 let mut text_input =
                     widget::text_input(fl!("enter-server-address"), &self.network_drive_input);
@@ -7187,10 +11901,26 @@ impl Application for App {
                 name,
                 archive_type,
                 password,
+                separate,
+                include_files,
             } => {
                 let mut dialog = widget::dialog().title(fl!("create-archive"));
+                if paths.is_empty() {
+                    dialog = dialog.body(fl!("new-archive-description"));
+                }
 
-                let complete_maybe = if name.is_empty() {
+                let complete_maybe = if *separate {
+                    let archives = separate_archives(paths, to, *archive_type, *include_files);
+                    if archives.is_empty() {
+                        None
+                    } else if archives.iter().any(|(_, archive)| archive.exists()) {
+                        dialog =
+                            dialog.tertiary_action(widget::text::body(fl!("file-already-exists")));
+                        None
+                    } else {
+                        Some(Message::DialogComplete)
+                    }
+                } else if name.is_empty() {
                     None
                 } else if name == "." || name == ".." {
                     dialog = dialog.tertiary_action(widget::text::body(fl!(
@@ -7219,6 +11949,25 @@ impl Application for App {
 
                 let archive_types = ArchiveType::all();
                 let selected = archive_types.iter().position(|&x| x == *archive_type);
+                let name_input: Element<_> = if *separate {
+                    widget::text::body(fl!("archive-named-after-item")).into()
+                } else {
+                    widget::text_input("", name.as_str())
+                        .id(self.dialog_text_input.clone())
+                        .on_input(move |name| {
+                            Message::DialogUpdate(DialogPage::Compress {
+                                paths: paths.clone(),
+                                to: to.clone(),
+                                name: name.clone(),
+                                archive_type: *archive_type,
+                                password: password.clone(),
+                                separate: *separate,
+                                include_files: *include_files,
+                            })
+                        })
+                        .on_submit_maybe(complete_maybe.clone())
+                        .into()
+                };
                 dialog = dialog
                     .primary_action(
                         widget::button::suggested(fl!("create"))
@@ -7231,19 +11980,7 @@ impl Application for App {
                         widget::column::with_children(vec![
                             widget::text::body(fl!("file-name")).into(),
                             widget::row::with_children(vec![
-                                widget::text_input("", name.as_str())
-                                    .id(self.dialog_text_input.clone())
-                                    .on_input(move |name| {
-                                        Message::DialogUpdate(DialogPage::Compress {
-                                            paths: paths.clone(),
-                                            to: to.clone(),
-                                            name: name.clone(),
-                                            archive_type: *archive_type,
-                                            password: password.clone(),
-                                        })
-                                    })
-                                    .on_submit_maybe(complete_maybe.clone())
-                                    .into(),
+                                name_input,
                                 widget::dropdown(archive_types, selected, move |index| {
                                     Message::DialogUpdate(DialogPage::Compress {
                                         paths: paths.clone(),
@@ -7251,6 +11988,8 @@ impl Application for App {
                                         name: name.clone(),
                                         archive_type: archive_types[index],
                                         password: password.clone(),
+                                        separate: *separate,
+                                        include_files: *include_files,
                                     })
                                 })
                                 .into(),
@@ -7262,6 +12001,41 @@ impl Application for App {
                         .spacing(space_xxs),
                     );
 
+                if paths.len() > 1 {
+                    let mut column = widget::column::with_capacity(2).spacing(space_xxs).push(
+                        widget::checkbox(fl!("archive-per-item"), *separate).on_toggle(
+                            move |separate| {
+                                Message::DialogUpdate(DialogPage::Compress {
+                                    paths: paths.clone(),
+                                    to: to.clone(),
+                                    name: name.clone(),
+                                    archive_type: *archive_type,
+                                    password: password.clone(),
+                                    separate,
+                                    include_files: *include_files,
+                                })
+                            },
+                        ),
+                    );
+                    if *separate {
+                        column = column.push(
+                            widget::checkbox(fl!("archive-include-files"), *include_files)
+                                .on_toggle(move |include_files| {
+                                    Message::DialogUpdate(DialogPage::Compress {
+                                        paths: paths.clone(),
+                                        to: to.clone(),
+                                        name: name.clone(),
+                                        archive_type: *archive_type,
+                                        password: password.clone(),
+                                        separate: *separate,
+                                        include_files,
+                                    })
+                                }),
+                        );
+                    }
+                    dialog = dialog.control(column);
+                }
+
                 if *archive_type == ArchiveType::Zip {
                     let password_unwrapped = password.clone().unwrap_or_else(String::default);
                     dialog = dialog.control(widget::column::with_children(vec![
@@ -7275,6 +12049,8 @@ impl Application for App {
                                     name: name.clone(),
                                     archive_type: *archive_type,
                                     password: Some(password_unwrapped),
+                                    separate: *separate,
+                                    include_files: *include_files,
                                 })
                             })
                             .on_submit_maybe(complete_maybe)
@@ -7284,84 +12060,358 @@ impl Application for App {
 
                 dialog
             }
-            DialogPage::EmptyTrash => widget::dialog()
-                .title(fl!("empty-trash"))
-                .body(fl!("empty-trash-warning"))
-                .primary_action(
-                    widget::button::suggested(fl!("empty-trash")).on_press(Message::DialogComplete),
-                )
-                .secondary_action(
-                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
-                ),
-            DialogPage::FailedOperation(id) => {
-                //TODO: try next dialog page (making sure index is used by Dialog messages)?
-                let (operation, _, err) = self.failed_operations.get(id)?;
+            DialogPage::CopyAs {
+                from,
+                parent,
+                name,
+                dir,
+            } => {
+                let mut dialog = widget::dialog().title(fl!("copy-as-title"));
 
-                //TODO: nice description of error
-                widget::dialog()
-                    .title("Failed operation")
-                    .body(format!("{:#?}\n{}", operation, err))
-                    .icon(widget::icon::from_name("dialog-error").size(64))
-                    //TODO: retry action
-                    .primary_action(
-                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
-                    )
-            }
-            DialogPage::ExtractPassword { id, password } => {
-                widget::dialog()
-                    .title(fl!("extract-password-required"))
-                    .icon(widget::icon::from_name("dialog-error").size(64))
-                    .control(widget::text_input("", password).password().on_input(
-                        move |password| {
-                            Message::DialogUpdate(DialogPage::ExtractPassword { id: *id, password })
-                        },
+                let complete_maybe = if name.is_empty() {
+                    None
+                } else if name == "." || name == ".." {
+                    dialog = dialog.tertiary_action(widget::text::body(fl!(
+                        "name-invalid",
+                        filename = name.as_str()
+                    )));
+                    None
+                } else if name.contains('/') {
+                    dialog = dialog.tertiary_action(widget::text::body(fl!("name-no-slashes")));
+                    None
+                } else {
+                    let path = parent.join(name);
+                    if from == &path {
+                        dialog = dialog.tertiary_action(widget::text::body(fl!("copy-as-same")));
+                        None
+                    } else {
+                        if path.exists() {
+                            // Replacing is confirmed by the replace dialog during the copy
+                            dialog =
+                                dialog.tertiary_action(widget::text::body(fl!("copy-as-replace")));
+                        } else if name.starts_with('.') {
+                            dialog = dialog.tertiary_action(widget::text::body(fl!("name-hidden")));
+                        }
+                        Some(Message::DialogComplete)
+                    }
+                };
+
+                dialog
+                    .body(fl!(
+                        "copy-as-description",
+                        from = from.display().to_string(),
+                        to = parent.display().to_string()
                     ))
                     .primary_action(
-                        widget::button::suggested(fl!("extract-here"))
-                            .on_press(Message::DialogComplete),
+                        widget::button::suggested(fl!("copy"))
+                            .on_press_maybe(complete_maybe.clone()),
                     )
                     .secondary_action(
                         widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                     )
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::text::body(if *dir {
+                                fl!("folder-name")
+                            } else {
+                                fl!("file-name")
+                            })
+                            .into(),
+                            widget::text_input("", name.as_str())
+                                .id(self.dialog_text_input.clone())
+                                .on_input(move |name| {
+                                    Message::DialogUpdate(DialogPage::CopyAs {
+                                        from: from.clone(),
+                                        parent: parent.clone(),
+                                        name,
+                                        dir: *dir,
+                                    })
+                                })
+                                .on_submit_maybe(complete_maybe)
+                                .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
             }
-            DialogPage::MountError {
-                mounter_key: _,
-                item: _,
-                error,
-            } => widget::dialog()
-                .title(fl!("mount-error"))
-                .body(error)
-                .icon(widget::icon::from_name("dialog-error").size(64))
-                .primary_action(
-                    widget::button::standard(fl!("try-again")).on_press(Message::DialogComplete),
-                )
-                .secondary_action(
-                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
-                ),
-            DialogPage::NetworkAuth {
-                mounter_key,
-                uri,
-                auth,
-                auth_tx,
+            DialogPage::CopyMoveTo {
+                to,
+                folders_opt,
+                new_folder,
+                error_opt,
+                ..
+            }
+            | DialogPage::RestoreTo {
+                to,
+                folders_opt,
+                new_folder,
+                error_opt,
+                ..
             } => {
-                //TODO: use URI!
-                let mut controls = Vec::with_capacity(4);
-                let mut id_assigned = false;
+                let (title, complete_label) = match dialog_page {
+                    DialogPage::CopyMoveTo { paths, copy, .. } => {
+                        if *copy {
+                            (fl!("copy-to-title", items = paths.len()), fl!("copy-here"))
+                        } else {
+                            (fl!("move-to-title", items = paths.len()), fl!("move-here"))
+                        }
+                    }
+                    DialogPage::RestoreTo { items, .. } => (
+                        fl!("restore-to-title", items = items.len()),
+                        fl!("restore-here"),
+                    ),
+                    _ => unreachable!(),
+                };
+                let mut dialog = widget::dialog().title(title);
 
-                if let Some(username) = &auth.username_opt {
-                    //TODO: what should submit do?
-                    let mut input = widget::text_input(fl!("username"), username)
-                        .on_input(move |value| {
-                            Message::DialogUpdate(DialogPage::NetworkAuth {
-                                mounter_key: *mounter_key,
-                                uri: uri.clone(),
-                                auth: MounterAuth {
-                                    username_opt: Some(value),
-                                    ..auth.clone()
-                                },
-                                auth_tx: auth_tx.clone(),
-                            })
-                        })
+                let folder_button = |icon: &'static str, name: String, path: PathBuf| {
+                    widget::button::custom(
+                        widget::row::with_children(vec![
+                            widget::icon::from_name(icon).size(16).into(),
+                            widget::text::body(name).into(),
+                        ])
+                        .spacing(space_s)
+                        .align_y(Alignment::Center),
+                    )
+                    .width(Length::Fill)
+                    .class(theme::Button::MenuItem)
+                    .on_press(Message::CopyMoveToNavigate(path))
+                };
+                let mut folders = widget::list_column();
+                if let Some(parent) = to.parent() {
+                    folders = folders.add(folder_button(
+                        "go-up-symbolic",
+                        "..".to_string(),
+                        parent.to_path_buf(),
+                    ));
+                }
+                for name in folders_opt.iter().flatten() {
+                    folders = folders.add(folder_button(
+                        "folder-symbolic",
+                        name.clone(),
+                        to.join(name),
+                    ));
+                }
+
+                let create_maybe = if new_folder.is_empty() {
+                    None
+                } else if new_folder == "." || new_folder == ".." {
+                    dialog = dialog.tertiary_action(widget::text::body(fl!(
+                        "name-invalid",
+                        filename = new_folder.as_str()
+                    )));
+                    None
+                } else if new_folder.contains('/') {
+                    dialog = dialog.tertiary_action(widget::text::body(fl!("name-no-slashes")));
+                    None
+                } else if folders_opt.is_none() {
+                    None
+                } else {
+                    Some(Message::CopyMoveToNewFolder)
+                };
+                if let Some(error) = error_opt {
+                    dialog = dialog.tertiary_action(widget::text::body(error.clone()));
+                }
+
+                // Only existing folders can be the destination
+                let complete_maybe = folders_opt.as_ref().map(|_| Message::DialogComplete);
+                dialog
+                    .primary_action(
+                        widget::button::suggested(complete_label)
+                            .on_press_maybe(complete_maybe.clone()),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::text_input("", to.to_string_lossy().to_string())
+                                .id(self.dialog_text_input.clone())
+                                .on_input(|path| Message::CopyMoveToNavigate(PathBuf::from(path)))
+                                .on_submit_maybe(complete_maybe)
+                                .into(),
+                            widget::scrollable(folders)
+                                .height(Length::Fixed(240.0))
+                                .into(),
+                            widget::row::with_children(vec![
+                                widget::text_input(fl!("folder-name"), new_folder.as_str())
+                                    .on_input(move |value| {
+                                        let mut dialog_page = dialog_page.clone();
+                                        if let DialogPage::CopyMoveTo {
+                                            new_folder,
+                                            error_opt,
+                                            ..
+                                        }
+                                        | DialogPage::RestoreTo {
+                                            new_folder,
+                                            error_opt,
+                                            ..
+                                        } = &mut dialog_page
+                                        {
+                                            *new_folder = value;
+                                            *error_opt = None;
+                                        }
+                                        Message::DialogUpdate(dialog_page)
+                                    })
+                                    .on_submit_maybe(create_maybe.clone())
+                                    .into(),
+                                widget::button::standard(fl!("create-folder"))
+                                    .on_press_maybe(create_maybe)
+                                    .into(),
+                            ])
+                            .spacing(space_xxs)
+                            .align_y(Alignment::Center)
+                            .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
+            }
+            DialogPage::RestoreMissing { missing, .. } => {
+                let missing_list = missing
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                widget::dialog()
+                    .title(fl!("restore-missing-title"))
+                    .body(fl!("restore-missing-warning", folders = missing.len()))
+                    .control(widget::text::body(missing_list))
+                    .primary_action(
+                        widget::button::suggested(fl!("recreate-folders"))
+                            .on_press(Message::DialogComplete),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .tertiary_action(
+                        widget::button::text(fl!("restore-to")).on_press(Message::RestoreMissingTo),
+                    )
+            }
+            DialogPage::EmptyTrash { summary_opt } => widget::dialog()
+                .title(fl!("empty-trash"))
+                .body(fl!("empty-trash-warning"))
+                .control(widget::text::body(match summary_opt {
+                    Some((items, size)) => fl!(
+                        "empty-trash-summary",
+                        items = items,
+                        size = tab1::format_size(*size)
+                    ),
+                    None => fl!("calculating-trash-size"),
+                }))
+                .primary_action(
+                    widget::button::suggested(fl!("empty-trash")).on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::InsufficientSpace { required, free, .. } => widget::dialog()
+                .title(fl!("insufficient-space-title"))
+                .body(fl!(
+                    "insufficient-space-warning",
+                    required = tab1::format_size(*required),
+                    free = tab1::format_size(*free)
+                ))
+                .icon(widget::icon::from_name("dialog-warning").size(64))
+                .primary_action(
+                    widget::button::destructive(fl!("continue-anyway"))
+                        .on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::FailedOperation(id) => {
+                //TODO: try next dialog page (making sure index is used by Dialog messages)?
+                let (operation, _, err) = self.failed_operations.get(id)?;
+
+                //TODO: nice description of error
+                widget::dialog()
+                    .title("Failed operation")
+                    .body(format!("{:#?}\n{}", operation, err))
+                    .icon(widget::icon::from_name("dialog-error").size(64))
+                    .primary_action(
+                        widget::button::suggested(fl!("retry")).on_press(Message::DialogComplete),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::ExtractPassword { id, password } => {
+                widget::dialog()
+                    .title(fl!("extract-password-required"))
+                    .icon(widget::icon::from_name("dialog-error").size(64))
+                    .control(widget::text_input("", password).password().on_input(
+                        move |password| {
+                            Message::DialogUpdate(DialogPage::ExtractPassword { id: *id, password })
+                        },
+                    ))
+                    .primary_action(
+                        widget::button::suggested(fl!("extract-here"))
+                            .on_press(Message::DialogComplete),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::MountError {
+                mounter_key: _,
+                item: _,
+                error,
+            } => widget::dialog()
+                .title(fl!("mount-error"))
+                .body(error)
+                .icon(widget::icon::from_name("dialog-error").size(64))
+                .primary_action(
+                    widget::button::standard(fl!("try-again")).on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::UnmountError {
+                mounter_key: _,
+                item,
+                error,
+            } => {
+                let body = match error {
+                    UnmountError::Busy(error) => {
+                        format!("{}\n\n{}", fl!("unmount-busy", name = item.name()), error)
+                    }
+                    UnmountError::Failed(error) => error.clone(),
+                };
+                widget::dialog()
+                    .title(fl!("unmount-error"))
+                    .body(body)
+                    .icon(widget::icon::from_name("dialog-error").size(64))
+                    .primary_action(
+                        widget::button::standard(fl!("try-again"))
+                            .on_press(Message::DialogComplete),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::NetworkAuth {
+                mounter_key,
+                uri,
+                auth,
+                auth_tx,
+            } => {
+                //TODO: use URI!
+                let mut controls = Vec::with_capacity(4);
+                let mut id_assigned = false;
+
+                if let Some(username) = &auth.username_opt {
+                    //TODO: what should submit do?
+                    let mut input = widget::text_input(fl!("username"), username)
+                        .on_input(move |value| {
+                            Message::DialogUpdate(DialogPage::NetworkAuth {
+                                mounter_key: *mounter_key,
+                                uri: uri.clone(),
+                                auth: MounterAuth {
+                                    username_opt: Some(value),
+                                    ..auth.clone()
+                                },
+                                auth_tx: auth_tx.clone(),
+                            })
+                        })
                         .on_submit(Message::DialogComplete);
                     if !id_assigned {
                         input = input.id(self.dialog_text_input.clone());
@@ -7481,9 +12531,16 @@ impl Application for App {
                 .secondary_action(
                     widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                 ),
-            DialogPage::NewItem { parent, name, dir } => {
+            DialogPage::NewItem {
+                parent,
+                name,
+                dir,
+                text_opt,
+            } => {
                 let mut dialog = widget::dialog().title(if *dir {
                     fl!("create-new-folder")
+                } else if text_opt.is_some() {
+                    fl!("paste-as-file")
                 } else {
                     fl!("create-new-file")
                 });
@@ -7541,6 +12598,7 @@ impl Application for App {
                                         parent: parent.clone(),
                                         name,
                                         dir: *dir,
+                                        text_opt: text_opt.clone(),
                                     })
                                 })
                                 .on_submit_maybe(complete_maybe)
@@ -7549,16 +12607,85 @@ impl Application for App {
                         .spacing(space_xxs),
                     )
             }
+            DialogPage::OpenFiles { paths } => widget::dialog()
+                .title(fl!("open-files-title", items = paths.len()))
+                .body(fl!("open-files-warning", items = paths.len()))
+                .primary_action(
+                    widget::button::suggested(fl!("open")).on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::OpenScript { path, .. } => {
+                let name = path
+                    .file_name()
+                    .unwrap_or(path.as_os_str())
+                    .to_string_lossy();
+                widget::dialog()
+                    .title(fl!("open-script-title"))
+                    .body(fl!("open-script-description", name = name.as_ref()))
+                    .primary_action(widget::button::suggested(fl!("run-in-terminal")).on_press(
+                        Message::DialogUpdateComplete(DialogPage::OpenScript {
+                            path: path.clone(),
+                            run: true,
+                        }),
+                    ))
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .tertiary_action(widget::button::text(fl!("open")).on_press(
+                        Message::DialogUpdateComplete(DialogPage::OpenScript {
+                            path: path.clone(),
+                            run: false,
+                        }),
+                    ))
+            }
+            DialogPage::OpenAsRoot { path, error_opt } => {
+                let name = path
+                    .file_name()
+                    .unwrap_or(path.as_os_str())
+                    .to_string_lossy();
+                match error_opt {
+                    Some(error) => widget::dialog()
+                        .title(fl!("open-as-root-failed"))
+                        .body(error)
+                        .icon(widget::icon::from_name("dialog-error").size(64))
+                        .primary_action(
+                            widget::button::standard(fl!("try-again"))
+                                .on_press(Message::DialogComplete),
+                        )
+                        .secondary_action(
+                            widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                        ),
+                    None => widget::dialog()
+                        .title(fl!("open-as-root-title"))
+                        .body(fl!("open-as-root-warning", name = name.as_ref()))
+                        .icon(widget::icon::from_name("dialog-warning").size(64))
+                        .primary_action(
+                            widget::button::destructive(fl!("open-as-root"))
+                                .on_press(Message::DialogComplete),
+                        )
+                        .secondary_action(
+                            widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                        ),
+                }
+            }
             DialogPage::OpenWith {
-                path,
+                paths,
                 mime,
                 selected,
+                always,
                 store_opt,
-                ..
             } => {
-                let name = match path.file_name() {
-                    Some(file_name) => file_name.to_str(),
-                    None => path.as_os_str().to_str(),
+                let title = match paths.as_slice() {
+                    [path] => {
+                        let name = match path.file_name() {
+                            Some(file_name) => file_name.to_str(),
+                            None => path.as_os_str().to_str(),
+                        };
+                        fl!("open-with-title", name = name)
+                    }
+                    _ => fl!("open-with-title-files", count = paths.len()),
                 };
 
                 let mut column = widget::list_column();
@@ -7595,15 +12722,70 @@ impl Application for App {
                     );
                 }
 
+                let apps = self.mime_app_cache.get(mime);
+                let default_name = apps
+                    .iter()
+                    .find(|app| app.is_default)
+                    .map(|app| app.name.as_str());
+                let selected_is_default = apps.get(*selected).is_some_and(|app| app.is_default);
+                let default_row = widget::row::with_children(vec![
+                    widget::text::body(match default_name {
+                        Some(name) => fl!(
+                            "current-default-app",
+                            mime = mime.essence_str(),
+                            name = name
+                        ),
+                        None => fl!("no-default-app", mime = mime.essence_str()),
+                    })
+                    .into(),
+                    widget::horizontal_space().into(),
+                    widget::button::standard(fl!("clear-default-app"))
+                        .on_press_maybe(default_name.map(|_| Message::OpenWithClearDefault))
+                        .into(),
+                    widget::button::standard(fl!("set-default-app"))
+                        .on_press_maybe(
+                            (!apps.is_empty() && !selected_is_default)
+                                .then_some(Message::OpenWithSetDefault),
+                        )
+                        .into(),
+                ])
+                .spacing(space_s)
+                .align_y(Alignment::Center);
+
+                let mut controls = Vec::with_capacity(4);
+                if let Some(app) = apps.get(*selected).filter(|_| paths.len() > 1) {
+                    controls.push(
+                        widget::text::body(fl!(
+                            "open-files-with",
+                            count = paths.len(),
+                            name = app.name.as_str()
+                        ))
+                        .into(),
+                    );
+                }
+                controls.push(column.into());
+                controls.push(default_row.into());
+                controls.push(
+                    widget::checkbox(
+                        fl!("always-open-with", mime = mime.essence_str()),
+                        *always || selected_is_default,
+                    )
+                    .on_toggle_maybe(
+                        (!apps.is_empty() && !selected_is_default)
+                            .then_some(Message::OpenWithAlways),
+                    )
+                    .into(),
+                );
+
                 let mut dialog = widget::dialog()
-                    .title(fl!("open-with-title", name = name))
+                    .title(title)
                     .primary_action(
                         widget::button::suggested(fl!("open")).on_press(Message::DialogComplete),
                     )
                     .secondary_action(
                         widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                     )
-                    .control(column);
+                    .control(widget::column::with_children(controls).spacing(space_s));
 
                 if let Some(app) = store_opt {
                     dialog = dialog.tertiary_action(
@@ -7803,1112 +12985,2967 @@ impl Application for App {
                         )
                 }
             }
-            DialogPage::SetExecutableAndLaunch { path } => {
-                let name = match path.file_name() {
-                    Some(file_name) => file_name.to_str(),
-                    None => path.as_os_str().to_str(),
+            DialogPage::SelectByCriteria(input) => {
+                let mut dialog = widget::dialog().title(fl!("select-by-criteria"));
+
+                let complete_maybe = if input.parse(SystemTime::now()).is_some() {
+                    Some(Message::DialogComplete)
+                } else {
+                    if *input != SelectCriteriaInput::default() {
+                        dialog =
+                            dialog.tertiary_action(widget::text::body(fl!("criteria-invalid")));
+                    }
+                    None
                 };
-                widget::dialog()
-                    .title(fl!("set-executable-and-launch"))
+
+                // Label and input of a field, `get` and `set` access its text
+                let field =
+                    |label: String,
+                     placeholder: &'static str,
+                     get: fn(&SelectCriteriaInput) -> &String,
+                     set: fn(&mut SelectCriteriaInput, String)| {
+                        widget::column::with_children(vec![
+                            widget::text::body(label).into(),
+                            widget::text_input(placeholder, get(input).as_str())
+                                .on_input(move |value| {
+                                    let mut input = input.clone();
+                                    set(&mut input, value);
+                                    Message::DialogUpdate(DialogPage::SelectByCriteria(input))
+                                })
+                                .on_submit_maybe(complete_maybe.clone())
+                                .into(),
+                        ])
+                        .spacing(space_xxs)
+                    };
+
+                dialog
                     .primary_action(
-                        widget::button::text(fl!("set-and-launch"))
-                            .class(theme::Button::Suggested)
-                            .on_press(Message::DialogComplete),
+                        widget::button::suggested(fl!("select"))
+                            .on_press_maybe(complete_maybe.clone()),
                     )
                     .secondary_action(
-                        widget::button::text(fl!("cancel"))
-                            .class(theme::Button::Standard)
-                            .on_press(Message::DialogCancel),
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                     )
-                    .control(widget::text::text(fl!(
-                        "set-executable-and-launch-description",
-                        name = name
-                    )))
-            }
-        };
-
-        Some(dialog.into())
-    }
-
-    fn footer(&self) -> Option<Element<Message>> {
-        if self.progress_operations.is_empty() {
-            return None;
-        }
-
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::column::with_children(vec![
+                                widget::text::body(fl!("pattern")).into(),
+                                widget::text_input("*", input.pattern.as_str())
+                                    .id(self.dialog_text_input.clone())
+                                    .on_input(move |pattern| {
+                                        Message::DialogUpdate(DialogPage::SelectByCriteria(
+                                            SelectCriteriaInput {
+                                                pattern,
+                                                ..input.clone()
+                                            },
+                                        ))
+                                    })
+                                    .on_submit_maybe(complete_maybe.clone())
+                                    .into(),
+                            ])
+                            .spacing(space_xxs)
+                            .into(),
+                            field(
+                                fl!("larger-than"),
+                                "10 MB",
+                                |input| &input.min_size,
+                                |input, value| input.min_size = value,
+                            )
+                            .into(),
+                            field(
+                                fl!("smaller-than"),
+                                "1 GB",
+                                |input| &input.max_size,
+                                |input, value| input.max_size = value,
+                            )
+                            .into(),
+                            field(
+                                fl!("modified-after"),
+                                "7",
+                                |input| &input.modified_after,
+                                |input, value| input.modified_after = value,
+                            )
+                            .into(),
+                            field(
+                                fl!("modified-before"),
+                                "2024-01-31",
+                                |input| &input.modified_before,
+                                |input, value| input.modified_before = value,
+                            )
+                            .into(),
+                            widget::text::caption(fl!("criteria-time-hint")).into(),
+                        ])
+                        .spacing(space_s),
+                    )
+            }
+            DialogPage::SelectPattern {
+                pattern,
+                select,
+                case_sensitive,
+            } => {
+                let mut dialog = widget::dialog().title(if *select {
+                    fl!("select-by-pattern")
+                } else {
+                    fl!("deselect-by-pattern")
+                });
+
+                let complete_maybe = if pattern.is_empty() {
+                    None
+                } else if glob::Pattern::new(pattern).is_err() {
+                    dialog = dialog.tertiary_action(widget::text::body(fl!("pattern-invalid")));
+                    None
+                } else {
+                    Some(Message::DialogComplete)
+                };
+
+                dialog
+                    .primary_action(
+                        widget::button::suggested(if *select {
+                            fl!("select")
+                        } else {
+                            fl!("deselect")
+                        })
+                        .on_press_maybe(complete_maybe.clone()),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::text::body(fl!("pattern")).into(),
+                            widget::text_input("*.jpg", pattern.as_str())
+                                .id(self.dialog_text_input.clone())
+                                .on_input(move |pattern| {
+                                    Message::DialogUpdate(DialogPage::SelectPattern {
+                                        pattern,
+                                        select: *select,
+                                        case_sensitive: *case_sensitive,
+                                    })
+                                })
+                                .on_submit_maybe(complete_maybe)
+                                .into(),
+                            widget::checkbox(fl!("case-sensitive"), *case_sensitive)
+                                .on_toggle(move |case_sensitive| {
+                                    Message::DialogUpdate(DialogPage::SelectPattern {
+                                        pattern: pattern.clone(),
+                                        select: *select,
+                                        case_sensitive,
+                                    })
+                                })
+                                .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
+            }
+            DialogPage::SetExecutableAndLaunch { path } => {
+                let name = match path.file_name() {
+                    Some(file_name) => file_name.to_str(),
+                    None => path.as_os_str().to_str(),
+                };
+                widget::dialog()
+                    .title(fl!("set-executable-and-launch"))
+                    .primary_action(
+                        widget::button::text(fl!("set-and-launch"))
+                            .class(theme::Button::Suggested)
+                            .on_press(Message::DialogComplete),
+                    )
+                    .secondary_action(
+                        widget::button::text(fl!("cancel"))
+                            .class(theme::Button::Standard)
+                            .on_press(Message::DialogCancel),
+                    )
+                    .control(widget::text::text(fl!(
+                        "set-executable-and-launch-description",
+                        name = name
+                    )))
+            }
+            DialogPage::ChangeExtension {
+                paths,
+                extension,
+                renames,
+            } => {
+                let mut dialog =
+                    widget::dialog().title(fl!("change-extension-title", items = paths.len()));
+
+                let complete_maybe = match renames {
+                    Ok(renames) if !renames.is_empty() => Some(Message::DialogComplete),
+                    Ok(_) => None,
+                    Err(reason) => {
+                        dialog = dialog.tertiary_action(widget::text::body(reason.as_str()));
+                        None
+                    }
+                };
+
+                dialog
+                    .primary_action(
+                        widget::button::suggested(fl!("rename"))
+                            .on_press_maybe(complete_maybe.clone()),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::text::body(fl!("extension")).into(),
+                            widget::text_input("", extension.as_str())
+                                .id(self.dialog_text_input.clone())
+                                .on_input(Message::ChangeExtensionInput)
+                                .on_submit_maybe(complete_maybe)
+                                .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
+            }
+            DialogPage::SetModified {
+                paths,
+                now,
+                time,
+                recursive,
+            } => {
+                let mut dialog =
+                    widget::dialog().title(fl!("set-modified-title", items = paths.len()));
+
+                let complete_maybe = if *now || parse_local_time(time).is_some() {
+                    Some(Message::DialogComplete)
+                } else {
+                    dialog = dialog.tertiary_action(widget::text::body(fl!("time-invalid")));
+                    None
+                };
+
+                let mut time_input = widget::text_input(TIME_FORMAT, time.as_str())
+                    .id(self.dialog_text_input.clone());
+                if !*now {
+                    time_input = time_input
+                        .on_input(move |time| {
+                            Message::DialogUpdate(DialogPage::SetModified {
+                                paths: paths.clone(),
+                                now: *now,
+                                time,
+                                recursive: *recursive,
+                            })
+                        })
+                        .on_submit_maybe(complete_maybe.clone());
+                }
+
+                dialog
+                    .primary_action(
+                        widget::button::suggested(fl!("apply")).on_press_maybe(complete_maybe),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::checkbox(fl!("current-time"), *now)
+                                .on_toggle(move |now| {
+                                    Message::DialogUpdate(DialogPage::SetModified {
+                                        paths: paths.clone(),
+                                        now,
+                                        time: time.clone(),
+                                        recursive: *recursive,
+                                    })
+                                })
+                                .into(),
+                            time_input.into(),
+                            widget::checkbox(fl!("recursive"), *recursive)
+                                .on_toggle(move |recursive| {
+                                    Message::DialogUpdate(DialogPage::SetModified {
+                                        paths: paths.clone(),
+                                        now: *now,
+                                        time: time.clone(),
+                                        recursive,
+                                    })
+                                })
+                                .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
+            }
+            DialogPage::MoveToTrash { paths } => widget::dialog()
+                .title(fl!("move-to-trash-title"))
+                .body(fl!("move-to-trash-warning", items = paths.len()))
+                .primary_action(
+                    widget::button::suggested(fl!("move-to-trash"))
+                        .on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::PermanentlyDelete { paths } => widget::dialog()
+                .title(fl!("permanently-delete-title"))
+                .body(fl!("permanently-delete-warning", items = paths.len()))
+                .icon(widget::icon::from_name("dialog-warning").size(64))
+                .primary_action(
+                    widget::button::destructive(fl!("delete-permanently"))
+                        .on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+            DialogPage::QuickPreview { pane, entity } => {
+                let entity = *entity;
+                // Follows the selection, so arrow keys preview the next and previous items
+                let preview_opt = if *pane == PaneType::LeftPane {
+                    self.tab_model1.data::<Tab1>(entity).and_then(|tab| {
+                        let item = tab
+                            .items_opt()?
+                            .iter()
+                            .find(|item| item.selected)
+                            .or(tab.parent_item_opt.as_ref())?;
+                        Some(
+                            item.preview_view(Some(&self.mime_app_cache), tab.config.icon_sizes)
+                                .map(move |x| Message::TabMessage(Some(entity), x)),
+                        )
+                    })
+                } else {
+                    self.tab_model2.data::<Tab2>(entity).and_then(|tab| {
+                        let item = tab
+                            .items_opt()?
+                            .iter()
+                            .find(|item| item.selected)
+                            .or(tab.parent_item_opt.as_ref())?;
+                        Some(
+                            item.preview_view(Some(&self.mime_app_cache), tab.config.icon_sizes)
+                                .map(move |x| Message::TabMessageRight(Some(entity), x)),
+                        )
+                    })
+                };
+
+                let mut dialog = widget::dialog().secondary_action(
+                    widget::button::standard(fl!("close")).on_press(Message::DialogCancel),
+                );
+                if let Some(preview) = preview_opt {
+                    dialog = dialog
+                        .control(widget::container(widget::scrollable(preview)).max_height(600.0));
+                }
+                dialog
+            }
+            DialogPage::RecentFiles { paths } => {
+                let mut column = widget::list_column();
+                for path in paths {
+                    let name = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let parent = path
+                        .parent()
+                        .map(|parent| parent.display().to_string())
+                        .unwrap_or_default();
+                    column = column.add(
+                        widget::row::with_children(vec![
+                            widget::icon(mime_icon::mime_icon(mime_icon::mime_for_path(path), 32))
+                                .size(32)
+                                .into(),
+                            widget::column::with_children(vec![
+                                widget::text::body(name).into(),
+                                widget::text::caption(parent).into(),
+                            ])
+                            .into(),
+                            widget::horizontal_space().into(),
+                            widget::button::standard(fl!("reveal-in-panel"))
+                                .on_press(Message::RecentFileReveal(path.clone()))
+                                .into(),
+                            widget::button::standard(fl!("open"))
+                                .on_press(Message::RecentFileOpen(path.clone()))
+                                .into(),
+                        ])
+                        .spacing(space_s)
+                        .align_y(Alignment::Center),
+                    );
+                }
+
+                let clear_button = widget::button::text(fl!("clear-recent"))
+                    .on_press_maybe((!paths.is_empty()).then_some(Message::RecentFilesClear));
+                let mut dialog = widget::dialog()
+                    .title(fl!("recent-files"))
+                    .secondary_action(
+                        widget::button::standard(fl!("close")).on_press(Message::DialogCancel),
+                    )
+                    .tertiary_action(clear_button);
+                if paths.is_empty() {
+                    dialog = dialog.body(fl!("no-recent-files"));
+                } else {
+                    dialog = dialog
+                        .control(widget::container(widget::scrollable(column)).max_height(400.0));
+                }
+                dialog
+            }
+            DialogPage::CommandPalette { query } => {
+                let actions = palette_actions();
+                let matches = palette_matches(&actions, &self.palette_recent, query);
+                let mut column = widget::list_column();
+                for (action, label) in matches.iter() {
+                    let shortcut = action_shortcut(&self.key_binds, action).unwrap_or_default();
+                    column = column.add(
+                        widget::button::custom(
+                            widget::row::with_children(vec![
+                                widget::text::body(label.as_str()).into(),
+                                widget::horizontal_space().into(),
+                                widget::text::caption(shortcut).into(),
+                            ])
+                            .spacing(space_s)
+                            .align_y(Alignment::Center),
+                        )
+                        .on_press(Message::CommandPaletteRun(*action))
+                        .class(theme::Button::MenuItem)
+                        .width(Length::Fill),
+                    );
+                }
+
+                let run_maybe = matches
+                    .first()
+                    .map(|(action, _)| Message::CommandPaletteRun(*action));
+                let mut dialog = widget::dialog()
+                    .title(fl!("command-palette"))
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(
+                        widget::text_input(fl!("type-a-command"), query.as_str())
+                            .id(self.dialog_text_input.clone())
+                            .on_input(|query| {
+                                Message::DialogUpdate(DialogPage::CommandPalette { query })
+                            })
+                            .on_submit_maybe(run_maybe),
+                    );
+                if matches.is_empty() {
+                    dialog = dialog.body(fl!("no-matching-commands"));
+                } else {
+                    dialog = dialog
+                        .control(widget::container(widget::scrollable(column)).max_height(400.0));
+                }
+                dialog
+            }
+            DialogPage::SettingsFile { export, path } => {
+                let complete_maybe = if path.is_empty() || (!*export && !Path::new(path).is_file())
+                {
+                    None
+                } else {
+                    Some(Message::DialogComplete)
+                };
+                let (title, action) = if *export {
+                    (fl!("export-settings"), fl!("export"))
+                } else {
+                    (fl!("import-settings"), fl!("import"))
+                };
+                let export = *export;
+                widget::dialog()
+                    .title(title)
+                    .primary_action(
+                        widget::button::suggested(action).on_press_maybe(complete_maybe.clone()),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::text::body(fl!("settings-file")).into(),
+                            widget::text_input("", path.as_str())
+                                .id(self.dialog_text_input.clone())
+                                .on_input(move |path| {
+                                    Message::DialogUpdate(DialogPage::SettingsFile { export, path })
+                                })
+                                .on_submit_maybe(complete_maybe)
+                                .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
+            }
+            DialogPage::Diff {
+                left,
+                right,
+                rows_res,
+            } => {
+                let name = |path: &PathBuf| {
+                    path.file_name().map_or_else(
+                        || path.display().to_string(),
+                        |name| name.to_string_lossy().into_owned(),
+                    )
+                };
+                let dialog = widget::dialog()
+                    .title(fl!(
+                        "compare-files-title",
+                        left = name(left),
+                        right = name(right)
+                    ))
+                    .primary_action(
+                        widget::button::standard(fl!("close")).on_press(Message::DialogComplete),
+                    );
+                match rows_res {
+                    Ok(rows) if rows.iter().all(|row| row.kind == DiffKind::Equal) => {
+                        dialog.body(fl!("compare-files-identical"))
+                    }
+                    Ok(rows) => {
+                        let cell = |line_opt: &Option<(usize, String)>,
+                                    kind: DiffKind|
+                         -> Element<Message> {
+                            let (number, text) = match line_opt {
+                                Some((number, text)) => (number.to_string(), text.clone()),
+                                None => (String::new(), String::new()),
+                            };
+                            widget::container(
+                                widget::row::with_children(vec![
+                                    widget::text::caption(number)
+                                        .font(cosmic::font::mono())
+                                        .width(Length::Fixed(40.0))
+                                        .into(),
+                                    widget::text::caption(text)
+                                        .font(cosmic::font::mono())
+                                        .width(Length::Fill)
+                                        .into(),
+                                ])
+                                .spacing(space_xxs),
+                            )
+                            .width(Length::Fill)
+                            .style(move |theme| {
+                                let cosmic = theme.cosmic();
+                                let mut color = match kind {
+                                    DiffKind::Equal => return widget::container::Style::default(),
+                                    DiffKind::Removed => cosmic.destructive_color(),
+                                    DiffKind::Added => cosmic.success_color(),
+                                    DiffKind::Changed => cosmic.warning_color(),
+                                };
+                                color.alpha = 0.25;
+                                widget::container::Style {
+                                    background: Some(cosmic::iced::Color::from(color).into()),
+                                    ..Default::default()
+                                }
+                            })
+                            .into()
+                        };
+                        let mut column = widget::column::with_capacity(rows.len());
+                        for row in rows.iter() {
+                            // Only the side that has the line is colored
+                            let left_kind = match row.kind {
+                                DiffKind::Added => DiffKind::Equal,
+                                kind => kind,
+                            };
+                            let right_kind = match row.kind {
+                                DiffKind::Removed => DiffKind::Equal,
+                                kind => kind,
+                            };
+                            column = column.push(
+                                widget::row::with_children(vec![
+                                    cell(&row.left, left_kind),
+                                    cell(&row.right, right_kind),
+                                ])
+                                .spacing(space_xxs),
+                            );
+                        }
+                        dialog.control(widget::scrollable(column).height(Length::Fixed(400.0)))
+                    }
+                    Err(err) => dialog.body(err.as_str()),
+                }
+            }
+            DialogPage::SettingsReport { title, lines } => widget::dialog()
+                .title(title.as_str())
+                .body(lines.join("\n"))
+                .primary_action(
+                    widget::button::standard(fl!("close")).on_press(Message::DialogComplete),
+                ),
+            DialogPage::TrashUnavailable {
+                paths, no_trash, ..
+            } => {
+                let choose = |choice| {
+                    Message::DialogUpdateComplete(DialogPage::TrashUnavailable {
+                        paths: paths.clone(),
+                        no_trash: no_trash.clone(),
+                        choice,
+                    })
+                };
+                widget::dialog()
+                    .title(fl!("trash-unavailable-title"))
+                    .body(fl!("trash-unavailable-warning", items = no_trash.len()))
+                    .icon(widget::icon::from_name("dialog-warning").size(64))
+                    .primary_action(
+                        widget::button::suggested(fl!("create-trash-folder"))
+                            .on_press(choose(TrashUnavailable::CreateTrash)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .tertiary_action(
+                        widget::button::text(fl!("delete-permanently"))
+                            .on_press(choose(TrashUnavailable::Delete)),
+                    )
+            }
+            DialogPage::UndoPermanentDelete { history } => widget::dialog()
+                .title(fl!("undo-permanent-delete"))
+                .body(fl!(
+                    "undo-permanent-delete-warning",
+                    items = history.destructive_paths().len()
+                ))
+                .icon(widget::icon::from_name("dialog-warning").size(64))
+                .primary_action(
+                    widget::button::destructive(fl!("undo")).on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
+        };
+
+        Some(dialog.into())
+    }
+
+    fn footer(&self) -> Option<Element<Message>> {
+        if self.progress_operations.is_empty() {
+            return None;
+        }
+
         let cosmic_theme::Spacing {
             space_xs, space_s, ..
         } = theme::active().cosmic().spacing;
 
-        let mut title = String::new();
-        let mut total_progress = 0.0;
-        let mut count = 0;
-        let mut all_paused = true;
-        for (_id, (op, controller)) in self.pending_operations.iter() {
-            if !controller.is_paused() {
-                all_paused = false;
+        let mut title = String::new();
+        let mut total_progress = 0.0;
+        let mut count = 0;
+        let mut all_paused = true;
+        for (_id, (op, controller)) in self.pending_operations.iter() {
+            if !controller.is_paused() {
+                all_paused = false;
+            }
+            if op.show_progress_notification() {
+                let progress = controller.progress();
+                if title.is_empty() {
+                    title = op.pending_text(progress, controller.state());
+                }
+                total_progress += progress;
+                count += 1;
+            }
+        }
+        let running = count;
+        // Adjust the progress bar so it does not jump around when operations finish
+        for id in self.progress_operations.iter() {
+            if self.complete_operations.contains_key(id) {
+                total_progress += 1.0;
+                count += 1;
+            }
+        }
+        let finished = count - running;
+        total_progress /= count as f32;
+        if running > 1 {
+            if finished > 0 {
+                title = fl!(
+                    "operations-running-finished",
+                    running = running,
+                    finished = finished,
+                    percent = (total_progress as i32)
+                );
+            } else {
+                title = fl!(
+                    "operations-running",
+                    running = running,
+                    percent = (total_progress as i32)
+                );
+            }
+        }
+
+        //TODO: get height from theme?
+        let progress_bar_height = Length::Fixed(4.0);
+        let progress_bar =
+            widget::progress_bar(0.0..=1.0, total_progress).height(progress_bar_height);
+
+        let container = widget::layer_container(widget::column::with_children(vec![
+            widget::row::with_children(vec![
+                progress_bar.into(),
+                if all_paused {
+                    widget::tooltip(
+                        widget::button::icon(widget::icon::from_name(
+                            "media-playback-start-symbolic",
+                        ))
+                        .on_press(Message::PendingPauseAll(false))
+                        .padding(8),
+                        widget::text::body(fl!("resume")),
+                        widget::tooltip::Position::Top,
+                    )
+                    .into()
+                } else {
+                    widget::tooltip(
+                        widget::button::icon(widget::icon::from_name(
+                            "media-playback-pause-symbolic",
+                        ))
+                        .on_press(Message::PendingPauseAll(true))
+                        .padding(8),
+                        widget::text::body(fl!("pause")),
+                        widget::tooltip::Position::Top,
+                    )
+                    .into()
+                },
+                widget::tooltip(
+                    widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                        .on_press(Message::PendingCancelAll)
+                        .padding(8),
+                    widget::text::body(fl!("cancel")),
+                    widget::tooltip::Position::Top,
+                )
+                .into(),
+            ])
+            .align_y(Alignment::Center)
+            .into(),
+            widget::text::body(title).into(),
+            widget::Space::with_height(space_s).into(),
+            widget::row::with_children(vec![
+                widget::button::link(fl!("details"))
+                    .on_press(Message::ToggleContextPage(ContextPage::EditHistory))
+                    .padding(0)
+                    .trailing_icon(true)
+                    .into(),
+                widget::horizontal_space().into(),
+                widget::button::standard(fl!("dismiss"))
+                    .on_press(Message::PendingDismiss)
+                    .into(),
+            ])
+            .align_y(Alignment::Center)
+            .into(),
+        ]))
+        .padding([8, space_xs])
+        .layer(cosmic_theme::Layer::Primary);
+
+        Some(container.into())
+    }
+
+    fn header_start(&self) -> Vec<Element<Self::Message>> {
+        vec![menu::menu_bar(
+            self.tab_model1.active_data::<Tab1>(),
+            &self.config,
+            self.mirror_navigation,
+            self.respect_gitignore(),
+            &self.key_binds,
+        )]
+    }
+
+    fn header_end(&self) -> Vec<Element<Self::Message>> {
+        let mut elements = Vec::with_capacity(2);
+
+        if self.config.read_only {
+            elements.push(
+                widget::tooltip(
+                    widget::button::icon(widget::icon::from_name("changes-prevent-symbolic"))
+                        .on_press(Message::ToggleReadOnly)
+                        .padding(8),
+                    widget::text::body(fl!("read-only-indicator")),
+                    widget::tooltip::Position::Bottom,
+                )
+                .into(),
+            );
+        }
+
+        if let Some(term) = self.search_get() {
+            if self.core.is_condensed() {
+                elements.push(
+                    //TODO: selected state is not appearing different
+                    widget::button::icon(widget::icon::from_name("system-search-symbolic"))
+                        .on_press(Message::SearchClear)
+                        .padding(8)
+                        .selected(true)
+                        .into(),
+                );
+            } else {
+                elements.push(
+                    widget::text_input::search_input("", term)
+                        .width(Length::Fixed(240.0))
+                        .id(self.search_id.clone())
+                        .on_clear(Message::SearchClear)
+                        .on_input(Message::SearchInput)
+                        .into(),
+                );
+            }
+        } else {
+            elements.push(
+                widget::button::icon(widget::icon::from_name("system-search-symbolic"))
+                    .on_press(Message::SearchActivate)
+                    .padding(8)
+                    .into(),
+            );
+        }
+
+        elements
+    }
+
+    /// Creates a view after each update.
+    fn view(&self) -> Element<Self::Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+
+        let mut pane_grid = PaneGrid::new(
+            &self.pane_model.panestates,
+            |pane, tab_model, _is_maximized| {
+                pane_grid::Content::new(cosmic::widget::responsive(move |size| {
+                    self.view_pane_content(pane, tab_model, size)
+                }))
+            },
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .spacing(space_xxs)
+        .on_click(Message::PaneClicked)
+        .drag_id(self.panegrid_drag_id)
+        .on_enter(|x, y, v| Message::DndEnterDndDestination(x, y, v))
+        .on_leave(|| Message::DndExitDndDestination)
+        .on_data_received(|name, data| Message::DndDropDndDataReceived(name, data))
+        .on_action_selected(|action| Message::DndActionSelectedDestination(action))
+        .on_drop(|x, y| Message::DndDropDndDestination(x, y));
+        //.on_dnd_enter(|v| Message::DndEnterPanegrid(v))
+        //.on_dnd_leave(|| Message::DndExitPanegrid)
+        //.on_dnd_drop(|drag_id, data, action| Message::DndDropPanegrid(drag_id, data, action))
+        //.on_resize(space_s, Message::PaneResized);
+        for p in self.pane_model.panes.iter() {
+            pane_grid.panes.push(p.to_owned());
+            pane_grid
+                .drag_id_by_pane
+                .insert(p.to_owned(), self.pane_model.drag_id_by_pane[p]);
+        }
+        pane_grid.panes.extend(self.pane_model.panes.clone());
+        pane_grid.drag_id_by_pane.extend(self.pane_model.drag_id_by_pane.clone());
+        pane_grid.dnd_pane = self.pane_model.dnd_pane.clone();
+        pane_grid.dnd_action = self.pane_model.dnd_action.clone();
+        pane_grid.dnd_pane_id = self.pane_model.dnd_pane_id.clone();
+        pane_grid.dnd_pos_x = self.pane_model.dnd_pos_x;
+        pane_grid.dnd_pos_y = self.pane_model.dnd_pos_y;
+        /*let commander_pane_grid =
+            crate::commanderpanegrid::CommanderDndDestination::new(pane_grid, Vec::new())
+                .drag_id(self.panegrid_drag_id)
+                .on_enter(|x, y, v| Message::DndEnterDndDestination(x, y, v))
+                .on_leave(|| Message::DndExitDndDestination)
+                .on_data_received(|name, data| Message::DndDropDndDataReceived(name, data))
+                .on_action_selected(|action| Message::DndActionSelectedDestination(action))
+                .on_drop(|x, y| Message::DndDropDndDestination(x, y));
+            */
+        widget::container(Element::new(pane_grid))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(space_xxs)
+            .into()
+    }
+
+    fn view_window(&self, id: WindowId) -> Element<Self::Message> {
+        let content = match self.windows.get(&id) {
+            Some(WindowKind::Desktop(entity)) => {
+                let mut tab_column = widget::column::with_capacity(3);
+                let entity = entity.to_owned();
+                if self.active_panel == PaneType::LeftPane {
+                    let tab_view = match self.tab_model1.data::<Tab1>(entity) {
+                        Some(tab) => tab
+                            .view(&self.key_binds, &self.custom_actions)
+                            .map(move |message| Message::TabMessage(Some(entity), message)),
+                        None => widget::vertical_space().into(),
+                    };
+                    let mut popover = widget::popover(tab_view);
+                    if let Some(dialog) = self.dialog() {
+                        popover = popover.popup(dialog);
+                    }
+                    tab_column = tab_column.push(popover);
+                } else {
+                    let tab_view = match self.tab_model2.data::<Tab2>(entity) {
+                        Some(tab) => tab
+                            .view(&self.key_binds, &self.custom_actions)
+                            .map(move |message| Message::TabMessageRight(Some(entity), message)),
+                        None => widget::vertical_space().into(),
+                    };
+                    let mut popover = widget::popover(tab_view);
+                    if let Some(dialog) = self.dialog() {
+                        popover = popover.popup(dialog);
+                    }
+                    tab_column = tab_column.push(popover);
+                }
+
+                // The toaster is added on top of an empty element to ensure that it does not override context menus
+                tab_column =
+                    tab_column.push(widget::toaster(&self.toasts, widget::horizontal_space()));
+                return if let Some(margin) = self.margin.get(&id) {
+                    if margin.0 >= 0. || margin.2 >= 0. {
+                        tab_column = widget::column::with_children(vec![
+                            vertical_space().height(margin.0).into(),
+                            tab_column.into(),
+                            vertical_space().height(margin.2).into(),
+                        ])
+                    }
+                    if margin.1 >= 0. || margin.3 >= 0. {
+                        Element::from(widget::row::with_children(vec![
+                            horizontal_space().width(margin.1).into(),
+                            tab_column.into(),
+                            horizontal_space().width(margin.3).into(),
+                        ]))
+                    } else {
+                        tab_column.into()
+                    }
+                } else {
+                    tab_column.into()
+                };
+            }
+            Some(WindowKind::DesktopViewOptions) => self.desktop_view_options(),
+            Some(WindowKind::Preview1(entity_opt, kind)) => {
+                let ret = self
+                    .preview_left(entity_opt, kind, false)
+                    .map(|x| Message::TabMessage(*entity_opt, x));
+                return ret.into();
+            }
+            Some(WindowKind::Preview2(entity_opt, kind)) => {
+                let ret = self
+                    .preview_right(entity_opt, kind, false)
+                    .map(|x| Message::TabMessageRight(*entity_opt, x));
+                return ret.into();
+            }
+            None => {
+                //TODO: distinct views per monitor in desktop mode
+                return self.view_main().map(|message| match message {
+                    app::Message::App(app) => app,
+                    app::Message::Cosmic(cosmic) => Message::Cosmic(cosmic),
+                    app::Message::None => Message::None,
+                });
+            }
+        };
+
+        widget::container(widget::scrollable(content))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .class(theme::Container::WindowBackground)
+            .into()
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        struct ThemeSubscription;
+        struct TerminalEventSubscription;
+        struct WatcherSubscription;
+        struct WatcherSubscriptionRight;
+        struct TrashWatcherSubscription;
+
+        let mut subscriptions = vec![
+            event::listen_with(|event, status, _window_id| match event {
+                Event::Keyboard(KeyEvent::KeyPressed { key, modifiers, .. }) => match status {
+                    event::Status::Ignored => Some(Message::Key(modifiers, key)),
+                    event::Status::Captured => None,
+                },
+                Event::Keyboard(KeyEvent::ModifiersChanged(modifiers)) => {
+                    Some(Message::Modifiers(modifiers))
+                }
+                Event::Window(WindowEvent::Unfocused) => Some(Message::WindowUnfocus),
+                Event::Window(WindowEvent::CloseRequested) => Some(Message::WindowClose),
+                Event::Window(WindowEvent::Opened { position: _, size }) => {
+                    Some(Message::Size(size))
+                }
+                Event::Window(WindowEvent::Resized(s)) => Some(Message::Size(s)),
+                Event::Window(WindowEvent::Moved(p)) => Some(Message::Move(p)),
+                Event::Window(WindowEvent::FileHovered(f)) => Some(Message::DndHoveredWindow(f)),
+                Event::Window(WindowEvent::FilesHoveredLeft) => Some(Message::DndHoveredLeftWindow),
+                Event::Window(WindowEvent::FileDropped(f)) => Some(Message::DndDropWindow(f)),
+                #[cfg(feature = "wayland")]
+                Event::PlatformSpecific(event::PlatformSpecific::Wayland(wayland_event)) => {
+                    match wayland_event {
+                        WaylandEvent::Output(output_event, output) => {
+                            Some(Message::OutputEvent(output_event, output))
+                        }
+                        #[cfg(feature = "desktop")]
+                        WaylandEvent::OverlapNotify(event) => {
+                            Some(Message::Overlap(event, window_id))
+                        }
+                        _ => None,
+                    }
+                }
+                Event::Mouse(cosmic::iced_core::mouse::Event::ButtonReleased(
+                    cosmic::iced_core::mouse::Button::Left,
+                )) => Some(Message::CopyPrimary(None)),
+                _ => None,
+            }),
+            Config::subscription().map(|update| {
+                if !update.errors.is_empty() {
+                    log::info!(
+                        "errors loading config {:?}: {:?}",
+                        update.keys,
+                        update.errors
+                    );
+                }
+                Message::Config(update.config)
+            }),
+            cosmic_config::config_subscription::<_, cosmic_theme::ThemeMode>(
+                TypeId::of::<ThemeSubscription>(),
+                cosmic_theme::THEME_MODE_ID.into(),
+                cosmic_theme::ThemeMode::version(),
+            )
+            .map(|update| {
+                if !update.errors.is_empty() {
+                    log::info!(
+                        "errors loading theme mode {:?}: {:?}",
+                        update.keys,
+                        update.errors
+                    );
+                }
+                Message::SystemThemeModeChange(update.config)
+            }),
+            Subscription::run_with_id(
+                TypeId::of::<TerminalEventSubscription>(),
+                stream::channel(100, |mut output| async move {
+                    let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+                    output.send(Message::TermEventTx(event_tx)).await.unwrap();
+
+                    while let Some((pane, entity, event)) = event_rx.recv().await {
+                        output
+                            .send(Message::TermEvent(pane, entity, event))
+                            .await
+                            .unwrap();
+                    }
+
+                    panic!("terminal event channel closed");
+                }),
+            ),
+            Subscription::run_with_id(
+                TypeId::of::<WatcherSubscription>(),
+                stream::channel(100, |mut output| async move {
+                    let watcher_res = {
+                        let mut output = output.clone();
+                        new_debouncer(
+                            time::Duration::from_millis(250),
+                            Some(time::Duration::from_millis(250)),
+                            move |events_res: notify_debouncer_full::DebounceEventResult| {
+                                match events_res {
+                                    Ok(mut events) => {
+                                        log::debug!("{:?}", events);
+
+                                        events.retain(|event| {
+                                            match &event.kind {
+                                                notify::EventKind::Access(_) => {
+                                                    // Data not mutated
+                                                    false
+                                                }
+                                                notify::EventKind::Modify(
+                                                    notify::event::ModifyKind::Metadata(e),
+                                                ) if (*e != notify::event::MetadataKind::Any
+                                                    && *e
+                                                        != notify::event::MetadataKind::WriteTime) =>
+                                                {
+                                                    // Data not mutated nor modify time changed
+                                                    false
+                                                }
+                                                _ => true
+                                            }
+                                        });
+
+                                        if !events.is_empty() {
+                                            match futures::executor::block_on(async {
+                                                output.send(Message::NotifyEvents(events)).await
+                                            }) {
+                                                Ok(()) => {}
+                                                Err(err) => {
+                                                    log::warn!(
+                                                        "failed to send notify events: {:?}",
+                                                        err
+                                                    );
+                                                }
+                                            }
+                                        }
+                                    }
+                                    Err(err) => {
+                                        log::warn!("failed to watch files: {:?}", err);
+                                    }
+                                }
+                            },
+                        )
+                    };
+
+                    match watcher_res {
+                        Ok(watcher) => {
+                            match output
+                                .send(Message::NotifyWatcherLeft(WatcherWrapper {
+                                    watcher_opt: Some(watcher),
+                                }))
+                                .await
+                            {
+                                Ok(()) => {}
+                                Err(err) => {
+                                    log::warn!("failed to send notify watcher: {:?}", err);
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            log::warn!("failed to create file watcher: {:?}", err);
+                        }
+                    }
+
+                    std::future::pending().await
+                }),
+            ),
+            Subscription::run_with_id(
+                TypeId::of::<WatcherSubscriptionRight>(),
+                stream::channel(100, |mut output| async move {
+                    let watcher_res = {
+                        let mut output = output.clone();
+                        new_debouncer(
+                            time::Duration::from_millis(250),
+                            Some(time::Duration::from_millis(250)),
+                            move |events_res: notify_debouncer_full::DebounceEventResult| {
+                                match events_res {
+                                    Ok(mut events) => {
+                                        log::debug!("{:?}", events);
+
+                                        events.retain(|event| {
+                                            match &event.kind {
+                                                notify::EventKind::Access(_) => {
+                                                    // Data not mutated
+                                                    false
+                                                }
+                                                notify::EventKind::Modify(
+                                                    notify::event::ModifyKind::Metadata(e),
+                                                ) if (*e != notify::event::MetadataKind::Any
+                                                    && *e
+                                                        != notify::event::MetadataKind::WriteTime) =>
+                                                {
+                                                    // Data not mutated nor modify time changed
+                                                    false
+                                                }
+                                                _ => true
+                                            }
+                                        });
+
+                                        if !events.is_empty() {
+                                            match futures::executor::block_on(async {
+                                                output.send(Message::NotifyEvents(events)).await
+                                            }) {
+                                                Ok(()) => {}
+                                                Err(err) => {
+                                                    log::warn!(
+                                                        "failed to send notify events: {:?}",
+                                                        err
+                                                    );
+                                                }
+                                            }
+                                        }
+                                    }
+                                    Err(err) => {
+                                        log::warn!("failed to watch files: {:?}", err);
+                                    }
+                                }
+                            },
+                        )
+                    };
+
+                    match watcher_res {
+                        Ok(watcher) => {
+                            match output
+                                .send(Message::NotifyWatcherRight(WatcherWrapper {
+                                    watcher_opt: Some(watcher),
+                                }))
+                                .await
+                            {
+                                Ok(()) => {}
+                                Err(err) => {
+                                    log::warn!("failed to send notify watcher: {:?}", err);
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            log::warn!("failed to create file watcher: {:?}", err);
+                        }
+                    }
+
+                    std::future::pending().await
+                }),
+            ),
+            Subscription::run_with_id(
+                TypeId::of::<TrashWatcherSubscription>(),
+                stream::channel(25, |mut output| async move {
+                    let watcher_res = new_debouncer(
+                        time::Duration::from_millis(250),
+                        Some(time::Duration::from_millis(250)),
+                        move |event_res: notify_debouncer_full::DebounceEventResult| match event_res
+                        {
+                            Ok(mut events) => {
+                                events.retain(|event| {
+                                    matches!(
+                                        event.kind,
+                                        notify::EventKind::Create(_) | notify::EventKind::Remove(_)
+                                    )
+                                });
+
+                                if !events.is_empty() {
+                                    if let Err(e) = futures::executor::block_on(async {
+                                        output.send(Message::RescanTrash).await
+                                    }) {
+                                        log::warn!("trash needs to be rescanned but sending message failed: {e:?}");
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                log::warn!("failed to watch trash bin for changes: {e:?}")
+                            }
+                        },
+                    );
+
+                    // TODO: Trash watching support for Windows, macOS, and other OSes
+                    #[cfg(all(
+                        unix,
+                        not(target_os = "macos"),
+                        not(target_os = "ios"),
+                        not(target_os = "android")
+                    ))]
+                    match (watcher_res, trash::os_limited::trash_folders()) {
+                        (Ok(mut watcher), Ok(trash_bins)) => {
+                            for path in trash_bins {
+                                if let Err(e) = watcher
+                                    .watcher()
+                                    .watch(&path, notify::RecursiveMode::Recursive)
+                                {
+                                    log::warn!(
+                                        "failed to add trash bin `{}` to watcher: {e:?}",
+                                        path.display()
+                                    );
+                                }
+                            }
+
+                            // Don't drop the watcher
+                            std::future::pending().await
+                        }
+                        (Err(e), _) => {
+                            log::warn!("failed to create new watcher for trash bin: {e:?}")
+                        }
+                        (_, Err(e)) => {
+                            log::warn!("could not find any valid trash bins to watch: {e:?}")
+                        }
+                    }
+
+                    std::future::pending().await
+                }),
+            ),
+        ];
+
+        // Only the main window can be controlled remotely, desktop mode runs as its own process.
+        // The service stops when the window is closed so new invocations open their own window.
+        #[cfg(feature = "dbus")]
+        if matches!(self.mode, Mode::App) && self.window_id_opt.is_some() {
+            subscriptions.push(crate::dbus::subscription());
+        }
+
+        for (key, mounter) in MOUNTERS.iter() {
+            subscriptions.push(
+                mounter.subscription().with(*key).map(
+                    |(key, mounter_message)| match mounter_message {
+                        MounterMessage::Items(items) => Message::MounterItems(key, items),
+                        MounterMessage::MountResult(item, res) => {
+                            Message::MountResult(key, item, res)
+                        }
+                        MounterMessage::NetworkAuth(uri, auth, auth_tx) => {
+                            Message::NetworkAuth(key, uri, auth, auth_tx)
+                        }
+                        MounterMessage::NetworkResult(uri, res) => {
+                            Message::NetworkResult(key, uri, res)
+                        }
+                        MounterMessage::UnmountResult(item, res) => {
+                            Message::UnmountResult(key, item, res)
+                        }
+                    },
+                ),
+            );
+        }
+
+        // Only the visible active tab is rescanned, and not while an operation changes its folder
+        if self.config.auto_refresh_interval > 0 && self.window_id_opt.is_some() {
+            let path_opt = match self.active_panel {
+                PaneType::LeftPane => self
+                    .tab_model1
+                    .data::<Tab1>(self.tab_model1.active())
+                    .and_then(|tab| match &tab.location {
+                        Location1::Path(path) => Some(path),
+                        _ => None,
+                    }),
+                _ => self
+                    .tab_model2
+                    .data::<Tab2>(self.tab_model2.active())
+                    .and_then(|tab| match &tab.location {
+                        Location2::Path(path) => Some(path),
+                        _ => None,
+                    }),
+            };
+            if let Some(path) = path_opt {
+                if !self
+                    .pending_operations
+                    .values()
+                    .any(|(op, _)| op.changes_dir(path))
+                {
+                    subscriptions.push(
+                        cosmic::iced::time::every(time::Duration::from_secs(
+                            self.config.auto_refresh_interval.into(),
+                        ))
+                        .map(|_| Message::AutoRefresh),
+                    );
+                }
             }
-            if op.show_progress_notification() {
-                let progress = controller.progress();
-                if title.is_empty() {
-                    title = op.pending_text(progress, controller.state());
+        }
+
+        if !self.pending_operations.is_empty() {
+            //TODO: inhibit suspend/shutdown?
+
+            if self.window_id_opt.is_some() {
+                // Refresh progress when window is open and operations are in progress
+                subscriptions.push(window::frames().map(|_| Message::None));
+            } else {
+                // Handle notification when window is closed and operations are in progress
+                #[cfg(feature = "notify")]
+                if self.config.notify_operations == NotifyOperations::Completion {
+                    struct NotificationSubscription;
+                    subscriptions.push(Subscription::run_with_id(
+                        TypeId::of::<NotificationSubscription>(),
+                        stream::channel(1, move |msg_tx| async move {
+                            let msg_tx = Arc::new(tokio::sync::Mutex::new(msg_tx));
+                            tokio::task::spawn_blocking(move || {
+                                match notify_rust::Notification::new()
+                                    .summary(&fl!("notification-in-progress"))
+                                    .timeout(notify_rust::Timeout::Never)
+                                    .show()
+                                {
+                                    Ok(notification) => {
+                                        let _ = futures::executor::block_on(async {
+                                            msg_tx
+                                                .lock()
+                                                .await
+                                                .send(Message::Notification(Arc::new(Mutex::new(
+                                                    notification,
+                                                ))))
+                                                .await
+                                        });
+                                    }
+                                    Err(err) => {
+                                        log::warn!("failed to create notification: {}", err);
+                                    }
+                                }
+                            })
+                            .await
+                            .unwrap();
+
+                            std::future::pending().await
+                        }),
+                    ));
                 }
-                total_progress += progress;
-                count += 1;
             }
         }
-        let running = count;
-        // Adjust the progress bar so it does not jump around when operations finish
-        for id in self.progress_operations.iter() {
-            if self.complete_operations.contains_key(id) {
-                total_progress += 1.0;
-                count += 1;
+
+        for id in self.read_only_refused.iter() {
+            let id = *id;
+            subscriptions.push(Subscription::run_with_id(
+                ("read-only-refused", id),
+                stream::channel(1, move |mut msg_tx| async move {
+                    let _ = msg_tx.send(Message::ReadOnlyRefused(id)).await;
+                    std::future::pending().await
+                }),
+            ));
+        }
+
+        for (id, (pending_operation, controller)) in self.pending_operations.iter() {
+            //TODO: use recipe?
+            let id = *id;
+            let pending_operation = pending_operation.clone();
+            let controller = controller.clone();
+            subscriptions.push(Subscription::run_with_id(
+                id,
+                stream::channel(16, move |msg_tx| async move {
+                    let msg_tx = Arc::new(tokio::sync::Mutex::new(msg_tx));
+                    match pending_operation.perform(&msg_tx, controller).await {
+                        Ok(result_paths) => {
+                            let _ = msg_tx
+                                .lock()
+                                .await
+                                .send(Message::PendingComplete(id, result_paths))
+                                .await;
+                        }
+                        Err(err) => {
+                            let _ = msg_tx
+                                .lock()
+                                .await
+                                .send(Message::PendingError(id, err.to_string()))
+                                .await;
+                        }
+                    }
+
+                    std::future::pending().await
+                }),
+            ));
+        }
+
+        let mut selected_preview = None;
+        if self.core.window.show_context {
+            if let ContextPage::Preview(entity_opt, PreviewKind::Selected) = self.context_page {
+                let entity = match entity_opt {
+                    Some(entity) => entity,
+                    None => {
+                        if self.active_panel == PaneType::LeftPane {
+                            self.tab_model1.active()
+                        } else {
+                            self.tab_model2.active()
+                        }
+                    }
+                };
+
+                selected_preview = Some(entity);
             }
         }
-        let finished = count - running;
-        total_progress /= count as f32;
-        if running > 1 {
-            if finished > 0 {
-                title = fl!(
-                    "operations-running-finished",
-                    running = running,
-                    finished = finished,
-                    percent = (total_progress as i32)
-                );
+        let entities: Vec<_> = match self.active_panel {
+            PaneType::LeftPane => self.tab_model1.iter().collect(),
+            PaneType::RightPane => self.tab_model2.iter().collect(),
+            _ => {
+                log::error!("unknown panel used!");
+                Vec::new()
+            }
+        };
+        for entity in entities {
+            if self.active_panel == PaneType::LeftPane {
+                if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
+                    subscriptions.push(
+                        tab.subscription(selected_preview == Some(entity))
+                            .with(entity)
+                            .map(|(entity, tab_msg)| Message::TabMessage(Some(entity), tab_msg)),
+                    );
+                }
             } else {
-                title = fl!(
-                    "operations-running",
-                    running = running,
-                    percent = (total_progress as i32)
-                );
+                if let Some(tab) = self.tab_model2.data::<Tab2>(entity) {
+                    subscriptions.push(
+                        tab.subscription(selected_preview == Some(entity))
+                            .with(entity)
+                            .map(|(entity, tab_msg)| {
+                                Message::TabMessageRight(Some(entity), tab_msg)
+                            }),
+                    );
+                }
             }
         }
+        Subscription::batch(subscriptions)
+    }
+}
 
-        //TODO: get height from theme?
-        let progress_bar_height = Length::Fixed(4.0);
-        let progress_bar =
-            widget::progress_bar(0.0..=1.0, total_progress).height(progress_bar_height);
+// Utilities to build a temporary file hierarchy for tests.
+//
+// Ideally, tests would use the cap-std crate which limits path traversal.
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use std::{
+        cmp::Ordering,
+        fs::File,
+        io::{self, Write},
+        iter,
+        path::Path,
+    };
 
-        let container = widget::layer_container(widget::column::with_children(vec![
-            widget::row::with_children(vec![
-                progress_bar.into(),
-                if all_paused {
-                    widget::tooltip(
-                        widget::button::icon(widget::icon::from_name(
-                            "media-playback-start-symbolic",
-                        ))
-                        .on_press(Message::PendingPauseAll(false))
-                        .padding(8),
-                        widget::text::body(fl!("resume")),
-                        widget::tooltip::Position::Top,
-                    )
-                    .into()
-                } else {
-                    widget::tooltip(
-                        widget::button::icon(widget::icon::from_name(
-                            "media-playback-pause-symbolic",
-                        ))
-                        .on_press(Message::PendingPauseAll(true))
-                        .padding(8),
-                        widget::text::body(fl!("pause")),
-                        widget::tooltip::Position::Top,
-                    )
-                    .into()
-                },
-                widget::tooltip(
-                    widget::button::icon(widget::icon::from_name("window-close-symbolic"))
-                        .on_press(Message::PendingCancelAll)
-                        .padding(8),
-                    widget::text::body(fl!("cancel")),
-                    widget::tooltip::Position::Top,
-                )
-                .into(),
-            ])
-            .align_y(Alignment::Center)
-            .into(),
-            widget::text::body(title).into(),
-            widget::Space::with_height(space_s).into(),
-            widget::row::with_children(vec![
-                widget::button::link(fl!("details"))
-                    .on_press(Message::ToggleContextPage(ContextPage::EditHistory))
-                    .padding(0)
-                    .trailing_icon(true)
-                    .into(),
-                widget::horizontal_space().into(),
-                widget::button::standard(fl!("dismiss"))
-                    .on_press(Message::PendingDismiss)
-                    .into(),
-            ])
-            .align_y(Alignment::Center)
-            .into(),
-        ]))
-        .padding([8, space_xs])
-        .layer(cosmic_theme::Layer::Primary);
+    use log::{debug, trace};
+    use tempfile::{tempdir, TempDir};
+
+    use crate::{
+        config::{IconSizes, TabConfig1},
+        tab1::Item,
+    };
+
+    use super::*;
+
+    // Default number of files, directories, and nested directories for test file system
+    pub const NUM_FILES: usize = 2;
+    pub const NUM_HIDDEN: usize = 1;
+    pub const NUM_DIRS: usize = 2;
+    pub const NUM_NESTED: usize = 1;
+    pub const NAME_LEN: usize = 5;
+
+    /// Add `n` temporary files in `dir`
+    ///
+    /// Each file is assigned a numeric name from [0, n) with a prefix.
+    pub fn file_flat_hier<D: AsRef<Path>>(dir: D, n: usize, prefix: &str) -> io::Result<Vec<File>> {
+        let dir = dir.as_ref();
+        (0..n)
+            .map(|i| -> io::Result<File> {
+                let name = format!("{prefix}{i}");
+                let path = dir.join(&name);
+
+                let mut file = File::create(path)?;
+                file.write_all(name.as_bytes())?;
+
+                Ok(file)
+            })
+            .collect()
+    }
+
+    // Random alphanumeric String of length `len`
+    fn rand_string(len: usize) -> String {
+        (0..len).map(|_| fastrand::alphanumeric()).collect()
+    }
+
+    /// Create a small, temporary file hierarchy.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - Number of files to create in temp directories
+    /// * `hidden` - Number of hidden files to create
+    /// * `dirs` - Number of directories to create
+    /// * `nested` - Number of nested directories to create in new dirs
+    /// * `name_len` - Length of randomized directory names
+    pub fn simple_fs(
+        files: usize,
+        hidden: usize,
+        dirs: usize,
+        nested: usize,
+        name_len: usize,
+    ) -> io::Result<TempDir> {
+        // Files created inside of a TempDir are deleted with the directory
+        // TempDir won't leak resources as long as the destructor runs
+        let root = tempdir()?;
+        debug!("Root temp directory: {}", root.as_ref().display());
+        trace!("Creating {files} files and {hidden} hidden files in {dirs} temp dirs with {nested} nested temp dirs");
+
+        // All paths for directories and nested directories
+        let paths = (0..dirs).flat_map(|_| {
+            let root = root.as_ref();
+            let current = rand_string(name_len);
 
-        Some(container.into())
-    }
+            iter::once(root.join(&current)).chain(
+                (0..nested).map(move |_| root.join(format!("{current}/{}", rand_string(name_len)))),
+            )
+        });
 
-    fn header_start(&self) -> Vec<Element<Self::Message>> {
-        vec![menu::menu_bar(
-            self.tab_model1.active_data::<Tab1>(),
-            &self.config,
-            &self.key_binds,
-        )]
-    }
+        // Create directories from `paths` and add a few files
+        for path in paths {
+            fs::create_dir_all(&path)?;
 
-    fn header_end(&self) -> Vec<Element<Self::Message>> {
-        let mut elements = Vec::with_capacity(2);
+            // Normal files
+            file_flat_hier(&path, files, "")?;
+            // Hidden files
+            file_flat_hier(&path, hidden, ".")?;
 
-        if let Some(term) = self.search_get() {
-            if self.core.is_condensed() {
-                elements.push(
-                    //TODO: selected state is not appearing different
-                    widget::button::icon(widget::icon::from_name("system-search-symbolic"))
-                        .on_press(Message::SearchClear)
-                        .padding(8)
-                        .selected(true)
-                        .into(),
-                );
-            } else {
-                elements.push(
-                    widget::text_input::search_input("", term)
-                        .width(Length::Fixed(240.0))
-                        .id(self.search_id.clone())
-                        .on_clear(Message::SearchClear)
-                        .on_input(Message::SearchInput)
-                        .into(),
-                );
+            for entry in path.read_dir()? {
+                let entry = entry?;
+                if entry.file_type()?.is_file() {
+                    trace!("Created file: {}", entry.path().display());
+                }
             }
-        } else {
-            elements.push(
-                widget::button::icon(widget::icon::from_name("system-search-symbolic"))
-                    .on_press(Message::SearchActivate)
-                    .padding(8)
-                    .into(),
-            );
         }
 
-        elements
+        Ok(root)
     }
 
-    /// Creates a view after each update.
-    fn view(&self) -> Element<Self::Message> {
-        let cosmic_theme::Spacing {
-            space_xxs, space_s, ..
-        } = theme::active().cosmic().spacing;
+    /// Empty file hierarchy
+    pub fn empty_fs() -> io::Result<TempDir> {
+        tempdir()
+    }
 
-        let mut pane_grid = PaneGrid::new(
-            &self.pane_model.panestates,
-            |pane, tab_model, _is_maximized| {
-                pane_grid::Content::new(cosmic::widget::responsive(move |size| {
-                    self.view_pane_content(pane, tab_model, size)
-                }))
-            },
-        )
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .spacing(space_xxs)
-        .on_click(Message::PaneClicked)
-        .drag_id(self.panegrid_drag_id)
-        .on_enter(|x, y, v| Message::DndEnterDndDestination(x, y, v))
-        .on_leave(|| Message::DndExitDndDestination)
-        .on_data_received(|name, data| Message::DndDropDndDataReceived(name, data))
-        .on_action_selected(|action| Message::DndActionSelectedDestination(action))
-        .on_drop(|x, y| Message::DndDropDndDestination(x, y));
-        //.on_dnd_enter(|v| Message::DndEnterPanegrid(v))
-        //.on_dnd_leave(|| Message::DndExitPanegrid)
-        //.on_dnd_drop(|drag_id, data, action| Message::DndDropPanegrid(drag_id, data, action))
-        //.on_resize(space_s, Message::PaneResized);
-        for p in self.pane_model.panes.iter() {
-            pane_grid.panes.push(p.to_owned());
-            pane_grid
-                .drag_id_by_pane
-                .insert(p.to_owned(), self.pane_model.drag_id_by_pane[p]);
+    /// Sort files.
+    ///
+    /// Directories are placed before files.
+    /// Files are lexically sorted.
+    /// This is more or less copied right from the [Tab] code
+    pub fn sort_files(a: &Path, b: &Path) -> Ordering {
+        match (a.is_dir(), b.is_dir()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => LANGUAGE_SORTER.compare(
+                a.file_name()
+                    .expect("temp entries should have names")
+                    .to_str()
+                    .expect("temp entries should be valid UTF-8"),
+                b.file_name()
+                    .expect("temp entries should have names")
+                    .to_str()
+                    .expect("temp entries should be valid UTF-8"),
+            ),
         }
-        pane_grid.panes.extend(self.pane_model.panes.clone());
-        pane_grid.drag_id_by_pane.extend(self.pane_model.drag_id_by_pane.clone());
-        pane_grid.dnd_pane = self.pane_model.dnd_pane.clone();
-        pane_grid.dnd_action = self.pane_model.dnd_action.clone();
-        pane_grid.dnd_pane_id = self.pane_model.dnd_pane_id.clone();
-        pane_grid.dnd_pos_x = self.pane_model.dnd_pos_x;
-        pane_grid.dnd_pos_y = self.pane_model.dnd_pos_y;
-        /*let commander_pane_grid =
-            crate::commanderpanegrid::CommanderDndDestination::new(pane_grid, Vec::new())
-                .drag_id(self.panegrid_drag_id)
-                .on_enter(|x, y, v| Message::DndEnterDndDestination(x, y, v))
-                .on_leave(|| Message::DndExitDndDestination)
-                .on_data_received(|name, data| Message::DndDropDndDataReceived(name, data))
-                .on_action_selected(|action| Message::DndActionSelectedDestination(action))
-                .on_drop(|x, y| Message::DndDropDndDestination(x, y));
-            */
-        widget::container(Element::new(pane_grid))
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .padding(space_xxs)
-            .into()
     }
 
-    fn view_window(&self, id: WindowId) -> Element<Self::Message> {
-        let content = match self.windows.get(&id) {
-            Some(WindowKind::Desktop(entity)) => {
-                let mut tab_column = widget::column::with_capacity(3);
-                let entity = entity.to_owned();
-                if self.active_panel == PaneType::LeftPane {
-                    let tab_view = match self.tab_model1.data::<Tab1>(entity) {
-                        Some(tab) => tab
-                            .view(&self.key_binds)
-                            .map(move |message| Message::TabMessage(Some(entity), message)),
-                        None => widget::vertical_space().into(),
-                    };
-                    let mut popover = widget::popover(tab_view);
-                    if let Some(dialog) = self.dialog() {
-                        popover = popover.popup(dialog);
-                    }
-                    tab_column = tab_column.push(popover);
+    /// Read directory entries from `path` and sort.
+    pub fn read_dir_sorted(path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut entries: Vec<_> = path
+            .read_dir()?
+            .map(|maybe_entry| maybe_entry.map(|entry| entry.path()))
+            .collect::<io::Result<_>>()?;
+        entries.sort_by(|a, b| sort_files(a, b));
+
+        Ok(entries)
+    }
+
+    /// Filter `path` for directories
+    pub fn filter_dirs(path: &Path) -> io::Result<impl Iterator<Item = PathBuf>> {
+        Ok(path.read_dir()?.filter_map(|entry| {
+            entry.ok().and_then(|entry| {
+                let path = entry.path();
+                if path.is_dir() {
+                    Some(path)
                 } else {
-                    let tab_view = match self.tab_model2.data::<Tab2>(entity) {
-                        Some(tab) => tab
-                            .view(&self.key_binds)
-                            .map(move |message| Message::TabMessageRight(Some(entity), message)),
-                        None => widget::vertical_space().into(),
-                    };
-                    let mut popover = widget::popover(tab_view);
-                    if let Some(dialog) = self.dialog() {
-                        popover = popover.popup(dialog);
-                    }
-                    tab_column = tab_column.push(popover);
+                    None
                 }
+            })
+        }))
+    }
 
-                // The toaster is added on top of an empty element to ensure that it does not override context menus
-                tab_column =
-                    tab_column.push(widget::toaster(&self.toasts, widget::horizontal_space()));
-                return if let Some(margin) = self.margin.get(&id) {
-                    if margin.0 >= 0. || margin.2 >= 0. {
-                        tab_column = widget::column::with_children(vec![
-                            vertical_space().height(margin.0).into(),
-                            tab_column.into(),
-                            vertical_space().height(margin.2).into(),
-                        ])
-                    }
-                    if margin.1 >= 0. || margin.3 >= 0. {
-                        Element::from(widget::row::with_children(vec![
-                            horizontal_space().width(margin.1).into(),
-                            tab_column.into(),
-                            horizontal_space().width(margin.3).into(),
-                        ]))
-                    } else {
-                        tab_column.into()
-                    }
-                } else {
-                    tab_column.into()
-                };
-            }
-            Some(WindowKind::DesktopViewOptions) => self.desktop_view_options(),
-            Some(WindowKind::Preview1(entity_opt, kind)) => {
-                let ret = self
-                    .preview_left(entity_opt, kind, false)
-                    .map(|x| Message::TabMessage(*entity_opt, x));
-                return ret.into();
-            }
-            Some(WindowKind::Preview2(entity_opt, kind)) => {
-                let ret = self
-                    .preview_right(entity_opt, kind, false)
-                    .map(|x| Message::TabMessageRight(*entity_opt, x));
-                return ret.into();
-            }
-            None => {
-                //TODO: distinct views per monitor in desktop mode
-                return self.view_main().map(|message| match message {
-                    app::Message::App(app) => app,
-                    app::Message::Cosmic(cosmic) => Message::Cosmic(cosmic),
-                    app::Message::None => Message::None,
-                });
-            }
-        };
+    // Filter `path` for files
+    pub fn filter_files(path: &Path) -> io::Result<impl Iterator<Item = PathBuf>> {
+        Ok(path.read_dir()?.filter_map(|entry| {
+            entry.ok().and_then(|entry| {
+                let path = entry.path();
+                path.is_file().then_some(path)
+            })
+        }))
+    }
+
+    /// Boiler plate for Tab tests
+    pub fn tab_click_new(
+        files: usize,
+        hidden: usize,
+        dirs: usize,
+        nested: usize,
+        name_len: usize,
+    ) -> io::Result<(TempDir, Tab1)> {
+        let fs = simple_fs(files, hidden, dirs, nested, name_len)?;
+        let path = fs.path();
+
+        // New tab with items
+        let location = Location1::Path(path.to_owned());
+        let (parent_item_opt, items) = location.scan(IconSizes::default());
+        let mut tab = Tab1::new(location, TabConfig1::default());
+        tab.parent_item_opt = parent_item_opt;
+        tab.set_items(items);
+
+        // Ensure correct number of directories as a sanity check
+        let items = tab.items_opt().expect("tab should be populated with Items");
+        assert_eq!(NUM_DIRS, items.len());
 
-        widget::container(widget::scrollable(content))
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .class(theme::Container::WindowBackground)
-            .into()
+        Ok((fs, tab))
     }
 
-    fn subscription(&self) -> Subscription<Self::Message> {
-        struct ThemeSubscription;
-        struct TerminalEventSubscription;
-        struct WatcherSubscription;
-        struct WatcherSubscriptionRight;
-        struct TrashWatcherSubscription;
-
-        let mut subscriptions = vec![
-            event::listen_with(|event, status, _window_id| match event {
-                Event::Keyboard(KeyEvent::KeyPressed { key, modifiers, .. }) => match status {
-                    event::Status::Ignored => Some(Message::Key(modifiers, key)),
-                    event::Status::Captured => None,
-                },
-                Event::Keyboard(KeyEvent::ModifiersChanged(modifiers)) => {
-                    Some(Message::Modifiers(modifiers))
-                }
-                Event::Window(WindowEvent::Unfocused) => Some(Message::WindowUnfocus),
-                Event::Window(WindowEvent::CloseRequested) => Some(Message::WindowClose),
-                Event::Window(WindowEvent::Opened { position: _, size }) => {
-                    Some(Message::Size(size))
-                }
-                Event::Window(WindowEvent::Resized(s)) => Some(Message::Size(s)),
-                Event::Window(WindowEvent::Moved(p)) => Some(Message::Move(p)),
-                Event::Window(WindowEvent::FileHovered(f)) => Some(Message::DndHoveredWindow(f)),
-                Event::Window(WindowEvent::FilesHoveredLeft) => Some(Message::DndHoveredLeftWindow),
-                Event::Window(WindowEvent::FileDropped(f)) => Some(Message::DndDropWindow(f)),
-                #[cfg(feature = "wayland")]
-                Event::PlatformSpecific(event::PlatformSpecific::Wayland(wayland_event)) => {
-                    match wayland_event {
-                        WaylandEvent::Output(output_event, output) => {
-                            Some(Message::OutputEvent(output_event, output))
-                        }
-                        #[cfg(feature = "desktop")]
-                        WaylandEvent::OverlapNotify(event) => {
-                            Some(Message::Overlap(event, window_id))
-                        }
-                        _ => None,
-                    }
-                }
-                Event::Mouse(cosmic::iced_core::mouse::Event::ButtonReleased(
-                    cosmic::iced_core::mouse::Button::Left,
-                )) => Some(Message::CopyPrimary(None)),
-                _ => None,
-            }),
-            Config::subscription().map(|update| {
-                if !update.errors.is_empty() {
-                    log::info!(
-                        "errors loading config {:?}: {:?}",
-                        update.keys,
-                        update.errors
-                    );
-                }
-                Message::Config(update.config)
-            }),
-            cosmic_config::config_subscription::<_, cosmic_theme::ThemeMode>(
-                TypeId::of::<ThemeSubscription>(),
-                cosmic_theme::THEME_MODE_ID.into(),
-                cosmic_theme::ThemeMode::version(),
-            )
-            .map(|update| {
-                if !update.errors.is_empty() {
-                    log::info!(
-                        "errors loading theme mode {:?}: {:?}",
-                        update.keys,
-                        update.errors
-                    );
-                }
-                Message::SystemThemeModeChange(update.config)
-            }),
-            Subscription::run_with_id(
-                TypeId::of::<TerminalEventSubscription>(),
-                stream::channel(100, |mut output| async move {
-                    let (event_tx, mut event_rx) = mpsc::unbounded_channel();
-                    output.send(Message::TermEventTx(event_tx)).await.unwrap();
+    pub fn _tab_click_new2(
+        files: usize,
+        hidden: usize,
+        dirs: usize,
+        nested: usize,
+        name_len: usize,
+    ) -> io::Result<(TempDir, Tab2)> {
+        let fs = simple_fs(files, hidden, dirs, nested, name_len)?;
+        let path = fs.path();
 
-                    while let Some((pane, entity, event)) = event_rx.recv().await {
-                        output
-                            .send(Message::TermEvent(pane, entity, event))
-                            .await
-                            .unwrap();
-                    }
+        // New tab with items
+        let location = Location2::Path(path.to_owned());
+        let (parent_item_opt, items) = location.scan(IconSizes::default());
+        let mut tab = Tab2::new(location, TabConfig2::default());
+        tab.parent_item_opt = parent_item_opt;
+        tab.set_items(items);
 
-                    panic!("terminal event channel closed");
-                }),
-            ),
-            Subscription::run_with_id(
-                TypeId::of::<WatcherSubscription>(),
-                stream::channel(100, |mut output| async move {
-                    let watcher_res = {
-                        let mut output = output.clone();
-                        new_debouncer(
-                            time::Duration::from_millis(250),
-                            Some(time::Duration::from_millis(250)),
-                            move |events_res: notify_debouncer_full::DebounceEventResult| {
-                                match events_res {
-                                    Ok(mut events) => {
-                                        log::debug!("{:?}", events);
+        // Ensure correct number of directories as a sanity check
+        let items = tab.items_opt().expect("tab should be populated with Items");
+        assert_eq!(NUM_DIRS, items.len());
 
-                                        events.retain(|event| {
-                                            match &event.kind {
-                                                notify::EventKind::Access(_) => {
-                                                    // Data not mutated
-                                                    false
-                                                }
-                                                notify::EventKind::Modify(
-                                                    notify::event::ModifyKind::Metadata(e),
-                                                ) if (*e != notify::event::MetadataKind::Any
-                                                    && *e
-                                                        != notify::event::MetadataKind::WriteTime) =>
-                                                {
-                                                    // Data not mutated nor modify time changed
-                                                    false
-                                                }
-                                                _ => true
-                                            }
-                                        });
+        Ok((fs, tab))
+    }
 
-                                        if !events.is_empty() {
-                                            match futures::executor::block_on(async {
-                                                output.send(Message::NotifyEvents(events)).await
-                                            }) {
-                                                Ok(()) => {}
-                                                Err(err) => {
-                                                    log::warn!(
-                                                        "failed to send notify events: {:?}",
-                                                        err
-                                                    );
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(err) => {
-                                        log::warn!("failed to watch files: {:?}", err);
-                                    }
-                                }
-                            },
-                        )
-                    };
+    /// Equality for [Path] and [Item].
+    pub fn eq_path_item(path: &Path, item: &Item) -> bool {
+        let name = path
+            .file_name()
+            .expect("temp entries should have names")
+            .to_str()
+            .expect("temp entries should be valid UTF-8");
+        let is_dir = path.is_dir();
 
-                    match watcher_res {
-                        Ok(watcher) => {
-                            match output
-                                .send(Message::NotifyWatcherLeft(WatcherWrapper {
-                                    watcher_opt: Some(watcher),
-                                }))
-                                .await
-                            {
-                                Ok(()) => {}
-                                Err(err) => {
-                                    log::warn!("failed to send notify watcher: {:?}", err);
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            log::warn!("failed to create file watcher: {:?}", err);
-                        }
-                    }
+        // NOTE: I don't want to change `tab::hidden_attribute` to `pub(crate)` for
+        // tests without asking
+        #[cfg(not(target_os = "windows"))]
+        let is_hidden = name.starts_with('.');
 
-                    std::future::pending().await
-                }),
-            ),
-            Subscription::run_with_id(
-                TypeId::of::<WatcherSubscriptionRight>(),
-                stream::channel(100, |mut output| async move {
-                    let watcher_res = {
-                        let mut output = output.clone();
-                        new_debouncer(
-                            time::Duration::from_millis(250),
-                            Some(time::Duration::from_millis(250)),
-                            move |events_res: notify_debouncer_full::DebounceEventResult| {
-                                match events_res {
-                                    Ok(mut events) => {
-                                        log::debug!("{:?}", events);
+        #[cfg(target_os = "windows")]
+        let is_hidden = {
+            use std::os::windows::fs::MetadataExt;
+            const FILE_ATTRIBUTE_HIDDEN: u32 = 2;
+            let metadata = path.metadata().expect("fetching file metadata");
+            metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN == FILE_ATTRIBUTE_HIDDEN
+        };
 
-                                        events.retain(|event| {
-                                            match &event.kind {
-                                                notify::EventKind::Access(_) => {
-                                                    // Data not mutated
-                                                    false
-                                                }
-                                                notify::EventKind::Modify(
-                                                    notify::event::ModifyKind::Metadata(e),
-                                                ) if (*e != notify::event::MetadataKind::Any
-                                                    && *e
-                                                        != notify::event::MetadataKind::WriteTime) =>
-                                                {
-                                                    // Data not mutated nor modify time changed
-                                                    false
-                                                }
-                                                _ => true
-                                            }
-                                        });
+        name == item.name
+            && is_dir == item.metadata.is_dir()
+            && path == item.path_opt().expect("item should have path")
+            && is_hidden == item.hidden
+    }
 
-                                        if !events.is_empty() {
-                                            match futures::executor::block_on(async {
-                                                output.send(Message::NotifyEvents(events)).await
-                                            }) {
-                                                Ok(()) => {}
-                                                Err(err) => {
-                                                    log::warn!(
-                                                        "failed to send notify events: {:?}",
-                                                        err
-                                                    );
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(err) => {
-                                        log::warn!("failed to watch files: {:?}", err);
-                                    }
-                                }
-                            },
-                        )
-                    };
+    pub fn _eq_path_item2(path: &Path, item: &crate::tab2::Item) -> bool {
+        let name = path
+            .file_name()
+            .expect("temp entries should have names")
+            .to_str()
+            .expect("temp entries should be valid UTF-8");
+        let is_dir = path.is_dir();
 
-                    match watcher_res {
-                        Ok(watcher) => {
-                            match output
-                                .send(Message::NotifyWatcherRight(WatcherWrapper {
-                                    watcher_opt: Some(watcher),
-                                }))
-                                .await
-                            {
-                                Ok(()) => {}
-                                Err(err) => {
-                                    log::warn!("failed to send notify watcher: {:?}", err);
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            log::warn!("failed to create file watcher: {:?}", err);
-                        }
-                    }
+        // NOTE: I don't want to change `tab::hidden_attribute` to `pub(crate)` for
+        // tests without asking
+        #[cfg(not(target_os = "windows"))]
+        let is_hidden = name.starts_with('.');
 
-                    std::future::pending().await
-                }),
-            ),
-            Subscription::run_with_id(
-                TypeId::of::<TrashWatcherSubscription>(),
-                stream::channel(25, |mut output| async move {
-                    let watcher_res = new_debouncer(
-                        time::Duration::from_millis(250),
-                        Some(time::Duration::from_millis(250)),
-                        move |event_res: notify_debouncer_full::DebounceEventResult| match event_res
-                        {
-                            Ok(mut events) => {
-                                events.retain(|event| {
-                                    matches!(
-                                        event.kind,
-                                        notify::EventKind::Create(_) | notify::EventKind::Remove(_)
-                                    )
-                                });
+        #[cfg(target_os = "windows")]
+        let is_hidden = {
+            use std::os::windows::fs::MetadataExt;
+            const FILE_ATTRIBUTE_HIDDEN: u32 = 2;
+            let metadata = path.metadata().expect("fetching file metadata");
+            metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN == FILE_ATTRIBUTE_HIDDEN
+        };
 
-                                if !events.is_empty() {
-                                    if let Err(e) = futures::executor::block_on(async {
-                                        output.send(Message::RescanTrash).await
-                                    }) {
-                                        log::warn!("trash needs to be rescanned but sending message failed: {e:?}");
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                log::warn!("failed to watch trash bin for changes: {e:?}")
-                            }
-                        },
-                    );
+        name == item.name
+            && is_dir == item.metadata.is_dir()
+            && path == item.path_opt().expect("item should have path")
+            && is_hidden == item.hidden
+    }
 
-                    // TODO: Trash watching support for Windows, macOS, and other OSes
-                    #[cfg(all(
-                        unix,
-                        not(target_os = "macos"),
-                        not(target_os = "ios"),
-                        not(target_os = "android")
-                    ))]
-                    match (watcher_res, trash::os_limited::trash_folders()) {
-                        (Ok(mut watcher), Ok(trash_bins)) => {
-                            for path in trash_bins {
-                                if let Err(e) = watcher
-                                    .watcher()
-                                    .watch(&path, notify::RecursiveMode::Recursive)
-                                {
-                                    log::warn!(
-                                        "failed to add trash bin `{}` to watcher: {e:?}",
-                                        path.display()
-                                    );
-                                }
-                            }
+    /// Asserts `tab`'s location changed to `path`
+    pub fn _assert_eq_tab_path2(tab: &Tab2, path: &Path) {
+        // Paths should be the same
+        let Some(tab_path) = tab.location.path_opt() else {
+            panic!("Expected tab's location to be a path");
+        };
 
-                            // Don't drop the watcher
-                            std::future::pending().await
-                        }
-                        (Err(e), _) => {
-                            log::warn!("failed to create new watcher for trash bin: {e:?}")
-                        }
-                        (_, Err(e)) => {
-                            log::warn!("could not find any valid trash bins to watch: {e:?}")
-                        }
-                    }
+        assert_eq!(
+            path,
+            tab_path,
+            "Tab's path is {} instead of being updated to {}",
+            tab_path.display(),
+            path.display()
+        );
+    }
 
-                    std::future::pending().await
-                }),
-            ),
-        ];
+    pub fn assert_eq_tab_path(tab: &Tab1, path: &Path) {
+        // Paths should be the same
+        let Some(tab_path) = tab.location.path_opt() else {
+            panic!("Expected tab's location to be a path");
+        };
 
-        for (key, mounter) in MOUNTERS.iter() {
-            subscriptions.push(
-                mounter.subscription().with(*key).map(
-                    |(key, mounter_message)| match mounter_message {
-                        MounterMessage::Items(items) => Message::MounterItems(key, items),
-                        MounterMessage::MountResult(item, res) => {
-                            Message::MountResult(key, item, res)
-                        }
-                        MounterMessage::NetworkAuth(uri, auth, auth_tx) => {
-                            Message::NetworkAuth(key, uri, auth, auth_tx)
-                        }
-                        MounterMessage::NetworkResult(uri, res) => {
-                            Message::NetworkResult(key, uri, res)
-                        }
-                    },
-                ),
-            );
-        }
+        assert_eq!(
+            path,
+            tab_path,
+            "Tab's path is {} instead of being updated to {}",
+            tab_path.display(),
+            path.display()
+        );
+    }
 
-        if !self.pending_operations.is_empty() {
-            //TODO: inhibit suspend/shutdown?
+    /// Assert that tab's items are equal to a path's entries.
+    pub fn _assert_eq_tab_path_contents(tab: &Tab1, path: &Path) {
+        let Some(tab_path) = tab.location.path_opt() else {
+            panic!("Expected tab's location to be a path");
+        };
 
-            if self.window_id_opt.is_some() {
-                // Refresh progress when window is open and operations are in progress
-                subscriptions.push(window::frames().map(|_| Message::None));
-            } else {
-                // Handle notification when window is closed and operations are in progress
-                #[cfg(feature = "notify")]
-                {
-                    struct NotificationSubscription;
-                    subscriptions.push(Subscription::run_with_id(
-                        TypeId::of::<NotificationSubscription>(),
-                        stream::channel(1, move |msg_tx| async move {
-                            let msg_tx = Arc::new(tokio::sync::Mutex::new(msg_tx));
-                            tokio::task::spawn_blocking(move || {
-                                match notify_rust::Notification::new()
-                                    .summary(&fl!("notification-in-progress"))
-                                    .timeout(notify_rust::Timeout::Never)
-                                    .show()
-                                {
-                                    Ok(notification) => {
-                                        let _ = futures::executor::block_on(async {
-                                            msg_tx
-                                                .lock()
-                                                .await
-                                                .send(Message::Notification(Arc::new(Mutex::new(
-                                                    notification,
-                                                ))))
-                                                .await
-                                        });
-                                    }
-                                    Err(err) => {
-                                        log::warn!("failed to create notification: {}", err);
-                                    }
-                                }
-                            })
-                            .await
-                            .unwrap();
+        // Tab items are sorted so paths from read_dir must be too
+        let entries = read_dir_sorted(path).expect("should be able to read paths from temp dir");
 
-                            std::future::pending().await
-                        }),
-                    ));
-                }
-            }
-        }
+        // Check lengths.
+        // `items_opt` is optional and the directory at `path` may have zero entries
+        // Therefore, this doesn't panic if `items_opt` is None
+        let items_len = tab.items_opt().map(|items| items.len()).unwrap_or_default();
+        assert_eq!(entries.len(), items_len);
 
-        for (id, (pending_operation, controller)) in self.pending_operations.iter() {
-            //TODO: use recipe?
-            let id = *id;
-            let pending_operation = pending_operation.clone();
-            let controller = controller.clone();
-            subscriptions.push(Subscription::run_with_id(
-                id,
-                stream::channel(16, move |msg_tx| async move {
-                    let msg_tx = Arc::new(tokio::sync::Mutex::new(msg_tx));
-                    match pending_operation.perform(&msg_tx, controller).await {
-                        Ok(result_paths) => {
-                            let _ = msg_tx
-                                .lock()
-                                .await
-                                .send(Message::PendingComplete(id, result_paths))
-                                .await;
-                        }
-                        Err(err) => {
-                            let _ = msg_tx
-                                .lock()
-                                .await
-                                .send(Message::PendingError(id, err.to_string()))
-                                .await;
-                        }
-                    }
+        let empty = Vec::new();
+        assert!(
+            entries
+                .into_iter()
+                .zip(tab.items_opt().unwrap_or(&empty))
+                .all(|(a, b)| eq_path_item(&a, b)),
+            "Path ({}) and Tab path ({}) don't have equal contents",
+            path.display(),
+            tab_path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::{NAME_LEN, NUM_DIRS, NUM_FILES};
+
+    #[test]
+    fn diff_pairs_changed_lines_side_by_side() {
+        let left = ["a", "b", "c", "d", "e"];
+        let right = ["a", "c", "D", "e", "f"];
+        let kinds: Vec<_> = diff::diff_lines(&left, &right)
+            .into_iter()
+            .map(|row| {
+                (
+                    row.kind,
+                    row.left.map(|(number, _)| number),
+                    row.right.map(|(number, _)| number),
+                )
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (DiffKind::Equal, Some(1), Some(1)),
+                (DiffKind::Removed, Some(2), None),
+                (DiffKind::Equal, Some(3), Some(2)),
+                (DiffKind::Changed, Some(4), Some(3)),
+                (DiffKind::Equal, Some(5), Some(4)),
+                (DiffKind::Added, None, Some(5)),
+            ]
+        );
+        assert!(diff::diff_lines(&[], &[]).is_empty());
+
+        // The left panel is always shown on the left
+        let (a, b, c) = (PathBuf::from("a"), PathBuf::from("b"), PathBuf::from("c"));
+        assert_eq!(
+            diff_pair(&[a.clone(), b.clone()], &[c.clone()], false),
+            Some((a.clone(), b.clone()))
+        );
+        assert_eq!(
+            diff_pair(&[a.clone()], &[c.clone()], false),
+            Some((c.clone(), a.clone()))
+        );
+        assert_eq!(diff_pair(&[a.clone()], &[b, c], true), None);
+        assert_eq!(diff_pair(&[a], &[], true), None);
+    }
 
-                    std::future::pending().await
-                }),
-            ));
+    #[test]
+    fn default_favorites_exist() {
+        let favorites = Favorite::defaults();
+        assert_eq!(favorites.first(), Some(&Favorite::Home));
+        let home_opt = Favorite::Home.path_opt();
+        for favorite in &favorites[1..] {
+            let path = favorite
+                .path_opt()
+                .expect("default favorite should have a path");
+            assert!(path.is_dir());
+            assert_ne!(Some(path), home_opt);
         }
+    }
 
-        let mut selected_preview = None;
-        if self.core.window.show_context {
-            if let ContextPage::Preview(entity_opt, PreviewKind::Selected) = self.context_page {
-                let entity = match entity_opt {
-                    Some(entity) => entity,
-                    None => {
-                        if self.active_panel == PaneType::LeftPane {
-                            self.tab_model1.active()
-                        } else {
-                            self.tab_model2.active()
-                        }
-                    }
-                };
+    #[test]
+    fn favorites_reorder_in_both_directions() {
+        let mut favorites = vec![Favorite::Home, Favorite::Documents, Favorite::Downloads];
+        assert!(Favorite::reorder(&mut favorites, 0, 2));
+        assert_eq!(
+            favorites,
+            [Favorite::Documents, Favorite::Downloads, Favorite::Home]
+        );
+        assert!(Favorite::reorder(&mut favorites, 2, 0));
+        assert_eq!(
+            favorites,
+            [Favorite::Home, Favorite::Documents, Favorite::Downloads]
+        );
+        assert!(!Favorite::reorder(&mut favorites, 1, 1));
+        assert!(!Favorite::reorder(&mut favorites, 1, 3));
+    }
 
-                selected_preview = Some(entity);
-            }
+    #[test]
+    fn custom_actions_match_and_substitute_paths() {
+        let custom_action = config::CustomAction {
+            label: "Convert to PNG".to_string(),
+            pattern: "*.svg; image/jpeg".to_string(),
+            command: "convert %f '%d/out 100%%.png' %F".to_string(),
+            terminal: false,
         }
-        let entities: Vec<_> = match self.active_panel {
-            PaneType::LeftPane => self.tab_model1.iter().collect(),
-            PaneType::RightPane => self.tab_model2.iter().collect(),
-            _ => {
-                log::error!("unknown panel used!");
-                Vec::new()
-            }
-        };
-        for entity in entities {
-            if self.active_panel == PaneType::LeftPane {
-                if let Some(tab) = self.tab_model1.data::<Tab1>(entity) {
-                    subscriptions.push(
-                        tab.subscription(selected_preview == Some(entity))
-                            .with(entity)
-                            .map(|(entity, tab_msg)| Message::TabMessage(Some(entity), tab_msg)),
-                    );
-                }
-            } else {
-                if let Some(tab) = self.tab_model2.data::<Tab2>(entity) {
-                    subscriptions.push(
-                        tab.subscription(selected_preview == Some(entity))
-                            .with(entity)
-                            .map(|(entity, tab_msg)| {
-                                Message::TabMessageRight(Some(entity), tab_msg)
-                            }),
-                    );
+        .parse()
+        .unwrap();
+        let jpeg: mime_guess::Mime = "image/jpeg".parse().unwrap();
+        let text: mime_guess::Mime = "text/plain".parse().unwrap();
+        assert!(custom_action.matches("a.SVG", &text));
+        assert!(custom_action.matches("b", &jpeg));
+        assert!(!custom_action.matches("c.txt", &text));
+
+        let paths = [PathBuf::from("/tmp/a b.svg"), PathBuf::from("/tmp/c.svg")];
+        let args = custom_action.args(&paths, Path::new("/tmp"));
+        assert_eq!(
+            args,
+            [
+                "convert",
+                "/tmp/a b.svg",
+                "/tmp/out 100%.png",
+                "/tmp/a b.svg",
+                "/tmp/c.svg"
+            ]
+            .map(OsString::from)
+        );
+
+        // Actions without a label or command are rejected
+        assert!(config::CustomAction::default().parse().is_err());
+    }
+
+    #[test]
+    fn imported_settings_merge_and_skip_unknown() {
+        let mut exported = Config::default();
+        exported.show_details = !exported.show_details;
+        let text = exported.export().unwrap();
+        let (imported, warnings) = Config::default().import(&text).unwrap();
+        assert_eq!(imported, exported);
+        assert!(warnings.is_empty());
+
+        // Unknown and invalid settings are skipped, the rest still applies
+        let text = format!(
+            r#"{{"version": {}, "config": {{"show_details": {}, "not_a_setting": 1, "app_theme": 42}}}}"#,
+            config::CONFIG_VERSION,
+            exported.show_details
+        );
+        let (imported, warnings) = Config::default().import(&text).unwrap();
+        assert_eq!(imported.show_details, exported.show_details);
+        assert_eq!(imported.app_theme, Config::default().app_theme);
+        assert_eq!(warnings.len(), 2);
+
+        // Files of a newer version are rejected
+        let text = format!(
+            r#"{{"version": {}, "config": {{}}}}"#,
+            config::CONFIG_VERSION + 1
+        );
+        assert!(Config::default().import(&text).is_err());
+    }
+
+    #[test]
+    fn set_sort_targets_active_tab_of_right_panel() {
+        let mut tab_model1: TabModel = segmented_button::ModelBuilder::default().build();
+        let mut tab_model2: TabModel = segmented_button::ModelBuilder::default().build();
+        // Keys of separate models start out equal, so offset the left one
+        let _ = tab_model1.insert().id();
+        let left = tab_model1.insert().activate().id();
+        let right = tab_model2.insert().activate().id();
+        assert_ne!(left, right);
+
+        for (sort, expected) in [
+            (HeadingOptions1::Name, HeadingOptions2::Name),
+            (HeadingOptions1::Modified, HeadingOptions2::Modified),
+            (HeadingOptions1::Size, HeadingOptions2::Size),
+            (HeadingOptions1::TrashedOn, HeadingOptions2::TrashedOn),
+        ] {
+            for dir in [false, true] {
+                match set_sort_message(
+                    PaneType::RightPane,
+                    tab_model1.active(),
+                    tab_model2.active(),
+                    sort,
+                    dir,
+                ) {
+                    Message::TabMessageRight(
+                        Some(entity),
+                        tab2::Message::SetSort(message_sort, message_dir),
+                    ) => {
+                        assert_eq!(entity, right);
+                        assert_eq!(message_sort, expected);
+                        assert_eq!(message_dir, dir);
+                    }
+                    message => panic!("unexpected message {:?}", message),
                 }
             }
         }
-        Subscription::batch(subscriptions)
     }
-}
 
-// Utilities to build a temporary file hierarchy for tests.
-//
-// Ideally, tests would use the cap-std crate which limits path traversal.
-#[cfg(test)]
-pub(crate) mod test_utils {
-    use std::{
-        cmp::Ordering,
-        fs::File,
-        io::{self, Write},
-        iter,
-        path::Path,
-    };
+    #[test]
+    fn set_sort_targets_active_tab_of_left_panel() {
+        let mut tab_model1: TabModel = segmented_button::ModelBuilder::default().build();
+        let mut tab_model2: TabModel = segmented_button::ModelBuilder::default().build();
+        let _ = tab_model2.insert().id();
+        let left = tab_model1.insert().activate().id();
+        let right = tab_model2.insert().activate().id();
+        assert_ne!(left, right);
+
+        match set_sort_message(
+            PaneType::LeftPane,
+            tab_model1.active(),
+            tab_model2.active(),
+            HeadingOptions1::Size,
+            true,
+        ) {
+            Message::TabMessage(Some(entity), tab1::Message::SetSort(sort, dir)) => {
+                assert_eq!(entity, left);
+                assert_eq!(sort, HeadingOptions1::Size);
+                assert!(dir);
+            }
+            message => panic!("unexpected message {:?}", message),
+        }
+    }
 
-    use log::{debug, trace};
-    use tempfile::{tempdir, TempDir};
+    #[test]
+    fn copy_tab_keeps_location_and_title_in_the_other_panel() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let left = fs.path().join("left");
+        let right = fs.path().join("right");
+        fs::create_dir(&left)?;
+        fs::create_dir(&right)?;
+        let mut app = test_app(fs.path());
+
+        // Left to right
+        let (source, _) = app.open_tab_entity_left(Location1::Path(left.clone()), true, None);
+        let title = app.tab_model1.data::<Tab1>(source).unwrap().title();
+        let count = app.tab_model2.iter().count();
+        app.active_panel = PaneType::LeftPane;
+        let _ = app.update(Message::CopyTab(None));
+        assert_eq!(app.tab_model2.iter().count(), count + 1);
+        let copied = app.tab_model2.active();
+        let tab = app.tab_model2.data::<Tab2>(copied).unwrap();
+        assert_eq!(tab.location, Location2::Path(left));
+        assert_eq!(tab.title(), title);
+        assert_eq!(app.tab_model2.text(copied), Some(title.as_str()));
+        // The source tab and the focus stay where they were
+        assert_eq!(app.tab_model1.active(), source);
+        assert_eq!(app.active_panel, PaneType::LeftPane);
+
+        // Right to left
+        let (source, _) = app.open_tab_entity_right(Location2::Path(right.clone()), true, None);
+        let title = app.tab_model2.data::<Tab2>(source).unwrap().title();
+        let count = app.tab_model1.iter().count();
+        app.active_panel = PaneType::RightPane;
+        let _ = app.update(Message::CopyTab(None));
+        assert_eq!(app.tab_model1.iter().count(), count + 1);
+        let copied = app.tab_model1.active();
+        let tab = app.tab_model1.data::<Tab1>(copied).unwrap();
+        assert_eq!(tab.location, Location1::Path(right));
+        assert_eq!(tab.title(), title);
+        assert_eq!(app.tab_model1.text(copied), Some(title.as_str()));
+        assert_eq!(app.tab_model2.active(), source);
+        assert_eq!(app.active_panel, PaneType::RightPane);
+        Ok(())
+    }
 
-    use crate::{
-        config::{IconSizes, TabConfig1},
-        tab1::Item,
-    };
+    /// Check the copy and move target, selection and trash tabs of a panel whose active tab
+    /// shows `path` with `selected` selected
+    fn check_active_pane<P: ActivePane>(
+        tab_model: &TabModel,
+        path: &Path,
+        selected: &Path,
+        trash: Entity,
+    ) {
+        assert_eq!(active_tab_path::<P>(tab_model).as_deref(), Some(path));
+        assert_eq!(
+            tab_selected_paths::<P>(tab_model, None),
+            vec![selected.to_path_buf()]
+        );
+        assert!(tab_selected_paths::<P>(tab_model, Some(trash)).is_empty());
+        let trash_tabs = trash_tabs::<P>(tab_model);
+        assert_eq!(trash_tabs.len(), 1);
+        assert_eq!(trash_tabs[0].0, trash);
+    }
 
-    use super::*;
+    #[test]
+    fn active_pane_left() -> io::Result<()> {
+        let (fs, mut tab) = test_utils::tab_click_new(1, 0, NUM_DIRS, 0, NAME_LEN)?;
+        let selected = {
+            let item = &mut tab.items_opt_mut().unwrap()[0];
+            item.selected = true;
+            item.path_opt().unwrap().clone()
+        };
 
-    // Default number of files, directories, and nested directories for test file system
-    pub const NUM_FILES: usize = 2;
-    pub const NUM_HIDDEN: usize = 1;
-    pub const NUM_DIRS: usize = 2;
-    pub const NUM_NESTED: usize = 1;
-    pub const NAME_LEN: usize = 5;
+        let mut tab_model: TabModel = segmented_button::ModelBuilder::default().build();
+        let trash = tab_model
+            .insert()
+            .data(Tab1::new(Location1::Trash, TabConfig1::default()))
+            .id();
+        let _ = tab_model.insert().data(tab).activate().id();
 
-    /// Add `n` temporary files in `dir`
-    ///
-    /// Each file is assigned a numeric name from [0, n) with a prefix.
-    pub fn file_flat_hier<D: AsRef<Path>>(dir: D, n: usize, prefix: &str) -> io::Result<Vec<File>> {
-        let dir = dir.as_ref();
-        (0..n)
-            .map(|i| -> io::Result<File> {
-                let name = format!("{prefix}{i}");
-                let path = dir.join(&name);
+        check_active_pane::<LeftPanel>(&tab_model, fs.path(), &selected, trash);
+        Ok(())
+    }
 
-                let mut file = File::create(path)?;
-                file.write_all(name.as_bytes())?;
+    #[test]
+    fn active_pane_right() -> io::Result<()> {
+        let fs = test_utils::simple_fs(1, 0, NUM_DIRS, 0, NAME_LEN)?;
+        let location = Location2::Path(fs.path().to_owned());
+        let (parent_item_opt, items) = location.scan(IconSizes::default());
+        let mut tab = Tab2::new(location, TabConfig2::default());
+        tab.parent_item_opt = parent_item_opt;
+        tab.set_items(items);
+        let selected = {
+            let item = &mut tab.items_opt_mut().unwrap()[0];
+            item.selected = true;
+            item.path_opt().unwrap().clone()
+        };
 
-                Ok(file)
-            })
-            .collect()
+        let mut tab_model: TabModel = segmented_button::ModelBuilder::default().build();
+        let trash = tab_model
+            .insert()
+            .data(Tab2::new(Location2::Trash, TabConfig2::default()))
+            .id();
+        let _ = tab_model.insert().data(tab).activate().id();
+
+        check_active_pane::<RightPanel>(&tab_model, fs.path(), &selected, trash);
+        Ok(())
     }
 
-    // Random alphanumeric String of length `len`
-    fn rand_string(len: usize) -> String {
-        (0..len).map(|_| fastrand::alphanumeric()).collect()
+    #[test]
+    fn mirror_path_follows_steps_below_the_other_root() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let left = fs.path().join("left");
+        let right = fs.path().join("right");
+        fs::create_dir_all(left.join("a").join("b"))?;
+        fs::create_dir_all(right.join("a").join("b"))?;
+
+        // Into a nested folder, which has to exist in the other panel too
+        assert_eq!(
+            mirror_path(&left, &left.join("a").join("b"), &right),
+            Some(right.join("a").join("b"))
+        );
+        assert_eq!(mirror_path(&left, &left.join("missing"), &right), None);
+
+        // Back up to the panel root
+        assert_eq!(
+            mirror_path(&left.join("a").join("b"), &left, &right.join("a").join("b")),
+            Some(right.clone())
+        );
+        // Staying at the root is no step at all
+        assert_eq!(mirror_path(&left, &left, &right), None);
+        // Going up past the root of the filesystem
+        assert_eq!(mirror_path(&left.join("a"), &left, Path::new("/")), None);
+
+        // Paths outside of the folder being left are not mirrored
+        assert_eq!(mirror_path(&left.join("a"), &right, &right), None);
+        Ok(())
     }
 
-    /// Create a small, temporary file hierarchy.
-    ///
-    /// # Arguments
-    ///
-    /// * `files` - Number of files to create in temp directories
-    /// * `hidden` - Number of hidden files to create
-    /// * `dirs` - Number of directories to create
-    /// * `nested` - Number of nested directories to create in new dirs
-    /// * `name_len` - Length of randomized directory names
-    pub fn simple_fs(
-        files: usize,
-        hidden: usize,
-        dirs: usize,
-        nested: usize,
-        name_len: usize,
-    ) -> io::Result<TempDir> {
-        // Files created inside of a TempDir are deleted with the directory
-        // TempDir won't leak resources as long as the destructor runs
-        let root = tempdir()?;
-        debug!("Root temp directory: {}", root.as_ref().display());
-        trace!("Creating {files} files and {hidden} hidden files in {dirs} temp dirs with {nested} nested temp dirs");
+    #[test]
+    fn drag_offers_file_uris() {
+        use cosmic::iced::clipboard::mime::AsMimeTypes;
 
-        // All paths for directories and nested directories
-        let paths = (0..dirs).flat_map(|_| {
-            let root = root.as_ref();
-            let current = rand_string(name_len);
+        let paths = [PathBuf::from("/tmp/a b.txt"), PathBuf::from("/tmp/c")];
+        let drag = ClipboardCopy::drag(&paths);
+        let uri_list = "file:///tmp/a%20b.txt\r\nfile:///tmp/c\r\n".as_bytes();
+        for mime in ["text/uri-list", "text/plain", "text/plain;charset=utf-8"] {
+            assert!(drag.available().iter().any(|x| x == mime));
+            assert_eq!(drag.as_bytes(mime).as_deref(), Some(uri_list));
+        }
+        // Copying to the clipboard keeps plain paths for pasting as text
+        let copy = ClipboardCopy::new(ClipboardKind::Copy, &paths);
+        assert_eq!(
+            copy.as_bytes("text/plain").as_deref(),
+            Some("/tmp/a b.txt\r\n/tmp/c".as_bytes())
+        );
 
-            iter::once(root.join(&current)).chain(
-                (0..nested).map(move |_| root.join(format!("{current}/{}", rand_string(name_len)))),
-            )
-        });
+        let paste =
+            ClipboardPaste::try_from((uri_list.to_vec(), "text/uri-list".to_string())).unwrap();
+        assert_eq!(paste.paths, paths);
+    }
 
-        // Create directories from `paths` and add a few files
-        for path in paths {
-            fs::create_dir_all(&path)?;
+    #[test]
+    fn drop_action_compresses_with_alt() {
+        for action in [DndAction::Copy, DndAction::Move] {
+            assert_eq!(
+                DropAction::new(action, Modifiers::ALT),
+                DropAction::Compress
+            );
+        }
+        assert_eq!(
+            DropAction::new(DndAction::Copy, Modifiers::empty()),
+            DropAction::Copy
+        );
+        assert_eq!(
+            DropAction::new(DndAction::Move, Modifiers::SHIFT),
+            DropAction::Move
+        );
+    }
 
-            // Normal files
-            file_flat_hier(&path, files, "")?;
-            // Hidden files
-            file_flat_hier(&path, hidden, ".")?;
+    #[test]
+    fn terminal_dirs_dedupes_containing_folders() -> io::Result<()> {
+        let fs = test_utils::simple_fs(NUM_FILES, 0, NUM_DIRS, 0, NAME_LEN)?;
+        let path = fs.path();
+        let dirs: Vec<_> = test_utils::filter_dirs(path)?.collect();
+        let files: Vec<_> = test_utils::filter_files(&dirs[0])?.collect();
+        assert!(files.len() > 1);
 
-            for entry in path.read_dir()? {
-                let entry = entry?;
-                if entry.file_type()?.is_file() {
-                    trace!("Created file: {}", entry.path().display());
-                }
-            }
+        // Files in one folder open a single terminal in that folder
+        let mut selected = files.clone();
+        selected.push(dirs[0].clone());
+        assert_eq!(terminal_dirs(selected, path), vec![dirs[0].clone()]);
+
+        assert_eq!(terminal_dirs(dirs.clone(), path), dirs);
+        assert_eq!(terminal_dirs(Vec::new(), path), vec![path.to_path_buf()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn space_opens_quick_preview() {
+        use cosmic::{
+            iced::keyboard::{key::Named, Key},
+            widget::menu::key_bind::Modifier,
+        };
+
+        let key_binds = key_binds(&tab1::Mode::App);
+        let space = |modifiers| KeyBind {
+            modifiers,
+            key: Key::Named(Named::Space),
+        };
+        assert_eq!(key_binds.get(&space(vec![])), Some(&Action::QuickPreview));
+        assert_eq!(
+            key_binds.get(&space(vec![Modifier::Shift])),
+            Some(&Action::Gallery)
+        );
+    }
+
+    #[test]
+    fn tab_focuses_other_panel_outside_of_terminal() {
+        use cosmic::iced::keyboard::{key::Named, Key};
+
+        let tab = KeyBind {
+            modifiers: vec![],
+            key: Key::Named(Named::Tab),
+        };
+        assert_eq!(
+            key_binds(&tab1::Mode::App).get(&tab),
+            Some(&Action::FocusOtherPanel)
+        );
+        // Tab completes in the shell of the terminal
+        assert_eq!(key_binds_terminal().get(&tab), None);
+    }
+
+    #[test]
+    fn terminal_paste_reads_clipboard_or_primary() {
+        use cosmic::{iced::keyboard::Key, widget::menu::key_bind::Modifier};
+
+        let source = |action: Action| terminal_paste_source(&action.message(None));
+        // Pastes what was copied elsewhere, not the selection
+        assert_eq!(source(Action::PasteTerminal), Some(PasteSource::Clipboard));
+        assert_eq!(source(Action::PastePrimary), Some(PasteSource::Primary));
+        assert_eq!(
+            source(Action::PastePrimaryTerminal),
+            Some(PasteSource::Primary)
+        );
+        let (_state, pane) = pane_grid::State::new(());
+        assert_eq!(
+            terminal_paste_source(&Message::TermMiddleClick(pane, None)),
+            Some(PasteSource::Primary)
+        );
+        assert_eq!(source(Action::Paste), None);
+
+        let key_binds = key_binds(&tab1::Mode::App);
+        let paste = KeyBind {
+            modifiers: vec![Modifier::Ctrl, Modifier::Shift],
+            key: Key::Character("V".into()),
+        };
+        assert_eq!(key_binds.get(&paste), Some(&Action::PasteTerminal));
+    }
+
+    #[test]
+    fn command_palette_matches_fuzzily_with_recent_first() {
+        assert_eq!(fuzzy_score("", "Copy"), Some(0));
+        assert_eq!(fuzzy_score("cp", "Copy"), Some(1));
+        assert_eq!(fuzzy_score("COPY", "copy to..."), Some(0));
+        assert_eq!(fuzzy_score("yc", "Copy"), None);
+
+        let actions = vec![
+            (Action::Copy, "Copy".to_string()),
+            (Action::CopyTo, "Copy to...".to_string()),
+            (Action::Cut, "Cut".to_string()),
+            (Action::Paste, "Paste".to_string()),
+        ];
+        let matched = |recent: &[Action], query: &str| -> Vec<Action> {
+            palette_matches(&actions, recent, query)
+                .into_iter()
+                .map(|(action, _)| *action)
+                .collect()
+        };
+        // Without a query every command is listed, recently used ones first
+        assert_eq!(
+            matched(&[Action::Paste, Action::Cut], ""),
+            [Action::Paste, Action::Cut, Action::Copy, Action::CopyTo]
+        );
+        assert_eq!(matched(&[], "ct"), [Action::Cut, Action::CopyTo]);
+        assert_eq!(
+            matched(&[Action::CopyTo], "copy"),
+            [Action::CopyTo, Action::Copy]
+        );
+        assert!(matched(&[], "xyz").is_empty());
+
+        // Every command in the palette can be dispatched and is listed once
+        let actions = palette_actions();
+        for (i, (action, _)) in actions.iter().enumerate() {
+            assert!(!actions[i + 1..].iter().any(|(other, _)| other == action));
         }
+    }
+
+    #[test]
+    fn current_path_elided_in_the_middle() {
+        let path = Path::new("/home/user/projects/backup/very-long-folder-name/file.bin");
+        assert_eq!(elide_path(path, 100), path.display().to_string());
+
+        let elided = elide_path(path, 30);
+        assert_eq!(elided.chars().count(), 30);
+        assert!(elided.starts_with("/home/user"));
+        assert!(elided.ends_with("file.bin"));
+        assert!(elided.contains('…'));
+    }
+
+    #[test]
+    fn separate_archives_named_after_each_item() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let folder = fs.path().join("project.rs");
+        let file = fs.path().join("notes.txt");
+        fs::create_dir(&folder)?;
+        fs::write(&file, "notes")?;
+        let paths = vec![folder.clone(), file.clone()];
+
+        assert_eq!(
+            separate_archives(&paths, fs.path(), ArchiveType::Zip, false),
+            [(folder.clone(), fs.path().join("project.rs.zip"))]
+        );
+        assert_eq!(
+            separate_archives(&paths, fs.path(), ArchiveType::Tgz, true),
+            [
+                (folder, fs.path().join("project.rs.tgz")),
+                (file, fs.path().join("notes.tgz")),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_window_args_keep_locations_and_layout() {
+        let flags = Flags {
+            config_handler: None,
+            config: Config::default(),
+            mode: Mode::App,
+            locations1: vec![
+                Location1::Path(PathBuf::from("/home")),
+                Location1::Trash,
+                Location1::Recents,
+            ],
+            locations2: vec![Location1::Path(PathBuf::from("/tmp"))],
+            reveal_opt: None,
+            layout_overrides: LayoutOverrides {
+                show_button_row: Some(false),
+                show_embedded_terminal: Some(true),
+                show_second_panel: None,
+            },
+        };
+        assert_eq!(
+            flags.args(),
+            [
+                "--left",
+                "/home",
+                "--trash",
+                "--right",
+                "/tmp",
+                "--no-button-row",
+                "--terminal"
+            ]
+            .map(OsString::from)
+        );
+    }
+
+    #[test]
+    fn terminal_command_held_in_its_folder() {
+        let args = ["grep", "-r", "TODO", "my notes"].map(OsString::from);
+        let (options, description) =
+            terminal_command_options(&args, PathBuf::from("/tmp")).unwrap();
+        assert_eq!(description, "grep -r TODO 'my notes'");
+        assert_eq!(options.working_directory, Some(PathBuf::from("/tmp")));
+        assert!(options.hold);
+        assert!(options.shell.is_some());
+
+        assert!(terminal_command_options(&[], PathBuf::from("/tmp")).is_none());
+    }
+
+    #[test]
+    fn pkexec_keeps_the_session_display() {
+        let program = ["/usr/bin/gedit", "/etc/fstab"].map(OsString::from);
+        let vars = [
+            ("WAYLAND_DISPLAY", OsString::from("wayland-1")),
+            ("XDG_RUNTIME_DIR", OsString::from("/run/user/1000")),
+        ];
+        assert_eq!(
+            pkexec_args(&program, &vars),
+            [
+                "env",
+                "WAYLAND_DISPLAY=wayland-1",
+                "XDG_RUNTIME_DIR=/run/user/1000",
+                "/usr/bin/gedit",
+                "/etc/fstab",
+            ]
+            .map(OsString::from)
+        );
+        assert_eq!(pkexec_args(&program, &[])[1..], program);
+    }
+
+    #[test]
+    fn select_criteria_combine_size_time_and_pattern() {
+        assert_eq!(parse_size("500"), Some(500));
+        assert_eq!(parse_size("1.5 MB"), Some(1_500_000));
+        assert_eq!(parse_size("2KiB"), Some(2048));
+        assert_eq!(parse_size("10 parsecs"), None);
+
+        let now = SystemTime::now();
+        let day = time::Duration::from_secs(24 * 60 * 60);
+        let input = SelectCriteriaInput {
+            pattern: "*.LOG".to_string(),
+            min_size: "1 MB".to_string(),
+            modified_after: "7".to_string(),
+            ..Default::default()
+        };
+        let criteria = input.parse(now).unwrap();
+        assert!(criteria.matches("old.log", Some(2_000_000), Some(now - day)));
+        assert!(!criteria.matches("old.log", Some(2_000_000), Some(now - 8 * day)));
+        assert!(!criteria.matches("old.txt", Some(2_000_000), Some(now - day)));
+        assert!(!criteria.matches("small.log", Some(10), Some(now - day)));
+        // Folders without a calculated size never match size criteria
+        assert!(!criteria.matches("dir.log", None, Some(now - day)));
+
+        assert!(SelectCriteriaInput::default().parse(now).is_none());
+        let invalid = SelectCriteriaInput {
+            max_size: "big".to_string(),
+            ..input
+        };
+        assert!(invalid.parse(now).is_none());
+    }
+
+    #[test]
+    fn tab_titles_tell_apart_folders_with_the_same_name() {
+        let tabs = [
+            ("src".to_string(), Some(PathBuf::from("/home/user/a/src"))),
+            ("src".to_string(), Some(PathBuf::from("/home/user/b/src"))),
+            ("src".to_string(), Some(PathBuf::from("/home/user/b/src"))),
+            ("docs".to_string(), Some(PathBuf::from("/home/user/a/docs"))),
+            ("Trash".to_string(), None),
+        ];
+        assert_eq!(
+            tab_titles(&tabs, TabTitles::FolderName),
+            ["src", "src", "src", "docs", "Trash"]
+        );
+        assert_eq!(
+            tab_titles(&tabs, TabTitles::Distinct),
+            ["a/src", "b/src", "b/src", "docs", "Trash"]
+        );
+        assert_eq!(
+            tab_titles(&tabs, TabTitles::FullPath),
+            [
+                "/home/user/a/src",
+                "/home/user/b/src",
+                "/home/user/b/src",
+                "/home/user/a/docs",
+                "Trash"
+            ]
+        );
 
-        Ok(root)
+        // Folders only differing further up get as many parent folders as needed
+        let tabs = [
+            ("src".to_string(), Some(PathBuf::from("/a/project/src"))),
+            ("src".to_string(), Some(PathBuf::from("/b/project/src"))),
+        ];
+        assert_eq!(
+            tab_titles(&tabs, TabTitles::Distinct),
+            ["a/project/src", "b/project/src"]
+        );
     }
 
-    /// Empty file hierarchy
-    pub fn empty_fs() -> io::Result<TempDir> {
-        tempdir()
+    #[test]
+    fn staged_archive_adds_new_paths_only() {
+        let mut staged = StagedArchive {
+            to: PathBuf::from("/home/user/Archive.zip"),
+            archive_type: ArchiveType::Zip,
+            password: None,
+            paths: Vec::new(),
+        };
+        staged.add(vec![PathBuf::from("/home/user/a"), PathBuf::from("/tmp/b")]);
+        staged.add(vec![
+            PathBuf::from("/tmp/b"),
+            PathBuf::from("/home/user/Archive.zip"),
+            PathBuf::from("/tmp/c"),
+        ]);
+        assert_eq!(
+            staged.paths,
+            [
+                PathBuf::from("/home/user/a"),
+                PathBuf::from("/tmp/b"),
+                PathBuf::from("/tmp/c")
+            ]
+        );
     }
 
-    /// Sort files.
-    ///
-    /// Directories are placed before files.
-    /// Files are lexically sorted.
-    /// This is more or less copied right from the [Tab] code
-    pub fn sort_files(a: &Path, b: &Path) -> Ordering {
-        match (a.is_dir(), b.is_dir()) {
-            (true, false) => Ordering::Less,
-            (false, true) => Ordering::Greater,
-            _ => LANGUAGE_SORTER.compare(
-                a.file_name()
-                    .expect("temp entries should have names")
-                    .to_str()
-                    .expect("temp entries should be valid UTF-8"),
-                b.file_name()
-                    .expect("temp entries should have names")
-                    .to_str()
-                    .expect("temp entries should be valid UTF-8"),
-            ),
+    #[test]
+    fn panel_options_leave_the_other_panel_unchanged() {
+        let options = [
+            PanelOption::FoldersFirst(false),
+            PanelOption::MiddleClickOpenFiles(true),
+            PanelOption::RespectGitignore(true),
+            PanelOption::ShowHidden(true),
+            PanelOption::SlideshowInterval(10),
+            PanelOption::SortSecondary(HeadingOptions1::Modified),
+        ];
+        let config = Config::default();
+        for option in options {
+            let left = option.apply(&config, PaneType::LeftPane);
+            assert_ne!(left.tab_left, config.tab_left, "{:?}", option);
+            assert_eq!(left.tab_right, config.tab_right, "{:?}", option);
+
+            let right = option.apply(&config, PaneType::RightPane);
+            assert_eq!(right.tab_left, config.tab_left, "{:?}", option);
+            assert_ne!(right.tab_right, config.tab_right, "{:?}", option);
+
+            // Each panel keeps its own option once both are set
+            let both = option.apply(&left, PaneType::RightPane);
+            assert_eq!(both.tab_left, left.tab_left, "{:?}", option);
+            assert_eq!(both.tab_right, right.tab_right, "{:?}", option);
         }
     }
 
-    /// Read directory entries from `path` and sort.
-    pub fn read_dir_sorted(path: &Path) -> io::Result<Vec<PathBuf>> {
-        let mut entries: Vec<_> = path
-            .read_dir()?
-            .map(|maybe_entry| maybe_entry.map(|entry| entry.path()))
-            .collect::<io::Result<_>>()?;
-        entries.sort_by(|a, b| sort_files(a, b));
-
-        Ok(entries)
+    /// App without a config handler, with one tab of `path` in each panel
+    fn test_app(path: &Path) -> App {
+        let flags = Flags {
+            config_handler: None,
+            config: Config::default(),
+            mode: Mode::App,
+            locations1: vec![Location1::Path(path.to_path_buf())],
+            locations2: vec![Location1::Path(path.to_path_buf())],
+            reveal_opt: None,
+            layout_overrides: LayoutOverrides::default(),
+        };
+        App::init(Core::default(), flags).0
     }
 
-    /// Filter `path` for directories
-    pub fn filter_dirs(path: &Path) -> io::Result<impl Iterator<Item = PathBuf>> {
-        Ok(path.read_dir()?.filter_map(|entry| {
-            entry.ok().and_then(|entry| {
-                let path = entry.path();
-                if path.is_dir() {
-                    Some(path)
-                } else {
-                    None
-                }
-            })
-        }))
-    }
+    #[test]
+    fn closing_a_tab_activates_its_neighbour_in_either_panel() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let mut app = test_app(fs.path());
+        let trash1 = app
+            .tab_model1
+            .insert()
+            .data(Tab1::new(Location1::Trash, TabConfig1::default()))
+            .activate()
+            .id();
+        let trash2 = app
+            .tab_model2
+            .insert()
+            .data(Tab2::new(Location2::Trash, TabConfig2::default()))
+            .activate()
+            .id();
 
-    // Filter `path` for files
-    pub fn filter_files(path: &Path) -> io::Result<impl Iterator<Item = PathBuf>> {
-        Ok(path.read_dir()?.filter_map(|entry| {
-            entry.ok().and_then(|entry| {
-                let path = entry.path();
-                path.is_file().then_some(path)
-            })
-        }))
+        let _ = app.update(Message::TabCloseLeft(None));
+        assert!(app.tab_model1.data::<Tab1>(trash1).is_none());
+        assert_eq!(
+            active_tab_path::<LeftPanel>(&app.tab_model1).as_deref(),
+            Some(fs.path())
+        );
+        assert_eq!(app.tab_model2.active(), trash2);
+
+        let _ = app.update(Message::TabCloseRight(Some(trash2)));
+        assert!(app.tab_model2.data::<Tab2>(trash2).is_none());
+        assert_eq!(
+            active_tab_path::<RightPanel>(&app.tab_model2).as_deref(),
+            Some(fs.path())
+        );
+        Ok(())
     }
 
-    /// Boiler plate for Tab tests
-    pub fn tab_click_new(
-        files: usize,
-        hidden: usize,
-        dirs: usize,
-        nested: usize,
-        name_len: usize,
-    ) -> io::Result<(TempDir, Tab1)> {
-        let fs = simple_fs(files, hidden, dirs, nested, name_len)?;
-        let path = fs.path();
+    /// App whose left panel shows `left` and right panel `right`, with the first item of each
+    /// selected. Returns the selected paths.
+    fn test_app_with_selection(root: &Path, left: &Path, right: &Path) -> (App, PathBuf, PathBuf) {
+        let mut app = test_app(root);
+        let _ = app.open_tab_entity_left(Location1::Path(left.to_path_buf()), true, None);
+        let _ = app.open_tab_entity_right(Location2::Path(right.to_path_buf()), true, None);
 
-        // New tab with items
-        let location = Location1::Path(path.to_owned());
-        let (parent_item_opt, items) = location.scan(IconSizes::default());
-        let mut tab = Tab1::new(location, TabConfig1::default());
+        let entity = app.tab_model1.active();
+        let tab = app.tab_model1.data_mut::<Tab1>(entity).unwrap();
+        let (parent_item_opt, items) = tab.location.scan(IconSizes::default());
         tab.parent_item_opt = parent_item_opt;
         tab.set_items(items);
+        let item = &mut tab.items_opt_mut().unwrap()[0];
+        item.selected = true;
+        let selected_left = item.path_opt().unwrap().clone();
 
-        // Ensure correct number of directories as a sanity check
-        let items = tab.items_opt().expect("tab should be populated with Items");
-        assert_eq!(NUM_DIRS, items.len());
+        let entity = app.tab_model2.active();
+        let tab = app.tab_model2.data_mut::<Tab2>(entity).unwrap();
+        let (parent_item_opt, items) = tab.location.scan(IconSizes::default());
+        tab.parent_item_opt = parent_item_opt;
+        tab.set_items(items);
+        let item = &mut tab.items_opt_mut().unwrap()[0];
+        item.selected = true;
+        let selected_right = item.path_opt().unwrap().clone();
 
-        Ok((fs, tab))
+        (app, selected_left, selected_right)
     }
 
-    pub fn _tab_click_new2(
-        files: usize,
-        hidden: usize,
-        dirs: usize,
-        nested: usize,
-        name_len: usize,
-    ) -> io::Result<(TempDir, Tab2)> {
-        let fs = simple_fs(files, hidden, dirs, nested, name_len)?;
-        let path = fs.path();
+    #[test]
+    fn copy_and_move_go_from_the_active_panel_to_the_other() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let left = fs.path().join("left");
+        let right = fs.path().join("right");
+        fs::create_dir(&left)?;
+        fs::create_dir(&right)?;
+        fs::write(left.join("crab"), "ferris")?;
+        fs::write(right.join("shell"), "corro")?;
+        let (mut app, selected_left, selected_right) =
+            test_app_with_selection(fs.path(), &left, &right);
+
+        let cases = [
+            (PaneType::LeftPane, Message::F5Copy),
+            (PaneType::LeftPane, Message::F6Move),
+            (PaneType::RightPane, Message::F5Copy),
+            (PaneType::RightPane, Message::F6Move),
+        ];
+        for (panel, message) in cases {
+            app.active_panel = panel;
+            app.pending_operations.clear();
+            let _ = app.update(message.clone());
+            let (paths, to) = if panel == PaneType::LeftPane {
+                (vec![selected_left.clone()], right.clone())
+            } else {
+                (vec![selected_right.clone()], left.clone())
+            };
+            let expected = if matches!(message, Message::F5Copy) {
+                Operation::Copy { paths, to }
+            } else {
+                Operation::Move { paths, to }
+            };
+            let operations: Vec<_> = app
+                .pending_operations
+                .values()
+                .map(|(operation, _)| operation.clone())
+                .collect();
+            assert_eq!(operations, [expected], "{:?} {:?}", panel, message);
+        }
+        Ok(())
+    }
 
-        // New tab with items
-        let location = Location2::Path(path.to_owned());
-        let (parent_item_opt, items) = location.scan(IconSizes::default());
-        let mut tab = Tab2::new(location, TabConfig2::default());
-        tab.parent_item_opt = parent_item_opt;
-        tab.set_items(items);
+    #[test]
+    fn rescan_only_reloads_the_active_panel() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let left = fs.path().join("left");
+        let right = fs.path().join("right");
+        fs::create_dir(&left)?;
+        fs::create_dir(&right)?;
+        fs::write(left.join("crab"), "ferris")?;
+        fs::write(right.join("shell"), "corro")?;
+
+        for panel in [PaneType::LeftPane, PaneType::RightPane] {
+            let (mut app, _, _) = test_app_with_selection(fs.path(), &left, &right);
+            app.active_panel = panel;
+            // The rescan starts out with no items until the scan of the folder arrives
+            let _ = app.update(Message::TabRescan);
+            let left_items = app
+                .tab_model1
+                .data::<Tab1>(app.tab_model1.active())
+                .and_then(|tab| tab.items_opt().map(Vec::len));
+            let right_items = app
+                .tab_model2
+                .data::<Tab2>(app.tab_model2.active())
+                .and_then(|tab| tab.items_opt().map(Vec::len));
+            if panel == PaneType::LeftPane {
+                assert_eq!((left_items, right_items), (Some(0), Some(1)));
+            } else {
+                assert_eq!((left_items, right_items), (Some(1), Some(0)));
+            }
+        }
+        Ok(())
+    }
 
-        // Ensure correct number of directories as a sanity check
-        let items = tab.items_opt().expect("tab should be populated with Items");
-        assert_eq!(NUM_DIRS, items.len());
+    #[test]
+    fn panel_toggles_leave_the_other_panel_unchanged() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let mut app = test_app(fs.path());
+
+        for panel in [PaneType::LeftPane, PaneType::RightPane] {
+            let toggles = [
+                Message::ToggleFoldersFirst,
+                Message::ToggleShowHidden(None),
+                Message::ToggleRespectGitignore,
+                Message::MiddleClickOpenFiles(true),
+                Message::SlideshowInterval(10),
+            ];
+            for toggle in toggles {
+                app.active_panel = panel;
+                let (tab_left, tab_right) = (app.config.tab_left, app.config.tab_right);
+                let _ = app.update(toggle.clone());
+                if panel == PaneType::LeftPane {
+                    assert_ne!(app.config.tab_left, tab_left, "{:?}", toggle);
+                    assert_eq!(app.config.tab_right, tab_right, "{:?}", toggle);
+                } else {
+                    assert_eq!(app.config.tab_left, tab_left, "{:?}", toggle);
+                    assert_ne!(app.config.tab_right, tab_right, "{:?}", toggle);
+                }
+            }
+        }
+        Ok(())
+    }
 
-        Ok((fs, tab))
+    #[test]
+    fn retrying_a_failure_drops_its_dialog_and_runs_once() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let mut app = test_app(fs.path());
+        let op = Operation::NewFolder {
+            path: fs.path().join("new"),
+        };
+        app.failed_operations
+            .insert(0, (op, Controller::default(), "failed".to_string()));
+        app.dialog_pages.push_back(DialogPage::FailedOperation(0));
+
+        let _ = app.update(Message::RetryAllFailed);
+        assert!(app.dialog_pages.is_empty());
+        assert_eq!(app.retry_operation_ids.len(), 1);
+        let pending = app.pending_operations.len();
+
+        // The failure is not retried again while its retry is pending
+        let _ = app.update(Message::RetryAllFailed);
+        assert_eq!(app.retry_operation_ids.len(), 1);
+        assert_eq!(app.pending_operations.len(), pending);
+        Ok(())
     }
 
-    /// Equality for [Path] and [Item].
-    pub fn eq_path_item(path: &Path, item: &Item) -> bool {
-        let name = path
-            .file_name()
-            .expect("temp entries should have names")
-            .to_str()
-            .expect("temp entries should be valid UTF-8");
-        let is_dir = path.is_dir();
+    #[test]
+    fn button_row_labels_show_shortcuts() {
+        let key_binds = key_binds(&tab1::Mode::App);
+        let buttons = button_row(&[], &key_binds);
+        assert_eq!(buttons.len(), ButtonRowAction::defaults().len());
+        assert_eq!(buttons[0].0, Action::F2Rename);
+        assert!(buttons[0].1.starts_with("F2 "), "{}", buttons[0].1);
+
+        // Buttons keep the configured order, those without a shortcut only show their name
+        let buttons = button_row(
+            &[ButtonRowAction::Quit, ButtonRowAction::Compress],
+            &key_binds,
+        );
+        assert_eq!(
+            buttons
+                .iter()
+                .map(|(action, _)| *action)
+                .collect::<Vec<_>>(),
+            [Action::F10Quit, Action::Compress]
+        );
+        assert_eq!(buttons[1].1, fl!("compress"));
+    }
 
-        // NOTE: I don't want to change `tab::hidden_attribute` to `pub(crate)` for
-        // tests without asking
-        #[cfg(not(target_os = "windows"))]
-        let is_hidden = name.starts_with('.');
+    #[test]
+    fn recents_bookmarks_removed_with_their_line() {
+        let xbel = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<xbel version=\"1.0\">
+  <bookmark href=\"file:///tmp/a%20b.txt\" added=\"2024-01-01T00:00:00Z\">
+    <info>
+      <metadata owner=\"http://freedesktop.org\">
+        <bookmark:applications>
+          <bookmark:application name=\"gedit\" exec=\"&apos;gedit %u&apos;\"/>
+        </bookmark:applications>
+      </metadata>
+    </info>
+  </bookmark>
+  <bookmark href=\"file:///home/user/notes.txt\"/>
+  <bookmark href=\"https://example.com/?a=1&amp;b=2\"/>
+</xbel>
+";
+        let (retained, removed) = recents::retain_bookmarks(xbel, |path| !path.starts_with("/tmp"));
+        assert_eq!(removed, 1);
+        assert_eq!(
+            retained,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<xbel version=\"1.0\">
+  <bookmark href=\"file:///home/user/notes.txt\"/>
+  <bookmark href=\"https://example.com/?a=1&amp;b=2\"/>
+</xbel>
+"
+        );
 
-        #[cfg(target_os = "windows")]
-        let is_hidden = {
-            use std::os::windows::fs::MetadataExt;
-            const FILE_ATTRIBUTE_HIDDEN: u32 = 2;
-            let metadata = path.metadata().expect("fetching file metadata");
-            metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN == FILE_ATTRIBUTE_HIDDEN
-        };
+        // Other than local files are kept when clearing
+        let (retained, removed) = recents::retain_bookmarks(xbel, |_| false);
+        assert_eq!(removed, 2);
+        assert!(retained.contains("https://example.com/"));
+        assert!(recents::is_excluded(
+            Path::new("/tmp/a b.txt"),
+            &[PathBuf::from("/tmp")]
+        ));
+        assert!(!recents::is_excluded(
+            Path::new("/tmpfiles/a.txt"),
+            &[PathBuf::from("/tmp")]
+        ));
+    }
 
-        name == item.name
-            && is_dir == item.metadata.is_dir()
-            && path == item.path_opt().expect("item should have path")
-            && is_hidden == item.hidden
+    #[test]
+    fn read_only_keeps_actions_that_do_not_change_files() {
+        let key_binds = key_binds(&tab1::Mode::App);
+        let enabled: Vec<Action> = button_row(&[], &key_binds)
+            .into_iter()
+            .map(|(action, _)| action)
+            .filter(|action| !action.changes_files())
+            .collect();
+        assert_eq!(
+            enabled,
+            [
+                Action::F3View,
+                Action::F4Edit,
+                Action::F9Terminal,
+                Action::F10Quit
+            ]
+        );
+        // Copying to the clipboard is allowed, pasting is not
+        assert!(!Action::Copy.changes_files());
+        assert!(Action::Paste.changes_files());
     }
 
-    pub fn _eq_path_item2(path: &Path, item: &crate::tab2::Item) -> bool {
-        let name = path
-            .file_name()
-            .expect("temp entries should have names")
-            .to_str()
-            .expect("temp entries should be valid UTF-8");
-        let is_dir = path.is_dir();
+    #[tokio::test]
+    async fn dropped_copy_onto_existing_file_asks_to_replace() -> io::Result<()> {
+        use futures::StreamExt;
+
+        let fs = test_utils::empty_fs()?;
+        let from = fs.path().join("from");
+        let to = fs.path().join("to");
+        fs::create_dir_all(&from)?;
+        fs::create_dir_all(&to)?;
+        fs::write(from.join("file"), "new")?;
+        fs::write(to.join("file"), "old")?;
+
+        // Drops become the same operation as pasting
+        let kind = DropAction::new(DndAction::Copy, Modifiers::empty())
+            .paste_kind()
+            .unwrap();
+        let op = paste_operation(
+            to.clone(),
+            ClipboardPaste {
+                kind,
+                paths: vec![from.join("file")],
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            op,
+            Operation::Copy {
+                paths: vec![from.join("file")],
+                to: to.clone(),
+            }
+        );
 
-        // NOTE: I don't want to change `tab::hidden_attribute` to `pub(crate)` for
-        // tests without asking
-        #[cfg(not(target_os = "windows"))]
-        let is_hidden = name.starts_with('.');
+        let (tx, mut rx) = futures::channel::mpsc::channel(8);
+        let handle = tokio::spawn(async move {
+            op.perform(
+                &Arc::new(tokio::sync::Mutex::new(tx)),
+                Controller::default(),
+            )
+            .await
+        });
+        let mut replace_asked = false;
+        while let Some(message) = rx.next().await {
+            if let Message::DialogPush(DialogPage::Replace1 { tx, .. }) = message {
+                replace_asked = true;
+                tx.send(ReplaceResult::KeepBoth).await.unwrap();
+            }
+        }
+        handle.await.unwrap().expect("copy should have succeeded");
+        assert!(replace_asked);
+        assert_eq!(fs::read_to_string(to.join("file"))?, "old");
+
+        // Nothing is pasted into the folder it comes from
+        assert!(paste_operation(
+            to.clone(),
+            ClipboardPaste {
+                kind: ClipboardKind::Cut,
+                paths: vec![to.clone()],
+            },
+        )
+        .is_none());
+        Ok(())
+    }
 
-        #[cfg(target_os = "windows")]
-        let is_hidden = {
-            use std::os::windows::fs::MetadataExt;
-            const FILE_ATTRIBUTE_HIDDEN: u32 = 2;
-            let metadata = path.metadata().expect("fetching file metadata");
-            metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN == FILE_ATTRIBUTE_HIDDEN
-        };
+    #[test]
+    fn extension_renames_replace_only_the_last_extension() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let path = fs.path();
+        for name in [
+            "photo.jpeg",
+            "archive.tar.gz",
+            "README",
+            ".hidden",
+            "b.jpeg",
+            "b.jpg",
+        ] {
+            fs::write(path.join(name), "")?;
+        }
+        let paths: Vec<PathBuf> = ["photo.jpeg", "archive.tar.gz", "README", ".hidden"]
+            .iter()
+            .map(|name| path.join(name))
+            .collect();
+        assert_eq!(
+            extension_renames(&paths, ".jpg").unwrap(),
+            [
+                (path.join("photo.jpeg"), path.join("photo.jpg")),
+                (path.join("archive.tar.gz"), path.join("archive.tar.jpg")),
+                (path.join("README"), path.join("README.jpg")),
+                (path.join(".hidden"), path.join(".hidden.jpg")),
+            ]
+        );
 
-        name == item.name
-            && is_dir == item.metadata.is_dir()
-            && path == item.path_opt().expect("item should have path")
-            && is_hidden == item.hidden
+        // Existing items and files renamed to the same name are not replaced
+        let paths = vec![path.join("b.jpeg"), path.join("photo.jpeg")];
+        assert_eq!(
+            extension_renames(&paths, "jpg"),
+            Err(vec!["b.jpg".to_string()])
+        );
+        let paths = vec![path.join("photo.jpeg"), path.join("photo.png")];
+        assert_eq!(
+            extension_renames(&paths, "jpg"),
+            Err(vec!["photo.jpg".to_string()])
+        );
+        Ok(())
     }
 
-    /// Asserts `tab`'s location changed to `path`
-    pub fn _assert_eq_tab_path2(tab: &Tab2, path: &Path) {
-        // Paths should be the same
-        let Some(tab_path) = tab.location.path_opt() else {
-            panic!("Expected tab's location to be a path");
+    #[test]
+    fn change_extension_input_updates_the_renames() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let path = fs.path();
+        fs::write(path.join("photo.jpeg"), "")?;
+        let mut app = test_app(path);
+        let paths = vec![path.join("photo.jpeg")];
+        app.dialog_pages.push_back(DialogPage::ChangeExtension {
+            paths: paths.clone(),
+            extension: "jpeg".to_string(),
+            renames: change_extension_renames(&paths, "jpeg"),
+        });
+        let renames = |app: &App| match app.dialog_pages.front() {
+            Some(DialogPage::ChangeExtension { renames, .. }) => renames.clone(),
+            page => panic!("unexpected dialog {:?}", page),
         };
 
+        let _ = app.update(Message::ChangeExtensionInput("jpg".to_string()));
         assert_eq!(
-            path,
-            tab_path,
-            "Tab's path is {} instead of being updated to {}",
-            tab_path.display(),
-            path.display()
+            renames(&app),
+            Ok(vec![(path.join("photo.jpeg"), path.join("photo.jpg"))])
         );
+
+        // Empty extensions would strip the extension and are refused
+        for input in ["", "."] {
+            let _ = app.update(Message::ChangeExtensionInput(input.to_string()));
+            assert_eq!(renames(&app), Err(fl!("extension-empty")), "{:?}", input);
+        }
+        let _ = app.update(Message::DialogComplete);
+        assert!(app.pending_operations.is_empty());
+        assert!(path.join("photo.jpeg").exists());
+        Ok(())
     }
 
-    pub fn assert_eq_tab_path(tab: &Tab1, path: &Path) {
-        // Paths should be the same
-        let Some(tab_path) = tab.location.path_opt() else {
-            panic!("Expected tab's location to be a path");
+    #[test]
+    fn open_with_several_files_uses_one_command_when_supported() {
+        let paths: Vec<OsString> = vec!["a.png".into(), "b.png".into()];
+        let args = |commands: Vec<process::Command>| -> Vec<Vec<OsString>> {
+            commands
+                .iter()
+                .map(|command| command.get_args().map(OsString::from).collect())
+                .collect()
         };
 
+        let commands = mime_app::exec_to_commands("gimp %U", &paths).unwrap();
+        assert_eq!(args(commands), [paths.clone()]);
+
+        let commands = mime_app::exec_to_commands("viewer --new %f", &paths).unwrap();
         assert_eq!(
-            path,
-            tab_path,
-            "Tab's path is {} instead of being updated to {}",
-            tab_path.display(),
-            path.display()
+            args(commands),
+            [
+                vec!["--new".into(), paths[0].clone()],
+                vec!["--new".into(), paths[1].clone()],
+            ]
         );
     }
 
-    /// Assert that tab's items are equal to a path's entries.
-    pub fn _assert_eq_tab_path_contents(tab: &Tab1, path: &Path) {
-        let Some(tab_path) = tab.location.path_opt() else {
-            panic!("Expected tab's location to be a path");
-        };
+    #[test]
+    fn desktop_grid_origin_flips_each_page() {
+        // Two pages of three rows and four columns
+        let position = |origin: DesktopGridOrigin, pos| origin.position(pos, 3, 4);
+        assert_eq!(position(DesktopGridOrigin::TopLeft, (4, 1)), (4, 1));
+        assert_eq!(position(DesktopGridOrigin::TopRight, (0, 0)), (0, 3));
+        assert_eq!(position(DesktopGridOrigin::BottomLeft, (0, 0)), (2, 0));
+        assert_eq!(position(DesktopGridOrigin::BottomLeft, (3, 0)), (5, 0));
+        assert_eq!(position(DesktopGridOrigin::BottomRight, (4, 1)), (4, 2));
+    }
 
-        // Tab items are sorted so paths from read_dir must be too
-        let entries = read_dir_sorted(path).expect("should be able to read paths from temp dir");
+    #[test]
+    fn script_files_have_a_shebang_or_are_executable_text() -> io::Result<()> {
+        let fs = test_utils::empty_fs()?;
+        let path = fs.path();
+        let text: mime_guess::Mime = "text/plain".parse().unwrap();
 
-        // Check lengths.
-        // `items_opt` is optional and the directory at `path` may have zero entries
-        // Therefore, this doesn't panic if `items_opt` is None
-        let items_len = tab.items_opt().map(|items| items.len()).unwrap_or_default();
-        assert_eq!(entries.len(), items_len);
+        let script = path.join("script");
+        fs::write(&script, "#!/bin/sh\necho hi\n")?;
+        assert!(script_file(&script, &text));
 
-        let empty = Vec::new();
-        assert!(
-            entries
-                .into_iter()
-                .zip(tab.items_opt().unwrap_or(&empty))
-                .all(|(a, b)| eq_path_item(&a, b)),
-            "Path ({}) and Tab path ({}) don't have equal contents",
-            path.display(),
-            tab_path.display()
-        );
+        let notes = path.join("notes.txt");
+        fs::write(&notes, "echo hi\n")?;
+        assert!(!script_file(&notes, &text));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&notes, fs::Permissions::from_mode(0o755))?;
+            assert!(script_file(&notes, &text));
+        }
+
+        assert!(!script_file(path, &text));
+        Ok(())
     }
 }
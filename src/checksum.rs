@@ -0,0 +1,227 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::{
+    fmt,
+    fs::{self, File},
+    io::Read,
+    path::Path,
+};
+
+use crate::{item_cache, operation::Controller};
+
+// Progress is reported each time this many bytes have been read
+const PROGRESS_INTERVAL: u64 = 16 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ChecksumKind {
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl ChecksumKind {
+    pub fn all() -> &'static [Self] {
+        &[Self::Md5, Self::Sha1, Self::Sha256]
+    }
+}
+
+impl fmt::Display for ChecksumKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Md5 => f.write_str("MD5"),
+            Self::Sha1 => f.write_str("SHA-1"),
+            Self::Sha256 => f.write_str("SHA-256"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ChecksumState {
+    /// Fraction of the file that has been read
+    Calculating(f32, Controller),
+    Done(String),
+    Error(String),
+}
+
+/// Checksum requested in the preview of an item
+#[derive(Clone, Debug)]
+pub struct ItemChecksum {
+    pub kind: ChecksumKind,
+    pub state: ChecksumState,
+    /// Checksum pasted by the user to compare against
+    pub verify: String,
+}
+
+impl ItemChecksum {
+    /// Start calculating a checksum, unless it is cached for the current modification time
+    pub fn new(path: &Path, kind: ChecksumKind) -> Self {
+        let state = match cached(path, kind) {
+            Some(checksum) => ChecksumState::Done(checksum),
+            None => ChecksumState::Calculating(0.0, Controller::default()),
+        };
+        Self {
+            kind,
+            state,
+            verify: String::new(),
+        }
+    }
+
+    /// Whether the pasted checksum matches, if both are available
+    pub fn verified(&self) -> Option<bool> {
+        let ChecksumState::Done(checksum) = &self.state else {
+            return None;
+        };
+        let verify = self.verify.trim();
+        if verify.is_empty() {
+            return None;
+        }
+        Some(verify.eq_ignore_ascii_case(checksum))
+    }
+}
+
+fn cached(path: &Path, kind: ChecksumKind) -> Option<String> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    item_cache::checksum(path, kind, modified)
+}
+
+fn digest<D: Digest, F: FnMut(f32)>(
+    path: &Path,
+    controller: &Controller,
+    mut progress: F,
+) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|err| err.to_string())?;
+    let len = file.metadata().map_err(|err| err.to_string())?.len();
+    let mut hasher = D::new();
+    let mut buf = vec![0; 64 * 1024];
+    let mut read = 0;
+    let mut next_progress = PROGRESS_INTERVAL;
+    loop {
+        controller.check()?;
+        let count = file.read(&mut buf).map_err(|err| err.to_string())?;
+        if count == 0 {
+            break;
+        }
+        hasher.update(&buf[..count]);
+        read += count as u64;
+        if read >= next_progress {
+            progress(read as f32 / len.max(1) as f32);
+            next_progress += PROGRESS_INTERVAL;
+        }
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Calculate the checksum of a file as lowercase hex. `progress` receives the fraction of the
+/// file that has been read every few megabytes.
+pub fn calculate<F: FnMut(f32)>(
+    path: &Path,
+    kind: ChecksumKind,
+    controller: &Controller,
+    progress: F,
+) -> Result<String, String> {
+    let modified_opt = fs::metadata(path).and_then(|m| m.modified()).ok();
+    let checksum = match kind {
+        ChecksumKind::Md5 => digest::<Md5, _>(path, controller, progress)?,
+        ChecksumKind::Sha1 => digest::<Sha1, _>(path, controller, progress)?,
+        ChecksumKind::Sha256 => digest::<Sha256, _>(path, controller, progress)?,
+    };
+    if let Some(modified) = modified_opt {
+        item_cache::insert_checksum(path, kind, modified, checksum.clone());
+    }
+    Ok(checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        io,
+        time::{Duration, SystemTime},
+    };
+
+    use super::{calculate, ChecksumKind, ChecksumState, ItemChecksum};
+    use crate::{app::test_utils::empty_fs, operation::Controller};
+
+    #[test]
+    fn checksums_match_known_digests() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let cases = [
+            (ChecksumKind::Md5, "", "d41d8cd98f00b204e9800998ecf8427e"),
+            (ChecksumKind::Md5, "abc", "900150983cd24fb0d6963f7d28e17f72"),
+            (
+                ChecksumKind::Sha1,
+                "",
+                "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+            ),
+            (
+                ChecksumKind::Sha1,
+                "abc",
+                "a9993e364706816aba3e25717850c26c9cd0d89d",
+            ),
+            (
+                ChecksumKind::Sha256,
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                ChecksumKind::Sha256,
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+        ];
+        for (kind, contents, expected) in cases {
+            let path = fs.path().join(format!("{:?}-{}", kind, contents.len()));
+            fs::write(&path, contents)?;
+            assert_eq!(
+                calculate(&path, kind, &Controller::default(), |_| {}).as_deref(),
+                Ok(expected),
+                "{} of {:?}",
+                kind,
+                contents
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn changed_files_are_not_served_from_the_cache() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path().join("crab");
+        fs::write(&path, "abc")?;
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(modified)?;
+
+        let kind = ChecksumKind::Sha256;
+        let abc = calculate(&path, kind, &Controller::default(), |_| {});
+        assert!(matches!(
+            ItemChecksum::new(&path, kind).state,
+            ChecksumState::Done(checksum) if Ok(&checksum) == abc.as_ref()
+        ));
+
+        // Rewriting the file changes its modification time, so it has to be calculated again
+        fs::write(&path, "")?;
+        File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(modified + Duration::from_secs(1))?;
+        assert!(matches!(
+            ItemChecksum::new(&path, kind).state,
+            ChecksumState::Calculating(..)
+        ));
+        assert_eq!(
+            calculate(&path, kind, &Controller::default(), |_| {}).as_deref(),
+            Ok("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+        Ok(())
+    }
+}
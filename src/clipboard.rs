@@ -87,6 +87,14 @@ impl ClipboardCopy {
             x_special_gnome_copied_files: Cow::from(x_special_gnome_copied_files.into_bytes()),
         }
     }
+
+    /// Files dragged out of a tab. Other applications accept a drop as text/uri-list or as
+    /// text/plain, so both contain file URIs.
+    pub fn drag<P: AsRef<Path>>(paths: &[P]) -> Self {
+        let mut copy = Self::new(ClipboardKind::Copy, paths);
+        copy.text_plain = copy.text_uri_list.clone();
+        copy
+    }
 }
 
 impl AsMimeTypes for ClipboardCopy {
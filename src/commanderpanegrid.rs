@@ -24,6 +24,10 @@ pub struct CommanderPaneGrid {
     pub pane_by_type: BTreeMap<PaneType, pane_grid::Pane>,
     pub type_by_entity: BTreeMap<segmented_button::Entity, PaneType>,
     pub type_by_pane: BTreeMap<pane_grid::Pane, PaneType>,
+    /// Pane type created by each split
+    pub type_by_split: BTreeMap<pane_grid::Split, PaneType>,
+    /// Ratios of the splits that were dragged, keyed by the pane type they created
+    pub ratio_by_type: BTreeMap<PaneType, f32>,
     pub mimes: Vec<String>,
     pub first_pane: pane_grid::Pane,
     pub _drag_pane: Option<pane_grid::Pane>,
@@ -53,6 +57,8 @@ impl CommanderPaneGrid {
             pane_by_type: BTreeMap::new(),
             type_by_entity: BTreeMap::new(),
             type_by_pane: BTreeMap::new(),
+            type_by_split: BTreeMap::new(),
+            ratio_by_type: BTreeMap::new(),
             mimes: Vec::new(),
             first_pane: pane,
             _drag_pane: None,
@@ -113,6 +119,7 @@ impl CommanderPaneGrid {
             self.drag_id_by_pane.insert(pane, drag_id);
             self.pane_by_type.insert(pane_type, pane);
             self.type_by_pane.insert(pane, pane_type);
+            self.type_by_split.insert(split, pane_type);
             self.entity_by_pane.insert(pane, entity);
             self.entity_by_type.insert(pane_type, entity);
             self.pane_by_entity.insert(entity, pane);
@@ -1,6 +1,11 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::{any::TypeId, num::NonZeroU16, path::PathBuf};
+use std::{
+    any::TypeId,
+    ffi::OsString,
+    num::NonZeroU16,
+    path::{Path, PathBuf},
+};
 
 use cosmic::{
     cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry},
@@ -8,10 +13,21 @@ use cosmic::{
     theme, Application,
 };
 use hex_color::HexColor;
+use mime_guess::Mime;
 use serde::{Deserialize, Serialize};
 
-use crate::{app::App, tab1::View as View1, tab2::View as View2};
-use crate::localize::LANGUAGE_SORTER;
+use crate::{
+    app::{App, PaneType},
+    err_str,
+    item_cache::ITEM_CACHE_SIZE,
+    localize::LANGUAGE_SORTER,
+    operation::{
+        recursive::{COPY_BUFFER_SIZE, COPY_CONCURRENCY},
+        ReplaceResult, UNIQUE_NAME_BASE, UNIQUE_NAME_TEMPLATE,
+    },
+    tab1::{HeadingOptions as HeadingOptions1, View as View1},
+    tab2::{HeadingOptions as HeadingOptions2, View as View2},
+};
 
 pub const CONFIG_VERSION: u64 = 1;
 pub const COSMIC_THEME_DARK: &str = "COSMIC Dark";
@@ -23,6 +39,8 @@ pub const ICON_SIZE_LIST_CONDENSED: u16 = 48;
 pub const ICON_SIZE_GRID: u16 = 64;
 // TODO: 5 is an arbitrary number. Maybe there's a better icon size max
 pub const ICON_SCALE_MAX: u16 = 5;
+// Number of folders whose view is remembered, the least recently changed are forgotten first
+pub const FOLDER_VIEWS_MAX: usize = 256;
 
 macro_rules! percent {
     ($perc:expr, $pixel:ident) => {
@@ -55,6 +73,285 @@ impl AppTheme {
     }
 }
 
+/// How to handle items that already exist at the destination of a copy or move
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ConflictPolicy {
+    #[default]
+    Ask,
+    Overwrite,
+    Skip,
+    Rename,
+    Newer,
+}
+
+impl ConflictPolicy {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Ask,
+            Self::Overwrite,
+            Self::Skip,
+            Self::Rename,
+            Self::Newer,
+        ]
+    }
+
+    /// Answer for every conflict of an operation, or None to ask for each one
+    pub fn replace_result(&self) -> Option<ReplaceResult> {
+        match self {
+            Self::Ask => None,
+            Self::Overwrite => Some(ReplaceResult::Replace(true)),
+            Self::Skip => Some(ReplaceResult::Skip(true)),
+            Self::Rename => Some(ReplaceResult::Rename(true)),
+            Self::Newer => Some(ReplaceResult::ReplaceIfNewer(true)),
+        }
+    }
+}
+
+/// What to do with items moved to the trash on a filesystem without a trash folder
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TrashUnavailable {
+    #[default]
+    Ask,
+    /// Create a trash folder for the user at the top of the filesystem
+    CreateTrash,
+    /// Delete the items without moving them to the trash
+    Delete,
+}
+
+impl TrashUnavailable {
+    pub fn all() -> &'static [Self] {
+        &[Self::Ask, Self::CreateTrash, Self::Delete]
+    }
+}
+
+/// What opening an archive that can be extracted does
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ArchiveOpenAction {
+    /// Open it with the default application
+    #[default]
+    Open,
+    /// Extract it into a new folder next to it
+    ExtractHere,
+    /// Extract it into a new folder in the folder of the other panel
+    ExtractToOtherPanel,
+}
+
+impl ArchiveOpenAction {
+    pub fn all() -> &'static [Self] {
+        &[Self::Open, Self::ExtractHere, Self::ExtractToOtherPanel]
+    }
+}
+
+/// What the titles of tabs showing folders include
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TabTitles {
+    /// Only the name of the folder
+    #[default]
+    FolderName,
+    /// Parent folders as needed to tell apart folders with the same name
+    Distinct,
+    /// The full path of the folder
+    FullPath,
+}
+
+impl TabTitles {
+    pub fn all() -> &'static [Self] {
+        &[Self::FolderName, Self::Distinct, Self::FullPath]
+    }
+}
+
+/// When to notify about file operations that finish while the window is closed
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum NotifyOperations {
+    /// Show progress, and notify when operations complete or fail
+    #[default]
+    Completion,
+    Failure,
+    Never,
+}
+
+impl NotifyOperations {
+    pub fn all() -> &'static [Self] {
+        &[Self::Completion, Self::Failure, Self::Never]
+    }
+
+    /// Whether an operation that finished, or failed, is notified
+    pub fn notify(&self, failed: bool) -> bool {
+        match self {
+            Self::Completion => true,
+            Self::Failure => failed,
+            Self::Never => false,
+        }
+    }
+}
+
+/// Entries of the sidebar that are always available, unlike favorites and devices
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SidebarBuiltin {
+    Recents,
+    Trash,
+    Networks,
+}
+
+impl SidebarBuiltin {
+    pub fn all() -> &'static [Self] {
+        &[Self::Recents, Self::Trash, Self::Networks]
+    }
+}
+
+/// Action of a button in the row below the panels
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ButtonRowAction {
+    Rename,
+    View,
+    Edit,
+    Copy,
+    Move,
+    NewFolder,
+    Delete,
+    Terminal,
+    Quit,
+    CompareFiles,
+    Compress,
+    CopyTo,
+    MoveTo,
+    NewFile,
+    SelectAll,
+    ShowHidden,
+    SwapPanels,
+}
+
+impl ButtonRowAction {
+    /// The function key layout of classic two panel file managers
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::Rename,
+            Self::View,
+            Self::Edit,
+            Self::Copy,
+            Self::Move,
+            Self::NewFolder,
+            Self::Delete,
+            Self::Terminal,
+            Self::Quit,
+        ]
+    }
+}
+
+/// Command added to the context menu of matching items
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct CustomAction {
+    pub label: String,
+    /// Patterns separated by `;`, matched against the names of items or, if they contain a
+    /// slash, against their mime types, like `*.svg;image/*`. Without patterns all items match.
+    pub pattern: String,
+    /// Command where `%f` is replaced by the first selected path, `%F` by all selected paths,
+    /// `%d` by the folder and `%%` by a percent sign
+    pub command: String,
+    /// Run the command in a terminal to show its output
+    pub terminal: bool,
+}
+
+impl CustomAction {
+    pub fn parse(&self) -> Result<ParsedCustomAction, String> {
+        if self.label.is_empty() {
+            return Err("no label".to_string());
+        }
+        let args = shlex::split(&self.command)
+            .filter(|args| !args.is_empty())
+            .ok_or_else(|| format!("invalid command {:?}", self.command))?;
+        let patterns = self
+            .pattern
+            .split(';')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .map(|glob| (pattern.contains('/'), glob))
+                    .map_err(|err| format!("invalid pattern {:?}: {}", pattern, err))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ParsedCustomAction {
+            label: self.label.clone(),
+            patterns,
+            args,
+            terminal: self.terminal,
+        })
+    }
+}
+
+/// Custom action with its patterns and command parsed
+#[derive(Clone, Debug)]
+pub struct ParsedCustomAction {
+    pub label: String,
+    /// Patterns and whether they match mime types instead of names
+    patterns: Vec<(bool, glob::Pattern)>,
+    args: Vec<String>,
+    pub terminal: bool,
+}
+
+impl ParsedCustomAction {
+    pub fn matches(&self, name: &str, mime: &Mime) -> bool {
+        let options = glob::MatchOptions {
+            case_sensitive: false,
+            ..glob::MatchOptions::new()
+        };
+        self.patterns.is_empty()
+            || self.patterns.iter().any(|(is_mime, pattern)| {
+                if *is_mime {
+                    pattern.matches_with(mime.essence_str(), options)
+                } else {
+                    pattern.matches_with(name, options)
+                }
+            })
+    }
+
+    /// Arguments of the command for the selected paths in a folder
+    pub fn args(&self, paths: &[PathBuf], dir: &Path) -> Vec<OsString> {
+        let mut args = Vec::with_capacity(self.args.len());
+        for arg in self.args.iter() {
+            // All paths as a whole argument are passed as separate arguments
+            if arg == "%F" {
+                args.extend(paths.iter().map(|path| path.as_os_str().to_owned()));
+                continue;
+            }
+            let mut expanded = OsString::new();
+            let mut chars = arg.chars();
+            while let Some(c) = chars.next() {
+                if c != '%' {
+                    expanded.push(c.encode_utf8(&mut [0; 4]));
+                    continue;
+                }
+                match chars.next() {
+                    Some('f') => {
+                        if let Some(path) = paths.first() {
+                            expanded.push(path);
+                        }
+                    }
+                    Some('F') => {
+                        for (i, path) in paths.iter().enumerate() {
+                            if i > 0 {
+                                expanded.push(" ");
+                            }
+                            expanded.push(path);
+                        }
+                    }
+                    Some('d') => expanded.push(dir),
+                    Some('%') => expanded.push("%"),
+                    Some(other) => {
+                        expanded.push("%");
+                        expanded.push(other.encode_utf8(&mut [0; 4]));
+                    }
+                    None => expanded.push("%"),
+                }
+            }
+            args.push(expanded);
+        }
+        args
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Favorite {
     Home,
@@ -86,6 +383,40 @@ impl Favorite {
         Self::Path(path)
     }
 
+    /// Home and the XDG user folders that exist, seeding the sidebar. Their paths come from
+    /// user-dirs.dirs, so they have the localized names of the session.
+    pub fn defaults() -> Vec<Self> {
+        let home_opt = Self::Home.path_opt();
+        let mut favorites = vec![Self::Home];
+        favorites.extend(
+            [
+                Self::Documents,
+                Self::Downloads,
+                Self::Music,
+                Self::Pictures,
+                Self::Videos,
+            ]
+            .into_iter()
+            .filter(|favorite| {
+                // User folders set to the home folder are disabled
+                favorite
+                    .path_opt()
+                    .is_some_and(|path| path.is_dir() && Some(&path) != home_opt.as_ref())
+            }),
+        );
+        favorites
+    }
+
+    /// Move the favorite at `from` to the position of the one at `to`
+    pub fn reorder(favorites: &mut Vec<Self>, from: usize, to: usize) -> bool {
+        if from == to || from >= favorites.len() || to >= favorites.len() {
+            return false;
+        }
+        let favorite = favorites.remove(from);
+        favorites.insert(to, favorite);
+        true
+    }
+
     pub fn path_opt(&self) -> Option<PathBuf> {
         match self {
             Self::Home => dirs::home_dir(),
@@ -281,28 +612,100 @@ pub struct Config {
     pub color_schemes_light: std::collections::BTreeMap<ColorSchemeId, ColorScheme>,
     pub desktop: DesktopConfig,
     pub favorites: Vec<Favorite>,
+    /// Built-in entries that are not shown in the sidebar
+    pub sidebar_hidden: Vec<SidebarBuiltin>,
+    /// List system filesystems, like internal disks and loop devices, with the drives
+    pub sidebar_system_mounts: bool,
+    /// Folders whose files are not listed in Recents
+    pub recents_excluded: Vec<PathBuf>,
     pub show_details: bool,
     pub show_button_row: bool,
+    /// Buttons of the button row in order, the default layout is used if empty
+    pub button_row: Vec<ButtonRowAction>,
     pub show_embedded_terminal: bool,
+    /// Open terminals for folders in the embedded terminal instead of an external one
+    pub open_terminal_embedded: bool,
+    /// Command F9 runs for the selection in the embedded terminal instead of opening a shell,
+    /// with the placeholders of custom actions
+    pub terminal_command: String,
+    /// Return to a shell when the command run in the embedded terminal exits, instead of
+    /// keeping its output
+    pub terminal_close_on_exit: bool,
+    /// Lines of output the embedded terminal keeps for scrolling back
+    pub terminal_scrollback: usize,
+    /// Color schemes of the embedded terminal, by name
+    pub terminal_theme_dark: String,
+    pub terminal_theme_light: String,
     pub show_second_panel: bool,
     pub queue_file_operations: bool,
+    pub conflict_policy: ConflictPolicy,
+    /// Name given to an item so it does not overwrite another one, where `{name}` and `{ext}`
+    /// are the stem and extension of the item and `{n}` is the first free number
+    pub unique_name_template: String,
+    /// First number tried for `{n}` in unique names
+    pub unique_name_base: u32,
+    pub archive_open_action: ArchiveOpenAction,
+    /// Ask whether to run scripts in a terminal or open them with an application
+    pub open_scripts_in_terminal: bool,
+    /// Seconds between rescans of the active tab, 0 to only rescan when the folder changes
+    pub auto_refresh_interval: u16,
+    pub tab_titles: TabTitles,
+    /// Ask before moving items to the trash
+    pub confirm_trash: bool,
+    /// What to do with items on filesystems without a trash folder, like removable drives
+    pub trash_unavailable: TrashUnavailable,
+    /// Ask before deleting items without moving them to the trash
+    pub confirm_permanent_delete: bool,
+    /// Commands added to the context menu of matching items
+    pub custom_actions: Vec<CustomAction>,
+    pub notify_operations: NotifyOperations,
+    /// Play a sound with the notification of completed operations
+    pub notify_sound: bool,
+    pub single_instance: bool,
+    /// Offer opening items as root with pkexec, after confirming
+    pub allow_open_as_root: bool,
+    /// Refuse all file operations, so files can only be browsed, opened and copied to the
+    /// clipboard
+    pub read_only: bool,
+    /// Number of items whose details, thumbnails, folder sizes and checksums are kept for reuse
+    pub item_cache_size: usize,
+    /// Size in bytes of the buffer files are copied through
+    pub copy_buffer_size: usize,
+    /// Number of files copied at the same time by one operation. More helps with high latency
+    /// remote folders, but can slow down spinning disks.
+    pub copy_concurrency: usize,
+    /// Fail copies of files that change while they are copied, instead of warning about them
+    pub copy_strict: bool,
     pub tab_left: TabConfig1,
     pub tab_right: TabConfig2,
     pub paths_left: Vec<String>,
     pub paths_right: Vec<String>,
+    /// Ratios of the dragged pane splits in per mille, keyed by the pane each split created
+    pub pane_ratios: Vec<(PaneType, u16)>,
+    /// Pane that had focus when the window was closed
+    pub pane_focus: PaneType,
+    pub recent_network_drives: Vec<String>,
+    /// Views remembered for folders in the left panel, most recently changed first
+    pub folder_views_left: Vec<(String, FolderView1)>,
+    /// Views remembered for folders in the right panel, most recently changed first
+    pub folder_views_right: Vec<(String, FolderView2)>,
 }
 
 impl Config {
     pub fn load() -> (Option<cosmic_config::Config>, Self) {
         match cosmic_config::Config::new(App::APP_ID, CONFIG_VERSION) {
             Ok(config_handler) => {
-                let config = match Config::get_entry(&config_handler) {
+                let mut config = match Config::get_entry(&config_handler) {
                     Ok(ok) => ok,
                     Err((errs, config)) => {
                         log::info!("errors loading config: {:?}", errs);
                         config
                     }
                 };
+                // Favorites the user has set are kept, an empty sidebar is seeded again
+                if config.favorites.is_empty() {
+                    config.favorites = Favorite::defaults();
+                }
                 (Some(config_handler), config)
             }
             Err(err) => {
@@ -362,6 +765,61 @@ impl Config {
         color_scheme_names
     }
 
+    /// Contents of a settings file holding the whole config, see [`Config::import`]
+    pub fn export(&self) -> Result<String, String> {
+        let file = serde_json::json!({
+            "version": CONFIG_VERSION,
+            "config": self,
+        });
+        serde_json::to_string_pretty(&file).map_err(err_str)
+    }
+
+    /// Apply the settings of an exported file on top of this config. Settings that are unknown
+    /// or invalid are skipped and returned as warnings, so files of other versions still apply
+    /// as much as possible.
+    pub fn import(&self, text: &str) -> Result<(Self, Vec<String>), String> {
+        let file: serde_json::Value = serde_json::from_str(text).map_err(err_str)?;
+        let version = file
+            .get("version")
+            .and_then(|version| version.as_u64())
+            .ok_or_else(|| "settings file has no version".to_string())?;
+        if version > CONFIG_VERSION {
+            return Err(format!(
+                "settings file version {} is newer than supported version {}",
+                version, CONFIG_VERSION
+            ));
+        }
+        let imported = file
+            .get("config")
+            .and_then(|config| config.as_object())
+            .ok_or_else(|| "settings file has no settings".to_string())?;
+
+        let mut merged = match serde_json::to_value(self).map_err(err_str)? {
+            serde_json::Value::Object(object) => object,
+            _ => return Err("config is not an object".to_string()),
+        };
+        let mut config = self.clone();
+        let mut warnings = Vec::new();
+        for (key, value) in imported {
+            if !merged.contains_key(key) {
+                warnings.push(format!("skipped unknown setting {:?}", key));
+                continue;
+            }
+            // Each setting is checked on its own, so one invalid setting does not stop the rest
+            let previous_opt = merged.insert(key.clone(), value.clone());
+            match serde_json::from_value(serde_json::Value::Object(merged.clone())) {
+                Ok(ok) => config = ok,
+                Err(err) => {
+                    warnings.push(format!("skipped invalid setting {:?}: {}", key, err));
+                    if let Some(previous) = previous_opt {
+                        merged.insert(key.clone(), previous);
+                    }
+                }
+            }
+        }
+        Ok((config, warnings))
+    }
+
 }
 
 impl Default for Config {
@@ -371,27 +829,107 @@ impl Default for Config {
             color_schemes_dark: std::collections::BTreeMap::new(),
             color_schemes_light: std::collections::BTreeMap::new(),
             desktop: DesktopConfig::default(),
-            favorites: vec![
-                Favorite::Home,
-                Favorite::Documents,
-                Favorite::Downloads,
-                Favorite::Music,
-                Favorite::Pictures,
-                Favorite::Videos,
-            ],
+            favorites: Favorite::defaults(),
+            sidebar_hidden: Vec::new(),
+            sidebar_system_mounts: false,
+            recents_excluded: Vec::new(),
             show_details: false,
             show_button_row: true,
+            button_row: ButtonRowAction::defaults(),
             show_embedded_terminal: true,
+            open_terminal_embedded: false,
+            terminal_command: String::new(),
+            terminal_close_on_exit: false,
+            terminal_scrollback: 10_000,
+            terminal_theme_dark: COSMIC_THEME_DARK.to_string(),
+            terminal_theme_light: COSMIC_THEME_LIGHT.to_string(),
             show_second_panel: true,
             queue_file_operations: true,
+            conflict_policy: ConflictPolicy::default(),
+            unique_name_template: UNIQUE_NAME_TEMPLATE.to_string(),
+            unique_name_base: UNIQUE_NAME_BASE,
+            archive_open_action: ArchiveOpenAction::default(),
+            open_scripts_in_terminal: true,
+            auto_refresh_interval: 0,
+            tab_titles: TabTitles::default(),
+            confirm_trash: false,
+            trash_unavailable: TrashUnavailable::default(),
+            confirm_permanent_delete: true,
+            custom_actions: Vec::new(),
+            notify_operations: NotifyOperations::default(),
+            notify_sound: false,
+            single_instance: false,
+            allow_open_as_root: false,
+            read_only: false,
+            item_cache_size: ITEM_CACHE_SIZE,
+            copy_buffer_size: COPY_BUFFER_SIZE,
+            copy_concurrency: COPY_CONCURRENCY,
+            copy_strict: false,
             tab_left: TabConfig1::default(),
             tab_right: TabConfig2::default(),
             paths_left: Vec::new(),
             paths_right: Vec::new(),
+            pane_ratios: Vec::new(),
+            pane_focus: PaneType::LeftPane,
+            recent_network_drives: Vec::new(),
+            folder_views_left: Vec::new(),
+            folder_views_right: Vec::new(),
         }
     }
 }
 
+/// Corner of the desktop that icons are placed from
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum DesktopGridOrigin {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl DesktopGridOrigin {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::TopLeft,
+            Self::TopRight,
+            Self::BottomLeft,
+            Self::BottomRight,
+        ]
+    }
+
+    /// Position in the grid of the item at a position counted from this corner. Each page of
+    /// `rows` rows is flipped on its own, so later pages still follow below.
+    pub fn position(self, (row, col): (usize, usize), rows: usize, cols: usize) -> (usize, usize) {
+        let row = match self {
+            Self::TopLeft | Self::TopRight => row,
+            Self::BottomLeft | Self::BottomRight => {
+                let rows = rows.max(1);
+                row - row % rows + (rows - 1 - row % rows)
+            }
+        };
+        let col = match self {
+            Self::TopLeft | Self::BottomLeft => col,
+            Self::TopRight | Self::BottomRight => cols.saturating_sub(col + 1),
+        };
+        (row, col)
+    }
+}
+
+/// Whether desktop icons fill a column or a row before starting the next
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum DesktopGridFlow {
+    #[default]
+    Columns,
+    Rows,
+}
+
+impl DesktopGridFlow {
+    pub fn all() -> &'static [Self] {
+        &[Self::Columns, Self::Rows]
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, CosmicConfigEntry, Deserialize, Serialize)]
 #[serde(default)]
 pub struct DesktopConfig {
@@ -400,6 +938,10 @@ pub struct DesktopConfig {
     pub show_content: bool,
     pub show_mounted_drives: bool,
     pub show_trash: bool,
+    /// Corner the icons start from
+    pub grid_origin: DesktopGridOrigin,
+    /// Direction the icons continue in from the corner
+    pub grid_flow: DesktopGridFlow,
 }
 
 impl Default for DesktopConfig {
@@ -410,6 +952,8 @@ impl Default for DesktopConfig {
             show_content: true,
             show_mounted_drives: false,
             show_trash: false,
+            grid_origin: DesktopGridOrigin::default(),
+            grid_flow: DesktopGridFlow::default(),
         }
     }
 }
@@ -431,8 +975,24 @@ pub struct TabConfig1 {
     pub view: View1,
     /// Show folders before files
     pub folders_first: bool,
+    /// Sort used for items that are equal by the chosen sort
+    pub sort_secondary: HeadingOptions1,
     /// Show hidden files and folders
     pub show_hidden: bool,
+    /// Navigate into symlinked folders
+    pub follow_symlinks: bool,
+    /// Middle clicking a file opens it, folders always open in a new tab
+    pub middle_click_open_files: bool,
+    /// Seconds each image is shown in the gallery slideshow
+    pub slideshow_interval: u16,
+    /// Color names by the kind of item
+    pub color_by_type: bool,
+    /// Skip what .gitignore files match when searching and calculating folder sizes
+    pub respect_gitignore: bool,
+    /// Show a ".." row above the items of folders in the list view to go to the parent folder
+    pub show_parent_entry: bool,
+    /// Arrow keys move from the last item to the first and back
+    pub wrap_navigation: bool,
     /// Icon zoom
     pub icon_sizes: IconSizes,
 }
@@ -442,7 +1002,15 @@ impl Default for TabConfig1 {
         Self {
             view: View1::List,
             folders_first: true,
+            sort_secondary: HeadingOptions1::Name,
             show_hidden: false,
+            follow_symlinks: true,
+            middle_click_open_files: false,
+            slideshow_interval: 5,
+            color_by_type: false,
+            respect_gitignore: false,
+            show_parent_entry: false,
+            wrap_navigation: false,
             icon_sizes: IconSizes::default(),
         }
     }
@@ -459,8 +1027,24 @@ pub struct TabConfig2 {
     pub view: View2,
     /// Show folders before files
     pub folders_first: bool,
+    /// Sort used for items that are equal by the chosen sort
+    pub sort_secondary: HeadingOptions2,
     /// Show hidden files and folders
     pub show_hidden: bool,
+    /// Navigate into symlinked folders
+    pub follow_symlinks: bool,
+    /// Middle clicking a file opens it, folders always open in a new tab
+    pub middle_click_open_files: bool,
+    /// Seconds each image is shown in the gallery slideshow
+    pub slideshow_interval: u16,
+    /// Color names by the kind of item
+    pub color_by_type: bool,
+    /// Skip what .gitignore files match when searching and calculating folder sizes
+    pub respect_gitignore: bool,
+    /// Show a ".." row above the items of folders in the list view to go to the parent folder
+    pub show_parent_entry: bool,
+    /// Arrow keys move from the last item to the first and back
+    pub wrap_navigation: bool,
     /// Icon zoom
     pub icon_sizes: IconSizes,
 }
@@ -470,12 +1054,46 @@ impl Default for TabConfig2 {
         Self {
             view: View2::List,
             folders_first: true,
+            sort_secondary: HeadingOptions2::Name,
             show_hidden: false,
+            follow_symlinks: true,
+            middle_click_open_files: false,
+            slideshow_interval: 5,
+            color_by_type: false,
+            respect_gitignore: false,
+            show_parent_entry: false,
+            wrap_navigation: false,
             icon_sizes: IconSizes::default(),
         }
     }
 }
 
+/// View options remembered for a folder in the left panel
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FolderView1 {
+    pub view: View1,
+    pub sort_name: HeadingOptions1,
+    pub sort_direction: bool,
+    pub show_hidden: bool,
+}
+
+/// View options remembered for a folder in the right panel
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FolderView2 {
+    pub view: View2,
+    pub sort_name: HeadingOptions2,
+    pub sort_direction: bool,
+    pub show_hidden: bool,
+}
+
+/// Remember the view of a folder as the most recent one, forgetting the oldest beyond
+/// [`FOLDER_VIEWS_MAX`]
+pub fn remember_folder_view<T>(folder_views: &mut Vec<(String, T)>, path: String, folder_view: T) {
+    folder_views.retain(|(folder, _)| *folder != path);
+    folder_views.insert(0, (path, folder_view));
+    folder_views.truncate(FOLDER_VIEWS_MAX);
+}
+
 macro_rules! percent {
     ($perc:expr, $pixel:ident) => {
         (($perc.get() as f32 * $pixel as f32) / 100.).clamp(1., ($pixel * ICON_SCALE_MAX) as _)
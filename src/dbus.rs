@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Session bus service that lets other processes control the panels of a running instance
+
+use cosmic::{
+    iced::{futures::SinkExt, stream, Subscription},
+    Application,
+};
+use std::{any::TypeId, fs, future::pending, path::Path};
+use tokio::sync::mpsc;
+use zbus::{connection, fdo, interface};
+
+use crate::{
+    app::{App, LayoutOverrides, Message},
+    tab1::Location as Location1,
+    tab2::Location as Location2,
+};
+
+/// Object path the service is exported at
+pub const DBUS_PATH: &str = "/eu/fangornsrealm/commander";
+
+/// Location argument used for the trash
+const TRASH_URI: &str = "trash:///";
+
+struct Commander {
+    msg_tx: mpsc::UnboundedSender<Message>,
+}
+
+impl Commander {
+    fn send(&self, message: Message) -> fdo::Result<()> {
+        self.msg_tx
+            .send(message)
+            .map_err(|err| fdo::Error::Failed(err.to_string()))
+    }
+}
+
+/// Resolve a location argument, files are opened in their parent folder
+fn location(arg: &str) -> fdo::Result<Location1> {
+    if arg == TRASH_URI {
+        return Ok(Location1::Trash);
+    }
+    let path = fs::canonicalize(arg)
+        .map_err(|err| fdo::Error::InvalidArgs(format!("{:?}: {}", arg, err)))?;
+    match path.parent() {
+        Some(parent) if path.is_file() => Ok(Location1::Path(parent.to_path_buf())),
+        _ => Ok(Location1::Path(path)),
+    }
+}
+
+#[interface(name = "eu.fangornsrealm.commander")]
+impl Commander {
+    /// Open a path in a new tab of the left panel and focus that panel
+    fn open_left(&self, path: &str) -> fdo::Result<()> {
+        let location = location(path)?;
+        self.send(Message::TabCreateLeft(Some(location)))?;
+        self.send(Message::TabActivateLeft)
+    }
+
+    /// Open a path in a new tab of the right panel and focus that panel
+    fn open_right(&self, path: &str) -> fdo::Result<()> {
+        let location = match location(path)? {
+            Location1::Path(path) => Location2::Path(path),
+            _ => Location2::Trash,
+        };
+        self.send(Message::TabCreateRight(Some(location)))?;
+        self.send(Message::TabActivateRight)
+    }
+
+    /// Focus the "left" or "right" panel
+    fn activate_panel(&self, side: &str) -> fdo::Result<()> {
+        match side {
+            "left" => self.send(Message::TabActivateLeft),
+            "right" => self.send(Message::TabActivateRight),
+            _ => Err(fdo::Error::InvalidArgs(format!(
+                "unknown panel {:?}, expected \"left\" or \"right\"",
+                side
+            ))),
+        }
+    }
+
+    /// Show a path selected in its folder in the active panel
+    fn reveal(&self, path: &str) -> fdo::Result<()> {
+        let path = fs::canonicalize(path)
+            .map_err(|err| fdo::Error::InvalidArgs(format!("{:?}: {}", path, err)))?;
+        self.send(Message::Reveal(path))
+    }
+
+    /// Show or hide a part of the layout for this session: "button-row", "terminal" or
+    /// "second-panel"
+    fn set_layout(&self, part: &str, show: bool) -> fdo::Result<()> {
+        let mut layout_overrides = LayoutOverrides::default();
+        match part {
+            "button-row" => layout_overrides.show_button_row = Some(show),
+            "terminal" => layout_overrides.show_embedded_terminal = Some(show),
+            "second-panel" => layout_overrides.show_second_panel = Some(show),
+            _ => {
+                return Err(fdo::Error::InvalidArgs(format!(
+                    "unknown layout part {:?}, expected \"button-row\", \"terminal\" or \"second-panel\"",
+                    part
+                )))
+            }
+        }
+        self.send(Message::LayoutOverrides(layout_overrides))
+    }
+
+    /// Raise and focus the main window
+    fn activate(&self) -> fdo::Result<()> {
+        self.send(Message::WindowFocus)
+    }
+}
+
+/// Serve the interface on the session bus and forward calls into the update loop
+pub fn subscription() -> Subscription<Message> {
+    struct DbusSubscription;
+    Subscription::run_with_id(
+        TypeId::of::<DbusSubscription>(),
+        stream::channel(16, |mut output| async move {
+            let (msg_tx, mut msg_rx) = mpsc::unbounded_channel();
+            let connection_res = async {
+                connection::Builder::session()?
+                    .name(App::APP_ID)?
+                    .serve_at(DBUS_PATH, Commander { msg_tx })?
+                    .build()
+                    .await
+            }
+            .await;
+            // The connection must stay alive for as long as the service is provided
+            let _connection = match connection_res {
+                Ok(connection) => connection,
+                Err(err) => {
+                    log::warn!("failed to serve {} on session bus: {}", App::APP_ID, err);
+                    pending().await
+                }
+            };
+
+            while let Some(message) = msg_rx.recv().await {
+                output.send(message).await.unwrap();
+            }
+
+            pending().await
+        }),
+    )
+}
+
+/// Ask a running instance to open locations in its panels, reveal a path, apply the layout
+/// given on the command line and focus its window, fails if no instance is running
+pub fn open_remote(
+    left: &[Location1],
+    right: &[Location1],
+    reveal_opt: Option<&Path>,
+    layout_overrides: LayoutOverrides,
+) -> zbus::Result<()> {
+    let connection = zbus::blocking::Connection::session()?;
+    let open = |method: &str, locations: &[Location1]| -> zbus::Result<()> {
+        for location in locations {
+            let arg = match location {
+                Location1::Trash => TRASH_URI.to_string(),
+                location => match location.path_opt() {
+                    Some(path) => path.to_string_lossy().into_owned(),
+                    None => continue,
+                },
+            };
+            connection.call_method(
+                Some(App::APP_ID),
+                DBUS_PATH,
+                Some(App::APP_ID),
+                method,
+                &(arg.as_str(),),
+            )?;
+        }
+        Ok(())
+    };
+    open("OpenLeft", left)?;
+    open("OpenRight", right)?;
+    if let Some(path) = reveal_opt {
+        connection.call_method(
+            Some(App::APP_ID),
+            DBUS_PATH,
+            Some(App::APP_ID),
+            "Reveal",
+            &(&*path.to_string_lossy(),),
+        )?;
+    }
+    let LayoutOverrides {
+        show_button_row,
+        show_embedded_terminal,
+        show_second_panel,
+    } = layout_overrides;
+    for (part, show_opt) in [
+        ("button-row", show_button_row),
+        ("terminal", show_embedded_terminal),
+        ("second-panel", show_second_panel),
+    ] {
+        if let Some(show) = show_opt {
+            connection.call_method(
+                Some(App::APP_ID),
+                DBUS_PATH,
+                Some(App::APP_ID),
+                "SetLayout",
+                &(part, show),
+            )?;
+        }
+    }
+    connection.call_method(
+        Some(App::APP_ID),
+        DBUS_PATH,
+        Some(App::APP_ID),
+        "Activate",
+        &(),
+    )?;
+    Ok(())
+}
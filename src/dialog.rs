@@ -327,9 +327,15 @@ enum Message {
     SearchActivate,
     SearchClear,
     SearchInput(String),
+    SetSortSecondary(tab1::HeadingOptions),
     #[allow(clippy::enum_variant_names)]
     TabMessage(tab1::Message),
-    TabRescan(tab1::Location, Option<tab1::Item>, Vec<tab1::Item>),
+    TabRescan(
+        tab1::Location,
+        Option<tab1::Item>,
+        Vec<tab1::Item>,
+        Option<String>,
+    ),
     TabViewLeft(tab1::View),
     ToggleFoldersFirst,
     ZoomDefault(Option<cosmic::widget::segmented_button::Entity>),
@@ -345,6 +351,7 @@ impl From<AppMessage> for Message {
             AppMessage::SearchActivate => Message::SearchActivate,
             AppMessage::TabMessage(_entity_opt, tab_message) => Message::TabMessage(tab_message),
             AppMessage::TabView(_entity_opt, view) => Message::TabViewLeft(view),
+            AppMessage::SetSortSecondary(sort) => Message::SetSortSecondary(sort),
             AppMessage::ToggleFoldersFirst => Message::ToggleFoldersFirst,
             AppMessage::ZoomDefault(entity_opt) => Message::ZoomDefault(entity_opt),
             AppMessage::ZoomIn(entity_opt) => Message::ZoomIn(entity_opt),
@@ -517,9 +524,19 @@ impl App {
         Task::perform(
             async move {
                 let location2 = location.clone();
-                match tokio::task::spawn_blocking(move || location2.scan(icon_sizes)).await {
-                    Ok((parent_item_opt, items)) => {
-                        message::app(Message::TabRescan(location, parent_item_opt, items))
+                match tokio::task::spawn_blocking(move || location2.scan_checked(icon_sizes)).await
+                {
+                    Ok((parent_item_opt, items_res)) => {
+                        let (items, scan_error_opt) = match items_res {
+                            Ok(items) => (items, None),
+                            Err(err) => (Vec::new(), Some(err)),
+                        };
+                        message::app(Message::TabRescan(
+                            location,
+                            parent_item_opt,
+                            items,
+                            scan_error_opt,
+                        ))
                     }
                     Err(err) => {
                         log::warn!("failed to rescan: {}", err);
@@ -1040,7 +1057,7 @@ impl Application for App {
     fn on_escape(&mut self) -> Task<Message> {
         if self.tab.gallery {
             // Close gallery if open
-            self.tab.gallery = false;
+            self.tab.set_gallery(false);
             return Task::none();
         }
 
@@ -1421,6 +1438,9 @@ impl Application for App {
                             self.context_page = ContextPage::Preview(None, kind);
                             self.set_show_context(true);
                         }
+                        tab1::Command::Rescan => {
+                            commands.push(self.rescan_tab());
+                        }
                         tab1::Command::WindowDrag => {
                             commands.push(window::drag(self.flags.window_id));
                         }
@@ -1434,7 +1454,7 @@ impl Application for App {
                 }
                 return Task::batch(commands);
             }
-            Message::TabRescan(location, parent_item_opt, mut items) => {
+            Message::TabRescan(location, parent_item_opt, mut items, scan_error_opt) => {
                 if location == self.tab.location {
                     // Filter
                     if let Some(filter_i) = self.filter_selected {
@@ -1505,6 +1525,7 @@ impl Application for App {
                     }
 
                     self.tab.parent_item_opt = parent_item_opt;
+                    self.tab.scan_error_opt = scan_error_opt;
                     self.tab.set_items(items);
 
                     // Reset focus on location change
@@ -1515,6 +1536,9 @@ impl Application for App {
                     }
                 }
             }
+            Message::SetSortSecondary(sort) => {
+                self.tab.config.sort_secondary = sort;
+            }
             Message::TabViewLeft(view) => {
                 self.tab.config.view = view;
             }
@@ -1589,7 +1613,7 @@ impl Application for App {
             }
         }
 
-        col = col.push(self.tab.view(&self.key_binds).map(Message::TabMessage));
+        col = col.push(self.tab.view(&self.key_binds, &[]).map(Message::TabMessage));
 
         col.into()
     }
@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{fs, path::Path};
+
+use crate::{err_str, fl};
+
+/// Larger files are not compared
+pub const DIFF_SIZE_MAX: u64 = 1024 * 1024;
+
+// Files that need more line edits than this are shown as replaced as a whole, as finding the
+// shortest edit takes memory growing with the square of the edits
+const DIFF_EDITS_MAX: usize = 2000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiffKind {
+    Equal,
+    Removed,
+    Added,
+    Changed,
+}
+
+/// Row of the side by side view, with the line number and text on each side
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiffRow {
+    pub kind: DiffKind,
+    pub left: Option<(usize, String)>,
+    pub right: Option<(usize, String)>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+// Shortest edit script using the algorithm of Myers, or `None` if it needs too many edits
+fn shortest_edit(a: &[&str], b: &[&str]) -> Option<Vec<Edit>> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = a.len() + b.len();
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    // Furthest reaching x for each diagonal between -d and d, before each step d
+    let mut trace = Vec::new();
+    'outer: for d in 0..=max as isize {
+        if d as usize > DIFF_EDITS_MAX {
+            return None;
+        }
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let i = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && v[i - 1] < v[i + 1]) {
+                v[i + 1]
+            } else {
+                v[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[i] = x;
+            if x >= n && y >= m {
+                break 'outer;
+            }
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let at = |k: isize| v[(k + d) as usize];
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = if d == 0 { 0 } else { at(prev_k) };
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Equal(x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == prev_x {
+                y -= 1;
+                edits.push(Edit::Insert(y as usize));
+            } else {
+                x -= 1;
+                edits.push(Edit::Delete(x as usize));
+            }
+        }
+    }
+    edits.reverse();
+    Some(edits)
+}
+
+fn line(lines: &[&str], i: usize) -> Option<(usize, String)> {
+    Some((i + 1, lines[i].to_string()))
+}
+
+// Pair lines removed and added in between equal lines as changed
+fn push_changed(
+    rows: &mut Vec<DiffRow>,
+    (a, deleted): (&[&str], &mut Vec<usize>),
+    (b, inserted): (&[&str], &mut Vec<usize>),
+) {
+    for i in 0..deleted.len().max(inserted.len()) {
+        let left = deleted.get(i).and_then(|x| line(a, *x));
+        let right = inserted.get(i).and_then(|y| line(b, *y));
+        let kind = match (&left, &right) {
+            (Some(_), Some(_)) => DiffKind::Changed,
+            (Some(_), None) => DiffKind::Removed,
+            _ => DiffKind::Added,
+        };
+        rows.push(DiffRow { kind, left, right });
+    }
+    deleted.clear();
+    inserted.clear();
+}
+
+/// Compare lines, pairing removed and added lines next to each other as changed
+pub fn diff_lines(a: &[&str], b: &[&str]) -> Vec<DiffRow> {
+    // Common lines at the start and end are skipped before searching for edits
+    let prefix = a.iter().zip(b.iter()).take_while(|(a, b)| a == b).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    let mid = shortest_edit(a_mid, b_mid).unwrap_or_else(|| {
+        (0..a_mid.len())
+            .map(Edit::Delete)
+            .chain((0..b_mid.len()).map(Edit::Insert))
+            .collect()
+    });
+    let edits = (0..prefix)
+        .map(|i| Edit::Equal(i, i))
+        .chain(mid.into_iter().map(|edit| match edit {
+            Edit::Equal(x, y) => Edit::Equal(x + prefix, y + prefix),
+            Edit::Delete(x) => Edit::Delete(x + prefix),
+            Edit::Insert(y) => Edit::Insert(y + prefix),
+        }))
+        .chain((0..suffix).map(|i| Edit::Equal(a.len() - suffix + i, b.len() - suffix + i)));
+
+    let mut rows = Vec::new();
+    let mut deleted = Vec::new();
+    let mut inserted = Vec::new();
+    for edit in edits {
+        match edit {
+            Edit::Equal(x, y) => {
+                push_changed(&mut rows, (a, &mut deleted), (b, &mut inserted));
+                rows.push(DiffRow {
+                    kind: DiffKind::Equal,
+                    left: line(a, x),
+                    right: line(b, y),
+                });
+            }
+            Edit::Delete(x) => deleted.push(x),
+            Edit::Insert(y) => inserted.push(y),
+        }
+    }
+    push_changed(&mut rows, (a, &mut deleted), (b, &mut inserted));
+    rows
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+    let name = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    let len = fs::metadata(path).map_err(err_str)?.len();
+    if len > DIFF_SIZE_MAX {
+        return Err(fl!("diff-too-large", name = name));
+    }
+    let data = fs::read(path).map_err(err_str)?;
+    match String::from_utf8(data) {
+        // Text files do not contain nul bytes
+        Ok(text) if !text.contains('\0') => Ok(text),
+        _ => Err(fl!("diff-binary", name = name)),
+    }
+}
+
+/// Compare two text files line by line
+pub fn diff_files(left: &Path, right: &Path) -> Result<Vec<DiffRow>, String> {
+    let left_text = read_text(left)?;
+    let right_text = read_text(right)?;
+    let left_lines: Vec<&str> = left_text.lines().collect();
+    let right_lines: Vec<&str> = right_text.lines().collect();
+    Ok(diff_lines(&left_lines, &right_lines))
+}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use ignore::WalkBuilder;
+use std::{
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+};
+
+use crate::{item_cache, operation::Controller};
+
+pub fn cached(path: &Path, metadata: &Metadata) -> Option<u64> {
+    item_cache::dir_size(path, metadata.modified().ok()?)
+}
+
+/// Forget all calculated sizes, used when the files that are counted change
+pub fn clear_cache() {
+    item_cache::forget_dir_sizes(|_| true);
+}
+
+/// Forget the sizes of the folders holding `paths` and of those below them. Changes deeper
+/// than its own items leave the modification time of a folder as it is.
+pub fn invalidate(paths: &[PathBuf]) {
+    item_cache::forget_dir_sizes(|cached| {
+        paths
+            .iter()
+            .any(|path| path.starts_with(cached) || cached.starts_with(path))
+    });
+}
+
+/// Sum the size of all files below `path`, skipping those matched by .gitignore files if
+/// `respect_gitignore` is set. `progress` receives the running total each time a top level
+/// folder has been walked completely.
+pub fn calculate<F: FnMut(u64)>(
+    path: &Path,
+    respect_gitignore: bool,
+    controller: &Controller,
+    mut progress: F,
+) -> Result<u64, String> {
+    let modified_opt = fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut total = 0;
+    let mut in_subtree = false;
+    let walker = WalkBuilder::new(path)
+        .standard_filters(false)
+        .git_ignore(respect_gitignore)
+        .parents(respect_gitignore)
+        .require_git(false)
+        // Symlinks below the folder are not followed, so they can't loop or be counted twice
+        .follow_links(false)
+        .build();
+    for entry_res in walker {
+        controller.check()?;
+        //TODO: report more errors?
+        if let Ok(entry) = entry_res {
+            if entry.depth() == 1 {
+                if in_subtree {
+                    // The previous top level folder is complete
+                    progress(total);
+                }
+                in_subtree = entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_dir());
+            }
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_file() {
+                    total += metadata.len();
+                }
+            }
+        }
+    }
+    if let Some(modified) = modified_opt {
+        item_cache::insert_dir_size(path, modified, total);
+    }
+    Ok(total)
+}
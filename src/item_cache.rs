@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::widget;
+use lru::LruCache;
+use mime_guess::Mime;
+use once_cell::sync::Lazy;
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use crate::checksum::ChecksumKind;
+#[cfg(unix)]
+use crate::xattrs::ItemXattrs;
+
+/// Number of items cached by default
+pub const ITEM_CACHE_SIZE: usize = 10_000;
+
+// Everything remembered about items, shared by the tabs of both panels and keyed by path. The
+// least recently used items are evicted first.
+static ITEM_CACHE: Lazy<Mutex<LruCache<PathBuf, CachedItem>>> =
+    Lazy::new(|| Mutex::new(LruCache::new(capacity(ITEM_CACHE_SIZE))));
+
+/// Values are only valid while the modification time stored with them matches
+#[derive(Default)]
+struct CachedItem {
+    details_opt: Option<(SystemTime, ItemDetails)>,
+    /// Total size of the files below a folder
+    dir_size_opt: Option<(SystemTime, u64)>,
+    checksums: Vec<(ChecksumKind, SystemTime, String)>,
+    /// Keyed by the change time instead, as setting an attribute does not change the
+    /// modification time
+    #[cfg(unix)]
+    xattrs_opt: Option<((i64, i64), ItemXattrs)>,
+}
+
+/// Details of an item that are slow to read
+#[derive(Clone, Debug)]
+pub struct ItemDetails {
+    pub mime: Mime,
+    /// Name and icon name of a desktop entry
+    pub desktop: (Option<String>, Option<String>),
+    /// Number of entries of a folder
+    pub children: usize,
+    pub thumbnail_opt: Option<CachedThumbnail>,
+}
+
+/// Thumbnail in a form that can be used by the tabs of both panels
+#[derive(Clone, Debug)]
+pub enum CachedThumbnail {
+    NotImage,
+    Image(widget::image::Handle, Option<(u32, u32)>),
+    Svg(widget::svg::Handle),
+}
+
+fn capacity(size: usize) -> NonZeroUsize {
+    NonZeroUsize::new(size).unwrap_or(NonZeroUsize::MIN)
+}
+
+/// Change how many items are cached, evicting the least recently used ones
+pub fn set_capacity(size: usize) {
+    ITEM_CACHE.lock().unwrap().resize(capacity(size));
+}
+
+fn entry<'a>(cache: &'a mut LruCache<PathBuf, CachedItem>, path: &Path) -> &'a mut CachedItem {
+    if !cache.contains(path) {
+        cache.put(path.to_path_buf(), CachedItem::default());
+    }
+    cache.get_mut(path).expect("item was just cached")
+}
+
+pub fn get(path: &Path, modified: SystemTime) -> Option<ItemDetails> {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    match cache.get(path)?.details_opt {
+        Some((cached_modified, ref details)) if cached_modified == modified => {
+            Some(details.clone())
+        }
+        _ => None,
+    }
+}
+
+pub fn insert(path: &Path, modified: SystemTime, details: ItemDetails) {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    entry(&mut cache, path).details_opt = Some((modified, details));
+}
+
+/// Remember the thumbnail of an item, if its details are cached for the same modification time
+pub fn insert_thumbnail(path: &Path, modified: SystemTime, thumbnail: CachedThumbnail) {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    if let Some(item) = cache.get_mut(path) {
+        if let Some((cached_modified, details)) = &mut item.details_opt {
+            if *cached_modified == modified {
+                details.thumbnail_opt = Some(thumbnail);
+            }
+        }
+    }
+}
+
+pub fn dir_size(path: &Path, modified: SystemTime) -> Option<u64> {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    match cache.get(path)?.dir_size_opt {
+        Some((cached_modified, size)) if cached_modified == modified => Some(size),
+        _ => None,
+    }
+}
+
+pub fn insert_dir_size(path: &Path, modified: SystemTime, size: u64) {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    entry(&mut cache, path).dir_size_opt = Some((modified, size));
+}
+
+/// Forget the folder sizes for which `forget` returns true, without changing which items are
+/// used most recently
+pub fn forget_dir_sizes<F: Fn(&Path) -> bool>(forget: F) {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    for (path, item) in cache.iter_mut() {
+        if forget(path.as_path()) {
+            item.dir_size_opt = None;
+        }
+    }
+}
+
+pub fn checksum(path: &Path, kind: ChecksumKind, modified: SystemTime) -> Option<String> {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    cache
+        .get(path)?
+        .checksums
+        .iter()
+        .find(|(cached_kind, cached_modified, _)| {
+            *cached_kind == kind && *cached_modified == modified
+        })
+        .map(|(_, _, checksum)| checksum.clone())
+}
+
+pub fn insert_checksum(path: &Path, kind: ChecksumKind, modified: SystemTime, checksum: String) {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    let checksums = &mut entry(&mut cache, path).checksums;
+    checksums.retain(|(cached_kind, _, _)| *cached_kind != kind);
+    checksums.push((kind, modified, checksum));
+}
+
+#[cfg(unix)]
+pub fn xattrs(path: &Path, changed: (i64, i64)) -> Option<ItemXattrs> {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    match cache.get(path)?.xattrs_opt {
+        Some((cached_changed, ref xattrs)) if cached_changed == changed => Some(xattrs.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(unix)]
+pub fn insert_xattrs(path: &Path, changed: (i64, i64), xattrs: ItemXattrs) {
+    let mut cache = ITEM_CACHE.lock().unwrap();
+    entry(&mut cache, path).xattrs_opt = Some((changed, xattrs));
+}
@@ -24,13 +24,16 @@ pub fn key_binds(mode: &tab1::Mode) -> HashMap<KeyBind, Action> {
     }
 
     // Common keys
-    bind!([], Key::Named(Named::Space), Gallery);
+    bind!([], Key::Named(Named::Space), QuickPreview);
+    bind!([Shift], Key::Named(Named::Space), Gallery);
     bind!([Shift], Key::Named(Named::Tab), SwapPanels);
     bind!([], Key::Named(Named::F2), F2Rename);
     bind!([], Key::Named(Named::F3), F3View);
     bind!([], Key::Named(Named::F4), F4Edit);
     bind!([], Key::Named(Named::F5), F5Copy);
     bind!([], Key::Named(Named::F6), F6Move);
+    bind!([Shift], Key::Named(Named::F5), CopyTo);
+    bind!([Shift], Key::Named(Named::F6), MoveTo);
     bind!([], Key::Named(Named::F7), F7Mkdir);
     bind!([], Key::Named(Named::F8), F8Delete);
     bind!([], Key::Named(Named::F9), F9Terminal);
@@ -42,12 +45,16 @@ pub fn key_binds(mode: &tab1::Mode) -> HashMap<KeyBind, Action> {
     bind!([], Key::Named(Named::ArrowUp), ItemUp);
     bind!([], Key::Named(Named::Home), SelectFirst);
     bind!([], Key::Named(Named::End), SelectLast);
+    bind!([], Key::Named(Named::PageDown), PageDown);
+    bind!([], Key::Named(Named::PageUp), PageUp);
     bind!([Shift], Key::Named(Named::ArrowDown), ItemDown);
     bind!([Shift], Key::Named(Named::ArrowLeft), ItemLeft);
     bind!([Shift], Key::Named(Named::ArrowRight), ItemRight);
     bind!([Shift], Key::Named(Named::ArrowUp), ItemUp);
     bind!([Shift], Key::Named(Named::Home), SelectFirst);
     bind!([Shift], Key::Named(Named::End), SelectLast);
+    bind!([Shift], Key::Named(Named::PageDown), PageDown);
+    bind!([Shift], Key::Named(Named::PageUp), PageUp);
     bind!([Ctrl, Shift], Key::Character("n".into()), NewFolder);
     bind!([], Key::Named(Named::Enter), Open);
     bind!([Ctrl], Key::Named(Named::Space), Preview);
@@ -66,13 +73,19 @@ pub fn key_binds(mode: &tab1::Mode) -> HashMap<KeyBind, Action> {
         bind!([Ctrl], Key::Named(Named::F5), TabRescan);
         bind!([Ctrl], Key::Character("r".into()), TabRescan);
         bind!([Ctrl], Key::Character(",".into()), Settings);
+        bind!([Ctrl, Shift], Key::Character("p".into()), CommandPalette);
         bind!([Ctrl], Key::Character("w".into()), TabClose);
         bind!([Ctrl], Key::Character("s".into()), SwapPanels);
         bind!([Ctrl], Key::Character("t".into()), TabNew);
         bind!([Ctrl], Key::Named(Named::Tab), TabNext);
         bind!([Ctrl, Shift], Key::Named(Named::Tab), TabPrev);
+        bind!([], Key::Named(Named::Tab), FocusOtherPanel);
         bind!([Ctrl], Key::Character("q".into()), WindowClose);
         bind!([Ctrl], Key::Character("n".into()), WindowNew);
+        bind!([], Key::Character("+".into()), SelectByPattern);
+        bind!([Shift], Key::Character("+".into()), SelectByPattern);
+        bind!([], Key::Character("-".into()), DeselectByPattern);
+        bind!([], Key::Named(Named::Pause), PendingPauseToggle);
         //bind!([Ctrl], Key::Character("r".into()), TabReload);
     }
 
@@ -81,9 +94,12 @@ pub fn key_binds(mode: &tab1::Mode) -> HashMap<KeyBind, Action> {
         bind!([Ctrl], Key::Character("c".into()), Copy);
         bind!([Ctrl], Key::Character("x".into()), Cut);
         bind!([], Key::Named(Named::Delete), MoveToTrash);
+        bind!([Shift], Key::Named(Named::Delete), PermanentlyDelete);
         bind!([Shift], Key::Named(Named::Enter), OpenInNewWindow);
         bind!([Ctrl], Key::Character("v".into()), Paste);
         bind!([], Key::Named(Named::F2), Rename);
+        bind!([Ctrl], Key::Character("z".into()), Undo);
+        bind!([Ctrl, Shift], Key::Character("z".into()), Redo);
     }
 
     // App and dialog only keys
@@ -4,14 +4,20 @@
 use cosmic::{app::Settings, iced::Limits};
 use std::{env, fs, path::PathBuf, process};
 
-use app::{App, Flags};
+use app::{App, Flags, LayoutOverrides};
 pub mod app;
+mod checksum;
 pub mod clipboard;
 use config::Config;
 mod commanderpanegrid;
 pub mod config;
+#[cfg(feature = "dbus")]
+mod dbus;
 pub mod dialog;
+mod diff;
+mod dir_size;
 pub mod dnd;
+mod item_cache;
 mod key_bind;
 mod localize;
 mod menu;
@@ -22,6 +28,7 @@ mod mouse_area;
 mod mouse_reporter;
 pub mod operation;
 mod pane_grid;
+mod recents;
 mod spawn_detached;
 use tab1::Location;
 pub mod tab1;
@@ -30,6 +37,8 @@ mod terminal_box;
 mod terminal_theme;
 mod terminal;
 mod thumbnailer;
+#[cfg(unix)]
+mod xattrs;
 //pub mod terminal;
 
 pub(crate) fn err_str<T: ToString>(err: T) -> String {
@@ -58,6 +67,51 @@ pub fn home_dir() -> PathBuf {
     }
 }
 
+const USAGE: &str = "\
+Usage: commander [OPTIONS] [PATH]...
+
+Paths and file URLs are opened in the left panel, files are selected in their folder.
+
+Options:
+  --left PATH          Open PATH in the left panel
+  --right PATH         Open PATH in the right panel
+  --open-left PATH     Open PATH in the left panel of the running instance
+  --open-right PATH    Open PATH in the right panel of the running instance
+  --select PATH        Show PATH selected in its folder, in the running instance if there is one
+  --new-window         Open a new window even if an instance is running
+  --trash              Open the trash in the left panel
+  --terminal           Show the embedded terminal
+  --no-terminal        Hide the embedded terminal
+  --second-panel       Show the right panel
+  --no-second-panel    Hide the right panel
+  --button-row         Show the button row
+  --no-button-row      Hide the button row
+  --no-daemon          Stay attached to the terminal
+  -h, --help           Print this help
+";
+
+/// Parse a command line path or file URL into an absolute path
+fn parse_path(arg: String) -> Option<PathBuf> {
+    //TODO: support more URLs
+    let path = match url::Url::parse(&arg) {
+        Ok(url) => match url.to_file_path() {
+            Ok(path) => path,
+            Err(()) => {
+                log::warn!("invalid argument {:?}", arg);
+                return None;
+            }
+        },
+        Err(_) => PathBuf::from(arg),
+    };
+    match fs::canonicalize(&path) {
+        Ok(absolute) => Some(absolute),
+        Err(err) => {
+            log::warn!("failed to canonicalize {:?}: {}", path, err);
+            None
+        }
+    }
+}
+
 /// Runs application in desktop mode
 #[rustfmt::skip]
 pub fn desktop() -> Result<(), Box<dyn std::error::Error>> {
@@ -85,6 +139,8 @@ pub fn desktop() -> Result<(), Box<dyn std::error::Error>> {
         mode: app::Mode::Desktop,
         locations1,
         locations2,
+        reveal_opt: None,
+        layout_overrides: LayoutOverrides::default(),
     };
     cosmic::app::run::<App>(settings, flags)?;
 
@@ -102,33 +158,71 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut daemonize = false;
     let mut locations = Vec::new();
-    for arg in env::args().skip(1) {
-        let location = if &arg == "--no-daemon" {
-            daemonize = false;
-            continue;
-        } else if &arg == "--trash" {
-            Location::Trash
-        } else {
-            //TODO: support more URLs
-            let path = match url::Url::parse(&arg) {
-                Ok(url) => match url.to_file_path() {
-                    Ok(path) => path,
-                    Err(()) => {
-                        log::warn!("invalid argument {:?}", arg);
-                        continue;
+    let mut locations2 = Vec::new();
+    let mut reveal_opt = None;
+    let mut layout_overrides = LayoutOverrides::default();
+    // Open the locations in an already running instance if there is one
+    let mut open_remote = false;
+    let mut new_window = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                return Ok(());
+            }
+            "--no-daemon" => daemonize = false,
+            "--new-window" => new_window = true,
+            "--trash" => locations.push(Location::Trash),
+            "--terminal" => layout_overrides.show_embedded_terminal = Some(true),
+            "--no-terminal" => layout_overrides.show_embedded_terminal = Some(false),
+            "--second-panel" => layout_overrides.show_second_panel = Some(true),
+            "--no-second-panel" => layout_overrides.show_second_panel = Some(false),
+            "--button-row" => layout_overrides.show_button_row = Some(true),
+            "--no-button-row" => layout_overrides.show_button_row = Some(false),
+            "--select" => {
+                let Some(value) = args.next() else {
+                    eprintln!("missing path after {}\n\n{}", arg, USAGE);
+                    process::exit(1);
+                };
+                open_remote = true;
+                reveal_opt = parse_path(value);
+            }
+            "--left" | "--right" | "--open-left" | "--open-right" => {
+                let Some(value) = args.next() else {
+                    eprintln!("missing path after {}\n\n{}", arg, USAGE);
+                    process::exit(1);
+                };
+                if arg.starts_with("--open-") {
+                    open_remote = true;
+                }
+                if let Some(path) = parse_path(value) {
+                    if arg.ends_with("left") {
+                        locations.push(Location::Path(path));
+                    } else {
+                        locations2.push(Location::Path(path));
                     }
-                },
-                Err(_) => PathBuf::from(arg),
-            };
-            match fs::canonicalize(&path) {
-                Ok(absolute) => Location::Path(absolute),
-                Err(err) => {
-                    log::warn!("failed to canonicalize {:?}: {}", path, err);
-                    continue;
                 }
             }
-        };
-        locations.push(location);
+            _ if arg.starts_with("--") => {
+                eprintln!("unknown option {}\n\n{}", arg, USAGE);
+                process::exit(1);
+            }
+            _ => {
+                if let Some(path) = parse_path(arg) {
+                    locations.push(Location::Path(path));
+                }
+            }
+        }
+    }
+
+    // Hand the locations to a running instance, or launch one if it can't be reached
+    if !new_window && (open_remote || config.single_instance) {
+        #[cfg(feature = "dbus")]
+        match dbus::open_remote(&locations, &locations2, reveal_opt.as_deref(), layout_overrides) {
+            Ok(()) => return Ok(()),
+            Err(err) => log::info!("no running instance to open locations in, launching one: {}", err),
+        }
     }
 
     if daemonize {
@@ -153,7 +247,9 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
         config,
         mode: app::Mode::App,
         locations1: locations,
-        locations2: Vec::new(),
+        locations2,
+        reveal_opt,
+        layout_overrides,
     };
     cosmic::app::run::<App>(settings, flags)?;
 
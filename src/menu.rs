@@ -16,8 +16,9 @@ use std::collections::HashMap;
 
 use crate::{
     app::{Action, Message},
-    config::Config,
+    config::{Config, ParsedCustomAction},
     fl,
+    operation::EXTRACT_MIME_TYPES,
     tab1::{self, HeadingOptions as HeadingOptions1, Location as Location1, LocationMenuAction as LocationMenuAction1, Tab as Tab1},
     tab2::{self, HeadingOptions as HeadingOptions2, Location as Location2, LocationMenuAction as LocationMenuAction2, Tab as Tab2},
 };
@@ -49,9 +50,29 @@ fn menu_button_optional(
     }
 }
 
+/// Disable the items of actions that change files in read-only mode
+fn read_only_items(
+    items: Vec<menu::Item<Action, String>>,
+    read_only: bool,
+) -> Vec<menu::Item<Action, String>> {
+    if !read_only {
+        return items;
+    }
+    items
+        .into_iter()
+        .map(|item| match item {
+            menu::Item::Button(label, icon, action) if action.changes_files() => {
+                menu::Item::ButtonDisabled(label, icon, action)
+            }
+            item => item,
+        })
+        .collect()
+}
+
 pub fn context_menu1<'a>(
     tab: &Tab1,
     key_binds: &HashMap<KeyBind, Action>,
+    custom_actions: &[ParsedCustomAction],
 ) -> Element<'a, tab1::Message> {
     let find_key = |action: &Action| -> String {
         for (key_bind, key_action) in key_binds.iter() {
@@ -86,17 +107,26 @@ pub fn context_menu1<'a>(
     };
 
     let mut selected_dir = 0;
+    let mut selected_symlink = 0;
     let mut selected = 0;
     let mut selected_trash_only = false;
     let mut selected_desktop_entry = None;
     let mut selected_types: Vec<Mime> = vec![];
+    // Custom actions are offered if they match all selected items
+    let mut custom_matched = vec![true; custom_actions.len()];
     if let Some(items) = tab.items_opt() {
         for item in items.iter() {
             if item.selected {
                 selected += 1;
+                for (matched, custom_action) in custom_matched.iter_mut().zip(custom_actions) {
+                    *matched = *matched && custom_action.matches(&item.name, &item.mime);
+                }
                 if item.metadata.is_dir() {
                     selected_dir += 1;
                 }
+                if item.symlink_target_opt.is_some() {
+                    selected_symlink += 1;
+                }
                 match &item.location_opt {
                     Some(Location1::Trash) => selected_trash_only = true,
                     Some(Location1::Path(path)) => {
@@ -132,7 +162,7 @@ pub fn context_menu1<'a>(
     match (&tab.mode, &tab.location) {
         (
             tab1::Mode::App | tab1::Mode::Desktop,
-            Location1::Desktop(..) | Location1::Path(..) | Location1::Search(..) | Location1::Recents,
+            Location1::Desktop(..) | Location1::Path(..) | Location1::Search(..) | Location1::Flatten(..) | Location1::Recents,
         ) => {
             if selected_trash_only {
                 children.push(menu_item(fl!("open"), Action::Open).into());
@@ -163,12 +193,32 @@ pub fn context_menu1<'a>(
                         children
                             .push(menu_item(fl!("open-in-terminal"), Action::OpenTerminal).into());
                     }
+                    if selected_symlink == 1 {
+                        children.push(
+                            menu_item(fl!("open-link-target"), Action::OpenLinkTarget).into(),
+                        );
+                    }
+                }
+                for (i, custom_action) in custom_actions.iter().enumerate() {
+                    if custom_matched[i] {
+                        children.push(
+                            menu_item(custom_action.label.clone(), Action::CustomAction(i)).into(),
+                        );
+                    }
                 }
-                if matches!(tab.location, Location1::Search(..) | Location1::Recents) {
+                if matches!(
+                    tab.location,
+                    Location1::Search(..) | Location1::Flatten(..) | Location1::Recents
+                ) {
                     children.push(
                         menu_item(fl!("open-item-location"), Action::OpenItemLocation).into(),
                     );
                 }
+                if matches!(tab.location, Location1::Recents) {
+                    children.push(
+                        menu_item(fl!("remove-from-recents"), Action::RemoveFromRecents).into(),
+                    );
+                }
                 // All selected items are directories
                 if selected == selected_dir && matches!(tab.mode, tab1::Mode::App) {
                     children.push(menu_item(fl!("open-in-new-tab"), Action::OpenInNewTab).into());
@@ -177,30 +227,24 @@ pub fn context_menu1<'a>(
                 }
                 children.push(divider::horizontal::light().into());
                 children.push(menu_item(fl!("rename"), Action::Rename).into());
+                if selected > selected_dir {
+                    children
+                        .push(menu_item(fl!("change-extension"), Action::ChangeExtension).into());
+                }
                 children.push(menu_item(fl!("cut"), Action::Cut).into());
                 children.push(menu_item(fl!("copy"), Action::Copy).into());
+                if selected == 1 {
+                    children.push(menu_item(fl!("copy-as"), Action::CopyAs).into());
+                }
 
                 children.push(divider::horizontal::light().into());
-                let supported_archive_types = [
-                    "application/gzip",
-                    "application/x-compressed-tar",
-                    "application/x-tar",
-                    "application/zip",
-                    #[cfg(feature = "bzip2")]
-                    "application/x-bzip",
-                    #[cfg(feature = "bzip2")]
-                    "application/x-bzip-compressed-tar",
-                    #[cfg(feature = "liblzma")]
-                    "application/x-xz",
-                    #[cfg(feature = "liblzma")]
-                    "application/x-xz-compressed-tar",
-                ]
-                .iter()
-                .filter_map(|mime_type| mime_type.parse::<Mime>().ok())
-                .collect::<Vec<_>>();
-                selected_types.retain(|t| !supported_archive_types.contains(t));
+                selected_types.retain(|t| !EXTRACT_MIME_TYPES.contains(&t.essence_str()));
                 if selected_types.is_empty() {
                     children.push(menu_item(fl!("extract-here"), Action::ExtractHere).into());
+                    children.push(
+                        menu_item(fl!("extract-to-other-panel"), Action::ExtractToOtherPanel)
+                            .into(),
+                    );
                 }
                 children.push(menu_item(fl!("compress"), Action::Compress).into());
                 children.push(divider::horizontal::light().into());
@@ -213,10 +257,13 @@ pub fn context_menu1<'a>(
                 }
                 children.push(divider::horizontal::light().into());
                 children.push(menu_item(fl!("move-to-trash"), Action::MoveToTrash).into());
+                children
+                    .push(menu_item(fl!("delete-permanently"), Action::PermanentlyDelete).into());
                 children.push(divider::horizontal::light().into());
                 children.push(menu_item(fl!("new-tab"), Action::TabNew).into());
                 children.push(menu_item(fl!("copy-tab"), Action::CopyTab).into());
                 children.push(menu_item(fl!("move-tab"), Action::MoveTab).into());
+                children.push(menu_item(fl!("move-tab-to-window"), Action::MoveTabToWindow).into());
                 // zoom does not work!
                 children.push(divider::horizontal::light().into());
                 children.push(menu_item(fl!("zoom-in"), Action::ZoomIn).into());
@@ -235,12 +282,19 @@ pub fn context_menu1<'a>(
                 //TODO: have things like properties but they apply to the folder?
                 children.push(menu_item(fl!("new-folder"), Action::NewFolder).into());
                 children.push(menu_item(fl!("new-file"), Action::NewFile).into());
+                children.push(menu_item(fl!("new-archive"), Action::NewArchive).into());
                 children.push(menu_item(fl!("open-in-terminal"), Action::OpenTerminal).into());
                 children.push(divider::horizontal::light().into());
                 if tab.mode.multiple() {
                     children.push(menu_item(fl!("select-all"), Action::SelectAll).into());
                 }
                 children.push(menu_item(fl!("paste"), Action::Paste).into());
+                children.push(
+                    menu_item(fl!("calculate-folder-sizes"), Action::CalculateFolderSizes).into(),
+                );
+                if matches!(tab.location, Location1::Recents) {
+                    children.push(menu_item(fl!("clear-recents"), Action::ClearRecents).into());
+                }
 
                 //TODO: only show if cosmic-settings is found?
                 if matches!(tab.mode, tab1::Mode::Desktop) {
@@ -268,6 +322,7 @@ pub fn context_menu1<'a>(
                 children.push(menu_item(fl!("new-tab"), Action::TabNew).into());
                 children.push(menu_item(fl!("copy-tab"), Action::CopyTab).into());
                 children.push(menu_item(fl!("move-tab"), Action::MoveTab).into());
+                children.push(menu_item(fl!("move-tab-to-window"), Action::MoveTabToWindow).into());
 
                 children.push(divider::horizontal::light().into());
                 // TODO: Nested menu
@@ -284,13 +339,16 @@ pub fn context_menu1<'a>(
         }
         (
             tab1::Mode::Dialog(dialog_kind),
-            Location1::Desktop(..) | Location1::Path(..) | Location1::Search(..) | Location1::Recents,
+            Location1::Desktop(..) | Location1::Path(..) | Location1::Search(..) | Location1::Flatten(..) | Location1::Recents,
         ) => {
             if selected > 0 {
                 if selected_dir == 1 && selected == 1 || selected_dir == 0 {
                     children.push(menu_item(fl!("open"), Action::Open).into());
                 }
-                if matches!(tab.location, Location1::Search(..) | Location1::Recents) {
+                if matches!(
+                    tab.location,
+                    Location1::Search(..) | Location1::Flatten(..) | Location1::Recents
+                ) {
                     children.push(
                         menu_item(fl!("open-item-location"), Action::OpenItemLocation).into(),
                     );
@@ -341,6 +399,7 @@ pub fn context_menu1<'a>(
                 children.push(divider::horizontal::light().into());
                 children
                     .push(menu_item(fl!("restore-from-trash"), Action::RestoreFromTrash).into());
+                children.push(menu_item(fl!("restore-to"), Action::RestoreTo).into());
             } else {
                 // TODO: Nested menu
                 children.push(sort_item(fl!("sort-by-name"), HeadingOptions1::Name));
@@ -375,6 +434,7 @@ pub fn context_menu1<'a>(
 pub fn context_menu2<'a>(
     tab: &Tab2,
     key_binds: &HashMap<KeyBind, Action>,
+    custom_actions: &[ParsedCustomAction],
 ) -> Element<'a, tab2::Message> {
     let find_key = |action: &Action| -> String {
         for (key_bind, key_action) in key_binds.iter() {
@@ -409,17 +469,26 @@ pub fn context_menu2<'a>(
     };
 
     let mut selected_dir = 0;
+    let mut selected_symlink = 0;
     let mut selected = 0;
     let mut selected_trash_only = false;
     let mut selected_desktop_entry = None;
     let mut selected_types: Vec<Mime> = vec![];
+    // Custom actions are offered if they match all selected items
+    let mut custom_matched = vec![true; custom_actions.len()];
     if let Some(items) = tab.items_opt() {
         for item in items.iter() {
             if item.selected {
                 selected += 1;
+                for (matched, custom_action) in custom_matched.iter_mut().zip(custom_actions) {
+                    *matched = *matched && custom_action.matches(&item.name, &item.mime);
+                }
                 if item.metadata.is_dir() {
                     selected_dir += 1;
                 }
+                if item.symlink_target_opt.is_some() {
+                    selected_symlink += 1;
+                }
                 match &item.location_opt {
                     Some(Location2::Trash) => selected_trash_only = true,
                     Some(Location2::Path(path)) => {
@@ -455,7 +524,7 @@ pub fn context_menu2<'a>(
     match (&tab.mode, &tab.location) {
         (
             tab2::Mode::App | tab2::Mode::Desktop,
-            Location2::Desktop(..) | Location2::Path(..) | Location2::Search(..) | Location2::Recents,
+            Location2::Desktop(..) | Location2::Path(..) | Location2::Search(..) | Location2::Flatten(..) | Location2::Recents,
         ) => {
             if selected_trash_only {
                 children.push(menu_item(fl!("open"), Action::Open).into());
@@ -486,12 +555,32 @@ pub fn context_menu2<'a>(
                         children
                             .push(menu_item(fl!("open-in-terminal"), Action::OpenTerminal).into());
                     }
+                    if selected_symlink == 1 {
+                        children.push(
+                            menu_item(fl!("open-link-target"), Action::OpenLinkTarget).into(),
+                        );
+                    }
+                }
+                for (i, custom_action) in custom_actions.iter().enumerate() {
+                    if custom_matched[i] {
+                        children.push(
+                            menu_item(custom_action.label.clone(), Action::CustomAction(i)).into(),
+                        );
+                    }
                 }
-                if matches!(tab.location, Location2::Search(..) | Location2::Recents) {
+                if matches!(
+                    tab.location,
+                    Location2::Search(..) | Location2::Flatten(..) | Location2::Recents
+                ) {
                     children.push(
                         menu_item(fl!("open-item-location"), Action::OpenItemLocation).into(),
                     );
                 }
+                if matches!(tab.location, Location2::Recents) {
+                    children.push(
+                        menu_item(fl!("remove-from-recents"), Action::RemoveFromRecents).into(),
+                    );
+                }
                 // All selected items are directories
                 if selected == selected_dir && matches!(tab.mode, tab2::Mode::App) {
                     children.push(menu_item(fl!("open-in-new-tab"), Action::OpenInNewTab).into());
@@ -500,30 +589,24 @@ pub fn context_menu2<'a>(
                 }
                 children.push(divider::horizontal::light().into());
                 children.push(menu_item(fl!("rename"), Action::Rename).into());
+                if selected > selected_dir {
+                    children
+                        .push(menu_item(fl!("change-extension"), Action::ChangeExtension).into());
+                }
                 children.push(menu_item(fl!("cut"), Action::Cut).into());
                 children.push(menu_item(fl!("copy"), Action::Copy).into());
+                if selected == 1 {
+                    children.push(menu_item(fl!("copy-as"), Action::CopyAs).into());
+                }
 
                 children.push(divider::horizontal::light().into());
-                let supported_archive_types = [
-                    "application/gzip",
-                    "application/x-compressed-tar",
-                    "application/x-tar",
-                    "application/zip",
-                    #[cfg(feature = "bzip2")]
-                    "application/x-bzip",
-                    #[cfg(feature = "bzip2")]
-                    "application/x-bzip-compressed-tar",
-                    #[cfg(feature = "liblzma")]
-                    "application/x-xz",
-                    #[cfg(feature = "liblzma")]
-                    "application/x-xz-compressed-tar",
-                ]
-                .iter()
-                .filter_map(|mime_type| mime_type.parse::<Mime>().ok())
-                .collect::<Vec<_>>();
-                selected_types.retain(|t| !supported_archive_types.contains(t));
+                selected_types.retain(|t| !EXTRACT_MIME_TYPES.contains(&t.essence_str()));
                 if selected_types.is_empty() {
                     children.push(menu_item(fl!("extract-here"), Action::ExtractHere).into());
+                    children.push(
+                        menu_item(fl!("extract-to-other-panel"), Action::ExtractToOtherPanel)
+                            .into(),
+                    );
                 }
                 children.push(menu_item(fl!("compress"), Action::Compress).into());
                 children.push(divider::horizontal::light().into());
@@ -536,6 +619,8 @@ pub fn context_menu2<'a>(
                 }
                 children.push(divider::horizontal::light().into());
                 children.push(menu_item(fl!("move-to-trash"), Action::MoveToTrash).into());
+                children
+                    .push(menu_item(fl!("delete-permanently"), Action::PermanentlyDelete).into());
                 // zoom does not work!
                 children.push(divider::horizontal::light().into());
                 children.push(menu_item(fl!("zoom-in"), Action::ZoomIn).into());
@@ -553,17 +638,25 @@ pub fn context_menu2<'a>(
                 children.push(menu_item(fl!("new-tab"), Action::TabNew).into());
                 children.push(menu_item(fl!("copy-tab"), Action::CopyTab).into());
                 children.push(menu_item(fl!("move-tab"), Action::MoveTab).into());
+                children.push(menu_item(fl!("move-tab-to-window"), Action::MoveTabToWindow).into());
             } else {
                 //TODO: need better designs for menu with no selection
                 //TODO: have things like properties but they apply to the folder?
                 children.push(menu_item(fl!("new-folder"), Action::NewFolder).into());
                 children.push(menu_item(fl!("new-file"), Action::NewFile).into());
+                children.push(menu_item(fl!("new-archive"), Action::NewArchive).into());
                 children.push(menu_item(fl!("open-in-terminal"), Action::OpenTerminal).into());
                 children.push(divider::horizontal::light().into());
                 if tab.mode.multiple() {
                     children.push(menu_item(fl!("select-all"), Action::SelectAll).into());
                 }
                 children.push(menu_item(fl!("paste"), Action::Paste).into());
+                children.push(
+                    menu_item(fl!("calculate-folder-sizes"), Action::CalculateFolderSizes).into(),
+                );
+                if matches!(tab.location, Location2::Recents) {
+                    children.push(menu_item(fl!("clear-recents"), Action::ClearRecents).into());
+                }
 
                 //TODO: only show if cosmic-settings is found?
                 if matches!(tab.mode, tab2::Mode::Desktop) {
@@ -583,6 +676,7 @@ pub fn context_menu2<'a>(
                 children.push(menu_item(fl!("new-tab"), Action::TabNew).into());
                 children.push(menu_item(fl!("copy-tab"), Action::CopyTab).into());
                 children.push(menu_item(fl!("move-tab"), Action::MoveTab).into());
+                children.push(menu_item(fl!("move-tab-to-window"), Action::MoveTabToWindow).into());
                 // zoom does not work!
                 children.push(divider::horizontal::light().into());
                 children.push(menu_item(fl!("zoom-in"), Action::ZoomIn).into());
@@ -606,13 +700,16 @@ pub fn context_menu2<'a>(
         }
         (
             tab2::Mode::Dialog(dialog_kind),
-            Location2::Desktop(..) | Location2::Path(..) | Location2::Search(..) | Location2::Recents,
+            Location2::Desktop(..) | Location2::Path(..) | Location2::Search(..) | Location2::Flatten(..) | Location2::Recents,
         ) => {
             if selected > 0 {
                 if selected_dir == 1 && selected == 1 || selected_dir == 0 {
                     children.push(menu_item(fl!("open"), Action::Open).into());
                 }
-                if matches!(tab.location, Location2::Search(..) | Location2::Recents) {
+                if matches!(
+                    tab.location,
+                    Location2::Search(..) | Location2::Flatten(..) | Location2::Recents
+                ) {
                     children.push(
                         menu_item(fl!("open-item-location"), Action::OpenItemLocation).into(),
                     );
@@ -663,6 +760,7 @@ pub fn context_menu2<'a>(
                 children.push(divider::horizontal::light().into());
                 children
                     .push(menu_item(fl!("restore-from-trash"), Action::RestoreFromTrash).into());
+                children.push(menu_item(fl!("restore-to"), Action::RestoreTo).into());
             } else {
                 // TODO: Nested menu
                 children.push(sort_item(fl!("sort-by-name"), HeadingOptions2::Name));
@@ -729,6 +827,8 @@ pub fn context_menu_term<'a>(
     widget::container(column!(
         menu_item(fl!("copy"), Action::CopyTerminal),
         menu_item(fl!("paste"), Action::PasteTerminal),
+        menu_item(fl!("clear-scrollback"), Action::ClearScrollback),
+        menu_item(fl!("reset-terminal"), Action::ResetTerminal),
     ))
     .padding(1)
     //TODO: move style to libcosmic
@@ -765,6 +865,14 @@ pub fn dialog_menu1(
             Action::SetSort(sort, dir),
         )
     };
+    let sort_secondary_item = |label, sort| {
+        menu::Item::CheckBox(
+            label,
+            None,
+            tab.config.sort_secondary == sort,
+            Action::SetSortSecondary(sort),
+        )
+    };
     let in_trash = tab.location == Location1::Trash;
 
     let mut selected_gallery = 0;
@@ -846,6 +954,17 @@ pub fn dialog_menu1(
                         false,
                     ),
                     //TODO: sort by type
+                    menu::Item::Divider,
+                    sort_secondary_item(fl!("sort-then-by-name"), tab1::HeadingOptions::Name),
+                    sort_secondary_item(
+                        fl!("sort-then-by-modified"),
+                        if in_trash {
+                            tab1::HeadingOptions::TrashedOn
+                        } else {
+                            tab1::HeadingOptions::Modified
+                        },
+                    ),
+                    sort_secondary_item(fl!("sort-then-by-size"), tab1::HeadingOptions::Size),
                 ],
             ),
         ),
@@ -893,6 +1012,8 @@ pub fn dialog_menu1(
 pub fn menu_bar<'a>(
     tab_opt: Option<&Tab1>,
     config: &Config,
+    mirror_navigation: bool,
+    respect_gitignore: bool,
     key_binds: &HashMap<KeyBind, Action>,
 ) -> Element<'a, Message> {
     let sort_options = tab_opt.map(|tab| tab.sort_options());
@@ -906,6 +1027,14 @@ pub fn menu_bar<'a>(
             Action::SetSort(sort, dir),
         )
     };
+    let sort_secondary_item = |label, sort| {
+        menu::Item::CheckBox(
+            label,
+            None,
+            tab_opt.map_or(false, |tab| tab.config.sort_secondary == sort),
+            Action::SetSortSecondary(sort),
+        )
+    };
     let in_trash = tab_opt.map_or(false, |tab| tab.location == Location1::Trash);
 
     let mut selected_dir = 0;
@@ -925,53 +1054,88 @@ pub fn menu_bar<'a>(
         }
     };
 
+    let mut file_items = vec![
+        menu::Item::Button(fl!("new-tab"), None, Action::TabNew),
+        menu::Item::Button(fl!("copy-tab"), None, Action::TabNew),
+        menu::Item::Button(fl!("move-tab"), None, Action::TabNew),
+        menu::Item::Button(fl!("move-tab-to-window"), None, Action::MoveTabToWindow),
+        menu::Item::Divider,
+        menu::Item::Button(fl!("new-window"), None, Action::WindowNew),
+        menu::Item::Button(fl!("duplicate-window"), None, Action::DuplicateWindow),
+        menu::Item::Button(fl!("new-folder"), None, Action::NewFolder),
+        menu::Item::Button(fl!("new-file"), None, Action::NewFile),
+        menu::Item::Button(fl!("new-archive"), None, Action::NewArchive),
+        menu::Item::Button(fl!("finalize-archive"), None, Action::FinalizeArchive),
+        menu_button_optional(
+            fl!("open"),
+            Action::Open,
+            (selected > 0 && selected_dir == 0) || (selected_dir == 1 && selected == 1),
+        ),
+        menu_button_optional(fl!("menu-open-with"), Action::OpenWith, selected == 1),
+    ];
+    // Only offered once enabled in the settings
+    if config.allow_open_as_root {
+        file_items.push(menu_button_optional(
+            fl!("open-as-root"),
+            Action::OpenAsRoot,
+            selected == 1,
+        ));
+    }
+    file_items.extend([
+        menu::Item::Button(fl!("recent-files"), None, Action::RecentFilesMenu),
+        menu_button_optional(fl!("compare-files"), Action::DiffSelected, selected > 0),
+        menu::Item::Divider,
+        menu_button_optional(fl!("rename"), Action::F2Rename, selected > 0),
+        menu_button_optional(
+            fl!("change-extension"),
+            Action::ChangeExtension,
+            selected > selected_dir,
+        ),
+        menu_button_optional(fl!("f5-copy"), Action::F5Copy, selected > 0),
+        menu_button_optional(fl!("f6-move"), Action::F6Move, selected > 0),
+        menu_button_optional(fl!("copy-to"), Action::CopyTo, selected > 0),
+        menu_button_optional(fl!("copy-as"), Action::CopyAs, selected == 1),
+        menu_button_optional(fl!("move-to"), Action::MoveTo, selected > 0),
+        menu_button_optional(fl!("set-modified-time"), Action::SetModified, selected > 0),
+        menu::Item::Divider,
+        menu_button_optional(fl!("add-to-sidebar"), Action::AddToSidebar, selected > 0),
+        menu::Item::Divider,
+        menu_button_optional(fl!("move-to-trash"), Action::MoveToTrash, selected > 0),
+        menu_button_optional(
+            fl!("delete-permanently"),
+            Action::PermanentlyDelete,
+            selected > 0,
+        ),
+        menu::Item::Divider,
+        menu::Item::Button(fl!("close-tab"), None, Action::TabClose),
+        menu::Item::Button(fl!("quit"), None, Action::WindowClose),
+    ]);
+
+    let edit_items = read_only_items(
+        vec![
+            menu::Item::Button(fl!("undo"), None, Action::Undo),
+            menu::Item::Button(fl!("redo"), None, Action::Redo),
+            menu::Item::Divider,
+            menu_button_optional(fl!("cut"), Action::Cut, selected > 0),
+            menu_button_optional(fl!("copy"), Action::Copy, selected > 0),
+            menu_button_optional(fl!("paste"), Action::Paste, selected > 0),
+            menu::Item::Button(fl!("select-all"), None, Action::SelectAll),
+            menu::Item::Button(fl!("select-by-pattern"), None, Action::SelectByPattern),
+            menu::Item::Button(fl!("deselect-by-pattern"), None, Action::DeselectByPattern),
+            menu::Item::Button(fl!("select-by-criteria"), None, Action::SelectByCriteria),
+            menu::Item::Button(fl!("mirror-selection"), None, Action::MirrorSelection),
+            menu::Item::Divider,
+            menu::Item::Button(fl!("history"), None, Action::EditHistory),
+        ],
+        config.read_only,
+    );
+
     MenuBar::new(vec![
         menu::Tree::with_children(
             menu::root(fl!("file")),
-            menu::items(
-                key_binds,
-                vec![
-                    menu::Item::Button(fl!("new-tab"), None, Action::TabNew),
-                    menu::Item::Button(fl!("copy-tab"), None, Action::TabNew),
-                    menu::Item::Button(fl!("move-tab"), None, Action::TabNew),
-                    menu::Item::Divider,
-                    menu::Item::Button(fl!("new-window"), None, Action::WindowNew),
-                    menu::Item::Button(fl!("new-folder"), None, Action::NewFolder),
-                    menu::Item::Button(fl!("new-file"), None, Action::NewFile),
-                    menu_button_optional(
-                        fl!("open"),
-                        Action::Open,
-                        (selected > 0 && selected_dir == 0) || (selected_dir == 1 && selected == 1),
-                    ),
-                    menu_button_optional(fl!("menu-open-with"), Action::OpenWith, selected == 1),
-                    menu::Item::Divider,
-                    menu_button_optional(fl!("rename"), Action::F2Rename, selected > 0),
-                    menu_button_optional(fl!("f5-copy"), Action::F5Copy, selected > 0),
-                    menu_button_optional(fl!("f6-move"), Action::F6Move, selected > 0),
-                    menu::Item::Divider,
-                    menu_button_optional(fl!("add-to-sidebar"), Action::AddToSidebar, selected > 0),
-                    menu::Item::Divider,
-                    menu_button_optional(fl!("move-to-trash"), Action::MoveToTrash, selected > 0),
-                    menu::Item::Divider,
-                    menu::Item::Button(fl!("close-tab"), None, Action::TabClose),
-                    menu::Item::Button(fl!("quit"), None, Action::WindowClose),
-                ],
-            ),
-        ),
-        menu::Tree::with_children(
-            menu::root(fl!("edit")),
-            menu::items(
-                key_binds,
-                vec![
-                    menu_button_optional(fl!("cut"), Action::Cut, selected > 0),
-                    menu_button_optional(fl!("copy"), Action::Copy, selected > 0),
-                    menu_button_optional(fl!("paste"), Action::Paste, selected > 0),
-                    menu::Item::Button(fl!("select-all"), None, Action::SelectAll),
-                    menu::Item::Divider,
-                    menu::Item::Button(fl!("history"), None, Action::EditHistory),
-                ],
-            ),
+            menu::items(key_binds, read_only_items(file_items, config.read_only)),
         ),
+        menu::Tree::with_children(menu::root(fl!("edit")), menu::items(key_binds, edit_items)),
         menu::Tree::with_children(
             menu::root(fl!("view")),
             menu::items(
@@ -1006,20 +1170,59 @@ pub fn menu_bar<'a>(
                         tab_opt.map_or(false, |tab| tab.config.folders_first),
                         Action::ToggleFoldersFirst,
                     ),
+                    menu::Item::CheckBox(
+                        fl!("flatten-view"),
+                        None,
+                        tab_opt.map_or(false, |tab| matches!(tab.location, Location1::Flatten(..))),
+                        Action::ToggleFlattenView,
+                    ),
                     menu::Item::CheckBox(
                         fl!("show-details"),
                         None,
                         config.show_details,
                         Action::Preview,
                     ),
+                    menu::Item::Button(
+                        fl!("calculate-folder-sizes"),
+                        None,
+                        Action::CalculateFolderSizes,
+                    ),
+                    menu::Item::Button(
+                        fl!("cancel-folder-sizes"),
+                        None,
+                        Action::CancelFolderSizes,
+                    ),
                     menu::Item::Divider,
+                    menu::Item::CheckBox(
+                        fl!("mirror-navigation"),
+                        None,
+                        mirror_navigation,
+                        Action::ToggleMirrorNavigation,
+                    ),
+                    menu::Item::CheckBox(
+                        fl!("respect-gitignore"),
+                        None,
+                        respect_gitignore,
+                        Action::ToggleRespectGitignore,
+                    ),
+                    menu::Item::CheckBox(
+                        fl!("read-only-mode"),
+                        None,
+                        config.read_only,
+                        Action::ToggleReadOnly,
+                    ),
+                    menu::Item::Divider,
+                    menu_button_optional(fl!("quick-preview"), Action::QuickPreview, selected > 0),
                     menu_button_optional(
                         fl!("gallery-preview"),
                         Action::Gallery,
                         selected_gallery > 0,
                     ),
                     menu::Item::Divider,
+                    menu::Item::Button(fl!("command-palette"), None, Action::CommandPalette),
                     menu::Item::Button(fl!("menu-settings"), None, Action::Settings),
+                    menu::Item::Button(fl!("import-settings"), None, Action::ImportSettings),
+                    menu::Item::Button(fl!("export-settings"), None, Action::ExportSettings),
                     menu::Item::Divider,
                     menu::Item::Button(fl!("menu-about"), None, Action::About),
                 ],
@@ -1061,6 +1264,17 @@ pub fn menu_bar<'a>(
                         false,
                     ),
                     //TODO: sort by type
+                    menu::Item::Divider,
+                    sort_secondary_item(fl!("sort-then-by-name"), tab1::HeadingOptions::Name),
+                    sort_secondary_item(
+                        fl!("sort-then-by-modified"),
+                        if in_trash {
+                            tab1::HeadingOptions::TrashedOn
+                        } else {
+                            tab1::HeadingOptions::Modified
+                        },
+                    ),
+                    sort_secondary_item(fl!("sort-then-by-size"), tab1::HeadingOptions::Size),
                 ],
             ),
         ),
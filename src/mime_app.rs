@@ -6,22 +6,42 @@ use cosmic::desktop;
 use cosmic::widget;
 pub use mime_guess::Mime;
 use std::{
-    cmp::Ordering, collections::HashMap, env, ffi::OsString, fs, io, path::PathBuf, process,
+    cmp::Ordering, collections::HashMap, env, ffi::OsString, fs, io, path::PathBuf, process, slice,
     time::Instant,
 };
 
 pub fn exec_to_command(exec: &str, path_opt: Option<OsString>) -> Option<process::Command> {
-    let args_vec: Vec<String> = shlex::split(exec)?;
-    let mut args = args_vec.iter();
+    let args: Vec<String> = shlex::split(exec)?;
+    args_to_command(exec, &args, &Vec::from_iter(path_opt))
+}
+
+/// Commands opening all of the paths, a single one if the Exec line takes several files and one
+/// per path otherwise
+pub fn exec_to_commands(exec: &str, paths: &[OsString]) -> Option<Vec<process::Command>> {
+    let args: Vec<String> = shlex::split(exec)?;
+    if paths.len() < 2 || args.iter().any(|arg| arg == "%F" || arg == "%U") {
+        return Some(vec![args_to_command(exec, &args, paths)?]);
+    }
+    paths
+        .iter()
+        .map(|path| args_to_command(exec, &args, slice::from_ref(path)))
+        .collect()
+}
+
+fn args_to_command(exec: &str, args: &[String], paths: &[OsString]) -> Option<process::Command> {
+    let mut args = args.iter();
     let mut command = process::Command::new(args.next()?);
     for arg in args {
         if arg.starts_with('%') {
             match arg.as_str() {
-                "%f" | "%F" | "%u" | "%U" => {
-                    if let Some(path) = &path_opt {
+                "%f" | "%u" => {
+                    if let Some(path) = paths.first() {
                         command.arg(path);
                     }
                 }
+                "%F" | "%U" => {
+                    command.args(paths);
+                }
                 _ => {
                     log::warn!("unsupported Exec code {:?} in {:?}", arg, exec);
                     return None;
@@ -45,10 +65,15 @@ pub struct MimeApp {
 }
 
 impl MimeApp {
-    //TODO: move to libcosmic, support multiple files
+    //TODO: move to libcosmic
     pub fn command(&self, path_opt: Option<OsString>) -> Option<process::Command> {
         exec_to_command(self.exec.as_deref()?, path_opt)
     }
+
+    /// Commands opening all of the paths, see [`exec_to_commands`]
+    pub fn commands(&self, paths: &[OsString]) -> Option<Vec<process::Command>> {
+        exec_to_commands(self.exec.as_deref()?, paths)
+    }
 }
 
 // This allows usage of MimeApp in a dropdown
@@ -347,6 +372,61 @@ impl MimeAppCache {
             }
         }
     }
+
+    #[cfg(not(feature = "desktop"))]
+    pub fn clear_default(&mut self, mime: Mime) {
+        log::warn!("failed to clear default handler for {mime:?}: desktop feature not enabled");
+    }
+
+    /// Remove the user's default handler for a mime type, so the system default applies again
+    #[cfg(feature = "desktop")]
+    pub fn clear_default(&mut self, mime: Mime) {
+        let Some(path) = cosmic_mime_apps::local_list_path() else {
+            log::warn!("failed to find mimeapps.list path");
+            return;
+        };
+
+        let string = match fs::read_to_string(&path) {
+            Ok(ok) => ok,
+            Err(err) => {
+                if err.kind() != io::ErrorKind::NotFound {
+                    log::warn!("failed to read {path:?}: {err}");
+                }
+                return;
+            }
+        };
+
+        match fs::write(&path, remove_default(&string, &mime)) {
+            Ok(()) => {
+                self.reload();
+            }
+            Err(err) => {
+                log::warn!("failed to write {path:?}: {err}");
+            }
+        }
+    }
+}
+
+// Remove the entry for a mime type from the Default Applications section of a mimeapps.list
+#[cfg(feature = "desktop")]
+fn remove_default(string: &str, mime: &Mime) -> String {
+    let mut in_defaults = false;
+    let mut output = String::with_capacity(string.len());
+    for line in string.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_defaults = trimmed == "[Default Applications]";
+        } else if in_defaults {
+            if let Some((key, _)) = trimmed.split_once('=') {
+                if key.trim() == mime.essence_str() {
+                    continue;
+                }
+            }
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    output
 }
 
 impl Default for MimeAppCache {
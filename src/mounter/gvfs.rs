@@ -6,7 +6,7 @@ use gio::{glib, prelude::*};
 use std::{any::TypeId, cell::Cell, future::pending, path::PathBuf, sync::Arc};
 use tokio::sync::{mpsc, Mutex};
 
-use super::{Mounter, MounterAuth, MounterItem, MounterItems, MounterMessage};
+use super::{Mounter, MounterAuth, MounterItem, MounterItems, MounterMessage, UnmountError};
 use crate::{
     config::IconSizes,
     err_str,
@@ -26,6 +26,22 @@ fn gio_icon_to_path(icon: &gio::Icon, size: u16) -> Option<PathBuf> {
     None
 }
 
+// Fixed internal disks and loop devices, like those of packages, are system filesystems
+fn is_system(drive_opt: Option<gio::Drive>, volume_opt: Option<&gio::Volume>) -> bool {
+    if let Some(volume) = volume_opt {
+        if volume.can_eject() {
+            return false;
+        }
+        if volume
+            .identifier("unix-device")
+            .is_some_and(|device| device.starts_with("/dev/loop"))
+        {
+            return true;
+        }
+    }
+    drive_opt.is_some_and(|drive| !drive.is_media_removable() && !drive.can_eject())
+}
+
 fn items(monitor: &gio::VolumeMonitor, sizes: IconSizes) -> MounterItems {
     let mut items = MounterItems::new();
     for (i, mount) in monitor.mounts().into_iter().enumerate() {
@@ -37,6 +53,8 @@ fn items(monitor: &gio::VolumeMonitor, sizes: IconSizes) -> MounterItems {
             icon_opt: gio_icon_to_path(&MountExt::icon(&mount), sizes.grid()),
             icon_symbolic_opt: gio_icon_to_path(&MountExt::symbolic_icon(&mount), 16),
             path_opt: MountExt::root(&mount).path(),
+            is_system: !MountExt::can_eject(&mount)
+                && is_system(MountExt::drive(&mount), MountExt::volume(&mount).as_ref()),
         }));
     }
     for (i, volume) in monitor.volumes().into_iter().enumerate() {
@@ -52,6 +70,7 @@ fn items(monitor: &gio::VolumeMonitor, sizes: IconSizes) -> MounterItems {
             icon_opt: gio_icon_to_path(&VolumeExt::icon(&volume), sizes.grid()),
             icon_symbolic_opt: gio_icon_to_path(&VolumeExt::symbolic_icon(&volume), 16),
             path_opt: None,
+            is_system: is_system(VolumeExt::drive(&volume), Some(&volume)),
         }));
     }
     items
@@ -122,6 +141,7 @@ fn network_scan(uri: &str, sizes: IconSizes) -> Result<Vec<tab1::Item>, String>
             display_name,
             metadata,
             hidden: false,
+            symlink_target_opt: None,
             location_opt: Some(location),
             mime,
             icon_handle_grid,
@@ -134,6 +154,9 @@ fn network_scan(uri: &str, sizes: IconSizes) -> Result<Vec<tab1::Item>, String>
             selected: false,
             highlighted: false,
             overlaps_drag_rect: false,
+            permissions_edit_opt: None,
+            symlink_target_edit_opt: None,
+            checksum_opt: None,
             //TODO: scan directory size on gvfs mounts?
             dir_size: DirSize::NotDirectory,
         });
@@ -217,6 +240,7 @@ enum Event {
     MountResult(MounterItem, Result<bool, String>),
     NetworkAuth(String, MounterAuth, mpsc::Sender<MounterAuth>),
     NetworkResult(String, Result<bool, String>),
+    UnmountResult(MounterItem, Result<bool, UnmountError>),
 }
 
 #[derive(Clone, Debug)]
@@ -235,6 +259,7 @@ pub struct Item {
     icon_opt: Option<PathBuf>,
     icon_symbolic_opt: Option<PathBuf>,
     path_opt: Option<PathBuf>,
+    is_system: bool,
 }
 
 impl Item {
@@ -258,6 +283,10 @@ impl Item {
     pub fn path(&self) -> Option<PathBuf> {
         self.path_opt.clone()
     }
+
+    pub fn is_system(&self) -> bool {
+        self.is_system
+    }
 }
 
 pub struct Gvfs {
@@ -328,7 +357,11 @@ impl Gvfs {
                         }
                         Cmd::Mount(mounter_item) => {
                             let MounterItem::Gvfs(ref item) = mounter_item else { continue };
-                            let ItemKind::Volume = item.kind else { continue };
+                            if !matches!(item.kind, ItemKind::Volume) {
+                                // Nothing to wait for, so the item is no longer shown as busy
+                                event_tx.send(Event::MountResult(mounter_item, Ok(false))).unwrap();
+                                continue;
+                            }
                             for (i, volume) in monitor.volumes().into_iter().enumerate() {
                                 if i != item.index {
                                     continue;
@@ -415,8 +448,12 @@ impl Gvfs {
                             }
                         }
                         Cmd::Unmount(mounter_item) => {
-                            let MounterItem::Gvfs(item) = mounter_item else { continue };
-                            let ItemKind::Mount = item.kind else { continue };
+                            let MounterItem::Gvfs(ref item) = mounter_item else { continue };
+                            if !matches!(item.kind, ItemKind::Mount) {
+                                // Nothing to wait for, so the item is no longer shown as busy
+                                event_tx.send(Event::UnmountResult(mounter_item, Ok(false))).unwrap();
+                                continue;
+                            }
                             for (i, mount) in monitor.mounts().into_iter().enumerate() {
                                 if i != item.index {
                                     continue;
@@ -429,6 +466,8 @@ impl Gvfs {
                                 }
 
                                 log::info!("unmount {}", name);
+                                let event_tx = event_tx.clone();
+                                let mounter_item = mounter_item.clone();
                                 MountExt::eject_with_operation(
                                     &mount,
                                     gio::MountUnmountFlags::NONE,
@@ -436,6 +475,14 @@ impl Gvfs {
                                     gio::Cancellable::NONE,
                                     move |result| {
                                         log::info!("unmount {}: result {:?}", name, result);
+                                        event_tx.send(Event::UnmountResult(mounter_item, match result {
+                                            Ok(()) => Ok(true),
+                                            Err(err) => match err.kind::<gio::IOErrorEnum>() {
+                                                Some(gio::IOErrorEnum::FailedHandled) => Ok(false),
+                                                Some(gio::IOErrorEnum::Busy) => Err(UnmountError::Busy(format!("{}", err))),
+                                                _ => Err(UnmountError::Failed(format!("{}", err)))
+                                            }
+                                        })).unwrap();
                                     },
                                 );
                             }
@@ -479,6 +526,11 @@ impl Mounter for Gvfs {
         )
     }
 
+    fn network_schemes(&self) -> &'static [&'static str] {
+        // Used for any scheme without a dedicated mounter
+        &[]
+    }
+
     fn network_scan(&self, uri: &str, sizes: IconSizes) -> Option<Result<Vec<tab1::Item>, String>> {
         let (items_tx, mut items_rx) = mpsc::channel(1);
         self.command_tx
@@ -522,6 +574,10 @@ impl Mounter for Gvfs {
                             .send(MounterMessage::NetworkResult(uri, res))
                             .await
                             .unwrap(),
+                        Event::UnmountResult(item, res) => output
+                            .send(MounterMessage::UnmountResult(item, res))
+                            .await
+                            .unwrap(),
                     }
                 }
                 pending().await
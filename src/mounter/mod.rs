@@ -8,6 +8,9 @@ use crate::{config::IconSizes, tab1};
 #[cfg(feature = "gvfs")]
 mod gvfs;
 
+#[cfg(feature = "sftp")]
+mod sftp;
+
 #[derive(Clone)]
 pub struct MounterAuth {
     pub message: String,
@@ -42,6 +45,8 @@ impl fmt::Debug for MounterAuth {
 pub enum MounterItem {
     #[cfg(feature = "gvfs")]
     Gvfs(gvfs::Item),
+    #[cfg(feature = "sftp")]
+    Sftp(sftp::Item),
     #[allow(dead_code)]
     None,
 }
@@ -51,6 +56,8 @@ impl MounterItem {
         match self {
             #[cfg(feature = "gvfs")]
             Self::Gvfs(item) => item.name(),
+            #[cfg(feature = "sftp")]
+            Self::Sftp(item) => item.name(),
             Self::None => unreachable!(),
         }
     }
@@ -59,6 +66,8 @@ impl MounterItem {
         match self {
             #[cfg(feature = "gvfs")]
             Self::Gvfs(item) => item.is_mounted(),
+            #[cfg(feature = "sftp")]
+            Self::Sftp(item) => item.is_mounted(),
             Self::None => unreachable!(),
         }
     }
@@ -67,6 +76,8 @@ impl MounterItem {
         match self {
             #[cfg(feature = "gvfs")]
             Self::Gvfs(item) => item.icon(symbolic),
+            #[cfg(feature = "sftp")]
+            Self::Sftp(item) => item.icon(symbolic),
             Self::None => unreachable!(),
         }
     }
@@ -75,6 +86,20 @@ impl MounterItem {
         match self {
             #[cfg(feature = "gvfs")]
             Self::Gvfs(item) => item.path(),
+            #[cfg(feature = "sftp")]
+            Self::Sftp(item) => item.path(),
+            Self::None => unreachable!(),
+        }
+    }
+
+    /// Whether the item is a system filesystem, like an internal disk or a loop device, instead
+    /// of a removable, remote or user mountable one
+    pub fn is_system(&self) -> bool {
+        match self {
+            #[cfg(feature = "gvfs")]
+            Self::Gvfs(item) => item.is_system(),
+            #[cfg(feature = "sftp")]
+            Self::Sftp(item) => item.is_system(),
             Self::None => unreachable!(),
         }
     }
@@ -82,12 +107,21 @@ impl MounterItem {
 
 pub type MounterItems = Vec<MounterItem>;
 
+/// Why an item could not be unmounted
+#[derive(Clone, Debug)]
+pub enum UnmountError {
+    /// Files on the device are still in use
+    Busy(String),
+    Failed(String),
+}
+
 #[derive(Clone, Debug)]
 pub enum MounterMessage {
     Items(MounterItems),
     MountResult(MounterItem, Result<bool, String>),
     NetworkAuth(String, MounterAuth, mpsc::Sender<MounterAuth>),
     NetworkResult(String, Result<bool, String>),
+    UnmountResult(MounterItem, Result<bool, UnmountError>),
 }
 
 pub trait Mounter: Send + Sync {
@@ -95,6 +129,8 @@ pub trait Mounter: Send + Sync {
     //TODO: send result
     fn mount(&self, item: MounterItem) -> Task<()>;
     fn network_drive(&self, uri: String) -> Task<()>;
+    /// URI schemes this mounter is preferred for when connecting to a network drive
+    fn network_schemes(&self) -> &'static [&'static str];
     fn network_scan(&self, uri: &str, sizes: IconSizes) -> Option<Result<Vec<tab1::Item>, String>>;
     fn unmount(&self, item: MounterItem) -> Task<()>;
     fn subscription(&self) -> Subscription<MounterMessage>;
@@ -114,6 +150,11 @@ pub fn mounters() -> Mounters {
         mounters.insert(MounterKey("gvfs"), Box::new(gvfs::Gvfs::new()));
     }
 
+    #[cfg(feature = "sftp")]
+    {
+        mounters.insert(MounterKey("sftp"), Box::new(sftp::Sftp::new()));
+    }
+
     Mounters::new(mounters)
 }
 
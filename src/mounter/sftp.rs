@@ -0,0 +1,808 @@
+use base64::{
+    prelude::{BASE64_STANDARD, BASE64_STANDARD_NO_PAD},
+    Engine,
+};
+use cosmic::{
+    iced::{futures::SinkExt, stream, Subscription},
+    widget, Task,
+};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use ssh2::{
+    Channel, CheckResult, ErrorCode, ExtendedData, HashType, HostKeyType,
+    KeyboardInteractivePrompt, KnownHostFileKind, Prompt, Session,
+};
+use std::{
+    any::TypeId,
+    env,
+    ffi::OsString,
+    fs,
+    future::pending,
+    io::{self, Read, Write},
+    net::TcpStream,
+    os::unix::{ffi::OsStringExt, io::AsRawFd},
+    path::PathBuf,
+    process::{ChildStdin, ChildStdout, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc as std_mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::sync::{mpsc, Mutex as TokioMutex};
+
+use super::{Mounter, MounterAuth, MounterItem, MounterItems, MounterMessage, UnmountError};
+use crate::{config::IconSizes, err_str, fl, tab1};
+
+// libssh2 fails with this when a key file can't be read, like when it needs a passphrase
+const LIBSSH2_ERROR_FILE: i32 = -16;
+
+// Time sshfs gets to mount the remote folder once connected
+const MOUNT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Escaped in remote paths to make them a single folder name
+const MOUNT_NAME_SET: &AsciiSet = &CONTROLS.add(b'/').add(b'%');
+
+enum Event {
+    Changed,
+    NetworkAuth(String, MounterAuth, mpsc::Sender<MounterAuth>),
+    NetworkResult(String, Result<bool, String>),
+    UnmountResult(MounterItem, Result<bool, UnmountError>),
+}
+
+struct Remote {
+    host: String,
+    port: u16,
+    username: String,
+    path: String,
+}
+
+impl Remote {
+    fn parse(uri: &str) -> Result<Self, String> {
+        let url = url::Url::parse(uri).map_err(err_str)?;
+        if !matches!(url.scheme(), "sftp" | "ssh") {
+            return Err(format!("unsupported scheme {:?}", url.scheme()));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("missing host in {:?}", uri))?
+            .to_string();
+        // Like ssh, log in with the local user name if none is given
+        let username = if url.username().is_empty() {
+            uzers::get_current_username()
+                .map(|name| name.to_string_lossy().into_owned())
+                .ok_or_else(|| "failed to get the local user name".to_string())?
+        } else {
+            url.username().to_string()
+        };
+        let path = percent_decode_str(url.path())
+            .decode_utf8()
+            .map_err(|err| format!("invalid path in {:?}: {}", uri, err))?;
+        // Trailing slashes are dropped so that both spellings use the same mount
+        let path = match path.trim_end_matches('/') {
+            "" => "/".to_string(),
+            path => path.to_string(),
+        };
+        Ok(Self {
+            host,
+            port: url.port().unwrap_or(22),
+            username,
+            path,
+        })
+    }
+
+    fn name(&self) -> String {
+        let mut name = format!("{}@{}", self.username, self.host);
+        if self.port != 22 {
+            name.push_str(&format!(":{}", self.port));
+        }
+        name
+    }
+
+    /// Host as written in known_hosts files
+    fn known_host(&self) -> String {
+        if self.port == 22 {
+            self.host.clone()
+        } else {
+            format!("[{}]:{}", self.host, self.port)
+        }
+    }
+
+    /// Each remote folder gets its own mount, so the path is part of the folder name
+    fn mount_dir(&self) -> PathBuf {
+        mount_base_dir().join(format!(
+            "{}{}",
+            self.name(),
+            utf8_percent_encode(&self.path, MOUNT_NAME_SET)
+        ))
+    }
+}
+
+/// Folder holding the folders remote locations are mounted on
+fn mount_base_dir() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(env::temp_dir)
+        .join("commander-sftp")
+}
+
+/// Show the authentication dialog and wait for it, returns None if it was cancelled
+fn ask(
+    uri: &str,
+    auth: MounterAuth,
+    event_tx: &mpsc::UnboundedSender<Event>,
+) -> Result<Option<MounterAuth>, String> {
+    let (auth_tx, mut auth_rx) = mpsc::channel(1);
+    event_tx
+        .send(Event::NetworkAuth(uri.to_string(), auth, auth_tx))
+        .map_err(err_str)?;
+    Ok(auth_rx.blocking_recv())
+}
+
+fn message_auth(message: String, password_opt: Option<String>) -> MounterAuth {
+    MounterAuth {
+        message,
+        username_opt: None,
+        domain_opt: None,
+        password_opt,
+        remember_opt: None,
+        anonymous_opt: None,
+    }
+}
+
+fn known_hosts_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ssh").join("known_hosts"))
+}
+
+fn key_type_name(key_type: HostKeyType) -> Option<&'static str> {
+    match key_type {
+        HostKeyType::Rsa => Some("ssh-rsa"),
+        HostKeyType::Dss => Some("ssh-dss"),
+        HostKeyType::Ecdsa256 => Some("ecdsa-sha2-nistp256"),
+        HostKeyType::Ecdsa384 => Some("ecdsa-sha2-nistp384"),
+        HostKeyType::Ecdsa521 => Some("ecdsa-sha2-nistp521"),
+        HostKeyType::Ed25519 => Some("ssh-ed25519"),
+        HostKeyType::Unknown => None,
+    }
+}
+
+/// Check the host key against the known hosts of the user. Unknown keys are shown in the
+/// authentication dialog and added to the known hosts once accepted, like ssh does.
+fn verify_host_key(
+    session: &Session,
+    remote: &Remote,
+    uri: &str,
+    event_tx: &mpsc::UnboundedSender<Event>,
+) -> Result<bool, String> {
+    let (key, key_type) = session
+        .host_key()
+        .ok_or_else(|| format!("{} sent no host key", remote.host))?;
+    let mut known_hosts = session.known_hosts().map_err(err_str)?;
+    let path_opt = known_hosts_path();
+    if let Some(path) = &path_opt {
+        // A missing file only means that no hosts are known yet
+        let _ = known_hosts.read_file(path, KnownHostFileKind::OpenSSH);
+    }
+    match known_hosts.check_port(&remote.host, remote.port, key) {
+        CheckResult::Match => return Ok(true),
+        CheckResult::Mismatch => {
+            return Err(fl!("sftp-host-key-changed", host = remote.known_host()));
+        }
+        CheckResult::NotFound | CheckResult::Failure => {}
+    }
+
+    let key_name = key_type_name(key_type);
+    let fingerprint = session
+        .host_key_hash(HashType::Sha256)
+        .map(|hash| BASE64_STANDARD_NO_PAD.encode(hash))
+        .unwrap_or_default();
+    let message = format!(
+        "{}\n{}",
+        fl!("sftp-unknown-host-key", host = remote.known_host()),
+        fl!(
+            "sftp-host-key-fingerprint",
+            key_type = key_name.unwrap_or("unknown"),
+            fingerprint = fingerprint
+        )
+    );
+    if ask(uri, message_auth(message, None), event_tx)?.is_none() {
+        return Ok(false);
+    }
+
+    // Appended instead of rewriting the file, which would drop lines libssh2 does not parse
+    if let (Some(path), Some(key_name)) = (path_opt, key_name) {
+        let line = format!(
+            "{} {} {}\n",
+            remote.known_host(),
+            key_name,
+            BASE64_STANDARD.encode(key)
+        );
+        let res = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::OpenOptions::new().create(true).append(true).open(&path))
+            .and_then(|mut file| file.write_all(line.as_bytes()));
+        if let Err(err) = res {
+            log::warn!("failed to add {} to {:?}: {}", remote.host, path, err);
+        }
+    }
+    Ok(true)
+}
+
+/// Answers every keyboard-interactive prompt with the password
+struct PasswordPrompt<'a>(&'a str);
+
+impl KeyboardInteractivePrompt for PasswordPrompt<'_> {
+    fn prompt<'b>(
+        &mut self,
+        _username: &str,
+        _instructions: &str,
+        prompts: &[Prompt<'b>],
+    ) -> Vec<String> {
+        prompts.iter().map(|_| self.0.to_string()).collect()
+    }
+}
+
+/// Log in with keys from the ssh agent or the default key files first, then ask for key
+/// passphrases and a password
+fn authenticate(
+    session: &Session,
+    remote: &Remote,
+    uri: &str,
+    event_tx: &mpsc::UnboundedSender<Event>,
+) -> Result<bool, String> {
+    let username = remote.username.as_str();
+    if session.userauth_agent(username).is_ok() {
+        return Ok(true);
+    }
+
+    let mut locked_keys = Vec::new();
+    if let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) {
+        for name in ["id_ed25519", "id_ecdsa", "id_rsa"] {
+            let key = ssh_dir.join(name);
+            if !key.is_file() {
+                continue;
+            }
+            match session.userauth_pubkey_file(username, None, &key, None) {
+                Ok(()) => return Ok(true),
+                Err(err) if matches!(err.code(), ErrorCode::Session(LIBSSH2_ERROR_FILE)) => {
+                    locked_keys.push(key);
+                }
+                Err(err) => log::info!("key {:?} not accepted: {}", key, err),
+            }
+        }
+    }
+
+    let methods = session.auth_methods(username).map_err(err_str)?.to_string();
+    if methods.split(',').any(|method| method == "publickey") {
+        for key in locked_keys {
+            let mut error_opt: Option<String> = None;
+            loop {
+                let mut message = fl!("sftp-key-passphrase", key = key.display().to_string());
+                if let Some(error) = &error_opt {
+                    message = format!("{}\n{}", message, error);
+                }
+                let Some(auth) = ask(uri, message_auth(message, Some(String::new())), event_tx)?
+                else {
+                    return Ok(false);
+                };
+                // An empty passphrase skips the key
+                let passphrase = auth.password_opt.unwrap_or_default();
+                if passphrase.is_empty() {
+                    break;
+                }
+                match session.userauth_pubkey_file(username, None, &key, Some(&passphrase)) {
+                    Ok(()) => return Ok(true),
+                    Err(err) => error_opt = Some(err.to_string()),
+                }
+            }
+        }
+    }
+
+    let password_method = methods.split(',').any(|method| method == "password");
+    let interactive_method = methods
+        .split(',')
+        .any(|method| method == "keyboard-interactive");
+    if !password_method && !interactive_method {
+        return Err(format!(
+            "{} offers no supported authentication method: {}",
+            remote.host, methods
+        ));
+    }
+    let mut error_opt: Option<String> = None;
+    loop {
+        let mut message = remote.name();
+        if let Some(error) = &error_opt {
+            message = format!("{}\n{}", message, error);
+        }
+        let Some(auth) = ask(uri, message_auth(message, Some(String::new())), event_tx)? else {
+            return Ok(false);
+        };
+        let password = auth.password_opt.unwrap_or_default();
+        let res = if password_method {
+            session.userauth_password(username, &password)
+        } else {
+            session.userauth_keyboard_interactive(username, &mut PasswordPrompt(&password))
+        };
+        match res {
+            Ok(()) => return Ok(true),
+            Err(err) => error_opt = Some(err.to_string()),
+        }
+    }
+}
+
+fn poll(fds: &mut [libc::pollfd], timeout: Duration) {
+    unsafe {
+        libc::poll(
+            fds.as_mut_ptr(),
+            fds.len() as libc::nfds_t,
+            timeout.as_millis() as libc::c_int,
+        );
+    }
+}
+
+fn pollfd(fd: i32, events: libc::c_short) -> libc::pollfd {
+    libc::pollfd {
+        fd,
+        events,
+        revents: 0,
+    }
+}
+
+fn set_nonblocking(fd: i32) -> io::Result<()> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn write_channel(channel: &mut Channel, socket: &TcpStream, mut data: &[u8]) -> io::Result<()> {
+    while !data.is_empty() {
+        match channel.write(data) {
+            Ok(count) => data = &data[count..],
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => poll(
+                &mut [pollfd(socket.as_raw_fd(), libc::POLLIN | libc::POLLOUT)],
+                Duration::from_millis(100),
+            ),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Pass SFTP packets between sshfs and the ssh channel until either side closes or `stop` is
+/// set. The session is used without blocking, as a blocking read would keep writes from going
+/// through.
+fn relay(
+    session: &Session,
+    socket: &TcpStream,
+    channel: &mut Channel,
+    stdin: &mut ChildStdin,
+    stdout: &mut ChildStdout,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    session.set_blocking(false);
+    set_nonblocking(stdout.as_raw_fd())?;
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        if stop.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        poll(
+            &mut [
+                pollfd(socket.as_raw_fd(), libc::POLLIN),
+                pollfd(stdout.as_raw_fd(), libc::POLLIN),
+            ],
+            Duration::from_millis(100),
+        );
+
+        // libssh2 may have buffered data already, so the channel is read even without events
+        loop {
+            match channel.read(&mut buf) {
+                Ok(0) => {
+                    if channel.eof() {
+                        return Ok(());
+                    }
+                    break;
+                }
+                Ok(count) => stdin.write_all(&buf[..count])?,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        match stdout.read(&mut buf) {
+            // sshfs exited, usually because it was unmounted
+            Ok(0) => return Ok(()),
+            Ok(count) => write_channel(channel, socket, &buf[..count])?,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Mount the remote folder with sshfs, which speaks SFTP over its stdin and stdout in passive
+/// mode while this process keeps the authenticated connection
+fn mount(
+    remote: &Remote,
+    session: Session,
+    socket: TcpStream,
+    event_tx: mpsc::UnboundedSender<Event>,
+) -> Result<(), String> {
+    let mut channel = session.channel_session().map_err(err_str)?;
+    channel
+        .handle_extended_data(ExtendedData::Ignore)
+        .map_err(err_str)?;
+    channel.subsystem("sftp").map_err(err_str)?;
+
+    let mount_dir = remote.mount_dir();
+    fs::create_dir_all(&mount_dir).map_err(err_str)?;
+    let mut child = Command::new("sshfs")
+        .arg(format!(
+            "{}@{}:{}",
+            remote.username, remote.host, remote.path
+        ))
+        .arg(&mount_dir)
+        .arg("-f")
+        .arg("-o")
+        .arg("passive")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("failed to run sshfs: {}", err))?;
+    let (Some(mut stdin), Some(mut stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        let _ = child.kill();
+        return Err("failed to connect to sshfs".to_string());
+    };
+
+    let (exit_tx, exit_rx) = std_mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    thread::spawn({
+        let stop = stop.clone();
+        move || {
+            if let Err(err) = relay(
+                &session,
+                &socket,
+                &mut channel,
+                &mut stdin,
+                &mut stdout,
+                &stop,
+            ) {
+                log::warn!("sftp connection lost: {}", err);
+            }
+            // sshfs exits once its input is closed, unless it is stopped while mounting
+            drop(stdin);
+            if stop.load(Ordering::SeqCst) {
+                let _ = child.kill();
+            }
+            let mut stderr = String::new();
+            if let Some(mut child_stderr) = child.stderr.take() {
+                let _ = child_stderr.read_to_string(&mut stderr);
+            }
+            let _ = child.wait();
+
+            session.set_blocking(true);
+            session.set_timeout(1000);
+            let _ = channel.close();
+            let _ = session.disconnect(None, "", None);
+
+            let _ = exit_tx.send(stderr.trim().to_string());
+            let _ = event_tx.send(Event::Changed);
+        }
+    });
+
+    let started = Instant::now();
+    loop {
+        if sshfs_mounts().iter().any(|item| item.path == mount_dir) {
+            return Ok(());
+        }
+        if let Ok(stderr) = exit_rx.try_recv() {
+            let _ = fs::remove_dir(&mount_dir);
+            return Err(stderr);
+        }
+        if started.elapsed() > MOUNT_TIMEOUT {
+            // Stop the relay and wait for it to kill sshfs and close the session
+            stop.store(true, Ordering::SeqCst);
+            let _ = exit_rx.recv();
+            let _ = fs::remove_dir(&mount_dir);
+            return Err(format!("timed out mounting {:?}", mount_dir));
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+fn network_drive(uri: String, event_tx: mpsc::UnboundedSender<Event>) -> Result<bool, String> {
+    let remote = Remote::parse(&uri)?;
+    let mount_dir = remote.mount_dir();
+    if sshfs_mounts().iter().any(|item| item.path == mount_dir) {
+        // Already connected
+        return Ok(true);
+    }
+
+    let socket = TcpStream::connect((remote.host.as_str(), remote.port)).map_err(err_str)?;
+    let mut session = Session::new().map_err(err_str)?;
+    session.set_tcp_stream(socket.try_clone().map_err(err_str)?);
+    session.handshake().map_err(err_str)?;
+    if !verify_host_key(&session, &remote, &uri, &event_tx)? {
+        return Ok(false);
+    }
+    if !authenticate(&session, &remote, &uri, &event_tx)? {
+        return Ok(false);
+    }
+    mount(&remote, session, socket, event_tx)?;
+    Ok(true)
+}
+
+/// Undo the octal escapes /proc/mounts uses for spaces, tabs, newlines and backslashes
+fn unescape_mount_field(field: &[u8]) -> Vec<u8> {
+    let mut unescaped = Vec::with_capacity(field.len());
+    let mut i = 0;
+    while i < field.len() {
+        let octal = field.get(i + 1..i + 4).and_then(|digits| {
+            let digits = std::str::from_utf8(digits).ok()?;
+            u8::from_str_radix(digits, 8).ok()
+        });
+        match octal {
+            Some(byte) if field[i] == b'\\' => {
+                unescaped.push(byte);
+                i += 4;
+            }
+            _ => {
+                unescaped.push(field[i]);
+                i += 1;
+            }
+        }
+    }
+    unescaped
+}
+
+/// Folders mounted by sshfs, also those mounted by other programs or earlier runs
+fn parse_sshfs_mounts(mounts: &[u8]) -> Vec<Item> {
+    mounts
+        .split(|byte| *byte == b'\n')
+        .filter_map(|line| {
+            let mut fields = line.split(|byte| *byte == b' ');
+            let source = fields.next()?;
+            let target = fields.next()?;
+            let fs_type = fields.next()?;
+            (fs_type == b"fuse.sshfs").then(|| Item {
+                name: String::from_utf8_lossy(&unescape_mount_field(source)).into_owned(),
+                path: PathBuf::from(OsString::from_vec(unescape_mount_field(target))),
+            })
+        })
+        .collect()
+}
+
+fn sshfs_mounts() -> Vec<Item> {
+    match fs::read("/proc/mounts") {
+        Ok(mounts) => parse_sshfs_mounts(&mounts),
+        Err(err) => {
+            log::warn!("failed to read /proc/mounts: {}", err);
+            Vec::new()
+        }
+    }
+}
+
+fn items() -> MounterItems {
+    sshfs_mounts().into_iter().map(MounterItem::Sftp).collect()
+}
+
+fn unmount(item: &Item) -> Result<(), UnmountError> {
+    for (program, args) in [
+        ("fusermount3", &["-u"][..]),
+        ("fusermount", &["-u"][..]),
+        ("umount", &[][..]),
+    ] {
+        match Command::new(program).args(args).arg(&item.path).output() {
+            Ok(output) if output.status.success() => {
+                // Only folders created for mounting are removed
+                if item.path.starts_with(mount_base_dir()) {
+                    let _ = fs::remove_dir(&item.path);
+                }
+                return Ok(());
+            }
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                log::warn!("{} {:?}: {}: {}", program, item.path, output.status, stderr);
+                // Other programs fail the same way while files are open
+                if stderr.to_lowercase().contains("busy") {
+                    return Err(UnmountError::Busy(stderr.trim().to_string()));
+                }
+            }
+            Err(err) => log::warn!("failed to run {}: {}", program, err),
+        }
+    }
+    Err(UnmountError::Failed(format!(
+        "failed to unmount {:?}",
+        item.path
+    )))
+}
+
+#[derive(Clone, Debug)]
+pub struct Item {
+    name: String,
+    path: PathBuf,
+}
+
+impl Item {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn is_mounted(&self) -> bool {
+        true
+    }
+
+    pub fn icon(&self, symbolic: bool) -> Option<widget::icon::Handle> {
+        Some(if symbolic {
+            widget::icon::from_name("folder-remote-symbolic")
+                .size(16)
+                .handle()
+        } else {
+            widget::icon::from_name("folder-remote").handle()
+        })
+    }
+
+    pub fn path(&self) -> Option<PathBuf> {
+        Some(self.path.clone())
+    }
+
+    pub fn is_system(&self) -> bool {
+        false
+    }
+}
+
+pub struct Sftp {
+    event_tx: mpsc::UnboundedSender<Event>,
+    event_rx: Arc<TokioMutex<mpsc::UnboundedReceiver<Event>>>,
+}
+
+impl Sftp {
+    pub fn new() -> Self {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        Self {
+            event_tx,
+            event_rx: Arc::new(TokioMutex::new(event_rx)),
+        }
+    }
+}
+
+impl Mounter for Sftp {
+    fn items(&self, _sizes: IconSizes) -> Option<MounterItems> {
+        Some(items())
+    }
+
+    fn mount(&self, _item: MounterItem) -> Task<()> {
+        // Items are only listed while mounted
+        Task::none()
+    }
+
+    fn network_drive(&self, uri: String) -> Task<()> {
+        let event_tx = self.event_tx.clone();
+        Task::perform(
+            async move {
+                let res = tokio::task::spawn_blocking({
+                    let uri = uri.clone();
+                    let event_tx = event_tx.clone();
+                    move || network_drive(uri, event_tx)
+                })
+                .await
+                .map_err(err_str)
+                .and_then(|res| res);
+                log::info!("network drive {}: result {:?}", uri, res);
+                if matches!(res, Ok(true)) {
+                    let _ = event_tx.send(Event::Changed);
+                }
+                let _ = event_tx.send(Event::NetworkResult(uri, res));
+            },
+            |x| x,
+        )
+    }
+
+    fn network_schemes(&self) -> &'static [&'static str] {
+        &["sftp", "ssh"]
+    }
+
+    fn network_scan(
+        &self,
+        _uri: &str,
+        _sizes: IconSizes,
+    ) -> Option<Result<Vec<tab1::Item>, String>> {
+        // Mounted locations are browsed as local paths
+        None
+    }
+
+    fn unmount(&self, item: MounterItem) -> Task<()> {
+        let MounterItem::Sftp(item) = item else {
+            return Task::none();
+        };
+        let event_tx = self.event_tx.clone();
+        Task::perform(
+            async move {
+                let item2 = item.clone();
+                let res = match tokio::task::spawn_blocking(move || unmount(&item2)).await {
+                    Ok(Ok(())) => {
+                        let _ = event_tx.send(Event::Changed);
+                        Ok(true)
+                    }
+                    Ok(Err(err)) => Err(err),
+                    Err(err) => Err(UnmountError::Failed(format!("failed to unmount: {}", err))),
+                };
+                let _ = event_tx.send(Event::UnmountResult(MounterItem::Sftp(item), res));
+            },
+            |x| x,
+        )
+    }
+
+    fn subscription(&self) -> Subscription<MounterMessage> {
+        let event_rx = self.event_rx.clone();
+        Subscription::run_with_id(
+            TypeId::of::<Self>(),
+            stream::channel(1, |mut output| async move {
+                while let Some(event) = event_rx.lock().await.recv().await {
+                    match event {
+                        Event::Changed => {
+                            output.send(MounterMessage::Items(items())).await.unwrap()
+                        }
+                        Event::NetworkAuth(uri, auth, auth_tx) => output
+                            .send(MounterMessage::NetworkAuth(uri, auth, auth_tx))
+                            .await
+                            .unwrap(),
+                        Event::NetworkResult(uri, res) => output
+                            .send(MounterMessage::NetworkResult(uri, res))
+                            .await
+                            .unwrap(),
+                        Event::UnmountResult(item, res) => output
+                            .send(MounterMessage::UnmountResult(item, res))
+                            .await
+                            .unwrap(),
+                    }
+                }
+                pending().await
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_sshfs_mounts, Remote};
+    use std::path::Path;
+
+    #[test]
+    fn remote_paths_are_decoded_and_mounted_separately() {
+        let srv = Remote::parse("sftp://me@nas/srv/my%20files/").unwrap();
+        assert_eq!(srv.path, "/srv/my files");
+        assert_eq!(srv.port, 22);
+        assert_eq!(
+            srv.mount_dir().file_name().and_then(|name| name.to_str()),
+            Some("me@nas%2Fsrv%2Fmy files")
+        );
+
+        let home = Remote::parse("ssh://me@nas:2222").unwrap();
+        assert_eq!(home.path, "/");
+        assert_eq!(home.port, 2222);
+        assert_ne!(srv.mount_dir(), home.mount_dir());
+        assert_ne!(
+            Remote::parse("sftp://me@nas/a/b").unwrap().mount_dir(),
+            Remote::parse("sftp://me@nas/a_b").unwrap().mount_dir()
+        );
+    }
+
+    #[test]
+    fn sshfs_mounts_are_read_with_escapes() {
+        let mounts = b"proc /proc proc rw,nosuid 0 0\n\
+            user@host:/srv /run/user/1000/commander-sftp/user@host fuse.sshfs rw 0 0\n\
+            me@nas:/my\\040files /mnt/my\\040nas fuse.sshfs rw 0 0\n";
+        let items = parse_sshfs_mounts(mounts);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name(), "user@host:/srv");
+        assert_eq!(
+            items[0].path().as_deref(),
+            Some(Path::new("/run/user/1000/commander-sftp/user@host"))
+        );
+        assert_eq!(items[1].name(), "me@nas:/my files");
+        assert_eq!(items[1].path().as_deref(), Some(Path::new("/mnt/my nas")));
+    }
+}
@@ -1,6 +1,9 @@
 use crate::fl;
 
-use std::sync::{Arc, Condvar, Mutex};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, Mutex},
+};
 
 #[derive(Clone, Copy, Debug)]
 pub enum ControllerState {
@@ -13,6 +16,7 @@ pub enum ControllerState {
 struct ControllerInner {
     state: Mutex<ControllerState>,
     progress: Mutex<f32>,
+    current: Mutex<Option<PathBuf>>,
     condvar: Condvar,
 }
 
@@ -29,6 +33,7 @@ impl Default for Controller {
             inner: Arc::new(ControllerInner {
                 state: Mutex::new(ControllerState::Running),
                 progress: Mutex::new(0.0),
+                current: Mutex::new(None),
                 condvar: Condvar::new(),
             }),
         }
@@ -57,6 +62,19 @@ impl Controller {
         *self.inner.progress.lock().unwrap() = progress;
     }
 
+    /// Path of the item being processed, for operations that go through items one by one
+    pub fn current(&self) -> Option<PathBuf> {
+        self.inner.current.lock().unwrap().clone()
+    }
+
+    pub fn set_current(&self, path: &Path) {
+        let mut current = self.inner.current.lock().unwrap();
+        // Progress is reported many times for each file copied
+        if current.as_deref() != Some(path) {
+            *current = Some(path.to_path_buf());
+        }
+    }
+
     pub fn state(&self) -> ControllerState {
         *self.inner.state.lock().unwrap()
     }
@@ -7,6 +7,7 @@ use crate::{
     tab1,
 };
 use cosmic::iced::futures::{channel::mpsc::Sender, executor, SinkExt};
+use once_cell::sync::Lazy;
 use std::collections::VecDeque;
 use std::fmt::Formatter;
 use std::{
@@ -14,7 +15,8 @@ use std::{
     fs,
     io::{self, Read, Write},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, RwLock},
+    time::SystemTime,
 };
 use tokio::sync::{mpsc, Mutex as TokioMutex};
 use walkdir::WalkDir;
@@ -30,6 +32,8 @@ pub mod reader;
 use self::recursive::Context;
 pub mod recursive;
 
+pub mod trash_dir;
+
 fn handle_replace(
     msg_tx: &Arc<TokioMutex<Sender<Message>>>,
     file_from: PathBuf,
@@ -138,6 +142,7 @@ fn zip_extract<R: io::Read + io::Seek, P: AsRef<Path>>(
             .ok_or(ZipError::InvalidArchive("Invalid file path"))?;
 
         let outpath = directory.as_ref().join(filepath);
+        controller.set_current(&outpath);
 
         if file.is_dir() {
             pending_directory_creates.push_back(outpath.clone());
@@ -255,11 +260,94 @@ fn zip_extract<R: io::Read + io::Seek, P: AsRef<Path>>(
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum ReplaceResult {
     Replace(bool),
+    /// Replace if the source is newer, skip otherwise
+    ReplaceIfNewer(bool),
     KeepBoth,
+    /// Keep both, numbering the new item like `name (2).ext`
+    Rename(bool),
     Skip(bool),
     Cancel,
 }
 
+/// Size of files, including the contents of folders
+fn paths_size(paths: &[&Path], controller: &Controller) -> Result<u64, OperationError> {
+    let mut size = 0;
+    for path in paths {
+        for entry in WalkDir::new(path)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+        {
+            controller.check().map_err(OperationError::from_str)?;
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_file() {
+                    size += metadata.len();
+                }
+            }
+        }
+    }
+    Ok(size)
+}
+
+#[cfg(unix)]
+fn same_filesystem(from: &Path, to: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::symlink_metadata(from), fs::metadata(to)) {
+        (Ok(from), Ok(to)) => from.dev() == to.dev(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_filesystem(_from: &Path, _to: &Path) -> bool {
+    false
+}
+
+/// Ask whether to continue if the items do not fit into the free space of `to`. Items moved
+/// within the same filesystem are only renamed and need no space.
+fn check_free_space(
+    msg_tx: &Arc<TokioMutex<Sender<Message>>>,
+    paths: &[PathBuf],
+    to: &Path,
+    moving: bool,
+    controller: &Controller,
+) -> Result<(), OperationError> {
+    let copied: Vec<&Path> = paths
+        .iter()
+        .map(|path| path.as_path())
+        .filter(|from| !(moving && same_filesystem(from, to)))
+        .collect();
+    if copied.is_empty() {
+        return Ok(());
+    }
+    let Some((free, _total)) = disk_space(to) else {
+        return Ok(());
+    };
+    let required = paths_size(&copied, controller)?;
+    if required <= free {
+        return Ok(());
+    }
+
+    let proceed = executor::block_on(async {
+        let (tx, mut rx) = mpsc::channel(1);
+        let _ = msg_tx
+            .lock()
+            .await
+            .send(Message::DialogPush(DialogPage::InsufficientSpace {
+                required,
+                free,
+                tx,
+            }))
+            .await;
+        rx.recv().await.unwrap_or(false)
+    });
+    if proceed {
+        Ok(())
+    } else {
+        controller.cancel();
+        Err(OperationError::from_str("not enough free space"))
+    }
+}
+
 async fn copy_or_move(
     paths: Vec<PathBuf>,
     to: PathBuf,
@@ -276,6 +364,8 @@ async fn copy_or_move(
             to
         );
 
+        check_free_space(&msg_tx, &paths, &to, moving, &controller)?;
+
         // Handle duplicate file names by renaming paths
         let from_to_pairs: Vec<(PathBuf, PathBuf)> = paths
             .into_iter()
@@ -296,110 +386,208 @@ async fn copy_or_move(
             })
             .collect();
 
-        let mut context = Context::new(controller.clone());
+        let mut context = copy_context(&msg_tx, controller, true);
+        context
+            .recursive_copy_or_move(from_to_pairs, moving)
+            .map_err(OperationError::from_str)?;
 
-        {
-            context = context.on_progress(move |_op, progress| {
-                let item_progress = match progress.total_bytes {
-                    Some(total_bytes) => {
-                        if total_bytes == 0 {
-                            1.0
-                        } else {
-                            progress.current_bytes as f32 / total_bytes as f32
-                        }
-                    }
-                    None => 0.0,
-                };
-                let total_progress =
-                    (item_progress + progress.current_ops as f32) / progress.total_ops as f32;
-                controller.set_progress(total_progress);
-            });
-        }
+        Ok(context.op_sel)
+    })
+    .await
+    .map_err(OperationError::from_str)?
+    //.map_err(OperationError::from_str)
+}
 
-        {
-            let msg_tx = msg_tx.clone();
-            context = context.on_replace(move |op| {
-                handle_replace(&msg_tx, op.from.clone(), op.to.clone(), true)
-            });
+async fn copy_as(
+    from: PathBuf,
+    to: PathBuf,
+    msg_tx: &Arc<TokioMutex<Sender<Message>>>,
+    controller: Controller,
+) -> Result<OperationSelection, OperationError> {
+    let msg_tx = msg_tx.clone();
+    tokio::task::spawn_blocking(move || -> Result<OperationSelection, OperationError> {
+        log::info!("Copy {:?} as {:?}", from, to);
+
+        if let Some(parent) = to.parent() {
+            check_free_space(&msg_tx, &[from.clone()], parent, false, &controller)?;
         }
 
+        // An existing item at the new name is confirmed through the replace dialog
+        let mut context = copy_context(&msg_tx, controller, false);
         context
-            .recursive_copy_or_move(from_to_pairs, moving)
+            .recursive_copy_or_move(vec![(from, to)], false)
             .map_err(OperationError::from_str)?;
 
         Ok(context.op_sel)
     })
     .await
     .map_err(OperationError::from_str)?
-    //.map_err(OperationError::from_str)
 }
 
-fn copy_unique_path(from: &Path, to: &Path) -> PathBuf {
-    // List of compound extensions to check
-    const COMPOUND_EXTENSIONS: &[&str] = &[
-        ".tar.gz",
-        ".tar.bz2",
-        ".tar.xz",
-        ".tar.zst",
-        ".tar.lz",
-        ".tar.lzma",
-        ".tar.sz",
-        ".tar.lzo",
-        ".tar.br",
-        ".tar.Z",
-        ".tar.pz",
-    ];
+/// Context for recursive copies that reports progress and asks before replacing items
+fn copy_context(
+    msg_tx: &Arc<TokioMutex<Sender<Message>>>,
+    controller: Controller,
+    multiple: bool,
+) -> Context {
+    let mut context = Context::new(controller.clone());
 
-    let mut to = to.to_owned();
-    if let Some(file_name) = from.file_name().and_then(|name| name.to_str()) {
-        let (stem, ext) = if from.is_dir() {
-            (file_name.to_string(), None)
-        } else {
-            let file_name = file_name.to_string();
-            COMPOUND_EXTENSIONS
-                .iter()
-                .find(|&&ext| file_name.ends_with(ext))
-                .map(|&ext| {
-                    (
-                        file_name.strip_suffix(ext).unwrap().to_string(),
-                        Some(ext[1..].to_string()),
-                    )
-                })
-                .unwrap_or_else(|| {
-                    from.file_stem()
-                        .and_then(|s| s.to_str())
-                        .map(|stem| {
-                            (
-                                stem.to_string(),
-                                from.extension()
-                                    .and_then(|e| e.to_str())
-                                    .map(|e| e.to_string()),
-                            )
-                        })
-                        .unwrap_or((file_name, None))
-                })
-        };
+    {
+        context = context.on_progress(move |op, progress| {
+            controller.set_progress(progress.fraction());
+            controller.set_current(op.target());
+        });
+    }
 
-        for n in 0.. {
-            let new_name = if n == 0 {
-                file_name.to_string()
-            } else {
-                match ext {
-                    Some(ref ext) => format!("{} ({} {}).{}", stem, fl!("copy_noun"), n, ext),
-                    None => format!("{} ({} {})", stem, fl!("copy_noun"), n),
-                }
-            };
+    {
+        let msg_tx = msg_tx.clone();
+        context = context.on_replace(move |op| {
+            handle_replace(&msg_tx, op.from.clone(), op.to.clone(), multiple)
+        });
+    }
 
-            to = to.join(&new_name);
+    context
+}
 
-            if !matches!(to.try_exists(), Ok(true)) {
-                break;
+/// Mime types of the archives that can be extracted
+pub const EXTRACT_MIME_TYPES: &[&str] = &[
+    "application/gzip",
+    "application/x-compressed-tar",
+    "application/x-tar",
+    "application/zip",
+    #[cfg(feature = "bzip2")]
+    "application/x-bzip",
+    #[cfg(feature = "bzip2")]
+    "application/x-bzip-compressed-tar",
+    #[cfg(feature = "liblzma")]
+    "application/x-xz",
+    #[cfg(feature = "liblzma")]
+    "application/x-xz-compressed-tar",
+];
+
+// List of compound extensions to check
+const COMPOUND_EXTENSIONS: &[&str] = &[
+    ".tar.gz",
+    ".tar.bz2",
+    ".tar.xz",
+    ".tar.zst",
+    ".tar.lz",
+    ".tar.lzma",
+    ".tar.sz",
+    ".tar.lzo",
+    ".tar.br",
+    ".tar.Z",
+    ".tar.pz",
+];
+
+/// Split a file name into stem and extension, keeping compound extensions together
+fn stem_and_extension(file_name: &str, is_dir: bool) -> (String, Option<String>) {
+    if is_dir {
+        return (file_name.to_string(), None);
+    }
+    if let Some(ext) = COMPOUND_EXTENSIONS
+        .iter()
+        .find(|&&ext| file_name.ends_with(ext))
+    {
+        return (
+            file_name.strip_suffix(ext).unwrap().to_string(),
+            Some(ext[1..].to_string()),
+        );
+    }
+    let path = Path::new(file_name);
+    match path.file_stem().and_then(|s| s.to_str()) {
+        Some(stem) => (
+            stem.to_string(),
+            path.extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.to_string()),
+        ),
+        None => (file_name.to_string(), None),
+    }
+}
+
+/// Default template for names that avoid overwriting an existing item
+pub const UNIQUE_NAME_TEMPLATE: &str = "{name} ({n}){ext}";
+/// Default first number tried in unique names
+pub const UNIQUE_NAME_BASE: u32 = 2;
+
+// Template and first number used by operations that start after they are changed
+static UNIQUE_NAME: Lazy<RwLock<(String, u32)>> =
+    Lazy::new(|| RwLock::new((UNIQUE_NAME_TEMPLATE.to_string(), UNIQUE_NAME_BASE)));
+
+/// Templates must contain `{n}` so every number gives a different name, and must not contain
+/// a path separator
+pub fn unique_name_template_valid(template: &str) -> bool {
+    template.contains("{n}") && !template.contains(['/', std::path::MAIN_SEPARATOR])
+}
+
+/// Change the template and first number of names that avoid overwriting an existing item.
+/// Invalid templates fall back to the default.
+pub fn set_unique_name_options(template: &str, base: u32) {
+    let template = if unique_name_template_valid(template) {
+        template
+    } else {
+        UNIQUE_NAME_TEMPLATE
+    };
+    *UNIQUE_NAME.write().unwrap() = (template.to_string(), base);
+}
+
+/// Fill in the `{name}`, `{n}` and `{ext}` placeholders of a unique name template
+fn unique_name(template: &str, stem: &str, ext: Option<&str>, n: u32) -> String {
+    let mut name = String::with_capacity(template.len() + stem.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        name.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("{name}") {
+            name.push_str(stem);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{n}") {
+            name.push_str(&n.to_string());
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{ext}") {
+            if let Some(ext) = ext {
+                name.push('.');
+                name.push_str(ext);
             }
-            // Continue if a copy with index exists
-            to.pop();
+            rest = after;
+        } else {
+            name.push('{');
+            rest = &rest[1..];
+        }
+    }
+    name.push_str(rest);
+    name
+}
+
+/// Find a free path for `from` in the folder `to`, keeping its name if that is free
+pub(crate) fn copy_unique_path(from: &Path, to: &Path) -> PathBuf {
+    let Some(file_name) = from.file_name() else {
+        return to.to_owned();
+    };
+    let path = to.join(file_name);
+    if matches!(path.try_exists(), Ok(true)) {
+        numbered_unique_path(from, &path)
+    } else {
+        path
+    }
+}
+
+/// Find a free path next to `to` by adding a number, like `name (2).ext`
+fn numbered_unique_path(from: &Path, to: &Path) -> PathBuf {
+    let (Some(parent), Some(file_name)) = (to.parent(), to.file_name().and_then(|x| x.to_str()))
+    else {
+        return to.to_owned();
+    };
+    let (stem, ext) = stem_and_extension(file_name, from.is_dir());
+    let (template, mut n) = UNIQUE_NAME.read().unwrap().clone();
+    loop {
+        let path = parent.join(unique_name(&template, &stem, ext.as_deref(), n));
+        if !matches!(path.try_exists(), Ok(true)) {
+            return path;
         }
+        n += 1;
     }
-    to
 }
 
 fn file_name(path: &Path) -> Cow<'_, str> {
@@ -434,12 +622,351 @@ fn paths_parent_name(paths: &[PathBuf]) -> Cow<'_, str> {
     file_name(parent)
 }
 
+/// Set the modification time of a path without opening it, a symlink is changed itself rather
+/// than its target
+#[cfg(unix)]
+fn set_path_modified(path: &Path, time: SystemTime) -> io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let (tv_sec, tv_nsec) = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(after) => (after.as_secs() as libc::time_t, after.subsec_nanos()),
+        // Times before the epoch count their nanoseconds up from the earlier whole second
+        Err(err) => {
+            let before = err.duration();
+            match before.subsec_nanos() {
+                0 => (-(before.as_secs() as libc::time_t), 0),
+                nanos => (
+                    -(before.as_secs() as libc::time_t) - 1,
+                    1_000_000_000 - nanos,
+                ),
+            }
+        }
+    };
+    // The access time is left as it is
+    let times = [
+        libc::timespec {
+            tv_sec: 0,
+            tv_nsec: libc::UTIME_OMIT,
+        },
+        libc::timespec {
+            tv_sec,
+            tv_nsec: tv_nsec as libc::c_long,
+        },
+    ];
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    // SAFETY: path is a valid C string and times holds the two timestamps utimensat reads
+    let res = unsafe {
+        libc::utimensat(
+            libc::AT_FDCWD,
+            path.as_ptr(),
+            times.as_ptr(),
+            libc::AT_SYMLINK_NOFOLLOW,
+        )
+    };
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(unix))]
+fn set_path_modified(path: &Path, time: SystemTime) -> io::Result<()> {
+    fs::File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(time)
+}
+
+/// Set the modification time of paths, continuing past failures so that every failed item is
+/// reported at once
+fn set_modified(
+    paths: &[PathBuf],
+    time: SystemTime,
+    recursive: bool,
+    controller: &Controller,
+) -> Result<(), OperationError> {
+    let mut failures = Vec::new();
+    let mut targets = Vec::new();
+    for path in paths {
+        if recursive && path.is_dir() {
+            for entry in WalkDir::new(path) {
+                match entry {
+                    Ok(entry) => targets.push(entry.into_path()),
+                    Err(err) => {
+                        failures.push(format!("{}: {}", err.path().unwrap_or(path).display(), err))
+                    }
+                }
+            }
+        } else {
+            targets.push(path.clone());
+        }
+    }
+
+    let total = targets.len();
+    for (i, target) in targets.iter().enumerate() {
+        controller.check().map_err(OperationError::from_str)?;
+
+        controller.set_progress((i as f32) / (total as f32));
+        controller.set_current(target);
+
+        if let Err(err) = set_path_modified(target, time) {
+            failures.push(format!("{}: {}", target.display(), err));
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(OperationError::from_str(failures.join("\n")))
+    }
+}
+
+/// Set the mode of a path, or of every item below a directory when recursive. Recursively the
+/// execute bits only apply to folders and to files that are already executable, like the `X` of
+/// `chmod -R`, and the items in a folder are changed before the folder itself. Failed items are
+/// reported together once every other item has been changed.
+#[cfg(unix)]
+fn set_permissions(
+    path: &Path,
+    mode: u32,
+    recursive: bool,
+    controller: &Controller,
+) -> Result<(), OperationError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let set_mode = |path: &Path, mode: u32| -> Result<(), String> {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).map_err(|err| {
+            match err.kind() {
+                io::ErrorKind::PermissionDenied => {
+                    fl!("permission-denied", name = file_name(path))
+                }
+                _ => format!("{:?}: {}", path, err),
+            }
+        })
+    };
+
+    if !recursive || !path.is_dir() {
+        controller.check().map_err(OperationError::from_str)?;
+        return set_mode(path, mode).map_err(OperationError::from_str);
+    }
+
+    let mut failures = Vec::new();
+    let mut entries = Vec::new();
+    for entry in WalkDir::new(path).contents_first(true) {
+        match entry {
+            Ok(entry) => entries.push(entry),
+            Err(err) => failures.push(format!("{:?}: {}", err.path().unwrap_or(path), err)),
+        }
+    }
+    let total = entries.len();
+    for (i, entry) in entries.into_iter().enumerate() {
+        controller.check().map_err(OperationError::from_str)?;
+
+        controller.set_progress((i as f32) / (total as f32));
+        controller.set_current(entry.path());
+
+        // Setting the mode of a symlink would change its target instead
+        if entry.path_is_symlink() {
+            continue;
+        }
+        let executable = entry.file_type().is_dir()
+            || entry
+                .metadata()
+                .map_or(false, |metadata| metadata.permissions().mode() & 0o111 != 0);
+        let entry_mode = if executable { mode } else { mode & !0o111 };
+        if let Err(err) = set_mode(entry.path(), entry_mode) {
+            failures.push(err);
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(OperationError::from_str(failures.join("\n")))
+    }
+}
+
+#[cfg(not(unix))]
+fn set_permissions(
+    _path: &Path,
+    _mode: u32,
+    _recursive: bool,
+    _controller: &Controller,
+) -> Result<(), OperationError> {
+    Err(OperationError::from_str(
+        "setting permissions is not supported on this platform",
+    ))
+}
+
+/// Point a symlink at a new target. The new link is created next to it and renamed over it, so
+/// the link is never missing.
+#[cfg(unix)]
+fn set_symlink_target(path: &Path, target: &Path) -> Result<(), OperationError> {
+    let map_err = |err: io::Error| match err.kind() {
+        io::ErrorKind::PermissionDenied => {
+            OperationError::from_str(fl!("permission-denied", name = file_name(path)))
+        }
+        _ => OperationError::from_str(format!("{:?}: {}", path, err)),
+    };
+
+    if !fs::symlink_metadata(path)
+        .map_err(map_err)?
+        .file_type()
+        .is_symlink()
+    {
+        return Err(OperationError::from_str(format!(
+            "{:?} is not a symbolic link",
+            path
+        )));
+    }
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(OperationError::from_str(format!(
+            "failed to get parent of {:?}",
+            path
+        )));
+    };
+
+    let mut n = 0;
+    let temp = loop {
+        let mut temp_name = std::ffi::OsString::from(".");
+        temp_name.push(name);
+        temp_name.push(format!(".{}.tmp", n));
+        let temp = parent.join(temp_name);
+        match std::os::unix::fs::symlink(target, &temp) {
+            Ok(()) => break temp,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(err) => return Err(map_err(err)),
+        }
+    };
+    fs::rename(&temp, path).map_err(|err| {
+        let _ = fs::remove_file(&temp);
+        map_err(err)
+    })
+}
+
+#[cfg(not(unix))]
+fn set_symlink_target(_path: &Path, _target: &Path) -> Result<(), OperationError> {
+    Err(OperationError::from_str(
+        "changing link targets is not supported on this platform",
+    ))
+}
+
+/// Size of a trashed item in bytes, directories are only measured where the trash layout is known
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn trash_item_size(item: &trash::TrashItem) -> u64 {
+    match trash::os_limited::metadata(item) {
+        Ok(metadata) => match metadata.size {
+            trash::TrashItemSize::Bytes(bytes) => bytes,
+            trash::TrashItemSize::Entries(_) => trashed_dir_size(item),
+        },
+        Err(err) => {
+            log::warn!("failed to get metadata for trash item {:?}: {}", item, err);
+            0
+        }
+    }
+}
+
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    not(target_os = "ios"),
+    not(target_os = "android")
+))]
+fn trashed_dir_size(item: &trash::TrashItem) -> u64 {
+    // The freedesktop trash keeps "info/NAME.trashinfo" next to "files/NAME"
+    let info = Path::new(&item.id);
+    let Some((trash, name)) = info.parent().and_then(Path::parent).zip(info.file_stem()) else {
+        return 0;
+    };
+    WalkDir::new(trash.join("files").join(name))
+        .into_iter()
+        .filter_map(|entry| entry.ok()?.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(target_os = "windows")]
+fn trashed_dir_size(_item: &trash::TrashItem) -> u64 {
+    0
+}
+
+/// Number of items in the trash and their total size in bytes
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn trash_summary() -> Result<(usize, u64), String> {
+    let items = trash::os_limited::list().map_err(|err| err.to_string())?;
+    Ok((items.len(), items.iter().map(trash_item_size).sum()))
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn trash_summary() -> Result<(usize, u64), String> {
+    Err("viewing trash not supported on this platform".to_string())
+}
+
+/// Free and total space in bytes of the filesystem containing `path`
+#[cfg(unix)]
+pub fn disk_space(path: &Path) -> Option<(u64, u64)> {
+    use std::{ffi::CString, mem::MaybeUninit, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: path is a valid C string and stat is only read after statvfs succeeded
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stat.assume_init()
+    };
+    // The fields are only narrower than u64 on some platforms
+    #[allow(clippy::unnecessary_cast)]
+    let (block_size, available, blocks) = (
+        stat.f_frsize as u64,
+        stat.f_bavail as u64,
+        stat.f_blocks as u64,
+    );
+    Some((available * block_size, blocks * block_size))
+}
+
+#[cfg(not(unix))]
+pub fn disk_space(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct OperationSelection {
     // Paths to ignore if they are already selected
     pub ignored: Vec<PathBuf>,
     // Paths to select
     pub selected: Vec<PathBuf>,
+    // Top level source and destination pairs, used to undo the operation
+    pub from_to: Vec<(PathBuf, PathBuf)>,
+    // Sources that changed while they were copied, so their copies may be incomplete
+    pub changed: Vec<PathBuf>,
+    // Existing items that were replaced or merged into
+    pub replaced: Vec<PathBuf>,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -456,6 +983,11 @@ pub enum Operation {
         paths: Vec<PathBuf>,
         to: PathBuf,
     },
+    /// Copy an item to a path with a different name
+    CopyAs {
+        from: PathBuf,
+        to: PathBuf,
+    },
     /// Move items to the trash
     Delete {
         paths: Vec<PathBuf>,
@@ -475,10 +1007,16 @@ pub enum Operation {
     },
     NewFile {
         path: PathBuf,
+        /// Text written to the file, empty for an empty file
+        contents: String,
     },
     NewFolder {
         path: PathBuf,
     },
+    /// Permanently delete items, used to undo copies
+    PermanentDelete {
+        paths: Vec<PathBuf>,
+    },
     Rename {
         from: PathBuf,
         to: PathBuf,
@@ -487,10 +1025,31 @@ pub enum Operation {
     Restore {
         items: Vec<trash::TrashItem>,
     },
+    /// Restore paths from the trash by their original location
+    RestorePaths {
+        paths: Vec<PathBuf>,
+    },
     /// Set executable and launch
     SetExecutableAndLaunch {
         path: PathBuf,
     },
+    /// Set the modification time, optionally of everything below directories
+    SetModified {
+        paths: Vec<PathBuf>,
+        time: SystemTime,
+        recursive: bool,
+    },
+    /// Set permission bits, optionally for everything below a directory
+    SetPermissions {
+        path: PathBuf,
+        mode: u32,
+        recursive: bool,
+    },
+    /// Point a symlink at a new target
+    SetSymlinkTarget {
+        path: PathBuf,
+        target: PathBuf,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -520,6 +1079,38 @@ impl std::fmt::Display for OperationError {
     }
 }
 
+/// How far a running operation got with one of the items it acts on
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ItemProgress {
+    Pending,
+    Current,
+    Done,
+}
+
+/// A completed operation that can be undone and redone
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperationHistory {
+    /// Operation to perform when redoing
+    pub redo: Operation,
+    /// Operations that revert the completed operation
+    pub undo: Vec<Operation>,
+    /// Existing items a copy replaced or merged into, undoing it would delete them for good
+    pub replaced: Vec<PathBuf>,
+}
+
+impl OperationHistory {
+    /// Paths that undoing will permanently delete, which requires confirmation
+    pub fn destructive_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for op in self.undo.iter() {
+            if let Operation::PermanentDelete { paths: op_paths } = op {
+                paths.extend(op_paths.iter().cloned());
+            }
+        }
+        paths
+    }
+}
+
 impl Operation {
     pub fn pending_text(&self, ratio: f32, state: ControllerState) -> String {
         let percent = (ratio * 100.0) as i32;
@@ -543,6 +1134,13 @@ impl Operation {
                 to = file_name(to),
                 progress = progress()
             ),
+            Self::CopyAs { from, to } => fl!(
+                "copying",
+                items = 1,
+                from = parent_name(from),
+                to = parent_name(to),
+                progress = progress()
+            ),
             Self::Delete { paths } => fl!(
                 "moving",
                 items = paths.len(),
@@ -569,7 +1167,7 @@ impl Operation {
                 to = file_name(to),
                 progress = progress()
             ),
-            Self::NewFile { path } => fl!(
+            Self::NewFile { path, .. } => fl!(
                 "creating",
                 name = file_name(path),
                 parent = parent_name(path)
@@ -579,13 +1177,38 @@ impl Operation {
                 name = file_name(path),
                 parent = parent_name(path)
             ),
+            Self::PermanentDelete { paths } => fl!(
+                "deleting",
+                items = paths.len(),
+                from = paths_parent_name(paths),
+                progress = progress()
+            ),
             Self::Rename { from, to } => {
                 fl!("renaming", from = file_name(from), to = file_name(to))
             }
             Self::Restore { items } => fl!("restoring", items = items.len(), progress = progress()),
+            Self::RestorePaths { paths } => {
+                fl!("restoring", items = paths.len(), progress = progress())
+            }
             Self::SetExecutableAndLaunch { path } => {
                 fl!("setting-executable-and-launching", name = file_name(path))
             }
+            Self::SetModified { paths, .. } => fl!(
+                "setting-modified",
+                items = paths.len(),
+                progress = progress()
+            ),
+            Self::SetPermissions { path, mode, .. } => fl!(
+                "setting-permissions",
+                name = file_name(path),
+                mode = format!("{:03o}", mode),
+                progress = progress()
+            ),
+            Self::SetSymlinkTarget { path, target } => fl!(
+                "setting-symlink-target",
+                name = file_name(path),
+                target = target.display().to_string()
+            ),
         }
     }
 
@@ -603,6 +1226,12 @@ impl Operation {
                 from = paths_parent_name(paths),
                 to = file_name(to)
             ),
+            Self::CopyAs { from, to } => fl!(
+                "copied",
+                items = 1,
+                from = parent_name(from),
+                to = parent_name(to)
+            ),
             Self::Delete { paths } => fl!(
                 "moved",
                 items = paths.len(),
@@ -626,7 +1255,7 @@ impl Operation {
                 from = paths_parent_name(paths),
                 to = file_name(to)
             ),
-            Self::NewFile { path } => fl!(
+            Self::NewFile { path, .. } => fl!(
                 "created",
                 name = file_name(path),
                 parent = parent_name(path)
@@ -636,11 +1265,28 @@ impl Operation {
                 name = file_name(path),
                 parent = parent_name(path)
             ),
+            Self::PermanentDelete { paths } => fl!(
+                "deleted",
+                items = paths.len(),
+                from = paths_parent_name(paths)
+            ),
             Self::Rename { from, to } => fl!("renamed", from = file_name(from), to = file_name(to)),
             Self::Restore { items } => fl!("restored", items = items.len()),
+            Self::RestorePaths { paths } => fl!("restored", items = paths.len()),
             Self::SetExecutableAndLaunch { path } => {
                 fl!("set-executable-and-launched", name = file_name(path))
             }
+            Self::SetModified { paths, .. } => fl!("set-modified", items = paths.len()),
+            Self::SetPermissions { path, mode, .. } => fl!(
+                "set-permissions",
+                name = file_name(path),
+                mode = format!("{:03o}", mode)
+            ),
+            Self::SetSymlinkTarget { path, target } => fl!(
+                "set-symlink-target",
+                name = file_name(path),
+                target = target.display().to_string()
+            ),
         }
     }
 
@@ -649,25 +1295,241 @@ impl Operation {
         match self {
             Self::Compress { .. }
             | Self::Copy { .. }
+            | Self::CopyAs { .. }
             | Self::Delete { .. }
             | Self::EmptyTrash
             | Self::Extract { .. }
             | Self::Move { .. }
-            | Self::Restore { .. } => true,
+            | Self::PermanentDelete { .. }
+            | Self::Restore { .. }
+            | Self::RestorePaths { .. }
+            | Self::SetModified { .. }
+            | Self::SetPermissions { .. } => true,
             Self::NewFile { .. }
             | Self::NewFolder { .. }
             | Self::Rename { .. }
-            | Self::SetExecutableAndLaunch { .. } => false,
+            | Self::SetExecutableAndLaunch { .. }
+            | Self::SetSymlinkTarget { .. } => false,
+        }
+    }
+
+    pub fn toast(&self) -> Option<String> {
+        match self {
+            Self::Compress { .. } => Some(self.completed_text()),
+            Self::Delete { .. } => Some(self.completed_text()),
+            Self::Extract { .. } => Some(self.completed_text()),
+            //TODO: more toasts
+            _ => None,
+        }
+    }
+
+    /// Whether running this operation adds, removes or changes items listed in a folder
+    pub fn changes_dir(&self, dir: &Path) -> bool {
+        let in_dir = |path: &Path| path.parent() == Some(dir);
+        match self {
+            Self::Copy { to, .. } | Self::Extract { to, .. } => to == dir,
+            Self::Move { paths, to } => to == dir || paths.iter().any(|path| in_dir(path)),
+            Self::Compress { to, .. } | Self::CopyAs { to, .. } => in_dir(to),
+            Self::Delete { paths }
+            | Self::PermanentDelete { paths }
+            | Self::RestorePaths { paths } => paths.iter().any(|path| in_dir(path)),
+            Self::NewFile { path, .. }
+            | Self::NewFolder { path }
+            | Self::SetExecutableAndLaunch { path }
+            | Self::SetSymlinkTarget { path, .. } => in_dir(path),
+            Self::Rename { from, to } => in_dir(from) || in_dir(to),
+            Self::Restore { items } => items.iter().any(|item| item.original_parent == dir),
+            // Recursive changes also reach the items of folders below the given paths
+            Self::SetModified {
+                paths, recursive, ..
+            } => paths
+                .iter()
+                .any(|path| in_dir(path) || (*recursive && dir.starts_with(path))),
+            Self::SetPermissions {
+                path, recursive, ..
+            } => in_dir(path) || (*recursive && dir.starts_with(path)),
+            Self::EmptyTrash => false,
+        }
+    }
+
+    /// Paths that running this operation may add, remove or change the contents of
+    pub fn changed_paths(&self) -> Vec<PathBuf> {
+        match self {
+            Self::Compress { to, .. }
+            | Self::Copy { to, .. }
+            | Self::CopyAs { to, .. }
+            | Self::Extract { to, .. } => vec![to.clone()],
+            Self::Delete { paths }
+            | Self::PermanentDelete { paths }
+            | Self::RestorePaths { paths }
+            | Self::SetModified { paths, .. } => paths.clone(),
+            Self::Move { paths, to } => paths.iter().chain([to]).cloned().collect(),
+            Self::NewFile { path, .. }
+            | Self::NewFolder { path }
+            | Self::SetExecutableAndLaunch { path }
+            | Self::SetPermissions { path, .. }
+            | Self::SetSymlinkTarget { path, .. } => vec![path.clone()],
+            Self::Rename { from, to } => vec![from.clone(), to.clone()],
+            Self::Restore { items } => items.iter().map(|item| item.original_path()).collect(),
+            Self::EmptyTrash => Vec::new(),
+        }
+    }
+
+    /// Items this operation acts on, in the order they are processed, with how far it got from
+    /// the path being processed. Copied and moved items are also found by their target.
+    pub fn item_progress(&self, current_opt: Option<&Path>) -> Vec<(PathBuf, ItemProgress)> {
+        let (paths, to_opt) = match self {
+            Self::Copy { paths, to } | Self::Move { paths, to } => (paths.clone(), Some(to)),
+            Self::Compress { paths, .. }
+            | Self::Delete { paths }
+            | Self::Extract { paths, .. }
+            | Self::PermanentDelete { paths }
+            | Self::RestorePaths { paths }
+            | Self::SetModified { paths, .. } => (paths.clone(), None),
+            Self::CopyAs { from, .. } | Self::Rename { from, .. } => (vec![from.clone()], None),
+            Self::SetExecutableAndLaunch { path }
+            | Self::SetPermissions { path, .. }
+            | Self::SetSymlinkTarget { path, .. } => (vec![path.clone()], None),
+            Self::Restore { items } => (
+                items.iter().map(|item| item.original_path()).collect(),
+                None,
+            ),
+            Self::EmptyTrash | Self::NewFile { .. } | Self::NewFolder { .. } => (Vec::new(), None),
+        };
+        let current_i_opt = current_opt.and_then(|current| {
+            let processing = |path: &PathBuf| {
+                current.starts_with(path)
+                    || to_opt
+                        .zip(path.file_name())
+                        .is_some_and(|(to, name)| current.starts_with(to.join(name)))
+            };
+            // A single item is being processed whatever the path, like the extracted files
+            paths
+                .iter()
+                .position(processing)
+                .or_else(|| (paths.len() == 1).then_some(0))
+        });
+        paths
+            .into_iter()
+            .enumerate()
+            .map(|(i, path)| {
+                let progress = match current_i_opt {
+                    Some(current_i) if i < current_i => ItemProgress::Done,
+                    Some(current_i) if i == current_i => ItemProgress::Current,
+                    _ => ItemProgress::Pending,
+                };
+                (path, progress)
+            })
+            .collect()
+    }
+
+    /// Record how to revert this operation once it has completed
+    pub fn history(&self, op_sel: &OperationSelection) -> Option<OperationHistory> {
+        let (redo, undo) = match self {
+            Self::Compress { to, .. } => (
+                self.clone(),
+                vec![Self::PermanentDelete {
+                    paths: vec![to.clone()],
+                }],
+            ),
+            // Copies can only be reverted by deleting the items that were created
+            Self::Copy { .. } | Self::CopyAs { .. } | Self::Extract { .. } => (
+                self.clone(),
+                vec![Self::PermanentDelete {
+                    paths: op_sel.selected.clone(),
+                }],
+            ),
+            Self::Delete { paths } => (
+                self.clone(),
+                vec![Self::RestorePaths {
+                    paths: paths.clone(),
+                }],
+            ),
+            Self::Move { .. } => {
+                // Move items back, grouped by their original parent
+                let mut moves: Vec<(PathBuf, Vec<PathBuf>)> = Vec::new();
+                for (from, to) in op_sel.from_to.iter() {
+                    let Some(parent) = from.parent() else {
+                        continue;
+                    };
+                    match moves.iter_mut().find(|(moved_to, _)| moved_to == parent) {
+                        Some((_, paths)) => paths.push(to.clone()),
+                        None => moves.push((parent.to_path_buf(), vec![to.clone()])),
+                    }
+                }
+                (
+                    self.clone(),
+                    moves
+                        .into_iter()
+                        .map(|(to, paths)| Self::Move { paths, to })
+                        .collect(),
+                )
+            }
+            Self::NewFile { path, .. } | Self::NewFolder { path } => (
+                self.clone(),
+                vec![Self::Delete {
+                    paths: vec![path.clone()],
+                }],
+            ),
+            Self::Rename { from, to } => (
+                self.clone(),
+                vec![Self::Rename {
+                    from: to.clone(),
+                    to: from.clone(),
+                }],
+            ),
+            // Trash items are consumed by restoring, so redo by original location
+            Self::Restore { .. } => (
+                Self::RestorePaths {
+                    paths: op_sel.selected.clone(),
+                },
+                vec![Self::Delete {
+                    paths: op_sel.selected.clone(),
+                }],
+            ),
+            Self::EmptyTrash
+            | Self::PermanentDelete { .. }
+            | Self::RestorePaths { .. }
+            | Self::SetExecutableAndLaunch { .. }
+            | Self::SetModified { .. }
+            | Self::SetPermissions { .. }
+            | Self::SetSymlinkTarget { .. } => return None,
+        };
+        if undo.iter().all(|op| op.is_empty()) {
+            return None;
         }
+        let replaced = match self {
+            Self::Copy { .. } | Self::CopyAs { .. } | Self::Extract { .. } => {
+                op_sel.replaced.clone()
+            }
+            _ => Vec::new(),
+        };
+        Some(OperationHistory {
+            redo,
+            undo,
+            replaced,
+        })
     }
 
-    pub fn toast(&self) -> Option<String> {
+    fn is_empty(&self) -> bool {
         match self {
-            Self::Compress { .. } => Some(self.completed_text()),
-            Self::Delete { .. } => Some(self.completed_text()),
-            Self::Extract { .. } => Some(self.completed_text()),
-            //TODO: more toasts
-            _ => None,
+            Self::Compress { paths, .. }
+            | Self::Copy { paths, .. }
+            | Self::Delete { paths }
+            | Self::Extract { paths, .. }
+            | Self::Move { paths, .. }
+            | Self::PermanentDelete { paths }
+            | Self::RestorePaths { paths }
+            | Self::SetModified { paths, .. } => paths.is_empty(),
+            Self::Restore { items } => items.is_empty(),
+            Self::CopyAs { .. }
+            | Self::EmptyTrash
+            | Self::NewFile { .. }
+            | Self::NewFolder { .. }
+            | Self::Rename { .. }
+            | Self::SetExecutableAndLaunch { .. }
+            | Self::SetPermissions { .. }
+            | Self::SetSymlinkTarget { .. } => false,
         }
     }
 
@@ -699,6 +1561,9 @@ impl Operation {
                         let op_sel = OperationSelection {
                             ignored: paths.clone(),
                             selected: vec![to.clone()],
+                            from_to: Vec::new(),
+                            changed: Vec::new(),
+                            replaced: Vec::new(),
                         };
 
                         let mut paths = paths;
@@ -730,6 +1595,7 @@ impl Operation {
                                     controller.check().map_err(OperationError::from_str)?;
 
                                     controller.set_progress((i as f32) / total_paths as f32);
+                                    controller.set_current(path);
 
                                     if let Some(relative_path) = path
                                         .strip_prefix(relative_root)
@@ -756,6 +1622,7 @@ impl Operation {
                                     controller.check().map_err(OperationError::from_str)?;
 
                                     controller.set_progress((i as f32) / total_paths as f32);
+                                    controller.set_current(path);
 
                                     let mut zip_options = zip::write::SimpleFileOptions::default();
                                     if password.is_some() {
@@ -831,12 +1698,14 @@ impl Operation {
                 //.map_err(|e| e)?
             }
             Self::Copy { paths, to } => copy_or_move(paths, to, false, msg_tx, controller).await,
+            Self::CopyAs { from, to } => copy_as(from, to, msg_tx, controller).await,
             Self::Delete { paths } => {
                 let total = paths.len();
                 for (i, path) in paths.into_iter().enumerate() {
                     controller.check().map_err(OperationError::from_str)?;
 
                     controller.set_progress((i as f32) / (total as f32));
+                    controller.set_current(&path);
 
                     let _items_opt = tokio::task::spawn_blocking(|| trash::delete(path))
                         .await
@@ -859,14 +1728,32 @@ impl Operation {
                 {
                     tokio::task::spawn_blocking(move || -> Result<(), OperationError> {
                         let items = trash::os_limited::list().map_err(OperationError::from_str)?;
+                        // Sizing large trashed folders takes a while, so it shows which item
+                        // is being looked at and can be cancelled like removing
+                        let mut sizes = Vec::with_capacity(items.len());
+                        for item in items.iter() {
+                            controller.check().map_err(OperationError::from_str)?;
+                            controller.set_current(&item.original_path());
+                            sizes.push(trash_item_size(item));
+                        }
+                        let total: u64 = sizes.iter().sum();
                         let count = items.len();
-                        for (i, item) in items.into_iter().enumerate() {
+                        let mut removed = 0;
+                        for (i, (item, size)) in items.into_iter().zip(sizes).enumerate() {
+                            // Cancelling stops between items, leaving the rest in the trash
                             controller.check().map_err(OperationError::from_str)?;
 
-                            controller.set_progress(i as f32 / count as f32);
+                            // Progress by bytes removed, or by items if no sizes are known
+                            controller.set_progress(if total > 0 {
+                                removed as f32 / total as f32
+                            } else {
+                                i as f32 / count as f32
+                            });
+                            controller.set_current(&item.original_path());
 
                             trash::os_limited::purge_all([item])
                                 .map_err(OperationError::from_str)?;
+                            removed += size;
                         }
                         Ok(())
                     })
@@ -888,6 +1775,7 @@ impl Operation {
                             controller.check().map_err(OperationError::from_str)?;
 
                             controller.set_progress((i as f32) / total_paths as f32);
+                            controller.set_current(path);
 
                             if let Some(file_name) = path.file_name().and_then(|f| f.to_str()) {
                                 let dir_name = get_directory_name(file_name);
@@ -974,6 +1862,32 @@ impl Operation {
                 //.map_err(OperationError::from_str)?
             }
             Self::Move { paths, to } => copy_or_move(paths, to, true, msg_tx, controller).await,
+            Self::PermanentDelete { paths } => tokio::task::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    let total = paths.len();
+                    for (i, path) in paths.iter().enumerate() {
+                        controller.check().map_err(OperationError::from_str)?;
+
+                        controller.set_progress((i as f32) / (total as f32));
+                        controller.set_current(path);
+
+                        if path.is_dir() && !path.is_symlink() {
+                            fs::remove_dir_all(path).map_err(OperationError::from_str)?;
+                        } else {
+                            fs::remove_file(path).map_err(OperationError::from_str)?;
+                        }
+                    }
+                    Ok(OperationSelection {
+                        ignored: paths,
+                        selected: Vec::new(),
+                        from_to: Vec::new(),
+                        changed: Vec::new(),
+                        replaced: Vec::new(),
+                    })
+                },
+            )
+            .await
+            .map_err(OperationError::from_str)?,
             Self::NewFolder { path } => tokio::task::spawn_blocking(
                 move || -> Result<OperationSelection, OperationError> {
                     controller.check().map_err(OperationError::from_str)?;
@@ -981,18 +1895,24 @@ impl Operation {
                     Ok(OperationSelection {
                         ignored: Vec::new(),
                         selected: vec![path],
+                        from_to: Vec::new(),
+                        changed: Vec::new(),
+                        replaced: Vec::new(),
                     })
                 },
             )
             .await
             .map_err(OperationError::from_str)?,
-            Self::NewFile { path } => tokio::task::spawn_blocking(
+            Self::NewFile { path, contents } => tokio::task::spawn_blocking(
                 move || -> Result<OperationSelection, OperationError> {
                     controller.check().map_err(OperationError::from_str)?;
-                    fs::File::create(&path).map_err(OperationError::from_str)?;
+                    fs::write(&path, contents).map_err(OperationError::from_str)?;
                     Ok(OperationSelection {
                         ignored: Vec::new(),
                         selected: vec![path],
+                        from_to: Vec::new(),
+                        changed: Vec::new(),
+                        replaced: Vec::new(),
                     })
                 },
             )
@@ -1003,8 +1923,11 @@ impl Operation {
                     controller.check().map_err(OperationError::from_str)?;
                     fs::rename(&from, &to).map_err(OperationError::from_str)?;
                     Ok(OperationSelection {
-                        ignored: vec![from],
-                        selected: vec![to],
+                        ignored: vec![from.clone()],
+                        selected: vec![to.clone()],
+                        from_to: vec![(from, to)],
+                        changed: Vec::new(),
+                        replaced: Vec::new(),
                     })
                 },
             )
@@ -1024,7 +1947,9 @@ impl Operation {
 
                     controller.set_progress((i as f32) / (total as f32));
 
-                    paths.push(item.original_path());
+                    let path = item.original_path();
+                    controller.set_current(&path);
+                    paths.push(path);
 
                     tokio::task::spawn_blocking(|| trash::os_limited::restore_all([item]))
                         .await
@@ -1034,8 +1959,56 @@ impl Operation {
                 Ok(OperationSelection {
                     ignored: Vec::new(),
                     selected: paths,
+                    from_to: Vec::new(),
+                    changed: Vec::new(),
+                    replaced: Vec::new(),
                 })
             }
+            #[cfg(target_os = "macos")]
+            Self::RestorePaths { .. } => {
+                // TODO: add support for macos
+                return Err(OperationError::from_str(
+                    "Restoring from trash is not supported on macos",
+                ));
+            }
+            #[cfg(not(target_os = "macos"))]
+            Self::RestorePaths { paths } => {
+                tokio::task::spawn_blocking(
+                    move || -> Result<OperationSelection, OperationError> {
+                        let mut items =
+                            trash::os_limited::list().map_err(OperationError::from_str)?;
+                        // Restore the most recently trashed item for each path
+                        items.sort_by_key(|item| std::cmp::Reverse(item.time_deleted));
+                        let total = paths.len();
+                        let mut restored = Vec::with_capacity(total);
+                        for (i, path) in paths.into_iter().enumerate() {
+                            controller.check().map_err(OperationError::from_str)?;
+
+                            controller.set_progress((i as f32) / (total as f32));
+                            controller.set_current(&path);
+
+                            let Some(index) =
+                                items.iter().position(|item| item.original_path() == path)
+                            else {
+                                log::warn!("{:?} not found in trash", path);
+                                continue;
+                            };
+                            trash::os_limited::restore_all([items.remove(index)])
+                                .map_err(OperationError::from_str)?;
+                            restored.push(path);
+                        }
+                        Ok(OperationSelection {
+                            ignored: Vec::new(),
+                            selected: restored,
+                            from_to: Vec::new(),
+                            changed: Vec::new(),
+                            replaced: Vec::new(),
+                        })
+                    },
+                )
+                .await
+                .map_err(OperationError::from_str)?
+            }
             Self::SetExecutableAndLaunch { path } => {
                 tokio::task::spawn_blocking(move || -> Result<(), OperationError> {
                     //TODO: what to do on non-Unix systems?
@@ -1066,6 +2039,57 @@ impl Operation {
                 .map_err(|e| e)?;
                 Ok(OperationSelection::default())
             }
+            Self::SetModified {
+                paths,
+                time,
+                recursive,
+            } => tokio::task::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    set_modified(&paths, time, recursive, &controller)?;
+                    Ok(OperationSelection {
+                        ignored: Vec::new(),
+                        selected: paths,
+                        from_to: Vec::new(),
+                        changed: Vec::new(),
+                        replaced: Vec::new(),
+                    })
+                },
+            )
+            .await
+            .map_err(OperationError::from_str)?,
+            Self::SetPermissions {
+                path,
+                mode,
+                recursive,
+            } => tokio::task::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    set_permissions(&path, mode, recursive, &controller)?;
+                    Ok(OperationSelection {
+                        ignored: Vec::new(),
+                        selected: vec![path],
+                        from_to: Vec::new(),
+                        changed: Vec::new(),
+                        replaced: Vec::new(),
+                    })
+                },
+            )
+            .await
+            .map_err(OperationError::from_str)?,
+            Self::SetSymlinkTarget { path, target } => tokio::task::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    controller.check().map_err(OperationError::from_str)?;
+                    set_symlink_target(&path, &target)?;
+                    Ok(OperationSelection {
+                        ignored: Vec::new(),
+                        selected: vec![path],
+                        from_to: Vec::new(),
+                        changed: Vec::new(),
+                        replaced: Vec::new(),
+                    })
+                },
+            )
+            .await
+            .map_err(OperationError::from_str)?,
         };
 
         controller_clone.set_progress(100.0);
@@ -1079,7 +2103,9 @@ mod tests {
     use std::{
         fs::{self, File},
         io,
-        path::PathBuf,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+        time::{Duration, SystemTime},
     };
 
     use cosmic::iced::futures::{channel::mpsc, StreamExt};
@@ -1087,16 +2113,17 @@ mod tests {
     use test_log::test;
     use tokio::sync;
 
-    use super::{Controller, Operation, OperationError, OperationSelection, ReplaceResult};
-    use crate::{
-        app::{
-            test_utils::{
-                empty_fs, filter_dirs, filter_files, simple_fs, NAME_LEN, NUM_DIRS, NUM_FILES,
-                NUM_HIDDEN, NUM_NESTED,
-            },
-            DialogPage, Message,
+    use super::{
+        numbered_unique_path, recursive::Context, set_symlink_target, trash_dir, unique_name,
+        unique_name_template_valid, Controller, ItemProgress, Operation, OperationError,
+        OperationSelection, ReplaceResult,
+    };
+    use crate::app::{
+        test_utils::{
+            empty_fs, filter_dirs, filter_files, simple_fs, NAME_LEN, NUM_DIRS, NUM_FILES,
+            NUM_HIDDEN, NUM_NESTED,
         },
-        fl,
+        DialogPage, Message,
     };
 
     // Tests hang with lower values
@@ -1162,12 +2189,110 @@ mod tests {
             .expect("Copy operation should have succeeded");
 
         assert!(first_file.exists(), "Original file should still exist");
-        let expected = first_dir.join(format!("{base_name} ({} 1)", fl!("copy_noun")));
+        let expected = first_dir.join(format!("{base_name} (2)"));
         assert!(expected.exists(), "File should have been duplicated");
 
         Ok(())
     }
 
+    #[test(tokio::test)]
+    async fn set_modified_recurses_into_directories() -> io::Result<()> {
+        let fs = simple_fs(NUM_FILES, NUM_HIDDEN, NUM_DIRS, NUM_NESTED, NAME_LEN)?;
+        let path = fs.path();
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+
+        let (tx, _rx) = mpsc::channel(BUF_SIZE);
+        Operation::SetModified {
+            paths: filter_dirs(path)?.collect(),
+            time,
+            recursive: true,
+        }
+        .perform(&sync::Mutex::new(tx).into(), Controller::default())
+        .await
+        .expect("Setting modification times should have succeeded");
+
+        for dir in filter_dirs(path)? {
+            for entry in walkdir::WalkDir::new(&dir) {
+                let entry = entry?;
+                assert_eq!(
+                    entry.metadata()?.modified()?,
+                    time,
+                    "{} should have the new modification time",
+                    entry.path().display()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test(tokio::test)]
+    async fn set_modified_changes_symlinks_not_their_targets() -> io::Result<()> {
+        let temp = empty_fs()?;
+        let target = temp.path().join("target");
+        let folder = temp.path().join("folder");
+        let link = folder.join("link");
+        File::create(&target)?;
+        fs::create_dir(&folder)?;
+        std::os::unix::fs::symlink(&target, &link)?;
+        let target_modified = fs::metadata(&target)?.modified()?;
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+
+        let (tx, _rx) = mpsc::channel(BUF_SIZE);
+        Operation::SetModified {
+            paths: vec![folder],
+            time,
+            recursive: true,
+        }
+        .perform(&sync::Mutex::new(tx).into(), Controller::default())
+        .await
+        .expect("Setting modification times should have succeeded");
+
+        assert_eq!(fs::symlink_metadata(&link)?.modified()?, time);
+        assert_eq!(fs::metadata(&target)?.modified()?, target_modified);
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test(tokio::test)]
+    async fn set_permissions_recursive_only_keeps_execute_for_folders_and_programs(
+    ) -> io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = empty_fs()?;
+        let root = temp.path().join("root");
+        let folder = root.join("folder");
+        fs::create_dir_all(&folder)?;
+        let file = folder.join("file");
+        let program = folder.join("program");
+        File::create(&file)?;
+        File::create(&program)?;
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o644))?;
+        fs::set_permissions(&program, fs::Permissions::from_mode(0o755))?;
+
+        let (tx, _rx) = mpsc::channel(BUF_SIZE);
+        Operation::SetPermissions {
+            path: root.clone(),
+            mode: 0o750,
+            recursive: true,
+        }
+        .perform(&sync::Mutex::new(tx).into(), Controller::default())
+        .await
+        .expect("Setting permissions should have succeeded");
+
+        let mode = |path: &Path| -> io::Result<u32> {
+            Ok(fs::metadata(path)?.permissions().mode() & 0o777)
+        };
+        assert_eq!(mode(&root)?, 0o750);
+        assert_eq!(mode(&folder)?, 0o750);
+        assert_eq!(mode(&program)?, 0o750);
+        assert_eq!(mode(&file)?, 0o640);
+
+        Ok(())
+    }
+
     #[test(tokio::test)]
     async fn copy_file_with_extension_to_same_loc() -> io::Result<()> {
         let fs = empty_fs()?;
@@ -1182,7 +2307,7 @@ mod tests {
             .expect("Copy operation should have succeeded");
 
         assert!(base_path.exists(), "Original file should still exist");
-        let expected = path.join(format!("foo ({} 1).txt", fl!("copy_noun")));
+        let expected = path.join("foo (2).txt");
         assert!(expected.exists(), "File should have been duplicated");
 
         Ok(())
@@ -1207,7 +2332,7 @@ mod tests {
             .expect("Copy operation should have succeeded");
 
         assert!(first_dir.exists(), "Original directory should still exist");
-        let expected = path.join(format!("{base_name} ({} 1)", fl!("copy_noun")));
+        let expected = path.join(format!("{base_name} (2)"));
         assert!(expected.exists(), "Directory should have been duplicated");
 
         Ok(())
@@ -1229,8 +2354,7 @@ mod tests {
                 .expect("Copy operation should have succeeded");
             assert!(base_path.exists(), "Original file should still exist");
             assert!(
-                path.join(format!("{base_name} ({} {i})", fl!("copy_noun")))
-                    .exists(),
+                path.join(format!("{base_name} ({})", i + 1)).exists(),
                 "File should have been duplicated (copy #{i})"
             );
         }
@@ -1302,4 +2426,454 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn concurrent_copy_copies_every_file() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let from = fs.path().join("from");
+        let to = fs.path().join("to");
+        fs::create_dir_all(from.join("nested"))?;
+        for i in 0..20 {
+            let dir = if i % 2 == 0 {
+                from.clone()
+            } else {
+                from.join("nested")
+            };
+            fs::write(dir.join(format!("{i}.txt")), i.to_string().repeat(i * 1000))?;
+        }
+
+        let fractions = Arc::new(Mutex::new(Vec::new()));
+        let mut context = {
+            let fractions = fractions.clone();
+            Context::new(Controller::default())
+                .copy_options(0, 4, false)
+                .on_progress(move |_op, progress| {
+                    fractions.lock().unwrap().push(progress.fraction());
+                })
+        };
+        assert_eq!(
+            context.recursive_copy_or_move(vec![(from.clone(), to.clone())], false),
+            Ok(true)
+        );
+        assert_eq!(context.op_sel.selected, [to.clone()]);
+        for i in 0..20 {
+            let name = format!("{i}.txt");
+            let relative = if i % 2 == 0 {
+                PathBuf::from(name)
+            } else {
+                PathBuf::from("nested").join(name)
+            };
+            assert_eq!(
+                fs::read(to.join(&relative))?,
+                fs::read(from.join(&relative))?
+            );
+        }
+
+        // Progress of files copied at the same time never goes backwards
+        let fractions = fractions.lock().unwrap();
+        assert!(fractions.windows(2).all(|x| x[0] <= x[1]));
+        assert!(fractions.iter().all(|x| (0.0..=1.0).contains(x)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_reports_files_changed_while_copying() -> io::Result<()> {
+        use std::io::Write;
+
+        let fs = empty_fs()?;
+        let from = fs.path().join("download");
+        let copy = |to: PathBuf, strict: bool| {
+            let source = from.clone();
+            let mut context = Context::new(Controller::default())
+                .copy_options(0, 1, strict)
+                .on_progress(move |_op, progress| {
+                    // Append to the source once its first chunk was copied
+                    if progress.current_bytes == 4 {
+                        fs::OpenOptions::new()
+                            .append(true)
+                            .open(&source)
+                            .and_then(|mut file| file.write_all(b"ferris"))
+                            .unwrap();
+                    }
+                });
+            let result = context.recursive_copy_or_move(vec![(from.clone(), to)], false);
+            (result, context.op_sel.changed)
+        };
+
+        fs::write(&from, "crab")?;
+        let to = fs.path().join("copy");
+        let (result, changed) = copy(to.clone(), false);
+        assert_eq!(result, Ok(true));
+        assert_eq!(changed, [from.clone()]);
+        assert!(to.is_file());
+
+        // Strict copies fail and remove the incomplete copy
+        fs::write(&from, "crab")?;
+        let to = fs.path().join("strict copy");
+        let (result, changed) = copy(to.clone(), true);
+        assert!(result.is_err());
+        assert!(changed.is_empty());
+        assert!(!to.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_history_keeps_replaced_items() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let from = fs.path().join("from");
+        let to = fs.path().join("to");
+        fs::create_dir_all(&from)?;
+        fs::create_dir_all(&to)?;
+        fs::write(from.join("crab"), "ferris")?;
+        fs::write(to.join("crab"), "corro")?;
+
+        let mut context =
+            Context::new(Controller::default()).on_replace(|_op| ReplaceResult::Replace(false));
+        assert_eq!(
+            context.recursive_copy_or_move(vec![(from.join("crab"), to.join("crab"))], false),
+            Ok(true)
+        );
+        assert_eq!(fs::read_to_string(to.join("crab"))?, "ferris");
+        assert_eq!(context.op_sel.replaced, [to.join("crab")]);
+
+        let op = Operation::Copy {
+            paths: vec![from.join("crab")],
+            to: to.clone(),
+        };
+        let history = op
+            .history(&context.op_sel)
+            .expect("Copy should be in the history");
+        assert_eq!(history.replaced, [to.join("crab")]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn undoing_a_skipped_copy_keeps_the_existing_file() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let from = fs.path().join("from");
+        let to = fs.path().join("to");
+        fs::create_dir_all(&from)?;
+        fs::create_dir_all(&to)?;
+        fs::write(from.join("crab"), "ferris")?;
+        fs::write(to.join("crab"), "corro")?;
+
+        for moving in [false, true] {
+            let mut context =
+                Context::new(Controller::default()).on_replace(|_op| ReplaceResult::Skip(false));
+            assert_eq!(
+                context.recursive_copy_or_move(vec![(from.join("crab"), to.join("crab"))], moving),
+                Ok(true)
+            );
+            assert!(context.op_sel.selected.is_empty());
+            assert!(context.op_sel.from_to.is_empty());
+
+            let op = if moving {
+                Operation::Move {
+                    paths: vec![from.join("crab")],
+                    to: to.clone(),
+                }
+            } else {
+                Operation::Copy {
+                    paths: vec![from.join("crab")],
+                    to: to.clone(),
+                }
+            };
+            let history = op
+                .history(&context.op_sel)
+                .expect("operation should be in the history");
+            for undo in history.undo {
+                let (tx, _rx) = mpsc::channel(BUF_SIZE);
+                undo.perform(&sync::Mutex::new(tx).into(), Controller::default())
+                    .await
+                    .expect("undo should have succeeded");
+            }
+            assert_eq!(fs::read_to_string(to.join("crab"))?, "corro");
+            assert_eq!(fs::read_to_string(from.join("crab"))?, "ferris");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn numbered_unique_path_skips_existing_names() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+
+        for name in ["foo.tar.gz", "foo (2).tar.gz", "bar"] {
+            File::create(path.join(name))?;
+        }
+        let from = path.join("foo.tar.gz");
+        assert_eq!(
+            numbered_unique_path(&from, &path.join("foo.tar.gz")),
+            path.join("foo (3).tar.gz")
+        );
+        let from = path.join("bar");
+        assert_eq!(
+            numbered_unique_path(&from, &path.join("bar")),
+            path.join("bar (2)")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn unique_name_fills_in_template() {
+        assert_eq!(
+            unique_name("{name} ({n}){ext}", "foo", Some("tar.gz"), 2),
+            "foo (2).tar.gz"
+        );
+        assert_eq!(unique_name("{name}_{n}{ext}", "bar", None, 0), "bar_0");
+        assert_eq!(
+            unique_name("{n}-{name}{ext}", "{n}", Some("txt"), 1),
+            "1-{n}.txt"
+        );
+        assert_eq!(unique_name("{name} {x}{n}", "foo", None, 3), "foo {x}3");
+        assert!(unique_name_template_valid("{name} ({n}){ext}"));
+        assert!(!unique_name_template_valid("{name} copy{ext}"));
+        assert!(!unique_name_template_valid("{n}/{name}{ext}"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn set_symlink_target_replaces_the_link() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+        File::create(path.join("old"))?;
+        File::create(path.join("new"))?;
+        let link = path.join("link");
+        std::os::unix::fs::symlink("old", &link)?;
+
+        set_symlink_target(&link, Path::new("new")).expect("link should be retargeted");
+        assert_eq!(fs::read_link(&link)?, PathBuf::from("new"));
+        // No temporary link is left behind
+        assert_eq!(fs::read_dir(path)?.count(), 3);
+        // Other items are not replaced by links
+        assert!(set_symlink_target(&path.join("old"), Path::new("new")).is_err());
+        assert!(path.join("old").is_file());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn move_history_moves_items_back() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+
+        let dir_path = path.join("cosmic");
+        fs::create_dir(&dir_path)?;
+        let file_path = path.join("ferris");
+        File::create(&file_path)?;
+
+        let (tx, _rx) = mpsc::channel(BUF_SIZE);
+        let msg_tx = sync::Mutex::new(tx).into();
+        let op = Operation::Move {
+            paths: vec![file_path.clone()],
+            to: dir_path.clone(),
+        };
+        let op_sel = op
+            .clone()
+            .perform(&msg_tx, Controller::default())
+            .await
+            .expect("Move operation should have succeeded");
+        assert!(
+            dir_path.join("ferris").exists(),
+            "File should have been moved"
+        );
+
+        let history = op.history(&op_sel).expect("Move should be undoable");
+        assert!(history.destructive_paths().is_empty());
+        for undo in history.undo {
+            undo.perform(&msg_tx, Controller::default())
+                .await
+                .expect("Undo operation should have succeeded");
+        }
+        assert!(file_path.exists(), "File should have been moved back");
+        assert!(!dir_path.join("ferris").exists());
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn disk_space_of_existing_and_missing_paths() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let (free, total) =
+            super::disk_space(fs.path()).expect("temporary folder should have a filesystem");
+        assert!(total > 0);
+        assert!(free <= total);
+        assert_eq!(super::disk_space(&fs.path().join("missing")), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn paths_size_counts_folder_contents() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+
+        let dir_path = path.join("cosmic");
+        fs::create_dir(&dir_path)?;
+        fs::write(dir_path.join("ferris"), [0; 100])?;
+        fs::write(path.join("crab"), [0; 20])?;
+
+        let size = super::paths_size(
+            &[dir_path.as_path(), path.join("crab").as_path()],
+            &Controller::default(),
+        )
+        .expect("size should be calculated");
+        assert_eq!(size, 120);
+
+        #[cfg(unix)]
+        assert!(super::same_filesystem(&dir_path, path));
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn copy_as_renames_copy() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+
+        let dir_path = path.join("cosmic");
+        fs::create_dir(&dir_path)?;
+        let file_path = path.join("ferris");
+        fs::write(&file_path, "crab")?;
+        let expected = dir_path.join("ferris copy");
+
+        let (tx, _rx) = mpsc::channel(BUF_SIZE);
+        let msg_tx = sync::Mutex::new(tx).into();
+        let op = Operation::CopyAs {
+            from: file_path.clone(),
+            to: expected.clone(),
+        };
+        let op_sel = op
+            .clone()
+            .perform(&msg_tx, Controller::default())
+            .await
+            .expect("Copy operation should have succeeded");
+
+        assert!(file_path.exists(), "Original file should still exist");
+        assert_eq!(fs::read_to_string(&expected)?, "crab");
+        assert!(!dir_path.join("ferris").exists());
+        assert_eq!(op_sel.selected, vec![expected.clone()]);
+
+        let history = op.history(&op_sel).expect("Copy should be undoable");
+        assert_eq!(history.destructive_paths(), vec![expected]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn new_file_written_with_pasted_text() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let file_path = fs.path().join("snippet.txt");
+
+        let (tx, _rx) = mpsc::channel(BUF_SIZE);
+        let msg_tx = sync::Mutex::new(tx).into();
+        let op_sel = Operation::NewFile {
+            path: file_path.clone(),
+            contents: "fn main() {}\n".to_string(),
+        }
+        .perform(&msg_tx, Controller::default())
+        .await
+        .expect("New file operation should have succeeded");
+
+        assert_eq!(fs::read_to_string(&file_path)?, "fn main() {}\n");
+        assert_eq!(op_sel.selected, vec![file_path]);
+
+        Ok(())
+    }
+    #[cfg(unix)]
+    #[test]
+    fn trash_folder_created_only_for_user() -> io::Result<()> {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+        let fs = empty_fs()?;
+        // A shared trash without the sticky bit is not used
+        fs::DirBuilder::new()
+            .mode(0o777)
+            .create(fs.path().join(".Trash"))?;
+        fs::set_permissions(fs.path().join(".Trash"), fs::Permissions::from_mode(0o777))?;
+        assert!(!trash_dir::has_trash(fs.path()));
+
+        trash_dir::create(fs.path())?;
+        assert!(trash_dir::has_trash(fs.path()));
+        let trash = fs
+            .path()
+            .join(format!(".Trash-{}", unsafe { libc::getuid() }));
+        assert!(trash.join("files").is_dir());
+        assert!(trash.join("info").is_dir());
+        assert_eq!(fs::metadata(&trash)?.permissions().mode() & 0o777, 0o700);
+
+        Ok(())
+    }
+
+    #[test]
+    fn operations_change_source_and_destination_dirs() {
+        let dir = PathBuf::from("/home/user/cosmic");
+        let other = PathBuf::from("/home/user/other");
+        let moved = Operation::Move {
+            paths: vec![dir.join("ferris")],
+            to: other.clone(),
+        };
+        assert!(moved.changes_dir(&dir));
+        assert!(moved.changes_dir(&other));
+        assert!(!moved.changes_dir(&dir.join("ferris")));
+
+        let copied = Operation::Copy {
+            paths: vec![dir.join("ferris")],
+            to: other.clone(),
+        };
+        assert!(!copied.changes_dir(&dir));
+        assert!(copied.changes_dir(&other));
+
+        // Recursive changes reach folders below the path
+        let permissions = |recursive| Operation::SetPermissions {
+            path: dir.clone(),
+            mode: 0o755,
+            recursive,
+        };
+        assert!(permissions(false).changes_dir(Path::new("/home/user")));
+        assert!(!permissions(false).changes_dir(&dir.join("crab")));
+        assert!(permissions(true).changes_dir(&dir.join("crab")));
+    }
+
+    #[test]
+    fn copied_items_progress_by_their_target() {
+        let dir = PathBuf::from("/home/user/cosmic");
+        let other = PathBuf::from("/home/user/other");
+        let paths = vec![dir.join("crab"), dir.join("ferris"), dir.join("gear")];
+        let copied = Operation::Copy {
+            paths: paths.clone(),
+            to: other.clone(),
+        };
+        let progress = |current_opt: Option<PathBuf>| -> Vec<ItemProgress> {
+            copied
+                .item_progress(current_opt.as_deref())
+                .into_iter()
+                .zip(paths.iter())
+                .map(|((path, progress), expected)| {
+                    assert_eq!(&path, expected);
+                    progress
+                })
+                .collect()
+        };
+        assert_eq!(progress(None), [ItemProgress::Pending; 3]);
+        assert_eq!(
+            progress(Some(other.join("ferris").join("file.txt"))),
+            [
+                ItemProgress::Done,
+                ItemProgress::Current,
+                ItemProgress::Pending
+            ]
+        );
+        // A different item with the name of a copied one is not a target
+        assert_eq!(
+            progress(Some(other.join("ferris-2"))),
+            [ItemProgress::Pending; 3]
+        );
+    }
 }
@@ -3,19 +3,60 @@ use std::{
     fs,
     io::{Read, Write},
     ops::ControlFlow,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::atomic::{self, AtomicBool, AtomicU64, AtomicUsize},
+    thread,
+    time::Duration,
 };
 use walkdir::WalkDir;
 
-use super::{copy_unique_path, Controller, OperationSelection, ReplaceResult};
+use super::{
+    copy_unique_path, numbered_unique_path, Controller, OperationSelection, ReplaceResult,
+};
+
+/// Size of the buffer each file is copied through by default
+pub const COPY_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+/// Number of files copied at the same time within one operation by default
+pub const COPY_CONCURRENCY: usize = 1;
+
+const COPY_BUFFER_SIZE_MIN: usize = 64 * 1024;
+const COPY_BUFFER_SIZE_MAX: usize = 64 * 1024 * 1024;
+const COPY_CONCURRENCY_MAX: usize = 16;
+
+// Options used by operations that start after they are changed
+static COPY_BUFFER: AtomicUsize = AtomicUsize::new(COPY_BUFFER_SIZE);
+static COPY_WORKERS: AtomicUsize = AtomicUsize::new(COPY_CONCURRENCY);
+static COPY_STRICT: AtomicBool = AtomicBool::new(false);
+
+/// Change the buffer size and the number of files copied at the same time, clamped to
+/// reasonable ranges. Copying more files at the same time helps with high latency remote
+/// folders, but can slow down spinning disks that have to seek between the files. Strict
+/// copies fail when a file changes while it is copied, instead of reporting it.
+pub fn set_copy_options(buffer_size: usize, concurrency: usize, strict: bool) {
+    let (buffer_size, concurrency) = clamp_copy_options(buffer_size, concurrency);
+    COPY_BUFFER.store(buffer_size, atomic::Ordering::Relaxed);
+    COPY_WORKERS.store(concurrency, atomic::Ordering::Relaxed);
+    COPY_STRICT.store(strict, atomic::Ordering::Relaxed);
+}
+
+fn clamp_copy_options(buffer_size: usize, concurrency: usize) -> (usize, usize) {
+    (
+        buffer_size.clamp(COPY_BUFFER_SIZE_MIN, COPY_BUFFER_SIZE_MAX),
+        concurrency.clamp(1, COPY_CONCURRENCY_MAX),
+    )
+}
 
 pub struct Context {
     buf: Vec<u8>,
+    concurrency: usize,
     controller: Controller,
     on_progress: Box<dyn OnProgress>,
     on_replace: Box<dyn OnReplace>,
     pub(crate) op_sel: OperationSelection,
     replace_result_opt: Option<ReplaceResult>,
+    // Sources that were skipped, which must not be removed when moving
+    skipped: Vec<PathBuf>,
+    strict: bool,
 }
 
 pub trait OnProgress: Fn(&Op, &Progress) + 'static {}
@@ -27,12 +68,15 @@ impl<F> OnReplace for F where F: Fn(&Op) -> ReplaceResult + 'static {}
 impl Context {
     pub fn new(controller: Controller) -> Self {
         Self {
-            buf: vec![0; 4 * 1024 * 1024],
+            buf: vec![0; COPY_BUFFER.load(atomic::Ordering::Relaxed)],
+            concurrency: COPY_WORKERS.load(atomic::Ordering::Relaxed),
             controller,
             on_progress: Box::new(|_op, _progress| {}),
             on_replace: Box::new(|_op| ReplaceResult::Cancel),
             op_sel: OperationSelection::default(),
             replace_result_opt: None,
+            skipped: Vec::new(),
+            strict: COPY_STRICT.load(atomic::Ordering::Relaxed),
         }
     }
 
@@ -106,27 +150,38 @@ impl Context {
         }
 
         let total_ops = ops.len();
-        for (current_ops, mut op) in ops.into_iter().enumerate() {
+        let mut ops = ops.into_iter().enumerate().peekable();
+        while let Some((current_ops, mut op)) = ops.next() {
             self.controller.check()?;
 
+            if self.concurrency > 1 && matches!(op.kind, OpKind::Copy) {
+                // Files following each other are in folders that were already created
+                let mut batch = vec![op];
+                while let Some((_, next)) = ops.next_if(|(_, op)| matches!(op.kind, OpKind::Copy)) {
+                    batch.push(next);
+                }
+                match self.copy_concurrent(batch, current_ops, total_ops)? {
+                    Some(done) => {
+                        for op in done.iter() {
+                            self.op_done(op);
+                        }
+                        continue;
+                    }
+                    // Cancelled
+                    None => return Ok(false),
+                }
+            }
+
             let progress = Progress {
                 current_ops,
                 total_ops,
                 current_bytes: 0,
                 total_bytes: None,
+                concurrent_ops: 1,
             };
             (self.on_progress)(&op, &progress);
-            if op.run(self, progress).map_err(|err| {
-                format!(
-                    "failed to {:?} {:?} to {:?}: {}",
-                    op.kind, op.from, op.to, err
-                )
-            })? {
-                // The from path is ignored in the operation selection if it is a top level item
-                if self.op_sel.ignored.contains(&op.from) {
-                    // So add the to path to the selection
-                    self.op_sel.selected.push(op.to.clone());
-                }
+            if op.run(self, progress).map_err(|err| op.error(err))? {
+                self.op_done(&op);
             } else {
                 // Cancelled
                 return Ok(false);
@@ -136,6 +191,136 @@ impl Context {
         Ok(true)
     }
 
+    fn op_done(&mut self, op: &Op) {
+        // Skipped items were left as they are, so undoing must not delete or move them.
+        // Sources that changed while copying were still written and stay selected.
+        if self.skipped.contains(&op.from) && !self.op_sel.changed.contains(&op.from) {
+            return;
+        }
+        // The from path is ignored in the operation selection if it is a top level item
+        if self.op_sel.ignored.contains(&op.from) {
+            // So add the to path to the selection
+            self.op_sel.selected.push(op.to.clone());
+            if !matches!(op.kind, OpKind::Remove | OpKind::Rmdir) {
+                self.op_sel.from_to.push((op.from.clone(), op.to.clone()));
+            }
+        }
+    }
+
+    // Copy files with up to `concurrency` workers, returning the ops that are done or `None` if
+    // cancelled. Replacements are confirmed first, one at a time.
+    fn copy_concurrent(
+        &mut self,
+        ops: Vec<Op>,
+        current_ops: usize,
+        total_ops: usize,
+    ) -> Result<Option<Vec<Op>>, String> {
+        let mut done = Vec::with_capacity(ops.len());
+        let mut copies = Vec::with_capacity(ops.len());
+        let mut total_bytes = 0;
+        for mut op in ops {
+            self.controller.check()?;
+            let len = fs::metadata(&op.from)
+                .map_err(|err| op.error(err.into()))?
+                .len();
+            if op.to.is_file() {
+                match self.replace(&op).map_err(|err| op.error(err))? {
+                    ControlFlow::Continue(to) => {
+                        op.to = to;
+                    }
+                    // Skipped
+                    ControlFlow::Break(true) => {
+                        done.push(op);
+                        continue;
+                    }
+                    ControlFlow::Break(false) => return Ok(None),
+                }
+            }
+            total_bytes += len;
+            copies.push(op);
+        }
+
+        let progress = |current_bytes| Progress {
+            current_ops,
+            total_ops,
+            current_bytes,
+            total_bytes: Some(total_bytes),
+            concurrent_ops: copies.len(),
+        };
+        let Some(first) = copies.first() else {
+            return Ok(Some(done));
+        };
+        (self.on_progress)(first, &progress(0));
+
+        let buffer_size = self.buf.len();
+        let controller = &self.controller;
+        let next = &AtomicUsize::new(0);
+        let copied = &AtomicU64::new(0);
+        let failed = &AtomicBool::new(false);
+        let strict = self.strict;
+        let result = thread::scope(|scope| {
+            let copies = &copies;
+            let workers: Vec<_> = (0..self.concurrency.min(copies.len()))
+                .map(|_| {
+                    // Each worker returns the sources that changed while they were copied
+                    scope.spawn(move || -> Result<Vec<PathBuf>, String> {
+                        let mut buf = vec![0; buffer_size];
+                        let mut changed = Vec::new();
+                        while !failed.load(atomic::Ordering::Relaxed) {
+                            let Some(op) = copies.get(next.fetch_add(1, atomic::Ordering::Relaxed))
+                            else {
+                                break;
+                            };
+                            let unchanged =
+                                copy_file(&op.from, &op.to, &mut buf, controller, |count| {
+                                    copied.fetch_add(count, atomic::Ordering::Relaxed);
+                                })
+                                .and_then(|unchanged| check_source(unchanged, strict, &op.to))
+                                .map_err(|err| {
+                                    failed.store(true, atomic::Ordering::Relaxed);
+                                    op.error(err)
+                                })?;
+                            if !unchanged {
+                                changed.push(op.from.clone());
+                            }
+                        }
+                        Ok(changed)
+                    })
+                })
+                .collect();
+
+            // Workers cannot report progress themselves, so the bytes they copied are polled
+            while !workers.iter().all(|worker| worker.is_finished()) {
+                (self.on_progress)(first, &progress(copied.load(atomic::Ordering::Relaxed)));
+                thread::sleep(Duration::from_millis(100));
+            }
+            workers
+                .into_iter()
+                .try_fold(Vec::new(), |mut changed, worker| {
+                    let worker_changed = worker
+                        .join()
+                        .map_err(|_| "file copy worker panicked".to_string())??;
+                    changed.extend(worker_changed);
+                    Ok::<_, String>(changed)
+                })
+        });
+        self.op_sel.changed.extend(result?);
+        (self.on_progress)(first, &progress(total_bytes));
+
+        done.extend(copies);
+        Ok(Some(done))
+    }
+
+    /// Copy through a buffer of `buffer_size` bytes, with up to `concurrency` files at a time,
+    /// failing on files that change while they are copied if `strict`
+    pub fn copy_options(mut self, buffer_size: usize, concurrency: usize, strict: bool) -> Self {
+        let (buffer_size, concurrency) = clamp_copy_options(buffer_size, concurrency);
+        self.buf = vec![0; buffer_size];
+        self.concurrency = concurrency;
+        self.strict = strict;
+        self
+    }
+
     pub fn on_progress<F: OnProgress>(mut self, f: F) -> Self {
         self.on_progress = Box::new(f);
         self
@@ -156,16 +341,43 @@ impl Context {
                     self.replace_result_opt = Some(replace_result);
                 }
                 fs::remove_file(&op.to)?;
+                self.op_sel.replaced.push(op.to.clone());
                 Ok(ControlFlow::Continue(op.to.clone()))
             }
+            ReplaceResult::ReplaceIfNewer(apply_to_all) => {
+                if apply_to_all {
+                    self.replace_result_opt = Some(replace_result);
+                }
+                let modified = |path| fs::symlink_metadata(path).and_then(|m| m.modified());
+                match (modified(&op.from), modified(&op.to)) {
+                    (Ok(from), Ok(to)) if from > to => {
+                        fs::remove_file(&op.to)?;
+                        self.op_sel.replaced.push(op.to.clone());
+                        Ok(ControlFlow::Continue(op.to.clone()))
+                    }
+                    _ => {
+                        self.skipped.push(op.from.clone());
+                        Ok(ControlFlow::Break(true))
+                    }
+                }
+            }
             ReplaceResult::KeepBoth => match op.to.parent() {
                 Some(to_parent) => Ok(ControlFlow::Continue(copy_unique_path(&op.from, to_parent))),
                 None => Err(format!("failed to get parent of {:?}", op.to).into()),
             },
+            ReplaceResult::Rename(apply_to_all) => {
+                if apply_to_all {
+                    self.replace_result_opt = Some(replace_result);
+                }
+                Ok(ControlFlow::Continue(numbered_unique_path(
+                    &op.from, &op.to,
+                )))
+            }
             ReplaceResult::Skip(apply_to_all) => {
                 if apply_to_all {
                     self.replace_result_opt = Some(replace_result);
                 }
+                self.skipped.push(op.from.clone());
                 Ok(ControlFlow::Break(true))
             }
             ReplaceResult::Cancel => Ok(ControlFlow::Break(false)),
@@ -179,6 +391,72 @@ pub struct Progress {
     pub total_ops: usize,
     pub current_bytes: u64,
     pub total_bytes: Option<u64>,
+    /// Number of ops counted by the bytes, more than one when files are copied at the same time
+    pub concurrent_ops: usize,
+}
+
+impl Progress {
+    /// Progress of all ops, from 0 to 1
+    pub fn fraction(&self) -> f32 {
+        let item_progress = match self.total_bytes {
+            Some(total_bytes) => {
+                if total_bytes == 0 {
+                    1.0
+                } else {
+                    self.current_bytes as f32 / total_bytes as f32
+                }
+            }
+            None => 0.0,
+        };
+        (item_progress * self.concurrent_ops as f32 + self.current_ops as f32)
+            / self.total_ops as f32
+    }
+}
+
+// Copy a file to a new path with the same permissions, reporting the length of each chunk.
+// Returns whether the source stayed the same while it was copied.
+fn copy_file(
+    from: &Path,
+    to: &Path,
+    buf: &mut [u8],
+    controller: &Controller,
+    mut on_copied: impl FnMut(u64),
+) -> Result<bool, Box<dyn Error>> {
+    let mut from_file = fs::OpenOptions::new().read(true).open(from)?;
+    let metadata = from_file.metadata()?;
+    let mut copied = 0;
+    // This is atomic and ensures `to` is not created by any other process
+    let mut to_file = fs::OpenOptions::new()
+        .create_new(true)
+        .write(true)
+        .open(to)?;
+    to_file.set_permissions(metadata.permissions())?;
+    loop {
+        controller.check()?;
+
+        let count = from_file.read(buf)?;
+        if count == 0 {
+            break;
+        }
+        to_file.write_all(&buf[..count])?;
+        copied += count as u64;
+        on_copied(count as u64);
+    }
+    to_file.sync_all()?;
+    // Files written to while copying, like downloads, may have been copied partially
+    let after = from_file.metadata()?;
+    Ok(copied == metadata.len()
+        && after.len() == metadata.len()
+        && after.modified().ok() == metadata.modified().ok())
+}
+
+// Strict copies fail on sources that changed while they were copied, removing the copy
+fn check_source(unchanged: bool, strict: bool, to: &Path) -> Result<bool, Box<dyn Error>> {
+    if strict && !unchanged {
+        fs::remove_file(to)?;
+        return Err("the file changed while it was copied".into());
+    }
+    Ok(unchanged)
 }
 
 #[derive(Debug)]
@@ -199,6 +477,14 @@ pub struct Op {
 }
 
 impl Op {
+    /// Path written, or removed, by the op
+    pub fn target(&self) -> &Path {
+        match self.kind {
+            OpKind::Remove | OpKind::Rmdir => &self.from,
+            _ => &self.to,
+        }
+    }
+
     fn move_cleanup_op(&self) -> Option<Self> {
         let kind = match self.kind {
             OpKind::Copy | OpKind::Move | OpKind::Symlink { .. } => OpKind::Remove,
@@ -213,11 +499,17 @@ impl Op {
         })
     }
 
+    fn error(&self, err: Box<dyn Error>) -> String {
+        format!(
+            "failed to {:?} {:?} to {:?}: {}",
+            self.kind, self.from, self.to, err
+        )
+    }
+
     fn run(&mut self, ctx: &mut Context, mut progress: Progress) -> Result<bool, Box<dyn Error>> {
         match self.kind {
             OpKind::Copy => {
-                let mut from_file = fs::OpenOptions::new().read(true).open(&self.from)?;
-                let metadata = from_file.metadata()?;
+                let metadata = fs::metadata(&self.from)?;
                 // Remove `to` if overwriting and it is an existing file
                 if self.to.is_file() {
                     match ctx.replace(self)? {
@@ -231,24 +523,21 @@ impl Op {
                 }
                 progress.total_bytes = Some(metadata.len());
                 (ctx.on_progress)(self, &progress);
-                // This is atomic and ensures `to` is not created by any other process
-                let mut to_file = fs::OpenOptions::new()
-                    .create_new(true)
-                    .write(true)
-                    .open(&self.to)?;
-                to_file.set_permissions(metadata.permissions())?;
-                loop {
-                    ctx.controller.check()?;
-
-                    let count = from_file.read(&mut ctx.buf)?;
-                    if count == 0 {
-                        break;
-                    }
-                    to_file.write_all(&ctx.buf[..count])?;
-                    progress.current_bytes += count as u64;
-                    (ctx.on_progress)(self, &progress);
+                let unchanged = copy_file(
+                    &self.from,
+                    &self.to,
+                    &mut ctx.buf,
+                    &ctx.controller,
+                    |count| {
+                        progress.current_bytes += count;
+                        (ctx.on_progress)(self, &progress);
+                    },
+                )?;
+                if !check_source(unchanged, ctx.strict, &self.to)? {
+                    // The source is kept when moving, as its copy may be incomplete
+                    ctx.op_sel.changed.push(self.from.clone());
+                    ctx.skipped.push(self.from.clone());
                 }
-                to_file.sync_all()?;
             }
             OpKind::Move => {
                 // Remove `to` if overwriting and it is an existing file
@@ -282,13 +571,22 @@ impl Op {
                 }
             }
             OpKind::Mkdir => {
+                // Items copied into an existing folder are merged with what it holds
+                if self.to.is_dir() {
+                    ctx.op_sel.replaced.push(self.to.clone());
+                }
                 fs::create_dir_all(&self.to)?;
             }
             OpKind::Remove => {
-                fs::remove_file(&self.from)?;
+                if !ctx.skipped.contains(&self.from) {
+                    fs::remove_file(&self.from)?;
+                }
             }
             OpKind::Rmdir => {
-                fs::remove_dir(&self.from)?;
+                // Folders still holding skipped items are kept
+                if !ctx.skipped.iter().any(|path| path.starts_with(&self.from)) {
+                    fs::remove_dir(&self.from)?;
+                }
             }
             OpKind::Symlink { ref target } => {
                 // Remove `to` if overwriting and it is an existing file
@@ -0,0 +1,75 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Top directory of the filesystem holding `path`, if it has no trash folder the item can be
+/// moved to
+#[cfg(unix)]
+pub fn missing(path: &Path) -> Option<PathBuf> {
+    use std::os::unix::fs::MetadataExt;
+
+    let dev = fs::symlink_metadata(path).ok()?.dev();
+    // Items on the same filesystem as the home trash are moved there
+    let home_trash = dirs::data_dir().map(|dir| dir.join("Trash"));
+    if home_trash
+        .iter()
+        .chain(dirs::home_dir().iter())
+        .any(|dir| fs::metadata(dir).is_ok_and(|metadata| metadata.dev() == dev))
+    {
+        return None;
+    }
+
+    let mut topdir = path;
+    while let Some(parent) = topdir.parent() {
+        match fs::metadata(parent) {
+            Ok(metadata) if metadata.dev() == dev => topdir = parent,
+            _ => break,
+        }
+    }
+    if has_trash(topdir) {
+        None
+    } else {
+        Some(topdir.to_path_buf())
+    }
+}
+
+#[cfg(not(unix))]
+pub fn missing(_path: &Path) -> Option<PathBuf> {
+    None
+}
+
+/// Whether the top directory of a filesystem has a shared or a per user trash folder
+#[cfg(unix)]
+pub fn has_trash(topdir: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    // The shared trash is only used when it is a real folder with the sticky bit set
+    let shared = fs::symlink_metadata(topdir.join(".Trash")).is_ok_and(|metadata| {
+        metadata.is_dir() && metadata.permissions().mode() & libc::S_ISVTX as u32 != 0
+    });
+    shared || topdir.join(user_trash_name()).is_dir()
+}
+
+#[cfg(unix)]
+fn user_trash_name() -> String {
+    format!(".Trash-{}", unsafe { libc::getuid() })
+}
+
+/// Create the per user trash folder in the top directory of a filesystem, only accessible by
+/// the user
+#[cfg(unix)]
+pub fn create(topdir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    let trash = topdir.join(user_trash_name());
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true).mode(0o700);
+    builder.create(trash.join("files"))?;
+    builder.create(trash.join("info"))
+}
+
+#[cfg(not(unix))]
+pub fn create(_topdir: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
@@ -1048,7 +1048,7 @@ where
                     .map(std::borrow::Cow::Owned)
                     .collect(),
                 actions: DndAction::Copy | DndAction::Move,
-                preferred: DndAction::Move,
+                preferred: DndAction::Copy,
             };
             dnd_rectangles.push(dnd_rect);
         }
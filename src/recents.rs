@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Path of the recently used history shared with other applications
+pub fn path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("recently-used.xbel"))
+}
+
+/// Whether a file is in one of the folders excluded from Recents
+pub fn is_excluded(path: &Path, excluded: &[PathBuf]) -> bool {
+    excluded.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Remove the entries of the recently used history whose file is not kept, returning how many
+/// were removed
+pub fn retain(keep: impl Fn(&Path) -> bool) -> io::Result<usize> {
+    let Some(path) = path() else {
+        return Ok(0);
+    };
+    let xbel = match fs::read_to_string(&path) {
+        Ok(ok) => ok,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    };
+    let (xbel, removed) = retain_bookmarks(&xbel, keep);
+    if removed > 0 {
+        // Replaced at once, as other applications read the history at any time
+        let tmp = path.with_extension("xbel.tmp");
+        fs::write(&tmp, xbel)?;
+        fs::rename(&tmp, &path)?;
+    }
+    Ok(removed)
+}
+
+/// Length of the bookmark element at the start of `xbel`
+fn bookmark_len(xbel: &str) -> Option<usize> {
+    let tag_end = xbel.find('>')?;
+    if xbel[..tag_end].ends_with('/') {
+        return Some(tag_end + 1);
+    }
+    const END: &str = "</bookmark>";
+    xbel.find(END).map(|end| end + END.len())
+}
+
+/// Unescaped value of the href attribute of a bookmark element
+fn bookmark_href(bookmark: &str) -> Option<String> {
+    let tag = &bookmark[..bookmark.find('>')?];
+    let start = tag.find(" href=\"")? + " href=\"".len();
+    let value = &tag[start..start + tag[start..].find('"')?];
+    Some(
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// Remove the bookmarks of files that are not kept from the history document, along with their
+/// line. Bookmarks of other than local files are kept.
+pub fn retain_bookmarks(xbel: &str, keep: impl Fn(&Path) -> bool) -> (String, usize) {
+    let mut retained = String::with_capacity(xbel.len());
+    let mut removed = 0;
+    let mut rest = xbel;
+    while let Some(start) = rest.find("<bookmark ") {
+        let Some(len) = bookmark_len(&rest[start..]) else {
+            break;
+        };
+        let bookmark = &rest[start..start + len];
+        let path_opt = bookmark_href(bookmark)
+            .and_then(|href| url::Url::parse(&href).ok()?.to_file_path().ok());
+        if path_opt.map_or(true, |path| keep(&path)) {
+            retained.push_str(&rest[..start + len]);
+            rest = &rest[start + len..];
+        } else {
+            retained.push_str(rest[..start].trim_end_matches([' ', '\t']));
+            rest = &rest[start + len..];
+            rest = rest.strip_prefix('\n').unwrap_or(rest);
+            removed += 1;
+        }
+    }
+    retained.push_str(rest);
+    (retained, removed)
+}
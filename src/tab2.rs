@@ -12,10 +12,12 @@ use cosmic::{
         futures::SinkExt,
         keyboard::Modifiers,
         stream,
+        time,
         //TODO: export in cosmic::widget
         widget::{
             horizontal_rule, rule,
             scrollable::{self, AbsoluteOffset, Viewport},
+            stack,
         },
         Alignment,
         Border,
@@ -45,34 +47,41 @@ use serde::{Deserialize, Serialize};
 use std::{
     cell::Cell,
     cmp::Ordering,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     error::Error,
     fmt::{self, Display},
     fs::{self, File, Metadata},
-    io::{BufRead, BufReader},
+    io::{self, BufRead, BufReader},
+    mem,
     os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
     sync::{atomic, Arc, Mutex, RwLock},
     time::{Duration, Instant, SystemTime},
 };
 use tokio::sync::mpsc;
-use walkdir::WalkDir;
 
+#[cfg(unix)]
+use crate::xattrs;
 use crate::{
     app::{Action, PreviewItem2, PreviewKind},
+    checksum::{self, ChecksumKind, ChecksumState, ItemChecksum},
     clipboard::{ClipboardCopy, ClipboardKind, ClipboardPaste},
-    config::{DesktopConfig, IconSizes, TabConfig2, ICON_SCALE_MAX, ICON_SIZE_GRID},
+    config::{
+        DesktopConfig, DesktopGridFlow, DesktopGridOrigin, FolderView2, IconSizes,
+        ParsedCustomAction, TabConfig2, ICON_SCALE_MAX, ICON_SIZE_GRID,
+    },
     dialog::DialogKind,
-    fl,
+    dir_size, fl,
+    item_cache::{self, CachedThumbnail, ItemDetails},
     localize::{LANGUAGE_CHRONO, LANGUAGE_SORTER},
     menu, mime_app,
     mime_icon::{mime_for_path, mime_icon},
     mounter::MOUNTERS,
     mouse_area,
-    operation::Controller,
+    operation::{Controller, ItemProgress, Operation},
+    recents,
     thumbnailer::thumbnailer,
 };
-use unix_permissions_ext::UNIXPermissionsExt;
 use uzers::{get_group_by_gid, get_user_by_uid};
 
 pub const DOUBLE_CLICK_DURATION: Duration = Duration::from_millis(500);
@@ -80,8 +89,33 @@ pub const HOVER_DURATION: Duration = Duration::from_millis(1600);
 //TODO: best limit for search items
 const MAX_SEARCH_LATENCY: Duration = Duration::from_millis(20);
 const MAX_SEARCH_RESULTS: usize = 200;
+// The flattened view of a folder keeps the most recently modified files beyond this
+const MAX_FLATTEN_RESULTS: usize = 10000;
+// Items of a folder are handed to the panel in batches of this size while scanning
+const SCAN_BATCH_SIZE: usize = 1000;
 //TODO: configurable thumbnail size?
 const THUMBNAIL_SIZE: u32 = (ICON_SIZE_GRID as u32) * (ICON_SCALE_MAX as u32);
+// Zoom levels of the gallery image, relative to fitting it into the window
+const GALLERY_ZOOM_LEVELS: &[f32] = &[0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 4.0, 6.0, 8.0];
+// Larger images are not preloaded for the gallery
+const GALLERY_PRELOAD_MAX: u64 = 64 * 1000 * 1000;
+// Mime types of archives, whose names are colored when coloring by type
+const ARCHIVE_MIME_TYPES: &[&str] = &[
+    "application/gzip",
+    "application/vnd.rar",
+    "application/x-7z-compressed",
+    "application/x-bzip",
+    "application/x-bzip2",
+    "application/x-bzip-compressed-tar",
+    "application/x-compressed-tar",
+    "application/x-rar-compressed",
+    "application/x-tar",
+    "application/x-xz",
+    "application/x-xz-compressed-tar",
+    "application/x-zstd-compressed-tar",
+    "application/zip",
+    "application/zstd",
+];
 
 //TODO: adjust for locales?
 const DATE_TIME_FORMAT: &str = "%b %-d, %-Y, %-I:%M %p";
@@ -324,12 +358,24 @@ fn format_size(size: u64) -> String {
         format!("{} B", size)
     }
 }
+#[derive(Clone, Copy)]
 enum PermissionOwner {
     Owner,
     Group,
     Other,
 }
 
+impl PermissionOwner {
+    /// Offset of the read, write and execute bits of this owner in a mode
+    fn shift(self) -> u32 {
+        match self {
+            Self::Owner => 6,
+            Self::Group => 3,
+            Self::Other => 0,
+        }
+    }
+}
+
 fn format_permissions_owner(metadata: &Metadata, owner: PermissionOwner) -> String {
     match owner {
         PermissionOwner::Owner => get_user_by_uid(metadata.uid())
@@ -342,30 +388,8 @@ fn format_permissions_owner(metadata: &Metadata, owner: PermissionOwner) -> Stri
     }
 }
 
-fn format_permissions(metadata: &Metadata, owner: PermissionOwner) -> String {
-    let mut mode = 0;
-    if match owner {
-        PermissionOwner::Owner => metadata.permissions().readable_by_owner(),
-        PermissionOwner::Group => metadata.permissions().readable_by_group(),
-        PermissionOwner::Other => metadata.permissions().readable_by_other(),
-    } {
-        mode |= 4;
-    }
-    if match owner {
-        PermissionOwner::Owner => metadata.permissions().writable_by_owner(),
-        PermissionOwner::Group => metadata.permissions().writable_by_group(),
-        PermissionOwner::Other => metadata.permissions().writable_by_other(),
-    } {
-        mode |= 2;
-    }
-    if match owner {
-        PermissionOwner::Owner => metadata.permissions().executable_by_owner(),
-        PermissionOwner::Group => metadata.permissions().executable_by_group(),
-        PermissionOwner::Other => metadata.permissions().executable_by_other(),
-    } {
-        mode |= 1;
-    }
-    match mode {
+fn format_permissions(mode: u32, owner: PermissionOwner) -> String {
+    match (mode >> owner.shift()) & 0o7 {
         0 => fl!("none"),
         1 => fl!("execute-only"),
         2 => fl!("write-only"),
@@ -378,6 +402,83 @@ fn format_permissions(metadata: &Metadata, owner: PermissionOwner) -> String {
     }
 }
 
+/// Extended attributes and SELinux context of a path for the preview, read when it is shown
+#[cfg(unix)]
+fn xattrs_view(path: &Path) -> Option<Element<'static, Message>> {
+    let cosmic_theme::Spacing { space_xxxs, .. } = theme::active().cosmic().spacing;
+
+    let mut section = widget::settings::section().title(fl!("extended-attributes"));
+    match xattrs::read(path) {
+        Ok(xattrs) => {
+            if xattrs.attrs.is_empty() && xattrs.selinux_opt.is_none() {
+                return None;
+            }
+            if let Some(context) = xattrs.selinux_opt {
+                section = section.add(
+                    widget::settings::item::builder(fl!("selinux-context"))
+                        .control(widget::text::body(context).font(font::mono())),
+                );
+            }
+            for (name, value) in xattrs.attrs {
+                section = section.add(
+                    widget::column()
+                        .spacing(space_xxxs)
+                        .push(widget::text::body(name))
+                        .push(widget::text::caption(value).font(font::mono())),
+                );
+            }
+        }
+        Err(err) => {
+            section = section.add(widget::text::body(err));
+        }
+    }
+    Some(section.into())
+}
+
+/// Permission changes being edited in the preview of an item
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PermissionsEdit {
+    pub mode: u32,
+    pub mode_text: String,
+    pub recursive: bool,
+}
+
+impl PermissionsEdit {
+    pub fn new(mode: u32) -> Self {
+        let mode = mode & 0o7777;
+        Self {
+            mode,
+            mode_text: format!("{:03o}", mode),
+            recursive: false,
+        }
+    }
+
+    fn with_bit(&self, bit: u32, set: bool) -> Self {
+        let mode = if set {
+            self.mode | bit
+        } else {
+            self.mode & !bit
+        };
+        Self {
+            recursive: self.recursive,
+            ..Self::new(mode)
+        }
+    }
+
+    /// Update the octal text, the mode only changes while the text is valid
+    fn with_text(&self, mode_text: String) -> Self {
+        let mode = match u32::from_str_radix(&mode_text, 8) {
+            Ok(mode) if mode <= 0o7777 => mode,
+            _ => self.mode,
+        };
+        Self {
+            mode,
+            mode_text,
+            recursive: self.recursive,
+        }
+    }
+}
+
 struct FormatTime(SystemTime);
 
 impl FormatTime {
@@ -451,6 +552,41 @@ pub fn parse_desktop_file(path: &Path) -> (Option<String>, Option<String>) {
     )
 }
 
+/// Details of an item that are slow to read, these are shared through the [`item_cache`]
+fn read_details(path: &Path, metadata: &Metadata) -> ItemDetails {
+    if metadata.is_dir() {
+        //TODO: calculate children in the background (and make it cancellable?)
+        let children = match fs::read_dir(path) {
+            Ok(entries) => entries.count(),
+            Err(err) => {
+                log::warn!("failed to read directory {:?}: {}", path, err);
+                0
+            }
+        };
+        ItemDetails {
+            //TODO: make this a static
+            mime: "inode/directory".parse().unwrap(),
+            desktop: (None, None),
+            children,
+            thumbnail_opt: None,
+        }
+    } else {
+        let mime = mime_for_path(path);
+        //TODO: clean this up, implement for trash
+        let desktop = if mime == "application/x-desktop" {
+            parse_desktop_file(path)
+        } else {
+            (None, None)
+        };
+        ItemDetails {
+            mime,
+            desktop,
+            children: 0,
+            thumbnail_opt: None,
+        }
+    }
+}
+
 pub fn item_from_entry(
     path: PathBuf,
     name: String,
@@ -461,27 +597,35 @@ pub fn item_from_entry(
 
     let hidden = name.starts_with(".") || hidden_attribute(&metadata);
 
+    let symlink_target_opt = fs::read_link(&path).ok();
+
+    // Details are shared with other tabs showing the same items, until they are modified
+    let modified_opt = metadata.modified().ok();
+    let details = match modified_opt.and_then(|modified| item_cache::get(&path, modified)) {
+        Some(details) => details,
+        None => {
+            let details = read_details(&path, &metadata);
+            if let Some(modified) = modified_opt {
+                item_cache::insert(&path, modified, details.clone());
+            }
+            details
+        }
+    };
+
     let (mime, icon_handle_grid, icon_handle_list, icon_handle_list_condensed) =
         if metadata.is_dir() {
             (
-                //TODO: make this a static
-                "inode/directory".parse().unwrap(),
+                details.mime,
                 folder_icon(&path, sizes.grid()),
                 folder_icon(&path, sizes.list()),
                 folder_icon(&path, sizes.list_condensed()),
             )
         } else {
-            let mime = mime_for_path(&path);
-            //TODO: clean this up, implement for trash
-            let icon_name_opt = if mime == "application/x-desktop" {
-                let (desktop_name_opt, icon_name_opt) = parse_desktop_file(&path);
-                if let Some(desktop_name) = desktop_name_opt {
-                    display_name = Item::display_name(&desktop_name);
-                }
-                icon_name_opt
-            } else {
-                None
-            };
+            let mime = details.mime;
+            let (desktop_name_opt, icon_name_opt) = details.desktop;
+            if let Some(desktop_name) = desktop_name_opt {
+                display_name = Item::display_name(&desktop_name);
+            }
             if let Some(icon_name) = icon_name_opt {
                 (
                     mime.clone(),
@@ -505,30 +649,23 @@ pub fn item_from_entry(
             }
         };
 
-    let children = if metadata.is_dir() {
-        //TODO: calculate children in the background (and make it cancellable?)
-        match fs::read_dir(&path) {
-            Ok(entries) => entries.count(),
-            Err(err) => {
-                log::warn!("failed to read directory {:?}: {}", path, err);
-                0
-            }
-        }
-    } else {
-        0
-    };
+    let children = details.children;
 
     let dir_size = if metadata.is_dir() {
-        DirSize::Calculating(Controller::default())
+        match dir_size::cached(&path, &metadata) {
+            Some(size) => DirSize::Directory(size),
+            None => DirSize::Calculating(Controller::default()),
+        }
     } else {
         DirSize::NotDirectory
     };
 
-    Item {
+    let mut item = Item {
         name,
         display_name,
         metadata: ItemMetadata::Path { metadata, children },
         hidden,
+        symlink_target_opt,
         location_opt: Some(Location::Path(path)),
         mime,
         icon_handle_grid,
@@ -541,8 +678,15 @@ pub fn item_from_entry(
         selected: false,
         highlighted: false,
         overlaps_drag_rect: false,
+        permissions_edit_opt: None,
+        symlink_target_edit_opt: None,
+        checksum_opt: None,
         dir_size,
+    };
+    if let Some(thumbnail) = details.thumbnail_opt {
+        item.set_thumbnail(thumbnail.into());
     }
+    item
 }
 
 pub fn item_from_path<P: Into<PathBuf>>(path: P, sizes: IconSizes) -> Result<Item, String> {
@@ -564,9 +708,54 @@ pub fn item_from_path<P: Into<PathBuf>>(path: P, sizes: IconSizes) -> Result<Ite
     Ok(item_from_entry(path, name, metadata, sizes))
 }
 
+/// Sort scanned items with folders first, then by name
+fn sort_scanned(items: &mut [Item]) {
+    items.sort_by(|a, b| match (a.metadata.is_dir(), b.metadata.is_dir()) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => LANGUAGE_SORTER.compare(&a.display_name, &b.display_name),
+    });
+}
+
 pub fn scan_path(tab_path: &PathBuf, sizes: IconSizes) -> Vec<Item> {
+    scan_path_checked(tab_path, sizes).unwrap_or_default()
+}
+
+/// Scan a folder, failing with a message that can be shown if it could not be read
+pub fn scan_path_checked(tab_path: &PathBuf, sizes: IconSizes) -> Result<Vec<Item>, String> {
     let mut items = Vec::new();
-    let mut hidden_files = Vec::new();
+    scan_path_batched(tab_path, sizes, |batch| {
+        items.extend(batch);
+        true
+    })?;
+    sort_scanned(&mut items);
+    Ok(items)
+}
+
+/// Message shown in place of the items of a folder that could not be read
+fn scan_error(err: &io::Error) -> String {
+    match err.kind() {
+        io::ErrorKind::PermissionDenied => fl!("scan-permission-denied"),
+        io::ErrorKind::NotFound => fl!("scan-not-found"),
+        _ => err.to_string(),
+    }
+}
+
+/// Scan a folder, handing its items to `batch` in sorted chunks of up to `SCAN_BATCH_SIZE` as
+/// they are read. Stops early if `batch` returns false.
+pub fn scan_path_batched<F: FnMut(Vec<Item>) -> bool>(
+    tab_path: &PathBuf,
+    sizes: IconSizes,
+    mut batch: F,
+) -> Result<(), String> {
+    // Items are handed out before the whole folder is read, so the hidden list is read first
+    let hidden_path = tab_path.join(".hidden");
+    let hidden_files = if hidden_path.is_file() {
+        parse_hidden_file(&hidden_path)
+    } else {
+        Vec::new()
+    };
+    let mut items = Vec::with_capacity(SCAN_BATCH_SIZE);
     match fs::read_dir(tab_path) {
         Ok(entries) => {
             for entry_res in entries {
@@ -592,10 +781,6 @@ pub fn scan_path(tab_path: &PathBuf, sizes: IconSizes) -> Vec<Item> {
                     }
                 };
 
-                if name == ".hidden" && path.is_file() {
-                    hidden_files = parse_hidden_file(&path);
-                }
-
                 let metadata = match fs::metadata(&path) {
                     Ok(ok) => ok,
                     Err(err) => {
@@ -604,30 +789,38 @@ pub fn scan_path(tab_path: &PathBuf, sizes: IconSizes) -> Vec<Item> {
                     }
                 };
 
-                items.push(item_from_entry(path, name, metadata, sizes));
+                let mut item = item_from_entry(path, name, metadata, sizes);
+                if hidden_files.contains(&item.name) {
+                    item.hidden = true;
+                }
+                items.push(item);
+                if items.len() >= SCAN_BATCH_SIZE {
+                    sort_scanned(&mut items);
+                    let full = mem::replace(&mut items, Vec::with_capacity(SCAN_BATCH_SIZE));
+                    if !batch(full) {
+                        return Ok(());
+                    }
+                }
             }
         }
         Err(err) => {
             log::warn!("failed to read directory {:?}: {}", tab_path, err);
+            return Err(scan_error(&err));
         }
     }
-    items.sort_by(|a, b| match (a.metadata.is_dir(), b.metadata.is_dir()) {
-        (true, false) => Ordering::Less,
-        (false, true) => Ordering::Greater,
-        _ => LANGUAGE_SORTER.compare(&a.display_name, &b.display_name),
-    });
-    items.iter_mut().for_each(|item| {
-        if hidden_files.iter().any(|hidden| &item.name == hidden) {
-            item.hidden = true;
-        }
-    });
-    items
+    if !items.is_empty() {
+        sort_scanned(&mut items);
+        batch(items);
+    }
+    Ok(())
 }
 
 pub fn scan_search<F: Fn(&Path, &str, Metadata) -> bool + Sync>(
     tab_path: &PathBuf,
     term: &str,
     show_hidden: bool,
+    follow_symlinks: bool,
+    respect_gitignore: bool,
     callback: F,
 ) {
     if term.is_empty() {
@@ -646,9 +839,56 @@ pub fn scan_search<F: Fn(&Path, &str, Metadata) -> bool + Sync>(
         }
     };
 
+    scan_tree(
+        tab_path,
+        show_hidden,
+        follow_symlinks,
+        respect_gitignore,
+        |file_name, _is_dir| regex.is_match(file_name),
+        callback,
+    );
+}
+
+/// Scan every file below `tab_path`, for the flattened view of a folder
+pub fn scan_flatten<F: Fn(&Path, &str, Metadata) -> bool + Sync>(
+    tab_path: &PathBuf,
+    show_hidden: bool,
+    follow_symlinks: bool,
+    respect_gitignore: bool,
+    callback: F,
+) {
+    scan_tree(
+        tab_path,
+        show_hidden,
+        follow_symlinks,
+        respect_gitignore,
+        |_file_name, is_dir| !is_dir,
+        callback,
+    );
+}
+
+// Walk the tree below `tab_path`, passing the entries that match by name and kind to `callback`
+// until it returns false
+fn scan_tree<M: Fn(&str, bool) -> bool + Sync, F: Fn(&Path, &str, Metadata) -> bool + Sync>(
+    tab_path: &PathBuf,
+    show_hidden: bool,
+    follow_symlinks: bool,
+    respect_gitignore: bool,
+    is_match: M,
+    callback: F,
+) {
+    // Names listed in the .hidden file of each searched folder
+    let hidden_files = Mutex::new(HashMap::new());
+
     ignore::WalkBuilder::new(tab_path)
         .standard_filters(false)
         .hidden(!show_hidden)
+        // Skip what the nearest .gitignore matches, also outside of git repositories
+        .git_ignore(respect_gitignore)
+        .parents(respect_gitignore)
+        .require_git(false)
+        // Loops of followed symlinks are reported as errors and skipped
+        .follow_links(follow_symlinks)
         //TODO: only use this on supported targets
         .same_file_system(true)
         .build_parallel()
@@ -664,7 +904,19 @@ pub fn scan_search<F: Fn(&Path, &str, Metadata) -> bool + Sync>(
                     return ignore::WalkState::Skip;
                 };
 
-                if regex.is_match(file_name) {
+                if !show_hidden && entry.depth() > 0 {
+                    if let Some(dir) = entry.path().parent() {
+                        if hidden_file_listed(&hidden_files, dir, file_name) {
+                            // Also skips the contents of hidden folders
+                            return ignore::WalkState::Skip;
+                        }
+                    }
+                }
+
+                let is_dir = entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_dir());
+                if is_match(file_name, is_dir) {
                     let path = entry.path();
 
                     let metadata = match entry.metadata() {
@@ -754,6 +1006,7 @@ pub fn scan_trash(sizes: IconSizes) -> Vec<Item> {
                     display_name,
                     metadata: ItemMetadata::Trash { metadata, entry },
                     hidden: false,
+                    symlink_target_opt: None,
                     location_opt: None,
                     mime,
                     icon_handle_grid,
@@ -766,6 +1019,9 @@ pub fn scan_trash(sizes: IconSizes) -> Vec<Item> {
                     selected: false,
                     highlighted: false,
                     overlaps_drag_rect: false,
+                    permissions_edit_opt: None,
+                    symlink_target_edit_opt: None,
+                    checksum_opt: None,
                     dir_size: DirSize::NotDirectory,
                 });
             }
@@ -787,8 +1043,11 @@ fn uri_to_path(uri: String) -> Option<PathBuf> {
     uri.strip_prefix("file://").map(PathBuf::from)
 }
 
-pub fn scan_recents(sizes: IconSizes) -> Vec<Item> {
+/// Recently used files, leaving out those in excluded folders. Entries of files that no longer
+/// exist are removed from the history.
+pub fn scan_recents(sizes: IconSizes, excluded: &[PathBuf]) -> Vec<Item> {
     let mut recents = Vec::new();
+    let mut missing = Vec::new();
 
     match recently_used_xbel::parse_file() {
         Ok(recent_files) => {
@@ -806,6 +1065,9 @@ pub fn scan_recents(sizes: IconSizes) -> Vec<Item> {
                     Ok(last_visit) => last_visit,
                     Err(_) => continue,
                 };
+                if recents::is_excluded(&path, excluded) {
+                    continue;
+                }
                 let path_exist = path.exists();
 
                 if path_exist {
@@ -837,7 +1099,7 @@ pub fn scan_recents(sizes: IconSizes) -> Vec<Item> {
                         ))
                     }
                 } else {
-                    log::warn!("recent file path not exist: {:?}", path);
+                    missing.push(path);
                 }
             }
         }
@@ -846,6 +1108,12 @@ pub fn scan_recents(sizes: IconSizes) -> Vec<Item> {
         }
     }
 
+    if !missing.is_empty() {
+        if let Err(err) = recents::retain(|path| !missing.iter().any(|missing| missing == path)) {
+            log::warn!("failed to remove missing recent files: {}", err);
+        }
+    }
+
     recents.sort_by(|a, b| b.1.cmp(&a.1));
 
     recents.into_iter().take(50).map(|(item, _)| item).collect()
@@ -920,6 +1188,7 @@ pub fn scan_desktop(
             display_name,
             metadata,
             hidden: false,
+            symlink_target_opt: None,
             location_opt: Some(Location::Trash),
             mime,
             icon_handle_grid,
@@ -932,6 +1201,9 @@ pub fn scan_desktop(
             selected: false,
             highlighted: false,
             overlaps_drag_rect: false,
+            permissions_edit_opt: None,
+            symlink_target_edit_opt: None,
+            checksum_opt: None,
             dir_size: DirSize::NotDirectory,
         })
     }
@@ -989,6 +1261,35 @@ impl From<Location> for EditLocation {
     }
 }
 
+/// Name of an item being edited in place of its name
+#[derive(Clone, Debug)]
+pub struct RenameInPlace {
+    pub from: PathBuf,
+    pub name: String,
+}
+
+impl RenameInPlace {
+    /// Path to rename to, if the new name is valid and not taken by another item
+    pub fn target(&self) -> Option<PathBuf> {
+        let name = self.name.as_str();
+        if name.is_empty() || name == "." || name == ".." || name.contains('/') {
+            return None;
+        }
+        let to = self.from.with_file_name(name);
+        (to == self.from || !to.exists()).then_some(to)
+    }
+}
+
+/// Length in characters of the part of a name that is selected when renaming, which leaves out
+/// the extension of files
+fn rename_stem_len(name: &str, dir: bool) -> usize {
+    let stem = match name.rfind('.') {
+        Some(i) if i > 0 && !dir => &name[..i],
+        _ => name,
+    };
+    stem.chars().count()
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Location {
     Desktop(PathBuf, String, DesktopConfig),
@@ -996,6 +1297,8 @@ pub enum Location {
     Path(PathBuf),
     Recents,
     Search(PathBuf, String, bool, Instant),
+    /// Every file below a folder, listed together
+    Flatten(PathBuf, bool, Instant),
     Trash,
 }
 
@@ -1009,6 +1312,7 @@ impl std::fmt::Display for Location {
             Self::Path(path) => write!(f, "{}", path.display()),
             Self::Recents => write!(f, "recents"),
             Self::Search(path, term, ..) => write!(f, "search {} for {}", path.display(), term),
+            Self::Flatten(path, ..) => write!(f, "flatten {}", path.display()),
             Self::Trash => write!(f, "trash"),
         }
     }
@@ -1020,6 +1324,7 @@ impl Location {
             Self::Desktop(path, ..) => Some(path),
             Self::Path(path) => Some(path),
             Self::Search(path, ..) => Some(path),
+            Self::Flatten(path, ..) => Some(path),
             _ => None,
         }
     }
@@ -1033,25 +1338,37 @@ impl Location {
             Self::Search(_, term, show_hidden, _) => {
                 Self::Search(path, term.clone(), *show_hidden, Instant::now())
             }
+            Self::Flatten(_, show_hidden, _) => Self::Flatten(path, *show_hidden, Instant::now()),
             other => other.clone(),
         }
     }
 
     pub fn scan(&self, sizes: IconSizes) -> (Option<Item>, Vec<Item>) {
-        let items = match self {
+        let (parent_item_opt, items_res) = self.scan_checked(sizes);
+        (parent_item_opt, items_res.unwrap_or_default())
+    }
+
+    /// Scan the location, failing if a folder could not be read so it is not shown as empty
+    pub fn scan_checked(&self, sizes: IconSizes) -> (Option<Item>, Result<Vec<Item>, String>) {
+        let items_res = match self {
             Self::Desktop(path, display, desktop_config) => {
-                scan_desktop(path, display, *desktop_config, sizes)
+                Ok(scan_desktop(path, display, *desktop_config, sizes))
             }
-            Self::Path(path) => scan_path(path, sizes),
-            Self::Search(..) => {
+            Self::Path(path) => scan_path_checked(path, sizes),
+            Self::Search(..) | Self::Flatten(..) => {
                 // Search is done incrementally
-                Vec::new()
+                Ok(Vec::new())
             }
-            Self::Trash => scan_trash(sizes),
-            Self::Recents => scan_recents(sizes),
-            Self::Network(_uri, _) => Vec::new(),
+            Self::Trash => Ok(scan_trash(sizes)),
+            Self::Recents => Ok(scan_recents(sizes, &[])),
+            Self::Network(_uri, _) => Ok(Vec::new()),
         };
-        let parent_item_opt = match self.path_opt() {
+        (self.parent_item(sizes), items_res)
+    }
+
+    /// Item for the location itself, shown when nothing is selected
+    pub fn parent_item(&self, sizes: IconSizes) -> Option<Item> {
+        match self.path_opt() {
             Some(path) => match item_from_path(path, sizes) {
                 Ok(item) => Some(item),
                 Err(err) => {
@@ -1061,8 +1378,7 @@ impl Location {
             },
             //TODO: support other locations?
             None => None,
-        };
-        (parent_item_opt, items)
+        }
     }
 }
 
@@ -1090,14 +1406,20 @@ pub enum Command {
     EmptyTrash,
     #[cfg(feature = "desktop")]
     ExecEntryAction(cosmic::desktop::DesktopEntryData, usize),
+    FolderView(String, FolderView2),
     Iced(TaskWrapper),
     MoveToTrash(Vec<PathBuf>),
     OpenFile(PathBuf),
+    OpenFiles(Vec<PathBuf>),
     OpenInNewTab(PathBuf),
     OpenInNewWindow(PathBuf),
     OpenTrash,
     Preview(PreviewKind),
+    Rename(PathBuf, PathBuf),
+    Rescan,
     SetOpenWith(Mime, String),
+    SetPermissions(PathBuf, u32, bool),
+    SetSymlinkTarget(PathBuf, PathBuf),
     WindowDrag,
     WindowToggleMaximize,
 }
@@ -1105,6 +1427,13 @@ pub enum Command {
 #[derive(Clone, Debug)]
 pub enum Message {
     AddNetworkDrive,
+    CalculateFolderSizes,
+    CancelFolderSizes,
+    Checksum(PathBuf, Option<ChecksumKind>),
+    ChecksumCopy(String),
+    ChecksumProgress(PathBuf, f32),
+    ChecksumResult(PathBuf, ChecksumKind, Result<String, String>),
+    ChecksumVerify(PathBuf, String),
     Click(Option<usize>),
     DoubleClick(Option<usize>),
     ClickRelease(Option<usize>),
@@ -1121,13 +1450,19 @@ pub enum Message {
     EditLocationEnable,
     EditLocationSubmit,
     OpenInNewTab(PathBuf),
+    OpenLinkTarget,
     EmptyTrash,
     #[cfg(feature = "desktop")]
     ExecEntryAction(Option<PathBuf>, usize),
     Gallery(bool),
+    GalleryImage(PathBuf, Option<widget::image::Handle>),
     GalleryPrevious,
     GalleryNext,
+    GallerySlideshow(bool),
     GalleryToggle,
+    GalleryZoomDefault,
+    GalleryZoomIn,
+    GalleryZoomOut,
     GoNext,
     GoPrevious,
     ItemDown,
@@ -1137,6 +1472,14 @@ pub enum Message {
     Location(Location),
     LocationUp,
     Open(Option<PathBuf>),
+    PageDown,
+    PageUp,
+    PermissionsApply(PathBuf),
+    PermissionsEdit(PathBuf, Option<PermissionsEdit>),
+    RenameInput(String),
+    RenameStart,
+    RenameSubmit,
+    Rescan,
     RightClick(Option<usize>),
     MiddleClick(usize),
     Scroll(Viewport),
@@ -1148,8 +1491,11 @@ pub enum Message {
     SelectLast,
     SetOpenWith(Mime, String),
     SetSort(HeadingOptions, bool),
+    SymlinkTargetApply(PathBuf),
+    SymlinkTargetEdit(PathBuf, Option<String>),
     TabComplete(PathBuf, Vec<(String, PathBuf)>),
     Thumbnail(PathBuf, ItemThumbnail),
+    ToggleFlatten,
     ToggleShowHidden,
     View(View),
     ToggleSort(HeadingOptions),
@@ -1157,6 +1503,7 @@ pub enum Message {
     DndHover(Location),
     DndEnter(Location),
     DndLeave(Location),
+    DndAction(DndAction),
     WindowDrag,
     WindowToggleMaximize,
     ZoomIn,
@@ -1164,6 +1511,7 @@ pub enum Message {
     HighlightDeactivate(usize),
     HighlightActivate(usize),
     DirectorySize(PathBuf, DirSize),
+    DirectorySizeProgress(PathBuf, u64),
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -1185,6 +1533,7 @@ impl MenuAction for LocationMenuAction {
 #[derive(Clone, Debug)]
 pub enum DirSize {
     Calculating(Controller),
+    Partial(u64, Controller),
     Directory(u64),
     NotDirectory,
     Error(String),
@@ -1229,6 +1578,31 @@ impl ItemMetadata {
     }
 }
 
+/// Kind of item used to color its name
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ItemCategory {
+    Directory,
+    Executable,
+    Symlink,
+    Archive,
+    Image,
+}
+
+impl ItemCategory {
+    /// Accent color of the active theme for names of this category
+    pub fn color(self) -> Color {
+        let theme = theme::active();
+        let palette = &theme.cosmic().palette;
+        Color::from(match self {
+            Self::Directory => palette.accent_blue,
+            Self::Executable => palette.accent_green,
+            Self::Symlink => palette.accent_indigo,
+            Self::Archive => palette.accent_red,
+            Self::Image => palette.accent_purple,
+        })
+    }
+}
+
 #[derive(Debug)]
 pub enum ItemThumbnail {
     NotImage,
@@ -1251,8 +1625,51 @@ impl Clone for ItemThumbnail {
     }
 }
 
+impl From<CachedThumbnail> for ItemThumbnail {
+    fn from(thumbnail: CachedThumbnail) -> Self {
+        match thumbnail {
+            CachedThumbnail::NotImage => Self::NotImage,
+            CachedThumbnail::Image(handle, size_opt) => Self::Image(handle, size_opt),
+            CachedThumbnail::Svg(handle) => Self::Svg(handle),
+        }
+    }
+}
+
 impl ItemThumbnail {
+    /// Create a thumbnail, or reuse the one cached for the current modification time
     pub fn new(path: &Path, metadata: fs::Metadata, mime: mime::Mime, thumbnail_size: u32) -> Self {
+        let modified_opt = metadata.modified().ok();
+        if let Some(thumbnail) = modified_opt
+            .and_then(|modified| item_cache::get(path, modified))
+            .and_then(|details| details.thumbnail_opt)
+        {
+            return thumbnail.into();
+        }
+        let thumbnail = Self::generate(path, metadata, mime, thumbnail_size);
+        if let (Some(modified), Some(cached)) = (modified_opt, thumbnail.cached()) {
+            item_cache::insert_thumbnail(path, modified, cached);
+        }
+        thumbnail
+    }
+
+    /// Form shared through the [`item_cache`], text thumbnails are not cached
+    fn cached(&self) -> Option<CachedThumbnail> {
+        match self {
+            Self::NotImage => Some(CachedThumbnail::NotImage),
+            Self::Image(handle, size_opt) => {
+                Some(CachedThumbnail::Image(handle.clone(), *size_opt))
+            }
+            Self::Svg(handle) => Some(CachedThumbnail::Svg(handle.clone())),
+            Self::Text(_) => None,
+        }
+    }
+
+    fn generate(
+        path: &Path,
+        metadata: fs::Metadata,
+        mime: mime::Mime,
+        thumbnail_size: u32,
+    ) -> Self {
         let size = metadata.len();
         let check_size = |thumbnailer: &str, max_size| {
             if size <= max_size {
@@ -1388,6 +1805,8 @@ pub struct Item {
     pub display_name: String,
     pub metadata: ItemMetadata,
     pub hidden: bool,
+    /// Target of the link if the item is a symlink
+    pub symlink_target_opt: Option<PathBuf>,
     pub location_opt: Option<Location>,
     pub mime: Mime,
     pub icon_handle_grid: widget::icon::Handle,
@@ -1401,6 +1820,10 @@ pub struct Item {
     pub highlighted: bool,
     pub overlaps_drag_rect: bool,
     pub dir_size: DirSize,
+    pub permissions_edit_opt: Option<PermissionsEdit>,
+    /// Target typed in the details of a symlink, not applied yet
+    pub symlink_target_edit_opt: Option<String>,
+    pub checksum_opt: Option<ItemChecksum>,
 }
 
 impl Item {
@@ -1413,10 +1836,141 @@ impl Item {
         self.location_opt.as_ref()?.path_opt()
     }
 
+    /// Use a thumbnail, images also replace the icons
+    fn set_thumbnail(&mut self, thumbnail: ItemThumbnail) {
+        let handle_opt = match &thumbnail {
+            ItemThumbnail::NotImage => None,
+            ItemThumbnail::Image(handle, _) => Some(widget::icon::Handle {
+                symbolic: false,
+                data: widget::icon::Data::Image(handle.clone()),
+            }),
+            ItemThumbnail::Svg(handle) => Some(widget::icon::Handle {
+                symbolic: false,
+                data: widget::icon::Data::Svg(handle.clone()),
+            }),
+            //TODO: text thumbnails?
+            ItemThumbnail::Text(_text) => None,
+        };
+        if let Some(handle) = handle_opt {
+            self.icon_handle_grid = handle.clone();
+            self.icon_handle_list = handle.clone();
+            self.icon_handle_list_condensed = handle;
+        }
+        self.thumbnail_opt = Some(thumbnail);
+    }
+
+    /// Icon of the item, symlinks have an emblem in the bottom corner and items of running
+    /// operations one in the top corner
+    fn icon_view(
+        &self,
+        handle: widget::icon::Handle,
+        size: u16,
+        progress_opt: Option<ItemProgress>,
+    ) -> Element<'static, Message> {
+        let icon = widget::icon::icon(handle)
+            .content_fit(ContentFit::Contain)
+            .size(size);
+        let emblem = |name: &'static str, align_y| -> Element<'static, Message> {
+            widget::container(widget::icon::from_name(name).size((size / 2).max(8)).icon())
+                .width(Length::Fixed(size.into()))
+                .height(Length::Fixed(size.into()))
+                .align_x(Horizontal::Right)
+                .align_y(align_y)
+                .into()
+        };
+        let mut layers = Vec::new();
+        if self.symlink_target_opt.is_some() {
+            layers.push(emblem("emblem-symbolic-link", Vertical::Bottom));
+        }
+        if let Some(progress) = progress_opt {
+            layers.push(emblem(
+                match progress {
+                    ItemProgress::Pending => "content-loading-symbolic",
+                    ItemProgress::Current => "emblem-synchronizing-symbolic",
+                    ItemProgress::Done => "emblem-ok-symbolic",
+                },
+                Vertical::Top,
+            ));
+        }
+        if layers.is_empty() {
+            return icon.into();
+        }
+        layers.insert(0, icon.into());
+        stack(layers).into()
+    }
+
+    fn is_executable(&self) -> bool {
+        match &self.metadata {
+            ItemMetadata::Path { metadata, .. } => {
+                metadata.is_file() && metadata.mode() & 0o111 != 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Category used to color the name, links are colored as links whatever they point to
+    pub fn category(&self) -> Option<ItemCategory> {
+        if self.symlink_target_opt.is_some() {
+            Some(ItemCategory::Symlink)
+        } else if self.metadata.is_dir() {
+            Some(ItemCategory::Directory)
+        } else if ARCHIVE_MIME_TYPES.contains(&self.mime.essence_str()) {
+            Some(ItemCategory::Archive)
+        } else if self.mime.type_() == mime::IMAGE {
+            Some(ItemCategory::Image)
+        } else if self.is_executable() {
+            Some(ItemCategory::Executable)
+        } else {
+            None
+        }
+    }
+
+    /// Text class of the name, colored by category unless selected
+    fn name_class(&self, color_by_type: bool) -> theme::Text {
+        match self.category() {
+            Some(category) if color_by_type && !self.selected => {
+                theme::Text::Color(category.color())
+            }
+            _ => theme::Text::Default,
+        }
+    }
+
     pub fn can_gallery(&self) -> bool {
         self.mime.type_() == mime::IMAGE || self.mime.type_() == mime::TEXT
     }
 
+    fn set_dir_size_progress(&mut self, size: u64) {
+        // Move the controller so that dropping the item still cancels the calculation
+        self.dir_size = match std::mem::replace(&mut self.dir_size, DirSize::NotDirectory) {
+            DirSize::Calculating(controller) | DirSize::Partial(_, controller) => {
+                DirSize::Partial(size, controller)
+            }
+            other => other,
+        };
+    }
+
+    /// Size in bytes, directories only have a size once it has been calculated
+    pub fn size_opt(&self) -> Option<u64> {
+        match &self.metadata {
+            ItemMetadata::Path { metadata, .. } => {
+                if metadata.is_dir() {
+                    match self.dir_size {
+                        DirSize::Directory(size) => Some(size),
+                        _ => None,
+                    }
+                } else {
+                    Some(metadata.len())
+                }
+            }
+            ItemMetadata::Trash { metadata, .. } => match metadata.size {
+                trash::TrashItemSize::Entries(_) => None,
+                trash::TrashItemSize::Bytes(bytes) => Some(bytes),
+            },
+            ItemMetadata::SimpleDir { .. } => None,
+            ItemMetadata::SimpleFile { size } => Some(*size),
+        }
+    }
+
     fn preview(&self, sizes: IconSizes) -> Element<'_, Message> {
         let spacing = cosmic::theme::active().cosmic().spacing;
         // This loads the image only if thumbnailing worked
@@ -1481,6 +2035,7 @@ impl Item {
     ) -> Element<'a, Message> {
         let cosmic_theme::Spacing {
             space_xxxs,
+            space_xxs,
             space_m,
             ..
         } = theme::active().cosmic().spacing;
@@ -1499,7 +2054,48 @@ impl Item {
             "type",
             mime = self.mime.to_string()
         )));
+        if let Some(target) = &self.symlink_target_opt {
+            details = details.push(widget::text::body(fl!(
+                "link-target",
+                target = target.display().to_string()
+            )));
+            // Following a broken link fails
+            if self.path_opt().is_some_and(|path| !path.exists()) {
+                details = details.push(widget::text::body(fl!("link-broken")).class(
+                    theme::Text::Color(theme::active().cosmic().warning_color().into()),
+                ));
+            }
+        }
         let mut settings = Vec::new();
+        if let (Some(target), Some(path)) = (&self.symlink_target_opt, self.path_opt()) {
+            let target = target.display().to_string();
+            let edit_opt = self.symlink_target_edit_opt.as_ref();
+            let changed = edit_opt.is_some_and(|edit| !edit.is_empty() && *edit != target);
+            settings.push(
+                widget::settings::item::builder(fl!("symlink-target")).control(
+                    widget::text_input("", edit_opt.unwrap_or(&target).as_str())
+                        .on_input(|edit| Message::SymlinkTargetEdit(path.clone(), Some(edit)))
+                        .on_submit_maybe(
+                            changed.then(|| Message::SymlinkTargetApply(path.clone())),
+                        ),
+                ),
+            );
+            if edit_opt.is_some() {
+                settings.push(
+                    widget::settings::item::builder(String::new()).control(
+                        widget::row()
+                            .push(
+                                widget::button::standard(fl!("reset"))
+                                    .on_press(Message::SymlinkTargetEdit(path.clone(), None)),
+                            )
+                            .push(widget::button::suggested(fl!("apply")).on_press_maybe(
+                                changed.then(|| Message::SymlinkTargetApply(path.clone())),
+                            ))
+                            .spacing(space_xxs),
+                    ),
+                );
+            }
+        }
         if let Some(mime_app_cache) = mime_app_cache_opt {
             let mime_apps = mime_app_cache.get(&self.mime);
             if !mime_apps.is_empty() {
@@ -1524,6 +2120,9 @@ impl Item {
                     details = details.push(widget::text::body(fl!("items", items = children)));
                     let size = match &self.dir_size {
                         DirSize::Calculating(_) => fl!("calculating"),
+                        DirSize::Partial(size, _) => {
+                            fl!("calculating-size", size = format_size(*size))
+                        }
                         DirSize::Directory(size) => format_size(*size),
                         DirSize::NotDirectory => String::new(),
                         DirSize::Error(err) => err.clone(),
@@ -1558,34 +2157,115 @@ impl Item {
                 }
 
                 #[cfg(not(target_os = "windows"))]
-                {
-                    settings.push(
-                        widget::settings::item::builder(format_permissions_owner(
-                            metadata,
-                            PermissionOwner::Owner,
-                        ))
-                        .description(fl!("owner"))
-                        .control(widget::text::body(format_permissions(
-                            metadata,
-                            PermissionOwner::Owner,
-                        ))),
-                    );
+                if let Some(path) = self.path_opt() {
+                    let current_mode = metadata.mode() & 0o7777;
+                    let edit = self
+                        .permissions_edit_opt
+                        .clone()
+                        .unwrap_or_else(|| PermissionsEdit::new(current_mode));
+
+                    for owner in [
+                        PermissionOwner::Owner,
+                        PermissionOwner::Group,
+                        PermissionOwner::Other,
+                    ] {
+                        let mut checkboxes = widget::row().spacing(space_xxs);
+                        for (bit, label) in [
+                            (0o4, fl!("permission-read")),
+                            (0o2, fl!("permission-write")),
+                            (0o1, fl!("permission-execute")),
+                        ] {
+                            let bit = bit << owner.shift();
+                            let edit = edit.clone();
+                            checkboxes = checkboxes.push(
+                                widget::checkbox(label, edit.mode & bit != 0).on_toggle(
+                                    move |set| {
+                                        Message::PermissionsEdit(
+                                            path.clone(),
+                                            Some(edit.with_bit(bit, set)),
+                                        )
+                                    },
+                                ),
+                            );
+                        }
+                        let builder = match owner {
+                            PermissionOwner::Owner => widget::settings::item::builder(
+                                format_permissions_owner(metadata, owner),
+                            )
+                            .description(fl!("owner")),
+                            PermissionOwner::Group => widget::settings::item::builder(
+                                format_permissions_owner(metadata, owner),
+                            )
+                            .description(fl!("group")),
+                            PermissionOwner::Other => widget::settings::item::builder(fl!("other")),
+                        };
+                        settings.push(
+                            builder.control(
+                                widget::column()
+                                    .push(widget::text::body(format_permissions(edit.mode, owner)))
+                                    .push(checkboxes)
+                                    .spacing(space_xxxs),
+                            ),
+                        );
+                    }
 
+                    let mut mode_item = widget::settings::item::builder(fl!("permissions-mode"));
+                    // Only the owner and root may change permissions
+                    if uzers::get_current_uid() != metadata.uid() && uzers::get_current_uid() != 0 {
+                        mode_item = mode_item.description(fl!("permissions-not-owner"));
+                    }
                     settings.push(
-                        widget::settings::item::builder(format_permissions_owner(
-                            metadata,
-                            PermissionOwner::Group,
-                        ))
-                        .description(fl!("group"))
-                        .control(widget::text::body(format_permissions(
-                            metadata,
-                            PermissionOwner::Group,
-                        ))),
+                        mode_item.control(
+                            widget::text_input("755", edit.mode_text.clone())
+                                .on_input({
+                                    let edit = edit.clone();
+                                    move |text| {
+                                        Message::PermissionsEdit(
+                                            path.clone(),
+                                            Some(edit.with_text(text)),
+                                        )
+                                    }
+                                })
+                                .width(Length::Fixed(80.0)),
+                        ),
                     );
 
-                    settings.push(widget::settings::item::builder(fl!("other")).control(
-                        widget::text::body(format_permissions(metadata, PermissionOwner::Other)),
-                    ));
+                    if metadata.is_dir() {
+                        settings.push(
+                            widget::settings::item::builder(fl!("permissions-recursive")).toggler(
+                                edit.recursive,
+                                {
+                                    let edit = edit.clone();
+                                    move |recursive| {
+                                        Message::PermissionsEdit(
+                                            path.clone(),
+                                            Some(PermissionsEdit {
+                                                recursive,
+                                                ..edit.clone()
+                                            }),
+                                        )
+                                    }
+                                },
+                            ),
+                        );
+                    }
+
+                    if self.permissions_edit_opt.is_some() {
+                        let changed = edit.mode != current_mode || edit.recursive;
+                        settings.push(
+                            widget::settings::item::builder(String::new()).control(
+                                widget::row()
+                                    .push(
+                                        widget::button::standard(fl!("reset"))
+                                            .on_press(Message::PermissionsEdit(path.clone(), None)),
+                                    )
+                                    .push(widget::button::suggested(fl!("apply")).on_press_maybe(
+                                        changed.then(|| Message::PermissionsApply(path.clone())),
+                                    ))
+                                    .spacing(space_xxs),
+                            ),
+                        );
+                    }
                 }
             }
             _ => {
@@ -1616,10 +2296,102 @@ impl Item {
             column = column.push(section);
         }
 
-        column.into()
-    }
+        #[cfg(unix)]
+        if let (Some(path), ItemMetadata::Path { .. }) = (self.path_opt(), &self.metadata) {
+            if let Some(view) = xattrs_view(path) {
+                column = column.push(view);
+            }
+        }
 
-    pub fn replace_view(&self, heading: String, sizes: IconSizes) -> Element<'_, Message> {
+        if let (Some(path), ItemMetadata::Path { metadata, .. }) = (self.path_opt(), &self.metadata)
+        {
+            if metadata.is_file() {
+                column = column.push(self.checksum_view(path));
+            }
+        }
+
+        column.into()
+    }
+
+    fn checksum_view<'a>(&'a self, path: &'a PathBuf) -> Element<'a, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let mut column = widget::column().spacing(space_xxs);
+
+        let mut row = widget::row()
+            .spacing(space_xxs)
+            .align_y(Alignment::Center)
+            .push(widget::text::heading(fl!("checksum")))
+            .push(widget::horizontal_space());
+        for kind in ChecksumKind::all() {
+            let selected = self.checksum_opt.as_ref().map(|checksum| checksum.kind) == Some(*kind);
+            let button = if selected {
+                widget::button::suggested(kind.to_string())
+            } else {
+                widget::button::standard(kind.to_string())
+            };
+            row = row.push(button.on_press(Message::Checksum(path.clone(), Some(*kind))));
+        }
+        column = column.push(row);
+
+        let Some(checksum) = &self.checksum_opt else {
+            return column.into();
+        };
+        match &checksum.state {
+            ChecksumState::Calculating(progress, _) => {
+                column = column.push(
+                    widget::row()
+                        .spacing(space_xxs)
+                        .align_y(Alignment::Center)
+                        .push(widget::text::body(fl!(
+                            "calculating-checksum",
+                            percent = (progress * 100.0) as i32
+                        )))
+                        .push(widget::horizontal_space())
+                        .push(
+                            widget::button::standard(fl!("cancel"))
+                                .on_press(Message::Checksum(path.clone(), None)),
+                        ),
+                );
+            }
+            ChecksumState::Done(value) => {
+                column = column.push(
+                    widget::row()
+                        .spacing(space_xxs)
+                        .align_y(Alignment::Center)
+                        .push(
+                            widget::text::body(value.as_str())
+                                .font(font::mono())
+                                .width(Length::Fill),
+                        )
+                        .push(
+                            widget::button::icon(widget::icon::from_name("edit-copy-symbolic"))
+                                .on_press(Message::ChecksumCopy(value.clone())),
+                        ),
+                );
+                column = column.push(
+                    widget::text_input(fl!("checksum-verify"), checksum.verify.as_str())
+                        .on_input(move |verify| Message::ChecksumVerify(path.clone(), verify)),
+                );
+                match checksum.verified() {
+                    Some(true) => {
+                        column = column.push(widget::text::body(fl!("checksum-match")));
+                    }
+                    Some(false) => {
+                        column = column.push(widget::text::body(fl!("checksum-mismatch")));
+                    }
+                    None => {}
+                }
+            }
+            ChecksumState::Error(err) => {
+                column = column.push(widget::text::body(err.as_str()));
+            }
+        }
+
+        column.into()
+    }
+
+    pub fn replace_view(&self, heading: String, sizes: IconSizes) -> Element<'_, Message> {
         let cosmic_theme::Spacing { space_xxxs, .. } = theme::active().cosmic().spacing;
 
         let mut row = widget::row().spacing(space_xxxs);
@@ -1690,6 +2462,93 @@ impl HeadingOptions {
             HeadingOptions::TrashedOn.to_string(),
         ]
     }
+
+    /// Sort direction used when sorting by this heading for the first time, modified is
+    /// descending and the others are ascending
+    pub fn default_direction(self) -> bool {
+        self != HeadingOptions::Modified
+    }
+
+    /// Heading sorted by after this one when cycling through the sort of the grid view, in the
+    /// order of the list view columns
+    pub fn next(self, trash: bool) -> HeadingOptions {
+        match self {
+            HeadingOptions::Name if trash => HeadingOptions::TrashedOn,
+            HeadingOptions::Name => HeadingOptions::Modified,
+            HeadingOptions::Modified | HeadingOptions::TrashedOn => HeadingOptions::Size,
+            HeadingOptions::Size => HeadingOptions::Name,
+        }
+    }
+
+    /// Compare items by this heading in ascending order
+    pub fn compare(self, a: &Item, b: &Item) -> Ordering {
+        match self {
+            HeadingOptions::Name => LANGUAGE_SORTER.compare(&a.display_name, &b.display_name),
+            HeadingOptions::Modified => a.metadata.modified().cmp(&b.metadata.modified()),
+            HeadingOptions::Size => sort_size(a).1.cmp(&sort_size(b).1),
+            HeadingOptions::TrashedOn => {
+                let time_deleted = |x: &Item| match &x.metadata {
+                    ItemMetadata::Trash { entry, .. } => Some(entry.time_deleted),
+                    _ => None,
+                };
+                time_deleted(a).cmp(&time_deleted(b))
+            }
+        }
+    }
+}
+
+// Whether the item has entries instead of a size in bytes, and its entries or size
+fn sort_size(item: &Item) -> (bool, u64) {
+    match &item.metadata {
+        ItemMetadata::Path { metadata, children } => {
+            if metadata.is_dir() {
+                (true, *children as u64)
+            } else {
+                (false, metadata.len())
+            }
+        }
+        ItemMetadata::Trash { metadata, .. } => match metadata.size {
+            trash::TrashItemSize::Entries(entries) => (true, entries as u64),
+            trash::TrashItemSize::Bytes(bytes) => (false, bytes),
+        },
+        ItemMetadata::SimpleDir { entries } => (true, *entries),
+        ItemMetadata::SimpleFile { size } => (false, *size),
+    }
+}
+
+/// Compare items by the primary sort and direction, breaking ties with the secondary sort in its
+/// default direction. Folders are grouped before files first if `folders_first` is set, and
+/// items with entries are always grouped before files when sorting by size.
+pub fn sort_compare(
+    a: &Item,
+    b: &Item,
+    (sort_name, sort_direction, folders_first): (HeadingOptions, bool, bool),
+    sort_secondary: HeadingOptions,
+) -> Ordering {
+    let check_reverse = |ord: Ordering, sort: bool| if sort { ord } else { ord.reverse() };
+    let folders = if folders_first {
+        b.metadata.is_dir().cmp(&a.metadata.is_dir())
+    } else {
+        Ordering::Equal
+    };
+    let entries = if sort_name == HeadingOptions::Size {
+        sort_size(b).0.cmp(&sort_size(a).0)
+    } else {
+        Ordering::Equal
+    };
+    folders
+        .then(entries)
+        .then_with(|| check_reverse(sort_name.compare(a, b), sort_direction))
+        .then_with(|| {
+            if sort_secondary == sort_name {
+                Ordering::Equal
+            } else {
+                check_reverse(
+                    sort_secondary.compare(a, b),
+                    sort_secondary.default_direction(),
+                )
+            }
+        })
 }
 
 #[derive(Clone, Debug)]
@@ -1742,6 +2601,9 @@ pub struct Tab {
     pub size_opt: Cell<Option<Size>>,
     pub item_view_size_opt: Cell<Option<Size>>,
     pub edit_location: Option<EditLocation>,
+    /// Item being renamed in place
+    pub rename_opt: Option<RenameInPlace>,
+    rename_id: widget::Id,
     pub edit_location_id: widget::Id,
     pub history_i: usize,
     pub history: Vec<Location>,
@@ -1749,9 +2611,29 @@ pub struct Tab {
     pub sort_name: HeadingOptions,
     pub sort_direction: bool,
     pub gallery: bool,
+    /// Zoom of the gallery image, 1.0 fits it into the window
+    pub gallery_zoom: f32,
+    /// Step to the next gallery image on a timer
+    pub gallery_slideshow: bool,
+    /// Decoded gallery images, `None` if decoding failed
+    gallery_images: HashMap<PathBuf, Option<widget::image::Handle>>,
+    /// Views remembered per folder, most recently changed first
+    pub(crate) folder_views: Arc<Vec<(String, FolderView2)>>,
+    /// Running operations, to show how far they got with the items
+    pub(crate) operations: Arc<Vec<(Operation, Controller)>>,
+    /// Global config, used for folders without a remembered view
+    default_config: TabConfig2,
     pub(crate) parent_item_opt: Option<Item>,
     pub(crate) items_opt: Option<Vec<Item>>,
+    /// Why the location could not be read, shown instead of the empty folder message
+    pub(crate) scan_error_opt: Option<String>,
+    /// Incremented for every scan, results of superseded scans are dropped
+    scan_epoch: Arc<atomic::AtomicU64>,
+    /// Free and total space of the filesystem of the location, if available
+    pub(crate) disk_space_opt: Option<(u64, u64)>,
     pub dnd_hovered: Option<(Location, Instant)>,
+    /// Whether files dragged over the tab are copied or moved when dropped
+    pub dnd_action: Option<DndAction>,
     scrollable_id: widget::Id,
     select_focus: Option<usize>,
     select_range: Option<(usize, usize)>,
@@ -1759,22 +2641,227 @@ pub struct Tab {
     selected_clicked: bool,
     last_right_click: Option<usize>,
     search_context: Option<SearchContext>,
+    folder_size_paths: Vec<PathBuf>,
+    drag_select_base: Option<Vec<usize>>,
 }
 
-fn calculate_dir_size(path: &Path, controller: Controller) -> Result<u64, String> {
-    let mut total = 0;
-    for entry_res in WalkDir::new(path) {
-        controller.check()?;
-        //TODO: report more errors?
-        if let Ok(entry) = entry_res {
-            if let Ok(metadata) = entry.metadata() {
-                if metadata.is_file() {
-                    total += metadata.len();
+fn checksum_subscription(
+    path: PathBuf,
+    kind: ChecksumKind,
+    controller: Controller,
+) -> Subscription<Message> {
+    Subscription::run_with_id(
+        ("checksum", path.clone(), kind),
+        stream::channel(1, |mut output| async move {
+            let message = {
+                let path = path.clone();
+                let mut progress_output = output.clone();
+                tokio::task::spawn_blocking(move || {
+                    let progress = |fraction| {
+                        let _ = futures::executor::block_on(
+                            progress_output.send(Message::ChecksumProgress(path.clone(), fraction)),
+                        );
+                    };
+                    let res = checksum::calculate(&path, kind, &controller, progress);
+                    if let Err(err) = &res {
+                        log::warn!("failed to calculate {} of {:?}: {}", kind, path, err);
+                    }
+                    Message::ChecksumResult(path.clone(), kind, res)
+                })
+                .await
+                .unwrap()
+            };
+
+            match output.send(message).await {
+                Ok(()) => {}
+                Err(err) => {
+                    log::warn!("failed to send checksum for {:?}: {}", &path, err);
                 }
             }
+
+            std::future::pending().await
+        }),
+    )
+}
+
+/// Decode a full size image for the gallery
+fn gallery_image(path: &Path) -> Option<widget::image::Handle> {
+    if fs::metadata(path).is_ok_and(|metadata| metadata.len() > GALLERY_PRELOAD_MAX) {
+        // Left for the renderer to load
+        return None;
+    }
+    let reader = match image::ImageReader::open(path).and_then(|img| img.with_guessed_format()) {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!("failed to read {:?}: {}", path, err);
+            return None;
+        }
+    };
+    match reader.decode().map(|image| image.into_rgba8()) {
+        Ok(image) => Some(widget::image::Handle::from_rgba(
+            image.width(),
+            image.height(),
+            image.into_raw(),
+        )),
+        Err(err) => {
+            log::warn!("failed to decode {:?}: {}", path, err);
+            None
         }
     }
-    Ok(total)
+}
+
+fn gallery_image_subscription(path: PathBuf) -> Subscription<Message> {
+    Subscription::run_with_id(
+        ("gallery", path.clone()),
+        stream::channel(1, |mut output| async move {
+            let message = {
+                let path = path.clone();
+                tokio::task::spawn_blocking(move || {
+                    let start = Instant::now();
+                    let handle_opt = gallery_image(&path);
+                    log::debug!("decoded {:?} in {:?}", path, start.elapsed());
+                    Message::GalleryImage(path.clone(), handle_opt)
+                })
+                .await
+                .unwrap()
+            };
+
+            match output.send(message).await {
+                Ok(()) => {}
+                Err(err) => {
+                    log::warn!("failed to send gallery image for {:?}: {}", &path, err);
+                }
+            }
+
+            std::future::pending().await
+        }),
+    )
+}
+
+/// Next zoom level of the gallery image, stays at the smallest or largest level
+fn gallery_zoom_step(zoom: f32, zoom_in: bool) -> f32 {
+    if zoom_in {
+        GALLERY_ZOOM_LEVELS
+            .iter()
+            .copied()
+            .find(|level| *level > zoom)
+            .unwrap_or(zoom)
+    } else {
+        GALLERY_ZOOM_LEVELS
+            .iter()
+            .rev()
+            .copied()
+            .find(|level| *level < zoom)
+            .unwrap_or(zoom)
+    }
+}
+
+/// Gallery image fit into the available space and then scaled by the zoom
+fn gallery_image_view(
+    handle: widget::image::Handle,
+    size_opt: Option<(u32, u32)>,
+    zoom: f32,
+) -> Element<'static, Message> {
+    let Some((width, height)) = size_opt.filter(|_| zoom != 1.0) else {
+        return widget::container(
+            //TODO: use widget::image::viewer, when its zoom can be reset
+            widget::image(handle),
+        )
+        .center(Length::Fill)
+        .into();
+    };
+    widget::responsive(move |size| {
+        // Images are only scaled down to fit
+        let fit = (size.width / width as f32)
+            .min(size.height / height as f32)
+            .min(1.0);
+        let image_width = width as f32 * fit * zoom;
+        let image_height = height as f32 * fit * zoom;
+        widget::scrollable(
+            widget::container(
+                widget::image(handle.clone())
+                    .width(Length::Fixed(image_width))
+                    .height(Length::Fixed(image_height)),
+            )
+            .center_x(Length::Fixed(image_width.max(size.width)))
+            .center_y(Length::Fixed(image_height.max(size.height))),
+        )
+        .direction(scrollable::Direction::Both {
+            vertical: scrollable::Scrollbar::default(),
+            horizontal: scrollable::Scrollbar::default(),
+        })
+        .into()
+    })
+    .into()
+}
+
+fn dir_size_subscription(
+    path: PathBuf,
+    respect_gitignore: bool,
+    controller: Controller,
+) -> Subscription<Message> {
+    Subscription::run_with_id(
+        ("dir_size", path.clone(), respect_gitignore),
+        stream::channel(1, |mut output| async move {
+            let message = {
+                let path = path.clone();
+                let mut progress_output = output.clone();
+                tokio::task::spawn_blocking(move || {
+                    let start = Instant::now();
+                    let progress = |size| {
+                        // Report the running total so the size fills in incrementally
+                        let _ = futures::executor::block_on(
+                            progress_output
+                                .send(Message::DirectorySizeProgress(path.clone(), size)),
+                        );
+                    };
+                    match dir_size::calculate(&path, respect_gitignore, &controller, progress) {
+                        Ok(size) => {
+                            log::debug!(
+                                "calculated directory size of {:?} in {:?}",
+                                path,
+                                start.elapsed()
+                            );
+                            Message::DirectorySize(path.clone(), DirSize::Directory(size))
+                        }
+                        Err(err) => {
+                            log::warn!("failed to calculate directory size of {:?}: {}", path, err);
+                            Message::DirectorySize(path.clone(), DirSize::Error(err))
+                        }
+                    }
+                })
+                .await
+                .unwrap()
+            };
+
+            match output.send(message).await {
+                Ok(()) => {}
+                Err(err) => {
+                    log::warn!("failed to send directory size for {:?}: {}", &path, err);
+                }
+            }
+
+            std::future::pending().await
+        }),
+    )
+}
+
+/// Deepest folder containing all of the paths
+fn common_parent<'a>(paths: impl IntoIterator<Item = &'a Path>) -> Option<PathBuf> {
+    let mut common_opt: Option<PathBuf> = None;
+    for path in paths {
+        let parent = path.parent()?;
+        common_opt = Some(match common_opt {
+            Some(common) => common
+                .components()
+                .zip(parent.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+            None => parent.to_path_buf(),
+        });
+    }
+    common_opt
 }
 
 fn folder_name<P: AsRef<Path>>(path: P) -> (String, bool) {
@@ -1796,6 +2883,28 @@ fn folder_name<P: AsRef<Path>>(path: P) -> (String, bool) {
     (name, found_home)
 }
 
+/// Whether a name is listed in the .hidden file of its folder, which is only read once
+fn hidden_file_listed(
+    hidden_files: &Mutex<HashMap<PathBuf, Arc<Vec<String>>>>,
+    dir: &Path,
+    name: &str,
+) -> bool {
+    let cached = hidden_files.lock().unwrap().get(dir).cloned();
+    let names = match cached {
+        Some(names) => names,
+        None => {
+            // Read without holding the lock, another thread may have read it as well
+            let names = Arc::new(parse_hidden_file(&dir.join(".hidden")));
+            hidden_files
+                .lock()
+                .unwrap()
+                .insert(dir.to_path_buf(), names.clone());
+            names
+        }
+    };
+    names.iter().any(|hidden| hidden == name)
+}
+
 // parse .hidden file and return files path
 fn parse_hidden_file(path: &PathBuf) -> Vec<String> {
     let file = match File::open(path) {
@@ -1827,22 +2936,36 @@ impl Tab {
             item_view_size_opt: Cell::new(None),
             edit_location: None,
             edit_location_id: widget::Id::unique(),
+            rename_opt: None,
+            rename_id: widget::Id::unique(),
             history_i: 0,
             history,
             config,
             sort_name: HeadingOptions::Name,
             sort_direction: true,
             gallery: false,
+            gallery_zoom: 1.0,
+            gallery_slideshow: false,
+            gallery_images: HashMap::new(),
+            folder_views: Arc::new(Vec::new()),
+            operations: Arc::new(Vec::new()),
+            default_config: config,
             parent_item_opt: None,
             items_opt: None,
+            scan_error_opt: None,
+            scan_epoch: Arc::new(atomic::AtomicU64::new(0)),
+            disk_space_opt: None,
             scrollable_id: widget::Id::unique(),
             select_focus: None,
             select_range: None,
             clicked: None,
             dnd_hovered: None,
+            dnd_action: None,
             selected_clicked: false,
             last_right_click: None,
             search_context: None,
+            folder_size_paths: Vec::new(),
+            drag_select_base: None,
         }
     }
 
@@ -1861,6 +2984,10 @@ impl Tab {
                 let (name, _) = folder_name(path);
                 format!("Search \"{}\": {}", term, name)
             }
+            Location::Flatten(path, ..) => {
+                let (name, _) = folder_name(path);
+                fl!("flatten-title", name = name)
+            }
             Location::Trash => {
                 fl!("trash")
             }
@@ -1879,6 +3006,21 @@ impl Tab {
         self.items_opt.as_mut()
     }
 
+    /// Start a new scan of the location, superseding any scan still running
+    pub fn scan_begin(&self) -> u64 {
+        self.scan_epoch.fetch_add(1, atomic::Ordering::SeqCst) + 1
+    }
+
+    /// Whether results of the scan with this epoch are still wanted
+    pub fn scan_current(&self, epoch: u64) -> bool {
+        self.scan_epoch.load(atomic::Ordering::SeqCst) == epoch
+    }
+
+    /// Shared epoch, lets a running scan notice that it was superseded and stop early
+    pub fn scan_epoch(&self) -> Arc<atomic::AtomicU64> {
+        self.scan_epoch.clone()
+    }
+
     pub fn set_items(&mut self, mut items: Vec<Item>) {
         let selected = self.selected_locations();
         for item in items.iter_mut() {
@@ -1892,6 +3034,14 @@ impl Tab {
         self.items_opt = Some(items);
     }
 
+    /// Add items of a folder that is still being scanned, keeping all items sorted
+    pub fn append_items(&mut self, items: Vec<Item>) {
+        let all_items = self.items_opt.get_or_insert_with(Vec::new);
+        all_items.extend(items);
+        // Both parts are already sorted, which the sort takes advantage of
+        sort_scanned(all_items);
+    }
+
     pub fn selected_locations(&self) -> Vec<Location> {
         let mut locations = Vec::new();
         if let Some(ref items) = self.items_opt {
@@ -1906,6 +3056,113 @@ impl Tab {
         locations
     }
 
+    /// Item count and total size of the tab, and of its selection
+    pub fn summary(&self) -> String {
+        let mut items = 0;
+        // Totals are unknown while any of their folders has no computed size
+        let mut total = Some(0);
+        let mut selected = 0;
+        let mut selected_total = Some(0);
+        if let Some(ref tab_items) = self.items_opt {
+            for item in tab_items.iter() {
+                if item.hidden && !self.config.show_hidden {
+                    continue;
+                }
+                let size_opt = item.size_opt();
+                items += 1;
+                total = total.zip(size_opt).map(|(total, size)| total + size);
+                if item.selected {
+                    selected += 1;
+                    selected_total = selected_total
+                        .zip(size_opt)
+                        .map(|(total, size)| total + size);
+                }
+            }
+        }
+        let format_total =
+            |total: Option<u64>| total.map_or_else(|| fl!("panel-size-unknown"), format_size);
+        fl!(
+            "panel-summary",
+            items = items,
+            total = format_total(total),
+            selected = selected,
+            selected_total = format_total(selected_total)
+        )
+    }
+
+    /// Aggregate properties of the selection, if more than one item is selected
+    pub fn selection_preview_view(&self) -> Option<Element<Message>> {
+        let items: Vec<&Item> = self
+            .items_opt
+            .iter()
+            .flatten()
+            .filter(|item| item.selected)
+            .collect();
+        if items.len() < 2 {
+            return None;
+        }
+
+        let cosmic_theme::Spacing { space_xxxs, .. } = theme::active().cosmic().spacing;
+
+        let mut types = BTreeMap::<String, usize>::new();
+        let mut size = 0;
+        let mut calculating = false;
+        for item in items.iter() {
+            *types.entry(item.mime.to_string()).or_default() += 1;
+            match (item.size_opt(), &item.dir_size) {
+                (Some(item_size), _) => size += item_size,
+                (None, DirSize::Partial(partial, _)) => {
+                    size += partial;
+                    calculating = true;
+                }
+                (None, DirSize::Calculating(_)) => calculating = true,
+                (None, _) => {}
+            }
+        }
+
+        let mut details = widget::column().spacing(space_xxxs);
+        details = details.push(widget::text::heading(fl!(
+            "selected-items",
+            items = items.len()
+        )));
+        for (mime, count) in types {
+            details = details.push(widget::text::body(fl!(
+                "type-count",
+                mime = mime,
+                count = count
+            )));
+        }
+        let size = if calculating {
+            fl!("calculating-size", size = format_size(size))
+        } else {
+            format_size(size)
+        };
+        details = details.push(widget::text::body(fl!("item-size", size = size)));
+        if let Some(parent) = common_parent(
+            items
+                .iter()
+                .filter_map(|item| item.path_opt().map(|path| path.as_path())),
+        ) {
+            details = details.push(widget::text::body(fl!(
+                "common-parent",
+                parent = parent.display().to_string()
+            )));
+        }
+        Some(details.into())
+    }
+
+    /// Free and total space of the filesystem of the location
+    pub fn disk_space(&self) -> String {
+        match self.disk_space_opt {
+            Some((free, total)) => fl!(
+                "panel-free-space",
+                free = format_size(free),
+                total = format_size(total)
+            ),
+            None => fl!("panel-free-space-unavailable"),
+        }
+    }
+
     pub fn select_all(&mut self) {
         if let Some(ref mut items) = self.items_opt {
             for item in items.iter_mut() {
@@ -1918,6 +3175,80 @@ impl Tab {
         }
     }
 
+    fn item_by_path_mut(&mut self, path: &Path) -> Option<&mut Item> {
+        let item_opt = self.items_opt.as_mut().and_then(|items| {
+            items
+                .iter_mut()
+                .find(|item| item.path_opt().map(|x| x.as_path()) == Some(path))
+        });
+        match item_opt {
+            Some(item) => Some(item),
+            None => self
+                .parent_item_opt
+                .as_mut()
+                .filter(|item| item.path_opt().map(|x| x.as_path()) == Some(path)),
+        }
+    }
+
+    /// Select or deselect the visible items whose name matches a glob pattern
+    pub fn select_pattern(&mut self, pattern: &glob::Pattern, case_sensitive: bool, select: bool) {
+        let options = glob::MatchOptions {
+            case_sensitive,
+            ..glob::MatchOptions::new()
+        };
+        if let Some(ref mut items) = self.items_opt {
+            for item in items.iter_mut() {
+                if !self.config.show_hidden && item.hidden {
+                    continue;
+                }
+                if pattern.matches_with(&item.name, options) {
+                    item.selected = select;
+                }
+            }
+        }
+    }
+
+    /// Names of the selected items that are visible
+    pub fn selected_names(&self) -> HashSet<String> {
+        self.items_opt
+            .iter()
+            .flatten()
+            .filter(|item| item.selected && (self.config.show_hidden || !item.hidden))
+            .map(|item| item.name.clone())
+            .collect()
+    }
+
+    /// Select only the visible items with one of these names, returns how many were selected
+    pub fn select_names(&mut self, names: &HashSet<String>) -> usize {
+        self.select_focus = None;
+        let mut count = 0;
+        if let Some(ref mut items) = self.items_opt {
+            for item in items.iter_mut() {
+                item.selected =
+                    (self.config.show_hidden || !item.hidden) && names.contains(&item.name);
+                if item.selected {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Select only the visible items that match, returns how many were selected
+    pub fn select_matching(&mut self, matches: impl Fn(&Item) -> bool) -> usize {
+        self.select_focus = None;
+        let mut count = 0;
+        if let Some(ref mut items) = self.items_opt {
+            for item in items.iter_mut() {
+                item.selected = (self.config.show_hidden || !item.hidden) && matches(item);
+                if item.selected {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
     pub fn select_none(&mut self) -> bool {
         self.select_focus = None;
         let mut had_selection = false;
@@ -1944,6 +3275,17 @@ impl Tab {
         }
     }
 
+    /// Whether the item at this index is being renamed in place
+    fn renaming(&self, i: usize) -> bool {
+        self.rename_opt.as_ref().is_some_and(|rename| {
+            self.items_opt
+                .as_ref()
+                .and_then(|items| items.get(i))
+                .and_then(|item| item.path_opt())
+                == Some(&rename.from)
+        })
+    }
+
     pub fn select_paths(&mut self, paths: Vec<PathBuf>) {
         self.select_focus = None;
         if let Some(ref mut items) = self.items_opt {
@@ -2011,7 +3353,15 @@ impl Tab {
 
     pub fn select_rect(&mut self, rect: Rectangle, mod_ctrl: bool, mod_shift: bool) {
         if let Some(ref mut items) = self.items_opt {
-            for item in items.iter_mut() {
+            // Remember the selection from before the drag so Ctrl can add to it
+            let base = self.drag_select_base.get_or_insert_with(|| {
+                items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, item)| item.selected.then_some(i))
+                    .collect()
+            });
+            for (i, item) in items.iter_mut().enumerate() {
                 let was_overlapped = item.overlaps_drag_rect;
                 item.overlaps_drag_rect = item
                     .rect_opt
@@ -2019,7 +3369,9 @@ impl Tab {
                     .map(|r| r.intersects(&rect))
                     .unwrap_or(false);
 
-                item.selected = if mod_ctrl || mod_shift {
+                item.selected = if mod_ctrl {
+                    item.overlaps_drag_rect || base.contains(&i)
+                } else if mod_shift {
                     if was_overlapped == item.overlaps_drag_rect {
                         item.selected
                     } else {
@@ -2079,6 +3431,65 @@ impl Tab {
         }
     }
 
+    /// Position of the last item
+    fn last_pos_opt(&self) -> Option<(usize, usize)> {
+        let items = self.items_opt.as_ref()?;
+        items.iter().filter_map(|item| item.pos_opt.get()).max()
+    }
+
+    /// Last row, or the last row with an item in the column
+    fn last_row_opt(&self, col_opt: Option<usize>) -> Option<usize> {
+        let items = self.items_opt.as_ref()?;
+        items
+            .iter()
+            .filter_map(|item| item.pos_opt.get())
+            .filter(|(_, col)| col_opt.map_or(true, |col_opt| *col == col_opt))
+            .map(|(row, _)| row)
+            .max()
+    }
+
+    /// Last column of the row
+    fn last_col_opt(&self, row: usize) -> Option<usize> {
+        let items = self.items_opt.as_ref()?;
+        items
+            .iter()
+            .filter_map(|item| item.pos_opt.get())
+            .filter(|(item_row, _)| *item_row == row)
+            .map(|(_, col)| col)
+            .max()
+    }
+
+    /// Rows of items that fit in the view, moved over by page up and page down
+    fn page_rows(&self) -> usize {
+        let (Some(items), Some(size)) = (self.items_opt.as_ref(), self.item_view_size_opt.get())
+        else {
+            return 1;
+        };
+        let top = |row| {
+            items
+                .iter()
+                .find_map(|item| match (item.pos_opt.get(), item.rect_opt.get()) {
+                    (Some((item_row, _)), Some(rect)) if item_row == row => Some(rect.y),
+                    _ => None,
+                })
+        };
+        match (top(0), top(1)) {
+            (Some(first), Some(second)) if second > first => {
+                ((size.height / (second - first)).floor() as usize).max(1)
+            }
+            _ => 1,
+        }
+    }
+
+    /// How far the running operations got with the items, by path
+    fn item_progress(&self) -> HashMap<PathBuf, ItemProgress> {
+        let mut item_progress = HashMap::new();
+        for (op, controller) in self.operations.iter() {
+            item_progress.extend(op.item_progress(controller.current().as_deref()));
+        }
+        item_progress
+    }
+
     fn select_range_start_pos_opt(&self) -> Option<(usize, usize)> {
         let items = self.items_opt.as_ref()?;
         let item = items.get(self.select_range.map(|r| r.0)?)?;
@@ -2139,11 +3550,15 @@ impl Tab {
         self.location = location.clone();
         self.context_menu = None;
         self.edit_location = None;
+        self.rename_opt = None;
         self.items_opt = None;
+        self.scan_error_opt = None;
         //TODO: remember scroll by location?
         self.scroll_opt = None;
         self.select_focus = None;
         self.search_context = None;
+        self.folder_size_paths.clear();
+        self.drag_select_base = None;
         if let Some(history_i) = history_i_opt {
             // Navigating in history
             self.history_i = history_i;
@@ -2155,6 +3570,53 @@ impl Tab {
             self.history_i = self.history.len();
             self.history.push(location.clone());
         }
+        self.apply_folder_view();
+    }
+
+    /// View options of the current folder that are remembered
+    pub fn folder_view(&self) -> FolderView2 {
+        FolderView2 {
+            view: self.config.view,
+            sort_name: self.sort_name,
+            sort_direction: self.sort_direction,
+            show_hidden: self.config.show_hidden,
+        }
+    }
+
+    /// Restore the view remembered for the current folder, or the global default
+    pub fn apply_folder_view(&mut self) {
+        if !matches!(self.mode, Mode::App) {
+            return;
+        }
+        let Location::Path(path) = &self.location else {
+            return;
+        };
+        let path = path.to_string_lossy();
+        let folder_view = match self.folder_views.iter().find(|(folder, _)| *folder == path) {
+            Some((_, folder_view)) => *folder_view,
+            None => FolderView2 {
+                view: self.default_config.view,
+                sort_name: HeadingOptions::Name,
+                sort_direction: true,
+                show_hidden: self.default_config.show_hidden,
+            },
+        };
+        self.config.view = folder_view.view;
+        self.sort_name = folder_view.sort_name;
+        self.sort_direction = folder_view.sort_direction;
+        self.config.show_hidden = folder_view.show_hidden;
+    }
+
+    fn remember_folder_view(&self, commands: &mut Vec<Command>) {
+        if !matches!(self.mode, Mode::App) {
+            return;
+        }
+        if let Location::Path(path) = &self.location {
+            commands.push(Command::FolderView(
+                path.to_string_lossy().into_owned(),
+                self.folder_view(),
+            ));
+        }
     }
 
     pub fn update(&mut self, message: Message, modifiers: Modifiers) -> Vec<Command> {
@@ -2167,6 +3629,104 @@ impl Tab {
             Message::AddNetworkDrive => {
                 commands.push(Command::AddNetworkDrive);
             }
+            Message::Rescan => {
+                commands.push(Command::Rescan);
+            }
+            Message::CalculateFolderSizes => {
+                // Calculate selected folders, or all visible folders if none are selected
+                let show_hidden = self.config.show_hidden;
+                if let Some(ref mut items) = self.items_opt {
+                    let any_selected = items
+                        .iter()
+                        .any(|item| item.selected && item.metadata.is_dir());
+                    for item in items.iter_mut() {
+                        if !item.metadata.is_dir()
+                            || (any_selected && !item.selected)
+                            || (!any_selected && item.hidden && !show_hidden)
+                        {
+                            continue;
+                        }
+                        let Some(path) = item.path_opt().cloned() else {
+                            continue;
+                        };
+                        if let DirSize::Error(_) = item.dir_size {
+                            // Retry folders that failed or were cancelled
+                            item.dir_size = DirSize::Calculating(Controller::default());
+                        }
+                        if !self.folder_size_paths.contains(&path) {
+                            self.folder_size_paths.push(path);
+                        }
+                    }
+                }
+            }
+            Message::CancelFolderSizes => {
+                self.folder_size_paths.clear();
+                if let Some(ref mut items) = self.items_opt {
+                    for item in items.iter_mut() {
+                        match &item.dir_size {
+                            // A cancelled controller stays cancelled, so a later calculation
+                            // gets a new one
+                            DirSize::Calculating(controller) | DirSize::Partial(_, controller) => {
+                                controller.cancel();
+                                item.dir_size = DirSize::Calculating(Controller::default());
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            Message::Checksum(path, kind_opt) => {
+                if let Some(item) = self.item_by_path_mut(&path) {
+                    match kind_opt {
+                        // Keep a checksum of the same kind that is calculating or done
+                        Some(kind)
+                            if item.checksum_opt.as_ref().is_some_and(|checksum| {
+                                checksum.kind == kind
+                                    && !matches!(checksum.state, ChecksumState::Error(_))
+                            }) => {}
+                        Some(kind) => item.checksum_opt = Some(ItemChecksum::new(&path, kind)),
+                        // Dropping the controller cancels the calculation
+                        None => item.checksum_opt = None,
+                    }
+                }
+            }
+            Message::ChecksumCopy(checksum) => {
+                commands.push(Command::Iced(
+                    cosmic::iced_runtime::clipboard::write(checksum).into(),
+                ));
+            }
+            Message::ChecksumProgress(path, fraction) => {
+                if let Some(item) = self.item_by_path_mut(&path) {
+                    if let Some(ItemChecksum {
+                        state: ChecksumState::Calculating(progress, _),
+                        ..
+                    }) = &mut item.checksum_opt
+                    {
+                        *progress = fraction;
+                    }
+                }
+            }
+            Message::ChecksumResult(path, kind, res) => {
+                if let Some(item) = self.item_by_path_mut(&path) {
+                    if let Some(checksum) = &mut item.checksum_opt {
+                        if checksum.kind == kind
+                            && matches!(checksum.state, ChecksumState::Calculating(..))
+                        {
+                            checksum.state = match res {
+                                Ok(value) => ChecksumState::Done(value),
+                                Err(err) => ChecksumState::Error(err),
+                            };
+                        }
+                    }
+                }
+            }
+            Message::ChecksumVerify(path, verify) => {
+                if let Some(item) = self.item_by_path_mut(&path) {
+                    if let Some(checksum) = &mut item.checksum_opt {
+                        checksum.verify = verify;
+                    }
+                }
+            }
             Message::ClickRelease(click_i_opt) => {
                 if click_i_opt == self.clicked.take() {
                     return commands;
@@ -2188,6 +3748,7 @@ impl Tab {
             }
             Message::DragEnd(_) => {
                 self.clicked = None;
+                self.drag_select_base = None;
                 if let Some(ref mut items) = self.items_opt {
                     for item in items.iter_mut() {
                         item.overlaps_drag_rect = false;
@@ -2195,10 +3756,12 @@ impl Tab {
                 }
             }
             Message::DoubleClick(click_i_opt) => {
+                // Double clicking the name being edited selects text instead of opening
                 if let Some(clicked_item) = self
                     .items_opt
                     .as_ref()
                     .and_then(|items| click_i_opt.and_then(|click_i| items.get(click_i)))
+                    .filter(|_| !click_i_opt.is_some_and(|click_i| self.renaming(click_i)))
                 {
                     if let Some(location) = &clicked_item.location_opt {
                         if clicked_item.metadata.is_dir() {
@@ -2219,6 +3782,10 @@ impl Tab {
                 self.selected_clicked = false;
                 self.context_menu = None;
                 self.edit_location = None;
+                // Clicking anything but the item being renamed stops renaming it
+                if !click_i_opt.is_some_and(|click_i| self.renaming(click_i)) {
+                    self.rename_opt = None;
+                }
                 self.location_context_menu_index = None;
                 if click_i_opt.is_none() {
                     self.clicked = click_i_opt;
@@ -2343,6 +3910,7 @@ impl Tab {
                 // View is preserved for existing tabs
                 let view = self.config.view;
                 let show_hidden = self.config.show_hidden;
+                self.default_config = config;
                 self.config = config;
                 self.config.view = view;
                 self.config.show_hidden = show_hidden;
@@ -2423,6 +3991,8 @@ impl Tab {
                 if let Some(rect) = rect_opt {
                     self.context_menu = None;
                     self.location_context_menu_index = None;
+                    // Rubber band selection starts on empty space, never drag the items around
+                    self.selected_clicked = false;
                     self.select_rect(rect, mod_ctrl, mod_shift);
                     if self.select_focus.take().is_some() {
                         // Unfocus currently focused button
@@ -2460,6 +4030,23 @@ impl Tab {
             Message::OpenInNewTab(path) => {
                 commands.push(Command::OpenInNewTab(path));
             }
+            Message::OpenLinkTarget => {
+                let link_opt = self.items_opt.as_ref().and_then(|items| {
+                    items
+                        .iter()
+                        .find(|item| item.selected && item.symlink_target_opt.is_some())
+                        .and_then(|item| item.path_opt().cloned())
+                });
+                if let Some(link) = link_opt {
+                    // Resolves chains of links, fails if they loop
+                    match fs::canonicalize(&link) {
+                        Ok(target) => cd = Some(Location::Path(target)),
+                        Err(err) => {
+                            log::warn!("failed to resolve link target of {:?}: {}", link, err)
+                        }
+                    }
+                }
+            }
             Message::EmptyTrash => {
                 commands.push(Command::EmptyTrash);
             }
@@ -2483,35 +4070,30 @@ impl Tab {
                 }
             }
             Message::Gallery(gallery) => {
-                self.gallery = gallery;
+                self.set_gallery(gallery);
             }
-            Message::GalleryPrevious | Message::GalleryNext => {
-                let mut pos_opt = None;
-                if let Some(mut indices) = self.column_sort() {
-                    if matches!(message, Message::GalleryPrevious) {
-                        indices.reverse();
-                    }
-                    let mut found = false;
-                    for (index, item) in indices {
-                        if self.select_focus.is_none() {
-                            found = true;
-                        }
-                        if self.select_focus == Some(index) {
-                            found = true;
-                            continue;
-                        }
-                        if found && item.can_gallery() {
-                            pos_opt = item.pos_opt.get();
-                            if pos_opt.is_some() {
-                                break;
-                            }
-                        }
-                    }
+            Message::GalleryImage(path, handle_opt) => {
+                // Ignore images decoded after moving on
+                if self.gallery && self.gallery_image_paths().contains(&path) {
+                    self.gallery_images.insert(path, handle_opt);
                 }
+            }
+            Message::GalleryPrevious | Message::GalleryNext => {
+                let previous = matches!(message, Message::GalleryPrevious);
+                let pos_opt = self
+                    .gallery_step(previous)
+                    .and_then(|index| self.items_opt.as_ref()?.get(index)?.pos_opt.get());
                 if let Some((row, col)) = pos_opt {
                     // Should mod_shift be available?
                     self.select_position(row, col, mod_shift);
+                    self.gallery_zoom = 1.0;
+                } else if !previous {
+                    // The slideshow ends at the last image
+                    self.gallery_slideshow = false;
                 }
+                // Only keep the shown image and its neighbours
+                let paths = self.gallery_image_paths();
+                self.gallery_images.retain(|path, _| paths.contains(path));
                 if let Some(offset) = self.select_focus_scroll() {
                     commands.push(Command::Iced(
                         scrollable::scroll_to(self.scrollable_id.clone(), offset).into(),
@@ -2525,12 +4107,24 @@ impl Tab {
                 if let Some(indices) = self.column_sort() {
                     for (_, item) in indices.iter() {
                         if item.selected && item.can_gallery() {
-                            self.gallery = !self.gallery;
+                            self.set_gallery(!self.gallery);
                             break;
                         }
                     }
                 }
             }
+            Message::GallerySlideshow(slideshow) => {
+                self.gallery_slideshow = slideshow;
+            }
+            Message::GalleryZoomDefault => {
+                self.gallery_zoom = 1.0;
+            }
+            Message::GalleryZoomIn => {
+                self.gallery_zoom = gallery_zoom_step(self.gallery_zoom, true);
+            }
+            Message::GalleryZoomOut => {
+                self.gallery_zoom = gallery_zoom_step(self.gallery_zoom, false);
+            }
             Message::GoNext => {
                 if let Some(history_i) = self.history_i.checked_add(1) {
                     if let Some(location) = self.history.get(history_i) {
@@ -2564,8 +4158,11 @@ impl Tab {
                         }
 
                         //TODO: Shift modifier should select items in between
-                        // Try to select item in next row
-                        if !self.select_position(row + 1, col, mod_shift) {
+                        // Try to select item in next row, or in the first row when wrapping
+                        if !self.select_position(row + 1, col, mod_shift)
+                            && !(self.config.wrap_navigation
+                                && self.select_position(0, col, mod_shift))
+                        {
                             // Ensure current item is still selected if there are no other items
                             self.select_position(row, col, mod_shift);
                         }
@@ -2617,7 +4214,11 @@ impl Tab {
                                     }
                                 }
                                 self.select_position(row, col, mod_shift)
-                            }) {
+                            }) && !(self.config.wrap_navigation
+                                && self.last_pos_opt().map_or(false, |(row, col)| {
+                                    self.select_position(row, col, mod_shift)
+                                }))
+                            {
                                 // Ensure current item is still selected if there are no other items
                                 self.select_position(row, col, mod_shift);
                             }
@@ -2652,8 +4253,12 @@ impl Tab {
                         }
                         // Try to select next item in current row
                         if !self.select_position(row, col + 1, mod_shift) {
-                            // Try to select first item in next row
-                            if !self.select_position(row + 1, 0, mod_shift) {
+                            // Try to select first item in next row, or the first item when
+                            // wrapping
+                            if !self.select_position(row + 1, 0, mod_shift)
+                                && !(self.config.wrap_navigation
+                                    && self.select_position(0, 0, mod_shift))
+                            {
                                 // Ensure current item is still selected if there are no other items
                                 self.select_position(row, col, mod_shift);
                             }
@@ -2690,10 +4295,14 @@ impl Tab {
                         }
 
                         //TODO: Shift modifier should select items in between
-                        // Try to select item in last row
+                        // Try to select item in last row, or in the bottom row when wrapping
                         if !row
                             .checked_sub(1)
                             .map_or(false, |row| self.select_position(row, col, mod_shift))
+                            && !(self.config.wrap_navigation
+                                && self.last_row_opt(Some(col)).map_or(false, |last_row| {
+                                    self.select_position(last_row, col, mod_shift)
+                                }))
                         {
                             // Ensure current item is still selected if there are no other items
                             self.select_position(row, col, mod_shift);
@@ -2735,6 +4344,9 @@ impl Tab {
                     if let Some(parent) = path.parent() {
                         cd = Some(Location::Path(parent.to_owned()));
                     }
+                } else if let Location::Flatten(ref path, ..) = self.location {
+                    // Leaves the flattened view for the normal listing of the folder
+                    cd = Some(Location::Path(path.clone()));
                 }
             }
             Message::Open(path_opt) => {
@@ -2747,22 +4359,86 @@ impl Tab {
                         }
                     }
                     None => {
-                        if let Some(ref mut items) = self.items_opt {
-                            for item in items.iter() {
-                                if item.selected {
-                                    if let Some(location) = &item.location_opt {
-                                        if item.metadata.is_dir() {
-                                            //TODO: allow opening multiple tabs?
-                                            cd = Some(location.clone());
-                                        } else if let Some(path) = location.path_opt() {
-                                            commands.push(Command::OpenFile(path.to_path_buf()));
-                                        }
-                                    } else {
-                                        //TODO: open properties?
+                        let mut dirs = Vec::new();
+                        let mut files = Vec::new();
+                        if let Some(ref items) = self.items_opt {
+                            for item in items.iter().filter(|item| item.selected) {
+                                if let Some(location) = &item.location_opt {
+                                    if item.metadata.is_dir() {
+                                        dirs.push(location.clone());
+                                    } else if let Some(path) = location.path_opt() {
+                                        files.push(path.to_path_buf());
                                     }
+                                } else {
+                                    //TODO: open properties?
                                 }
                             }
                         }
+
+                        let dialog = matches!(self.mode, Mode::Dialog(_));
+                        if dialog || (files.is_empty() && dirs.len() == 1) {
+                            // Navigate into a single folder
+                            cd = dirs.pop();
+                        } else {
+                            for location in dirs {
+                                if let Some(path) = location.path_opt() {
+                                    commands.push(Command::OpenInNewTab(path.to_path_buf()));
+                                }
+                            }
+                        }
+
+                        if dialog || files.len() <= 1 {
+                            for path in files {
+                                commands.push(Command::OpenFile(path));
+                            }
+                        } else {
+                            // Launching many files at once may need confirmation
+                            commands.push(Command::OpenFiles(files));
+                        }
+                    }
+                }
+            }
+            Message::RenameInput(name) => {
+                if let Some(rename) = &mut self.rename_opt {
+                    rename.name = name;
+                }
+            }
+            Message::RenameStart => {
+                // Only a single item is renamed in place
+                let rename_opt = self.items_opt.as_ref().and_then(|items| {
+                    let mut selected = items.iter().filter(|item| item.selected);
+                    let item = selected.next()?;
+                    if selected.next().is_some() {
+                        return None;
+                    }
+                    Some((
+                        item.path_opt()?.clone(),
+                        item.name.clone(),
+                        item.metadata.is_dir(),
+                    ))
+                });
+                if let Some((from, name, dir)) = rename_opt {
+                    let stem_len = rename_stem_len(&name, dir);
+                    commands.push(Command::Iced(
+                        widget::text_input::focus(self.rename_id.clone()).into(),
+                    ));
+                    commands.push(Command::Iced(
+                        widget::text_input::select_range(self.rename_id.clone(), 0, stem_len)
+                            .into(),
+                    ));
+                    self.rename_opt = Some(RenameInPlace { from, name });
+                }
+            }
+            Message::RenameSubmit => {
+                if let Some(rename) = self.rename_opt.take() {
+                    match rename.target() {
+                        Some(to) => {
+                            if to != rename.from {
+                                commands.push(Command::Rename(rename.from, to));
+                            }
+                        }
+                        // Keep editing until the name is valid
+                        None => self.rename_opt = Some(rename),
                     }
                 }
             }
@@ -2797,7 +4473,7 @@ impl Tab {
                             if clicked_item.metadata.is_dir() {
                                 //cd = Some(Location::Path(path.clone()));
                                 commands.push(Command::OpenInNewTab(path.clone()))
-                            } else {
+                            } else if self.config.middle_click_open_files {
                                 commands.push(Command::OpenFile(path.clone()));
                             }
                         } else {
@@ -2841,6 +4517,11 @@ impl Tab {
                 }
             }
             Message::SearchReady(finished) => {
+                let max_results = if matches!(self.location, Location::Flatten(..)) {
+                    MAX_FLATTEN_RESULTS
+                } else {
+                    MAX_SEARCH_RESULTS
+                };
                 if let Some(context) = &mut self.search_context {
                     if let Some(items) = &mut self.items_opt {
                         if finished || context.ready.swap(false, atomic::Ordering::SeqCst) {
@@ -2854,7 +4535,7 @@ impl Tab {
                                     Ok(index) => index,
                                     Err(index) => index,
                                 };
-                                if index < MAX_SEARCH_RESULTS {
+                                if index < max_results {
                                     //TODO: use correct IconSizes
                                     items.insert(
                                         index,
@@ -2867,8 +4548,8 @@ impl Tab {
                                 }
                             }
                         }
-                        if items.len() >= MAX_SEARCH_RESULTS {
-                            items.truncate(MAX_SEARCH_RESULTS);
+                        if items.len() >= max_results {
+                            items.truncate(max_results);
                             if let Some(last_modified) =
                                 items.last().and_then(|item| item.metadata.modified())
                             {
@@ -2922,13 +4603,75 @@ impl Tab {
                     }
                 }
             }
+            Message::PageDown | Message::PageUp => {
+                if self.edit_location.is_none() && !self.gallery {
+                    let page_down = matches!(message, Message::PageDown);
+                    let edge_opt = if page_down {
+                        self.select_last_pos_opt()
+                    } else {
+                        self.select_first_pos_opt()
+                    };
+                    if let Some((row, col)) = self.select_focus_pos_opt().or(edge_opt) {
+                        if self.select_focus.is_none() {
+                            // Select item at the edge of the current selection to focus it.
+                            self.select_position(row, col, mod_shift);
+                        }
+
+                        let row = if page_down {
+                            let last_row = self.last_row_opt(None).unwrap_or(row);
+                            (row + self.page_rows()).min(last_row)
+                        } else {
+                            row.saturating_sub(self.page_rows())
+                        };
+                        // The last row of a grid may not reach the column
+                        let col = self.last_col_opt(row).map_or(col, |last| col.min(last));
+                        self.select_position(row, col, mod_shift);
+                    } else {
+                        // Select first item
+                        self.select_position(0, 0, mod_shift);
+                    }
+                    if let Some(offset) = self.select_focus_scroll() {
+                        commands.push(Command::Iced(
+                            scrollable::scroll_to(self.scrollable_id.clone(), offset).into(),
+                        ));
+                    }
+                    if let Some(id) = self.select_focus_id() {
+                        commands.push(Command::Iced(widget::button::focus(id).into()));
+                    }
+                }
+            }
+            Message::PermissionsApply(path) => {
+                if let Some(item) = self.item_by_path_mut(&path) {
+                    if let Some(edit) = item.permissions_edit_opt.take() {
+                        commands.push(Command::SetPermissions(path, edit.mode, edit.recursive));
+                    }
+                }
+            }
+            Message::PermissionsEdit(path, edit_opt) => {
+                if let Some(item) = self.item_by_path_mut(&path) {
+                    item.permissions_edit_opt = edit_opt;
+                }
+            }
+            Message::SymlinkTargetApply(path) => {
+                if let Some(item) = self.item_by_path_mut(&path) {
+                    if let Some(edit) = item.symlink_target_edit_opt.take() {
+                        commands.push(Command::SetSymlinkTarget(path, PathBuf::from(edit)));
+                    }
+                }
+            }
+            Message::SymlinkTargetEdit(path, edit_opt) => {
+                if let Some(item) = self.item_by_path_mut(&path) {
+                    item.symlink_target_edit_opt = edit_opt;
+                }
+            }
             Message::SetOpenWith(mime, id) => {
                 commands.push(Command::SetOpenWith(mime, id));
             }
             Message::SetSort(heading_option, dir) => {
-                if !matches!(self.location, Location::Search(..)) {
+                if !matches!(self.location, Location::Search(..) | Location::Flatten(..)) {
                     self.sort_name = heading_option;
                     self.sort_direction = dir;
+                    self.remember_folder_view(&mut commands);
                 }
             }
             Message::TabComplete(path, completions) => {
@@ -2946,32 +4689,26 @@ impl Tab {
                     let location = Location::Path(path);
                     for item in items.iter_mut() {
                         if item.location_opt.as_ref() == Some(&location) {
-                            let handle_opt = match &thumbnail {
-                                ItemThumbnail::NotImage => None,
-                                ItemThumbnail::Image(handle, _) => Some(widget::icon::Handle {
-                                    symbolic: false,
-                                    data: widget::icon::Data::Image(handle.clone()),
-                                }),
-                                ItemThumbnail::Svg(handle) => Some(widget::icon::Handle {
-                                    symbolic: false,
-                                    data: widget::icon::Data::Svg(handle.clone()),
-                                }),
-                                //TODO: text thumbnails?
-                                ItemThumbnail::Text(_text) => None,
-                            };
-                            if let Some(handle) = handle_opt {
-                                item.icon_handle_grid = handle.clone();
-                                item.icon_handle_list = handle.clone();
-                                item.icon_handle_list_condensed = handle;
-                            }
-                            item.thumbnail_opt = Some(thumbnail);
+                            item.set_thumbnail(thumbnail);
                             break;
                         }
                     }
                 }
             }
+            Message::ToggleFlatten => match &self.location {
+                Location::Flatten(path, ..) => cd = Some(Location::Path(path.clone())),
+                Location::Path(path) | Location::Search(path, ..) => {
+                    cd = Some(Location::Flatten(
+                        path.clone(),
+                        self.config.show_hidden,
+                        Instant::now(),
+                    ));
+                }
+                _ => {}
+            },
             Message::ToggleShowHidden => {
                 self.config.show_hidden = !self.config.show_hidden;
+                self.remember_folder_view(&mut commands);
                 if let Location::Search(path, term, ..) = &self.location {
                     cd = Some(Location::Search(
                         path.clone(),
@@ -2979,25 +4716,33 @@ impl Tab {
                         self.config.show_hidden,
                         Instant::now(),
                     ));
+                } else if let Location::Flatten(path, ..) = &self.location {
+                    cd = Some(Location::Flatten(
+                        path.clone(),
+                        self.config.show_hidden,
+                        Instant::now(),
+                    ));
                 }
             }
             Message::View(view) => {
                 self.config.view = view;
+                self.remember_folder_view(&mut commands);
             }
             Message::ToggleSort(heading_option) => {
-                if !matches!(self.location, Location::Search(..)) {
+                if !matches!(self.location, Location::Search(..) | Location::Flatten(..)) {
                     let heading_sort = if self.sort_name == heading_option {
                         !self.sort_direction
                     } else {
-                        // Default modified to descending, and others to ascending.
-                        heading_option != HeadingOptions::Modified
+                        heading_option.default_direction()
                     };
                     self.sort_direction = heading_sort;
                     self.sort_name = heading_option;
+                    self.remember_folder_view(&mut commands);
                 }
             }
             Message::Drop(Some((to, mut from))) => {
                 self.dnd_hovered = None;
+                self.dnd_action = None;
                 match to {
                     Location::Desktop(to, ..) | Location::Path(to) => {
                         if let Ok(entries) = fs::read_dir(&to) {
@@ -3022,6 +4767,7 @@ impl Tab {
             }
             Message::Drop(None) => {
                 self.dnd_hovered = None;
+                self.dnd_action = None;
             }
             Message::DndHover(loc) => {
                 if self
@@ -3050,8 +4796,12 @@ impl Tab {
             Message::DndLeave(loc) => {
                 if Some(&loc) == self.dnd_hovered.as_ref().map(|(l, _)| l) {
                     self.dnd_hovered = None;
+                    self.dnd_action = None;
                 }
             }
+            Message::DndAction(action) => {
+                self.dnd_action = Some(action);
+            }
             Message::WindowDrag => {
                 commands.push(Command::WindowDrag);
             }
@@ -3064,17 +4814,33 @@ impl Tab {
             Message::ZoomOut => {
                 commands.push(Command::Action(Action::ZoomOut));
             }
-            Message::DirectorySize(path, dir_size) => {
+            Message::DirectorySize(path, dir_size) => {
+                let location = Location::Path(path);
+                if let Some(ref mut item) = self.parent_item_opt {
+                    if item.location_opt.as_ref() == Some(&location) {
+                        item.dir_size = dir_size.clone();
+                    }
+                }
+                if let Some(ref mut items) = self.items_opt {
+                    for item in items.iter_mut() {
+                        if item.location_opt.as_ref() == Some(&location) {
+                            item.dir_size = dir_size;
+                            break;
+                        }
+                    }
+                }
+            }
+            Message::DirectorySizeProgress(path, size) => {
                 let location = Location::Path(path);
                 if let Some(ref mut item) = self.parent_item_opt {
                     if item.location_opt.as_ref() == Some(&location) {
-                        item.dir_size = dir_size.clone();
+                        item.set_dir_size_progress(size);
                     }
                 }
                 if let Some(ref mut items) = self.items_opt {
                     for item in items.iter_mut() {
                         if item.location_opt.as_ref() == Some(&location) {
-                            item.dir_size = dir_size;
+                            item.set_dir_size_progress(size);
                             break;
                         }
                     }
@@ -3103,6 +4869,13 @@ impl Tab {
                     }
                     _ => {}
                 }
+            } else if !self.config.follow_symlinks
+                && location
+                    .path_opt()
+                    .is_some_and(|path| path.is_symlink() && path.is_dir())
+            {
+                // Symlinked folders are leaves when symlinks are not followed
+                log::info!("not following symlinked folder {:?}", location);
             } else {
                 // Select parent if location is not directory
                 let mut selected_paths = None;
@@ -3138,9 +4911,24 @@ impl Tab {
         commands
     }
 
+    // Where a search result is, relative to the folder of a flattened view
+    fn result_caption(&self, item: &Item) -> String {
+        match (item.path_opt(), &self.location) {
+            (Some(path), Location::Flatten(root, ..)) => path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .display()
+                .to_string(),
+            (Some(path), _) => path.display().to_string(),
+            (None, _) => String::new(),
+        }
+    }
+
     pub(crate) fn sort_options(&self) -> (HeadingOptions, bool, bool) {
         match self.location {
-            Location::Search(..) => (HeadingOptions::Modified, false, false),
+            Location::Search(..) | Location::Flatten(..) => {
+                (HeadingOptions::Modified, false, false)
+            }
             _ => (
                 self.sort_name,
                 self.sort_direction,
@@ -3149,99 +4937,76 @@ impl Tab {
         }
     }
 
-    fn column_sort(&self) -> Option<Vec<(usize, &Item)>> {
-        let check_reverse = |ord: Ordering, sort: bool| {
-            if sort {
-                ord
-            } else {
-                ord.reverse()
+    /// Show or hide the gallery, starting at the default zoom
+    pub fn set_gallery(&mut self, gallery: bool) {
+        self.gallery = gallery;
+        self.gallery_zoom = 1.0;
+        if !gallery {
+            self.gallery_slideshow = false;
+            self.gallery_images.clear();
+        }
+    }
+
+    /// Index of the item before or after the focused one that can be shown in the gallery
+    fn gallery_step(&self, previous: bool) -> Option<usize> {
+        let mut indices = self.column_sort()?;
+        if previous {
+            indices.reverse();
+        }
+        let mut found = false;
+        for (index, item) in indices {
+            if self.select_focus.is_none() {
+                found = true;
             }
-        };
-        let mut items: Vec<_> = self.items_opt.as_ref()?.iter().enumerate().collect();
-        let (sort_name, sort_direction, folders_first) = self.sort_options();
-        match sort_name {
-            HeadingOptions::Size => {
-                items.sort_by(|a, b| {
-                    // entries take precedence over size
-                    let get_size = |x: &Item| match &x.metadata {
-                        ItemMetadata::Path { metadata, children } => {
-                            if metadata.is_dir() {
-                                (true, *children as u64)
-                            } else {
-                                (false, metadata.len())
-                            }
-                        }
-                        ItemMetadata::Trash { metadata, .. } => match metadata.size {
-                            trash::TrashItemSize::Entries(entries) => (true, entries as u64),
-                            trash::TrashItemSize::Bytes(bytes) => (false, bytes),
-                        },
-                        ItemMetadata::SimpleDir { entries } => (true, *entries),
-                        ItemMetadata::SimpleFile { size } => (false, *size),
-                    };
-                    let (a_is_entry, a_size) = get_size(a.1);
-                    let (b_is_entry, b_size) = get_size(b.1);
-
-                    //TODO: use folders_first?
-                    match (a_is_entry, b_is_entry) {
-                        (true, false) => Ordering::Less,
-                        (false, true) => Ordering::Greater,
-                        _ => check_reverse(a_size.cmp(&b_size), sort_direction),
-                    }
-                })
+            if self.select_focus == Some(index) {
+                found = true;
+                continue;
             }
-            HeadingOptions::Name => items.sort_by(|a, b| {
-                if folders_first {
-                    match (a.1.metadata.is_dir(), b.1.metadata.is_dir()) {
-                        (true, false) => Ordering::Less,
-                        (false, true) => Ordering::Greater,
-                        _ => check_reverse(
-                            LANGUAGE_SORTER.compare(&a.1.display_name, &b.1.display_name),
-                            sort_direction,
-                        ),
-                    }
-                } else {
-                    check_reverse(
-                        LANGUAGE_SORTER.compare(&a.1.display_name, &b.1.display_name),
-                        sort_direction,
-                    )
-                }
-            }),
-            HeadingOptions::Modified => {
-                items.sort_by(|a, b| {
-                    let a_modified = a.1.metadata.modified();
-                    let b_modified = b.1.metadata.modified();
-                    if folders_first {
-                        match (a.1.metadata.is_dir(), b.1.metadata.is_dir()) {
-                            (true, false) => Ordering::Less,
-                            (false, true) => Ordering::Greater,
-                            _ => check_reverse(a_modified.cmp(&b_modified), sort_direction),
-                        }
-                    } else {
-                        check_reverse(a_modified.cmp(&b_modified), sort_direction)
-                    }
-                });
+            if found && item.can_gallery() && item.pos_opt.get().is_some() {
+                return Some(index);
             }
-            HeadingOptions::TrashedOn => {
-                let time_deleted = |x: &Item| match &x.metadata {
-                    ItemMetadata::Trash { entry, .. } => Some(entry.time_deleted),
-                    _ => None,
-                };
+        }
+        None
+    }
 
-                items.sort_by(|a, b| {
-                    let a_time_deleted = time_deleted(a.1);
-                    let b_time_deleted = time_deleted(b.1);
-                    if folders_first {
-                        match (a.1.metadata.is_dir(), b.1.metadata.is_dir()) {
-                            (true, false) => Ordering::Less,
-                            (false, true) => Ordering::Greater,
-                            _ => check_reverse(a_time_deleted.cmp(&b_time_deleted), sort_direction),
-                        }
-                    } else {
-                        check_reverse(b_time_deleted.cmp(&a_time_deleted), sort_direction)
-                    }
-                });
-            }
+    /// Paths of the gallery image and of the images before and after it
+    fn gallery_image_paths(&self) -> Vec<PathBuf> {
+        let Some(items) = &self.items_opt else {
+            return Vec::new();
+        };
+        [
+            self.select_focus,
+            self.gallery_step(true),
+            self.gallery_step(false),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|index| items.get(index))
+        .filter(|item| item.mime.type_() == mime::IMAGE && item.mime != mime::IMAGE_SVG)
+        .filter_map(|item| item.path_opt().map(|path| path.to_path_buf()))
+        .collect()
+    }
+
+    /// Text input shown instead of the name of the item being renamed in place
+    fn rename_input(&self, item: &Item) -> Option<Element<'_, Message>> {
+        let rename = self.rename_opt.as_ref()?;
+        if item.path_opt() != Some(&rename.from) {
+            return None;
         }
+        Some(
+            widget::text_input("", rename.name.as_str())
+                .id(self.rename_id.clone())
+                .on_input(Message::RenameInput)
+                .on_submit(Message::RenameSubmit)
+                .width(Length::Fill)
+                .into(),
+        )
+    }
+
+    fn column_sort(&self) -> Option<Vec<(usize, &Item)>> {
+        let mut items: Vec<_> = self.items_opt.as_ref()?.iter().enumerate().collect();
+        let sort_options = self.sort_options();
+        items.sort_by(|a, b| sort_compare(a.1, b.1, sort_options, self.config.sort_secondary));
         Some(items)
     }
 
@@ -3271,7 +5036,8 @@ impl Tab {
                 }
             })
             .on_enter(move |_, _, _| Message::DndEnter(location2.clone()))
-            .on_leave(move || Message::DndLeave(location3.clone())),
+            .on_leave(move || Message::DndLeave(location3.clone()))
+            .on_action_selected(Message::DndAction),
         );
         if is_dnd_hovered {
             container = container.style(|t| {
@@ -3313,26 +5079,19 @@ impl Tab {
                         .unwrap_or(&ItemThumbnail::NotImage)
                     {
                         ItemThumbnail::NotImage => {}
-                        ItemThumbnail::Image(handle, _) => {
-                            if let Some(path) = item.path_opt() {
-                                element_opt = Some(
-                                    widget::container(
-                                        //TODO: use widget::image::viewer, when its zoom can be reset
-                                        widget::image(widget::image::Handle::from_path(path)),
-                                    )
-                                    .center(Length::Fill)
-                                    .into(),
-                                );
-                            } else {
-                                element_opt = Some(
-                                    widget::container(
-                                        //TODO: use widget::image::viewer, when its zoom can be reset
-                                        widget::image(handle.clone()),
-                                    )
-                                    .center(Length::Fill)
-                                    .into(),
-                                );
-                            }
+                        ItemThumbnail::Image(handle, size_opt) => {
+                            let handle = match item.path_opt() {
+                                // Preloaded images are already decoded
+                                Some(path) => self
+                                    .gallery_images
+                                    .get(path)
+                                    .cloned()
+                                    .flatten()
+                                    .unwrap_or_else(|| widget::image::Handle::from_path(path)),
+                                None => handle.clone(),
+                            };
+                            element_opt =
+                                Some(gallery_image_view(handle, *size_opt, self.gallery_zoom));
                         }
                         ItemThumbnail::Svg(handle) => {
                             element_opt = Some(
@@ -3363,12 +5122,37 @@ impl Tab {
         let mut column = widget::column::with_capacity(2);
         column = column.push(widget::Space::with_height(Length::Fixed(space_m.into())));
         {
-            let mut row = widget::row::with_capacity(5).align_y(Alignment::Center);
+            let mut row = widget::row::with_capacity(9)
+                .align_y(Alignment::Center)
+                .spacing(space_xxs);
             row = row.push(widget::horizontal_space());
             if let Some(name) = name_opt {
                 row = row.push(name);
             }
             row = row.push(widget::horizontal_space());
+            row = row.push(
+                widget::button::icon(widget::icon::from_name("zoom-out-symbolic"))
+                    .class(theme::Button::Standard)
+                    .on_press(Message::GalleryZoomOut),
+            );
+            row = row.push(
+                widget::button::text(format!("{:.0}%", self.gallery_zoom * 100.0))
+                    .on_press(Message::GalleryZoomDefault),
+            );
+            row = row.push(
+                widget::button::icon(widget::icon::from_name("zoom-in-symbolic"))
+                    .class(theme::Button::Standard)
+                    .on_press(Message::GalleryZoomIn),
+            );
+            row = row.push(
+                widget::button::icon(widget::icon::from_name(if self.gallery_slideshow {
+                    "media-playback-pause-symbolic"
+                } else {
+                    "media-playback-start-symbolic"
+                }))
+                .class(theme::Button::Standard)
+                .on_press(Message::GallerySlideshow(!self.gallery_slideshow)),
+            );
             row = row.push(
                 widget::button::icon(widget::icon::from_name("window-close-symbolic"))
                     .class(theme::Button::Standard)
@@ -3494,9 +5278,14 @@ impl Tab {
 
         //TODO: allow resizing?
         let name_width = 300.0;
+        let original_width = if self.location == Location::Trash {
+            300.0
+        } else {
+            0.0
+        };
         let modified_width = 200.0;
         let size_width = 100.0;
-        let condensed = size.width < (name_width + modified_width + size_width);
+        let condensed = size.width < (name_width + original_width + modified_width + size_width);
 
         let (sort_name, sort_direction, _) = self.sort_options();
         let heading_item = |name, width, msg| {
@@ -3520,26 +5309,38 @@ impl Tab {
                 .into()
         };
 
-        let heading_row = widget::row::with_children(vec![
-            heading_item(fl!("name"), Length::Fill, HeadingOptions::Name),
-            if self.location == Location::Trash {
-                heading_item(
-                    fl!("trashed-on"),
-                    Length::Fixed(modified_width),
-                    HeadingOptions::TrashedOn,
-                )
-            } else {
-                heading_item(
-                    fl!("modified"),
-                    Length::Fixed(modified_width),
-                    HeadingOptions::Modified,
-                )
-            },
-            heading_item(fl!("size"), Length::Fixed(size_width), HeadingOptions::Size),
-        ])
-        .align_y(Alignment::Center)
-        .height(Length::Fixed((space_m + 4).into()))
-        .padding([0, space_xxs]);
+        let mut heading_items = vec![heading_item(
+            fl!("name"),
+            Length::Fill,
+            HeadingOptions::Name,
+        )];
+        if self.location == Location::Trash {
+            heading_items.push(
+                widget::text::heading(fl!("original-location"))
+                    .width(Length::Fixed(original_width))
+                    .into(),
+            );
+            heading_items.push(heading_item(
+                fl!("trashed-on"),
+                Length::Fixed(modified_width),
+                HeadingOptions::TrashedOn,
+            ));
+        } else {
+            heading_items.push(heading_item(
+                fl!("modified"),
+                Length::Fixed(modified_width),
+                HeadingOptions::Modified,
+            ));
+        }
+        heading_items.push(heading_item(
+            fl!("size"),
+            Length::Fixed(size_width),
+            HeadingOptions::Size,
+        ));
+        let heading_row = widget::row::with_children(heading_items)
+            .align_y(Alignment::Center)
+            .height(Length::Fixed((space_m + 4).into()))
+            .padding([0, space_xxs]);
 
         let accent_rule =
             horizontal_rule(1).class(theme::Rule::Custom(Box::new(|theme| rule::Style {
@@ -3626,9 +5427,54 @@ impl Tab {
             w += 16.0 + 2.0 * space_xxs as f32;
         }
 
+        // The grid view has no column headings, so its sort is shown and changed here
+        let mut sort_control = None;
+        if self.config.view == View::Grid {
+            let (sort_name, sort_direction, _) = self.sort_options();
+            let next = sort_name.next(self.location == Location::Trash);
+            let next_label = match next {
+                HeadingOptions::Name => fl!("sort-by-name"),
+                HeadingOptions::Modified => fl!("sort-by-modified"),
+                HeadingOptions::Size => fl!("sort-by-size"),
+                HeadingOptions::TrashedOn => fl!("sort-by-trashed"),
+            };
+            let name = sort_name.to_string();
+            w += text_width_heading(&name) + 2.0 * space_xxxs as f32;
+            w += 16.0 + 2.0 * space_xxs as f32;
+            sort_control = Some(
+                widget::row::with_children(vec![
+                    widget::tooltip(
+                        widget::button::custom(widget::text::heading(name))
+                            .padding(space_xxxs)
+                            .class(theme::Button::Text)
+                            .on_press(Message::SetSort(next, next.default_direction())),
+                        widget::text::body(next_label),
+                        widget::tooltip::Position::Bottom,
+                    )
+                    .into(),
+                    widget::button::custom(
+                        widget::icon::from_name(if sort_direction {
+                            "pan-down-symbolic"
+                        } else {
+                            "pan-up-symbolic"
+                        })
+                        .size(16),
+                    )
+                    .padding(space_xxs)
+                    .class(theme::Button::Icon)
+                    .on_press(Message::ToggleSort(sort_name))
+                    .into(),
+                ])
+                .align_y(Alignment::Center),
+            );
+        }
+
         let mut children: Vec<Element<_>> = Vec::new();
         match &self.location {
-            Location::Desktop(path, ..) | Location::Path(path) | Location::Search(path, ..) => {
+            Location::Desktop(path, ..)
+            | Location::Path(path)
+            | Location::Search(path, ..)
+            | Location::Flatten(path, ..) => {
                 let excess_str = "...";
                 let excess_width = text_width_body(excess_str);
                 for (index, ancestor) in path.ancestors().enumerate() {
@@ -3737,6 +5583,10 @@ impl Tab {
         for child in children {
             row = row.push(child);
         }
+        if let Some(sort_control) = sort_control {
+            row = row.push(widget::horizontal_space());
+            row = row.push(sort_control);
+        }
         let mut column = widget::column::with_capacity(4).padding([0, space_s]);
         column = column.push(row);
         column = column.push(accent_rule);
@@ -3766,8 +5616,18 @@ impl Tab {
         let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
 
         mouse_area::MouseArea::new(widget::column::with_children(vec![widget::container(
-            widget::column::with_children(match self.mode {
-                Mode::App | Mode::Dialog(_) => vec![
+            widget::column::with_children(match (&self.mode, &self.scan_error_opt) {
+                (Mode::App | Mode::Dialog(_), Some(scan_error)) => vec![
+                    widget::icon::from_name("dialog-error-symbolic")
+                        .size(64)
+                        .icon()
+                        .into(),
+                    widget::text::body(scan_error.clone()).into(),
+                    widget::button::standard(fl!("retry"))
+                        .on_press(Message::Rescan)
+                        .into(),
+                ],
+                (Mode::App | Mode::Dialog(_), None) => vec![
                     widget::icon::from_name("folder-symbolic")
                         .size(64)
                         .icon()
@@ -3781,7 +5641,7 @@ impl Tab {
                     })
                     .into(),
                 ],
-                Mode::Desktop => Vec::new(),
+                (Mode::Desktop, _) => Vec::new(),
             })
             .align_x(Alignment::Center)
             .spacing(space_xxs),
@@ -3807,9 +5667,13 @@ impl Tab {
         } = self.config;
 
         let mut grid_spacing = space_xxs;
+        let mut grid_origin = DesktopGridOrigin::default();
+        let mut grid_flow = DesktopGridFlow::default();
         if let Location::Desktop(_path, _output, desktop_config) = &self.location {
             icon_sizes.grid = desktop_config.icon_size;
             grid_spacing = desktop_config.grid_spacing_for(space_xxs);
+            grid_origin = desktop_config.grid_origin;
+            grid_flow = desktop_config.grid_flow;
         };
 
         let text_height = 3 * 20; // 3 lines of text
@@ -3855,6 +5719,7 @@ impl Tab {
         let mut drag_s_i = 0;
 
         let mut children = Vec::new();
+        let item_progress = self.item_progress();
 
         if let Some(items) = self.column_sort() {
             let mut count = 0;
@@ -3862,7 +5727,7 @@ impl Tab {
             let mut row = 0;
             let mut page_row = 0;
             let mut hidden = 0;
-            let mut grid_elements = Vec::new();
+            let mut grid_elements = BTreeMap::new();
             for &(i, item) in items.iter() {
                 if !show_hidden && item.hidden {
                     item.pos_opt.set(None);
@@ -3870,22 +5735,28 @@ impl Tab {
                     hidden += 1;
                     continue;
                 }
-                item.pos_opt.set(Some((row, col)));
+                // Desktop icons start from the configured corner
+                let (pos_row, pos_col) = grid_origin.position((row, col), rows, cols);
+                item.pos_opt.set(Some((pos_row, pos_col)));
                 item.rect_opt.set(Some(Rectangle::new(
                     Point::new(
-                        (col * (item_width + column_spacing as usize) + space_m as usize) as f32,
-                        (row * (item_height + grid_spacing as usize)) as f32,
+                        (pos_col * (item_width + column_spacing as usize) + space_m as usize)
+                            as f32,
+                        (pos_row * (item_height + grid_spacing as usize)) as f32,
                     ),
                     Size::new(item_width as f32, item_height as f32),
                 )));
 
+                let progress_opt = item
+                    .path_opt()
+                    .and_then(|path| item_progress.get(path).copied());
                 //TODO: one focus group per grid item (needs custom widget)
                 let buttons: Vec<Element<Message>> = vec![
-                    widget::button::custom(
-                        widget::icon::icon(item.icon_handle_grid.clone())
-                            .content_fit(ContentFit::Contain)
-                            .size(icon_sizes.grid()),
-                    )
+                    widget::button::custom(item.icon_view(
+                        item.icon_handle_grid.clone(),
+                        icon_sizes.grid(),
+                        progress_opt,
+                    ))
                     .padding(space_xxxs)
                     .class(button_style(
                         item.selected,
@@ -3895,21 +5766,24 @@ impl Tab {
                         false,
                     ))
                     .into(),
-                    widget::tooltip(
-                        widget::button::custom(widget::text::body(&item.display_name))
-                            .id(item.button_id.clone())
-                            .padding([0, space_xxxs])
-                            .class(button_style(
-                                item.selected,
-                                item.highlighted,
-                                true,
-                                true,
-                                matches!(self.mode, Mode::Desktop),
-                            )),
-                        widget::text::body(&item.name),
-                        widget::tooltip::Position::Bottom,
-                    )
-                    .into(),
+                    match self.rename_input(item) {
+                        Some(rename_input) => rename_input,
+                        None => widget::tooltip(
+                            widget::button::custom(widget::text::body(&item.display_name))
+                                .id(item.button_id.clone())
+                                .padding([0, space_xxxs])
+                                .class(button_style(
+                                    item.selected,
+                                    item.highlighted,
+                                    true,
+                                    true,
+                                    matches!(self.mode, Mode::Desktop),
+                                )),
+                            widget::text::body(&item.name),
+                            widget::tooltip::Position::Bottom,
+                        )
+                        .into(),
+                    },
                 ];
 
                 let mut column = widget::column::with_capacity(buttons.len())
@@ -3936,11 +5810,11 @@ impl Tab {
                     };
 
                 if item.selected {
-                    dnd_items.push((i, (row, col), item));
-                    drag_w_i = drag_w_i.min(col);
-                    drag_n_i = drag_n_i.min(row);
-                    drag_e_i = drag_e_i.max(col);
-                    drag_s_i = drag_s_i.max(row);
+                    dnd_items.push((i, (pos_row, pos_col), item));
+                    drag_w_i = drag_w_i.min(pos_col);
+                    drag_n_i = drag_n_i.min(pos_row);
+                    drag_e_i = drag_e_i.max(pos_col);
+                    drag_s_i = drag_s_i.max(pos_row);
                 }
                 let mouse_area = crate::mouse_area::MouseArea::new(column)
                     .on_press(move |_| Message::Click(Some(i)))
@@ -3951,16 +5825,13 @@ impl Tab {
                     .on_exit(move || Message::HighlightDeactivate(i));
 
                 //TODO: error if the row or col is already set?
-                while grid_elements.len() <= row {
-                    grid_elements.push(Vec::new());
-                }
-                grid_elements[row].push(mouse_area);
+                grid_elements.insert((pos_row, pos_col), mouse_area);
 
                 count += 1;
-                if matches!(self.mode, Mode::Desktop) {
+                if matches!(self.mode, Mode::Desktop) && grid_flow == DesktopGridFlow::Columns {
                     row += 1;
                     if row >= page_row + rows {
-                        row = 0;
+                        row = page_row;
                         col += 1;
                     }
                     if col >= cols {
@@ -3977,9 +5848,29 @@ impl Tab {
                 }
             }
 
-            for row_elements in grid_elements {
-                for element in row_elements {
+            // Cells without items are left empty, for icons starting from the right or bottom of
+            // the desktop
+            let empty_cell = || {
+                Space::new(
+                    Length::Fixed(item_width as f32),
+                    Length::Fixed(item_height as f32),
+                )
+            };
+            let last_row = grid_elements.keys().last().map_or(0, |(row, _)| *row);
+            let mut elements = grid_elements.into_iter().peekable();
+            for row in 0..=last_row {
+                let mut col = 0;
+                while let Some(((_, item_col), element)) =
+                    elements.next_if(|((item_row, _), _)| *item_row == row)
+                {
+                    for _ in col..item_col {
+                        grid = grid.push(empty_cell());
+                    }
                     grid = grid.push(element);
+                    col = item_col + 1;
+                }
+                if col == 0 {
+                    grid = grid.push(empty_cell());
                 }
                 grid = grid.insert_row();
             }
@@ -4020,6 +5911,8 @@ impl Tab {
             }
         }
 
+        // The dragged items are laid out row by row
+        dnd_items.sort_by_key(|(_, pos, _)| *pos);
         (
             (!dnd_items.is_empty()).then(|| {
                 let mut dnd_grid = widget::grid()
@@ -4050,9 +5943,10 @@ impl Tab {
                                     false,
                                     false,
                                 )),
-                                widget::button::custom(widget::text::body(
-                                    item.display_name.clone(),
-                                ))
+                                widget::button::custom(
+                                    widget::text::body(item.display_name.clone())
+                                        .class(item.name_class(self.config.color_by_type)),
+                                )
                                 .id(item.button_id.clone())
                                 .on_press(Message::Click(Some(*i)))
                                 .padding([0, space_xxxs])
@@ -4096,6 +5990,40 @@ impl Tab {
         )
     }
 
+    /// Parent folder shown as a ".." row above the items, not at roots or outside of folders
+    pub fn parent_entry(&self) -> Option<PathBuf> {
+        match &self.location {
+            Location::Path(path) if self.config.show_parent_entry => {
+                path.parent().map(Path::to_path_buf)
+            }
+            _ => None,
+        }
+    }
+
+    // Row that goes to the parent folder when activated, and is never selected
+    fn parent_entry_row(&self, parent: PathBuf, icon_size: u16) -> Element<Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        let row = widget::row::with_children(vec![
+            widget::icon::from_name("go-up-symbolic")
+                .size(icon_size)
+                .icon()
+                .into(),
+            widget::text::body("..").width(Length::Fill).into(),
+        ])
+        .height(Length::Fixed((icon_size + 2 * space_xxs) as f32))
+        .align_y(Alignment::Center)
+        .spacing(space_xxs);
+        let button = crate::mouse_area::MouseArea::new(
+            widget::button::custom(row)
+                .width(Length::Fill)
+                .padding([0, space_xxs])
+                .class(button_style(false, false, true, true, false)),
+        )
+        .on_press(|_| Message::Click(None))
+        .on_double_click(|_| Message::LocationUp);
+        self.dnd_dest(&Location::Path(parent), button)
+    }
+
     pub fn list_view(&self) -> (Option<Element<'static, Message>>, Element<Message>, bool) {
         let cosmic_theme::Spacing {
             space_m,
@@ -4113,10 +6041,12 @@ impl Tab {
         let size = self.size_opt.get().unwrap_or_else(|| Size::new(0.0, 0.0));
         //TODO: allow resizing?
         let name_width = 300.0;
+        let is_trash = self.location == Location::Trash;
+        let original_width = if is_trash { 300.0 } else { 0.0 };
         let modified_width = 200.0;
         let size_width = 100.0;
-        let condensed = size.width < (name_width + modified_width + size_width);
-        let is_search = matches!(self.location, Location::Search(..));
+        let condensed = size.width < (name_width + original_width + modified_width + size_width);
+        let is_search = matches!(self.location, Location::Search(..) | Location::Flatten(..));
         let icon_size = if condensed || is_search {
             icon_sizes.list_condensed()
         } else {
@@ -4130,7 +6060,9 @@ impl Tab {
         let rule_padding = theme::active().cosmic().corner_radii.radius_xs[0] as u16;
 
         let items = self.column_sort();
+        let item_progress = self.item_progress();
         let mut drag_items = Vec::new();
+        let parent_entry_opt = self.parent_entry();
         if let Some(items) = items {
             let mut count = 0;
             let mut hidden = 0;
@@ -4141,13 +6073,21 @@ impl Tab {
                     hidden += 1;
                     continue;
                 }
+
+                if count == 0 {
+                    if let Some(parent) = parent_entry_opt.clone() {
+                        children.push(self.parent_entry_row(parent, icon_size));
+                        y += row_height as u32;
+                    }
+                }
+
                 item.pos_opt.set(Some((count, 0)));
                 item.rect_opt.set(Some(Rectangle::new(
                     Point::new(space_m as f32, y as f32),
                     Size::new(size.width - (2 * space_m) as f32, row_height as f32),
                 )));
 
-                if count > 0 {
+                if count > 0 || parent_entry_opt.is_some() {
                     children.push(
                         widget::container(horizontal_rule(1))
                             .padding([0, rule_padding])
@@ -4169,7 +6109,11 @@ impl Tab {
 
                 let size_text = match &item.metadata {
                     ItemMetadata::Path { metadata, children } => {
-                        if metadata.is_dir() {
+                        if let DirSize::Directory(size) = item.dir_size {
+                            format_size(size)
+                        } else if let DirSize::Partial(size, _) = item.dir_size {
+                            format!("{}...", format_size(size))
+                        } else if metadata.is_dir() {
                             //TODO: translate
                             if *children == 1 {
                                 format!("{} item", children)
@@ -4202,14 +6146,22 @@ impl Tab {
                     ItemMetadata::SimpleFile { size } => format_size(*size),
                 };
 
+                let progress_opt = item
+                    .path_opt()
+                    .and_then(|path| item_progress.get(path).copied());
                 let row = if condensed {
                     widget::row::with_children(vec![
-                        widget::icon::icon(item.icon_handle_list_condensed.clone())
-                            .content_fit(ContentFit::Contain)
-                            .size(icon_size)
-                            .into(),
+                        item.icon_view(
+                            item.icon_handle_list_condensed.clone(),
+                            icon_size,
+                            progress_opt,
+                        ),
                         widget::column::with_children(vec![
-                            widget::text::body(item.display_name.clone()).into(),
+                            self.rename_input(item).unwrap_or_else(|| {
+                                widget::text::body(item.display_name.clone())
+                                    .class(item.name_class(self.config.color_by_type))
+                                    .into()
+                            }),
                             //TODO: translate?
                             widget::text::caption(format!("{} - {}", modified_text, size_text))
                                 .into(),
@@ -4221,17 +6173,18 @@ impl Tab {
                     .spacing(space_xxs)
                 } else if is_search {
                     widget::row::with_children(vec![
-                        widget::icon::icon(item.icon_handle_list_condensed.clone())
-                            .content_fit(ContentFit::Contain)
-                            .size(icon_size)
-                            .into(),
+                        item.icon_view(
+                            item.icon_handle_list_condensed.clone(),
+                            icon_size,
+                            progress_opt,
+                        ),
                         widget::column::with_children(vec![
-                            widget::text::body(item.display_name.clone()).into(),
-                            widget::text::caption(match item.path_opt() {
-                                Some(path) => path.display().to_string(),
-                                None => String::new(),
-                            })
-                            .into(),
+                            self.rename_input(item).unwrap_or_else(|| {
+                                widget::text::body(item.display_name.clone())
+                                    .class(item.name_class(self.config.color_by_type))
+                                    .into()
+                            }),
+                            widget::text::caption(self.result_caption(item)).into(),
                         ])
                         .width(Length::Fill)
                         .into(),
@@ -4246,24 +6199,42 @@ impl Tab {
                     .align_y(Alignment::Center)
                     .spacing(space_xxs)
                 } else {
-                    widget::row::with_children(vec![
-                        widget::icon::icon(item.icon_handle_list.clone())
-                            .content_fit(ContentFit::Contain)
-                            .size(icon_size)
-                            .into(),
-                        widget::text::body(item.display_name.clone())
-                            .width(Length::Fill)
-                            .into(),
+                    let mut columns: Vec<Element<_>> = vec![
+                        item.icon_view(item.icon_handle_list.clone(), icon_size, progress_opt),
+                        self.rename_input(item).unwrap_or_else(|| {
+                            widget::text::body(item.display_name.clone())
+                                .class(item.name_class(self.config.color_by_type))
+                                .width(Length::Fill)
+                                .into()
+                        }),
+                    ];
+                    if is_trash {
+                        let original_text = match &item.metadata {
+                            ItemMetadata::Trash { entry, .. } => {
+                                entry.original_parent.display().to_string()
+                            }
+                            _ => String::new(),
+                        };
+                        columns.push(
+                            widget::text::body(original_text)
+                                .width(Length::Fixed(original_width))
+                                .into(),
+                        );
+                    }
+                    columns.push(
                         widget::text::body(modified_text.clone())
                             .width(Length::Fixed(modified_width))
                             .into(),
+                    );
+                    columns.push(
                         widget::text::body(size_text.clone())
                             .width(Length::Fixed(size_width))
                             .into(),
-                    ])
-                    .height(Length::Fixed(row_height as f32))
-                    .align_y(Alignment::Center)
-                    .spacing(space_xxs)
+                    );
+                    widget::row::with_children(columns)
+                        .height(Length::Fixed(row_height as f32))
+                        .align_y(Alignment::Center)
+                        .spacing(space_xxs)
                 };
 
                 let button = |row| {
@@ -4332,11 +6303,7 @@ impl Tab {
                                 .into(),
                             widget::column::with_children(vec![
                                 widget::text::body(item.display_name.clone()).into(),
-                                widget::text::caption(match item.path_opt() {
-                                    Some(path) => path.display().to_string(),
-                                    None => String::new(),
-                                })
-                                .into(),
+                                widget::text::caption(self.result_caption(item)).into(),
                             ])
                             .width(Length::Fill)
                             .into(),
@@ -4429,6 +6396,7 @@ impl Tab {
     pub fn view_responsive(
         &self,
         key_binds: &HashMap<KeyBind, Action>,
+        custom_actions: &[ParsedCustomAction],
         size: Size,
     ) -> Element<Message> {
         // Update cached size
@@ -4468,9 +6436,9 @@ impl Tab {
             Some(drag_list) if self.selected_clicked => {
                 let drag_list = ArcElementWrapper::<Message>(Arc::new(Mutex::new(drag_list)));
                 item_view
-                    .drag_content(move || {
-                        ClipboardCopy::new(crate::clipboard::ClipboardKind::Copy, &files)
-                    })
+                    // Copy unless the drop target or the modifiers held choose to move
+                    .action(DndAction::Copy | DndAction::Move)
+                    .drag_content(move || ClipboardCopy::drag(&files))
                     .drag_icon(move |v| {
                         let state: tree::State = Widget::<Message, _, _>::state(&drag_list);
                         (Element::from(drag_list.clone()).map(|_m| ()), state, v)
@@ -4499,7 +6467,7 @@ impl Tab {
 
         if let Some(point) = self.context_menu {
             popover = popover
-                .popup(menu::context_menu2(self, key_binds))
+                .popup(menu::context_menu2(self, key_binds, custom_actions))
                 .position(widget::popover::Position::Point(point));
         }
         let mut tab_column = widget::column::with_capacity(3);
@@ -4548,6 +6516,29 @@ impl Tab {
             }
             _ => {}
         }
+        // Files dragged over a folder show whether dropping copies or moves them
+        if let Some(((location, _), action)) = self.dnd_hovered.as_ref().zip(self.dnd_action) {
+            if let Some(name) = location.path_opt().and_then(|path| path.file_name()) {
+                let name = name.to_string_lossy().into_owned();
+                let (icon, label) = if action == DndAction::Move {
+                    ("go-jump-symbolic", fl!("drop-move", name = name))
+                } else {
+                    ("edit-copy-symbolic", fl!("drop-copy", name = name))
+                };
+                tab_column = tab_column.push(
+                    widget::layer_container(
+                        widget::row::with_children(vec![
+                            widget::icon::from_name(icon).size(16).icon().into(),
+                            widget::text::body(label).into(),
+                        ])
+                        .align_y(Alignment::Center)
+                        .spacing(space_xxs),
+                    )
+                    .padding([space_xxs, space_xs])
+                    .layer(cosmic_theme::Layer::Primary),
+                );
+            }
+        }
         let mut tab_view = widget::container(tab_column)
             .height(Length::Fill)
             .width(Length::Fill);
@@ -4583,13 +6574,18 @@ impl Tab {
             }
         })
         .on_enter(move |_, _, _| Message::DndEnter(tab_location_2.clone()))
-        .on_leave(move || Message::DndLeave(tab_location_3.clone()));
+        .on_leave(move || Message::DndLeave(tab_location_3.clone()))
+        .on_action_selected(Message::DndAction);
 
         dnd_dest.into()
     }
 
-    pub fn view<'a>(&'a self, key_binds: &'a HashMap<KeyBind, Action>) -> Element<'a, Message> {
-        widget::responsive(|size| self.view_responsive(key_binds, size)).into()
+    pub fn view<'a>(
+        &'a self,
+        key_binds: &'a HashMap<KeyBind, Action>,
+        custom_actions: &'a [ParsedCustomAction],
+    ) -> Element<'a, Message> {
+        widget::responsive(|size| self.view_responsive(key_binds, custom_actions, size)).into()
     }
 
     pub fn subscription(&self, preview: bool) -> Subscription<Message> {
@@ -4678,66 +6674,23 @@ impl Tab {
             }
 
             if preview {
-                // Load directory size for selected items
-                if let Some(item) = items
-                    .iter()
-                    .find(|item| item.selected)
-                    .or(self.parent_item_opt.as_ref())
-                {
+                // Load directory sizes for selected items
+                let mut preview_items: Vec<&Item> =
+                    items.iter().filter(|item| item.selected).collect();
+                if preview_items.is_empty() {
+                    preview_items.extend(self.parent_item_opt.iter());
+                }
+                for item in preview_items {
                     // Item must have a path
                     if let Some(path) = item.path_opt().map(|path| path.to_path_buf()) {
                         // Item must be calculating directory size
-                        if let DirSize::Calculating(controller) = &item.dir_size {
-                            let controller = controller.clone();
-                            subscriptions.push(Subscription::run_with_id(
-                                ("dir_size", path.clone()),
-                                stream::channel(1, |mut output| async move {
-                                    let message = {
-                                        let path = path.clone();
-                                        tokio::task::spawn_blocking(move || {
-                                            let start = Instant::now();
-                                            match calculate_dir_size(&path, controller) {
-                                                Ok(size) => {
-                                                    log::debug!(
-                                                        "calculated directory size of {:?} in {:?}",
-                                                        path,
-                                                        start.elapsed()
-                                                    );
-                                                    Message::DirectorySize(
-                                                        path.clone(),
-                                                        DirSize::Directory(size),
-                                                    )
-                                                }
-                                                Err(err) => {
-                                                    log::warn!(
-                                                "failed to calculate directory size of {:?}: {}",
-                                                path,
-                                                err
-                                            );
-                                                    Message::DirectorySize(
-                                                        path.clone(),
-                                                        DirSize::Error(err),
-                                                    )
-                                                }
-                                            }
-                                        })
-                                        .await
-                                        .unwrap()
-                                    };
-
-                                    match output.send(message).await {
-                                        Ok(()) => {}
-                                        Err(err) => {
-                                            log::warn!(
-                                                "failed to send directory size for {:?}: {}",
-                                                &path,
-                                                err
-                                            );
-                                        }
-                                    }
-
-                                    std::future::pending().await
-                                }),
+                        if let DirSize::Calculating(controller) | DirSize::Partial(_, controller) =
+                            &item.dir_size
+                        {
+                            subscriptions.push(dir_size_subscription(
+                                path,
+                                self.config.respect_gitignore,
+                                controller.clone(),
                             ));
                         }
                     }
@@ -4745,13 +6698,83 @@ impl Tab {
             }
         }
 
+        // Calculate checksums requested in the preview
+        for item in self
+            .items_opt
+            .iter()
+            .flatten()
+            .chain(self.parent_item_opt.iter())
+        {
+            if let (
+                Some(path),
+                Some(ItemChecksum {
+                    kind,
+                    state: ChecksumState::Calculating(_, controller),
+                    ..
+                }),
+            ) = (item.path_opt(), &item.checksum_opt)
+            {
+                subscriptions.push(checksum_subscription(
+                    path.clone(),
+                    *kind,
+                    controller.clone(),
+                ));
+            }
+        }
+
+        if self.gallery {
+            // Decode the shown image and its neighbours ahead of time for smooth stepping
+            for path in self.gallery_image_paths() {
+                if !self.gallery_images.contains_key(&path) {
+                    subscriptions.push(gallery_image_subscription(path));
+                }
+            }
+            if self.gallery_slideshow {
+                subscriptions.push(
+                    time::every(Duration::from_secs(self.config.slideshow_interval.into()))
+                        .map(|_| Message::GalleryNext),
+                );
+            }
+        }
+
+        // Load directory sizes requested by CalculateFolderSizes
+        if !self.folder_size_paths.is_empty() {
+            if let Some(items) = &self.items_opt {
+                for item in items.iter() {
+                    let Some(path) = item.path_opt() else {
+                        continue;
+                    };
+                    if !self.folder_size_paths.contains(path) {
+                        continue;
+                    }
+                    if let DirSize::Calculating(controller) | DirSize::Partial(_, controller) =
+                        &item.dir_size
+                    {
+                        subscriptions.push(dir_size_subscription(
+                            path.to_path_buf(),
+                            self.config.respect_gitignore,
+                            controller.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+
         // Load search items incrementally
-        if let Location::Search(path, term, show_hidden, start) = &self.location {
+        let scan_opt = match &self.location {
+            Location::Search(path, term, show_hidden, start) => {
+                Some((path.clone(), Some(term.clone()), *show_hidden, *start))
+            }
+            // The flattened view is a search that matches every file
+            Location::Flatten(path, show_hidden, start) => {
+                Some((path.clone(), None, *show_hidden, *start))
+            }
+            _ => None,
+        };
+        if let Some((path, term_opt, show_hidden, start)) = scan_opt {
             let location = self.location.clone();
-            let path = path.clone();
-            let term = term.clone();
-            let show_hidden = *show_hidden;
-            let start = *start;
+            let follow_symlinks = self.config.follow_symlinks;
+            let respect_gitignore = self.config.respect_gitignore;
             subscriptions.push(Subscription::run_with_id(
                 location.clone(),
                 stream::channel(2, move |mut output| async move {
@@ -4776,11 +6799,8 @@ impl Tab {
                     {
                         let output = output.clone();
                         tokio::task::spawn_blocking(move || {
-                            scan_search(
-                                &path,
-                                &term,
-                                show_hidden,
-                                move |path, name, metadata| -> bool {
+                            let callback =
+                                move |path: &Path, name: &str, metadata: Metadata| -> bool {
                                     // Don't send if the result is too old
                                     if let Some(last_modified) = *last_modified_opt.read().unwrap()
                                     {
@@ -4815,11 +6835,27 @@ impl Tab {
                                         }
                                         Err(_) => false,
                                     }
-                                },
-                            );
+                                };
+                            match &term_opt {
+                                Some(term) => scan_search(
+                                    &path,
+                                    term,
+                                    show_hidden,
+                                    follow_symlinks,
+                                    respect_gitignore,
+                                    callback,
+                                ),
+                                None => scan_flatten(
+                                    &path,
+                                    show_hidden,
+                                    follow_symlinks,
+                                    respect_gitignore,
+                                    callback,
+                                ),
+                            }
                             log::info!(
                                 "searched for {:?} in {:?} in {:?}",
-                                term,
+                                term_opt,
                                 path,
                                 start.elapsed(),
                             );
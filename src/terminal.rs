@@ -12,7 +12,7 @@ use alacritty_terminal::{
         viewport_to_point, Config, TermDamage, TermMode,
     },
     tty::{self, Options},
-    vte::ansi::{Color, CursorShape, NamedColor, Rgb},
+    vte::ansi::{Color, CursorShape, Handler, NamedColor, Rgb},
     Term,
 };
 use cosmic::{
@@ -297,6 +297,21 @@ impl Terminal {
         &self.colors
     }
 
+    /// Change the color scheme, the terminal is redrawn with it
+    pub fn set_colors(&mut self, colors: Colors) {
+        let default_bg = convert_color(&colors, Color::Named(NamedColor::Background));
+        let default_fg = convert_color(&colors, Color::Named(NamedColor::Foreground));
+        let (default_metadata_idx, _) = self
+            .metadata_set
+            .insert_full(Metadata::new(default_bg, default_fg));
+        let default_attrs = mem::replace(&mut self.default_attrs, Attrs::new());
+        self.default_attrs = default_attrs
+            .color(default_fg)
+            .metadata(default_metadata_idx);
+        self.colors = colors;
+        self.needs_update = true;
+    }
+
     pub fn default_attrs(&self) -> &Attrs<'static> {
         &self.default_attrs
     }
@@ -330,6 +345,20 @@ impl Terminal {
         self.scroll(TerminalScroll::Bottom);
     }
 
+    /// Show a line of text below the output, without sending it to the process
+    pub fn notice(&mut self, text: &str) {
+        {
+            let mut term = self.term.lock();
+            term.carriage_return();
+            term.linefeed();
+            for c in text.chars() {
+                term.input(c);
+            }
+        }
+        self.scroll(TerminalScroll::Bottom);
+        self.needs_update = true;
+    }
+
     pub fn paste(&self, value: String) {
         // This code is ported from alacritty
         let bracketed_paste = {
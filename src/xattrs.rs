@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{fmt::Write, fs, os::unix::fs::MetadataExt, path::Path};
+
+use crate::{err_str, item_cache};
+
+// Attribute holding the SELinux security context
+const SELINUX_ATTR: &str = "security.selinux";
+
+/// Extended attributes of an item
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ItemXattrs {
+    /// Names and printable values, without the SELinux context
+    pub attrs: Vec<(String, String)>,
+    pub selinux_opt: Option<String>,
+}
+
+/// Printable form of an attribute value, values that are not text are shown as hex
+pub fn format_value(value: &[u8]) -> String {
+    // Text values are often nul terminated
+    let text = value.strip_suffix(&[0]).unwrap_or(value);
+    let printable = |c: char| !c.is_control() || c == '\n' || c == '\t';
+    match std::str::from_utf8(text) {
+        Ok(text) if text.chars().all(printable) => text.to_string(),
+        _ => value.iter().fold(String::from("0x"), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        }),
+    }
+}
+
+fn read_uncached(path: &Path) -> Result<ItemXattrs, String> {
+    let mut xattrs = ItemXattrs::default();
+    let names = match xattr::list_deref(path) {
+        Ok(ok) => ok,
+        // File systems without extended attributes simply have none
+        Err(err) if err.raw_os_error() == Some(libc::ENOTSUP) => return Ok(xattrs),
+        Err(err) => return Err(err_str(err)),
+    };
+    for name in names {
+        let Some(value) = xattr::get_deref(path, &name).map_err(err_str)? else {
+            // Removed while listing
+            continue;
+        };
+        let name = name.to_string_lossy().into_owned();
+        let value = format_value(&value);
+        if name == SELINUX_ATTR {
+            xattrs.selinux_opt = Some(value);
+        } else {
+            xattrs.attrs.push((name, value));
+        }
+    }
+    xattrs.attrs.sort();
+    Ok(xattrs)
+}
+
+/// Read the extended attributes of a path, following symlinks
+pub fn read(path: &Path) -> Result<ItemXattrs, String> {
+    let metadata = fs::metadata(path).map_err(err_str)?;
+    let changed = (metadata.ctime(), metadata.ctime_nsec());
+    if let Some(xattrs) = item_cache::xattrs(path, changed) {
+        return Ok(xattrs);
+    }
+    let xattrs = read_uncached(path)?;
+    item_cache::insert_xattrs(path, changed, xattrs.clone());
+    Ok(xattrs)
+}